@@ -1,3 +1,6 @@
 fn main() {
-    slint_build::compile("ui/main.slint").expect("Slint compilation failed");
+    // The UI flow tests find elements by label, which needs Slint's debug info
+    let config = slint_build::CompilerConfiguration::new()
+        .with_debug_info(std::env::var_os("CARGO_FEATURE_UI_TESTING").is_some());
+    slint_build::compile_with_config("ui/main.slint", config).expect("Slint compilation failed");
 }
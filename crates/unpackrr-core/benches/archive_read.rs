@@ -0,0 +1,112 @@
+//! Memory-mapped vs buffered reading of a large archive
+//!
+//! Writes a general archive of `UNPACKRR_BENCH_GB` gigabytes (default 4) made
+//! of 1 MiB files, or uses the archive at `UNPACKRR_BENCH_ARCHIVE`, then opens
+//! it and reads every file with each [`ReadMode`]:
+//!
+//! ```bash
+//! cargo bench -p unpackrr-core --bench archive_read
+//! UNPACKRR_BENCH_ARCHIVE="D:\Mods\Big - Main.ba2" cargo bench -p unpackrr-core --bench archive_read
+//! ```
+//!
+//! The first pass warms the page cache so both modes read cached data.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use unpackrr_core::ba2::{BA2Archive, ReadMode};
+
+/// Size of every generated file
+const FILE_SIZE: usize = 1024 * 1024;
+
+/// Size of a general file record
+const RECORD_SIZE: u64 = 36;
+
+#[allow(clippy::cast_precision_loss)] // Rates are approximate
+fn main() {
+    // Stays empty when an archive is given
+    let generated = tempfile::tempdir().expect("create temporary folder");
+    let path = std::env::var_os("UNPACKRR_BENCH_ARCHIVE").map_or_else(
+        || {
+            let gigabytes = std::env::var("UNPACKRR_BENCH_GB")
+                .ok()
+                .and_then(|gb| gb.parse().ok())
+                .unwrap_or(4);
+            let path = generated.path().join("Bench - Main.ba2");
+            println!("Writing a {gigabytes} GB archive to {}", path.display());
+            write_archive(&path, gigabytes * 1024).expect("write archive");
+            path
+        },
+        PathBuf::from,
+    );
+
+    for mode in [
+        ReadMode::Buffered,
+        ReadMode::Mapped,
+        ReadMode::Buffered,
+        ReadMode::Mapped,
+    ] {
+        let (open, read, bytes) = read_all(&path, mode);
+        println!(
+            "{mode:?}: opened in {open:?}, read {:.1} GB in {read:?} ({:.0} MB/s)",
+            bytes as f64 / 1e9,
+            bytes as f64 / 1e6 / read.as_secs_f64().max(f64::EPSILON)
+        );
+    }
+}
+
+/// Open the archive and read every file, returning the timings and bytes read
+fn read_all(path: &Path, mode: ReadMode) -> (Duration, Duration, u64) {
+    let start = Instant::now();
+    let archive = BA2Archive::open_with(path, mode).expect("open archive");
+    let open = start.elapsed();
+
+    let start = Instant::now();
+    let mut bytes = 0;
+    for entry in archive.entries() {
+        bytes += archive.read(entry).expect("read file").len() as u64;
+    }
+    for texture in archive.textures() {
+        bytes += archive.read_texture(texture).expect("read texture").len() as u64;
+    }
+    (open, start.elapsed(), bytes)
+}
+
+/// Stream an uncompressed general archive of `files` files of [`FILE_SIZE`]
+fn write_archive(path: &Path, files: usize) -> std::io::Result<()> {
+    let mut out = BufWriter::with_capacity(FILE_SIZE, File::create(path)?);
+    let count = u32::try_from(files).expect("file count fits in u32");
+    let data_start = 24 + u64::from(count) * RECORD_SIZE;
+    let size = FILE_SIZE as u64;
+
+    out.write_all(b"BTDX")?;
+    out.write_all(&1u32.to_le_bytes())?;
+    out.write_all(b"GNRL")?;
+    out.write_all(&count.to_le_bytes())?;
+    out.write_all(&(data_start + u64::from(count) * size).to_le_bytes())?;
+    for index in 0..u64::from(count) {
+        out.write_all(&index.to_le_bytes()[..4])?; // Name hash
+        out.write_all(b"bin\0")?;
+        out.write_all(&0u32.to_le_bytes())?; // Directory hash
+        out.write_all(&0u32.to_le_bytes())?; // Flags
+        out.write_all(&(data_start + index * size).to_le_bytes())?;
+        out.write_all(&0u32.to_le_bytes())?; // Stored uncompressed
+        out.write_all(
+            &u32::try_from(FILE_SIZE)
+                .expect("file size fits in u32")
+                .to_le_bytes(),
+        )?;
+        out.write_all(&0xBAAD_F00Du32.to_le_bytes())?;
+    }
+    let block: Vec<u8> = (0..=250u8).cycle().take(FILE_SIZE).collect();
+    for _ in 0..files {
+        out.write_all(&block)?;
+    }
+    for index in 0..files {
+        let name = format!("bench\\file{index:06}.bin");
+        out.write_all(&u16::try_from(name.len()).expect("name fits").to_le_bytes())?;
+        out.write_all(name.as_bytes())?;
+    }
+    out.flush()
+}
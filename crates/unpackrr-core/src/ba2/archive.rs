@@ -0,0 +1,1327 @@
+//! Native reading of BA2 archives
+//!
+//! [`BA2Archive::open`] reads the header, the file records and the name
+//! table of an archive, so its contents can be listed and sized without
+//! `BSArch`. General (GNRL) archives store one 36-byte record per file:
+//!
+//! - Name hash: CRC-32 of the lowercase file name without extension (u32)
+//! - Extension: up to 4 bytes, null padded
+//! - Directory hash: CRC-32 of the lowercase folder path (u32)
+//! - Flags (u32)
+//! - Data offset from the start of the archive (u64)
+//! - Packed size: size of the compressed data, 0 if stored uncompressed (u32)
+//! - Unpacked size (u32)
+//! - Sentinel: `0xBAADF00D` (u32)
+//!
+//! Texture (DX10) archives store one 24-byte record per texture, followed by
+//! a 24-byte record per chunk of mip levels:
+//!
+//! - Name hash, extension and directory hash as above
+//! - Unknown (u8), chunk count (u8), chunk record size (u16, always 24)
+//! - Height and width (u16 each)
+//! - Mip count, DXGI format, cubemap flag and tile mode (u8 each)
+//! - Per chunk: data offset (u64), packed and unpacked size (u32 each),
+//!   first and last mip level (u16 each) and the sentinel
+//!
+//! The DDS header of a texture is not stored and is rebuilt from the record
+//! (see [`dds`](super::dds)).
+//!
+//! Starfield archives extend the 24-byte header: version 2 by 8 bytes and
+//! version 3 by 12 bytes. The records follow the extended header.
+//!
+//! Compressed data is usually a zlib stream. Version 3 archives name their
+//! codec in the last field of the header extension (0 for zlib, 3 for raw LZ4
+//! blocks), and repacking tools write zstd and LZ4 frames into archives that
+//! claim zlib, so the codec of every file is detected from the first bytes
+//! of its data ([`Codec`]). Data in any other format is reported as
+//! [`BA2Error::UnsupportedCompression`] instead of being unpacked into
+//! garbage.
+//!
+//! Archives on local drives are memory mapped ([`ReadMode`]), so the tables
+//! and the data of multi-gigabyte archives are read straight from the page
+//! cache instead of being copied through read buffers. Archives on network
+//! and removable drives are read with buffered IO: a mapping of a file that
+//! disappears with its drive faults on access instead of returning an error.
+
+use super::texture_ops::{MipSkip, SkipWriter};
+use super::{BA2Header, dds, read_names_from_reader};
+use crate::error::{BA2Error, Result};
+use crate::platform::drive::drive_kind;
+use flate2::bufread::ZlibDecoder;
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Marker ending every file record
+pub const RECORD_SENTINEL: u32 = 0xBAAD_F00D;
+
+/// Size of a general file record
+pub const GENERAL_RECORD_SIZE: usize = 36;
+
+/// Size of a texture record without its chunks
+pub const TEXTURE_RECORD_SIZE: usize = 24;
+
+/// Size of a texture chunk record
+pub const TEXTURE_CHUNK_SIZE: usize = 24;
+
+/// Size of the buffer [`BA2Archive::read`] unpacks through
+const COPY_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Largest file [`BA2Archive::read`] unpacks into memory
+///
+/// Larger files are streamed with [`BA2Archive::write_entry`] instead.
+pub const MAX_READ_SIZE: u64 = 1 << 30;
+
+/// Compression format of a version 3 header: zlib
+const COMPRESSION_ZLIB: u32 = 0;
+
+/// Compression format of a version 3 header: raw LZ4 blocks
+const COMPRESSION_LZ4: u32 = 3;
+
+/// Magic number of a zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
+
+/// Magic number of an LZ4 frame
+const LZ4_FRAME_MAGIC: [u8; 4] = [0x04, 0x22, 0x4D, 0x18];
+
+/// Compression codec of stored data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    /// zlib stream
+    Zlib,
+    /// Raw LZ4 block, as written by Starfield's LZ4 archives
+    Lz4Block,
+    /// LZ4 frame
+    Lz4Frame,
+    /// Zstandard frame
+    Zstd,
+}
+
+impl Codec {
+    /// Detect the codec of compressed data from its first bytes
+    ///
+    /// `format` is the compression format of the archive header. Archives
+    /// declaring LZ4 store raw blocks, which have no magic number; other
+    /// archives are sniffed for zstd and LZ4 frames before zlib. Returns a
+    /// description of the data if no codec matches.
+    pub fn detect(format: u32, head: &[u8]) -> std::result::Result<Self, String> {
+        match format {
+            COMPRESSION_LZ4 => Ok(Self::Lz4Block),
+            COMPRESSION_ZLIB if head.starts_with(&ZSTD_MAGIC) => Ok(Self::Zstd),
+            COMPRESSION_ZLIB if head.starts_with(&LZ4_FRAME_MAGIC) => Ok(Self::Lz4Frame),
+            COMPRESSION_ZLIB if is_zlib_header(head) => Ok(Self::Zlib),
+            COMPRESSION_ZLIB => Err(format!(
+                "unknown codec, data starts with {}",
+                head.iter()
+                    .take(4)
+                    .map(|byte| format!("{byte:02X}"))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            )),
+            format => Err(format!("compression format {format}")),
+        }
+    }
+
+    /// Name of the codec
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Zlib => "zlib",
+            Self::Lz4Block => "LZ4 block",
+            Self::Lz4Frame => "LZ4 frame",
+            Self::Zstd => "zstd",
+        }
+    }
+}
+
+impl std::fmt::Display for Codec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Whether data starts with a valid zlib header (deflate, checksum of the
+/// first two bytes divisible by 31)
+fn is_zlib_header(head: &[u8]) -> bool {
+    match head {
+        [cmf, flg, ..] => {
+            cmf & 0x0F == 8 && cmf >> 4 <= 7 && ((u16::from(*cmf) << 8) | u16::from(*flg)) % 31 == 0
+        }
+        _ => false,
+    }
+}
+
+/// A file stored in a general archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneralEntry {
+    /// Path inside the archive, from the name table
+    pub name: String,
+
+    /// CRC-32 of the lowercase file name without extension
+    pub name_hash: u32,
+
+    /// Extension without the dot, null padded
+    pub extension: [u8; 4],
+
+    /// CRC-32 of the lowercase folder path
+    pub dir_hash: u32,
+
+    /// Record flags
+    pub flags: u32,
+
+    /// Offset of the file's data from the start of the archive
+    pub offset: u64,
+
+    /// Size of the compressed data, 0 if the file is stored uncompressed
+    pub packed_size: u32,
+
+    /// Size of the file once unpacked
+    pub unpacked_size: u32,
+}
+
+impl GeneralEntry {
+    /// Parse a file record; the name is filled in from the name table later
+    fn parse(record: &[u8; GENERAL_RECORD_SIZE]) -> Option<Self> {
+        let u32_at = |at: usize| {
+            u32::from_le_bytes([record[at], record[at + 1], record[at + 2], record[at + 3]])
+        };
+        if u32_at(32) != RECORD_SENTINEL {
+            return None;
+        }
+        let mut offset = [0u8; 8];
+        offset.copy_from_slice(&record[16..24]);
+        Some(Self {
+            name: String::new(),
+            name_hash: u32_at(0),
+            extension: [record[4], record[5], record[6], record[7]],
+            dir_hash: u32_at(8),
+            flags: u32_at(12),
+            offset: u64::from_le_bytes(offset),
+            packed_size: u32_at(24),
+            unpacked_size: u32_at(28),
+        })
+    }
+
+    /// Whether the data is compressed
+    pub const fn is_compressed(&self) -> bool {
+        self.packed_size != 0
+    }
+
+    /// Number of bytes the file takes up in the archive
+    pub const fn stored_size(&self) -> u32 {
+        if self.is_compressed() {
+            self.packed_size
+        } else {
+            self.unpacked_size
+        }
+    }
+
+    const fn span(&self) -> Span {
+        Span {
+            offset: self.offset,
+            packed_size: self.packed_size,
+            unpacked_size: self.unpacked_size,
+        }
+    }
+
+    /// Extension from the record (e.g., "nif")
+    pub fn extension(&self) -> String {
+        String::from_utf8_lossy(&self.extension)
+            .trim_end_matches('\0')
+            .to_string()
+    }
+}
+
+/// A texture stored in a DX10 archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextureEntry {
+    /// Path inside the archive, from the name table
+    pub name: String,
+
+    /// CRC-32 of the lowercase file name without extension
+    pub name_hash: u32,
+
+    /// Extension without the dot, null padded
+    pub extension: [u8; 4],
+
+    /// CRC-32 of the lowercase folder path
+    pub dir_hash: u32,
+
+    /// Height of the top mip level in pixels
+    pub height: u16,
+
+    /// Width of the top mip level in pixels
+    pub width: u16,
+
+    /// Number of mip levels
+    pub num_mips: u8,
+
+    /// DXGI format of the pixel data
+    pub format: u8,
+
+    /// Whether the texture is a cubemap (six faces)
+    pub is_cubemap: bool,
+
+    /// Tile mode (8 for regular, linear textures)
+    pub tile_mode: u8,
+
+    /// Chunks holding the mip levels, largest first
+    pub chunks: Vec<TextureChunk>,
+}
+
+/// A range of mip levels of a texture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureChunk {
+    /// Offset of the chunk's data from the start of the archive
+    pub offset: u64,
+
+    /// Size of the compressed data, 0 if the chunk is stored uncompressed
+    pub packed_size: u32,
+
+    /// Size of the chunk once unpacked
+    pub unpacked_size: u32,
+
+    /// First mip level in the chunk
+    pub start_mip: u16,
+
+    /// Last mip level in the chunk
+    pub end_mip: u16,
+}
+
+impl TextureEntry {
+    /// Parse a texture record; the chunks and name are filled in later
+    fn parse(record: &[u8; TEXTURE_RECORD_SIZE]) -> (Self, usize, u16) {
+        let u16_at = |at: usize| u16::from_le_bytes([record[at], record[at + 1]]);
+        let u32_at = |at: usize| {
+            u32::from_le_bytes([record[at], record[at + 1], record[at + 2], record[at + 3]])
+        };
+        let entry = Self {
+            name: String::new(),
+            name_hash: u32_at(0),
+            extension: [record[4], record[5], record[6], record[7]],
+            dir_hash: u32_at(8),
+            height: u16_at(16),
+            width: u16_at(18),
+            num_mips: record[20],
+            format: record[21],
+            is_cubemap: record[22] != 0,
+            tile_mode: record[23],
+            chunks: Vec::new(),
+        };
+        (entry, usize::from(record[13]), u16_at(14))
+    }
+
+    /// Reconstructed DDS header
+    pub fn dds_header(&self) -> Vec<u8> {
+        dds::header(
+            self.width,
+            self.height,
+            self.num_mips,
+            self.format,
+            self.is_cubemap,
+        )
+    }
+
+    /// Size of the DDS file once unpacked, including the header
+    pub fn unpacked_size(&self) -> u64 {
+        dds::header_size(self.format) as u64
+            + self
+                .chunks
+                .iter()
+                .map(|chunk| u64::from(chunk.unpacked_size))
+                .sum::<u64>()
+    }
+
+    /// Extension from the record (e.g., "dds")
+    pub fn extension(&self) -> String {
+        String::from_utf8_lossy(&self.extension)
+            .trim_end_matches('\0')
+            .to_string()
+    }
+}
+
+impl TextureChunk {
+    /// Parse a chunk record
+    fn parse(record: &[u8; TEXTURE_CHUNK_SIZE]) -> Option<Self> {
+        let u16_at = |at: usize| u16::from_le_bytes([record[at], record[at + 1]]);
+        let u32_at = |at: usize| {
+            u32::from_le_bytes([record[at], record[at + 1], record[at + 2], record[at + 3]])
+        };
+        if u32_at(20) != RECORD_SENTINEL {
+            return None;
+        }
+        let mut offset = [0u8; 8];
+        offset.copy_from_slice(&record[0..8]);
+        Some(Self {
+            offset: u64::from_le_bytes(offset),
+            packed_size: u32_at(8),
+            unpacked_size: u32_at(12),
+            start_mip: u16_at(16),
+            end_mip: u16_at(18),
+        })
+    }
+
+    /// Whether the data is compressed
+    pub const fn is_compressed(&self) -> bool {
+        self.packed_size != 0
+    }
+
+    /// Number of bytes the chunk takes up in the archive
+    pub const fn stored_size(&self) -> u32 {
+        if self.is_compressed() {
+            self.packed_size
+        } else {
+            self.unpacked_size
+        }
+    }
+
+    const fn span(&self) -> Span {
+        Span {
+            offset: self.offset,
+            packed_size: self.packed_size,
+            unpacked_size: self.unpacked_size,
+        }
+    }
+}
+
+/// How the data of an archive is read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadMode {
+    /// Map archives on local drives, read archives on other drives
+    #[default]
+    Auto,
+    /// Always map archives into memory
+    Mapped,
+    /// Always read archives with buffered IO
+    Buffered,
+}
+
+impl ReadMode {
+    /// Whether an archive at `path` is memory mapped
+    pub fn maps(self, path: &Path) -> bool {
+        match self {
+            Self::Auto => !drive_kind(path).is_slow(),
+            Self::Mapped => true,
+            Self::Buffered => false,
+        }
+    }
+}
+
+/// Where file data is read from
+#[derive(Debug, Clone)]
+enum Source {
+    Mapped(Arc<Mmap>),
+    Buffered,
+}
+
+/// A BA2 archive with its file table
+///
+/// Only the tables are read when opening; file data is read on demand with
+/// [`BA2Archive::read`]. Clones share the memory mapping.
+#[derive(Debug, Clone)]
+pub struct BA2Archive {
+    path: PathBuf,
+    len: u64,
+    header: BA2Header,
+    compression: u32,
+    names: Vec<String>,
+    entries: Vec<GeneralEntry>,
+    textures: Vec<TextureEntry>,
+    source: Source,
+}
+
+impl BA2Archive {
+    /// Read the header, file records and name table of an archive
+    ///
+    /// The archive is read as chosen by [`ReadMode::Auto`].
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::open_with(path, ReadMode::Auto)
+    }
+
+    /// Read the tables of an archive, reading its data as chosen by `mode`
+    ///
+    /// Falls back to buffered IO if the archive cannot be mapped.
+    pub fn open_with(path: &Path, mode: ReadMode) -> Result<Self> {
+        let file = File::open(path).map_err(|e| BA2Error::ExtractionFailed {
+            path: path.to_path_buf(),
+            reason: format!("Failed to open file: {e}"),
+        })?;
+        let len = file.metadata()?.len();
+
+        if mode.maps(path) {
+            // SAFETY: The mapping is only read. Archives are not modified while
+            // they are open; a file truncated by another program makes reads
+            // fault, as with any memory-mapped file.
+            match unsafe { Mmap::map(&file) } {
+                Ok(map) => {
+                    let map = Arc::new(map);
+                    return Self::parse(
+                        path,
+                        Cursor::new(&map[..]),
+                        len,
+                        Source::Mapped(Arc::clone(&map)),
+                    );
+                }
+                Err(e) => tracing::debug!(
+                    "Failed to map {}, reading it instead: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+        Self::parse(path, BufReader::new(file), len, Source::Buffered)
+    }
+
+    /// Parse the tables of an archive of `len` bytes
+    fn parse<R: Read + Seek>(path: &Path, mut reader: R, len: u64, source: Source) -> Result<Self> {
+        let header = BA2Header::parse_from_reader(&mut reader, path)?;
+        // The record layout of other versions is unknown
+        header.check_version(path)?;
+        let corrupted = |reason: String| BA2Error::Corrupted {
+            path: path.to_path_buf(),
+            reason,
+        };
+
+        // The compression format is the last field of the version 3 extension
+        let compression = if header.version == 3 {
+            let mut extension = [0u8; 12];
+            reader
+                .read_exact(&mut extension)
+                .map_err(|e| corrupted(format!("Header extension is truncated: {e}")))?;
+            u32::from_le_bytes([extension[8], extension[9], extension[10], extension[11]])
+        } else {
+            COMPRESSION_ZLIB
+        };
+
+        let records_offset = records_offset(header.version);
+        let record_size = if header.is_texture() {
+            TEXTURE_RECORD_SIZE + TEXTURE_CHUNK_SIZE
+        } else {
+            GENERAL_RECORD_SIZE
+        };
+        // Texture records are at least one record and one chunk long
+        if records_offset + u64::from(header.file_count) * record_size as u64 > len {
+            return Err(corrupted(format!(
+                "File table of {} records extends past the end of the file",
+                header.file_count
+            ))
+            .into());
+        }
+        reader
+            .seek(SeekFrom::Start(records_offset))
+            .map_err(|e| corrupted(format!("Failed to seek to file table: {e}")))?;
+        let past_end = |index: u32, offset: u64, size: u32| {
+            if offset.saturating_add(u64::from(size)) > len {
+                Err(corrupted(format!(
+                    "Data of file record {index} extends past the end of the file"
+                )))
+            } else {
+                Ok(())
+            }
+        };
+
+        let mut entries = Vec::new();
+        let mut textures = Vec::new();
+        if header.is_general() {
+            entries.reserve(header.file_count as usize);
+            for index in 0..header.file_count {
+                let mut record = [0u8; GENERAL_RECORD_SIZE];
+                reader.read_exact(&mut record).map_err(|e| {
+                    corrupted(format!("File table truncated at entry {index}: {e}"))
+                })?;
+                let entry = GeneralEntry::parse(&record)
+                    .ok_or_else(|| corrupted(format!("File record {index} has no end marker")))?;
+                past_end(index, entry.offset, entry.stored_size())?;
+                entries.push(entry);
+            }
+        } else if header.is_texture() {
+            textures.reserve(header.file_count as usize);
+            for index in 0..header.file_count {
+                let truncated = |e: std::io::Error| {
+                    corrupted(format!("File table truncated at entry {index}: {e}"))
+                };
+                let mut record = [0u8; TEXTURE_RECORD_SIZE];
+                reader.read_exact(&mut record).map_err(truncated)?;
+                let (mut texture, chunk_count, chunk_size) = TextureEntry::parse(&record);
+                if usize::from(chunk_size) != TEXTURE_CHUNK_SIZE {
+                    return Err(corrupted(format!(
+                        "Texture record {index} has chunk records of {chunk_size} bytes"
+                    ))
+                    .into());
+                }
+                for _ in 0..chunk_count {
+                    let mut record = [0u8; TEXTURE_CHUNK_SIZE];
+                    reader.read_exact(&mut record).map_err(truncated)?;
+                    let chunk = TextureChunk::parse(&record).ok_or_else(|| {
+                        corrupted(format!("Chunk of texture record {index} has no end marker"))
+                    })?;
+                    past_end(index, chunk.offset, chunk.stored_size())?;
+                    texture.chunks.push(chunk);
+                }
+                textures.push(texture);
+            }
+        }
+
+        let names = read_names_from_reader(reader, &header, path)?;
+        for (entry, name) in entries.iter_mut().zip(&names) {
+            entry.name.clone_from(name);
+        }
+        for (texture, name) in textures.iter_mut().zip(&names) {
+            texture.name.clone_from(name);
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            len,
+            header,
+            compression,
+            names,
+            entries,
+            textures,
+            source,
+        })
+    }
+
+    /// Compression format declared by the header (0 for zlib, 3 for LZ4)
+    ///
+    /// Archives before version 3 do not declare a format and report zlib.
+    pub const fn compression_format(&self) -> u32 {
+        self.compression
+    }
+
+    /// Whether the archive is memory mapped
+    pub const fn is_mapped(&self) -> bool {
+        matches!(self.source, Source::Mapped(_))
+    }
+
+    /// Path the archive was opened from
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Header of the archive
+    pub const fn header(&self) -> &BA2Header {
+        &self.header
+    }
+
+    /// Names of all files, in the order of the file records
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Files of a general archive (empty for texture archives)
+    pub fn entries(&self) -> &[GeneralEntry] {
+        &self.entries
+    }
+
+    /// Look up a file by its path inside the archive, ignoring case and separators
+    pub fn entry(&self, name: &str) -> Option<&GeneralEntry> {
+        let wanted = normalize_name(name);
+        self.entries
+            .iter()
+            .find(|entry| normalize_name(&entry.name) == wanted)
+    }
+
+    /// Textures of a texture archive (empty for general archives)
+    pub fn textures(&self) -> &[TextureEntry] {
+        &self.textures
+    }
+
+    /// Look up a texture by its path inside the archive, ignoring case and separators
+    pub fn texture(&self, name: &str) -> Option<&TextureEntry> {
+        let wanted = normalize_name(name);
+        self.textures
+            .iter()
+            .find(|texture| normalize_name(&texture.name) == wanted)
+    }
+
+    /// Total size of the files once unpacked, including DDS headers
+    pub fn unpacked_size(&self) -> u64 {
+        self.entries
+            .iter()
+            .map(|entry| u64::from(entry.unpacked_size))
+            .chain(self.textures.iter().map(TextureEntry::unpacked_size))
+            .sum()
+    }
+
+    /// Read and unpack the data of a file
+    pub fn read(&self, entry: &GeneralEntry) -> Result<Vec<u8>> {
+        let mut data = self.read_buffer(&entry.name, u64::from(entry.unpacked_size))?;
+        self.write_entry(entry, &mut data, &mut vec![0; COPY_BUFFER_SIZE])?;
+        Ok(data)
+    }
+
+    /// Read a texture as a DDS file: the rebuilt header followed by all mip levels
+    pub fn read_texture(&self, texture: &TextureEntry) -> Result<Vec<u8>> {
+        let mut data = self.read_buffer(&texture.name, texture.unpacked_size())?;
+        self.write_texture(texture, &mut data, &mut vec![0; COPY_BUFFER_SIZE])?;
+        Ok(data)
+    }
+
+    /// Read `len` bytes of a texture's pixel data, starting `offset` bytes in
+    ///
+    /// The DDS header is not included. Chunks that end before the range or
+    /// start after it are not read at all, so a small mip level can be read
+    /// without unpacking the larger ones.
+    pub fn read_texture_data(
+        &self,
+        texture: &TextureEntry,
+        offset: u64,
+        len: u64,
+    ) -> Result<Vec<u8>> {
+        let mut data = self.read_buffer(&texture.name, len)?;
+        let mut reader = self.data_reader()?;
+        let mut buffer = vec![0; COPY_BUFFER_SIZE];
+        let end = offset.saturating_add(len);
+        let mut head = HeadWriter::new(&mut data, len);
+        let mut out = SkipWriter::new(&mut head, offset);
+        let mut position = 0u64;
+        for chunk in &texture.chunks {
+            if position >= end {
+                break;
+            }
+            let size = u64::from(chunk.unpacked_size);
+            if position + size <= offset {
+                out.skip(size);
+            } else {
+                self.copy_data(
+                    &mut reader,
+                    &texture.name,
+                    chunk.span(),
+                    &mut out,
+                    &mut buffer,
+                )?;
+            }
+            position += size;
+        }
+
+        if (data.len() as u64) < len {
+            return Err(BA2Error::Corrupted {
+                path: self.path.clone(),
+                reason: format!("Data of {} is truncated", texture.name),
+            }
+            .into());
+        }
+        Ok(data)
+    }
+
+    /// Buffer for a file the file table says unpacks to `size` bytes
+    ///
+    /// A damaged record can claim any size, so files over [`MAX_READ_SIZE`]
+    /// are refused and no more than the archive's length is reserved up front.
+    fn read_buffer(&self, name: &str, size: u64) -> Result<Vec<u8>> {
+        if size > MAX_READ_SIZE {
+            return Err(BA2Error::ExtractionFailed {
+                path: self.path.clone(),
+                reason: format!("{name} unpacks to {size} bytes, too large to read into memory"),
+            }
+            .into());
+        }
+        let reserved = usize::try_from(size.min(self.len)).unwrap_or(0);
+        Ok(Vec::with_capacity(reserved))
+    }
+
+    /// Unpack a file into `out`, returning the number of bytes written
+    ///
+    /// Data is streamed through `buffer` (which must not be empty), so only
+    /// `buffer.len()` bytes of the file are held in memory at a time.
+    /// Uncompressed files of a mapped archive are written straight from the
+    /// mapping.
+    pub fn write_entry<W: Write + ?Sized>(
+        &self,
+        entry: &GeneralEntry,
+        out: &mut W,
+        buffer: &mut [u8],
+    ) -> Result<u64> {
+        let mut reader = self.data_reader()?;
+        self.copy_data(&mut reader, &entry.name, entry.span(), out, buffer)
+    }
+
+    /// Write a texture as a DDS file into `out`, like [`BA2Archive::write_entry`]
+    pub fn write_texture<W: Write + ?Sized>(
+        &self,
+        texture: &TextureEntry,
+        out: &mut W,
+        buffer: &mut [u8],
+    ) -> Result<u64> {
+        let mut reader = self.data_reader()?;
+        let header = texture.dds_header();
+        out.write_all(&header)?;
+        let mut written = header.len() as u64;
+        for chunk in &texture.chunks {
+            written += self.copy_data(&mut reader, &texture.name, chunk.span(), out, buffer)?;
+        }
+        Ok(written)
+    }
+
+    /// Write a texture with up to `skip_mips` of its largest mip levels dropped
+    ///
+    /// See [`MipSkip::plan`] for which textures are downscaled; the others are
+    /// written at full size, like [`BA2Archive::write_texture`]. Chunks that
+    /// only hold dropped levels are not read at all.
+    pub fn write_texture_downscaled<W: Write + ?Sized>(
+        &self,
+        texture: &TextureEntry,
+        skip_mips: u8,
+        out: &mut W,
+        buffer: &mut [u8],
+    ) -> Result<u64> {
+        let Some(skip) = MipSkip::plan(texture, skip_mips) else {
+            return self.write_texture(texture, out, buffer);
+        };
+        let mut reader = self.data_reader()?;
+        let header = skip.dds_header(texture);
+        out.write_all(&header)?;
+        let mut out = SkipWriter::new(out, skip.skipped_bytes);
+        for chunk in &texture.chunks {
+            let size = u64::from(chunk.unpacked_size);
+            if size <= out.remaining() {
+                out.skip(size);
+                continue;
+            }
+            self.copy_data(&mut reader, &texture.name, chunk.span(), &mut out, buffer)?;
+        }
+        Ok(header.len() as u64 + out.written())
+    }
+
+    fn data_reader(&self) -> Result<DataReader<'_>> {
+        Ok(match &self.source {
+            Source::Mapped(map) => DataReader::Mapped(&map[..]),
+            Source::Buffered => DataReader::File(File::open(&self.path).map_err(|e| {
+                BA2Error::ExtractionFailed {
+                    path: self.path.clone(),
+                    reason: format!("Failed to open file: {e}"),
+                }
+            })?),
+        })
+    }
+
+    /// Unpack the data described by `span` into `out`
+    ///
+    /// Failures to read or unpack the archive are reported as corruption,
+    /// failures to write as IO errors.
+    fn copy_data<W: Write + ?Sized>(
+        &self,
+        reader: &mut DataReader<'_>,
+        name: &str,
+        span: Span,
+        out: &mut W,
+        buffer: &mut [u8],
+    ) -> Result<u64> {
+        debug_assert!(!buffer.is_empty(), "copy buffer must not be empty");
+        let corrupted = |reason: String| BA2Error::Corrupted {
+            path: self.path.clone(),
+            reason,
+        };
+
+        if span.packed_size == 0
+            && let DataReader::Mapped(map) = reader
+        {
+            let data = slice(map, span.offset, span.unpacked_size)
+                .ok_or_else(|| corrupted(format!("Data of {name} is truncated")))?;
+            out.write_all(data)?;
+            return Ok(data.len() as u64);
+        }
+
+        let stored = reader
+            .stored(span.offset, span.stored_size())
+            .map_err(|e| corrupted(format!("Data of {name} is truncated: {e}")))?;
+        let mut source: Box<dyn Read + '_> = if span.packed_size == 0 {
+            stored
+        } else {
+            self.decoder(stored, name, span)?
+        };
+        let mut written = 0u64;
+        loop {
+            let read = match source.read(buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(corrupted(format!("Failed to unpack {name}: {e}")).into()),
+            };
+            out.write_all(&buffer[..read])?;
+            written += read as u64;
+        }
+        if written != u64::from(span.unpacked_size) {
+            return Err(corrupted(format!(
+                "{name} unpacked to {written} bytes instead of {}",
+                span.unpacked_size
+            ))
+            .into());
+        }
+        Ok(written)
+    }
+
+    /// Reader that unpacks compressed data with the codec detected for it
+    fn decoder<'a>(
+        &self,
+        stored: Box<dyn Read + 'a>,
+        name: &str,
+        span: Span,
+    ) -> Result<Box<dyn Read + 'a>> {
+        let corrupted = |reason: String| BA2Error::Corrupted {
+            path: self.path.clone(),
+            reason,
+        };
+        let mut stored = BufReader::with_capacity(COPY_BUFFER_SIZE, stored);
+        let head = stored
+            .fill_buf()
+            .map_err(|e| corrupted(format!("Data of {name} is truncated: {e}")))?;
+        let codec = Codec::detect(self.compression, head).map_err(|codec| {
+            BA2Error::UnsupportedCompression {
+                path: self.path.clone(),
+                name: name.to_string(),
+                codec,
+            }
+        })?;
+
+        Ok(match codec {
+            Codec::Zlib => Box::new(ZlibDecoder::new(stored)),
+            Codec::Zstd => Box::new(
+                zstd::stream::read::Decoder::with_buffer(stored)
+                    .map_err(|e| corrupted(format!("Failed to unpack {name}: {e}")))?,
+            ),
+            Codec::Lz4Frame => Box::new(lz4_flex::frame::FrameDecoder::new(stored)),
+            // Raw blocks cannot be streamed and are unpacked whole, so the sizes
+            // from the record are bounded before anything is allocated
+            Codec::Lz4Block => {
+                if u64::from(span.unpacked_size) > MAX_READ_SIZE {
+                    return Err(corrupted(format!(
+                        "{name} claims {} bytes unpacked from an LZ4 block",
+                        span.unpacked_size
+                    ))
+                    .into());
+                }
+                let remaining = self.len.saturating_sub(span.offset);
+                let reserved = u64::from(span.packed_size).min(remaining);
+                let mut packed = Vec::with_capacity(usize::try_from(reserved).unwrap_or(0));
+                stored
+                    .read_to_end(&mut packed)
+                    .map_err(|e| corrupted(format!("Data of {name} is truncated: {e}")))?;
+                let data = lz4_flex::block::decompress(&packed, span.unpacked_size as usize)
+                    .map_err(|e| corrupted(format!("Failed to unpack {name}: {e}")))?;
+                Box::new(Cursor::new(data))
+            }
+        })
+    }
+}
+
+/// Location and sizes of stored data
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    offset: u64,
+    packed_size: u32,
+    unpacked_size: u32,
+}
+
+impl Span {
+    const fn stored_size(self) -> u32 {
+        if self.packed_size == 0 {
+            self.unpacked_size
+        } else {
+            self.packed_size
+        }
+    }
+}
+
+/// Reads stored data from the mapping or the file
+enum DataReader<'a> {
+    Mapped(&'a [u8]),
+    File(File),
+}
+
+impl DataReader<'_> {
+    /// Reader over the `size` bytes at `offset`
+    fn stored(&mut self, offset: u64, size: u32) -> std::io::Result<Box<dyn Read + '_>> {
+        match self {
+            Self::Mapped(map) => slice(map, offset, size)
+                .map(|data| Box::new(data) as Box<dyn Read + '_>)
+                .ok_or_else(|| std::io::ErrorKind::UnexpectedEof.into()),
+            Self::File(file) => {
+                file.seek(SeekFrom::Start(offset))?;
+                Ok(Box::new(Read::by_ref(file).take(u64::from(size))))
+            }
+        }
+    }
+}
+
+/// Keeps the first `limit` bytes written into it and drops the rest
+struct HeadWriter<'a> {
+    out: &'a mut Vec<u8>,
+    limit: u64,
+}
+
+impl<'a> HeadWriter<'a> {
+    const fn new(out: &'a mut Vec<u8>, limit: u64) -> Self {
+        Self { out, limit }
+    }
+}
+
+impl Write for HeadWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let room = self.limit.saturating_sub(self.out.len() as u64);
+        let kept = usize::try_from(room).map_or(buf.len(), |room| room.min(buf.len()));
+        self.out.extend_from_slice(&buf[..kept]);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The `size` bytes at `offset` of a mapping
+fn slice(map: &[u8], offset: u64, size: u32) -> Option<&[u8]> {
+    let start = usize::try_from(offset).ok()?;
+    map.get(start..start.checked_add(size as usize)?)
+}
+
+/// Offset of the first file record for a format version
+pub const fn records_offset(version: u32) -> u64 {
+    let header = BA2Header::HEADER_SIZE as u64;
+    match version {
+        2 => header + 8,
+        3 => header + 12,
+        _ => header,
+    }
+}
+
+fn normalize_name(name: &str) -> String {
+    name.replace('/', "\\").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures::{
+        FixtureFile, FixtureTexture, TEXTURE_FORMAT, dx10_archive, entry_data, gnrl_archive,
+        write_dx10, write_gnrl,
+    };
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use std::io::Write;
+
+    const NAMES: &[&str] = &["Meshes\\Armor\\Helmet.nif", "scripts\\quest.pex"];
+
+    #[test]
+    fn test_open_general_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Mod - Main.ba2");
+        write_gnrl(&path, NAMES).unwrap();
+
+        let archive = BA2Archive::open(&path).unwrap();
+        assert_eq!(archive.names(), NAMES);
+        let entries = archive.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, NAMES[0]);
+        assert_eq!(entries[0].extension(), "nif");
+        assert!(!entries[0].is_compressed());
+        assert_ne!(entries[0].dir_hash, entries[1].dir_hash);
+        assert_eq!(
+            archive.unpacked_size(),
+            NAMES
+                .iter()
+                .map(|n| entry_data(n).len() as u64)
+                .sum::<u64>()
+        );
+
+        let entry = archive.entry("meshes/armor/helmet.NIF").unwrap();
+        assert_eq!(archive.read(entry).unwrap(), entry_data(NAMES[0]));
+        assert!(archive.entry("meshes\\missing.nif").is_none());
+
+        assert!(archive.textures().is_empty());
+    }
+
+    #[test]
+    fn test_open_texture_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Mod - Textures.ba2");
+        write_dx10(
+            &path,
+            &["Textures\\Armor\\Helmet_d.dds", "textures\\sky.dds"],
+        )
+        .unwrap();
+
+        let archive = BA2Archive::open(&path).unwrap();
+        assert!(archive.entries().is_empty());
+        let textures = archive.textures();
+        assert_eq!(textures.len(), 2);
+        let texture = archive.texture("textures/armor/helmet_d.dds").unwrap();
+        assert_eq!(texture.extension(), "dds");
+        assert_eq!((texture.width, texture.height), (4, 4));
+        assert_eq!(texture.num_mips, 1);
+        assert_eq!(texture.format, TEXTURE_FORMAT);
+        assert!(!texture.is_cubemap);
+        assert_eq!(texture.chunks.len(), 1);
+        assert_eq!(
+            (texture.chunks[0].start_mip, texture.chunks[0].end_mip),
+            (0, 0)
+        );
+
+        let dds = archive.read_texture(texture).unwrap();
+        let fixture = FixtureTexture::named("Textures\\Armor\\Helmet_d.dds");
+        let header = texture.dds_header();
+        assert_eq!(&dds[..4], b"DDS ");
+        assert_eq!(&dds[..header.len()], header.as_slice());
+        assert_eq!(&dds[header.len()..], fixture.data.as_slice());
+        assert_eq!(archive.unpacked_size(), 2 * dds.len() as u64);
+    }
+
+    #[test]
+    fn test_write_texture_downscaled() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Mod - Textures.ba2");
+        let fixture = FixtureTexture::with_mips("textures\\sky.dds", 32, 16, 4);
+        std::fs::write(&path, dx10_archive(std::slice::from_ref(&fixture))).unwrap();
+
+        let archive = BA2Archive::open(&path).unwrap();
+        let texture = &archive.textures()[0];
+        let mut buffer = [0u8; 64];
+        let mut dds = Vec::new();
+        let written = archive
+            .write_texture_downscaled(texture, 2, &mut dds, &mut buffer)
+            .unwrap();
+        assert_eq!(written, dds.len() as u64);
+
+        // The 8x4 level is the new top level; 4 pixels is the smallest edge kept
+        let header = dds::header(8, 4, 2, TEXTURE_FORMAT, false);
+        assert_eq!(&dds[..header.len()], header.as_slice());
+        let pixels = &dds[header.len()..];
+        assert_eq!(pixels.len(), (8 * 4 + 4 * 2) * 4);
+        assert!(pixels[..8 * 4 * 4].iter().all(|&b| b == 2));
+        assert!(pixels[8 * 4 * 4..].iter().all(|&b| b == 3));
+
+        let mut full = Vec::new();
+        archive
+            .write_texture_downscaled(texture, 0, &mut full, &mut buffer)
+            .unwrap();
+        assert_eq!(full, archive.read_texture(texture).unwrap());
+    }
+
+    #[test]
+    fn test_read_texture_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Mod - Textures.ba2");
+        let fixture = FixtureTexture::with_mips("textures\\sky.dds", 32, 16, 4);
+        std::fs::write(&path, dx10_archive(std::slice::from_ref(&fixture))).unwrap();
+
+        let archive = BA2Archive::open(&path).unwrap();
+        let texture = &archive.textures()[0];
+        let level2 = (32 * 16 + 16 * 8) * 4;
+        let data = archive
+            .read_texture_data(texture, level2, 8 * 4 * 4)
+            .unwrap();
+        assert_eq!(data.len(), 8 * 4 * 4);
+        assert!(data.iter().all(|&b| b == 2));
+
+        // Reading past the end of the pixel data is reported as corruption
+        let len = fixture.data.len() as u64;
+        assert!(archive.read_texture_data(texture, len - 4, 8).is_err());
+    }
+
+    #[test]
+    fn test_read_modes() {
+        let dir = tempfile::tempdir().unwrap();
+        let general = dir.path().join("Mod - Main.ba2");
+        let textures = dir.path().join("Mod - Textures.ba2");
+        write_gnrl(&general, NAMES).unwrap();
+        write_dx10(&textures, &["textures\\a.dds"]).unwrap();
+
+        let mapped = BA2Archive::open_with(&general, ReadMode::Mapped).unwrap();
+        let buffered = BA2Archive::open_with(&general, ReadMode::Buffered).unwrap();
+        assert!(mapped.is_mapped());
+        assert!(!buffered.is_mapped());
+        assert_eq!(mapped.entries(), buffered.entries());
+        for entry in mapped.entries() {
+            assert_eq!(mapped.read(entry).unwrap(), buffered.read(entry).unwrap());
+        }
+
+        let mapped = BA2Archive::open_with(&textures, ReadMode::Mapped).unwrap();
+        let buffered = BA2Archive::open_with(&textures, ReadMode::Buffered).unwrap();
+        let texture = &mapped.textures()[0];
+        assert_eq!(
+            mapped.read_texture(texture).unwrap(),
+            buffered.read_texture(texture).unwrap()
+        );
+
+        assert!(!ReadMode::Buffered.maps(dir.path()));
+    }
+
+    const TEXT: &[u8] = b"compressed fixture data, compressed fixture data";
+
+    /// General archive with one file of `unpacked` bytes stored as `packed`
+    fn compressed_archive(packed: &[u8], unpacked: usize) -> Vec<u8> {
+        let mut archive = gnrl_archive(&[FixtureFile {
+            name: "misc\\notes.txt".to_string(),
+            data: packed.to_vec(),
+        }]);
+        // Patch the packed and unpacked sizes of the record
+        let record = BA2Header::HEADER_SIZE;
+        archive[record + 24..record + 28]
+            .copy_from_slice(&u32::try_from(packed.len()).unwrap().to_le_bytes());
+        archive[record + 28..record + 32]
+            .copy_from_slice(&u32::try_from(unpacked).unwrap().to_le_bytes());
+        archive
+    }
+
+    /// Turn a single-file general archive into a version 3 archive
+    fn version3(mut archive: Vec<u8>, compression: u32) -> Vec<u8> {
+        let shift = |bytes: &mut [u8]| {
+            let offset = u64::from_le_bytes(bytes.try_into().unwrap()) + 12;
+            bytes.copy_from_slice(&offset.to_le_bytes());
+        };
+        archive[4..8].copy_from_slice(&3u32.to_le_bytes());
+        shift(&mut archive[16..24]);
+        let record = BA2Header::HEADER_SIZE;
+        shift(&mut archive[record + 16..record + 24]);
+        let mut extension = vec![0u8; 12];
+        extension[8..].copy_from_slice(&compression.to_le_bytes());
+        archive.splice(record..record, extension);
+        archive
+    }
+
+    fn read_only_entry(dir: &Path, archive: &[u8]) -> Result<Vec<u8>> {
+        let path = dir.join("Compressed.ba2");
+        std::fs::write(&path, archive).unwrap();
+        let mut data = None;
+        for mode in [ReadMode::Mapped, ReadMode::Buffered] {
+            let archive = BA2Archive::open_with(&path, mode).unwrap();
+            let entry = &archive.entries()[0];
+            assert!(entry.is_compressed());
+            let read = archive.read(entry)?;
+            assert!(data.as_ref().is_none_or(|data| *data == read));
+            data = Some(read);
+        }
+        Ok(data.unwrap())
+    }
+
+    #[test]
+    fn test_read_compressed_entry() {
+        let text = TEXT.repeat(8);
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&text).unwrap();
+        let packed = encoder.finish().unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Compressed.ba2");
+        std::fs::write(&path, compressed_archive(&packed, text.len())).unwrap();
+
+        let archive = BA2Archive::open(&path).unwrap();
+        let entry = &archive.entries()[0];
+        assert!(entry.is_compressed());
+        assert_eq!(entry.stored_size() as usize, packed.len());
+        assert_eq!(archive.read(entry).unwrap(), text);
+    }
+
+    #[test]
+    fn test_read_zstd_and_lz4_entries() {
+        let text = TEXT.repeat(8);
+        let dir = tempfile::tempdir().unwrap();
+
+        let zstd = zstd::encode_all(&text[..], 3).unwrap();
+        let archive = compressed_archive(&zstd, text.len());
+        assert_eq!(read_only_entry(dir.path(), &archive).unwrap(), text);
+
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+        encoder.write_all(&text).unwrap();
+        let frame = encoder.finish().unwrap();
+        let archive = compressed_archive(&frame, text.len());
+        assert_eq!(read_only_entry(dir.path(), &archive).unwrap(), text);
+
+        // Raw LZ4 blocks are only recognized in archives declaring LZ4
+        let block = lz4_flex::block::compress(&text);
+        let archive = version3(compressed_archive(&block, text.len()), COMPRESSION_LZ4);
+        assert_eq!(read_only_entry(dir.path(), &archive).unwrap(), text);
+        let archive = compressed_archive(&block, text.len());
+        assert!(matches!(
+            read_only_entry(dir.path(), &archive),
+            Err(crate::error::Error::BA2(
+                BA2Error::UnsupportedCompression { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_read_refuses_oversized_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(TEXT).unwrap();
+        let packed = encoder.finish().unwrap();
+        let claimed = usize::try_from(MAX_READ_SIZE).unwrap() + 1;
+        let error = read_only_entry(dir.path(), &compressed_archive(&packed, claimed)).unwrap_err();
+        assert!(error.to_string().contains("too large"), "{error}");
+    }
+
+    #[test]
+    fn test_unsupported_compression() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = compressed_archive(&[0xDE, 0xAD, 0xBE, 0xEF, 0x00], 64);
+        let error = read_only_entry(dir.path(), &archive).unwrap_err();
+        assert_eq!(error.category(), "compression");
+        assert!(error.to_string().contains("DE AD BE EF"), "{error}");
+
+        let archive = version3(compressed_archive(&[0x78, 0x9C], 64), 7);
+        let error = read_only_entry(dir.path(), &archive).unwrap_err();
+        assert!(
+            error.to_string().contains("compression format 7"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_detect_codec() {
+        assert_eq!(Codec::detect(0, &[0x78, 0x9C, 0]), Ok(Codec::Zlib));
+        assert_eq!(Codec::detect(0, &[0x78, 0xDA]), Ok(Codec::Zlib));
+        assert_eq!(Codec::detect(0, &ZSTD_MAGIC), Ok(Codec::Zstd));
+        assert_eq!(Codec::detect(0, &LZ4_FRAME_MAGIC), Ok(Codec::Lz4Frame));
+        assert_eq!(Codec::detect(3, &[0x78, 0x9C]), Ok(Codec::Lz4Block));
+        // Header checksum does not match
+        assert!(Codec::detect(0, &[0x78, 0x9D]).is_err());
+        assert!(Codec::detect(0, &[]).is_err());
+    }
+
+    #[test]
+    fn test_corrupted_file_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Broken.ba2");
+        let files = [FixtureFile::named("meshes\\a.nif")];
+
+        // Missing end marker
+        let mut archive = gnrl_archive(&files);
+        let sentinel = BA2Header::HEADER_SIZE + 32;
+        archive[sentinel..sentinel + 4].fill(0);
+        std::fs::write(&path, &archive).unwrap();
+        assert!(BA2Archive::open(&path).unwrap_err().is_ba2_corrupted());
+
+        // Data offset past the end of the file
+        let mut archive = gnrl_archive(&files);
+        let offset = BA2Header::HEADER_SIZE + 16;
+        archive[offset..offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        std::fs::write(&path, &archive).unwrap();
+        assert!(BA2Archive::open(&path).unwrap_err().is_ba2_corrupted());
+
+        // Chunk without an end marker
+        let mut archive = dx10_archive(&[FixtureTexture::named("textures\\a.dds")]);
+        let sentinel = BA2Header::HEADER_SIZE + TEXTURE_RECORD_SIZE + 20;
+        archive[sentinel..sentinel + 4].fill(0);
+        std::fs::write(&path, &archive).unwrap();
+        assert!(BA2Archive::open(&path).unwrap_err().is_ba2_corrupted());
+
+        // File count larger than the file
+        let mut archive = gnrl_archive(&files);
+        archive[12..16].copy_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&path, &archive).unwrap();
+        assert!(BA2Archive::open(&path).unwrap_err().is_ba2_corrupted());
+    }
+
+    #[test]
+    fn test_records_offset() {
+        assert_eq!(records_offset(1), 24);
+        assert_eq!(records_offset(8), 24);
+        assert_eq!(records_offset(2), 32);
+        assert_eq!(records_offset(3), 36);
+    }
+}
@@ -0,0 +1,252 @@
+//! DDS headers for textures stored in DX10 archives
+//!
+//! Texture archives store raw pixel data and only the facts needed to rebuild
+//! the header (size, mip count, DXGI format, cubemap flag). [`header`] writes
+//! the same kind of header `BSArch` does: a legacy pixel format for the
+//! formats older tools understand (DXT1/3/5, ATI1/2, 8-bit RGBA and
+//! luminance) and a DX10 extension header for everything else.
+
+/// "DDS " followed by the 124-byte header
+const HEADER_SIZE: usize = 4 + 124;
+
+/// Size of the DX10 extension header
+const DX10_HEADER_SIZE: usize = 20;
+
+// DDS_HEADER flags
+const DDSD_CAPS: u32 = 0x1;
+const DDSD_HEIGHT: u32 = 0x2;
+const DDSD_WIDTH: u32 = 0x4;
+const DDSD_PITCH: u32 = 0x8;
+const DDSD_PIXELFORMAT: u32 = 0x1000;
+const DDSD_MIPMAPCOUNT: u32 = 0x2_0000;
+const DDSD_LINEARSIZE: u32 = 0x8_0000;
+
+// DDS_PIXELFORMAT flags
+const DDPF_ALPHAPIXELS: u32 = 0x1;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDPF_LUMINANCE: u32 = 0x2_0000;
+
+// Caps
+const DDSCAPS_COMPLEX: u32 = 0x8;
+const DDSCAPS_TEXTURE: u32 = 0x1000;
+const DDSCAPS_MIPMAP: u32 = 0x40_0000;
+const DDSCAPS2_CUBEMAP_ALL_FACES: u32 = 0xFE00;
+
+/// `D3D10_RESOURCE_DIMENSION_TEXTURE2D`
+const DIMENSION_TEXTURE2D: u32 = 3;
+
+/// `D3D10_RESOURCE_MISC_TEXTURECUBE`
+const MISC_TEXTURECUBE: u32 = 0x4;
+
+/// Pixel format of a DDS header
+enum PixelFormat {
+    FourCC(&'static [u8; 4]),
+    Rgb { bits: u32, masks: [u32; 4] },
+    Luminance { bits: u32, mask: u32 },
+    Dx10,
+}
+
+/// Legacy pixel format for a DXGI format, if there is one
+const fn pixel_format(format: u8) -> PixelFormat {
+    match format {
+        71 => PixelFormat::FourCC(b"DXT1"), // BC1_UNORM
+        74 => PixelFormat::FourCC(b"DXT3"), // BC2_UNORM
+        77 => PixelFormat::FourCC(b"DXT5"), // BC3_UNORM
+        80 => PixelFormat::FourCC(b"ATI1"), // BC4_UNORM
+        83 => PixelFormat::FourCC(b"ATI2"), // BC5_UNORM
+        // R8G8B8A8_UNORM
+        28 => PixelFormat::Rgb {
+            bits: 32,
+            masks: [0x0000_00FF, 0x0000_FF00, 0x00FF_0000, 0xFF00_0000],
+        },
+        // B8G8R8A8_UNORM
+        87 => PixelFormat::Rgb {
+            bits: 32,
+            masks: [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000],
+        },
+        // R8_UNORM
+        61 => PixelFormat::Luminance {
+            bits: 8,
+            mask: 0xFF,
+        },
+        _ => PixelFormat::Dx10,
+    }
+}
+
+/// Bytes per 4x4 block of a block-compressed DXGI format
+pub(crate) const fn block_size(format: u8) -> Option<u32> {
+    match format {
+        70..=72 | 79..=81 => Some(8),            // BC1, BC4
+        73..=78 | 82..=84 | 94..=99 => Some(16), // BC2, BC3, BC5, BC6H, BC7
+        _ => None,
+    }
+}
+
+/// Bits per pixel of an uncompressed DXGI format
+pub(crate) const fn bits_per_pixel(format: u8) -> Option<u32> {
+    match format {
+        2 => Some(128),                               // R32G32B32A32
+        10..=14 => Some(64),                          // R16G16B16A16
+        24 | 27..=29 | 87 | 88 | 91 | 93 => Some(32), // 10:10:10:2 and 8-bit RGBA
+        48..=51 | 54 | 56 | 85 | 86 => Some(16),      // R8G8, R16, 5:6:5, 5:5:5:1
+        60..=65 => Some(8),                           // R8, A8
+        _ => None,
+    }
+}
+
+/// Short name of a DXGI format (e.g., "BC7"), `None` for formats mods rarely use
+pub const fn format_name(format: u8) -> Option<&'static str> {
+    match format {
+        70..=72 => Some("BC1"),
+        73..=75 => Some("BC2"),
+        76..=78 => Some("BC3"),
+        79..=81 => Some("BC4"),
+        82..=84 => Some("BC5"),
+        94..=96 => Some("BC6H"),
+        97..=99 => Some("BC7"),
+        27..=29 => Some("RGBA8"),
+        87 | 90 | 91 => Some("BGRA8"),
+        88 | 92 | 93 => Some("BGRX8"),
+        85 => Some("B5G6R5"),
+        86 => Some("B5G5R5A1"),
+        48..=51 => Some("RG8"),
+        60..=62 => Some("R8"),
+        65 => Some("A8"),
+        10..=14 => Some("RGBA16"),
+        2 => Some("RGBA32F"),
+        _ => None,
+    }
+}
+
+/// Whether a DXGI format is block compressed
+pub const fn is_block_compressed(format: u8) -> bool {
+    block_size(format).is_some()
+}
+
+/// Size of the top mip level in bytes, or its row pitch for uncompressed formats
+fn pitch_or_linear_size(format: u8, width: u32, height: u32) -> Option<(u32, u32)> {
+    block_size(format).map_or_else(
+        || bits_per_pixel(format).map(|bits| (DDSD_PITCH, (width * bits).div_ceil(8))),
+        |block| {
+            let blocks = width.div_ceil(4).max(1) * height.div_ceil(4).max(1);
+            Some((DDSD_LINEARSIZE, blocks * block))
+        },
+    )
+}
+
+/// Size of the header [`header`] writes for a DXGI format
+pub const fn header_size(format: u8) -> usize {
+    match pixel_format(format) {
+        PixelFormat::Dx10 => HEADER_SIZE + DX10_HEADER_SIZE,
+        _ => HEADER_SIZE,
+    }
+}
+
+/// Build the DDS header for a texture
+pub fn header(width: u16, height: u16, mips: u8, format: u8, cubemap: bool) -> Vec<u8> {
+    let width = u32::from(width);
+    let height = u32::from(height);
+    let pixel_format = pixel_format(format);
+
+    let mut flags = DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT | DDSD_MIPMAPCOUNT;
+    let pitch = if let Some((flag, size)) = pitch_or_linear_size(format, width, height) {
+        flags |= flag;
+        size
+    } else {
+        0
+    };
+
+    let mut caps = DDSCAPS_TEXTURE;
+    if mips > 1 {
+        caps |= DDSCAPS_COMPLEX | DDSCAPS_MIPMAP;
+    }
+    let mut caps2 = 0;
+    if cubemap {
+        caps |= DDSCAPS_COMPLEX;
+        caps2 |= DDSCAPS2_CUBEMAP_ALL_FACES;
+    }
+
+    let (pf_flags, four_cc, bits, masks) = match pixel_format {
+        PixelFormat::FourCC(code) => (DDPF_FOURCC, *code, 0, [0; 4]),
+        PixelFormat::Rgb { bits, masks } => (DDPF_RGB | DDPF_ALPHAPIXELS, [0; 4], bits, masks),
+        PixelFormat::Luminance { bits, mask } => (DDPF_LUMINANCE, [0; 4], bits, [mask, 0, 0, 0]),
+        PixelFormat::Dx10 => (DDPF_FOURCC, *b"DX10", 0, [0; 4]),
+    };
+
+    let mut out = Vec::with_capacity(header_size(format));
+    out.extend_from_slice(b"DDS ");
+    for value in [124, flags, height, width, pitch, 0, u32::from(mips.max(1))] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    out.extend_from_slice(&[0; 11 * 4]); // Reserved
+    for value in [32, pf_flags] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+    out.extend_from_slice(&four_cc);
+    out.extend_from_slice(&bits.to_le_bytes());
+    for mask in masks {
+        out.extend_from_slice(&mask.to_le_bytes());
+    }
+    for value in [caps, caps2, 0, 0, 0] {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    if matches!(pixel_format, PixelFormat::Dx10) {
+        let misc = if cubemap { MISC_TEXTURECUBE } else { 0 };
+        for value in [u32::from(format), DIMENSION_TEXTURE2D, misc, 1, 0] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_at(data: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(data[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_legacy_header() {
+        // 256x128 DXT5 with 9 mips
+        let dds = header(256, 128, 9, 77, false);
+        assert_eq!(dds.len(), HEADER_SIZE);
+        assert_eq!(&dds[..4], b"DDS ");
+        assert_eq!(u32_at(&dds, 4), 124);
+        assert_eq!(u32_at(&dds, 12), 128); // Height
+        assert_eq!(u32_at(&dds, 16), 256); // Width
+        assert_eq!(u32_at(&dds, 20), 64 * 32 * 16); // Linear size
+        assert_eq!(u32_at(&dds, 28), 9); // Mips
+        assert_eq!(&dds[84..88], b"DXT5");
+        assert_eq!(
+            u32_at(&dds, 108),
+            DDSCAPS_TEXTURE | DDSCAPS_COMPLEX | DDSCAPS_MIPMAP
+        );
+
+        // Uncompressed RGBA stores the row pitch and channel masks
+        let dds = header(4, 4, 1, 28, false);
+        assert_eq!(u32_at(&dds, 8) & DDSD_PITCH, DDSD_PITCH);
+        assert_eq!(u32_at(&dds, 20), 16);
+        assert_eq!(u32_at(&dds, 88), 32);
+        assert_eq!(u32_at(&dds, 92), 0xFF);
+    }
+
+    #[test]
+    fn test_dx10_header() {
+        // BC7 cubemap
+        let dds = header(512, 512, 10, 98, true);
+        assert_eq!(dds.len(), header_size(98));
+        assert_eq!(&dds[84..88], b"DX10");
+        assert_eq!(u32_at(&dds, 112), DDSCAPS2_CUBEMAP_ALL_FACES);
+        assert_eq!(u32_at(&dds, HEADER_SIZE), 98);
+        assert_eq!(u32_at(&dds, HEADER_SIZE + 4), DIMENSION_TEXTURE2D);
+        assert_eq!(u32_at(&dds, HEADER_SIZE + 8), MISC_TEXTURECUBE);
+        assert!(is_block_compressed(98));
+        assert!(!is_block_compressed(28));
+        assert_eq!(format_name(98), Some("BC7"));
+        assert_eq!(format_name(0), None);
+    }
+}
@@ -0,0 +1,457 @@
+//! Parsing of `BSArch` console output
+//!
+//! `BSArch` reports problems on stdout and does not always set a failing exit
+//! code, so the exit status alone is not enough to tell whether an archive was
+//! unpacked. This module recognizes the lines `BSArch` prints (banner, file
+//! counts, per-file progress, warnings and errors, including localized error
+//! prefixes on non-English systems) and condenses them into a [`BsarchOutcome`].
+//! It also parses the tool version, which gates features older releases lack,
+//! and recognizes programs that are not unpackers at all.
+
+use std::fmt;
+use std::path::Path;
+
+/// Prefixes that mark an error line, including common localizations
+const ERROR_PREFIXES: &[&str] = &[
+    "error", "fehler", "erreur", "errore", "ошибка", "błąd", "erro", "chyba", "hata", "错误",
+    "錯誤", "エラー", "오류",
+];
+
+/// Prefixes that mark a warning line, including common localizations
+const WARNING_PREFIXES: &[&str] = &[
+    "warning", "warnung", "avertissement", "attenzione", "advertencia", "aviso", "внимание",
+    "предупреждение", "ostrzeżenie", "警告", "경고",
+];
+
+/// A single classified line of `BSArch` output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BsarchLine {
+    /// Banner with the tool version (e.g., "`BSArch` v0.9 by zilav")
+    Version(String),
+    /// Number of files in the archive
+    FileCount(u32),
+    /// A file was unpacked (`current` of `total`, if known)
+    Progress {
+        /// Number of files unpacked so far
+        current: u32,
+        /// Total number of files, if printed on the line
+        total: Option<u32>,
+    },
+    /// Overall completion percentage
+    Percent(u8),
+    /// Non-fatal warning
+    Warning(String),
+    /// Error message
+    Error(String),
+    /// Anything else (file names, blank lines, informational messages)
+    Other,
+}
+
+impl BsarchLine {
+    /// Classify a single line of output
+    pub fn parse(line: &str) -> Self {
+        let line = line.trim();
+        if line.is_empty() {
+            return Self::Other;
+        }
+
+        if let Some(message) = strip_prefix_message(line, ERROR_PREFIXES) {
+            return Self::Error(message);
+        }
+        // Delphi exceptions are printed as "[EFOpenError] Cannot open file ..."
+        if line.starts_with("[E") && line.contains(']') {
+            return Self::Error(line.to_string());
+        }
+        if let Some(message) = strip_prefix_message(line, WARNING_PREFIXES) {
+            return Self::Warning(message);
+        }
+
+        let lower = line.to_lowercase();
+        if lower.starts_with("bsarch")
+            && let Some(version) = line
+                .split_whitespace()
+                .find_map(|word| word.strip_prefix(['v', 'V']))
+                .filter(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            return Self::Version(version.to_string());
+        }
+
+        if let Some(rest) = lower
+            .strip_prefix("files:")
+            .or_else(|| lower.strip_prefix("files count:"))
+            .or_else(|| lower.strip_prefix("file count:"))
+            && let Ok(count) = rest.trim().parse()
+        {
+            return Self::FileCount(count);
+        }
+
+        // "[12/340] textures\foo.dds" or "12/340"
+        if let Some(progress) = parse_counter(line) {
+            return progress;
+        }
+
+        // "45%"
+        if let Some(percent) = line
+            .strip_suffix('%')
+            .and_then(|n| n.trim().parse::<u8>().ok())
+            .filter(|p| *p <= 100)
+        {
+            return Self::Percent(percent);
+        }
+
+        Self::Other
+    }
+}
+
+/// Parse a "[current/total]" counter at the start of a line
+fn parse_counter(line: &str) -> Option<BsarchLine> {
+    let inner = line.strip_prefix('[').map_or(line, |rest| {
+        rest.split_once(']').map_or(rest, |(counter, _)| counter)
+    });
+    let counter = inner.split_whitespace().next()?;
+    let (current, total) = counter.split_once('/')?;
+    let current = current.trim().parse().ok()?;
+    let total = total.trim().parse().ok();
+    Some(BsarchLine::Progress { current, total })
+}
+
+/// Strip a "Prefix:" marker (case-insensitive) and return the message
+fn strip_prefix_message(line: &str, prefixes: &[&str]) -> Option<String> {
+    prefixes.iter().find_map(|prefix| {
+        // Compare by characters; lowercasing can change byte lengths
+        let split = line
+            .char_indices()
+            .nth(prefix.chars().count())
+            .map_or(line.len(), |(i, _)| i);
+        let (head, rest) = line.split_at(split);
+        if head.to_lowercase() != *prefix {
+            return None;
+        }
+
+        // Require a separator so "Errors: 0" style summaries are not errors
+        let rest = rest.trim_start();
+        let message = rest.strip_prefix(':').or_else(|| rest.strip_prefix('：'))?;
+        Some(message.trim().to_string())
+    })
+}
+
+/// Summary of a `BSArch` run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BsarchOutcome {
+    /// Tool version from the banner, if printed
+    pub version: Option<String>,
+    /// Number of files in the archive, if printed
+    pub file_count: Option<u32>,
+    /// Number of files reported as unpacked
+    pub files_done: u32,
+    /// Warnings printed by the tool
+    pub warnings: Vec<String>,
+    /// Errors printed by the tool
+    pub errors: Vec<String>,
+}
+
+impl BsarchOutcome {
+    /// Parse the complete stdout and stderr of a run
+    pub fn parse(stdout: &str, stderr: &str) -> Self {
+        let mut outcome = Self::default();
+        for line in stdout.lines() {
+            outcome.record(&BsarchLine::parse(line));
+        }
+        outcome.record_stderr(stderr);
+        outcome
+    }
+
+    /// Add the contents of stderr to the outcome
+    ///
+    /// Anything on stderr that is not a warning is treated as an error.
+    pub fn record_stderr(&mut self, stderr: &str) {
+        for line in stderr.lines().filter(|l| !l.trim().is_empty()) {
+            match BsarchLine::parse(line) {
+                BsarchLine::Warning(message) => self.warnings.push(message),
+                BsarchLine::Error(message) => self.errors.push(message),
+                _ => self.errors.push(line.trim().to_string()),
+            }
+        }
+    }
+
+    /// Update the outcome with a parsed line
+    pub fn record(&mut self, line: &BsarchLine) {
+        match line {
+            BsarchLine::Version(version) => self.version = Some(version.clone()),
+            BsarchLine::FileCount(count) => self.file_count = Some(*count),
+            BsarchLine::Progress { current, total } => {
+                self.files_done = self.files_done.max(*current);
+                if self.file_count.is_none() {
+                    self.file_count = *total;
+                }
+            }
+            BsarchLine::Warning(message) => self.warnings.push(message.clone()),
+            BsarchLine::Error(message) => self.errors.push(message.clone()),
+            BsarchLine::Percent(_) | BsarchLine::Other => {}
+        }
+    }
+
+    /// Whether the run succeeded given the process exit status
+    pub const fn is_success(&self, exit_success: bool) -> bool {
+        exit_success && self.errors.is_empty()
+    }
+
+    /// Completion percentage from the per-file counter, if known
+    pub fn percent(&self) -> Option<u8> {
+        let total = self.file_count.filter(|t| *t > 0)?;
+        let percent = (u64::from(self.files_done.min(total)) * 100) / u64::from(total);
+        u8::try_from(percent).ok()
+    }
+
+    /// Whether the errors show output files held open by another process
+    pub fn is_file_lock(&self) -> bool {
+        self.errors.iter().any(|e| is_lock_message(e))
+    }
+
+    /// First error message, for reporting
+    pub fn error_summary(&self) -> Option<&str> {
+        self.errors.first().map(String::as_str)
+    }
+}
+
+/// Messages of sharing violations, as printed for files an antivirus holds open
+const LOCK_PATTERNS: &[&str] = &[
+    "being used by another process",
+    "sharing violation",
+    "lock violation",
+    "access is denied",
+    "access denied",
+    "os error 32",
+    "os error 33",
+];
+
+/// Check whether an error message describes a file locked by another process
+pub fn is_lock_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    LOCK_PATTERNS.iter().any(|pattern| message.contains(pattern))
+}
+
+/// Version of the `BSArch` tool, as printed in its banner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BsarchVersion {
+    /// Major version
+    pub major: u32,
+    /// Minor version
+    pub minor: u32,
+    /// Patch version (0 if not printed)
+    pub patch: u32,
+}
+
+/// First release that can run unpacking multithreaded (`-mt`)
+pub const MULTITHREAD_MIN_VERSION: BsarchVersion = BsarchVersion::new(0, 9, 0);
+
+/// First release that can unpack Starfield (format version 2 and 3) archives
+pub const STARFIELD_MIN_VERSION: BsarchVersion = BsarchVersion::new(0, 10, 0);
+
+impl BsarchVersion {
+    /// Create a version from its components
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Parse a version such as "0.9", "0.10.1" or "v0.9b"
+    ///
+    /// Trailing non-digit suffixes ("b", "-beta") are ignored.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim().trim_start_matches(['v', 'V']);
+        let mut parts = text.split('.').map(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse::<u32>().ok()
+        });
+
+        let major = parts.next().flatten()?;
+        let minor = parts.next().flatten()?;
+        let patch = parts.next().flatten().unwrap_or(0);
+        Some(Self::new(major, minor, patch))
+    }
+
+    /// Features supported by this version
+    pub fn capabilities(self) -> BsarchCapabilities {
+        BsarchCapabilities {
+            multithreaded: self >= MULTITHREAD_MIN_VERSION,
+            starfield: self >= STARFIELD_MIN_VERSION,
+        }
+    }
+}
+
+impl fmt::Display for BsarchVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.patch == 0 {
+            write!(f, "{}.{}", self.major, self.minor)
+        } else {
+            write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+        }
+    }
+}
+
+/// Features of the installed `BSArch` that extraction may use
+///
+/// The default (all `false`) is used when the version is unknown, so no
+/// optional flags are passed to a tool that might not understand them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BsarchCapabilities {
+    /// Supports multithreaded unpacking (`-mt`)
+    pub multithreaded: bool,
+    /// Can unpack Starfield archives
+    pub starfield: bool,
+}
+
+/// Find the tool version in the output of a run (e.g., the usage banner)
+pub fn version_from_output(output: &str) -> Option<BsarchVersion> {
+    output.lines().find_map(|line| match BsarchLine::parse(line) {
+        BsarchLine::Version(version) => BsarchVersion::parse(&version),
+        _ => None,
+    })
+}
+
+/// Programs often registered as the .ba2 handler that cannot unpack archives
+///
+/// Pairs of lowercase file stem and display name.
+const NON_UNPACKERS: &[(&str, &str)] = &[
+    ("nifskope", "NifSkope"),
+    ("notepad", "Notepad"),
+    ("notepad++", "Notepad++"),
+    ("explorer", "Windows Explorer"),
+    ("modorganizer", "Mod Organizer"),
+    ("vortex", "Vortex"),
+    ("7zfm", "7-Zip"),
+    ("7zg", "7-Zip"),
+    ("winrar", "WinRAR"),
+];
+
+/// Name of a program known not to be an archive unpacker, if `path` is one
+///
+/// Both separators are accepted, as configured paths are usually Windows paths.
+pub fn known_non_unpacker(path: &Path) -> Option<&'static str> {
+    let path = path.to_string_lossy();
+    let file_name = path.rsplit(['/', '\\']).next()?;
+    let stem = file_name
+        .rsplit_once('.')
+        .map_or(file_name, |(stem, _)| stem)
+        .to_lowercase();
+    NON_UNPACKERS
+        .iter()
+        .find(|(known, _)| *known == stem)
+        .map(|(_, name)| *name)
+}
+
+/// Check whether the usage output of a tool offers the commands extraction needs
+///
+/// `BSArch` and compatible tools list `unpack` (and `-list`) when run without
+/// arguments; other programs cannot be driven by the extraction commands.
+pub fn looks_like_unpacker(usage: &str) -> bool {
+    usage
+        .to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '-'))
+        .any(|word| word == "unpack")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lines() {
+        assert_eq!(
+            BsarchLine::parse("BSArch v0.9 by zilav, ElminsterAU"),
+            BsarchLine::Version("0.9".to_string())
+        );
+        assert_eq!(BsarchLine::parse("Files: 340"), BsarchLine::FileCount(340));
+        assert_eq!(
+            BsarchLine::parse("[12/340] textures\\foo.dds"),
+            BsarchLine::Progress {
+                current: 12,
+                total: Some(340)
+            }
+        );
+        assert_eq!(BsarchLine::parse("45%"), BsarchLine::Percent(45));
+        assert_eq!(
+            BsarchLine::parse("Warning: duplicate file name"),
+            BsarchLine::Warning("duplicate file name".to_string())
+        );
+        assert_eq!(
+            BsarchLine::parse("Fehler: Datei kann nicht geöffnet werden"),
+            BsarchLine::Error("Datei kann nicht geöffnet werden".to_string())
+        );
+        assert_eq!(
+            BsarchLine::parse("错误：无法打开文件"),
+            BsarchLine::Error("无法打开文件".to_string())
+        );
+        assert!(matches!(
+            BsarchLine::parse("[EFOpenError] Cannot open file \"x.ba2\""),
+            BsarchLine::Error(_)
+        ));
+        assert_eq!(BsarchLine::parse("Errors found"), BsarchLine::Other);
+    }
+
+    #[test]
+    fn test_outcome_detects_errors_with_zero_exit_code() {
+        let stdout = "BSArch v0.9\nUnpacking archive: a.ba2\nFiles: 2\n[1/2] a.nif\nError: Not enough disk space\n";
+        let outcome = BsarchOutcome::parse(stdout, "");
+        assert_eq!(outcome.version.as_deref(), Some("0.9"));
+        assert_eq!(outcome.file_count, Some(2));
+        assert_eq!(outcome.files_done, 1);
+        assert_eq!(outcome.percent(), Some(50));
+        assert!(!outcome.is_success(true));
+        assert_eq!(outcome.error_summary(), Some("Not enough disk space"));
+    }
+
+    #[test]
+    fn test_outcome_detects_file_locks() {
+        let stdout = "[1/2] a.nif\nError: The process cannot access the file because it is being used by another process\n";
+        assert!(BsarchOutcome::parse(stdout, "").is_file_lock());
+        let stdout = "[1/2] a.nif\nError: Not enough disk space\n";
+        assert!(!BsarchOutcome::parse(stdout, "").is_file_lock());
+    }
+
+    #[test]
+    fn test_version_parsing_and_capabilities() {
+        assert_eq!(BsarchVersion::parse("0.9"), Some(BsarchVersion::new(0, 9, 0)));
+        assert_eq!(BsarchVersion::parse("v0.10.1"), Some(BsarchVersion::new(0, 10, 1)));
+        assert_eq!(BsarchVersion::parse("0.9b"), Some(BsarchVersion::new(0, 9, 0)));
+        assert_eq!(BsarchVersion::parse("beta"), None);
+        assert_eq!(BsarchVersion::new(0, 10, 1).to_string(), "0.10.1");
+
+        let old = BsarchVersion::new(0, 8, 0).capabilities();
+        assert!(!old.multithreaded && !old.starfield);
+        let current = BsarchVersion::new(0, 9, 0).capabilities();
+        assert!(current.multithreaded && !current.starfield);
+        assert!(BsarchVersion::new(0, 10, 0).capabilities().starfield);
+
+        let banner = "\nBSArch v0.9 by zilav, ElminsterAU\nUsage: bsarch unpack <archive> <folder>\n";
+        assert_eq!(version_from_output(banner), Some(BsarchVersion::new(0, 9, 0)));
+    }
+
+    #[test]
+    fn test_outcome_success_with_warnings() {
+        let outcome = BsarchOutcome::parse("[1/1] a.nif\nWarning: skipped empty file\nDone.\n", "");
+        assert!(outcome.is_success(true));
+        assert!(!outcome.is_success(false));
+        assert_eq!(outcome.warnings, vec!["skipped empty file".to_string()]);
+        assert_eq!(outcome.percent(), Some(100));
+    }
+
+    #[test]
+    fn test_tool_validation() {
+        assert_eq!(
+            known_non_unpacker(Path::new(r"C:\Tools\NifSkope\NifSkope.exe")),
+            Some("NifSkope")
+        );
+        assert_eq!(known_non_unpacker(Path::new(r"C:\Tools\BSArch.exe")), None);
+
+        assert!(looks_like_unpacker(
+            "BSArch v0.9 by zilav\nUsage:\n  bsarch unpack <archive> <folder> [-mt]\n  bsarch <archive> -list"
+        ));
+        assert!(!looks_like_unpacker("NifSkope 2.0\nUsage: nifskope [file]"));
+        assert!(!looks_like_unpacker("unpacked nothing"));
+    }
+}
@@ -0,0 +1,638 @@
+//! Quick metadata of archive entries
+//!
+//! Mod authors often only need a few facts about the files in an archive to
+//! audit it: which NIF version a mesh was exported with and roughly how many
+//! triangles it has, how long a sound or voice line plays, how many scripts
+//! an archive ships. All of these can be read from the first bytes of an
+//! entry, without unpacking the archive:
+//!
+//! - NIF: header version, block count, `BSTriShape` count and triangle count
+//! - WAV/XWM: duration from the format chunk and the data (or `dpds`) chunk
+//! - FUZ: the XWM audio after the lip-sync data
+//! - Scripts: counted from the entry names alone
+//!
+//! Parsers never fail: entries they cannot read are reported as unknown.
+//! [`inspect_archive`] runs them over every entry of an archive, unpacking
+//! only the first [`INSPECT_BYTES`] of each into memory.
+
+use super::BA2Archive;
+use crate::error::Result;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Bytes of an entry that are usually enough to inspect it
+pub const INSPECT_BYTES: usize = 256 * 1024;
+
+/// Extensions of the entries [`inspect`] reads
+const INSPECTED_EXTENSIONS: &[&str] = &["nif", "wav", "xwm", "fuz"];
+
+/// Start of every NIF header line
+const NIF_SIGNATURE: &[u8] = b"Gamebryo File Format, Version ";
+
+/// First NIF version with block sizes in the header (20.2.0.5)
+const NIF_BLOCK_SIZES_VERSION: u32 = 0x1402_0005;
+
+/// First Bethesda stream version with a 32-bit triangle count (Fallout 4)
+const BS_VERSION_FO4: u32 = 130;
+
+/// Block types holding a `BSTriShape`
+const TRI_SHAPE_BLOCKS: &[&str] = &[
+    "BSTriShape",
+    "BSSubIndexTriShape",
+    "BSMeshLODTriShape",
+    "BSDynamicTriShape",
+];
+
+/// Quick metadata of an archive entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntryInfo {
+    /// A NIF mesh
+    Mesh(NifInfo),
+
+    /// A WAV, XWM or FUZ sound
+    Audio(AudioInfo),
+}
+
+/// Header facts of a NIF mesh
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NifInfo {
+    /// Version from the header line (e.g., "20.2.0.7")
+    pub version: String,
+
+    /// User version (12 for Bethesda games)
+    pub user_version: u32,
+
+    /// Bethesda stream version (130 for Fallout 4, 172 for Starfield)
+    pub bs_version: u32,
+
+    /// Number of blocks in the file
+    pub num_blocks: u32,
+
+    /// Number of `BSTriShape` blocks
+    pub shapes: u32,
+
+    /// Triangles of the shapes within the inspected bytes
+    pub triangles: u64,
+}
+
+/// Format and length of a sound
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioInfo {
+    /// Container format ("WAV", "XWM" or "FUZ")
+    pub format: &'static str,
+
+    /// Number of channels
+    pub channels: u16,
+
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+
+    /// Playing time
+    pub duration: Duration,
+}
+
+/// An archive entry with quick metadata
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InspectedEntry {
+    /// Path of the entry inside the archive
+    pub name: String,
+
+    /// What was read from its first bytes
+    pub info: EntryInfo,
+}
+
+/// Quick metadata of the entries of a whole archive
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveInspection {
+    /// Number of files in the archive
+    pub files: usize,
+
+    /// Number of compiled Papyrus scripts
+    pub scripts: usize,
+
+    /// Meshes and sounds that could be read, in archive order
+    pub entries: Vec<InspectedEntry>,
+}
+
+impl ArchiveInspection {
+    /// The meshes with their header facts
+    pub fn meshes(&self) -> impl Iterator<Item = (&str, &NifInfo)> {
+        self.entries.iter().filter_map(|entry| match &entry.info {
+            EntryInfo::Mesh(info) => Some((entry.name.as_str(), info)),
+            EntryInfo::Audio(_) => None,
+        })
+    }
+
+    /// The sounds with their format and length
+    pub fn sounds(&self) -> impl Iterator<Item = (&str, &AudioInfo)> {
+        self.entries.iter().filter_map(|entry| match &entry.info {
+            EntryInfo::Audio(info) => Some((entry.name.as_str(), info)),
+            EntryInfo::Mesh(_) => None,
+        })
+    }
+}
+
+/// Inspect every mesh and sound of the archive at `path` and count its scripts
+///
+/// Texture archives only hold textures, so only their files are counted.
+pub fn inspect_archive(path: &Path) -> Result<ArchiveInspection> {
+    let archive = BA2Archive::open(path)?;
+    let mut report = ArchiveInspection {
+        files: archive.entries().len() + archive.textures().len(),
+        scripts: script_count(archive.names()),
+        entries: Vec::new(),
+    };
+
+    let mut buffer = vec![0; INSPECT_BYTES];
+    for entry in archive.entries() {
+        let inspectable = Path::new(&entry.name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| {
+                INSPECTED_EXTENSIONS
+                    .iter()
+                    .any(|known| ext.eq_ignore_ascii_case(known))
+            });
+        if !inspectable {
+            continue;
+        }
+        let mut head = HeadWriter(Vec::with_capacity(INSPECT_BYTES));
+        archive.write_entry(entry, &mut head, &mut buffer)?;
+        if let Some(info) = inspect(&entry.name, &head.0) {
+            report.entries.push(InspectedEntry {
+                name: entry.name.clone(),
+                info,
+            });
+        }
+    }
+    tracing::debug!(
+        "Inspected {}: {} files, {} with quick metadata",
+        path.display(),
+        report.files,
+        report.entries.len()
+    );
+    Ok(report)
+}
+
+/// Keeps the first [`INSPECT_BYTES`] written into it and drops the rest
+struct HeadWriter(Vec<u8>);
+
+impl Write for HeadWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let room = INSPECT_BYTES.saturating_sub(self.0.len());
+        self.0.extend_from_slice(&buf[..buf.len().min(room)]);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Inspect the first bytes of the entry `name`
+///
+/// Returns `None` for file types without quick metadata and for data that
+/// cannot be parsed.
+pub fn inspect(name: &str, data: &[u8]) -> Option<EntryInfo> {
+    let extension = Path::new(name).extension()?.to_str()?.to_lowercase();
+    match extension.as_str() {
+        "nif" => inspect_nif(data).map(EntryInfo::Mesh),
+        "wav" | "xwm" => inspect_riff(data).map(EntryInfo::Audio),
+        "fuz" => inspect_fuz(data).map(EntryInfo::Audio),
+        _ => None,
+    }
+}
+
+/// Count the compiled Papyrus scripts among the entry names of an archive
+pub fn script_count<S: AsRef<str>>(names: &[S]) -> usize {
+    names
+        .iter()
+        .filter(|name| {
+            Path::new(name.as_ref())
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("pex"))
+        })
+        .count()
+}
+
+/// Parse the header of a NIF and count the triangles of its shapes
+pub fn inspect_nif(data: &[u8]) -> Option<NifInfo> {
+    let line_end = data.iter().position(|&b| b == b'\n')?;
+    let version = data[..line_end].strip_prefix(NIF_SIGNATURE)?;
+    let version = String::from_utf8_lossy(version).trim().to_string();
+
+    let mut reader = ByteReader::new(&data[line_end + 1..]);
+    let binary_version = reader.u32()?;
+    if binary_version < NIF_BLOCK_SIZES_VERSION {
+        return None;
+    }
+    let _endian = reader.u8()?;
+    let user_version = reader.u32()?;
+    let num_blocks = reader.u32()?;
+
+    let mut bs_version = 0;
+    if user_version >= 10 {
+        bs_version = reader.u32()?;
+        // Author, process script and export script
+        for _ in 0..3 {
+            reader.short_string()?;
+        }
+        if bs_version >= BS_VERSION_FO4 {
+            // Max file path
+            reader.short_string()?;
+        }
+    }
+
+    let num_block_types = reader.u16()?;
+    let block_types = (0..num_block_types)
+        .map(|_| reader.sized_string())
+        .collect::<Option<Vec<_>>>()?;
+    let type_indices = (0..num_blocks)
+        .map(|_| reader.u16())
+        .collect::<Option<Vec<_>>>()?;
+    let block_sizes = (0..num_blocks)
+        .map(|_| reader.u32())
+        .collect::<Option<Vec<_>>>()?;
+
+    let num_strings = reader.u32()?;
+    let _max_string_length = reader.u32()?;
+    for _ in 0..num_strings {
+        reader.sized_string()?;
+    }
+    let num_groups = reader.u32()?;
+    reader.skip(num_groups as usize * 4)?;
+
+    let mut info = NifInfo {
+        version,
+        user_version,
+        bs_version,
+        num_blocks,
+        shapes: 0,
+        triangles: 0,
+    };
+    let mut offset = reader.position();
+    for (type_index, size) in type_indices.iter().zip(&block_sizes) {
+        let is_shape = block_types
+            .get(usize::from(*type_index))
+            .is_some_and(|name| TRI_SHAPE_BLOCKS.contains(&name.as_str()));
+        if is_shape {
+            info.shapes += 1;
+            if let Some(block) = data.get(line_end + 1 + offset..) {
+                info.triangles += tri_shape_triangles(block, bs_version).unwrap_or(0);
+            }
+        }
+        offset += *size as usize;
+    }
+    Some(info)
+}
+
+/// Read the triangle count of a `BSTriShape` block
+fn tri_shape_triangles(block: &[u8], bs_version: u32) -> Option<u64> {
+    let mut reader = ByteReader::new(block);
+    // NiObjectNET: name, extra data list, controller
+    reader.skip(4)?;
+    let num_extra_data = reader.u32()?;
+    reader.skip(num_extra_data as usize * 4 + 4)?;
+    // NiAVObject: flags, translation, rotation, scale, collision object
+    reader.skip(4 + 12 + 36 + 4 + 4)?;
+    // Bounding sphere, skin, shader property, alpha property, vertex description
+    reader.skip(16 + 4 + 4 + 4 + 8)?;
+    if bs_version >= BS_VERSION_FO4 {
+        reader.u32().map(u64::from)
+    } else {
+        reader.u16().map(u64::from)
+    }
+}
+
+/// Parse a RIFF WAV or XWM file
+pub fn inspect_riff(data: &[u8]) -> Option<AudioInfo> {
+    let mut reader = ByteReader::new(data);
+    if reader.bytes(4)? != b"RIFF" {
+        return None;
+    }
+    reader.skip(4)?;
+    let format = match reader.bytes(4)? {
+        b"WAVE" => "WAV",
+        b"XWMA" => "XWM",
+        _ => return None,
+    };
+
+    let mut channels = 0;
+    let mut sample_rate = 0;
+    let mut bytes_per_second = 0;
+    let mut data_size = None;
+    let mut decoded_size = None;
+    while let (Some(id), Some(size)) = (reader.bytes(4), reader.u32()) {
+        let start = reader.position();
+        match id {
+            b"fmt " => {
+                let mut fmt = ByteReader::new(reader.bytes(16)?);
+                fmt.skip(2)?;
+                channels = fmt.u16()?;
+                sample_rate = fmt.u32()?;
+                bytes_per_second = fmt.u32()?;
+            }
+            b"data" => data_size = Some(size),
+            // Cumulative decoded PCM bytes per XWMA packet; the last one is the total
+            b"dpds" if size >= 4 => {
+                reader.skip(size as usize - 4)?;
+                decoded_size = reader.u32();
+            }
+            _ => {}
+        }
+        // The data chunk may reach beyond the inspected bytes
+        if reader
+            .seek(start + size as usize + (size as usize & 1))
+            .is_none()
+        {
+            break;
+        }
+    }
+
+    let duration = match (decoded_size, data_size) {
+        // xWMA always decodes to 16-bit PCM
+        (Some(decoded), _) if channels > 0 && sample_rate > 0 => Duration::from_secs_f64(
+            f64::from(decoded) / (f64::from(sample_rate) * f64::from(channels) * 2.0),
+        ),
+        (_, Some(size)) if bytes_per_second > 0 => {
+            Duration::from_secs_f64(f64::from(size) / f64::from(bytes_per_second))
+        }
+        _ => return None,
+    };
+    Some(AudioInfo {
+        format,
+        channels,
+        sample_rate,
+        duration,
+    })
+}
+
+/// Parse a FUZ voice file (lip-sync data followed by XWM audio)
+pub fn inspect_fuz(data: &[u8]) -> Option<AudioInfo> {
+    let mut reader = ByteReader::new(data);
+    if reader.bytes(4)? != b"FUZE" {
+        return None;
+    }
+    let _version = reader.u32()?;
+    let lip_size = reader.u32()?;
+    reader.skip(lip_size as usize)?;
+    let audio = inspect_riff(&data[reader.position()..])?;
+    Some(AudioInfo {
+        format: "FUZ",
+        ..audio
+    })
+}
+
+/// Little-endian reader over a byte slice that stops at its end
+struct ByteReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    const fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    const fn position(&self) -> usize {
+        self.position
+    }
+
+    const fn seek(&mut self, position: usize) -> Option<()> {
+        if position > self.data.len() {
+            return None;
+        }
+        self.position = position;
+        Some(())
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self
+            .data
+            .get(self.position..self.position.checked_add(len)?)?;
+        self.position += len;
+        Some(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// String with a u8 length prefix (including a trailing null)
+    fn short_string(&mut self) -> Option<&'a [u8]> {
+        let len = self.u8()?;
+        self.bytes(usize::from(len))
+    }
+
+    /// String with a u32 length prefix
+    fn sized_string(&mut self) -> Option<String> {
+        let len = self.u32()?;
+        self.bytes(len as usize)
+            .map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sized(out: &mut Vec<u8>, text: &str) {
+        out.extend_from_slice(&u32::try_from(text.len()).unwrap().to_le_bytes());
+        out.extend_from_slice(text.as_bytes());
+    }
+
+    /// A Fallout 4 NIF with a root node and two `BSTriShape` blocks
+    fn fallout4_nif() -> Vec<u8> {
+        let mut shape = Vec::new();
+        shape.extend_from_slice(&0u32.to_le_bytes()); // Name
+        shape.extend_from_slice(&1u32.to_le_bytes()); // One extra data ref
+        shape.extend_from_slice(&[0xFF; 8]); // Extra data ref, controller
+        shape.extend_from_slice(&[0; 60]); // Flags, transform, collision
+        shape.extend_from_slice(&[0; 36]); // Bounds, refs, vertex description
+        let mut small = shape.clone();
+        shape.extend_from_slice(&1500u32.to_le_bytes());
+        small.extend_from_slice(&12u32.to_le_bytes());
+
+        let mut nif = b"Gamebryo File Format, Version 20.2.0.7\n".to_vec();
+        nif.extend_from_slice(&0x1402_0007u32.to_le_bytes());
+        nif.push(1); // Little endian
+        nif.extend_from_slice(&12u32.to_le_bytes()); // User version
+        nif.extend_from_slice(&3u32.to_le_bytes()); // Blocks
+        nif.extend_from_slice(&130u32.to_le_bytes()); // Bethesda version
+        for text in ["\0", "\0", "\0", "\0"] {
+            nif.push(u8::try_from(text.len()).unwrap());
+            nif.extend_from_slice(text.as_bytes());
+        }
+        nif.extend_from_slice(&2u16.to_le_bytes());
+        sized(&mut nif, "NiNode");
+        sized(&mut nif, "BSTriShape");
+        for index in [0u16, 1, 1] {
+            nif.extend_from_slice(&index.to_le_bytes());
+        }
+        for size in [8, shape.len(), small.len()] {
+            nif.extend_from_slice(&u32::try_from(size).unwrap().to_le_bytes());
+        }
+        nif.extend_from_slice(&1u32.to_le_bytes()); // Strings
+        nif.extend_from_slice(&4u32.to_le_bytes());
+        sized(&mut nif, "Root");
+        nif.extend_from_slice(&0u32.to_le_bytes()); // Groups
+        nif.extend_from_slice(&[0; 8]); // NiNode
+        nif.extend_from_slice(&shape);
+        nif.extend_from_slice(&small);
+        nif
+    }
+
+    fn riff(form: &[u8], chunks: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+        let mut body = form.to_vec();
+        for (id, data) in chunks {
+            body.extend_from_slice(*id);
+            body.extend_from_slice(&u32::try_from(data.len()).unwrap().to_le_bytes());
+            body.extend_from_slice(data);
+        }
+        let mut file = b"RIFF".to_vec();
+        file.extend_from_slice(&u32::try_from(body.len()).unwrap().to_le_bytes());
+        file.extend_from_slice(&body);
+        file
+    }
+
+    fn fmt_chunk(channels: u16, sample_rate: u32, bytes_per_second: u32) -> Vec<u8> {
+        let mut fmt = 1u16.to_le_bytes().to_vec();
+        fmt.extend_from_slice(&channels.to_le_bytes());
+        fmt.extend_from_slice(&sample_rate.to_le_bytes());
+        fmt.extend_from_slice(&bytes_per_second.to_le_bytes());
+        fmt.extend_from_slice(&[0; 4]);
+        fmt
+    }
+
+    #[test]
+    fn test_inspect_nif() {
+        let nif = fallout4_nif();
+        let Some(EntryInfo::Mesh(info)) = inspect("Meshes\\Armor\\Helmet.NIF", &nif) else {
+            panic!("mesh not recognized");
+        };
+        assert_eq!(info.version, "20.2.0.7");
+        assert_eq!(info.bs_version, 130);
+        assert_eq!(info.num_blocks, 3);
+        assert_eq!(info.shapes, 2);
+        assert_eq!(info.triangles, 1512);
+
+        // Shapes past the inspected bytes are counted without their triangles
+        let partial = inspect_nif(&nif[..nif.len() - 10]).unwrap();
+        assert_eq!(partial.shapes, 2);
+        assert_eq!(partial.triangles, 1500);
+
+        assert_eq!(inspect_nif(b"NetImmerse File Format\n"), None);
+    }
+
+    #[test]
+    fn test_inspect_audio() {
+        // One second of 16-bit stereo PCM, truncated after the header
+        let wav = riff(
+            b"WAVE",
+            &[
+                (b"fmt ", fmt_chunk(2, 44100, 176_400)),
+                (b"data", vec![0; 176_400]),
+            ],
+        );
+        let info = inspect_riff(&wav[..64]).unwrap();
+        assert_eq!(info.format, "WAV");
+        assert_eq!(info.channels, 2);
+        assert_eq!(info.duration, Duration::from_secs(1));
+
+        // Two and a half seconds of mono xWMA
+        let mut dpds = 44100u32.to_le_bytes().to_vec();
+        dpds.extend_from_slice(&110_250u32.to_le_bytes());
+        let xwm = riff(
+            b"XWMA",
+            &[
+                (b"fmt ", fmt_chunk(1, 22050, 6000)),
+                (b"dpds", dpds),
+                (b"data", vec![0; 64]),
+            ],
+        );
+        let mut fuz = b"FUZE".to_vec();
+        fuz.extend_from_slice(&1u32.to_le_bytes());
+        fuz.extend_from_slice(&3u32.to_le_bytes());
+        fuz.extend_from_slice(b"lip");
+        fuz.extend_from_slice(&xwm);
+
+        let Some(EntryInfo::Audio(info)) = inspect("Sound\\Voice\\Line.fuz", &fuz) else {
+            panic!("voice file not recognized");
+        };
+        assert_eq!(info.format, "FUZ");
+        assert_eq!(info.sample_rate, 22050);
+        assert_eq!(info.duration, Duration::from_millis(2500));
+
+        assert_eq!(inspect("Sound\\Line.wav", b"RIFX"), None);
+    }
+
+    #[test]
+    fn test_inspect_archive() {
+        use crate::testing::fixtures::{FixtureFile, gnrl_archive};
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("Mod - Main.ba2");
+        let file = |name: &str, data: Vec<u8>| FixtureFile {
+            name: name.to_string(),
+            data,
+        };
+        // Five seconds of mono PCM, longer than the bytes inspected
+        let wav = riff(
+            b"WAVE",
+            &[
+                (b"fmt ", fmt_chunk(1, 44100, 88200)),
+                (b"data", vec![0; 441_000]),
+            ],
+        );
+        std::fs::write(
+            &path,
+            gnrl_archive(&[
+                file("Meshes\\Helmet.nif", fallout4_nif()),
+                file("Sound\\Hum.wav", wav),
+                file("Meshes\\Broken.nif", b"not a mesh".to_vec()),
+                file("Scripts\\Quest.pex", b"script".to_vec()),
+            ]),
+        )
+        .unwrap();
+
+        let report = inspect_archive(&path).unwrap();
+        assert_eq!(report.files, 4);
+        assert_eq!(report.scripts, 1);
+        let meshes: Vec<(&str, u64)> = report
+            .meshes()
+            .map(|(name, info)| (name, info.triangles))
+            .collect();
+        assert_eq!(meshes, [("Meshes\\Helmet.nif", 1512)]);
+        let sounds: Vec<(&str, Duration)> = report
+            .sounds()
+            .map(|(name, info)| (name, info.duration))
+            .collect();
+        assert_eq!(sounds, [("Sound\\Hum.wav", Duration::from_secs(5))]);
+    }
+
+    #[test]
+    fn test_script_count() {
+        let names = [
+            "Scripts\\Quest.pex",
+            "Scripts\\Source\\User\\Quest.psc",
+            "scripts\\fragments\\TIF_0001.PEX",
+            "Meshes\\Scripts.nif",
+        ];
+        assert_eq!(script_count(&names), 2);
+        assert_eq!(inspect("Scripts\\Quest.pex", b""), None);
+    }
+}
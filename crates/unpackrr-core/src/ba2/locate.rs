@@ -0,0 +1,248 @@
+//! Finding the `BSArch` executable
+//!
+//! A configured external tool always wins. Otherwise BSArch.exe is searched
+//! in priority order: next to the application, in the settings folder, in a
+//! `tools` folder next to the application, on `PATH`, and finally as the
+//! registered handler of `.ba2` files. The first existing candidate is used
+//! and reported with its [`ToolOrigin`], so Settings can show where the tool
+//! came from.
+
+use crate::ba2::extractor::known_non_unpacker;
+use crate::config::AppConfig;
+use crate::platform::get_default_ba2_handler;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// File name of the bundled tool
+pub const BSARCH_EXE: &str = "BSArch.exe";
+
+/// Where the extraction tool was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolOrigin {
+    /// External BA2 tool set in Settings
+    Configured,
+    /// Next to the application executable
+    ExeDir,
+    /// In the settings folder
+    ConfigDir,
+    /// In the `tools` folder next to the application executable
+    ToolsDir,
+    /// On the `PATH`
+    SearchPath,
+    /// Registered handler of `.ba2` files
+    Registry,
+    /// Not found anywhere; the path is where the bundled tool is expected
+    NotFound,
+}
+
+impl ToolOrigin {
+    /// Whether the tool may be something other than `BSArch` and must be
+    /// checked before use (see [`validate_ba2_tool`](crate::operations::validate_ba2_tool))
+    pub const fn needs_validation(self) -> bool {
+        matches!(self, Self::Configured | Self::Registry)
+    }
+}
+
+impl fmt::Display for ToolOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Configured => "configured in Settings",
+            Self::ExeDir => "next to the application",
+            Self::ConfigDir => "in the settings folder",
+            Self::ToolsDir => "in the tools folder",
+            Self::SearchPath => "on PATH",
+            Self::Registry => "registered .ba2 handler",
+            Self::NotFound => "not found",
+        })
+    }
+}
+
+/// The tool to run and where it was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedTool {
+    /// Path of the executable
+    pub path: PathBuf,
+    /// Where it was found
+    pub origin: ToolOrigin,
+}
+
+/// Places searched for BSArch.exe when no external tool is configured
+#[derive(Debug, Clone, Default)]
+pub struct SearchLocations {
+    /// Folder of the application executable
+    pub exe_dir: Option<PathBuf>,
+    /// Folder of the settings file
+    pub config_dir: Option<PathBuf>,
+    /// Value of the `PATH` environment variable
+    pub path_var: Option<OsString>,
+    /// Registered handler of `.ba2` files
+    pub registry_handler: Option<PathBuf>,
+}
+
+impl SearchLocations {
+    /// Locations of the running application
+    pub fn current() -> Self {
+        Self {
+            exe_dir: std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(Path::to_path_buf)),
+            config_dir: AppConfig::config_dir().ok(),
+            path_var: std::env::var_os("PATH"),
+            registry_handler: get_default_ba2_handler(),
+        }
+    }
+
+    /// Candidate paths in priority order
+    fn candidates(&self) -> Vec<(PathBuf, ToolOrigin)> {
+        let mut candidates = Vec::new();
+        if let Some(exe_dir) = &self.exe_dir {
+            candidates.push((exe_dir.join(BSARCH_EXE), ToolOrigin::ExeDir));
+        }
+        if let Some(config_dir) = &self.config_dir {
+            candidates.push((config_dir.join(BSARCH_EXE), ToolOrigin::ConfigDir));
+        }
+        if let Some(exe_dir) = &self.exe_dir {
+            candidates.push((exe_dir.join("tools").join(BSARCH_EXE), ToolOrigin::ToolsDir));
+        }
+        if let Some(path_var) = &self.path_var {
+            candidates.extend(
+                std::env::split_paths(path_var)
+                    .filter(|dir| !dir.as_os_str().is_empty())
+                    .map(|dir| (dir.join(BSARCH_EXE), ToolOrigin::SearchPath)),
+            );
+        }
+        // The handler is often a viewer such as NifSkope
+        if let Some(handler) = self
+            .registry_handler
+            .as_ref()
+            .filter(|handler| known_non_unpacker(handler).is_none())
+        {
+            candidates.push((handler.clone(), ToolOrigin::Registry));
+        }
+        candidates
+    }
+}
+
+/// Find the extraction tool for the running application
+///
+/// Logs which tool was chosen and where it was found.
+pub fn locate_bsarch(config: &AppConfig) -> ResolvedTool {
+    let tool = locate_bsarch_in(config, &SearchLocations::current());
+    match tool.origin {
+        ToolOrigin::NotFound => tracing::warn!(
+            "{BSARCH_EXE} not found next to the application, in the settings or tools folder, on PATH or as .ba2 handler"
+        ),
+        origin => tracing::info!("Using BA2 tool {} ({origin})", tool.path.display()),
+    }
+    tool
+}
+
+/// Find the extraction tool in the given locations
+pub fn locate_bsarch_in(config: &AppConfig, locations: &SearchLocations) -> ResolvedTool {
+    if !config.advanced.ext_ba2_exe.is_empty() {
+        return ResolvedTool {
+            path: PathBuf::from(&config.advanced.ext_ba2_exe),
+            origin: ToolOrigin::Configured,
+        };
+    }
+
+    locations
+        .candidates()
+        .into_iter()
+        .find(|(path, _)| path.is_file())
+        .map_or_else(
+            || ResolvedTool {
+                path: locations
+                    .exe_dir
+                    .as_ref()
+                    .map_or_else(|| PathBuf::from(BSARCH_EXE), |dir| dir.join(BSARCH_EXE)),
+                origin: ToolOrigin::NotFound,
+            },
+            |(path, origin)| ResolvedTool { path, origin },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locations(root: &Path) -> SearchLocations {
+        SearchLocations {
+            exe_dir: Some(root.join("app")),
+            config_dir: Some(root.join("config")),
+            path_var: Some(std::env::join_paths([root.join("bin")]).unwrap()),
+            registry_handler: None,
+        }
+    }
+
+    fn install(path: &Path) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, b"").unwrap();
+    }
+
+    #[test]
+    fn test_searches_in_priority_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let config = AppConfig::default();
+
+        let missing = locate_bsarch_in(&config, &locations(root));
+        assert_eq!(missing.origin, ToolOrigin::NotFound);
+        assert_eq!(missing.path, root.join("app").join(BSARCH_EXE));
+
+        install(&root.join("bin").join(BSARCH_EXE));
+        assert_eq!(
+            locate_bsarch_in(&config, &locations(root)).origin,
+            ToolOrigin::SearchPath
+        );
+
+        install(&root.join("app").join("tools").join(BSARCH_EXE));
+        assert_eq!(
+            locate_bsarch_in(&config, &locations(root)).origin,
+            ToolOrigin::ToolsDir
+        );
+
+        install(&root.join("config").join(BSARCH_EXE));
+        assert_eq!(
+            locate_bsarch_in(&config, &locations(root)).origin,
+            ToolOrigin::ConfigDir
+        );
+
+        install(&root.join("app").join(BSARCH_EXE));
+        let bundled = locate_bsarch_in(&config, &locations(root));
+        assert_eq!(bundled.origin, ToolOrigin::ExeDir);
+        assert_eq!(bundled.path, root.join("app").join(BSARCH_EXE));
+    }
+
+    #[test]
+    fn test_configured_tool_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        install(&dir.path().join("app").join(BSARCH_EXE));
+        let mut config = AppConfig::default();
+        config.advanced.ext_ba2_exe = "C:\\Tools\\BSArch.exe".to_string();
+
+        let tool = locate_bsarch_in(&config, &locations(dir.path()));
+        assert_eq!(tool.origin, ToolOrigin::Configured);
+        assert_eq!(tool.path, PathBuf::from("C:\\Tools\\BSArch.exe"));
+    }
+
+    #[test]
+    fn test_registry_handler_skips_known_viewers() {
+        let dir = tempfile::tempdir().unwrap();
+        let viewer = dir.path().join("NifSkope.exe");
+        let unpacker = dir.path().join("Unpacker.exe");
+        install(&viewer);
+        install(&unpacker);
+        let config = AppConfig::default();
+
+        let mut locations = locations(dir.path());
+        locations.registry_handler = Some(viewer);
+        assert_eq!(locate_bsarch_in(&config, &locations).origin, ToolOrigin::NotFound);
+
+        locations.registry_handler = Some(unpacker.clone());
+        let tool = locate_bsarch_in(&config, &locations);
+        assert_eq!(tool.origin, ToolOrigin::Registry);
+        assert_eq!(tool.path, unpacker);
+    }
+}
@@ -3,14 +3,31 @@
 //! This module provides:
 //! - BA2 header parsing and validation
 //! - File count extraction without full extraction
+//! - Reading of file tables and file data ([`archive`])
+//! - DDS headers for textures of DX10 archives ([`dds`])
+//! - Downscaling textures while they are unpacked ([`texture_ops`])
 //! - Integration with BSArch.exe for extraction
+//! - Parsing of `BSArch` output ([`extractor`])
+//! - Finding the `BSArch` executable ([`locate`])
+//! - Quick metadata of meshes, sounds and scripts ([`inspect`])
+//! - Small previews of textures ([`preview`])
 //!
 //! Note: We use BSArch.exe (MPL-2.0 licensed) as the extraction engine.
 //! This module wraps it with a Rust-friendly API.
 
+pub mod archive;
+pub mod dds;
+pub mod extractor;
+pub mod inspect;
+pub mod locate;
+pub mod preview;
+pub mod texture_ops;
+
+pub use archive::{BA2Archive, Codec, GeneralEntry, ReadMode, TextureChunk, TextureEntry};
+
 use crate::error::{BA2Error, Result};
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 
 /// BA2 archive header
@@ -127,6 +144,27 @@ impl BA2Header {
         }
     }
 
+    /// Reject format versions no supported game writes (see [`KNOWN_VERSIONS`])
+    ///
+    /// Not part of [`BA2Header::validate`]: archives of unknown versions are
+    /// still listed, only extracting them is refused.
+    pub fn check_version(&self, path: &Path) -> Result<()> {
+        if is_known_version(self.version) {
+            Ok(())
+        } else {
+            Err(BA2Error::UnsupportedVersion {
+                version: self.version,
+                path: path.to_path_buf(),
+            }
+            .into())
+        }
+    }
+
+    /// Check if this is a Starfield archive (format version 2 or 3)
+    pub const fn is_starfield(&self) -> bool {
+        is_starfield_version(self.version)
+    }
+
     /// Check if this is a General archive
     pub fn is_general(&self) -> bool {
         self.archive_type == "GNRL"
@@ -138,6 +176,39 @@ impl BA2Header {
     }
 }
 
+/// Check if a BA2 format version belongs to Starfield
+///
+/// Fallout 4 uses versions 1, 7 and 8; Starfield uses 2 and 3.
+pub const fn is_starfield_version(version: u32) -> bool {
+    matches!(version, 2 | 3)
+}
+
+/// BA2 format versions written by each game
+///
+/// Fallout 4 writes version 1, and 7 or 8 since its next-gen update;
+/// Fallout 76 writes version 1; Starfield writes 2 and 3.
+pub const KNOWN_VERSIONS: &[(&str, &[u32])] = &[
+    ("Fallout 4", &[1, 7, 8]),
+    ("Fallout 76", &[1]),
+    ("Starfield", &[2, 3]),
+];
+
+/// Check if a BA2 format version is written by any supported game
+pub fn is_known_version(version: u32) -> bool {
+    KNOWN_VERSIONS
+        .iter()
+        .any(|(_, versions)| versions.contains(&version))
+}
+
+/// Names of the games that write a BA2 format version
+pub fn games_for_version(version: u32) -> Vec<&'static str> {
+    KNOWN_VERSIONS
+        .iter()
+        .filter(|(_, versions)| versions.contains(&version))
+        .map(|(game, _)| *game)
+        .collect()
+}
+
 /// Get the number of files in a BA2 archive without extracting
 ///
 /// This function only reads the header (24 bytes) to extract the file count.
@@ -147,6 +218,58 @@ pub fn num_files_in_ba2(path: &Path) -> Result<u32> {
     Ok(header.file_count)
 }
 
+/// Read the names of all files stored in a BA2 archive
+///
+/// Names are read from the name table at the end of the archive, which holds
+/// one length-prefixed (u16) path per file, in the same order as the file
+/// records. Paths use the separators stored in the archive (usually '\\').
+pub fn read_file_names(path: &Path) -> Result<Vec<String>> {
+    let file = File::open(path).map_err(|e| BA2Error::ExtractionFailed {
+        path: path.to_path_buf(),
+        reason: format!("Failed to open file: {e}"),
+    })?;
+    let mut reader = BufReader::new(file);
+    let header = BA2Header::parse_from_reader(&mut reader, path)?;
+    read_names_from_reader(reader, &header, path)
+}
+
+/// Read the name table described by `header` from a reader positioned anywhere
+fn read_names_from_reader<R: Read + Seek>(
+    mut reader: R,
+    header: &BA2Header,
+    path: &Path,
+) -> Result<Vec<String>> {
+    let corrupted = |reason: String| BA2Error::Corrupted {
+        path: path.to_path_buf(),
+        reason,
+    };
+
+    if header.names_offset == 0 {
+        return Err(corrupted("Archive has no name table".to_string()).into());
+    }
+
+    reader
+        .seek(SeekFrom::Start(header.names_offset))
+        .map_err(|e| corrupted(format!("Failed to seek to name table: {e}")))?;
+
+    let mut names = Vec::with_capacity(header.file_count as usize);
+    for index in 0..header.file_count {
+        let mut len_buf = [0u8; 2];
+        reader
+            .read_exact(&mut len_buf)
+            .map_err(|e| corrupted(format!("Name table truncated at entry {index}: {e}")))?;
+
+        let mut name_buf = vec![0u8; usize::from(u16::from_le_bytes(len_buf))];
+        reader
+            .read_exact(&mut name_buf)
+            .map_err(|e| corrupted(format!("Name table truncated at entry {index}: {e}")))?;
+
+        names.push(String::from_utf8_lossy(&name_buf).into_owned());
+    }
+
+    Ok(names)
+}
+
 /// Check if a file is a valid BA2 archive
 ///
 /// This performs a quick validation by:
@@ -251,6 +374,60 @@ mod tests {
         assert!(!header.is_general());
     }
 
+    #[test]
+    fn test_known_versions() {
+        for version in [1, 2, 3, 7, 8] {
+            assert!(is_known_version(version), "{version}");
+        }
+        assert!(!is_known_version(0));
+        assert!(!is_known_version(9));
+        assert_eq!(games_for_version(1), ["Fallout 4", "Fallout 76"]);
+        assert_eq!(games_for_version(3), ["Starfield"]);
+
+        let header = BA2Header {
+            magic: *b"BTDX",
+            version: 9,
+            archive_type: "GNRL".to_string(),
+            file_count: 1,
+            names_offset: 64,
+        };
+        let path = PathBuf::from("test.ba2");
+        assert!(header.validate(&path).is_ok());
+        assert!(matches!(
+            header.check_version(&path).unwrap_err(),
+            crate::error::Error::BA2(BA2Error::UnsupportedVersion { version: 9, .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_names_from_reader() {
+        let names = ["meshes\\a.nif", "textures\\b.dds"];
+        let mut data = vec![0u8; 32];
+        for name in names {
+            data.extend_from_slice(&u16::try_from(name.len()).unwrap().to_le_bytes());
+            data.extend_from_slice(name.as_bytes());
+        }
+
+        let header = BA2Header {
+            magic: *b"BTDX",
+            version: 1,
+            archive_type: "GNRL".to_string(),
+            file_count: 2,
+            names_offset: 32,
+        };
+        let path = PathBuf::from("test.ba2");
+
+        let read = read_names_from_reader(Cursor::new(data.clone()), &header, &path).unwrap();
+        assert_eq!(read, names);
+
+        // A file count beyond the table is reported as corruption
+        let header = BA2Header {
+            file_count: 3,
+            ..header
+        };
+        assert!(read_names_from_reader(Cursor::new(data), &header, &path).is_err());
+    }
+
     #[test]
     fn test_parse_truncated_header() {
         // Create truncated data (less than 24 bytes)
@@ -0,0 +1,560 @@
+//! Small previews of textures in DX10 archives
+//!
+//! [`preview_texture`] picks the largest mip level that fits in a preview,
+//! reads only that level with [`BA2Archive::read_texture_data`] and decodes
+//! it to 8-bit RGBA. BC1 to BC5 and the 8-bit RGBA, BGRA and R formats are
+//! decoded; BC6H, BC7 and the rest have no preview. Cubemaps show their
+//! first face.
+//!
+//! Levels over [`MAX_PREVIEW_READ`] bytes are refused, and [`PreviewCache`]
+//! keeps recent previews up to a total size, so stepping back and forth
+//! through an archive does not unpack the same textures again.
+
+use super::archive::{BA2Archive, TextureEntry};
+use super::texture_ops::mip_size;
+use crate::error::{BA2Error, Result};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Longest edge of a preview in pixels
+pub const PREVIEW_EDGE: u32 = 512;
+
+/// Most bytes of pixel data read for one preview
+pub const MAX_PREVIEW_READ: u64 = 16 * 1024 * 1024;
+
+/// Default size of a [`PreviewCache`], in bytes of decoded pixels
+pub const PREVIEW_CACHE_BYTES: usize = 32 * 1024 * 1024;
+
+/// A texture decoded for display
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TexturePreview {
+    /// Width in pixels
+    pub width: u32,
+
+    /// Height in pixels
+    pub height: u32,
+
+    /// Mip level the preview was decoded from
+    pub level: u8,
+
+    /// Pixels as 8-bit RGBA, row by row
+    pub rgba: Vec<u8>,
+}
+
+/// Whether [`decode`] can read a DXGI format
+pub const fn can_preview(format: u8) -> bool {
+    matches!(
+        format,
+        27..=29 | 60 | 61 | 70..=80 | 82 | 83 | 87 | 88 | 90..=93
+    )
+}
+
+/// Decode a texture of the archive for display
+///
+/// The largest mip level with no edge over `max_edge` pixels is decoded;
+/// textures without such a level are decoded from their smallest level and
+/// scaled down. Returns `None` for formats [`can_preview`] rejects and for
+/// textures whose data does not match their mip chain.
+///
+/// # Errors
+///
+/// Returns an error if the level is over [`MAX_PREVIEW_READ`] bytes or
+/// cannot be read from the archive.
+pub fn preview_texture(
+    archive: &BA2Archive,
+    texture: &TextureEntry,
+    max_edge: u32,
+) -> Result<Option<TexturePreview>> {
+    if !can_preview(texture.format) {
+        return Ok(None);
+    }
+    let Some((level, offset, len)) = preview_level(texture, max_edge) else {
+        tracing::debug!(
+            "No preview of {}: its data does not match its mip chain",
+            texture.name
+        );
+        return Ok(None);
+    };
+    if len > MAX_PREVIEW_READ {
+        return Err(BA2Error::ExtractionFailed {
+            path: archive.path().to_path_buf(),
+            reason: format!("{} is too large to preview ({len} bytes)", texture.name),
+        }
+        .into());
+    }
+
+    let data = archive.read_texture_data(texture, offset, len)?;
+    let width = edge(u32::from(texture.width), level);
+    let height = edge(u32::from(texture.height), level);
+    let Some(rgba) = decode(texture.format, width, height, &data) else {
+        return Ok(None);
+    };
+    let (width, height, rgba) = shrink(width, height, rgba, max_edge.max(1));
+    Ok(Some(TexturePreview {
+        width,
+        height,
+        level,
+        rgba,
+    }))
+}
+
+/// Edge of a mip level in pixels
+fn edge(size: u32, level: u8) -> u32 {
+    size.checked_shr(u32::from(level)).unwrap_or(0).max(1)
+}
+
+/// Mip level to preview, with its offset and size in the pixel data
+///
+/// `None` when the data of the texture does not match its mip chain (all
+/// six faces of it for cubemaps).
+fn preview_level(texture: &TextureEntry, max_edge: u32) -> Option<(u8, u64, u64)> {
+    let width = u32::from(texture.width);
+    let height = u32::from(texture.height);
+    let mips = texture.num_mips.max(1);
+    let sizes = (0..mips)
+        .map(|level| mip_size(texture.format, edge(width, level), edge(height, level)))
+        .collect::<Option<Vec<u64>>>()?;
+    let faces = if texture.is_cubemap { 6 } else { 1 };
+    let stored: u64 = texture
+        .chunks
+        .iter()
+        .map(|chunk| u64::from(chunk.unpacked_size))
+        .sum();
+    if sizes.iter().sum::<u64>() * faces != stored {
+        return None;
+    }
+
+    let level = (0..mips)
+        .find(|&level| edge(width, level).max(edge(height, level)) <= max_edge)
+        .unwrap_or(mips - 1);
+    let level_index = usize::from(level);
+    Some((level, sizes[..level_index].iter().sum(), sizes[level_index]))
+}
+
+/// Decode a `width` x `height` mip level of a DXGI format to 8-bit RGBA
+///
+/// Returns `None` for formats [`can_preview`] rejects and for data too
+/// short for the level.
+pub fn decode(format: u8, width: u32, height: u32, data: &[u8]) -> Option<Vec<u8>> {
+    let pixels = width as usize * height as usize;
+    match format {
+        27..=29 => Some(data.get(..pixels * 4)?.to_vec()),
+        87 | 90 | 91 => Some(
+            data.get(..pixels * 4)?
+                .chunks_exact(4)
+                .flat_map(|bgra| [bgra[2], bgra[1], bgra[0], bgra[3]])
+                .collect(),
+        ),
+        88 | 92 | 93 => Some(
+            data.get(..pixels * 4)?
+                .chunks_exact(4)
+                .flat_map(|bgrx| [bgrx[2], bgrx[1], bgrx[0], u8::MAX])
+                .collect(),
+        ),
+        60 | 61 => Some(
+            data.get(..pixels)?
+                .iter()
+                .flat_map(|&r| [r, r, r, u8::MAX])
+                .collect(),
+        ),
+        70..=72 => decode_blocks(width, height, data, 8, |block| color_block(block, true)),
+        73..=75 => decode_blocks(width, height, data, 16, bc2_block),
+        76..=78 => decode_blocks(width, height, data, 16, bc3_block),
+        79 | 80 => decode_blocks(width, height, data, 8, |block| {
+            alpha_block(block).map(|r| [r, r, r, u8::MAX])
+        }),
+        82 | 83 => decode_blocks(width, height, data, 16, bc5_block),
+        _ => None,
+    }
+}
+
+/// Decode 4x4 blocks of `block_size` bytes, dropping pixels past the edges
+fn decode_blocks(
+    width: u32,
+    height: u32,
+    data: &[u8],
+    block_size: usize,
+    decode_block: impl Fn(&[u8]) -> [[u8; 4]; 16],
+) -> Option<Vec<u8>> {
+    let width = width as usize;
+    let height = height as usize;
+    let blocks_x = width.div_ceil(4);
+    let blocks = blocks_x * height.div_ceil(4);
+    let data = data.get(..blocks * block_size)?;
+
+    let mut rgba = vec![0; width * height * 4];
+    for (index, block) in data.chunks_exact(block_size).enumerate() {
+        let left = index % blocks_x * 4;
+        let top = index / blocks_x * 4;
+        for (pixel, color) in decode_block(block).iter().enumerate() {
+            let x = left + pixel % 4;
+            let y = top + pixel / 4;
+            if x < width && y < height {
+                let at = (y * width + x) * 4;
+                rgba[at..at + 4].copy_from_slice(color);
+            }
+        }
+    }
+    Some(rgba)
+}
+
+/// The `bits`-wide index of a pixel in a little-endian index field
+fn index_at(field: u64, pixel: usize, bits: usize) -> usize {
+    let mask = (1u64 << bits) - 1;
+    usize::try_from((field >> (pixel * bits)) & mask).unwrap_or(0)
+}
+
+/// Weighted average of two channel values
+fn mix(a: u8, b: u8, weight_a: u32, weight_b: u32) -> u8 {
+    let value = (u32::from(a) * weight_a + u32::from(b) * weight_b) / (weight_a + weight_b);
+    u8::try_from(value).unwrap_or(u8::MAX)
+}
+
+/// Widen a 5- or 6-bit channel to 8 bits
+fn widen(value: u16, bits: u32) -> u8 {
+    u8::try_from((value << (8 - bits)) | (value >> (2 * bits - 8))).unwrap_or(u8::MAX)
+}
+
+/// Opaque color of an RGB565 value
+fn rgb565(value: u16) -> [u8; 4] {
+    [
+        widen(value >> 11, 5),
+        widen((value >> 5) & 0x3F, 6),
+        widen(value & 0x1F, 5),
+        u8::MAX,
+    ]
+}
+
+/// Decode the 8-byte color block of BC1, BC2 and BC3
+///
+/// BC1 blocks whose first color is not greater than the second hold three
+/// colors and transparent black; BC2 and BC3 blocks always hold four colors.
+fn color_block(block: &[u8], bc1: bool) -> [[u8; 4]; 16] {
+    let first = u16::from_le_bytes([block[0], block[1]]);
+    let second = u16::from_le_bytes([block[2], block[3]]);
+    let (a, b) = (rgb565(first), rgb565(second));
+    let blend = |weight_a, weight_b| {
+        [
+            mix(a[0], b[0], weight_a, weight_b),
+            mix(a[1], b[1], weight_a, weight_b),
+            mix(a[2], b[2], weight_a, weight_b),
+            u8::MAX,
+        ]
+    };
+    let palette = if first > second || !bc1 {
+        [a, b, blend(2, 1), blend(1, 2)]
+    } else {
+        [a, b, blend(1, 1), [0; 4]]
+    };
+    let indices = u64::from(u32::from_le_bytes([block[4], block[5], block[6], block[7]]));
+    std::array::from_fn(|pixel| palette[index_at(indices, pixel, 2)])
+}
+
+/// Decode an 8-byte BC3 alpha or BC4 channel block
+fn alpha_block(block: &[u8]) -> [u8; 16] {
+    let (a, b) = (block[0], block[1]);
+    let palette = if a > b {
+        [
+            a,
+            b,
+            mix(a, b, 6, 1),
+            mix(a, b, 5, 2),
+            mix(a, b, 4, 3),
+            mix(a, b, 3, 4),
+            mix(a, b, 2, 5),
+            mix(a, b, 1, 6),
+        ]
+    } else {
+        [
+            a,
+            b,
+            mix(a, b, 4, 1),
+            mix(a, b, 3, 2),
+            mix(a, b, 2, 3),
+            mix(a, b, 1, 4),
+            0,
+            u8::MAX,
+        ]
+    };
+    let mut field = [0u8; 8];
+    field[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(field);
+    std::array::from_fn(|pixel| palette[index_at(indices, pixel, 3)])
+}
+
+/// Decode a BC2 block: explicit 4-bit alpha, then a color block
+fn bc2_block(block: &[u8]) -> [[u8; 4]; 16] {
+    let mut field = [0u8; 8];
+    field.copy_from_slice(&block[..8]);
+    let alpha = u64::from_le_bytes(field);
+    let mut colors = color_block(&block[8..], false);
+    for (pixel, color) in colors.iter_mut().enumerate() {
+        color[3] = u8::try_from(index_at(alpha, pixel, 4) * 17).unwrap_or(u8::MAX);
+    }
+    colors
+}
+
+/// Decode a BC3 block: an alpha block, then a color block
+fn bc3_block(block: &[u8]) -> [[u8; 4]; 16] {
+    let alpha = alpha_block(&block[..8]);
+    let mut colors = color_block(&block[8..], false);
+    for (color, alpha) in colors.iter_mut().zip(alpha) {
+        color[3] = alpha;
+    }
+    colors
+}
+
+/// Decode a BC5 block: red and green channel blocks
+///
+/// BC5 textures are normal maps, so blue is rebuilt from red and green.
+fn bc5_block(block: &[u8]) -> [[u8; 4]; 16] {
+    let red = alpha_block(&block[..8]);
+    let green = alpha_block(&block[8..]);
+    std::array::from_fn(|pixel| {
+        let (r, g) = (red[pixel], green[pixel]);
+        [r, g, normal_z(r, g), u8::MAX]
+    })
+}
+
+/// Blue channel of a normal map pixel with the given red and green
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Clamped to 0..=255
+fn normal_z(red: u8, green: u8) -> u8 {
+    let x = f32::from(red) / 127.5 - 1.0;
+    let y = f32::from(green) / 127.5 - 1.0;
+    let z = (1.0 - x * x - y * y).max(0.0).sqrt();
+    (z * 127.5 + 127.5).round().clamp(0.0, 255.0) as u8
+}
+
+/// Scale an image down to fit in `max_edge` pixels, keeping every n-th pixel
+fn shrink(width: u32, height: u32, rgba: Vec<u8>, max_edge: u32) -> (u32, u32, Vec<u8>) {
+    let longest = width.max(height);
+    if longest <= max_edge {
+        return (width, height, rgba);
+    }
+    let step = longest.div_ceil(max_edge);
+    let new_width = width.div_ceil(step);
+    let new_height = height.div_ceil(step);
+    let mut scaled = Vec::with_capacity(new_width as usize * new_height as usize * 4);
+    for y in 0..new_height {
+        for x in 0..new_width {
+            let at = ((y * step) as usize * width as usize + (x * step) as usize) * 4;
+            scaled.extend_from_slice(&rgba[at..at + 4]);
+        }
+    }
+    (new_width, new_height, scaled)
+}
+
+/// Recently decoded previews, bounded by the total size of their pixels
+#[derive(Debug)]
+pub struct PreviewCache {
+    /// Most bytes of pixels held
+    capacity: usize,
+
+    /// Bytes of pixels held
+    size: usize,
+
+    /// Archive path, texture name and preview; least recently used first
+    entries: VecDeque<(PathBuf, String, Arc<TexturePreview>)>,
+}
+
+impl Default for PreviewCache {
+    fn default() -> Self {
+        Self::new(PREVIEW_CACHE_BYTES)
+    }
+}
+
+impl PreviewCache {
+    /// An empty cache holding up to `capacity` bytes of pixels
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            size: 0,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Number of cached previews
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no previews are cached
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Bytes of pixels held
+    pub const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Position of the preview of texture `name` of the archive at `archive`
+    fn position(&self, archive: &Path, name: &str) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|(path, texture, _)| path == archive && texture == name)
+    }
+
+    /// The cached preview of texture `name` of the archive at `archive`
+    pub fn get(&mut self, archive: &Path, name: &str) -> Option<Arc<TexturePreview>> {
+        let index = self.position(archive, name)?;
+        let entry = self.entries.remove(index)?;
+        let preview = Arc::clone(&entry.2);
+        self.entries.push_back(entry);
+        Some(preview)
+    }
+
+    /// Cache a preview, dropping the least recently used ones to make room
+    ///
+    /// Previews larger than the whole cache are returned without being kept.
+    pub fn insert(
+        &mut self,
+        archive: &Path,
+        name: &str,
+        preview: TexturePreview,
+    ) -> Arc<TexturePreview> {
+        let preview = Arc::new(preview);
+        let bytes = preview.rgba.len();
+        if bytes > self.capacity {
+            return preview;
+        }
+        if let Some((_, _, old)) = self
+            .position(archive, name)
+            .and_then(|index| self.entries.remove(index))
+        {
+            self.size -= old.rgba.len();
+        }
+        while self.size + bytes > self.capacity {
+            let Some((_, _, old)) = self.entries.pop_front() else {
+                break;
+            };
+            self.size -= old.rgba.len();
+        }
+        self.size += bytes;
+        self.entries.push_back((
+            archive.to_path_buf(),
+            name.to_string(),
+            Arc::clone(&preview),
+        ));
+        preview
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures::{FixtureTexture, dx10_archive};
+
+    /// A BC1 block of two colors with the given 2-bit indices
+    fn bc1(first: u16, second: u16, indices: u32) -> Vec<u8> {
+        let mut block = Vec::new();
+        block.extend_from_slice(&first.to_le_bytes());
+        block.extend_from_slice(&second.to_le_bytes());
+        block.extend_from_slice(&indices.to_le_bytes());
+        block
+    }
+
+    #[test]
+    fn test_decode_bc1() {
+        // Red, then blue for pixel 1 and the third color for pixel 2
+        let block = bc1(0xF800, 0x001F, 0b10_01_00);
+        let rgba = decode(71, 4, 4, &block).unwrap();
+        assert_eq!(rgba.len(), 4 * 4 * 4);
+        assert_eq!(rgba[..4], [255, 0, 0, 255]);
+        assert_eq!(rgba[4..8], [0, 0, 255, 255]);
+        assert_eq!(rgba[8..12], [170, 0, 85, 255]);
+
+        // A first color not greater than the second makes index 3 transparent
+        let block = bc1(0x001F, 0xF800, 0b11);
+        assert_eq!(decode(71, 4, 4, &block).unwrap()[..4], [0, 0, 0, 0]);
+
+        // Pixels past the edge of a 2x2 level are dropped
+        let rgba = decode(71, 2, 2, &bc1(0xFFFF, 0, 0)).unwrap();
+        assert_eq!(rgba, [255; 16]);
+
+        assert_eq!(decode(71, 8, 8, &block), None);
+    }
+
+    #[test]
+    fn test_decode_bc3_and_bc4() {
+        // Alpha 255 and 0, pixel 1 using the second value
+        let mut block = vec![255, 0, 0b1000, 0, 0, 0, 0, 0];
+        let alpha = block.clone();
+        block.extend(bc1(0xFFFF, 0xFFFF, 0));
+        let rgba = decode(77, 4, 4, &block).unwrap();
+        assert_eq!(rgba[..4], [255, 255, 255, 255]);
+        assert_eq!(rgba[4..8], [255, 255, 255, 0]);
+
+        let gray = decode(80, 4, 4, &alpha).unwrap();
+        assert_eq!(gray[4..8], [0, 0, 0, 255]);
+        assert_eq!(normal_z(128, 128), 255);
+    }
+
+    #[test]
+    fn test_decode_uncompressed() {
+        let bgra = [1, 2, 3, 4];
+        assert_eq!(decode(87, 1, 1, &bgra).unwrap(), [3, 2, 1, 4]);
+        assert_eq!(decode(88, 1, 1, &bgra).unwrap(), [3, 2, 1, 255]);
+        assert_eq!(decode(28, 1, 1, &bgra).unwrap(), bgra);
+        assert_eq!(decode(61, 1, 1, &bgra).unwrap(), [1, 1, 1, 255]);
+        assert!(!can_preview(98));
+        assert_eq!(decode(98, 4, 4, &[0; 16]), None);
+    }
+
+    #[test]
+    fn test_preview_texture() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Mod - Textures.ba2");
+        let fixture = FixtureTexture::with_mips("textures\\sky.dds", 32, 16, 4);
+        std::fs::write(&path, dx10_archive(std::slice::from_ref(&fixture))).unwrap();
+        let archive = BA2Archive::open(&path).unwrap();
+        let texture = &archive.textures()[0];
+
+        // The 8x4 level is the largest that fits
+        let preview = preview_texture(&archive, texture, 8).unwrap().unwrap();
+        assert_eq!((preview.width, preview.height, preview.level), (8, 4, 2));
+        assert!(preview.rgba.iter().all(|&b| b == 2));
+
+        // The smallest level (4x2) is scaled down when none fits
+        let preview = preview_texture(&archive, texture, 2).unwrap().unwrap();
+        assert_eq!((preview.width, preview.height, preview.level), (2, 1, 3));
+        assert_eq!(preview.rgba, [3; 8]);
+
+        let mut truncated = texture.clone();
+        truncated.chunks[0].unpacked_size -= 4;
+        assert_eq!(preview_texture(&archive, &truncated, 8).unwrap(), None);
+    }
+
+    #[test]
+    fn test_preview_cache() {
+        let preview = |value: u8| TexturePreview {
+            width: 4,
+            height: 4,
+            level: 0,
+            rgba: vec![value; 64],
+        };
+        let archive = Path::new("Mod - Textures.ba2");
+        let mut cache = PreviewCache::new(150);
+        cache.insert(archive, "a.dds", preview(1));
+        cache.insert(archive, "b.dds", preview(2));
+        assert_eq!(cache.size(), 128);
+
+        // Reading "a" makes "b" the least recently used, dropped for "c"
+        assert_eq!(cache.get(archive, "a.dds").unwrap().rgba[0], 1);
+        cache.insert(archive, "c.dds", preview(3));
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(archive, "b.dds").is_none());
+        assert!(cache.get(archive, "a.dds").is_some());
+        assert!(cache.get(Path::new("Other.ba2"), "a.dds").is_none());
+
+        // Previews larger than the cache are not kept
+        let large = TexturePreview {
+            rgba: vec![0; 256],
+            ..preview(0)
+        };
+        assert_eq!(cache.insert(archive, "d.dds", large).rgba.len(), 256);
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.size(), 128);
+    }
+}
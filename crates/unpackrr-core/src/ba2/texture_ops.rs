@@ -0,0 +1,247 @@
+//! Downscaling textures while they are unpacked
+//!
+//! Textures in DX10 archives store every mip level, largest first. Dropping
+//! the top levels downscales a texture without re-encoding anything: the
+//! smaller levels are already there, so the file only needs a header with the
+//! new size and mip count, followed by the remaining pixel data. This saves
+//! disk space and VRAM for users who do not need full-resolution textures.
+//!
+//! [`MipSkip::plan`] works out what to drop for one texture. Textures whose
+//! layout is not fully understood (cubemaps, unknown formats, chunk sizes that
+//! do not match the mip chain) are kept at full size.
+
+use super::archive::TextureEntry;
+use super::dds;
+use std::io::{self, Write};
+
+/// Most mip levels that can be skipped
+pub const MAX_SKIP_MIPS: u8 = 4;
+
+/// Smallest edge a downscaled texture keeps, in pixels
+///
+/// Block-compressed formats store 4x4 blocks, so smaller levels would not
+/// shrink the file any further.
+pub const MIN_EDGE: u32 = 4;
+
+/// Size of one mip level in bytes, `None` for formats of unknown layout
+pub fn mip_size(format: u8, width: u32, height: u32) -> Option<u64> {
+    let width = u64::from(width.max(1));
+    let height = u64::from(height.max(1));
+    match (dds::block_size(format), dds::bits_per_pixel(format)) {
+        (Some(block), _) => Some(width.div_ceil(4) * height.div_ceil(4) * u64::from(block)),
+        (None, Some(bits)) => Some((width * u64::from(bits)).div_ceil(8) * height),
+        (None, None) => None,
+    }
+}
+
+/// Edge of a mip level in pixels, 0 once a texture claims more levels than it can have
+fn edge(size: u32, level: u8) -> u32 {
+    size.checked_shr(u32::from(level)).unwrap_or(0)
+}
+
+/// How a texture is downscaled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MipSkip {
+    /// Mip levels dropped
+    pub levels: u8,
+
+    /// Bytes of pixel data dropped from the start of the texture
+    pub skipped_bytes: u64,
+
+    /// Width of the new top mip level
+    pub width: u16,
+
+    /// Height of the new top mip level
+    pub height: u16,
+
+    /// Mip levels left
+    pub num_mips: u8,
+}
+
+impl MipSkip {
+    /// Plan dropping up to `levels` mip levels of a texture
+    ///
+    /// Fewer levels are dropped when the texture would otherwise lose its
+    /// last mip or shrink below [`MIN_EDGE`]. Returns `None` when the texture
+    /// is kept at full size.
+    pub fn plan(texture: &TextureEntry, levels: u8) -> Option<Self> {
+        if texture.is_cubemap {
+            return None;
+        }
+        let width = u32::from(texture.width);
+        let height = u32::from(texture.height);
+        let levels = (1..=levels
+            .min(MAX_SKIP_MIPS)
+            .min(texture.num_mips.saturating_sub(1)))
+            .take_while(|level| (width.min(height) >> level) >= MIN_EDGE)
+            .last()?;
+
+        let sizes = (0..texture.num_mips)
+            .map(|level| mip_size(texture.format, edge(width, level), edge(height, level)))
+            .collect::<Option<Vec<u64>>>()?;
+        let stored: u64 = texture
+            .chunks
+            .iter()
+            .map(|chunk| u64::from(chunk.unpacked_size))
+            .sum();
+        if sizes.iter().sum::<u64>() != stored {
+            tracing::debug!(
+                "Keeping {} at full size: its data does not match its mip chain",
+                texture.name
+            );
+            return None;
+        }
+
+        Some(Self {
+            levels,
+            skipped_bytes: sizes[..usize::from(levels)].iter().sum(),
+            width: (texture.width >> levels).max(1),
+            height: (texture.height >> levels).max(1),
+            num_mips: texture.num_mips - levels,
+        })
+    }
+
+    /// DDS header of the downscaled texture
+    pub fn dds_header(&self, texture: &TextureEntry) -> Vec<u8> {
+        dds::header(
+            self.width,
+            self.height,
+            self.num_mips,
+            texture.format,
+            texture.is_cubemap,
+        )
+    }
+
+    /// Size of the downscaled DDS file, including the header
+    pub fn unpacked_size(&self, texture: &TextureEntry) -> u64 {
+        texture.unpacked_size() - self.skipped_bytes
+    }
+}
+
+/// Writer that discards a number of bytes before passing the rest on
+pub struct SkipWriter<'a, W: Write + ?Sized> {
+    inner: &'a mut W,
+    remaining: u64,
+    written: u64,
+}
+
+impl<'a, W: Write + ?Sized> SkipWriter<'a, W> {
+    /// Discard the first `skip` bytes written
+    pub const fn new(inner: &'a mut W, skip: u64) -> Self {
+        Self {
+            inner,
+            remaining: skip,
+            written: 0,
+        }
+    }
+
+    /// Bytes still to be discarded
+    pub const fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Discard bytes without writing them, e.g. for data that was never read
+    pub const fn skip(&mut self, bytes: u64) {
+        self.remaining = self.remaining.saturating_sub(bytes);
+    }
+
+    /// Bytes passed on to the inner writer
+    pub const fn written(&self) -> u64 {
+        self.written
+    }
+}
+
+impl<W: Write + ?Sized> Write for SkipWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let skipped = usize::try_from(self.remaining).map_or(buf.len(), |r| r.min(buf.len()));
+        self.remaining -= skipped as u64;
+        if skipped == buf.len() {
+            return Ok(buf.len());
+        }
+        let written = self.inner.write(&buf[skipped..])?;
+        self.written += written as u64;
+        Ok(skipped + written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ba2::archive::TextureChunk;
+
+    fn texture(width: u16, height: u16, num_mips: u8, format: u8) -> TextureEntry {
+        let stored: u64 = (0..num_mips)
+            .map(|level| {
+                mip_size(
+                    format,
+                    u32::from(width) >> level,
+                    u32::from(height) >> level,
+                )
+                .unwrap()
+            })
+            .sum();
+        TextureEntry {
+            name: "textures\\a.dds".to_string(),
+            name_hash: 0,
+            extension: *b"dds\0",
+            dir_hash: 0,
+            height,
+            width,
+            num_mips,
+            format,
+            is_cubemap: false,
+            tile_mode: 8,
+            chunks: vec![TextureChunk {
+                offset: 0,
+                packed_size: 0,
+                unpacked_size: u32::try_from(stored).unwrap(),
+                start_mip: 0,
+                end_mip: u16::from(num_mips - 1),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_mip_size() {
+        assert_eq!(mip_size(71, 256, 256), Some(64 * 64 * 8)); // BC1
+        assert_eq!(mip_size(77, 2, 2), Some(16)); // BC3, one block
+        assert_eq!(mip_size(28, 16, 8), Some(16 * 8 * 4)); // R8G8B8A8
+        assert_eq!(mip_size(0, 16, 16), None);
+    }
+
+    #[test]
+    fn test_plan() {
+        let bc1 = texture(1024, 512, 11, 71);
+        let skip = MipSkip::plan(&bc1, 2).unwrap();
+        assert_eq!((skip.width, skip.height, skip.num_mips), (256, 128, 9));
+        assert_eq!(skip.skipped_bytes, (256 * 128 + 128 * 64) * 8);
+
+        // Small textures keep a 4 pixel edge and at least one mip
+        assert_eq!(MipSkip::plan(&texture(16, 16, 5, 71), 4).unwrap().levels, 2);
+        assert_eq!(MipSkip::plan(&texture(64, 64, 1, 71), 2), None);
+        assert_eq!(MipSkip::plan(&bc1, 0), None);
+
+        let mut cubemap = bc1.clone();
+        cubemap.is_cubemap = true;
+        assert_eq!(MipSkip::plan(&cubemap, 2), None);
+
+        let mut truncated = bc1;
+        truncated.chunks[0].unpacked_size -= 8;
+        assert_eq!(MipSkip::plan(&truncated, 2), None);
+    }
+
+    #[test]
+    fn test_skip_writer() {
+        let mut out = Vec::new();
+        let mut writer = SkipWriter::new(&mut out, 5);
+        writer.write_all(b"abc").unwrap();
+        writer.skip(1);
+        writer.write_all(b"defgh").unwrap();
+        assert_eq!(writer.written(), 4);
+        assert_eq!(out, b"efgh");
+    }
+}
@@ -0,0 +1,138 @@
+//! When a batch stops after repeated failures
+//!
+//! A wrong tool path or a locked destination makes every archive of a run
+//! fail the same way. [`AbortThreshold`] ends the batch once too many
+//! extractions in a row have failed, either as a count ("10") or as a share
+//! of the batch ("25%"), instead of running the tool hundreds of times.
+
+use crate::error::{Error, Result, ValidationError};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Consecutive failures after which the rest of a batch is not started
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum AbortThreshold {
+    /// Always extract the whole batch
+    Never,
+    /// Stop after more than this many failures in a row
+    Count(u32),
+    /// Stop after more than this percentage of the batch failed in a row
+    Percent(u8),
+}
+
+impl Default for AbortThreshold {
+    fn default() -> Self {
+        Self::Count(10)
+    }
+}
+
+impl AbortThreshold {
+    /// Parse "10", "25%", or "never" (also "off", "0" or an empty string)
+    pub fn parse(input: &str) -> Result<Self> {
+        let invalid = || Error::from(ValidationError::InvalidAbortThreshold(input.trim().to_string()));
+
+        let trimmed = input.trim();
+        if trimmed.is_empty() || matches!(trimmed.to_ascii_lowercase().as_str(), "never" | "off") {
+            return Ok(Self::Never);
+        }
+
+        if let Some(percent) = trimmed.strip_suffix('%') {
+            let percent: u8 = percent.trim().parse().map_err(|_| invalid())?;
+            return match percent {
+                0 => Ok(Self::Never),
+                1..100 => Ok(Self::Percent(percent)),
+                _ => Err(invalid()),
+            };
+        }
+
+        match trimmed.parse().map_err(|_| invalid())? {
+            0 => Ok(Self::Never),
+            count => Ok(Self::Count(count)),
+        }
+    }
+
+    /// Whether `consecutive` failures in a row of `total` archives end the batch
+    ///
+    /// A streak ends the batch once it is *longer* than the threshold, so a
+    /// count of 10 tolerates ten failures and stops at the eleventh.
+    pub fn is_exceeded(self, consecutive: usize, total: usize) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Count(count) => consecutive > count as usize,
+            Self::Percent(percent) => consecutive * 100 > total * usize::from(percent),
+        }
+    }
+}
+
+impl fmt::Display for AbortThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Never => f.write_str("never"),
+            Self::Count(count) => write!(f, "{count}"),
+            Self::Percent(percent) => write!(f, "{percent}%"),
+        }
+    }
+}
+
+impl FromStr for AbortThreshold {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for AbortThreshold {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        Self::parse(&value)
+    }
+}
+
+impl From<AbortThreshold> for String {
+    fn from(value: AbortThreshold) -> Self {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(AbortThreshold::parse("10").unwrap(), AbortThreshold::Count(10));
+        assert_eq!(AbortThreshold::parse(" 25 % ").unwrap(), AbortThreshold::Percent(25));
+        assert_eq!(AbortThreshold::parse("").unwrap(), AbortThreshold::Never);
+        assert_eq!(AbortThreshold::parse("Off").unwrap(), AbortThreshold::Never);
+        assert_eq!(AbortThreshold::parse("0").unwrap(), AbortThreshold::Never);
+        assert_eq!(AbortThreshold::parse("0%").unwrap(), AbortThreshold::Never);
+        assert!(AbortThreshold::parse("100%").is_err());
+        assert!(AbortThreshold::parse("-3").is_err());
+        assert!(AbortThreshold::parse("ten").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for threshold in [
+            AbortThreshold::Never,
+            AbortThreshold::Count(3),
+            AbortThreshold::Percent(40),
+        ] {
+            let json = serde_json::to_string(&threshold).unwrap();
+            assert_eq!(serde_json::from_str::<AbortThreshold>(&json).unwrap(), threshold);
+        }
+    }
+
+    #[test]
+    fn test_is_exceeded() {
+        assert!(!AbortThreshold::Count(3).is_exceeded(3, 100));
+        assert!(AbortThreshold::Count(3).is_exceeded(4, 100));
+        assert!(!AbortThreshold::Percent(25).is_exceeded(5, 20));
+        assert!(AbortThreshold::Percent(25).is_exceeded(6, 20));
+        assert!(!AbortThreshold::Never.is_exceeded(1000, 1000));
+    }
+}
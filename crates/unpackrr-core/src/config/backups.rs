@@ -0,0 +1,220 @@
+//! Restore points of the configuration file
+//!
+//! Before [`AppConfig::save`] replaces `config.json` with different settings,
+//! the previous version is copied to the `backups` folder next to it, at most
+//! once every [`BACKUP_INTERVAL`]: the app saves on every change, down to the
+//! last folder scanned, and a restore point per save would push useful ones
+//! out within minutes. The last [`KEEP_BACKUPS`] versions are kept and older
+//! ones removed. Restoring
+//! one loads it and saves it like any other change, so the settings it
+//! replaces become a restore point in turn.
+
+use super::AppConfig;
+use crate::error::{ConfigError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Folder of the restore points, next to the configuration file
+pub const BACKUP_DIR: &str = "backups";
+
+/// Number of previous configuration files kept
+pub const KEEP_BACKUPS: usize = 10;
+
+/// Shortest time between two restore points
+pub const BACKUP_INTERVAL: Duration = Duration::from_hours(1);
+
+/// A previous version of the configuration file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigBackup {
+    /// Path of the copy
+    pub path: PathBuf,
+
+    /// When the version was replaced, in milliseconds since the Unix epoch
+    pub replaced_at: u64,
+}
+
+impl ConfigBackup {
+    /// When the version was replaced, in seconds since the Unix epoch
+    pub const fn replaced_at_secs(&self) -> u64 {
+        self.replaced_at / 1000
+    }
+
+    /// Load the settings of the copy
+    pub fn load(&self) -> Result<AppConfig> {
+        let content = fs::read_to_string(&self.path).map_err(|e| ConfigError::LoadFailed {
+            path: self.path.clone(),
+            source: e,
+        })?;
+        AppConfig::from_json(&content)
+    }
+}
+
+/// Folder of the restore points of the configuration file at `config_path`
+pub fn backup_dir(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(BACKUP_DIR)
+}
+
+/// Copy the configuration file at `config_path` before `new_content` replaces it
+///
+/// Nothing is copied if the file doesn't exist yet, already has that
+/// content, or the newest restore point is less than `interval` old.
+/// Returns the new restore point, if any.
+pub fn back_up_before_save(
+    config_path: &Path,
+    new_content: &str,
+    interval: Duration,
+) -> Result<Option<PathBuf>> {
+    let Ok(current) = fs::read_to_string(config_path) else {
+        return Ok(None);
+    };
+    if current == new_content {
+        return Ok(None);
+    }
+
+    let dir = backup_dir(config_path);
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX));
+    let interval = u64::try_from(interval.as_millis()).unwrap_or(u64::MAX);
+    if list(&dir)
+        .first()
+        .is_some_and(|newest| millis.saturating_sub(newest.replaced_at) < interval)
+    {
+        return Ok(None);
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| ConfigError::SaveFailed {
+        path: dir.clone(),
+        source: e,
+    })?;
+    let backup = dir.join(format!("config-{millis}.json"));
+    fs::write(&backup, current).map_err(|e| ConfigError::SaveFailed {
+        path: backup.clone(),
+        source: e,
+    })?;
+
+    for old in list(&dir).into_iter().skip(KEEP_BACKUPS) {
+        if let Err(e) = fs::remove_file(&old.path) {
+            tracing::debug!(
+                "Failed to remove old restore point {}: {}",
+                old.path.display(),
+                e
+            );
+        }
+    }
+    Ok(Some(backup))
+}
+
+/// Restore points in `dir`, newest first
+pub fn list(dir: &Path) -> Vec<ConfigBackup> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<ConfigBackup> = entries
+        .filter_map(std::result::Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let replaced_at = name
+                .to_str()?
+                .strip_prefix("config-")?
+                .strip_suffix(".json")?
+                .parse()
+                .ok()?;
+            Some(ConfigBackup {
+                path: entry.path(),
+                replaced_at,
+            })
+        })
+        .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.replaced_at));
+    backups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_back_up_before_save() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let dir = backup_dir(&path);
+
+        // Nothing to keep before the first save
+        assert_eq!(
+            back_up_before_save(&path, "{}", BACKUP_INTERVAL).unwrap(),
+            None
+        );
+        fs::write(&path, "first").unwrap();
+        assert_eq!(
+            back_up_before_save(&path, "first", BACKUP_INTERVAL).unwrap(),
+            None
+        );
+        assert!(list(&dir).is_empty());
+
+        let backup = back_up_before_save(&path, "second", BACKUP_INTERVAL)
+            .unwrap()
+            .unwrap();
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "first");
+        assert_eq!(list(&dir)[0].path, backup);
+
+        // Further saves within the interval keep the same restore point
+        fs::write(&path, "second").unwrap();
+        assert_eq!(
+            back_up_before_save(&path, "third", BACKUP_INTERVAL).unwrap(),
+            None
+        );
+        assert_eq!(list(&dir).len(), 1);
+        // Unless the caller asks for one regardless, as a restore does
+        assert!(
+            back_up_before_save(&path, "third", Duration::ZERO)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_old_backups_removed() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        let dir = backup_dir(&path);
+        fs::create_dir_all(&dir).unwrap();
+        for millis in 1..=KEEP_BACKUPS as u64 + 3 {
+            fs::write(dir.join(format!("config-{millis}.json")), "old").unwrap();
+        }
+        fs::write(dir.join("notes.txt"), "not a backup").unwrap();
+        fs::write(&path, "current").unwrap();
+
+        back_up_before_save(&path, "next", BACKUP_INTERVAL).unwrap();
+        let backups = list(&dir);
+        assert_eq!(backups.len(), KEEP_BACKUPS);
+        assert!(
+            backups
+                .windows(2)
+                .all(|w| w[0].replaced_at > w[1].replaced_at)
+        );
+        assert!(!dir.join("config-4.json").exists());
+        assert!(dir.join("config-5.json").exists());
+        assert!(dir.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn test_load_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = AppConfig::default();
+        config.saved.directory = "D:/Mods".to_string();
+        let backup = ConfigBackup {
+            path: temp_dir.path().join("config-5000.json"),
+            replaced_at: 5000,
+        };
+        fs::write(&backup.path, serde_json::to_string(&config).unwrap()).unwrap();
+
+        assert_eq!(backup.replaced_at_secs(), 5);
+        assert_eq!(backup.load().unwrap().saved.directory, "D:/Mods");
+    }
+}
@@ -0,0 +1,334 @@
+//! Patterns that keep archives out of scans
+//!
+//! Each entry of the ignore list states how its pattern matches:
+//!
+//! - `exact` matches the archive file name or its full path
+//! - `substring` matches file names containing the text
+//! - `glob` matches the whole file name with the `*` and `?` wildcards
+//! - `regex` matches file names the regular expression finds a match in
+//!
+//! All patterns are case-insensitive, like postfixes; a regex can opt out
+//! with `(?-i)`. Trailing whitespace of the other kinds is ignored, as file
+//! names on Windows can't end with it, and exact paths match with either
+//! kind of slash.
+//!
+//! Older configurations stored plain strings and guessed the type from the
+//! characters used. Those entries are migrated on load: paths become exact
+//! patterns, strings with regex syntax such as `^`, `[...]` or `.*` become
+//! regexes, other strings with `*` or `?` globs and everything else a
+//! substring.
+
+use super::rules::glob_match;
+use crate::error::{ConfigError, Result};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// How an ignore pattern matches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternKind {
+    /// The file name or full path, exactly
+    Exact,
+    /// Text anywhere in the file name
+    #[default]
+    Substring,
+    /// The whole file name with `*` and `?` wildcards
+    Glob,
+    /// A regular expression
+    Regex,
+}
+
+impl PatternKind {
+    /// All kinds, in the order the Settings editor cycles through them
+    pub const ALL: [Self; 4] = [Self::Exact, Self::Substring, Self::Glob, Self::Regex];
+
+    /// Name shown in the Settings editor
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Exact => "Exact",
+            Self::Substring => "Contains",
+            Self::Glob => "Wildcard",
+            Self::Regex => "Regex",
+        }
+    }
+}
+
+/// An entry of the ignore list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "StoredPattern")]
+pub struct IgnorePattern {
+    /// How the pattern matches
+    pub kind: PatternKind,
+
+    /// The pattern as written
+    pub pattern: String,
+}
+
+/// Ignore list entry as stored, either typed or a string of older versions
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredPattern {
+    Typed { kind: PatternKind, pattern: String },
+    Legacy(String),
+}
+
+impl From<StoredPattern> for IgnorePattern {
+    fn from(stored: StoredPattern) -> Self {
+        match stored {
+            StoredPattern::Typed { kind, pattern } => Self::new(kind, pattern),
+            StoredPattern::Legacy(pattern) => Self::migrate(pattern),
+        }
+    }
+}
+
+impl IgnorePattern {
+    /// Create a pattern of a kind
+    pub fn new(kind: PatternKind, pattern: impl Into<String>) -> Self {
+        Self {
+            kind,
+            pattern: pattern.into(),
+        }
+    }
+
+    /// Type an untyped pattern of older configurations
+    pub fn migrate(pattern: impl Into<String>) -> Self {
+        let pattern = pattern.into();
+        // Dots are common in file names and were rarely meant as regex syntax,
+        // unless they are repeated like in `.*`
+        let has_regex_syntax = pattern.contains(['[', ']', '(', ')', '+', '|', '^', '$'])
+            || [".*", ".?"].iter().any(|any| pattern.contains(any));
+        let kind = if !has_regex_syntax && Path::new(&pattern).components().count() > 1 {
+            PatternKind::Exact
+        } else if has_regex_syntax || pattern.contains('\\') {
+            PatternKind::Regex
+        } else if pattern.contains(['*', '?']) {
+            PatternKind::Glob
+        } else {
+            PatternKind::Substring
+        };
+        Self { kind, pattern }
+    }
+}
+
+/// An ignore pattern prepared for matching
+#[derive(Debug, Clone)]
+enum Compiled {
+    Exact(String),
+    Substring(String),
+    Glob(String),
+    Regex(Regex),
+}
+
+impl Compiled {
+    fn new(pattern: &IgnorePattern) -> Result<Self> {
+        let text = pattern.pattern.trim_end();
+        Ok(match pattern.kind {
+            PatternKind::Exact => Self::Exact(normalize_path(text)),
+            PatternKind::Substring => Self::Substring(text.to_lowercase()),
+            PatternKind::Glob => Self::Glob(text.to_string()),
+            PatternKind::Regex => Self::Regex(
+                RegexBuilder::new(&pattern.pattern)
+                    .case_insensitive(true)
+                    .build()
+                    .map_err(|e| ConfigError::InvalidRegex {
+                        pattern: pattern.pattern.clone(),
+                        source: e,
+                    })?,
+            ),
+        })
+    }
+
+    fn matches(&self, path: &Path, file_name: &str) -> bool {
+        match self {
+            Self::Exact(exact) => {
+                file_name.to_lowercase() == *exact
+                    || normalize_path(&path.to_string_lossy()) == *exact
+            }
+            Self::Substring(text) => file_name.to_lowercase().contains(text.as_str()),
+            Self::Glob(pattern) => glob_match(pattern, file_name),
+            Self::Regex(regex) => regex.is_match(file_name),
+        }
+    }
+}
+
+/// Lowercase a name or path with forward slashes, for exact matches
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").to_lowercase()
+}
+
+/// The ignore list, compiled once per scan
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreMatcher {
+    patterns: Vec<Compiled>,
+}
+
+impl IgnoreMatcher {
+    /// Compile all patterns, failing on the first invalid regex
+    pub fn new(patterns: &[IgnorePattern]) -> Result<Self> {
+        patterns
+            .iter()
+            .map(Compiled::new)
+            .collect::<Result<_>>()
+            .map(|patterns| Self { patterns })
+    }
+
+    /// Whether any pattern matches an archive
+    pub fn ignores(&self, path: &Path) -> bool {
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches(path, &file_name))
+    }
+}
+
+/// Archives one ignore pattern hides
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnorePreview {
+    /// The pattern
+    pub pattern: IgnorePattern,
+
+    /// File names it hides, or the reason it is invalid
+    pub matched: std::result::Result<Vec<String>, String>,
+}
+
+/// List the archives each ignore pattern hides
+pub fn preview_ignored<P: AsRef<Path>>(
+    patterns: &[IgnorePattern],
+    archives: &[P],
+) -> Vec<IgnorePreview> {
+    patterns
+        .iter()
+        .map(|pattern| IgnorePreview {
+            pattern: pattern.clone(),
+            matched: Compiled::new(pattern)
+                .map(|compiled| {
+                    archives
+                        .iter()
+                        .map(AsRef::as_ref)
+                        .filter_map(|path| {
+                            let file_name = path.file_name()?.to_string_lossy();
+                            compiled
+                                .matches(path, &file_name)
+                                .then(|| file_name.into_owned())
+                        })
+                        .collect()
+                })
+                .map_err(|e| e.user_message()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn ignores(kind: PatternKind, pattern: &str, path: &str) -> bool {
+        IgnoreMatcher::new(&[IgnorePattern::new(kind, pattern)])
+            .unwrap()
+            .ignores(Path::new(path))
+    }
+
+    #[test]
+    fn test_pattern_kinds() {
+        assert!(ignores(
+            PatternKind::Exact,
+            "Mod - Main.ba2",
+            "/data/Mod/mod - main.ba2"
+        ));
+        assert!(ignores(
+            PatternKind::Exact,
+            "/data/Mod/Mod - Main.ba2",
+            "/data/Mod/Mod - Main.ba2"
+        ));
+        assert!(!ignores(
+            PatternKind::Exact,
+            "Mod",
+            "/data/Mod/Mod - Main.ba2"
+        ));
+
+        assert!(ignores(
+            PatternKind::Substring,
+            "test",
+            "/data/My_TEST_Mod.ba2"
+        ));
+        // A substring's dot is literal
+        assert!(!ignores(PatternKind::Substring, "a.ba2", "/data/ab.ba2"));
+
+        assert!(ignores(PatternKind::Glob, "*.ba2", "/data/Mod.ba2"));
+        assert!(!ignores(PatternKind::Glob, "Mod*", "/data/The Mod.ba2"));
+
+        assert!(ignores(
+            PatternKind::Regex,
+            "^Mod_[0-9]+",
+            "/data/Mod_12.ba2"
+        ));
+        assert!(ignores(
+            PatternKind::Regex,
+            "^Mod_[0-9]+",
+            "/data/mod_12.ba2"
+        ));
+        assert!(!ignores(
+            PatternKind::Regex,
+            "(?-i)^Mod_[0-9]+",
+            "/data/mod_12.ba2"
+        ));
+        assert!(IgnoreMatcher::new(&[IgnorePattern::new(PatternKind::Regex, "[")]).is_err());
+    }
+
+    #[test]
+    fn test_migrate_legacy_strings() {
+        let kind = |pattern: &str| IgnorePattern::migrate(pattern).kind;
+        assert_eq!(kind("debug"), PatternKind::Substring);
+        assert_eq!(kind("TestMod1_Main.ba2"), PatternKind::Substring);
+        assert_eq!(kind("*.log"), PatternKind::Glob);
+        assert_eq!(kind(".*test.*"), PatternKind::Regex);
+        assert_eq!(kind("^test_[0-9]+"), PatternKind::Regex);
+        assert_eq!(kind(r"\.ba2$"), PatternKind::Regex);
+        assert_eq!(kind("/data/Mod/Mod - Main.ba2"), PatternKind::Exact);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let json = r#"["debug", "*.log", {"kind": "regex", "pattern": "^x"}]"#;
+        let patterns: Vec<IgnorePattern> = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            patterns,
+            [
+                IgnorePattern::new(PatternKind::Substring, "debug"),
+                IgnorePattern::new(PatternKind::Glob, "*.log"),
+                IgnorePattern::new(PatternKind::Regex, "^x"),
+            ]
+        );
+
+        // Saved entries are typed and load back unchanged
+        let saved = serde_json::to_string(&patterns).unwrap();
+        assert!(saved.contains(r#"{"kind":"glob","pattern":"*.log"}"#));
+        let loaded: Vec<IgnorePattern> = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded, patterns);
+    }
+
+    #[test]
+    fn test_preview_ignored() {
+        let archives = [
+            PathBuf::from("/data/A/A - Main.ba2"),
+            PathBuf::from("/data/B/B - Textures.ba2"),
+        ];
+        let previews = preview_ignored(
+            &[
+                IgnorePattern::new(PatternKind::Glob, "* - Textures.ba2"),
+                IgnorePattern::new(PatternKind::Regex, "("),
+            ],
+            &archives,
+        );
+        assert_eq!(
+            previews[0].matched,
+            Ok(vec!["B - Textures.ba2".to_string()])
+        );
+        assert!(previews[1].matched.is_err());
+    }
+}
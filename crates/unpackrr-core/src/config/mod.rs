@@ -0,0 +1,991 @@
+//! Configuration management for Unpackrr-rs
+//!
+//! This module handles loading, saving, and validating application configuration.
+//! Configuration is stored in JSON format and includes settings for:
+//! - Extraction behavior (postfixes, ignored files, auto backup)
+//! - Postfix patterns that pick the archives a scan lists ([`postfix`])
+//! - When a batch stops after repeated failures ([`abort`])
+//! - Patterns that keep archives out of scans ([`ignore`])
+//! - Settings overridden for a single run without saving them ([`overrides`])
+//! - Rules that build the default selection after a scan ([`rules`]), and
+//!   archives that must never be unpacked ([`rules::known_exclusions`])
+//! - The game whose mods are unpacked and its defaults ([`profile`])
+//! - Reloading the file when it is edited outside the app ([`watch`]), and
+//!   repairing it when it can't be loaded ([`repair`])
+//! - Restore points of the previous versions of the file ([`backups`])
+//! - API keys, kept in the credential store of the OS ([`secrets`])
+//! - Programs the row menu opens archives with ([`tools`])
+//! - Appearance (theme, language, accent color)
+//! - Advanced settings (debug mode, paths, external tools)
+//! - Update checking preferences
+
+pub mod abort;
+pub mod backups;
+pub mod ignore;
+pub mod overrides;
+pub mod postfix;
+pub mod profile;
+pub mod repair;
+pub mod rules;
+pub mod secrets;
+pub mod tools;
+pub mod watch;
+
+use crate::ba2::archive::ReadMode;
+use crate::error::{ConfigError, Result};
+use crate::ipc;
+use crate::operations::loose_stats::DEFAULT_LOOSE_FILE_LIMIT;
+use crate::operations::native::FsyncPolicy;
+use crate::operations::{LinkMode, SizeSpec};
+use crate::platform::drive::StorageMedium;
+use crate::platform::wine;
+use abort::AbortThreshold;
+use ignore::{IgnoreMatcher, IgnorePattern};
+use postfix::PostfixMatcher;
+use profile::{FALLOUT4, Game, GameProfile};
+use rules::SelectionRule;
+use tools::ExternalTool;
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// Whether [`AppConfig::save`] leaves the configuration file alone
+static SAVES_HELD: AtomicBool = AtomicBool::new(false);
+
+/// Stop saving the configuration, or save it again
+///
+/// Held in safe mode, so settings changed while it runs on defaults don't
+/// replace a configuration file that failed to load.
+pub fn hold_saves(held: bool) {
+    SAVES_HELD.store(held, Ordering::Relaxed);
+}
+
+/// Main application configuration
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct AppConfig {
+    /// Game whose mods are unpacked
+    #[serde(default)]
+    pub game: Game,
+
+    /// Extraction-related settings
+    pub extraction: ExtractionConfig,
+
+    /// Saved user settings
+    pub saved: SavedConfig,
+
+    /// Appearance and personalization settings
+    pub appearance: AppearanceConfig,
+
+    /// Advanced settings
+    pub advanced: AdvancedConfig,
+
+    /// Update checking settings
+    pub update: UpdateConfig,
+
+    /// Local automation interface (see [`crate::ipc`])
+    #[serde(default)]
+    pub automation: AutomationConfig,
+}
+
+/// Extraction configuration
+#[allow(clippy::struct_excessive_bools)] // Independent toggles, each saved under its own key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionConfig {
+    /// BA2 file postfixes to process (e.g., "main.ba2", "* - Textures.ba2")
+    /// Plain postfixes must end with .ba2; see [`postfix`] for globs and regexes
+    #[serde(default = "default_postfixes")]
+    pub postfixes: Vec<String>,
+
+    /// Files to ignore, each an exact, substring, glob or regex pattern
+    /// (untyped strings of older versions are migrated on load)
+    #[serde(default)]
+    pub ignored_files: Vec<IgnorePattern>,
+
+    /// Rules that build the default selection after each scan (empty = select nothing)
+    #[serde(default)]
+    pub selection_rules: Vec<SelectionRule>,
+
+    /// Ignore corrupted BA2 files
+    #[serde(default = "default_true")]
+    pub ignore_bad_files: bool,
+
+    /// Extract archives of BA2 versions the selected game does not write
+    #[serde(default)]
+    pub allow_unknown_versions: bool,
+
+    /// Automatically backup BA2 files before extraction
+    #[serde(default = "default_true")]
+    pub auto_backup: bool,
+
+    /// List and extract the archives of the game's Data folder itself, for
+    /// installs without a mod manager (see [`datafolder`](crate::operations::datafolder))
+    #[serde(default)]
+    pub data_folder_mode: bool,
+
+    /// Show a summary of the run to confirm before extraction starts
+    #[serde(default = "default_true")]
+    pub confirm_extraction: bool,
+
+    /// Write a checksum manifest of the extracted files next to each archive
+    #[serde(default)]
+    pub write_manifest: bool,
+
+    /// Extract into a temporary folder and only move verified results into the mod folder
+    #[serde(default = "default_true")]
+    pub use_temp: bool,
+
+    /// Extract into a shared cache and link the files into the mod folder
+    #[serde(default)]
+    pub link_mode: LinkMode,
+
+    /// How texture (DX10) archives are handled
+    #[serde(default)]
+    pub texture_policy: TexturePolicy,
+
+    /// Largest mip levels dropped from textures to downscale them (0 = full
+    /// size); only the built-in reader does this, see [`crate::ba2::texture_ops`]
+    #[serde(default)]
+    pub texture_skip_mips: u8,
+
+    /// Order in which archives are started
+    #[serde(default)]
+    pub schedule: ScheduleOrder,
+
+    /// Extract archives of the same mod one at a time, parallelizing only across mods
+    #[serde(default)]
+    pub serialize_per_mod: bool,
+
+    /// Failures in a row after which the rest of the batch is not started
+    /// ("10", "25%" of the batch, or "never")
+    #[serde(default)]
+    pub abort_after_failures: AbortThreshold,
+
+    /// Loose files a mod folder may hold after extraction before the run warns
+    /// (0 = no warning), see [`loose_stats`](crate::operations::loose_stats)
+    #[serde(default = "default_loose_file_limit")]
+    pub loose_file_limit: usize,
+}
+
+/// Saved user settings
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct SavedConfig {
+    /// Last used directory
+    #[serde(default)]
+    pub directory: String,
+
+    /// Last used size threshold (0 = none); stored as bytes, accepts size strings
+    #[serde(default)]
+    pub threshold: SizeSpec,
+
+    /// Whether the auto-threshold (engine archive limit) was enabled
+    #[serde(default)]
+    pub auto_threshold: bool,
+
+    /// Last loaded Wabbajack modlist (empty = none)
+    #[serde(default)]
+    pub modlist_path: String,
+
+    /// Chosen plugins.txt or loadorder.txt (empty = the game's plugins.txt)
+    #[serde(default)]
+    pub plugins_path: String,
+
+    /// Last folder of mod downloads looked inside (empty = none)
+    #[serde(default)]
+    pub downloads_path: String,
+}
+
+/// Whether the last used directory is still there
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SavedDirectory {
+    /// No directory was used yet
+    None,
+    /// The directory is there
+    Available(PathBuf),
+    /// The directory was moved or deleted
+    Missing(PathBuf),
+    /// The drive holding the directory isn't connected
+    DriveMissing(PathBuf),
+}
+
+impl SavedConfig {
+    /// Check the last used directory
+    pub fn saved_directory(&self) -> SavedDirectory {
+        if self.directory.trim().is_empty() {
+            return SavedDirectory::None;
+        }
+        let path = wine::host_path(&self.directory);
+        if path.is_dir() {
+            return SavedDirectory::Available(path);
+        }
+
+        // A drive letter or root that is gone means an unplugged drive
+        let root: PathBuf = path
+            .components()
+            .take_while(|component| matches!(component, Component::Prefix(_) | Component::RootDir))
+            .collect();
+        if root.as_os_str().is_empty() || root.exists() {
+            SavedDirectory::Missing(path)
+        } else {
+            SavedDirectory::DriveMissing(path)
+        }
+    }
+}
+
+/// Appearance configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppearanceConfig {
+    /// Theme mode: "light", "dark", or "system"
+    pub theme_mode: String,
+
+    /// Accent color (hex format)
+    pub accent_color: String,
+
+    /// Language: "auto", "en", "zh-CN", "zh-TW"
+    pub language: String,
+
+    /// Unit system for displayed sizes
+    #[serde(default)]
+    pub size_units: SizeUnits,
+
+    /// Use the high-contrast color scheme
+    #[serde(default)]
+    pub high_contrast: bool,
+
+    /// UI font scale multiplier (1.0 = default size)
+    #[serde(default = "default_font_scale")]
+    pub font_scale: f32,
+}
+
+impl AppearanceConfig {
+    /// Smallest allowed font scale
+    pub const MIN_FONT_SCALE: f32 = 0.75;
+
+    /// Largest allowed font scale
+    pub const MAX_FONT_SCALE: f32 = 2.0;
+}
+
+/// Advanced configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdvancedConfig {
+    /// Show debug information in UI
+    #[serde(default)]
+    pub show_debug: bool,
+
+    /// Analysis-only mode: refuse extraction and other file-changing actions
+    #[serde(default)]
+    pub analysis_only: bool,
+
+    /// Log level for debugging
+    #[serde(default)]
+    pub log_level: LogLevel,
+
+    /// First launch flag
+    #[serde(default = "default_true")]
+    pub first_launch: bool,
+
+    /// Custom extraction path (empty = use default)
+    #[serde(default)]
+    pub extraction_path: String,
+
+    /// Custom backup path (empty = use default)
+    #[serde(default)]
+    pub backup_path: String,
+
+    /// External BA2 tool path (empty = use bundled BSArch.exe)
+    #[serde(default)]
+    pub ext_ba2_exe: String,
+
+    /// Programs the row menu offers to open archives with
+    #[serde(default)]
+    pub open_with: Vec<ExternalTool>,
+
+    /// Cache folder for linked extractions (empty = use default)
+    #[serde(default)]
+    pub cache_path: String,
+
+    /// Quarantine folder for corrupted archives (empty = use default)
+    #[serde(default)]
+    pub quarantine_path: String,
+
+    /// Wine or Proton prefix Windows paths in the settings are translated
+    /// through on Linux (empty = from `WINEPREFIX` or the game's Proton prefix)
+    #[serde(default)]
+    pub wine_prefix: String,
+
+    /// Metrics and diagnostics, saved with the other advanced settings
+    #[serde(flatten)]
+    pub diagnostics: DiagnosticsConfig,
+
+    /// Notifications and other desktop integration, saved with the other
+    /// advanced settings
+    #[serde(flatten)]
+    pub integrations: IntegrationConfig,
+
+    /// How extractions use the CPU and drives, saved with the other
+    /// advanced settings
+    #[serde(flatten)]
+    pub performance: PerformanceConfig,
+}
+
+/// Metrics and diagnostics settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiagnosticsConfig {
+    /// Record local performance metrics of extraction runs (never uploaded)
+    #[serde(default)]
+    pub collect_metrics: bool,
+
+    /// Include a write benchmark of the mod folder in the diagnostics, to
+    /// tell whether antivirus scans slow extractions down
+    #[serde(default)]
+    pub defender_benchmark: bool,
+
+    /// Keep a progress snapshot of running extractions in the status file
+    /// for external tools (see [`status`](crate::operations::status))
+    #[serde(default)]
+    pub write_status_file: bool,
+}
+
+/// Desktop integration settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrationConfig {
+    /// Show a desktop notification when an extraction finishes
+    #[serde(default)]
+    pub notify_when_done: bool,
+
+    /// Scan the last used directory when the app starts
+    #[serde(default)]
+    pub auto_scan_on_start: bool,
+
+    /// Show the progress of running extractions on the user's Discord
+    /// profile (builds with the `discord` feature only)
+    #[serde(default)]
+    pub discord_presence: bool,
+}
+
+/// Extraction performance settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceConfig {
+    /// Run extractions below normal CPU and disk priority
+    #[serde(default)]
+    pub low_priority: bool,
+
+    /// How archives are read when unpacked without `BSArch`
+    /// (auto = memory mapped except on network and removable drives)
+    #[serde(default)]
+    pub archive_read: ReadMode,
+
+    /// Unpack archives with the built-in reader instead of `BSArch` (experimental)
+    #[serde(default)]
+    pub native_extraction: bool,
+
+    /// Buffer each job of the built-in reader unpacks files through, in KiB
+    /// (0 = automatic, based on the storage medium)
+    #[serde(default)]
+    pub write_buffer_kb: u32,
+
+    /// Files up to this size (KiB) are written in batches by the built-in reader (0 = never)
+    #[serde(default = "default_coalesce_below_kb")]
+    pub coalesce_below_kb: u32,
+
+    /// When files written by the built-in reader are flushed to disk
+    #[serde(default)]
+    pub fsync: FsyncPolicy,
+
+    /// Archives extracted at once (0 = automatic, based on the CPU and drive)
+    #[serde(default)]
+    pub max_jobs: usize,
+
+    /// Storage medium of the mod and output drives (unset = detect), which
+    /// picks the automatic number of jobs and buffer size
+    #[serde(default)]
+    pub storage_medium: Option<StorageMedium>,
+}
+
+/// Log level enumeration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+#[derive(Default)]
+pub enum LogLevel {
+    /// Fatal errors (most critical)
+    Fatal = 0,
+    /// Error messages
+    Error = 1,
+    /// Warning messages
+    #[default]
+    Warning = 2,
+    /// Informational messages
+    Info = 3,
+    /// Debug messages
+    Debug = 4,
+    /// Trace messages (most verbose)
+    Trace = 5,
+}
+
+/// Unit system used when displaying sizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeUnits {
+    /// Powers of 1024 (KiB, MiB, GiB)
+    #[default]
+    Binary,
+    /// Powers of 1000 (kB, MB, GB)
+    Decimal,
+}
+
+/// Handling of texture (DX10) archives
+///
+/// Unpacked textures are rarely worth it: they take far more space as loose
+/// files and lose the archive's load-order behavior, so they are skipped
+/// unless the user opts in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum TexturePolicy {
+    /// Never extract texture archives
+    #[default]
+    Skip,
+    /// Extract texture archives like any other archive
+    Extract,
+    /// Extract with the built-in reader, which rebuilds the DDS header of
+    /// every texture from its record
+    Convert,
+}
+
+impl TexturePolicy {
+    /// Short description shown next to texture archives in the file list
+    pub const fn row_note(self) -> &'static str {
+        match self {
+            Self::Skip => "Textures · skipped",
+            Self::Extract => "Textures",
+            Self::Convert => "Textures · DDS",
+        }
+    }
+}
+
+/// Order in which archives of a run are started
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScheduleOrder {
+    /// Biggest archives first, so a huge archive does not run alone at the end
+    #[default]
+    LargestFirst,
+    /// Smallest archives first, for quick visible progress
+    SmallestFirst,
+    /// In the order of the file list
+    AsListed,
+}
+
+/// Update checking configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateConfig {
+    /// Check for updates at startup
+    pub check_at_startup: bool,
+}
+
+/// Local automation interface configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationConfig {
+    /// Accept commands from other local programs
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Commands other programs may send (defaults to the read-only ones)
+    #[serde(default = "default_allowed_commands")]
+    pub allowed_commands: Vec<String>,
+}
+
+// Default value helpers for serde
+fn default_postfixes() -> Vec<String> {
+    FALLOUT4.default_postfixes()
+}
+
+fn default_allowed_commands() -> Vec<String> {
+    ipc::READ_ONLY_COMMANDS
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+const fn default_coalesce_below_kb() -> u32 {
+    64
+}
+
+const fn default_loose_file_limit() -> usize {
+    DEFAULT_LOOSE_FILE_LIMIT
+}
+
+const fn default_true() -> bool {
+    true
+}
+
+const fn default_font_scale() -> f32 {
+    1.0
+}
+
+impl Default for ExtractionConfig {
+    fn default() -> Self {
+        Self {
+            postfixes: default_postfixes(),
+            ignored_files: Vec::new(),
+            selection_rules: Vec::new(),
+            ignore_bad_files: true,
+            allow_unknown_versions: false,
+            auto_backup: true,
+            data_folder_mode: false,
+            confirm_extraction: true,
+            write_manifest: false,
+            use_temp: true,
+            link_mode: LinkMode::Off,
+            texture_policy: TexturePolicy::Skip,
+            texture_skip_mips: 0,
+            schedule: ScheduleOrder::LargestFirst,
+            serialize_per_mod: false,
+            abort_after_failures: AbortThreshold::default(),
+            loose_file_limit: DEFAULT_LOOSE_FILE_LIMIT,
+        }
+    }
+}
+
+impl Default for AppearanceConfig {
+    fn default() -> Self {
+        Self {
+            theme_mode: "dark".to_string(),
+            accent_color: "#0078D4".to_string(), // Fluent Design default blue
+            language: "auto".to_string(),
+            size_units: SizeUnits::default(),
+            high_contrast: false,
+            font_scale: default_font_scale(),
+        }
+    }
+}
+
+impl Default for AdvancedConfig {
+    fn default() -> Self {
+        Self {
+            show_debug: false,
+            analysis_only: false,
+            log_level: LogLevel::Warning,
+            first_launch: true,
+            extraction_path: String::new(),
+            backup_path: String::new(),
+            ext_ba2_exe: String::new(),
+            open_with: Vec::new(),
+            cache_path: String::new(),
+            quarantine_path: String::new(),
+            wine_prefix: String::new(),
+            diagnostics: DiagnosticsConfig::default(),
+            integrations: IntegrationConfig::default(),
+            performance: PerformanceConfig::default(),
+        }
+    }
+}
+
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            low_priority: false,
+            archive_read: ReadMode::Auto,
+            native_extraction: false,
+            write_buffer_kb: 0,
+            coalesce_below_kb: default_coalesce_below_kb(),
+            fsync: FsyncPolicy::Never,
+            max_jobs: 0,
+            storage_medium: None,
+        }
+    }
+}
+
+impl Default for AutomationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            allowed_commands: default_allowed_commands(),
+        }
+    }
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            check_at_startup: true,
+        }
+    }
+}
+
+impl AppConfig {
+    /// Profile of the selected game
+    pub const fn profile(&self) -> &'static GameProfile {
+        self.game.profile()
+    }
+
+    /// Switch to another game
+    ///
+    /// Postfixes left at the previous game's defaults are replaced with the
+    /// new game's defaults; customized postfixes are kept. Returns whether
+    /// the game changed.
+    pub fn set_game(&mut self, game: Game) -> bool {
+        if game == self.game {
+            return false;
+        }
+        if self.extraction.postfixes == self.profile().default_postfixes() {
+            self.extraction.postfixes = game.profile().default_postfixes();
+        }
+        self.game = game;
+        true
+    }
+
+    /// Get the default configuration directory path
+    pub fn config_dir() -> Result<PathBuf> {
+        ProjectDirs::from("com", "evildarkarchon", "unpackrr")
+            .map(|dirs| dirs.config_dir().to_path_buf())
+            .ok_or_else(|| {
+                ConfigError::ValidationFailed("Could not determine config directory".to_string())
+                    .into()
+            })
+    }
+
+    /// Get the configuration file path
+    pub fn config_file_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("config.json"))
+    }
+
+    /// Restore points of the configuration file, newest first
+    pub fn backups() -> Result<Vec<backups::ConfigBackup>> {
+        Ok(backups::list(&backups::backup_dir(&Self::config_file_path()?)))
+    }
+
+    /// Load configuration from file, or create default if not exists
+    pub fn load() -> Result<Self> {
+        let config_path = Self::config_file_path()?;
+
+        if !config_path.exists() {
+            tracing::info!(
+                "Configuration file not found, creating default at: {}",
+                config_path.display()
+            );
+            let default_config = Self::default();
+            default_config.save()?;
+            return Ok(default_config);
+        }
+
+        let content = fs::read_to_string(&config_path).map_err(|e| ConfigError::LoadFailed {
+            path: config_path.clone(),
+            source: e,
+        })?;
+
+        let config = Self::from_json(&content)?;
+
+        tracing::info!(
+            "Configuration loaded successfully from: {}",
+            config_path.display()
+        );
+        Ok(config)
+    }
+
+    /// Parse and validate the content of a configuration file
+    pub fn from_json(content: &str) -> Result<Self> {
+        let config: Self = serde_json::from_str(content)
+            .map_err(|e| ConfigError::InvalidFormat(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Save configuration to file
+    ///
+    /// Does nothing while saves are held (see [`hold_saves`]).
+    pub fn save(&self) -> Result<()> {
+        self.save_with_backup(backups::BACKUP_INTERVAL)
+    }
+
+    /// Save configuration to file, always keeping the replaced settings as a
+    /// restore point
+    ///
+    /// Used when restoring a restore point, so the restore can be undone.
+    pub fn save_keeping_backup(&self) -> Result<()> {
+        self.save_with_backup(Duration::ZERO)
+    }
+
+    /// Save, backing up the replaced settings unless the newest restore point
+    /// is less than `backup_interval` old
+    fn save_with_backup(&self, backup_interval: Duration) -> Result<()> {
+        if SAVES_HELD.load(Ordering::Relaxed) {
+            tracing::debug!("Not saving the configuration while saves are held");
+            return Ok(());
+        }
+        let config_path = Self::config_file_path()?;
+
+        // Validate before saving
+        self.validate()?;
+
+        // Create config directory if it doesn't exist
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| ConfigError::SaveFailed {
+                path: parent.to_path_buf(),
+                source: e,
+            })?;
+        }
+
+        // Serialize with pretty formatting
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| ConfigError::InvalidFormat(e.to_string()))?;
+
+        // Keep the settings being replaced as a restore point
+        if let Err(e) = backups::back_up_before_save(&config_path, &content, backup_interval) {
+            tracing::warn!("Failed to back up the previous configuration: {}", e);
+        }
+
+        fs::write(&config_path, content).map_err(|e| ConfigError::SaveFailed {
+            path: config_path.clone(),
+            source: e,
+        })?;
+
+        tracing::info!(
+            "Configuration saved successfully to: {}",
+            config_path.display()
+        );
+        Ok(())
+    }
+
+    /// Validate configuration
+    pub fn validate(&self) -> Result<()> {
+        // Validate postfixes - plain ones must end with .ba2, patterns must parse
+        PostfixMatcher::new(&self.extraction.postfixes)?;
+
+        // Validate font scale is within a usable range
+        let font_scale = self.appearance.font_scale;
+        if !(AppearanceConfig::MIN_FONT_SCALE..=AppearanceConfig::MAX_FONT_SCALE).contains(&font_scale) {
+            return Err(ConfigError::ValidationFailed(format!(
+                "Font scale {font_scale} must be between {} and {}",
+                AppearanceConfig::MIN_FONT_SCALE,
+                AppearanceConfig::MAX_FONT_SCALE
+            ))
+            .into());
+        }
+
+        // Validate threshold is non-negative (u64 is always non-negative, but check for clarity)
+        // This is mainly for documentation purposes
+
+        // Validate paths if specified
+        if !self.advanced.extraction_path.is_empty() {
+            let path = resolve_path(&self.advanced.extraction_path)?;
+            if !path.exists() {
+                tracing::warn!("Custom extraction path does not exist: {}", path.display());
+            }
+        }
+
+        if !self.advanced.backup_path.is_empty() {
+            let path = resolve_path(&self.advanced.backup_path)?;
+            if !path.exists() {
+                tracing::warn!("Custom backup path does not exist: {}", path.display());
+            }
+        }
+
+        if !self.advanced.ext_ba2_exe.is_empty() {
+            let path = resolve_path(&self.advanced.ext_ba2_exe)?;
+            if !path.exists() {
+                return Err(ConfigError::InvalidPath(path).into());
+            }
+        }
+
+        // Only known commands can be allowed for automation
+        if let Some(command) = self
+            .automation
+            .allowed_commands
+            .iter()
+            .find(|c| !ipc::COMMANDS.contains(&c.as_str()))
+        {
+            return Err(ConfigError::ValidationFailed(format!(
+                "Unknown automation command '{command}' (known: {})",
+                ipc::COMMANDS.join(", ")
+            ))
+            .into());
+        }
+
+        // Validate the regexes of the ignore list
+        self.ignore_matcher()?;
+
+        Ok(())
+    }
+
+    /// Compile the ignore list for matching scanned archives
+    pub fn ignore_matcher(&self) -> Result<IgnoreMatcher> {
+        IgnoreMatcher::new(&self.extraction.ignored_files)
+    }
+}
+
+/// Resolve a path to an absolute path, handling Windows UNC paths correctly
+pub fn resolve_path(path: &str) -> Result<PathBuf> {
+    if path.is_empty() {
+        return Err(ConfigError::InvalidPath(PathBuf::new()).into());
+    }
+
+    let path_buf = PathBuf::from(path);
+
+    // Use dunce to handle Windows UNC paths correctly
+    let resolved = if path_buf.is_absolute() {
+        dunce::canonicalize(&path_buf).unwrap_or(path_buf)
+    } else {
+        // Resolve relative to current directory
+        let current_dir = std::env::current_dir().map_err(|e| {
+            ConfigError::ValidationFailed(format!("Cannot get current directory: {e}"))
+        })?;
+        let full_path = current_dir.join(&path_buf);
+        dunce::canonicalize(&full_path).unwrap_or(full_path)
+    };
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ignore::PatternKind;
+
+    #[test]
+    fn test_default_config() {
+        let config = AppConfig::default();
+        assert_eq!(config.appearance.theme_mode, "dark");
+        assert!(config.update.check_at_startup);
+        assert_eq!(config.advanced.log_level, LogLevel::Warning);
+        assert!(config.advanced.first_launch);
+    }
+
+    #[test]
+    fn test_config_serialization() {
+        let config = AppConfig::default();
+        let json = serde_json::to_string(&config).expect("Failed to serialize");
+        let deserialized: AppConfig = serde_json::from_str(&json).expect("Failed to deserialize");
+        assert_eq!(deserialized.appearance.language, config.appearance.language);
+        assert_eq!(deserialized.advanced.log_level, LogLevel::Warning);
+    }
+
+    #[test]
+    fn test_advanced_groups_keep_flat_keys() {
+        let json = r#"{"collect_metrics":true,"discord_presence":true,"max_jobs":3}"#;
+        let advanced: AdvancedConfig = serde_json::from_str(json).expect("Failed to deserialize");
+        assert!(advanced.diagnostics.collect_metrics);
+        assert!(advanced.integrations.discord_presence);
+        assert_eq!(advanced.performance.max_jobs, 3);
+        assert_eq!(
+            advanced.performance.coalesce_below_kb,
+            default_coalesce_below_kb()
+        );
+
+        let value = serde_json::to_value(&advanced).expect("Failed to serialize");
+        assert_eq!(value["max_jobs"], 3);
+        assert!(value.get("performance").is_none());
+    }
+
+    #[test]
+    fn test_set_game_swaps_default_postfixes() {
+        let mut config = AppConfig::default();
+        assert_eq!(config.game, Game::Fallout4);
+        assert!(!config.set_game(Game::Fallout4));
+
+        assert!(config.set_game(Game::Starfield));
+        assert_eq!(config.profile().game, Game::Starfield);
+        assert_eq!(config.extraction.postfixes, ["main.ba2"]);
+
+        // Customized postfixes are kept
+        config.extraction.postfixes.push("textures.ba2".to_string());
+        assert!(config.set_game(Game::Fallout4));
+        assert_eq!(config.extraction.postfixes, ["main.ba2", "textures.ba2"]);
+
+        // Configurations without a game are Fallout 4 configurations
+        let json = serde_json::to_value(AppConfig::default()).unwrap();
+        let mut json = json.as_object().unwrap().clone();
+        json.remove("game");
+        let config: AppConfig = serde_json::from_value(json.into()).unwrap();
+        assert_eq!(config.game, Game::Fallout4);
+    }
+
+    #[test]
+    fn test_postfix_validation() {
+        let mut config = AppConfig::default();
+        config.extraction.postfixes.push("invalid.txt".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_postfix_validation_success() {
+        let mut config = AppConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.extraction.postfixes.push("* - Textures?.ba2".to_string());
+        config.extraction.postfixes.push(r"re:_misc\.ba2$".to_string());
+        assert!(config.validate().is_ok());
+
+        config.extraction.postfixes.push("re:(".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_invalid_regex_validation() {
+        let mut config = AppConfig::default();
+        config
+            .extraction
+            .ignored_files
+            .push(IgnorePattern::new(PatternKind::Regex, "[invalid"));
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_automation_command_validation() {
+        let mut config = AppConfig::default();
+        assert!(!config.automation.enabled);
+        assert_eq!(config.automation.allowed_commands, ["list", "status", "watch"]);
+
+        config.automation.allowed_commands.push("scan".to_string());
+        assert!(config.validate().is_ok());
+
+        config.automation.allowed_commands.push("delete".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_font_scale_validation() {
+        let mut config = AppConfig::default();
+        config.appearance.font_scale = 1.5;
+        assert!(config.validate().is_ok());
+
+        config.appearance.font_scale = 5.0;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_appearance_defaults_for_missing_fields() {
+        let json = r##"{"theme_mode":"light","accent_color":"#0078D4","language":"en"}"##;
+        let appearance: AppearanceConfig = serde_json::from_str(json).expect("Failed to deserialize");
+        assert!(!appearance.high_contrast);
+        assert!((appearance.font_scale - 1.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_saved_directory() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let saved = |directory: &std::path::Path| SavedConfig {
+            directory: directory.to_string_lossy().into_owned(),
+            ..SavedConfig::default()
+        };
+
+        assert_eq!(SavedConfig::default().saved_directory(), SavedDirectory::None);
+        assert_eq!(
+            saved(temp_dir.path()).saved_directory(),
+            SavedDirectory::Available(temp_dir.path().to_path_buf())
+        );
+        let gone = temp_dir.path().join("Mods");
+        assert_eq!(saved(&gone).saved_directory(), SavedDirectory::Missing(gone));
+    }
+
+    #[test]
+    fn test_log_level_serialization() {
+        let level = LogLevel::Debug;
+        let json = serde_json::to_string(&level).unwrap();
+        assert_eq!(json, "\"DEBUG\"");
+
+        let deserialized: LogLevel = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, LogLevel::Debug);
+    }
+}
@@ -0,0 +1,175 @@
+//! Settings overridden for a single run
+//!
+//! The Run options flyout changes postfixes, the ignore list, the output
+//! path, backups, the number of parallel jobs or texture downscaling for the
+//! next extraction only.
+//! The overrides are layered on a snapshot of the saved configuration as an
+//! [`EffectiveConfig`], which scans and extractions take in place of the
+//! [`AppConfig`]; the saved configuration is never touched.
+
+use super::AppConfig;
+use super::ignore::{IgnoreMatcher, IgnorePattern};
+use super::postfix::PostfixMatcher;
+use crate::error::Result;
+use std::ops::Deref;
+use std::path::Path;
+
+/// Settings replaced for the next run (`None` keeps the saved setting)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOverrides {
+    /// Postfixes picking the archives to list
+    pub postfixes: Option<Vec<String>>,
+
+    /// Patterns keeping archives out of the list
+    pub ignored_files: Option<Vec<IgnorePattern>>,
+
+    /// Folder extracted files are opened from
+    pub extraction_path: Option<String>,
+
+    /// Backup archives before extraction
+    pub auto_backup: Option<bool>,
+
+    /// Archives extracted at once (0 = automatic)
+    pub max_jobs: Option<usize>,
+
+    /// Largest mip levels dropped from textures (0 = full size)
+    pub texture_skip_mips: Option<u8>,
+}
+
+impl RunOverrides {
+    /// Whether no setting is overridden
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The saved configuration with the overrides of one run applied
+#[derive(Debug, Clone)]
+pub struct EffectiveConfig {
+    config: AppConfig,
+    overrides: RunOverrides,
+    postfixes: PostfixMatcher,
+    ignored: IgnoreMatcher,
+}
+
+impl EffectiveConfig {
+    /// Apply overrides to a snapshot of the saved configuration
+    ///
+    /// Fails if a postfix or ignore pattern in effect is invalid.
+    pub fn new(base: &AppConfig, overrides: &RunOverrides) -> Result<Self> {
+        let mut config = base.clone();
+        if let Some(postfixes) = &overrides.postfixes {
+            config.extraction.postfixes.clone_from(postfixes);
+        }
+        if let Some(ignored_files) = &overrides.ignored_files {
+            config.extraction.ignored_files.clone_from(ignored_files);
+        }
+        if let Some(extraction_path) = &overrides.extraction_path {
+            config.advanced.extraction_path.clone_from(extraction_path);
+        }
+        if let Some(auto_backup) = overrides.auto_backup {
+            config.extraction.auto_backup = auto_backup;
+        }
+        if let Some(max_jobs) = overrides.max_jobs {
+            config.advanced.performance.max_jobs = max_jobs;
+        }
+        if let Some(skip_mips) = overrides.texture_skip_mips {
+            config.extraction.texture_skip_mips = skip_mips;
+        }
+        Ok(Self {
+            postfixes: PostfixMatcher::new(&config.extraction.postfixes)?,
+            ignored: config.ignore_matcher()?,
+            config,
+            overrides: overrides.clone(),
+        })
+    }
+
+    /// Whether a scanned archive is still picked by the postfixes and ignore list in effect
+    pub fn includes(&self, archive: &Path) -> bool {
+        archive
+            .file_name()
+            .is_some_and(|name| self.postfixes.matches(&name.to_string_lossy()))
+            && !self.ignored.ignores(archive)
+    }
+
+    /// The overrides applied
+    pub const fn overrides(&self) -> &RunOverrides {
+        &self.overrides
+    }
+
+    /// The configuration to hand to an extraction
+    pub fn into_config(self) -> AppConfig {
+        self.config
+    }
+}
+
+impl Deref for EffectiveConfig {
+    type Target = AppConfig;
+
+    fn deref(&self) -> &AppConfig {
+        &self.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ignore::PatternKind;
+
+    #[test]
+    fn test_overrides_leave_base_untouched() {
+        let base = AppConfig::default();
+        let overrides = RunOverrides {
+            postfixes: Some(vec!["* - Textures.ba2".to_string()]),
+            ignored_files: Some(vec![IgnorePattern::new(PatternKind::Glob, "Test*")]),
+            auto_backup: Some(false),
+            max_jobs: Some(2),
+            texture_skip_mips: Some(1),
+            ..RunOverrides::default()
+        };
+
+        let effective = EffectiveConfig::new(&base, &overrides).unwrap();
+        assert_eq!(effective.extraction.postfixes, ["* - Textures.ba2"]);
+        assert_eq!(effective.extraction.ignored_files.len(), 1);
+        assert!(!effective.extraction.auto_backup);
+        assert_eq!(effective.advanced.performance.max_jobs, 2);
+        assert_eq!(effective.extraction.texture_skip_mips, 1);
+        // Settings not overridden come from the base
+        assert_eq!(
+            effective.advanced.extraction_path,
+            base.advanced.extraction_path
+        );
+
+        assert_eq!(
+            base.extraction.postfixes,
+            AppConfig::default().extraction.postfixes
+        );
+        assert_eq!(
+            base.advanced.performance.max_jobs,
+            AppConfig::default().advanced.performance.max_jobs
+        );
+        assert!(base.extraction.auto_backup);
+        assert!(!effective.overrides().is_empty());
+        assert!(RunOverrides::default().is_empty());
+
+        assert!(effective.includes(Path::new("/data/Mod/Mod - Textures.ba2")));
+        assert!(!effective.includes(Path::new("/data/Mod/Mod - Main.ba2")));
+        assert!(!effective.includes(Path::new("/data/Test/Test - Textures.ba2")));
+    }
+
+    #[test]
+    fn test_invalid_overrides() {
+        let base = AppConfig::default();
+        let postfixes = RunOverrides {
+            postfixes: Some(vec!["Main".to_string()]),
+            ..RunOverrides::default()
+        };
+        assert!(EffectiveConfig::new(&base, &postfixes).is_err());
+
+        let ignored = RunOverrides {
+            ignored_files: Some(vec![IgnorePattern::new(PatternKind::Regex, "(")]),
+            ..RunOverrides::default()
+        };
+        assert!(EffectiveConfig::new(&base, &ignored).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Postfix patterns that pick the archives a scan lists
+//!
+//! Each configured postfix is one of:
+//!
+//! - a plain suffix (`- Main.ba2`), matching file names that end with it
+//! - a glob (`* - Textures.ba2`), matching the whole file name with the `*`
+//!   and `?` wildcards
+//! - a regex prefixed with `re:` (`re:_(main|misc)\.ba2$`), matching anywhere
+//!   in the file name unless anchored
+//!
+//! All patterns are case-insensitive. Plain suffixes must end with `.ba2`, so
+//! `Main` no longer matches `MainMenu - Textures.ba2` by accident. Trailing
+//! whitespace of suffixes and globs is ignored, as file names on Windows
+//! can't end with it.
+
+use super::rules::glob_match;
+use crate::error::{ConfigError, Result};
+use regex::{Regex, RegexBuilder};
+
+/// Prefix marking a postfix as a regular expression
+pub const REGEX_PREFIX: &str = "re:";
+
+/// A parsed postfix
+#[derive(Debug, Clone)]
+pub enum PostfixPattern {
+    /// File names ending with the text
+    Suffix(String),
+    /// File names matching the glob
+    Glob(String),
+    /// File names the regex matches
+    Regex(Regex),
+}
+
+impl PostfixPattern {
+    /// Parse a configured postfix
+    pub fn parse(postfix: &str) -> Result<Self> {
+        if let Some(pattern) = postfix.strip_prefix(REGEX_PREFIX) {
+            return RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map(Self::Regex)
+                .map_err(|e| {
+                    ConfigError::InvalidRegex {
+                        pattern: pattern.to_string(),
+                        source: e,
+                    }
+                    .into()
+                });
+        }
+        let postfix = postfix.trim_end();
+        if postfix.contains(['*', '?']) {
+            return Ok(Self::Glob(postfix.to_string()));
+        }
+        if !postfix.to_ascii_lowercase().ends_with(".ba2") {
+            return Err(ConfigError::ValidationFailed(format!(
+                "Postfix '{postfix}' must end with .ba2 (use * or {REGEX_PREFIX} for patterns)"
+            ))
+            .into());
+        }
+        Ok(Self::Suffix(postfix.to_lowercase()))
+    }
+
+    /// Whether an archive file name matches
+    pub fn matches(&self, file_name: &str) -> bool {
+        match self {
+            Self::Suffix(suffix) => file_name.to_lowercase().ends_with(suffix.as_str()),
+            Self::Glob(pattern) => glob_match(pattern, file_name),
+            Self::Regex(regex) => regex.is_match(file_name),
+        }
+    }
+}
+
+/// The configured postfixes, parsed once per scan
+#[derive(Debug, Clone, Default)]
+pub struct PostfixMatcher {
+    patterns: Vec<PostfixPattern>,
+}
+
+impl PostfixMatcher {
+    /// Parse all postfixes, failing on the first invalid one
+    pub fn new(postfixes: &[String]) -> Result<Self> {
+        postfixes
+            .iter()
+            .map(|postfix| PostfixPattern::parse(postfix))
+            .collect::<Result<_>>()
+            .map(|patterns| Self { patterns })
+    }
+
+    /// Whether any postfix matches an archive file name
+    pub fn matches(&self, file_name: &str) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches(file_name))
+    }
+}
+
+/// Archives one postfix matches, for testing patterns before a scan
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostfixPreview {
+    /// The postfix as written
+    pub postfix: String,
+
+    /// File names it matched, or the reason it is invalid
+    pub matched: std::result::Result<Vec<String>, String>,
+}
+
+/// List the file names each postfix matches
+pub fn preview_postfixes(postfixes: &[String], file_names: &[String]) -> Vec<PostfixPreview> {
+    postfixes
+        .iter()
+        .map(|postfix| PostfixPreview {
+            postfix: postfix.clone(),
+            matched: PostfixPattern::parse(postfix)
+                .map(|pattern| {
+                    file_names
+                        .iter()
+                        .filter(|name| pattern.matches(name))
+                        .cloned()
+                        .collect()
+                })
+                .map_err(|e| e.to_string()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(postfix: &str, file_name: &str) -> bool {
+        PostfixPattern::parse(postfix).unwrap().matches(file_name)
+    }
+
+    #[test]
+    fn test_suffix_match() {
+        assert!(matches("- Main.ba2", "Armor - Main.ba2"));
+        assert!(matches("main.ba2", "Armor - MAIN.BA2"));
+        assert!(matches(".BA2", "Armor.ba2"));
+        // A suffix no longer matches in the middle of the name
+        assert!(!matches("main.ba2", "Main.ba2 - Textures.ba2"));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(matches("* - Textures.ba2", "Armor - Textures.ba2"));
+        assert!(matches("* - Textures?.ba2", "Armor - Textures2.ba2"));
+        assert!(!matches("* - Textures.ba2", "Armor - Main.ba2"));
+        assert!(matches("*main*", "MainMenu - Textures.ba2"));
+    }
+
+    #[test]
+    fn test_regex_match() {
+        assert!(matches(r"re:_(main|misc)\.ba2$", "Mod_MISC.ba2"));
+        assert!(!matches(r"re:_(main|misc)\.ba2$", "Mod_Textures.ba2"));
+    }
+
+    #[test]
+    fn test_invalid_postfixes() {
+        assert!(PostfixPattern::parse("Main").is_err());
+        assert!(PostfixPattern::parse("invalid.txt").is_err());
+        assert!(PostfixPattern::parse("re:[unclosed").is_err());
+        assert!(PostfixMatcher::new(&["main.ba2".to_string(), "Main".to_string()]).is_err());
+
+        let matcher = PostfixMatcher::new(&["main.ba2".to_string(), "*.zip".to_string()]).unwrap();
+        assert!(matcher.matches("Mod - Main.ba2"));
+        assert!(!matcher.matches("Mod - Textures.ba2"));
+    }
+
+    #[test]
+    fn test_preview_postfixes() {
+        let names = ["A - Main.ba2", "B - Textures.ba2", "C - Main.ba2"].map(String::from);
+        let previews = preview_postfixes(&["- Main.ba2".to_string(), "re:(".to_string()], &names);
+        assert_eq!(
+            previews[0].matched,
+            Ok(vec!["A - Main.ba2".to_string(), "C - Main.ba2".to_string()])
+        );
+        assert!(previews[1].matched.is_err());
+    }
+}
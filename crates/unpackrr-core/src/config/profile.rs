@@ -0,0 +1,226 @@
+//! Game profiles
+//!
+//! Fallout 4 and Starfield store mod assets in the same archive format, but
+//! differ in the archives mods ship, the BA2 versions they write, where the
+//! plugin list lives, how the game executable is named and how many archives
+//! the engine loads. A
+//! [`GameProfile`] holds these per-game values; the active one is selected
+//! by [`AppConfig::game`](super::AppConfig::game).
+
+use crate::models::stats::ENGINE_ARCHIVE_LIMIT;
+use crate::platform::wine;
+use directories::BaseDirs;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Game whose mods are unpacked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Game {
+    /// Fallout 4
+    #[default]
+    Fallout4,
+    /// Starfield
+    Starfield,
+}
+
+impl Game {
+    /// All games, in the order the game selector lists them
+    pub const ALL: [Self; 2] = [Self::Fallout4, Self::Starfield];
+
+    /// Profile of the game
+    pub const fn profile(self) -> &'static GameProfile {
+        match self {
+            Self::Fallout4 => &FALLOUT4,
+            Self::Starfield => &STARFIELD,
+        }
+    }
+
+    /// Game whose Data folder `dir` is, if any
+    pub fn of_data_folder(dir: &Path) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|game| game.profile().is_data_folder(dir))
+    }
+}
+
+impl std::fmt::Display for Game {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.profile().name)
+    }
+}
+
+/// Per-game defaults and limits
+#[derive(Debug, PartialEq, Eq)]
+pub struct GameProfile {
+    /// Game of the profile
+    pub game: Game,
+
+    /// Display name
+    pub name: &'static str,
+
+    /// Postfixes of the archives unpacked by default
+    pub postfixes: &'static [&'static str],
+
+    /// Number of archives the engine reliably loads, `None` if no limit is known
+    pub archive_limit: Option<usize>,
+
+    /// BA2 format versions the game writes
+    pub versions: &'static [u32],
+
+    /// Folder of `plugins.txt` under `%LOCALAPPDATA%`
+    pub plugins_folder: &'static str,
+
+    /// Masters the game loads before the plugins listed in `plugins.txt`
+    pub masters: &'static [&'static str],
+
+    /// File name of the game executable, next to the Data folder
+    pub executable: &'static str,
+
+    /// Steam app ID, which names the game's Proton prefix on Linux
+    pub steam_app_id: u32,
+}
+
+/// Fallout 4, including its next-gen update
+pub const FALLOUT4: GameProfile = GameProfile {
+    game: Game::Fallout4,
+    name: "Fallout 4",
+    postfixes: &["main.ba2", "materials.ba2", "misc.ba2", "scripts.ba2"],
+    archive_limit: Some(ENGINE_ARCHIVE_LIMIT),
+    versions: &[1, 7, 8],
+    plugins_folder: "Fallout4",
+    masters: &[
+        "Fallout4",
+        "DLCRobot",
+        "DLCworkshop01",
+        "DLCCoast",
+        "DLCworkshop02",
+        "DLCworkshop03",
+        "DLCNukaWorld",
+        "DLCUltraHighResolution",
+    ],
+    executable: "Fallout4.exe",
+    steam_app_id: 377_160,
+};
+
+/// Starfield
+pub const STARFIELD: GameProfile = GameProfile {
+    game: Game::Starfield,
+    name: "Starfield",
+    postfixes: &["main.ba2"],
+    archive_limit: None,
+    versions: &[2, 3],
+    plugins_folder: "Starfield",
+    masters: &[
+        "Starfield",
+        "Constellation",
+        "OldMars",
+        "BlueprintShips-Starfield",
+        "SFBGS003",
+        "SFBGS004",
+        "SFBGS006",
+        "SFBGS007",
+        "SFBGS008",
+        "ShatteredSpace",
+    ],
+    executable: "Starfield.exe",
+    steam_app_id: 1_716_740,
+};
+
+impl GameProfile {
+    /// Default postfixes as configured values
+    pub fn default_postfixes(&self) -> Vec<String> {
+        self.postfixes.iter().map(ToString::to_string).collect()
+    }
+
+    /// Whether the game writes a BA2 format version
+    pub fn expects_version(&self, version: u32) -> bool {
+        self.versions.contains(&version)
+    }
+
+    /// Whether `dir` is the game's Data folder
+    ///
+    /// The folder must be named `Data` and sit next to the game executable.
+    pub fn is_data_folder(&self, dir: &Path) -> bool {
+        dir.file_name()
+            .is_some_and(|name| name.eq_ignore_ascii_case("Data"))
+            && dir
+                .parent()
+                .is_some_and(|game_dir| game_dir.join(self.executable).is_file())
+    }
+
+    /// Whether an archive is one of the game's own, named after a master
+    /// (e.g., `Fallout4 - Textures1.ba2`, `DLCRobot - Main.ba2`)
+    pub fn is_game_archive(&self, file_name: &str) -> bool {
+        self.masters.iter().any(|master| {
+            file_name.len() > master.len() + 3
+                && file_name.is_char_boundary(master.len())
+                && file_name[..master.len()].eq_ignore_ascii_case(master)
+                && file_name[master.len()..].starts_with(" - ")
+        })
+    }
+
+    /// Default location of the game's `plugins.txt`
+    ///
+    /// Inside the Wine prefix of the game, if one is known (see
+    /// [`wine`](crate::platform::wine)).
+    pub fn plugins_path(&self) -> Option<PathBuf> {
+        let local = wine::local_app_data()
+            .or_else(|| BaseDirs::new().map(|dirs| dirs.data_local_dir().to_path_buf()))?;
+        Some(local.join(self.plugins_folder).join("plugins.txt"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ba2::is_known_version;
+
+    #[test]
+    fn test_profiles() {
+        for game in Game::ALL {
+            let profile = game.profile();
+            assert_eq!(profile.game, game);
+            assert_eq!(game.to_string(), profile.name);
+            assert!(profile.versions.iter().all(|&v| is_known_version(v)));
+            assert!(!profile.postfixes.is_empty());
+        }
+        assert!(FALLOUT4.expects_version(8));
+        assert!(!FALLOUT4.expects_version(2));
+        assert!(STARFIELD.expects_version(2));
+        assert_eq!(FALLOUT4.archive_limit, Some(ENGINE_ARCHIVE_LIMIT));
+        assert!(
+            STARFIELD
+                .plugins_path()
+                .is_none_or(|path| path.ends_with("Starfield/plugins.txt"))
+        );
+    }
+
+    #[test]
+    fn test_data_folder() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let data = temp_dir.path().join("Fallout 4").join("Data");
+        std::fs::create_dir_all(&data).unwrap();
+        assert_eq!(Game::of_data_folder(&data), None);
+
+        std::fs::write(temp_dir.path().join("Fallout 4").join("Fallout4.exe"), b"").unwrap();
+        assert_eq!(Game::of_data_folder(&data), Some(Game::Fallout4));
+        assert!(!STARFIELD.is_data_folder(&data));
+        assert_eq!(Game::of_data_folder(&data.join("Meshes")), None);
+
+        assert!(FALLOUT4.is_game_archive("Fallout4 - Textures1.ba2"));
+        assert!(FALLOUT4.is_game_archive("dlcrobot - Main.ba2"));
+        assert!(!FALLOUT4.is_game_archive("Fallout4Mod - Main.ba2"));
+        assert!(!FALLOUT4.is_game_archive("ArmorKeywords - Main.ba2"));
+    }
+
+    #[test]
+    fn test_game_serialization() {
+        assert_eq!(
+            serde_json::to_string(&Game::Starfield).unwrap(),
+            "\"starfield\""
+        );
+        let game: Game = serde_json::from_str("\"fallout4\"").unwrap();
+        assert_eq!(game, Game::Fallout4);
+    }
+}
@@ -0,0 +1,159 @@
+//! Repairing a configuration file that can't be loaded
+//!
+//! A hand-edited or truncated `config.json` fails to load as a whole, even if
+//! a single setting is wrong. [`repair`] keeps every setting that is valid on
+//! its own and resets the others to their defaults, listing them. The broken
+//! file is kept next to the repaired one by [`back_up`].
+
+use super::AppConfig;
+use crate::error::Result;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Result of repairing a configuration
+#[derive(Debug, Clone)]
+pub struct Repair {
+    /// The configuration with every valid setting kept
+    pub config: AppConfig,
+
+    /// Settings reset to their defaults, as `section.setting`
+    pub reset: Vec<String>,
+}
+
+/// Keep the valid settings of a configuration file's content
+///
+/// Content that isn't a JSON object at all gives the default configuration.
+pub fn repair(content: &str) -> Repair {
+    let defaults = AppConfig::default();
+    let Ok(Value::Object(broken)) = serde_json::from_str::<Value>(content) else {
+        return Repair {
+            config: defaults,
+            reset: vec!["all settings (the file is not valid JSON)".to_string()],
+        };
+    };
+    let Ok(mut merged) = serde_json::to_value(&defaults) else {
+        return Repair {
+            config: defaults,
+            reset: Vec::new(),
+        };
+    };
+
+    let mut reset = Vec::new();
+    for (section, value) in broken {
+        match value {
+            Value::Object(settings) if merged.get(&section).is_some_and(Value::is_object) => {
+                for (key, value) in settings {
+                    let mut candidate = merged.clone();
+                    candidate[&section][&key] = value;
+                    if is_valid(&candidate) {
+                        merged = candidate;
+                    } else {
+                        reset.push(format!("{section}.{key}"));
+                    }
+                }
+            }
+            value => {
+                let mut candidate = merged.clone();
+                candidate[&section] = value;
+                if is_valid(&candidate) {
+                    merged = candidate;
+                } else {
+                    reset.push(section);
+                }
+            }
+        }
+    }
+
+    Repair {
+        config: AppConfig::from_json(&merged.to_string()).unwrap_or(defaults),
+        reset,
+    }
+}
+
+fn is_valid(config: &Value) -> bool {
+    AppConfig::from_json(&config.to_string()).is_ok()
+}
+
+/// Copy the configuration file at `path` next to it, returning the copy's path
+///
+/// The copy is named after the file and the time, e.g.
+/// `config.broken-1760000000.json`.
+pub fn back_up(path: &Path) -> Result<PathBuf> {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let stem = path
+        .file_stem()
+        .map_or_else(|| "config".into(), |stem| stem.to_string_lossy());
+    let backup = path.with_file_name(format!("{stem}.broken-{secs}.json"));
+    fs::copy(path, &backup)?;
+    tracing::info!(
+        "Backed up configuration {} to {}",
+        path.display(),
+        backup.display()
+    );
+    Ok(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_repair_keeps_valid_settings() {
+        let mut config = AppConfig::default();
+        config.advanced.diagnostics.collect_metrics = true;
+        config.saved.directory = "D:/Mods".to_string();
+        let mut value = serde_json::to_value(&config).unwrap();
+        value["appearance"]["font_scale"] = serde_json::json!(40.0);
+        value["extraction"]["postfixes"] = serde_json::json!(["main.txt"]);
+        value["advanced"]["max_jobs"] = serde_json::json!("many");
+        let content = value.to_string();
+        assert!(AppConfig::from_json(&content).is_err());
+
+        let repaired = repair(&content);
+        assert!(repaired.config.advanced.diagnostics.collect_metrics);
+        assert_eq!(repaired.config.saved.directory, "D:/Mods");
+        assert_eq!(
+            repaired.config.extraction.postfixes,
+            AppConfig::default().extraction.postfixes
+        );
+        let mut reset = repaired.reset;
+        reset.sort();
+        assert_eq!(
+            reset,
+            [
+                "advanced.max_jobs",
+                "appearance.font_scale",
+                "extraction.postfixes"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repair_of_invalid_json() {
+        let repaired = repair("{ \"extraction\": ");
+        assert_eq!(repaired.reset.len(), 1);
+        assert_eq!(
+            repaired.config.extraction.postfixes,
+            AppConfig::default().extraction.postfixes
+        );
+    }
+
+    #[test]
+    fn test_back_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.json");
+        fs::write(&path, b"{ broken").unwrap();
+
+        let backup = back_up(&path).unwrap();
+        assert_eq!(fs::read(&backup).unwrap(), b"{ broken");
+        let name = backup.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with("config.broken-"));
+        assert_eq!(backup.extension().unwrap(), "json");
+        assert!(path.exists());
+    }
+}
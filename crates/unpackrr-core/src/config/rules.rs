@@ -0,0 +1,386 @@
+//! Rules that build the default selection after a scan
+//!
+//! Each rule is one line of the form `<action> <condition>`:
+//!
+//! - `always size < 5 MB` selects every archive the condition matches
+//! - `never mod *HD Textures*` keeps matching archives out of the selection
+//! - `only type GNRL` keeps archives the condition does not match out
+//!
+//! Conditions test the archive size (`size < 5 MB`, `size > 1 GB`), the mod
+//! folder (`mod <pattern>`), the archive file name (`name <pattern>`) or the
+//! archive type (`type GNRL`). Patterns are case-insensitive and support the
+//! `*` and `?` wildcards.
+//!
+//! Without any `always` rule every archive starts selected; `never` and
+//! `only` rules then narrow the selection down. Corrupted archives and
+//! archives on the [`known_exclusions`] list are never selected.
+
+pub mod known_exclusions;
+
+use crate::error::{ConfigError, Result};
+use crate::models::FileEntry;
+use crate::operations::SizeSpec;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// What a rule does with the archives its condition matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAction {
+    /// Select matching archives
+    Always,
+    /// Never select matching archives
+    Never,
+    /// Never select archives that do not match
+    Only,
+}
+
+/// Which archives a rule applies to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleCondition {
+    /// Archives smaller than the size
+    SmallerThan(SizeSpec),
+    /// Archives larger than the size
+    LargerThan(SizeSpec),
+    /// Archives in a mod folder matching the pattern
+    Mod(String),
+    /// Archives whose file name matches the pattern
+    Name(String),
+    /// Archives of a type ("GNRL" or "DX10")
+    Type(String),
+}
+
+/// A rule of the default selection
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SelectionRule {
+    /// What the rule does
+    pub action: RuleAction,
+
+    /// Which archives it applies to
+    pub condition: RuleCondition,
+}
+
+/// Archives a rule matched in a scan, for the dry-run preview
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RulePreview {
+    /// The rule as written
+    pub rule: String,
+
+    /// File names of the archives the condition matched
+    pub matched: Vec<String>,
+}
+
+impl SelectionRule {
+    /// Whether the rule's condition matches an archive
+    pub fn matches(&self, entry: &FileEntry) -> bool {
+        match &self.condition {
+            RuleCondition::SmallerThan(size) => entry.file_size < size.bytes(),
+            RuleCondition::LargerThan(size) => entry.file_size > size.bytes(),
+            RuleCondition::Mod(pattern) => glob_match(pattern, &entry.dir_name),
+            RuleCondition::Name(pattern) => glob_match(pattern, &entry.file_name),
+            RuleCondition::Type(archive_type) => {
+                entry.archive_type.eq_ignore_ascii_case(archive_type)
+            }
+        }
+    }
+}
+
+impl FromStr for SelectionRule {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| -> crate::error::Error {
+            ConfigError::ValidationFailed(format!("Invalid rule '{}': {reason}", s.trim())).into()
+        };
+
+        let split = |text: &str| -> (String, String) {
+            let text = text.trim();
+            let (word, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+            (word.to_lowercase(), rest.trim().to_string())
+        };
+        let (action, rest) = split(s);
+        let action = match action.as_str() {
+            "always" => RuleAction::Always,
+            "never" => RuleAction::Never,
+            "only" => RuleAction::Only,
+            _ => return Err(invalid("must start with always, never or only")),
+        };
+        let (field, value) = split(&rest);
+        let value = value.trim_matches('"');
+        if value.is_empty() {
+            return Err(invalid("missing value"));
+        }
+
+        let condition = match field.as_str() {
+            "size" => {
+                let size = |rest: &str| SizeSpec::parse(rest).map_err(|_| invalid("invalid size"));
+                if let Some(rest) = value.strip_prefix('<') {
+                    RuleCondition::SmallerThan(size(rest)?)
+                } else if let Some(rest) = value.strip_prefix('>') {
+                    RuleCondition::LargerThan(size(rest)?)
+                } else {
+                    return Err(invalid("size must be compared with < or >"));
+                }
+            }
+            "mod" => RuleCondition::Mod(value.to_string()),
+            "name" => RuleCondition::Name(value.to_string()),
+            "type" => RuleCondition::Type(value.to_uppercase()),
+            _ => return Err(invalid("condition must be size, mod, name or type")),
+        };
+        Ok(Self { action, condition })
+    }
+}
+
+impl fmt::Display for SelectionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match self.action {
+            RuleAction::Always => "always",
+            RuleAction::Never => "never",
+            RuleAction::Only => "only",
+        };
+        match &self.condition {
+            RuleCondition::SmallerThan(size) => write!(f, "{action} size < {}", exact_size(*size)),
+            RuleCondition::LargerThan(size) => write!(f, "{action} size > {}", exact_size(*size)),
+            RuleCondition::Mod(pattern) => write!(f, "{action} mod {pattern}"),
+            RuleCondition::Name(pattern) => write!(f, "{action} name {pattern}"),
+            RuleCondition::Type(archive_type) => write!(f, "{action} type {archive_type}"),
+        }
+    }
+}
+
+impl TryFrom<String> for SelectionRule {
+    type Error = crate::error::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl From<SelectionRule> for String {
+    fn from(rule: SelectionRule) -> Self {
+        rule.to_string()
+    }
+}
+
+/// Parse rules separated by semicolons or line breaks
+pub fn parse_rules(text: &str) -> Result<Vec<SelectionRule>> {
+    text.split([';', '\n'])
+        .map(str::trim)
+        .filter(|rule| !rule.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Write rules in the form [`parse_rules`] reads
+pub fn format_rules(rules: &[SelectionRule]) -> String {
+    rules
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Whether the rules select an archive
+pub fn is_selected(rules: &[SelectionRule], entry: &FileEntry) -> bool {
+    if entry.is_bad() || entry.is_excluded() {
+        return false;
+    }
+    let has_always = rules.iter().any(|r| r.action == RuleAction::Always);
+    let picked = !has_always
+        || rules
+            .iter()
+            .any(|r| r.action == RuleAction::Always && r.matches(entry));
+    picked
+        && rules.iter().all(|rule| match rule.action {
+            RuleAction::Always => true,
+            RuleAction::Never => !rule.matches(entry),
+            RuleAction::Only => rule.matches(entry),
+        })
+}
+
+/// Get the archives the rules select
+pub fn selected_paths(rules: &[SelectionRule], entries: &[FileEntry]) -> Vec<PathBuf> {
+    entries
+        .iter()
+        .filter(|entry| is_selected(rules, entry))
+        .map(|entry| entry.full_path.clone())
+        .collect()
+}
+
+/// List the archives each rule's condition matches, without selecting them
+pub fn preview_rules(rules: &[SelectionRule], entries: &[FileEntry]) -> Vec<RulePreview> {
+    rules
+        .iter()
+        .map(|rule| RulePreview {
+            rule: rule.to_string(),
+            matched: entries
+                .iter()
+                .filter(|entry| rule.matches(entry))
+                .map(|entry| entry.file_name.clone())
+                .collect(),
+        })
+        .collect()
+}
+
+/// Write a size in the largest unit that keeps it exact, independent of the locale
+fn exact_size(size: SizeSpec) -> String {
+    const UNITS: &[(u64, &str)] = &[
+        (1 << 40, "TiB"),
+        (1_000_000_000_000, "TB"),
+        (1 << 30, "GiB"),
+        (1_000_000_000, "GB"),
+        (1 << 20, "MiB"),
+        (1_000_000, "MB"),
+        (1 << 10, "KiB"),
+        (1_000, "KB"),
+    ];
+    let bytes = size.bytes();
+    UNITS
+        .iter()
+        .find(|(unit, _)| bytes >= *unit && bytes.is_multiple_of(*unit))
+        .map_or_else(
+            || format!("{bytes} B"),
+            |(unit, name)| format!("{} {name}", bytes / unit),
+        )
+}
+
+/// Match text against a case-insensitive pattern with `*` and `?` wildcards
+///
+/// Characters are compared one by one, so `?` stands for exactly one
+/// character even where lowercasing a whole name would change its length
+/// (like `İ`).
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position after the last '*' and the text position it was tried at
+    let mut backtrack = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || chars_match(pattern[p], text[t])) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            p += 1;
+            backtrack = Some((p, t));
+        } else if let Some((star_p, star_t)) = backtrack {
+            p = star_p;
+            t = star_t + 1;
+            backtrack = Some((star_p, t));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Whether two characters are equal, ignoring case
+fn chars_match(a: char, b: char) -> bool {
+    a == b || a.to_lowercase().eq(b.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ArchiveHealth;
+
+    fn entry(name: &str, mod_name: &str, size: u64, archive_type: &str) -> FileEntry {
+        let mut entry = FileEntry::new(
+            name.to_string(),
+            size,
+            10,
+            mod_name.to_string(),
+            PathBuf::from(format!("/mods/{mod_name}/{name}")),
+        );
+        entry.archive_type = archive_type.to_string();
+        entry
+    }
+
+    fn sample() -> Vec<FileEntry> {
+        vec![
+            entry("Small - Main.ba2", "Small Mod", 1_000_000, "GNRL"),
+            entry("Big - Main.ba2", "Big Mod", 50_000_000, "GNRL"),
+            entry("Small - Textures.ba2", "Small Mod", 2_000_000, "DX10"),
+            entry("Armor - Main.ba2", "Armor HD Textures", 3_000_000, "GNRL"),
+        ]
+    }
+
+    fn names(paths: &[PathBuf]) -> Vec<String> {
+        paths
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_and_format_rules() {
+        let rules =
+            parse_rules("always size < 5 MB;never mod \"*HD Textures*\"\n ONLY type gnrl ;")
+                .unwrap();
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[0].action, RuleAction::Always);
+        assert_eq!(
+            rules[0].condition,
+            RuleCondition::SmallerThan(SizeSpec::from_bytes(5_000_000))
+        );
+        assert_eq!(
+            rules[1].condition,
+            RuleCondition::Mod("*HD Textures*".to_string())
+        );
+        assert_eq!(rules[2].condition, RuleCondition::Type("GNRL".to_string()));
+        assert_eq!(parse_rules(&format_rules(&rules)).unwrap(), rules);
+        assert_eq!(rules[0].to_string(), "always size < 5 MB");
+        assert_eq!(
+            parse_rules("never  size >1.5 GiB").unwrap()[0].to_string(),
+            "never size > 1536 MiB"
+        );
+
+        assert!(parse_rules("sometimes size < 5 MB").is_err());
+        assert!(parse_rules("always size 5 MB").is_err());
+        assert!(parse_rules("never mod").is_err());
+    }
+
+    #[test]
+    fn test_select() {
+        let entries = sample();
+        let rules =
+            parse_rules("always size < 5 MB; never mod *HD Textures*; only type GNRL").unwrap();
+        assert_eq!(
+            names(&selected_paths(&rules, &entries)),
+            ["Small - Main.ba2"]
+        );
+
+        // Without an "always" rule every archive is a candidate
+        let rules = parse_rules("never size > 10 MB").unwrap();
+        assert_eq!(selected_paths(&rules, &entries).len(), 3);
+
+        let mut entries = entries;
+        entries[0].health = ArchiveHealth::InvalidFormat(String::new());
+        let rules = parse_rules("always name small*").unwrap();
+        assert_eq!(
+            names(&selected_paths(&rules, &entries)),
+            ["Small - Textures.ba2"]
+        );
+    }
+
+    #[test]
+    fn test_preview() {
+        let rules = parse_rules("never mod *hd textures*; always name *- Main.ba2").unwrap();
+        let preview = preview_rules(&rules, &sample());
+        assert_eq!(preview[0].rule, "never mod *hd textures*");
+        assert_eq!(preview[0].matched, ["Armor - Main.ba2"]);
+        assert_eq!(preview[1].matched.len(), 3);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*HD Textures*", "Armor HD Textures"));
+        assert!(glob_match("a?c*", "ABCdef"));
+        assert!(glob_match("*a*b", "xaybzab"));
+        assert!(glob_match("exact", "Exact"));
+        assert!(!glob_match("exact", "Exactly"));
+        assert!(!glob_match("*.ba2", "file.bsa"));
+    }
+}
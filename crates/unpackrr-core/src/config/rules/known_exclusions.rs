@@ -0,0 +1,213 @@
+//! Archives that must never be unpacked
+//!
+//! Some archives only work packed, e.g. copies of the game's own archives
+//! or Creation Club content the game installs and updates itself. A curated
+//! list of them ships with the app, and users can add their own in
+//! `known_exclusions.json` next to `config.json`, in the same format:
+//!
+//! ```json
+//! { "exclusions": [
+//!     { "names": ["Framework - Main.ba2"], "reason": "Reads its assets from the archive" },
+//!     { "sha256": "9f86d0…", "size": 1048576, "reason": "Broken when unpacked" }
+//! ] }
+//! ```
+//!
+//! Entries match archive file names (case-insensitive, with the `*` and `?`
+//! wildcards) or, with `sha256` and `size`, one exact file. Only archives of
+//! a listed size are hashed, so hash entries don't slow down scans. Scans
+//! flag matching archives with the entry's reason, and extraction skips them
+//! unless the user overrides the exclusion for an archive.
+
+use super::glob_match;
+use crate::config::AppConfig;
+use crate::error::{ConfigError, Result};
+use crate::operations::manifest::hash_file;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The list shipped with the app
+const BUILTIN: &str = include_str!("../../../data/known_exclusions.json");
+
+/// Name of the user's list in the configuration folder
+pub const USER_FILE: &str = "known_exclusions.json";
+
+/// An archive, or a group of archives, that must stay packed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownExclusion {
+    /// File name patterns the entry matches
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub names: Vec<String>,
+
+    /// SHA-256 of the archive (hex), matched together with `size`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+
+    /// Size of the archive in bytes, required with `sha256`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+
+    /// Why the archive must stay packed, shown to the user
+    pub reason: String,
+}
+
+impl KnownExclusion {
+    fn validate(&self) -> Result<()> {
+        let problem = if self.reason.trim().is_empty() {
+            "an entry has no reason"
+        } else if self.names.is_empty() && self.sha256.is_none() {
+            "an entry has neither names nor a sha256"
+        } else if self.sha256.is_some() && self.size.is_none() {
+            "an entry with a sha256 has no size"
+        } else {
+            return Ok(());
+        };
+        Err(ConfigError::ValidationFailed(format!("Known exclusions: {problem}")).into())
+    }
+
+    fn matches_name(&self, file_name: &str) -> bool {
+        self.names
+            .iter()
+            .any(|pattern| glob_match(pattern, file_name))
+    }
+}
+
+#[derive(Deserialize)]
+struct ExclusionFile {
+    exclusions: Vec<KnownExclusion>,
+}
+
+/// The shipped list of archives that must stay packed, and the user's
+#[derive(Debug, Clone, Default)]
+pub struct KnownExclusions {
+    entries: Vec<KnownExclusion>,
+}
+
+impl KnownExclusions {
+    /// Parse a list in the format of `known_exclusions.json`
+    pub fn from_json(json: &str) -> Result<Self> {
+        let file: ExclusionFile = serde_json::from_str(json)
+            .map_err(|e| ConfigError::InvalidFormat(format!("Known exclusions: {e}")))?;
+        for entry in &file.exclusions {
+            entry.validate()?;
+        }
+        Ok(Self {
+            entries: file.exclusions,
+        })
+    }
+
+    /// The list shipped with the app
+    pub fn builtin() -> Self {
+        Self::from_json(BUILTIN).unwrap_or_else(|e| {
+            tracing::error!("The shipped exclusion list is invalid: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Path of the user's list
+    pub fn user_file_path() -> Result<PathBuf> {
+        Ok(AppConfig::config_dir()?.join(USER_FILE))
+    }
+
+    /// The shipped list and the user's, if there is one
+    ///
+    /// A user list that can't be read is logged and left out.
+    pub fn load() -> Self {
+        let mut exclusions = Self::builtin();
+        if let Ok(path) = Self::user_file_path() {
+            match Self::read_user_file(&path) {
+                Ok(Some(user)) => exclusions.entries.extend(user.entries),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Ignoring {}: {}", path.display(), e),
+            }
+        }
+        exclusions
+    }
+
+    fn read_user_file(path: &Path) -> Result<Option<Self>> {
+        match fs::read_to_string(path) {
+            Ok(json) => Self::from_json(&json).map(Some),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// All entries, the shipped ones first
+    pub fn entries(&self) -> &[KnownExclusion] {
+        &self.entries
+    }
+
+    /// The entry an archive matches, by name or, if its size is listed, by hash
+    pub fn check(&self, path: &Path, file_name: &str, file_size: u64) -> Option<&KnownExclusion> {
+        if let Some(entry) = self.entries.iter().find(|e| e.matches_name(file_name)) {
+            return Some(entry);
+        }
+        let mut hashed = self
+            .entries
+            .iter()
+            .filter(|e| e.sha256.is_some() && e.size == Some(file_size))
+            .peekable();
+        hashed.peek()?;
+        let digest = match hash_file(path) {
+            Ok((_, digest)) => digest,
+            Err(e) => {
+                tracing::debug!("Could not hash {}: {}", path.display(), e);
+                return None;
+            }
+        };
+        hashed.find(|e| {
+            e.sha256
+                .as_deref()
+                .is_some_and(|sha256| sha256.eq_ignore_ascii_case(&digest))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_builtin_list() {
+        let exclusions = KnownExclusions::builtin();
+        assert!(!exclusions.entries().is_empty());
+        let path = Path::new("Mod/Fallout4 - Textures1.ba2");
+        assert!(
+            exclusions
+                .check(path, "Fallout4 - Textures1.ba2", 10)
+                .is_some()
+        );
+        assert!(
+            exclusions
+                .check(path, "ccBGSFO4044-HellfirePowerArmor - Main.ba2", 10)
+                .is_some()
+        );
+        assert!(exclusions.check(path, "Mod - Main.ba2", 10).is_none());
+    }
+
+    #[test]
+    fn test_match_by_hash() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("Renamed.ba2");
+        fs::write(&path, b"test").unwrap();
+        let exclusions = KnownExclusions::from_json(
+            r#"{ "exclusions": [{
+                "sha256": "9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08",
+                "size": 4,
+                "reason": "Needs to stay packed"
+            }] }"#,
+        )
+        .unwrap();
+        let entry = exclusions.check(&path, "Renamed.ba2", 4).unwrap();
+        assert_eq!(entry.reason, "Needs to stay packed");
+        // Other sizes are not hashed
+        assert!(exclusions.check(&path, "Renamed.ba2", 5).is_none());
+
+        assert!(KnownExclusions::from_json(r#"{ "exclusions": [{ "reason": "x" }] }"#).is_err());
+        assert!(
+            KnownExclusions::from_json(r#"{ "exclusions": [{ "sha256": "ab", "reason": "x" }] }"#)
+                .is_err()
+        );
+    }
+}
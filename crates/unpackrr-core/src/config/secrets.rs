@@ -0,0 +1,131 @@
+//! API keys kept in the credential store of the OS
+//!
+//! Keys such as the Nexus Mods API key are secrets and don't belong in
+//! `config.json`, which users attach when asking for help. They are kept in
+//! the Windows Credential Manager, the macOS Keychain or the Secret Service
+//! (GNOME Keyring, KDE Wallet) on Linux, behind [`SecretStore`] so tests can use
+//! another store.
+//!
+//! Access to the store blocks, and the Secret Service must not be reached
+//! from inside an async task: call it from a worker thread.
+
+use crate::error::{ConfigError, Result};
+
+/// Service name the keys are stored under
+const SERVICE: &str = "unpackrr";
+
+/// A secret the app stores
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Secret {
+    /// Personal API key for Nexus Mods
+    NexusApiKey,
+}
+
+impl Secret {
+    /// Account name of the secret in the store
+    pub const fn account(self) -> &'static str {
+        match self {
+            Self::NexusApiKey => "nexus-api-key",
+        }
+    }
+
+    /// Name of the secret for messages
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::NexusApiKey => "Nexus Mods API key",
+        }
+    }
+}
+
+/// Storage of secrets outside the settings file
+pub trait SecretStore {
+    /// The stored value, if there is one
+    fn get(&self, secret: Secret) -> Result<Option<String>>;
+
+    /// Store a value, replacing the previous one
+    fn set(&self, secret: Secret, value: &str) -> Result<()>;
+
+    /// Remove the value; removing a missing one is not an error
+    fn delete(&self, secret: Secret) -> Result<()>;
+}
+
+/// The credential store of the OS
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeyringStore;
+
+impl KeyringStore {
+    /// Name of the store shown to users
+    pub const fn name() -> &'static str {
+        if cfg!(windows) {
+            "Windows Credential Manager"
+        } else if cfg!(target_os = "macos") {
+            "macOS Keychain"
+        } else {
+            "system keyring"
+        }
+    }
+
+    fn entry(secret: Secret) -> Result<keyring::Entry> {
+        keyring::Entry::new(SERVICE, secret.account()).map_err(|e| store_error(&e))
+    }
+}
+
+impl SecretStore for KeyringStore {
+    fn get(&self, secret: Secret) -> Result<Option<String>> {
+        match Self::entry(secret)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(store_error(&e)),
+        }
+    }
+
+    fn set(&self, secret: Secret, value: &str) -> Result<()> {
+        Self::entry(secret)?
+            .set_password(value)
+            .map_err(|e| store_error(&e))?;
+        tracing::info!("Stored the {} in the {}", secret.label(), Self::name());
+        Ok(())
+    }
+
+    fn delete(&self, secret: Secret) -> Result<()> {
+        match Self::entry(secret)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {
+                tracing::info!("Removed the {} from the {}", secret.label(), Self::name());
+                Ok(())
+            }
+            Err(e) => Err(store_error(&e)),
+        }
+    }
+}
+
+fn store_error(e: &keyring::Error) -> crate::error::Error {
+    ConfigError::SecretStore(e.to_string()).into()
+}
+
+/// Check an API key as entered and trim it
+pub fn parse_api_key(key: &str) -> Result<&str> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(ConfigError::ValidationFailed("The API key is empty".to_string()).into());
+    }
+    if key.chars().any(char::is_whitespace) {
+        return Err(ConfigError::ValidationFailed(
+            "The API key must not contain spaces".to_string(),
+        )
+        .into());
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_parse_api_key() {
+        assert_eq!(parse_api_key("  abc123==\n").unwrap(), "abc123==");
+        assert!(matches!(parse_api_key(" "), Err(Error::Config(_))));
+        assert!(parse_api_key("abc 123").is_err());
+    }
+}
@@ -0,0 +1,266 @@
+//! External tools archives can be opened with
+//!
+//! Each tool is one entry of the form `<name> = <program> <arguments>`, e.g.
+//! `BAE = "C:\Tools\bae.exe" "%f"`; entries are separated by semicolons or
+//! line breaks. Program paths with spaces are quoted. `%f` in the arguments
+//! is replaced by the path of the archive; without it, the path is passed as
+//! the last argument.
+//!
+//! The program Windows opens `.ba2` files with is added to the configured
+//! tools by [`open_with_tools`], unless it is already one of them.
+
+use crate::error::{ConfigError, Result};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+
+/// Placeholder for the archive path in the arguments of a tool
+pub const FILE_PLACEHOLDER: &str = "%f";
+
+/// Name of the tool added for the registered `.ba2` handler
+pub const DEFAULT_HANDLER_NAME: &str = "Default Program";
+
+/// A program archives can be opened with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExternalTool {
+    /// Name shown in the row menu
+    pub name: String,
+
+    /// Path of the program
+    pub path: String,
+
+    /// Argument template, `%f` standing for the archive path
+    #[serde(default)]
+    pub arguments: String,
+}
+
+impl ExternalTool {
+    /// A tool opening the archive as its only argument
+    pub fn new(name: impl Into<String>, path: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+            arguments: FILE_PLACEHOLDER.to_string(),
+        }
+    }
+
+    /// Arguments opening `file`, with the placeholder replaced
+    pub fn args(&self, file: &Path) -> Vec<OsString> {
+        let mut args: Vec<OsString> = split_words(&self.arguments)
+            .into_iter()
+            .map(|word| {
+                if word == FILE_PLACEHOLDER {
+                    file.as_os_str().to_os_string()
+                } else {
+                    word.replace(FILE_PLACEHOLDER, &file.to_string_lossy())
+                        .into()
+                }
+            })
+            .collect();
+        if !self.arguments.contains(FILE_PLACEHOLDER) {
+            args.push(file.as_os_str().to_os_string());
+        }
+        args
+    }
+
+    /// Command opening `file` with the tool
+    pub fn command(&self, file: &Path) -> Command {
+        let mut command = Command::new(&self.path);
+        command.args(self.args(file));
+        command
+    }
+}
+
+impl FromStr for ExternalTool {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| -> crate::error::Error {
+            ConfigError::ValidationFailed(format!("Invalid tool '{}': {reason}", s.trim())).into()
+        };
+
+        let (name, command_line) = s
+            .split_once('=')
+            .ok_or_else(|| invalid("must be <name> = <program> <arguments>"))?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(invalid("missing name"));
+        }
+
+        let command_line = command_line.trim();
+        let (path, arguments) = match command_line.strip_prefix('"') {
+            Some(quoted) => quoted
+                .split_once('"')
+                .ok_or_else(|| invalid("missing closing quote"))?,
+            None => command_line
+                .split_once(char::is_whitespace)
+                .unwrap_or((command_line, "")),
+        };
+        if path.trim().is_empty() {
+            return Err(invalid("missing program"));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            path: path.trim().to_string(),
+            arguments: arguments.trim().to_string(),
+        })
+    }
+}
+
+impl fmt::Display for ExternalTool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.contains(char::is_whitespace) {
+            write!(f, "{} = \"{}\"", self.name, self.path)?;
+        } else {
+            write!(f, "{} = {}", self.name, self.path)?;
+        }
+        if self.arguments.is_empty() {
+            Ok(())
+        } else {
+            write!(f, " {}", self.arguments)
+        }
+    }
+}
+
+/// Split text on whitespace, keeping double-quoted parts together
+fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    let mut started = false;
+    for c in text.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                started = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if started {
+                    words.push(std::mem::take(&mut word));
+                    started = false;
+                }
+            }
+            c => {
+                word.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        words.push(word);
+    }
+    words
+}
+
+/// Parse tools separated by semicolons or line breaks (outside quotes)
+pub fn parse_tools(text: &str) -> Result<Vec<ExternalTool>> {
+    let mut entries = Vec::new();
+    let mut entry = String::new();
+    let mut quoted = false;
+    for c in text.chars() {
+        match c {
+            ';' | '\n' if !quoted => entries.push(std::mem::take(&mut entry)),
+            c => {
+                quoted ^= c == '"';
+                entry.push(c);
+            }
+        }
+    }
+    entries.push(entry);
+    entries
+        .iter()
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Write tools in the form [`parse_tools`] reads
+pub fn format_tools(tools: &[ExternalTool]) -> String {
+    tools
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// The tools of the row menu: the configured ones, then the `.ba2` handler
+///
+/// The handler is left out if a configured tool already runs the same program.
+pub fn open_with_tools(configured: &[ExternalTool], handler: Option<PathBuf>) -> Vec<ExternalTool> {
+    let mut tools = configured.to_vec();
+    if let Some(handler) = handler {
+        let handler = handler.to_string_lossy().into_owned();
+        if !tools
+            .iter()
+            .any(|tool| tool.path.eq_ignore_ascii_case(&handler))
+        {
+            tools.push(ExternalTool::new(DEFAULT_HANDLER_NAME, handler));
+        }
+    }
+    tools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format_tools() {
+        let text = r#"BAE = "C:\Program Files\BAE\bae.exe" "%f"; Viewer = C:\Tools\view.exe --open=%f
+            Plain = C:\Tools\plain.exe"#;
+        let tools = parse_tools(text).unwrap();
+        assert_eq!(tools.len(), 3);
+        assert_eq!(tools[0].name, "BAE");
+        assert_eq!(tools[0].path, r"C:\Program Files\BAE\bae.exe");
+        assert_eq!(tools[0].arguments, "\"%f\"");
+        assert_eq!(tools[1].arguments, "--open=%f");
+        assert_eq!(tools[2].arguments, "");
+        assert_eq!(parse_tools(&format_tools(&tools)).unwrap(), tools);
+
+        assert!(parse_tools("no program here").is_err());
+        assert!(parse_tools("= C:\\tool.exe").is_err());
+        assert!(parse_tools("Broken = \"C:\\no end").is_err());
+    }
+
+    #[test]
+    fn test_placeholder_substitution() {
+        let file = Path::new("/mods/My Mod/Mod - Main.ba2");
+        let tool = |arguments: &str| ExternalTool {
+            arguments: arguments.to_string(),
+            ..ExternalTool::new("Tool", "tool")
+        };
+
+        assert_eq!(tool("\"%f\"").args(file), [file.as_os_str()]);
+        assert_eq!(
+            tool("-x --file=%f").args(file),
+            [
+                OsString::from("-x"),
+                OsString::from("--file=/mods/My Mod/Mod - Main.ba2")
+            ]
+        );
+        // Without the placeholder the archive comes last
+        assert_eq!(
+            tool("--read-only").args(file),
+            [
+                OsString::from("--read-only"),
+                file.as_os_str().to_os_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_default_handler_added_once() {
+        let configured = [ExternalTool::new("BAE", r"C:\Tools\bae.exe")];
+        let tools = open_with_tools(&configured, Some(PathBuf::from(r"C:\Tools\viewer.exe")));
+        assert_eq!(tools.len(), 2);
+        assert_eq!(tools[1].name, DEFAULT_HANDLER_NAME);
+
+        let tools = open_with_tools(&configured, Some(PathBuf::from(r"c:\tools\BAE.exe")));
+        assert_eq!(tools, configured);
+        assert!(open_with_tools(&[], None).is_empty());
+    }
+}
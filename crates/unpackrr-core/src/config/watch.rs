@@ -0,0 +1,182 @@
+//! Reloading the configuration when it is edited outside the app
+//!
+//! Power users edit `config.json` by hand. A [`ConfigWatcher`] polls the
+//! file's modification time and size, and when they change reads the file,
+//! validates it and compares it with the settings in the app:
+//!
+//! - A file that matches the app's settings (usually the app's own save) is
+//!   taken as is.
+//! - If the app's settings still match what was on disk before, the new file
+//!   can be applied right away.
+//! - Otherwise the app has settings that were never saved, and the user has
+//!   to choose which side to keep.
+
+use super::AppConfig;
+use crate::error::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// How often the frontends poll the configuration file
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A change of the configuration file found by [`ConfigWatcher::poll`]
+#[derive(Debug, Clone)]
+pub enum ConfigChange {
+    /// The file changed and the app has no unsaved settings: apply these
+    Reloaded(AppConfig),
+
+    /// The file changed while the app has settings that were never saved
+    Conflict(AppConfig),
+
+    /// The file changed but cannot be used; the app keeps its settings
+    Invalid(String),
+}
+
+/// Modification time and size of the file, to notice changes cheaply
+type Stamp = (Option<SystemTime>, u64);
+
+/// Polls the configuration file for changes made outside the app
+#[derive(Debug, Clone)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    stamp: Option<Stamp>,
+    /// Settings last read from or written to the file
+    on_disk: serde_json::Value,
+}
+
+impl ConfigWatcher {
+    /// Watch the configuration file, whose content is `current`
+    pub fn new(current: &AppConfig) -> Result<Self> {
+        Ok(Self::with_path(AppConfig::config_file_path()?, current))
+    }
+
+    /// Watch the configuration file at `path`, whose content is `current`
+    pub fn with_path(path: impl Into<PathBuf>, current: &AppConfig) -> Self {
+        let path = path.into();
+        Self {
+            stamp: stamp(&path),
+            path,
+            on_disk: snapshot(current),
+        }
+    }
+
+    /// Path of the watched file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check the file for changes, given the settings the app has now
+    ///
+    /// Returns `None` if the file did not change or matches `current`.
+    pub fn poll(&mut self, current: &AppConfig) -> Option<ConfigChange> {
+        let stamp = stamp(&self.path);
+        if stamp == self.stamp {
+            return None;
+        }
+        self.stamp = stamp;
+
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            // Deleted, or replaced by an editor saving atomically; the app saves it again
+            Err(e) => {
+                tracing::debug!("Could not read {}: {}", self.path.display(), e);
+                return None;
+            }
+        };
+        let config = match AppConfig::from_json(&content) {
+            Ok(config) => config,
+            Err(e) => return Some(ConfigChange::Invalid(e.user_message())),
+        };
+
+        let disk = snapshot(&config);
+        let app = snapshot(current);
+        let unsaved = app != self.on_disk;
+        let changed = disk != app;
+        self.on_disk = disk;
+        if !changed {
+            return None;
+        }
+        tracing::info!("Configuration changed on disk: {}", self.path.display());
+        Some(if unsaved {
+            ConfigChange::Conflict(config)
+        } else {
+            ConfigChange::Reloaded(config)
+        })
+    }
+
+    /// Note that the app wrote `config` to the file, e.g. after keeping its
+    /// own settings in a conflict
+    pub fn saved(&mut self, config: &AppConfig) {
+        self.stamp = stamp(&self.path);
+        self.on_disk = snapshot(config);
+    }
+}
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok(), metadata.len()))
+}
+
+/// Settings in a comparable form (the configuration has no `PartialEq`)
+fn snapshot(config: &AppConfig) -> serde_json::Value {
+    serde_json::to_value(config).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, config: &AppConfig) {
+        fs::write(path, serde_json::to_string_pretty(config).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_reload_and_conflict() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let mut app = AppConfig::default();
+        write(&path, &app);
+        let mut watcher = ConfigWatcher::with_path(&path, &app);
+        assert!(watcher.poll(&app).is_none());
+
+        // Edited by hand while the app's settings are saved
+        let mut edited = app.clone();
+        edited.advanced.performance.max_jobs = 3;
+        edited.appearance.language = "de".to_string();
+        write(&path, &edited);
+        let Some(ConfigChange::Reloaded(reloaded)) = watcher.poll(&app) else {
+            panic!("expected a reload");
+        };
+        assert_eq!(reloaded.advanced.performance.max_jobs, 3);
+        app = reloaded;
+
+        // The app's own save is not reported
+        app.advanced.performance.max_jobs = 4;
+        write(&path, &app);
+        assert!(watcher.poll(&app).is_none());
+
+        // Edited by hand while the app has an unsaved setting
+        app.advanced.diagnostics.collect_metrics = !app.advanced.diagnostics.collect_metrics;
+        edited.advanced.performance.max_jobs = 5;
+        write(&path, &edited);
+        assert!(matches!(
+            watcher.poll(&app),
+            Some(ConfigChange::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let app = AppConfig::default();
+        write(&path, &app);
+        let mut watcher = ConfigWatcher::with_path(&path, &app);
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(matches!(watcher.poll(&app), Some(ConfigChange::Invalid(_))));
+        // Reported once, not at every poll
+        assert!(watcher.poll(&app).is_none());
+    }
+}
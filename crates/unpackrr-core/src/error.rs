@@ -0,0 +1,749 @@
+//! Error types for Unpackrr-rs
+//!
+//! This module defines comprehensive error types for all operations in the application.
+//! We use `thiserror` for library-level errors and `anyhow` for application-level error handling.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Result type alias using our custom Error type
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Main error type for Unpackrr-rs operations
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Configuration-related errors
+    #[error("Configuration error: {0}")]
+    Config(#[from] ConfigError),
+
+    /// BA2 file format or parsing errors
+    #[error("BA2 error: {0}")]
+    BA2(#[from] BA2Error),
+
+    /// File system I/O errors
+    #[error("I/O error: {0}")]
+    IO(#[from] std::io::Error),
+
+    /// Input validation errors
+    #[error("Validation error: {0}")]
+    Validation(#[from] ValidationError),
+
+    /// A file move that stopped after some of its steps
+    #[error("Moving {} to {} stopped partway: {outcome}", from.display(), to.display())]
+    PartialMove {
+        /// Original location
+        from: PathBuf,
+        /// Target of the move
+        to: PathBuf,
+        /// Where the file is now
+        outcome: BackupOutcome,
+    },
+
+    /// Operation was cancelled by the user
+    #[error("Operation was cancelled")]
+    Cancelled,
+
+    /// Generic error with context
+    #[error("{0}")]
+    Other(String),
+}
+
+/// Configuration-related errors
+#[derive(Error, Debug)]
+pub enum ConfigError {
+    /// Failed to load configuration file
+    #[error("Failed to load configuration from {path}: {source}")]
+    LoadFailed {
+        /// Path to the configuration file
+        path: PathBuf,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// Failed to save configuration file
+    #[error("Failed to save configuration to {path}: {source}")]
+    SaveFailed {
+        /// Path to the configuration file
+        path: PathBuf,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// Invalid configuration format
+    #[error("Invalid configuration format: {0}")]
+    InvalidFormat(String),
+
+    /// Configuration validation failed
+    #[error("Configuration validation failed: {0}")]
+    ValidationFailed(String),
+
+    /// Invalid regex pattern in configuration
+    #[error("Invalid regex pattern '{pattern}': {source}")]
+    InvalidRegex {
+        /// The invalid regex pattern
+        pattern: String,
+        /// Underlying regex compilation error
+        source: regex::Error,
+    },
+
+    /// Invalid path in configuration
+    #[error("Invalid path in configuration: {0}")]
+    InvalidPath(PathBuf),
+
+    /// The credential store holding API keys could not be used
+    #[error("Credential store error: {0}")]
+    SecretStore(String),
+}
+
+/// BA2 file format and parsing errors
+#[derive(Error, Debug)]
+pub enum BA2Error {
+    /// Invalid BA2 magic number
+    #[error("Invalid BA2 magic number in file {path}")]
+    InvalidMagic {
+        /// Path to the BA2 file with invalid magic number
+        path: PathBuf,
+    },
+
+    /// Unsupported BA2 version
+    #[error("Unsupported BA2 version {version} in file {path}")]
+    UnsupportedVersion {
+        /// The unsupported version number
+        version: u32,
+        /// Path to the BA2 file
+        path: PathBuf,
+    },
+
+    /// Corrupted BA2 file
+    #[error("Corrupted BA2 file: {path} - {reason}")]
+    Corrupted {
+        /// Path to the corrupted BA2 file
+        path: PathBuf,
+        /// Reason for corruption
+        reason: String,
+    },
+
+    /// File data uses a compression codec that cannot be unpacked
+    #[error("{name} in {path} uses unsupported compression: {codec}")]
+    UnsupportedCompression {
+        /// Path to the BA2 file
+        path: PathBuf,
+        /// Name of the file inside the archive
+        name: String,
+        /// Description of the codec
+        codec: String,
+    },
+
+    /// Failed to extract BA2 file
+    #[error("Failed to extract {path}: {reason}")]
+    ExtractionFailed {
+        /// Path to the BA2 file
+        path: PathBuf,
+        /// Reason for extraction failure
+        reason: String,
+    },
+
+    /// BSArch.exe not found
+    #[error("BSArch.exe not found at expected location: {path}")]
+    BSArchNotFound {
+        /// Expected path to BSArch.exe
+        path: PathBuf,
+    },
+
+    /// BSArch.exe execution failed
+    #[error("BSArch.exe execution failed: {0}")]
+    BSArchExecFailed(String),
+
+    /// Output files were locked by another process (usually an antivirus scan)
+    #[error("Files of {path} are locked by another process: {reason}")]
+    FileLocked {
+        /// Path to the BA2 file
+        path: PathBuf,
+        /// Message reported by the extraction tool
+        reason: String,
+    },
+
+    /// Configured extraction tool is not a BA2 unpacker
+    #[error("{path} is not a BA2 unpacker: {reason}")]
+    InvalidTool {
+        /// Path to the configured tool
+        path: PathBuf,
+        /// Why the tool was rejected
+        reason: String,
+    },
+
+    /// Extracted paths exceed the Windows path length limit
+    #[error("Extracting {path} produces paths of {longest} characters, over the {limit} character limit")]
+    PathTooLong {
+        /// Path to the BA2 file
+        path: PathBuf,
+        /// Length of the longest output path
+        longest: usize,
+        /// Path length limit that was exceeded
+        limit: usize,
+    },
+}
+
+/// Input validation errors
+#[derive(Error, Debug)]
+pub enum ValidationError {
+    /// Empty or invalid input
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// File or directory does not exist
+    #[error("Path does not exist: {0}")]
+    PathNotFound(PathBuf),
+
+    /// Path is not a directory
+    #[error("Path is not a directory: {0}")]
+    NotADirectory(PathBuf),
+
+    /// Path is not a file
+    #[error("Path is not a file: {0}")]
+    NotAFile(PathBuf),
+
+    /// Invalid size format
+    #[error("Invalid size format: {0}")]
+    InvalidSize(String),
+
+    /// Invalid failure threshold for aborting a batch
+    #[error("Invalid failure threshold: {0}")]
+    InvalidAbortThreshold(String),
+
+    /// Mutating action attempted while analysis-only mode is enabled
+    #[error("{0} is not allowed in analysis-only mode")]
+    ReadOnlyMode(String),
+}
+
+/// How far a move to another volume got before it failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackupOutcome {
+    /// The copy is complete, but the original could not be removed: the
+    /// file is in both places
+    CopiedNotRemoved {
+        /// Why the original could not be removed
+        reason: String,
+    },
+    /// Copying failed and the incomplete copy could not be removed: the
+    /// original is unchanged
+    PartialCopyLeft {
+        /// Why copying failed
+        reason: String,
+    },
+}
+
+impl BackupOutcome {
+    /// Whether the copy at the target is complete
+    pub const fn is_copied(&self) -> bool {
+        matches!(self, Self::CopiedNotRemoved { .. })
+    }
+
+    /// Why the step that failed did
+    pub fn reason(&self) -> &str {
+        match self {
+            Self::CopiedNotRemoved { reason } | Self::PartialCopyLeft { reason } => reason,
+        }
+    }
+
+    /// User-friendly message of a move from `from` to `to` that stopped here
+    fn user_message(&self, from: &Path, to: &Path) -> String {
+        match self {
+            Self::CopiedNotRemoved { reason } => format!(
+                "'{}' was copied to '{}', but the original could not be removed ({reason}); the file is in both places",
+                from.display(),
+                to.display()
+            ),
+            Self::PartialCopyLeft { reason } => format!(
+                "Copying '{}' failed ({reason}) and left an incomplete copy at '{}'; the original is unchanged",
+                from.display(),
+                to.display()
+            ),
+        }
+    }
+}
+
+impl fmt::Display for BackupOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CopiedNotRemoved { reason } => {
+                write!(
+                    f,
+                    "copied, but the original could not be removed ({reason})"
+                )
+            }
+            Self::PartialCopyLeft { reason } => {
+                write!(f, "copying failed and left an incomplete copy ({reason})")
+            }
+        }
+    }
+}
+
+impl Error {
+    /// Create a generic error with a message
+    #[must_use]
+    pub fn other(msg: impl Into<String>) -> Self {
+        Self::Other(msg.into())
+    }
+
+    /// Check if this error means the operation was cancelled
+    #[must_use]
+    pub const fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled)
+    }
+
+    /// Check if this error is a BA2 corruption error
+    #[must_use]
+    pub const fn is_ba2_corrupted(&self) -> bool {
+        matches!(self, Self::BA2(BA2Error::Corrupted { .. }))
+    }
+
+    /// Check if this error might be transient (retryable)
+    ///
+    /// Transient errors include temporary I/O issues, network problems,
+    /// or file access conflicts that might resolve with a retry.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::IO(e) => {
+                use std::io::ErrorKind;
+                matches!(
+                    e.kind(),
+                    ErrorKind::Interrupted
+                        | ErrorKind::WouldBlock
+                        | ErrorKind::TimedOut
+                        | ErrorKind::PermissionDenied // Might be temporary file lock
+                        | ErrorKind::AddrInUse
+                )
+            }
+            Self::BA2(BA2Error::BSArchExecFailed(_)) => true, // External tool might be busy
+            _ => self.is_file_lock(),
+        }
+    }
+
+    /// Short category name, used to group failures in run metrics
+    #[must_use]
+    pub const fn category(&self) -> &'static str {
+        match self {
+            Self::BA2(BA2Error::FileLocked { .. }) => "file-locked",
+            Self::BA2(
+                BA2Error::InvalidMagic { .. }
+                | BA2Error::UnsupportedVersion { .. }
+                | BA2Error::Corrupted { .. },
+            ) => "corrupted",
+            Self::BA2(BA2Error::PathTooLong { .. }) => "path-too-long",
+            Self::BA2(BA2Error::UnsupportedCompression { .. }) => "compression",
+            Self::BA2(
+                BA2Error::BSArchNotFound { .. }
+                | BA2Error::BSArchExecFailed(_)
+                | BA2Error::InvalidTool { .. },
+            ) => "tool",
+            Self::BA2(BA2Error::ExtractionFailed { .. }) => "extraction",
+            Self::IO(_) => "io",
+            Self::Config(_) => "config",
+            Self::Validation(_) => "validation",
+            Self::PartialMove { .. } => "partial-move",
+            Self::Cancelled => "cancelled",
+            Self::Other(_) => "other",
+        }
+    }
+
+    /// Check if this error is a file held open by another process
+    ///
+    /// Antivirus scanners open freshly written files and cause sharing
+    /// violations for a few seconds, so these errors are worth retrying.
+    #[must_use]
+    pub fn is_file_lock(&self) -> bool {
+        match self {
+            Self::BA2(BA2Error::FileLocked { .. }) => true,
+            // ERROR_SHARING_VIOLATION and ERROR_LOCK_VIOLATION
+            Self::IO(e) => cfg!(windows) && matches!(e.raw_os_error(), Some(32 | 33)),
+            _ => false,
+        }
+    }
+
+    /// Get a user-friendly error message (Phase 2.8)
+    #[must_use]
+    pub fn user_message(&self) -> String {
+        match self {
+            Self::Config(e) => e.user_message(),
+            Self::BA2(e) => e.user_message(),
+            Self::IO(e) => io_message(e),
+            Self::Validation(e) => e.user_message(),
+            Self::PartialMove { from, to, outcome } => outcome.user_message(from, to),
+            Self::Cancelled => "The operation was cancelled".to_string(),
+            Self::Other(msg) => msg.clone(),
+        }
+    }
+
+    /// Get recovery suggestions for the error (Phase 2.8)
+    ///
+    /// Returns a list of actionable suggestions the user can try
+    /// to resolve or work around the error.
+    #[must_use]
+    pub fn recovery_suggestions(&self) -> Vec<String> {
+        match self {
+            Self::Config(ConfigError::LoadFailed { .. }) => vec![
+                "Check that the settings file exists and is readable".to_string(),
+                "Try deleting the settings file to restore defaults".to_string(),
+                "Ensure you have read permissions for the file".to_string(),
+            ],
+            Self::Config(ConfigError::SaveFailed { .. }) => vec![
+                "Check that you have write permissions".to_string(),
+                "Ensure there is enough disk space".to_string(),
+                "Try running the application as administrator".to_string(),
+            ],
+            Self::Config(ConfigError::SecretStore(_)) => vec![
+                "On Linux, make sure a keyring such as GNOME Keyring or KWallet is running"
+                    .to_string(),
+                "Unlock the keyring if it asks for a password".to_string(),
+            ],
+            Self::Config(ConfigError::InvalidRegex { .. }) => vec![
+                "Check your file filter patterns in Settings".to_string(),
+                "Use simpler patterns or wildcards instead of regex".to_string(),
+                "Reset to default patterns".to_string(),
+            ],
+            Self::BA2(BA2Error::Corrupted { .. }) => vec![
+                "Try re-downloading the mod from its source".to_string(),
+                "Verify the file integrity if available".to_string(),
+                "Skip this file and continue with others".to_string(),
+            ],
+            Self::BA2(BA2Error::UnsupportedCompression { .. }) => vec![
+                "Unpack the archive with BSArch by turning off native_extraction in the settings file"
+                    .to_string(),
+                "Check for an updated version of the mod".to_string(),
+            ],
+            Self::BA2(BA2Error::BSArchNotFound { .. }) => vec![
+                "Specify the BA2 extraction tool path in Settings > Advanced".to_string(),
+                "Download BSArch.exe from TES5Edit project".to_string(),
+                "Check if an antivirus blocked the file".to_string(),
+            ],
+            Self::BA2(BA2Error::BSArchExecFailed(_)) => vec![
+                "Try running the extraction again".to_string(),
+                "Check if another program is using the files".to_string(),
+                "Ensure the extraction tool has execute permissions".to_string(),
+            ],
+            Self::BA2(BA2Error::FileLocked { .. }) => vec![
+                "Wait for the antivirus scan to finish and extract the archive again".to_string(),
+                "Add the mod folder to your antivirus exclusions".to_string(),
+                "Close programs that may have the files open (mod managers, xEdit)".to_string(),
+            ],
+            Self::BA2(BA2Error::InvalidTool { .. }) => vec![
+                "Select BSArch.exe as the external BA2 tool in Settings".to_string(),
+                "Clear the external tool setting to use the bundled BSArch.exe".to_string(),
+            ],
+            Self::BA2(BA2Error::PathTooLong { .. }) => vec![
+                "Move the mod folder closer to the drive root (e.g., C:\\Mods)".to_string(),
+                "Enable long path support in Windows (LongPathsEnabled)".to_string(),
+                "Use an extraction tool that supports long paths".to_string(),
+            ],
+            Self::IO(e) if e.kind() == std::io::ErrorKind::PermissionDenied => vec![
+                "Close any programs that might be using these files".to_string(),
+                "Run the application as administrator".to_string(),
+                "Check file and folder permissions".to_string(),
+            ],
+            Self::IO(e) if e.kind() == std::io::ErrorKind::NotFound => vec![
+                "Verify the path exists and hasn't been moved".to_string(),
+                "Check for typos in the file path".to_string(),
+                "Ensure the drive is connected and accessible".to_string(),
+            ],
+            Self::Validation(ValidationError::PathNotFound(_)) => vec![
+                "Browse for the folder instead of typing the path".to_string(),
+                "Verify the folder hasn't been moved or deleted".to_string(),
+                "Check that network drives are connected".to_string(),
+            ],
+            Self::Validation(ValidationError::InvalidSize(_)) => vec![
+                "Use format like '500MB' or '2GB'".to_string(),
+                "Valid units: B, KB, MB, GB, TB (or KiB, MiB, GiB, TiB for base-1024)".to_string(),
+                "Numbers without units are treated as bytes".to_string(),
+            ],
+            Self::Validation(ValidationError::InvalidAbortThreshold(_)) => vec![
+                "Enter a number of failures like '10' or a share of the batch like '25%'".to_string(),
+                "Enter 'never' to always extract the whole batch".to_string(),
+            ],
+            Self::PartialMove { outcome, .. } if outcome.is_copied() => vec![
+                "Close programs that may have the file open, then finish the move".to_string(),
+                "Or remove the copy to keep the file where it was".to_string(),
+            ],
+            Self::PartialMove { .. } => vec![
+                "Remove the incomplete copy".to_string(),
+                "Check that the target drive has enough free space".to_string(),
+            ],
+            Self::Validation(ValidationError::ReadOnlyMode(_)) => vec![
+                "Turn off Analysis Only Mode in Settings > Advanced".to_string(),
+            ],
+            _ => vec!["Try the operation again".to_string()],
+        }
+    }
+
+    /// Get detailed technical error information (Phase 2.8)
+    ///
+    /// Returns a detailed error report including the full error chain,
+    /// suitable for debugging or copying to bug reports.
+    #[must_use]
+    pub fn detailed_report(&self) -> String {
+        use std::fmt::Write;
+        let mut report = String::new();
+
+        // Error type
+        report.push_str("Error Type: ");
+        report.push_str(match self {
+            Self::Config(_) => "Configuration",
+            Self::BA2(_) => "BA2 File Format",
+            Self::IO(_) => "File System I/O",
+            Self::Validation(_) => "Input Validation",
+            Self::PartialMove { .. } => "Partial File Move",
+            Self::Cancelled => "Cancelled",
+            Self::Other(_) => "General",
+        });
+        report.push_str("\n\n");
+
+        // Main error message
+        report.push_str("Error: ");
+        report.push_str(&self.to_string());
+        report.push_str("\n\n");
+
+        // User-friendly message
+        report.push_str("User Message: ");
+        report.push_str(&self.user_message());
+        report.push_str("\n\n");
+
+        // Transient flag
+        if self.is_transient() {
+            report.push_str("Note: This error might be temporary. Retrying may succeed.\n\n");
+        }
+
+        // Recovery suggestions
+        let suggestions = self.recovery_suggestions();
+        if !suggestions.is_empty() {
+            report.push_str("Recovery Suggestions:\n");
+            for (i, suggestion) in suggestions.iter().enumerate() {
+                let _ = writeln!(report, "{}. {}", i + 1, suggestion);
+            }
+            report.push('\n');
+        }
+
+        // Version info for bug reports
+        let _ = writeln!(report, "Version: {}", env!("CARGO_PKG_VERSION"));
+        let _ = writeln!(report, "Platform: {}", std::env::consts::OS);
+
+        report
+    }
+}
+
+impl ConfigError {
+    /// User-friendly message for [`Error::user_message`]
+    fn user_message(&self) -> String {
+        match self {
+            Self::LoadFailed { path, .. } => {
+                format!("Failed to load settings from '{}'", path.display())
+            }
+            Self::SaveFailed { path, .. } => {
+                format!("Failed to save settings to '{}'", path.display())
+            }
+            Self::InvalidFormat(msg) => format!("Invalid settings format: {msg}"),
+            Self::ValidationFailed(msg) => {
+                format!("Settings validation failed: {msg}")
+            }
+            Self::InvalidRegex { pattern, .. } => {
+                format!("Invalid pattern: '{pattern}'")
+            }
+            Self::InvalidPath(path) => {
+                format!("Invalid path in settings: '{}'", path.display())
+            }
+            Self::SecretStore(msg) => {
+                format!("Could not access the system credential store: {msg}")
+            }
+        }
+    }
+}
+
+impl BA2Error {
+    /// User-friendly message for [`Error::user_message`]
+    fn user_message(&self) -> String {
+        match self {
+            Self::InvalidMagic { path } => {
+                format!("'{}' is not a valid BA2 file", path.display())
+            }
+            Self::UnsupportedVersion { version, path } => {
+                format!(
+                    "'{}' uses unsupported BA2 version {}",
+                    path.display(),
+                    version
+                )
+            }
+            Self::Corrupted { path, reason } => {
+                format!("BA2 file '{}' is corrupted: {}", path.display(), reason)
+            }
+            Self::UnsupportedCompression { path, codec, .. } => {
+                format!(
+                    "'{}' contains files with unsupported compression ({codec})",
+                    path.display()
+                )
+            }
+            Self::ExtractionFailed { path, reason } => {
+                format!("Failed to extract '{}': {}", path.display(), reason)
+            }
+            Self::BSArchNotFound { path } => {
+                format!("BA2 extraction tool not found at '{}'", path.display())
+            }
+            Self::BSArchExecFailed(msg) => {
+                format!("BA2 extraction tool failed: {msg}")
+            }
+            Self::FileLocked { path, .. } => {
+                format!(
+                    "Files of '{}' are locked by another program, likely an antivirus scan; retrying…",
+                    path.display()
+                )
+            }
+            Self::InvalidTool { path, reason } => {
+                format!(
+                    "'{}' cannot be used to unpack BA2 files: {}",
+                    path.display(),
+                    reason
+                )
+            }
+            Self::PathTooLong { path, longest, .. } => {
+                format!(
+                    "'{}' contains files whose extracted paths are too long ({longest} characters)",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+impl ValidationError {
+    /// User-friendly message for [`Error::user_message`]
+    fn user_message(&self) -> String {
+        match self {
+            Self::InvalidInput(msg) => format!("Invalid input: {msg}"),
+            Self::PathNotFound(path) => {
+                format!("Path not found: '{}'", path.display())
+            }
+            Self::NotADirectory(path) => {
+                format!("'{}' is not a folder", path.display())
+            }
+            Self::NotAFile(path) => {
+                format!("'{}' is not a file", path.display())
+            }
+            Self::InvalidSize(msg) => {
+                format!("Invalid size format: {msg}")
+            }
+            Self::InvalidAbortThreshold(msg) => {
+                format!("Invalid failure threshold: {msg}")
+            }
+            Self::ReadOnlyMode(action) => {
+                format!("{action} is disabled in analysis-only mode")
+            }
+        }
+    }
+}
+
+/// User-friendly message of an I/O error for [`Error::user_message`]
+fn io_message(e: &std::io::Error) -> String {
+    use std::io::ErrorKind;
+    match e.kind() {
+        ErrorKind::NotFound => "File or folder not found".to_string(),
+        ErrorKind::PermissionDenied => "Permission denied - check file permissions".to_string(),
+        ErrorKind::AlreadyExists => "File already exists".to_string(),
+        ErrorKind::InvalidInput => "Invalid input provided".to_string(),
+        ErrorKind::TimedOut => "Operation timed out".to_string(),
+        ErrorKind::Interrupted => "Operation was interrupted".to_string(),
+        _ => format!("File operation failed: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        let err = Error::other("test error");
+        assert_eq!(err.to_string(), "test error");
+    }
+
+    #[test]
+    fn test_ba2_corrupted_check() {
+        let err = Error::BA2(BA2Error::Corrupted {
+            path: PathBuf::from("test.ba2"),
+            reason: "invalid header".to_string(),
+        });
+        assert!(err.is_ba2_corrupted());
+    }
+
+    #[test]
+    fn test_user_message() {
+        let err = Error::Validation(ValidationError::PathNotFound(PathBuf::from("/test/path")));
+        let msg = err.user_message();
+        assert!(msg.contains("Path not found"));
+    }
+
+    #[test]
+    fn test_is_transient_io_interrupted() {
+        let err = Error::IO(std::io::Error::from(std::io::ErrorKind::Interrupted));
+        assert!(err.is_transient());
+    }
+
+    #[test]
+    fn test_is_transient_file_lock() {
+        let err = Error::BA2(BA2Error::FileLocked {
+            path: PathBuf::from("test.ba2"),
+            reason: "Sharing violation".to_string(),
+        });
+        assert!(err.is_transient());
+        assert!(err.is_file_lock());
+        assert!(err.user_message().contains("antivirus"));
+    }
+
+    #[test]
+    fn test_is_transient_permanent_error() {
+        let err = Error::Validation(ValidationError::PathNotFound(PathBuf::from("/test")));
+        assert!(!err.is_transient());
+    }
+
+    #[test]
+    fn test_recovery_suggestions() {
+        let err = Error::BA2(BA2Error::Corrupted {
+            path: PathBuf::from("test.ba2"),
+            reason: "invalid header".to_string(),
+        });
+        let suggestions = err.recovery_suggestions();
+        assert!(!suggestions.is_empty());
+        assert!(suggestions.iter().any(|s| s.contains("re-downloading")));
+    }
+
+    #[test]
+    fn test_detailed_report() {
+        let err = Error::other("test error");
+        let report = err.detailed_report();
+        assert!(report.contains("Error Type"));
+        assert!(report.contains("User Message"));
+        assert!(report.contains("Version"));
+        assert!(report.contains("Platform"));
+    }
+
+    #[test]
+    fn test_partial_move() {
+        let err = Error::PartialMove {
+            from: PathBuf::from("Mod - Main.ba2"),
+            to: PathBuf::from("quarantine/Mod - Main.ba2"),
+            outcome: BackupOutcome::CopiedNotRemoved {
+                reason: "Access is denied".to_string(),
+            },
+        };
+        assert_eq!(err.category(), "partial-move");
+        assert!(err.user_message().contains("in both places"));
+        assert!(err.to_string().ends_with("removed (Access is denied)"));
+        assert!(err.recovery_suggestions()[0].contains("finish the move"));
+    }
+
+    #[test]
+    fn test_user_message_io_not_found() {
+        let err = Error::IO(std::io::Error::from(std::io::ErrorKind::NotFound));
+        let msg = err.user_message();
+        assert_eq!(msg, "File or folder not found");
+    }
+}
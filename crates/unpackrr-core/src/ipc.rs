@@ -0,0 +1,462 @@
+//! Local automation interface
+//!
+//! Lets other local programs (scripts, mod manager plugins) drive a running
+//! Unpackrr over a Unix socket or, on Windows, a named pipe. Requests and
+//! responses are JSON objects, one per line:
+//!
+//! ```text
+//! > {"command":"scan","folder":"/games/mods"}
+//! < {"type":"ok","message":"Scan started"}
+//! > {"command":"status"}
+//! < {"type":"status","archives":120,"selected":0,"queued":0,"extracting":0,"done":0,"failed":0,"skipped":0}
+//! ```
+//!
+//! `watch` keeps the connection open and sends the status every time it
+//! changes. Only the commands allowed in the automation settings are
+//! accepted; by default these are the read-only ones.
+
+use crate::error::{Error, Result};
+use crate::models::{ExtractionStatus, FileEntry};
+use crate::operations::EventBus;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+/// All commands understood by the server
+pub const COMMANDS: [&str; 5] = ["scan", "list", "extract_selection", "status", "watch"];
+
+/// Commands that do not change anything
+pub const READ_ONLY_COMMANDS: [&str; 3] = ["list", "status", "watch"];
+
+/// Longest time `watch` waits between status checks
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A command sent by a client
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    /// Scan a folder (the current one if not given)
+    Scan {
+        #[serde(default)]
+        folder: Option<PathBuf>,
+    },
+    /// List the scanned archives
+    List,
+    /// Select the given archives and extract them
+    ExtractSelection {
+        #[serde(default)]
+        paths: Vec<PathBuf>,
+    },
+    /// Get the current status
+    Status,
+    /// Send the status whenever it changes
+    Watch,
+}
+
+impl Request {
+    /// Name of the command, as used in the allowlist
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Scan { .. } => "scan",
+            Self::List => "list",
+            Self::ExtractSelection { .. } => "extract_selection",
+            Self::Status => "status",
+            Self::Watch => "watch",
+        }
+    }
+}
+
+/// A scanned archive, as sent to clients
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveInfo {
+    pub path: PathBuf,
+    pub file_name: String,
+    pub mod_name: String,
+    pub size: u64,
+    pub num_files: u32,
+    /// Extraction status (see [`ExtractionStatus::as_str`])
+    pub status: String,
+    pub selected: bool,
+    pub corrupted: bool,
+    /// Why the header could not be read ("Unreadable: ...", "Corrupted: ..."), empty if it was
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub problem: String,
+}
+
+impl From<&FileEntry> for ArchiveInfo {
+    fn from(entry: &FileEntry) -> Self {
+        Self {
+            path: entry.full_path.clone(),
+            file_name: entry.file_name.clone(),
+            mod_name: entry.dir_name.clone(),
+            size: entry.file_size,
+            num_files: entry.num_files,
+            status: entry.status.as_str().to_string(),
+            selected: entry.selected,
+            corrupted: entry.is_corrupted(),
+            problem: if entry.is_bad() {
+                entry.health.to_string()
+            } else {
+                String::new()
+            },
+        }
+    }
+}
+
+/// Archive counts of the current scan and run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    pub archives: usize,
+    pub selected: usize,
+    pub queued: usize,
+    pub extracting: usize,
+    pub done: usize,
+    pub failed: usize,
+    pub skipped: usize,
+}
+
+impl Status {
+    /// Count the archives by selection and extraction status
+    pub fn from_entries(entries: &[FileEntry]) -> Self {
+        let mut status = Self {
+            archives: entries.len(),
+            ..Self::default()
+        };
+        for entry in entries {
+            if entry.selected {
+                status.selected += 1;
+            }
+            match entry.status {
+                ExtractionStatus::Queued => status.queued += 1,
+                ExtractionStatus::Extracting => status.extracting += 1,
+                ExtractionStatus::Done => status.done += 1,
+                ExtractionStatus::Failed => status.failed += 1,
+                ExtractionStatus::Skipped => status.skipped += 1,
+                ExtractionStatus::Idle => {}
+            }
+        }
+        status
+    }
+}
+
+/// A reply sent to a client
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    /// The command was accepted
+    Ok { message: String },
+    /// Reply to `list`
+    Archives { archives: Vec<ArchiveInfo> },
+    /// Reply to `status` and updates of `watch`
+    Status(Status),
+    /// The command was rejected or failed
+    Error { message: String },
+}
+
+impl Response {
+    /// Create an error reply
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::Error {
+            message: message.into(),
+        }
+    }
+}
+
+/// Carries out the commands of clients
+///
+/// Implemented by the frontend, which owns the scan results and starts
+/// scans and extractions. `watch` is handled by the server by asking for
+/// [`Request::Status`] after every event on the frontend's bus, and at least
+/// every second.
+pub trait Handler: Send + Sync + 'static {
+    fn handle(&self, request: Request) -> Response;
+
+    /// Bus the frontend's operations publish on, if any
+    fn events(&self) -> Option<EventBus> {
+        None
+    }
+}
+
+/// Parse a request line, check it against the allowlist and handle it
+pub fn dispatch(handler: &dyn Handler, allowed: &[String], line: &str) -> Response {
+    match parse(allowed, line) {
+        Ok(request) => handler.handle(request),
+        Err(response) => response,
+    }
+}
+
+fn parse(allowed: &[String], line: &str) -> std::result::Result<Request, Response> {
+    let request: Request =
+        serde_json::from_str(line).map_err(|e| Response::error(format!("Invalid request: {e}")))?;
+    if !allowed.iter().any(|command| command == request.name()) {
+        return Err(Response::error(format!(
+            "Command '{}' is not allowed (enable it in the automation settings)",
+            request.name()
+        )));
+    }
+    Ok(request)
+}
+
+/// Serve one client until it disconnects
+async fn handle_connection<S>(stream: S, allowed: &[String], handler: &dyn Handler) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match parse(allowed, &line) {
+            Ok(Request::Watch) => return watch(&mut writer, handler).await,
+            Ok(request) => send(&mut writer, &handler.handle(request)).await?,
+            Err(response) => send(&mut writer, &response).await?,
+        }
+    }
+    Ok(())
+}
+
+/// Send the status whenever it changes, until the client disconnects
+async fn watch<W>(writer: &mut W, handler: &dyn Handler) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut events = handler.events().map(|bus| bus.monitor());
+    let mut last = None;
+    loop {
+        let response = handler.handle(Request::Status);
+        if last.as_ref() != Some(&response) {
+            send(writer, &response).await?;
+            last = Some(response);
+        }
+        // Wait for the next event or the interval, whichever comes first
+        let closed = if let Some(rx) = events.as_mut() {
+            tokio::select! {
+                event = rx.recv() => event.is_none(),
+                () = tokio::time::sleep(WATCH_INTERVAL) => false,
+            }
+        } else {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+            false
+        };
+        if closed {
+            events = None;
+        }
+    }
+}
+
+async fn send<W>(writer: &mut W, response: &Response) -> Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut line = serde_json::to_string(response).map_err(|e| Error::other(e.to_string()))?;
+    line.push('\n');
+    writer.write_all(line.as_bytes()).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Get the default endpoint: a socket in the runtime (or data) folder, or
+/// a named pipe on Windows
+pub fn default_endpoint() -> PathBuf {
+    #[cfg(windows)]
+    {
+        PathBuf::from(r"\\.\pipe\unpackrr")
+    }
+    #[cfg(not(windows))]
+    {
+        directories::ProjectDirs::from("com", "evildarkarchon", "unpackrr").map_or_else(
+            || std::env::temp_dir().join("unpackrr.sock"),
+            |dirs| {
+                dirs.runtime_dir()
+                    .unwrap_or_else(|| dirs.data_local_dir())
+                    .join("unpackrr.sock")
+            },
+        )
+    }
+}
+
+/// Accept clients on `endpoint` until the task is dropped
+///
+/// Fails if another instance is already serving the endpoint.
+#[cfg(unix)]
+pub async fn serve(endpoint: &Path, allowed: Vec<String>, handler: Arc<dyn Handler>) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::{UnixListener, UnixStream};
+
+    if endpoint.exists() {
+        if UnixStream::connect(endpoint).await.is_ok() {
+            return Err(Error::other(format!(
+                "Another instance is already listening on {}",
+                endpoint.display()
+            )));
+        }
+        // Left behind by an instance that did not shut down cleanly
+        std::fs::remove_file(endpoint)?;
+    }
+    if let Some(parent) = endpoint.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let listener = UnixListener::bind(endpoint)?;
+    // Only the current user may send commands
+    std::fs::set_permissions(endpoint, std::fs::Permissions::from_mode(0o600))?;
+    tracing::info!("Automation interface listening on {}", endpoint.display());
+
+    let allowed: Arc<[String]> = allowed.into();
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let allowed = Arc::clone(&allowed);
+        let handler = Arc::clone(&handler);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &allowed, handler.as_ref()).await {
+                tracing::debug!("Automation client disconnected: {}", e);
+            }
+        });
+    }
+}
+
+/// Accept clients on `endpoint` until the task is dropped
+///
+/// Fails if another instance is already serving the endpoint.
+#[cfg(windows)]
+pub async fn serve(endpoint: &Path, allowed: Vec<String>, handler: Arc<dyn Handler>) -> Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(endpoint)?;
+    tracing::info!("Automation interface listening on {}", endpoint.display());
+
+    let allowed: Arc<[String]> = allowed.into();
+    loop {
+        server.connect().await?;
+        let client = server;
+        // Create the next instance before serving so clients can always connect
+        server = ServerOptions::new().create(endpoint)?;
+        let allowed = Arc::clone(&allowed);
+        let handler = Arc::clone(&handler);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(client, &allowed, handler.as_ref()).await {
+                tracing::debug!("Automation client disconnected: {}", e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct MockHandler;
+
+    impl Handler for MockHandler {
+        fn handle(&self, request: Request) -> Response {
+            match request {
+                Request::Status => Response::Status(Status {
+                    archives: 2,
+                    ..Status::default()
+                }),
+                Request::List => Response::Archives {
+                    archives: Vec::new(),
+                },
+                other => Response::Ok {
+                    message: other.name().to_string(),
+                },
+            }
+        }
+    }
+
+    fn read_only() -> Vec<String> {
+        READ_ONLY_COMMANDS.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_dispatch_enforces_allowlist() {
+        let allowed = read_only();
+        assert_eq!(
+            dispatch(&MockHandler, &allowed, r#"{"command":"list"}"#),
+            Response::Archives {
+                archives: Vec::new()
+            }
+        );
+
+        let response = dispatch(&MockHandler, &allowed, r#"{"command":"scan"}"#);
+        assert!(matches!(response, Response::Error { message } if message.contains("not allowed")));
+
+        let response = dispatch(&MockHandler, &allowed, "not json");
+        assert!(matches!(response, Response::Error { message } if message.contains("Invalid")));
+
+        let all: Vec<String> = COMMANDS.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            dispatch(
+                &MockHandler,
+                &all,
+                r#"{"command":"extract_selection","paths":["a.ba2"]}"#
+            ),
+            Response::Ok {
+                message: "extract_selection".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_message_format() {
+        let request: Request = serde_json::from_str(r#"{"command":"scan"}"#).unwrap();
+        assert_eq!(request, Request::Scan { folder: None });
+
+        let json = serde_json::to_string(&Response::Status(Status::default())).unwrap();
+        assert!(json.starts_with(r#"{"type":"status","archives":0"#));
+
+        let mut entry = FileEntry::new(
+            "Mod - Main.ba2".to_string(),
+            10,
+            1,
+            "Mod".to_string(),
+            PathBuf::from("Mod/Mod - Main.ba2"),
+        );
+        entry.status = ExtractionStatus::Done;
+        entry.selected = true;
+        let status = Status::from_entries(std::slice::from_ref(&entry));
+        assert_eq!((status.archives, status.selected, status.done), (1, 1, 1));
+        assert_eq!(ArchiveInfo::from(&entry).status, "done");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_serve_over_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let endpoint = dir.path().join("unpackrr.sock");
+        let server = tokio::spawn({
+            let endpoint = endpoint.clone();
+            async move { serve(&endpoint, read_only(), Arc::new(MockHandler)).await }
+        });
+        while !endpoint.exists() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        let stream = tokio::net::UnixStream::connect(&endpoint).await.unwrap();
+        let (reader, mut writer) = tokio::io::split(stream);
+        let mut lines = BufReader::new(reader).lines();
+        writer
+            .write_all(b"{\"command\":\"status\"}\n")
+            .await
+            .unwrap();
+        let line = lines.next_line().await.unwrap().unwrap();
+        let response: Response = serde_json::from_str(&line).unwrap();
+        assert_eq!(
+            response,
+            Response::Status(Status {
+                archives: 2,
+                ..Status::default()
+            })
+        );
+
+        // A second instance must not take over the socket
+        let second = serve(&endpoint, read_only(), Arc::new(MockHandler)).await;
+        assert!(second.is_err());
+        server.abort();
+    }
+}
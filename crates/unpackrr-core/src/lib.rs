@@ -0,0 +1,51 @@
+//! Unpackrr core: scanning and extracting BA2 archives
+//!
+//! The library behind the Unpackrr GUI, without any UI dependencies, for
+//! tools that embed scanning and extraction (mod manager plugins, Wabbajack
+//! post-install hooks).
+//!
+//! # Modules
+//!
+//! - `error`: Error types shared by all operations
+//! - `config`: Configuration management and persistence
+//! - `ba2`: BA2 file format support and BSArch.exe integration
+//! - `operations`: File system operations (scanning, extraction, validation)
+//! - `models`: Scanned archives and their extraction state
+//! - `platform`: Platform-specific functionality (Windows registry, etc.)
+//! - `ipc`: Local automation interface (socket or named pipe)
+//! - `testing`: Fake extractor and fixture archives (`test-support` feature)
+//!
+//! # Example
+//!
+//! Scan a mod folder and extract every archive that is not corrupted:
+//!
+//! ```no_run
+//! use unpackrr_core::config::AppConfig;
+//! use unpackrr_core::models::FileEntryList;
+//! use unpackrr_core::operations::{extract_all, scan_for_ba2};
+//! use std::path::Path;
+//!
+//! # async fn example() -> unpackrr_core::Result<()> {
+//! let config = AppConfig::load()?;
+//! let found = scan_for_ba2(Path::new("C:/Modding/MO2/mods"), &config, None).await?;
+//! let entries = FileEntryList::from_scan_results(found);
+//! let result = extract_all(entries.extraction_candidates(), config, None, None).await?;
+//! println!("{} extracted, {} failed", result.successful, result.failed);
+//! # Ok(())
+//! # }
+//! ```
+
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+#![allow(clippy::must_use_candidate, clippy::missing_errors_doc)]
+
+pub mod ba2;
+pub mod config;
+pub mod error;
+pub mod ipc;
+pub mod models;
+pub mod operations;
+pub mod platform;
+#[cfg(any(test, feature = "test-support"))]
+pub mod testing;
+
+pub use error::{Error, Result};
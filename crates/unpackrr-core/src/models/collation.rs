@@ -0,0 +1,109 @@
+//! Ordering of file and mod names as people read them
+//!
+//! Names are compared with a collator for the interface language instead of
+//! byte by byte: numbers by value, so `Part 2` comes before `Part 10`, letters
+//! case-insensitively first, and accented letters next to their base letter.
+//! The collator is process-wide and follows the language setting, like the
+//! number format of [`operations::format`](crate::operations::format).
+
+use crate::config::AppearanceConfig;
+use crate::operations::format::system_locale;
+use icu_collator::{Collator, CollatorOptions, Numeric};
+use icu_locid::Locale;
+use parking_lot::RwLock;
+use std::cmp::Ordering;
+use std::sync::LazyLock;
+
+/// Active collator used by [`compare_names`]
+static CURRENT_COLLATOR: LazyLock<RwLock<Collator>> =
+    LazyLock::new(|| RwLock::new(collator_for_locale("und")));
+
+/// Build the collator for a locale tag such as "en", "de-DE" or "fr_FR.UTF-8"
+///
+/// Unknown or malformed locales use the root collation, which orders most
+/// Latin-script languages correctly.
+fn collator_for_locale(locale: &str) -> Collator {
+    let mut options = CollatorOptions::new();
+    options.numeric = Some(Numeric::On);
+
+    let tag = locale
+        .split(['.', '@'])
+        .next()
+        .unwrap_or_default()
+        .replace('_', "-");
+    let locale: Locale = tag.parse().unwrap_or_default();
+    Collator::try_new(&(&locale).into(), options)
+        .or_else(|_| Collator::try_new(&Locale::default().into(), options))
+        .expect("the root collation is compiled in")
+}
+
+/// Collate names for a locale tag from now on
+pub fn set_name_collation(locale: &str) {
+    *CURRENT_COLLATOR.write() = collator_for_locale(locale);
+}
+
+/// Collate names for the language of the appearance settings from now on
+///
+/// A language of "auto" uses the system locale.
+pub fn set_name_collation_from_appearance(appearance: &AppearanceConfig) {
+    if appearance.language.eq_ignore_ascii_case("auto") {
+        set_name_collation(&system_locale());
+    } else {
+        set_name_collation(&appearance.language);
+    }
+}
+
+/// Compare two names in reading order
+///
+/// Names the collator considers equal, such as `02` and `2`, are ordered by
+/// their bytes, so sorting is deterministic.
+pub fn compare_names(a: &str, b: &str) -> Ordering {
+    CURRENT_COLLATOR.read().compare(a, b).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(names: &[&str]) -> Vec<String> {
+        let mut names: Vec<String> = names.iter().map(ToString::to_string).collect();
+        names.sort_by(|a, b| compare_names(a, b));
+        names
+    }
+
+    #[test]
+    fn test_numbers_by_value() {
+        assert_eq!(
+            sorted(&[
+                "Mod 10 - Main.ba2",
+                "Mod 2 - Main.ba2",
+                "mod 1 - Main.ba2",
+                "Mod 100 - Main.ba2",
+            ]),
+            [
+                "mod 1 - Main.ba2",
+                "Mod 2 - Main.ba2",
+                "Mod 10 - Main.ba2",
+                "Mod 100 - Main.ba2",
+            ]
+        );
+        assert_eq!(compare_names("file2", "file10"), Ordering::Less);
+        assert_ne!(compare_names("02", "2"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_letters_in_reading_order() {
+        assert_eq!(
+            sorted(&["zeta", "Éclair", "Apple", "eclair", "banana"]),
+            ["Apple", "banana", "eclair", "Éclair", "zeta"]
+        );
+    }
+
+    #[test]
+    fn test_malformed_locale() {
+        let collator = collator_for_locale("not a locale!");
+        assert_eq!(collator.compare("b2", "b10"), Ordering::Less);
+        let collator = collator_for_locale("de_DE.UTF-8");
+        assert_eq!(collator.compare("Ärger", "Bär"), Ordering::Less);
+    }
+}
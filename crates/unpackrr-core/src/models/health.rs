@@ -0,0 +1,124 @@
+//! Why an archive's header could or could not be read
+//!
+//! An archive that can't be opened (no permission, locked, gone) is not
+//! corrupted, and one of a BA2 version the reader doesn't know may be fine
+//! for the game. Scans keep these apart, so only real corruption is offered
+//! for quarantine and the others point at the actual problem.
+
+use crate::error::{BA2Error, Error};
+use std::fmt;
+
+/// Whether an archive's header could be read, and why not
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ArchiveHealth {
+    /// The header was read
+    #[default]
+    Ok,
+
+    /// The file could not be opened or read, e.g. for lack of permissions
+    Unreadable(String),
+
+    /// The file is not a valid BA2 archive (damaged header, wrong magic, truncated)
+    InvalidFormat(String),
+
+    /// The header names a BA2 version the reader does not support
+    UnsupportedVersion(u32),
+}
+
+impl ArchiveHealth {
+    /// Classify the error of reading an archive's header
+    pub fn from_error(error: &Error) -> Self {
+        match error {
+            Error::BA2(BA2Error::Corrupted { reason, .. }) => Self::InvalidFormat(reason.clone()),
+            Error::BA2(BA2Error::InvalidMagic { .. }) => {
+                Self::InvalidFormat("Not a BA2 archive (invalid magic number)".to_string())
+            }
+            Error::BA2(BA2Error::UnsupportedVersion { version, .. }) => {
+                Self::UnsupportedVersion(*version)
+            }
+            // The I/O message names no path, unlike the user message
+            Error::IO(e) => Self::Unreadable(e.to_string()),
+            other => Self::Unreadable(other.user_message()),
+        }
+    }
+
+    /// Whether the header was read
+    pub const fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok)
+    }
+
+    /// Whether the file itself is damaged, as opposed to unreadable or unsupported
+    pub const fn is_corrupted(&self) -> bool {
+        matches!(self, Self::InvalidFormat(_))
+    }
+
+    /// Short name for badges, empty for readable archives
+    pub const fn label(&self) -> &'static str {
+        match self {
+            Self::Ok => "",
+            Self::Unreadable(_) => "Unreadable",
+            Self::InvalidFormat(_) => "Corrupted",
+            Self::UnsupportedVersion(_) => "Unsupported",
+        }
+    }
+
+    /// Why the header could not be read, empty for readable archives
+    pub fn reason(&self) -> String {
+        match self {
+            Self::Ok => String::new(),
+            Self::Unreadable(reason) | Self::InvalidFormat(reason) => reason.clone(),
+            Self::UnsupportedVersion(version) => format!("Unsupported BA2 version {version}"),
+        }
+    }
+}
+
+impl fmt::Display for ArchiveHealth {
+    /// The label and the reason, e.g. "Unreadable: Permission denied"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ok => f.write_str("OK"),
+            Self::UnsupportedVersion(_) => f.write_str(&self.reason()),
+            Self::Unreadable(reason) | Self::InvalidFormat(reason) if reason.is_empty() => {
+                f.write_str(self.label())
+            }
+            Self::Unreadable(reason) | Self::InvalidFormat(reason) => {
+                write!(f, "{}: {}", self.label(), reason)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_from_error() {
+        let denied = Error::IO(io::Error::from(io::ErrorKind::PermissionDenied));
+        let health = ArchiveHealth::from_error(&denied);
+        assert!(matches!(health, ArchiveHealth::Unreadable(_)));
+        assert!(!health.is_corrupted());
+        assert_eq!(health.label(), "Unreadable");
+
+        let magic = Error::BA2(BA2Error::InvalidMagic {
+            path: PathBuf::from("Mod - Main.ba2"),
+        });
+        let health = ArchiveHealth::from_error(&magic);
+        assert!(health.is_corrupted());
+        assert_eq!(
+            health.to_string(),
+            "Corrupted: Not a BA2 archive (invalid magic number)"
+        );
+
+        let version = Error::BA2(BA2Error::UnsupportedVersion {
+            version: 9,
+            path: PathBuf::from("Mod - Main.ba2"),
+        });
+        let health = ArchiveHealth::from_error(&version);
+        assert_eq!(health, ArchiveHealth::UnsupportedVersion(9));
+        assert_eq!(health.to_string(), "Unsupported BA2 version 9");
+        assert!(!health.is_ok());
+    }
+}
@@ -0,0 +1,856 @@
+//! Data models for the UI
+//!
+//! This module provides data structures used by the UI layer, including:
+//! - File entries for the preview table
+//! - Sorting and comparison logic, ordering names as people read them
+//!   ([`collation`])
+//! - Display formatting helpers
+//! - Aggregate archive statistics for the dashboard
+//! - Whether an archive's header could be read, and why not
+
+pub mod collation;
+pub mod health;
+pub mod stats;
+
+pub use collation::compare_names;
+pub use health::ArchiveHealth;
+
+use crate::ba2::is_starfield_version;
+use crate::config::profile::GameProfile;
+use crate::operations::loadorder::LoadOrder;
+use crate::operations::notes::{ModNotes, Note};
+use crate::operations::wabbajack::ModlistIndex;
+use crate::operations::{BA2FileInfo, format_count, format_elapsed, format_size};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Extraction state of a single archive in the preview table
+///
+/// Updated from `ExtractionProgress` events so each row shows whether
+/// its archive is waiting, running, finished, or was skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ExtractionStatus {
+    /// Not part of an extraction run yet
+    #[default]
+    Idle,
+    /// Waiting for a free extraction slot
+    Queued,
+    /// Currently being extracted
+    Extracting,
+    /// Extracted successfully
+    Done,
+    /// Extraction failed
+    Failed,
+    /// Not extracted (corrupted archive or cancelled run)
+    Skipped,
+}
+
+impl ExtractionStatus {
+    /// Get the label shown in the status column
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Idle => "",
+            Self::Queued => "Queued",
+            Self::Extracting => "Extracting",
+            Self::Done => "Done",
+            Self::Failed => "Failed",
+            Self::Skipped => "Skipped",
+        }
+    }
+
+    /// Get a stable lowercase identifier (used in exported lists)
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Idle => "idle",
+            Self::Queued => "queued",
+            Self::Extracting => "extracting",
+            Self::Done => "done",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        }
+    }
+
+    /// Check if the archive is still waiting for or undergoing extraction
+    pub const fn is_pending(self) -> bool {
+        matches!(self, Self::Queued | Self::Extracting)
+    }
+}
+
+/// Reasons to skip an archive that the user chose to extract past
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SkipOverrides {
+    /// Extract even though the header could not be read and bad files are ignored
+    pub bad_file: bool,
+
+    /// Extract even though the archive is a known exclusion
+    pub exclusion: bool,
+}
+
+impl SkipOverrides {
+    /// No skip reason overridden
+    pub const NONE: Self = Self {
+        bad_file: false,
+        exclusion: false,
+    };
+}
+
+/// File entry for display in the preview table
+///
+/// This struct represents a BA2 file discovered during scanning,
+/// with additional functionality for sorting and display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileEntry {
+    /// File name (without path)
+    pub file_name: String,
+
+    /// File size in bytes
+    pub file_size: u64,
+
+    /// Number of files contained in the archive
+    pub num_files: u32,
+
+    /// Parent directory name (mod folder)
+    pub dir_name: String,
+
+    /// Full path to the file
+    pub full_path: PathBuf,
+
+    /// Whether the header could be read, and why not
+    pub health: ArchiveHealth,
+
+    /// Archive type from the header ("GNRL", "DX10"), empty if unknown
+    pub archive_type: String,
+
+    /// BA2 format version from the header, 0 if unknown
+    pub format_version: u32,
+
+    /// Extraction state in the current run
+    pub status: ExtractionStatus,
+
+    /// How long the archive took to extract in the current run
+    pub extraction_time: Option<Duration>,
+
+    /// Explicitly selected for extraction (e.g., from an imported list)
+    pub selected: bool,
+
+    /// Installed by the loaded Wabbajack modlist
+    pub in_modlist: bool,
+
+    /// Load order index of the archive's plugin, if known and active
+    pub load_order: Option<usize>,
+
+    /// Note and tags of the archive and its mod
+    pub note: Note,
+
+    /// Why the archive must stay packed, if it is a known exclusion
+    pub exclusion: Option<String>,
+
+    /// Skip reasons the user overrode for this archive
+    pub overrides: SkipOverrides,
+}
+
+impl FileEntry {
+    /// Create a new `FileEntry`
+    pub const fn new(
+        file_name: String,
+        file_size: u64,
+        num_files: u32,
+        dir_name: String,
+        full_path: PathBuf,
+    ) -> Self {
+        Self {
+            file_name,
+            file_size,
+            num_files,
+            dir_name,
+            full_path,
+            health: ArchiveHealth::Ok,
+            archive_type: String::new(),
+            format_version: 0,
+            status: ExtractionStatus::Idle,
+            extraction_time: None,
+            selected: false,
+            in_modlist: false,
+            load_order: None,
+            note: Note::new(),
+            exclusion: None,
+            overrides: SkipOverrides::NONE,
+        }
+    }
+
+    /// Get human-readable file size (e.g., "10.5 MiB")
+    pub fn size_display(&self) -> String {
+        format_size(self.file_size)
+    }
+
+    /// Get file name for display
+    pub fn name_display(&self) -> &str {
+        &self.file_name
+    }
+
+    /// Get number of files for display
+    pub fn file_count_display(&self) -> String {
+        format_count(u64::from(self.num_files))
+    }
+
+    /// Get mod folder name for display
+    pub fn mod_display(&self) -> &str {
+        &self.dir_name
+    }
+
+    /// Get load order index for display (empty if unknown)
+    pub fn load_order_display(&self) -> String {
+        self.load_order.map_or_else(String::new, |index| index.to_string())
+    }
+
+    /// Get the extraction time for display (empty if not extracted in this run)
+    pub fn extraction_time_display(&self) -> String {
+        self.extraction_time.map_or_else(String::new, format_elapsed)
+    }
+
+    /// Get the note for display: tags in brackets, then the text
+    pub fn note_display(&self) -> String {
+        let tags = self.note.tags.iter().map(|tag| format!("[{tag}]"));
+        tags.chain((!self.note.text.is_empty()).then(|| self.note.text.clone()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Check if the header could not be read, for whatever reason
+    pub const fn is_bad(&self) -> bool {
+        !self.health.is_ok()
+    }
+
+    /// Check if the file itself is damaged, as opposed to unreadable or unsupported
+    pub const fn is_corrupted(&self) -> bool {
+        self.health.is_corrupted()
+    }
+
+    /// Check if the archive is a known exclusion that was not overridden
+    pub const fn is_excluded(&self) -> bool {
+        self.exclusion.is_some() && !self.overrides.exclusion
+    }
+
+    /// Check if this is a Starfield archive
+    pub const fn is_starfield(&self) -> bool {
+        is_starfield_version(self.format_version)
+    }
+
+    /// Check if the header names a format version the game of `profile` does not write
+    ///
+    /// Archives whose header could not be read (version 0) are not flagged.
+    pub fn has_unknown_version(&self, profile: &GameProfile) -> bool {
+        self.format_version != 0 && !profile.expects_version(self.format_version)
+    }
+
+    /// Check if this is a texture (DX10) archive
+    pub fn is_texture(&self) -> bool {
+        self.archive_type == "DX10"
+    }
+}
+
+/// Convert from `BA2FileInfo` to `FileEntry`
+impl From<BA2FileInfo> for FileEntry {
+    fn from(info: BA2FileInfo) -> Self {
+        Self {
+            file_name: info.file_name,
+            file_size: info.file_size,
+            num_files: info.num_files,
+            dir_name: info.dir_name,
+            full_path: info.full_path,
+            health: info.health,
+            archive_type: info.archive_type,
+            format_version: info.format_version,
+            status: ExtractionStatus::Idle,
+            extraction_time: None,
+            selected: false,
+            in_modlist: false,
+            load_order: None,
+            note: Note::new(),
+            exclusion: info.exclusion,
+            overrides: SkipOverrides::NONE,
+        }
+    }
+}
+
+/// Sorting criteria for file entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortBy {
+    /// Sort by file name (alphabetically)
+    Name,
+    /// Sort by file size (largest first)
+    Size,
+    /// Sort by number of files (most first)
+    FileCount,
+    /// Sort by mod folder name (alphabetically)
+    ModName,
+    /// Sort by extraction status
+    Status,
+    /// Sort by plugin load order (unknown last)
+    LoadOrder,
+    /// Sort by note (archives without one last)
+    Note,
+}
+
+impl FileEntry {
+    /// Compare two entries based on a sorting criterion
+    pub fn compare(&self, other: &Self, sort_by: SortBy) -> Ordering {
+        match sort_by {
+            SortBy::Name => compare_names(&self.file_name, &other.file_name),
+            SortBy::Size => self.file_size.cmp(&other.file_size), // Smallest first (Natural)
+            SortBy::FileCount => self.num_files.cmp(&other.num_files), // Fewest first (Natural)
+            SortBy::ModName => compare_names(&self.dir_name, &other.dir_name),
+            SortBy::Status => self.status.cmp(&other.status),
+            SortBy::LoadOrder => self
+                .load_order
+                .unwrap_or(usize::MAX)
+                .cmp(&other.load_order.unwrap_or(usize::MAX)),
+            SortBy::Note => (self.note.is_empty(), self.note_display())
+                .cmp(&(other.note.is_empty(), other.note_display())),
+        }
+    }
+}
+
+/// Default ordering: by file size (largest first)
+impl Ord for FileEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Default to size descending for Ord implementation (legacy/default behavior)
+        other.file_size.cmp(&self.file_size)
+    }
+}
+
+impl PartialOrd for FileEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Collection of file entries for the preview table
+#[derive(Debug, Clone, Default)]
+pub struct FileEntryList {
+    entries: Vec<FileEntry>,
+}
+
+impl FileEntryList {
+    /// Create a new empty list
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Create from a vector of entries
+    pub const fn from_vec(entries: Vec<FileEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Create from `BA2FileInfo` results
+    pub fn from_scan_results(results: Vec<BA2FileInfo>) -> Self {
+        Self {
+            entries: results.into_iter().map(FileEntry::from).collect(),
+        }
+    }
+
+    /// Add an entry to the list
+    pub fn push(&mut self, entry: FileEntry) {
+        self.entries.push(entry);
+    }
+
+    /// Get the number of entries
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the list is empty
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get a reference to all entries
+    pub fn entries(&self) -> &[FileEntry] {
+        &self.entries
+    }
+
+    /// Get a mutable reference to all entries
+    pub const fn entries_mut(&mut self) -> &mut Vec<FileEntry> {
+        &mut self.entries
+    }
+
+    /// Sort entries by a specific criterion
+    pub fn sort_by(&mut self, sort_by: SortBy, reverse: bool) {
+        self.entries.sort_by(|a, b| {
+            let ord = a.compare(b, sort_by);
+            if reverse { ord.reverse() } else { ord }
+        });
+    }
+
+    /// Get entry at index
+    pub fn get(&self, index: usize) -> Option<&FileEntry> {
+        self.entries.get(index)
+    }
+
+    /// Remove entry at index
+    pub fn remove(&mut self, index: usize) -> Option<FileEntry> {
+        if index < self.entries.len() {
+            Some(self.entries.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Get the entry with the given path
+    pub fn find_mut(&mut self, path: &Path) -> Option<&mut FileEntry> {
+        self.entries.iter_mut().find(|e| e.full_path == path)
+    }
+
+    /// Remove the entry with the given path
+    pub fn remove_path(&mut self, path: &Path) -> Option<FileEntry> {
+        let index = self.entries.iter().position(|e| e.full_path == path)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Get total size of all files
+    pub fn total_size(&self) -> u64 {
+        self.entries.iter().map(|e| e.file_size).sum()
+    }
+
+    /// Get total number of files across all archives
+    pub fn total_file_count(&self) -> u32 {
+        self.entries.iter().map(|e| e.num_files).sum()
+    }
+
+    /// Get count of files whose header could not be read
+    pub fn bad_file_count(&self) -> usize {
+        self.entries.iter().filter(|e| e.is_bad()).count()
+    }
+
+    /// Filter entries to remove files whose header could not be read
+    pub fn filter_bad_files(&mut self) {
+        self.entries.retain(|e| !e.is_bad());
+    }
+
+    /// Set the extraction status of the entry with the given path
+    ///
+    /// Returns `true` if a matching entry was found.
+    pub fn set_status(&mut self, path: &Path, status: ExtractionStatus) -> bool {
+        let Some(entry) = self.entries.iter_mut().find(|e| e.full_path == path) else {
+            return false;
+        };
+        entry.status = status;
+        true
+    }
+
+    /// Set the extraction time of the entry with the given path
+    ///
+    /// Returns `true` if a matching entry was found.
+    pub fn set_extraction_time(&mut self, path: &Path, time: Duration) -> bool {
+        let Some(entry) = self.entries.iter_mut().find(|e| e.full_path == path) else {
+            return false;
+        };
+        entry.extraction_time = Some(time);
+        true
+    }
+
+    /// Set the extraction status of every entry, forgetting the times of an
+    /// earlier run
+    pub fn set_all_statuses(&mut self, status: ExtractionStatus) {
+        for entry in &mut self.entries {
+            entry.status = status;
+            entry.extraction_time = None;
+        }
+    }
+
+    /// Mark entries that never finished in the current run as skipped
+    pub fn skip_pending(&mut self) {
+        for entry in &mut self.entries {
+            if entry.status.is_pending() {
+                entry.status = ExtractionStatus::Skipped;
+            }
+        }
+    }
+
+    /// Select exactly the entries whose paths are in `paths`
+    ///
+    /// Returns the number of entries selected.
+    pub fn select_paths(&mut self, paths: &HashSet<PathBuf>) -> usize {
+        let mut count = 0;
+        for entry in &mut self.entries {
+            entry.selected = paths.contains(&entry.full_path);
+            count += usize::from(entry.selected);
+        }
+        count
+    }
+
+    /// Clear any explicit selection
+    pub fn clear_selection(&mut self) {
+        for entry in &mut self.entries {
+            entry.selected = false;
+        }
+    }
+
+    /// Get count of explicitly selected entries
+    pub fn selected_count(&self) -> usize {
+        self.entries.iter().filter(|e| e.selected).count()
+    }
+
+    /// Get the entries to extract: the selection if there is one, otherwise all
+    pub fn extraction_candidates(&self) -> Vec<FileEntry> {
+        if self.selected_count() == 0 {
+            self.entries.clone()
+        } else {
+            self.entries.iter().filter(|e| e.selected).cloned().collect()
+        }
+    }
+
+    /// Tag the entries installed by a Wabbajack modlist (clears tags without one)
+    ///
+    /// Returns the number of entries tagged.
+    pub fn tag_modlist(&mut self, modlist: Option<&ModlistIndex>) -> usize {
+        let mut count = 0;
+        for entry in &mut self.entries {
+            entry.in_modlist = modlist.is_some_and(|modlist| modlist.contains(entry));
+            count += usize::from(entry.in_modlist);
+        }
+        count
+    }
+
+    /// Attach the notes of every entry's archive and mod
+    ///
+    /// Returns the number of entries with a note.
+    pub fn apply_notes(&mut self, notes: &ModNotes) -> usize {
+        let mut count = 0;
+        for entry in &mut self.entries {
+            entry.note = notes.for_archive(&entry.dir_name, &entry.file_name);
+            count += usize::from(!entry.note.is_empty());
+        }
+        count
+    }
+
+    /// Set the load order index of every entry (clears them without a load order)
+    ///
+    /// Returns the number of entries whose plugin is active.
+    pub fn apply_load_order(&mut self, load_order: Option<&LoadOrder>) -> usize {
+        let mut count = 0;
+        for entry in &mut self.entries {
+            entry.load_order = load_order.and_then(|order| order.index_of(&entry.file_name));
+            count += usize::from(entry.load_order.is_some());
+        }
+        count
+    }
+
+    /// Get count of entries whose extraction failed
+    pub fn failed_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| e.status == ExtractionStatus::Failed)
+            .count()
+    }
+
+    /// Get indices of bad files
+    pub fn bad_file_indices(&self) -> Vec<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, entry)| if entry.is_bad() { Some(idx) } else { None })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::profile::{FALLOUT4, STARFIELD};
+
+    fn create_test_entry(name: &str, size: u64, num_files: u32, is_bad: bool) -> FileEntry {
+        let mut entry = FileEntry::new(
+            name.to_string(),
+            size,
+            num_files,
+            "TestMod".to_string(),
+            PathBuf::from(format!("/path/to/{}", name)),
+        );
+        if is_bad {
+            entry.health = ArchiveHealth::InvalidFormat("truncated header".to_string());
+        }
+        entry
+    }
+
+    #[test]
+    fn test_file_entry_creation() {
+        let entry = create_test_entry("test.ba2", 1000, 10, false);
+        assert_eq!(entry.file_name, "test.ba2");
+        assert_eq!(entry.file_size, 1000);
+        assert_eq!(entry.num_files, 10);
+        assert!(!entry.is_bad());
+    }
+
+    #[test]
+    fn test_file_entry_display_methods() {
+        let entry = create_test_entry("test.ba2", 1500, 25, false);
+        assert_eq!(entry.name_display(), "test.ba2");
+        assert_eq!(entry.file_count_display(), "25");
+        assert_eq!(entry.mod_display(), "TestMod");
+        assert!(!entry.size_display().is_empty());
+    }
+
+    #[test]
+    fn test_sorting_by_name() {
+        let mut entries = vec![
+            create_test_entry("zebra.ba2", 1000, 10, false),
+            create_test_entry("alpha.ba2", 2000, 20, false),
+            create_test_entry("beta.ba2", 1500, 15, false),
+        ];
+
+        // Ascending
+        entries.sort_by(|a, b| a.compare(b, SortBy::Name));
+        assert_eq!(entries[0].file_name, "alpha.ba2");
+        assert_eq!(entries[1].file_name, "beta.ba2");
+        assert_eq!(entries[2].file_name, "zebra.ba2");
+    }
+
+    #[test]
+    fn test_sorting_numbered_names() {
+        let mut entries = [
+            create_test_entry("Part 10 - Main.ba2", 1000, 10, false),
+            create_test_entry("part 2 - Main.ba2", 1000, 10, false),
+            create_test_entry("Part 1 - Main.ba2", 1000, 10, false),
+        ];
+        entries.sort_by(|a, b| a.compare(b, SortBy::Name));
+        assert_eq!(entries[0].file_name, "Part 1 - Main.ba2");
+        assert_eq!(entries[1].file_name, "part 2 - Main.ba2");
+        assert_eq!(entries[2].file_name, "Part 10 - Main.ba2");
+
+        for (entry, dir) in entries.iter_mut().zip(["Mod 9", "Mod 100", "Mod 10"]) {
+            entry.dir_name = dir.to_string();
+        }
+        entries.sort_by(|a, b| a.compare(b, SortBy::ModName));
+        let dirs: Vec<&str> = entries.iter().map(|e| e.dir_name.as_str()).collect();
+        assert_eq!(dirs, ["Mod 9", "Mod 10", "Mod 100"]);
+    }
+
+    #[test]
+    fn test_sorting_by_size() {
+        let mut entries = vec![
+            create_test_entry("small.ba2", 1000, 10, false),
+            create_test_entry("large.ba2", 3000, 30, false),
+            create_test_entry("medium.ba2", 2000, 20, false),
+        ];
+
+        // Ascending (Natural)
+        entries.sort_by(|a, b| a.compare(b, SortBy::Size));
+        assert_eq!(entries[0].file_name, "small.ba2");
+        assert_eq!(entries[1].file_name, "medium.ba2");
+        assert_eq!(entries[2].file_name, "large.ba2");
+    }
+
+    #[test]
+    fn test_sorting_by_file_count() {
+        let mut entries = vec![
+            create_test_entry("few.ba2", 1000, 5, false),
+            create_test_entry("many.ba2", 1000, 50, false),
+            create_test_entry("some.ba2", 1000, 20, false),
+        ];
+
+        // Ascending (Natural)
+        entries.sort_by(|a, b| a.compare(b, SortBy::FileCount));
+        assert_eq!(entries[0].file_name, "few.ba2");
+        assert_eq!(entries[1].file_name, "some.ba2");
+        assert_eq!(entries[2].file_name, "many.ba2");
+    }
+
+    #[test]
+    fn test_sorting_by_load_order() {
+        let mut list = FileEntryList::from_vec(vec![
+            create_test_entry("Unknown - Main.ba2", 1000, 10, false),
+            create_test_entry("Late - Main.ba2", 1000, 10, false),
+            create_test_entry("Early - Main.ba2", 1000, 10, false),
+        ]);
+        let order = LoadOrder::parse("*Early.esp\n*Late.esp\n", &FALLOUT4);
+        assert_eq!(list.apply_load_order(Some(&order)), 2);
+
+        // Ascending, archives without a known plugin last
+        list.sort_by(SortBy::LoadOrder, false);
+        assert_eq!(list.entries()[0].file_name, "Early - Main.ba2");
+        assert_eq!(list.entries()[1].file_name, "Late - Main.ba2");
+        assert_eq!(list.entries()[2].load_order, None);
+
+        assert_eq!(list.apply_load_order(None), 0);
+    }
+
+    #[test]
+    fn test_sorting_by_note() {
+        let mut list = FileEntryList::from_vec(vec![
+            create_test_entry("Plain - Main.ba2", 1000, 10, false),
+            create_test_entry("Later - Main.ba2", 1000, 10, false),
+            create_test_entry("Keep - Main.ba2", 1000, 10, false),
+        ]);
+        let mut notes = ModNotes::default();
+        for (name, tags) in [
+            ("Later - Main.ba2", "test later"),
+            ("Keep - Main.ba2", "keep packed"),
+        ] {
+            let dir_name = list.entries()[0].dir_name.clone();
+            notes.set(
+                ModNotes::archive_key(&dir_name, name),
+                Note::parse("", tags),
+            );
+        }
+        assert_eq!(list.apply_notes(&notes), 2);
+        assert_eq!(list.entries()[1].note_display(), "[test later]");
+
+        // Ascending by note, archives without one last
+        list.sort_by(SortBy::Note, false);
+        assert_eq!(list.entries()[0].file_name, "Keep - Main.ba2");
+        assert_eq!(list.entries()[2].file_name, "Plain - Main.ba2");
+    }
+
+    #[test]
+    fn test_default_ordering() {
+        let small = create_test_entry("small.ba2", 1000, 10, false);
+        let large = create_test_entry("large.ba2", 2000, 20, false);
+
+        // Ord implementation is still Descending Size
+        assert_eq!(small.cmp(&large), Ordering::Greater);
+        assert_eq!(large.cmp(&small), Ordering::Less);
+    }
+
+    #[test]
+    fn test_file_entry_list_sorting() {
+        let mut list = FileEntryList::from_vec(vec![
+            create_test_entry("zebra.ba2", 1000, 10, false),
+            create_test_entry("alpha.ba2", 2000, 20, false),
+        ]);
+
+        // Ascending
+        list.sort_by(SortBy::Name, false);
+        assert_eq!(list.entries()[0].file_name, "alpha.ba2");
+        assert_eq!(list.entries()[1].file_name, "zebra.ba2");
+
+        // Descending
+        list.sort_by(SortBy::Name, true);
+        assert_eq!(list.entries()[0].file_name, "zebra.ba2");
+        assert_eq!(list.entries()[1].file_name, "alpha.ba2");
+    }
+
+    #[test]
+    fn test_bad_file_handling() {
+        let list = FileEntryList::from_vec(vec![
+            create_test_entry("good1.ba2", 1000, 10, false),
+            create_test_entry("bad.ba2", 2000, 0, true),
+            create_test_entry("good2.ba2", 3000, 30, false),
+        ]);
+
+        assert_eq!(list.bad_file_count(), 1);
+
+        let bad_indices = list.bad_file_indices();
+        assert_eq!(bad_indices, vec![1]);
+    }
+
+    #[test]
+    fn test_filter_bad_files() {
+        let mut list = FileEntryList::from_vec(vec![
+            create_test_entry("good1.ba2", 1000, 10, false),
+            create_test_entry("bad.ba2", 2000, 0, true),
+            create_test_entry("good2.ba2", 3000, 30, false),
+        ]);
+
+        list.filter_bad_files();
+        assert_eq!(list.len(), 2);
+        assert_eq!(list.bad_file_count(), 0);
+    }
+
+    #[test]
+    fn test_from_ba2fileinfo() {
+        let ba2_info = BA2FileInfo {
+            file_name: "test.ba2".to_string(),
+            file_size: 1000,
+            num_files: 10,
+            dir_name: "TestMod".to_string(),
+            full_path: PathBuf::from("/path/to/test.ba2"),
+            health: ArchiveHealth::Ok,
+            archive_type: "GNRL".to_string(),
+            format_version: 2,
+            ignored: false,
+            exclusion: None,
+        };
+
+        let entry: FileEntry = ba2_info.into();
+        assert_eq!(entry.file_name, "test.ba2");
+        assert_eq!(entry.file_size, 1000);
+        assert_eq!(entry.archive_type, "GNRL");
+        assert!(entry.is_starfield());
+        assert!(!entry.is_texture());
+        assert!(!entry.has_unknown_version(&STARFIELD));
+        assert!(entry.has_unknown_version(&FALLOUT4));
+        assert_eq!(entry.status, ExtractionStatus::Idle);
+
+        let mut entry = entry;
+        entry.format_version = 9;
+        assert!(entry.has_unknown_version(&STARFIELD));
+        entry.format_version = 0;
+        assert!(!entry.has_unknown_version(&FALLOUT4));
+    }
+
+    #[test]
+    fn test_selection_limits_extraction_candidates() {
+        let mut list = FileEntryList::from_vec(vec![
+            create_test_entry("a.ba2", 1000, 10, false),
+            create_test_entry("b.ba2", 2000, 20, false),
+        ]);
+        assert_eq!(list.extraction_candidates().len(), 2);
+
+        let paths = HashSet::from([list.entries()[1].full_path.clone()]);
+        assert_eq!(list.select_paths(&paths), 1);
+        let candidates = list.extraction_candidates();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].file_name, "b.ba2");
+
+        list.clear_selection();
+        assert_eq!(list.selected_count(), 0);
+        assert_eq!(list.extraction_candidates().len(), 2);
+    }
+
+    #[test]
+    fn test_set_status() {
+        let mut list = FileEntryList::from_vec(vec![
+            create_test_entry("a.ba2", 1000, 10, false),
+            create_test_entry("b.ba2", 2000, 20, false),
+        ]);
+
+        list.set_all_statuses(ExtractionStatus::Queued);
+        assert!(list.set_status(Path::new("/path/to/b.ba2"), ExtractionStatus::Failed));
+        assert!(!list.set_status(Path::new("/path/to/c.ba2"), ExtractionStatus::Done));
+
+        assert_eq!(list.entries()[0].status, ExtractionStatus::Queued);
+        assert_eq!(list.entries()[1].status, ExtractionStatus::Failed);
+        assert_eq!(list.failed_count(), 1);
+
+        let time = Duration::from_millis(2_500);
+        assert!(list.set_extraction_time(Path::new("/path/to/b.ba2"), time));
+        assert_eq!(list.entries()[1].extraction_time, Some(time));
+        assert_eq!(list.entries()[1].extraction_time_display(), "2.5s");
+        assert_eq!(list.entries()[0].extraction_time_display(), "");
+        list.set_all_statuses(ExtractionStatus::Queued);
+        assert_eq!(list.entries()[1].extraction_time, None);
+    }
+
+    #[test]
+    fn test_skip_pending() {
+        let mut list = FileEntryList::from_vec(vec![
+            create_test_entry("a.ba2", 1000, 10, false),
+            create_test_entry("b.ba2", 2000, 20, false),
+            create_test_entry("c.ba2", 3000, 30, false),
+        ]);
+
+        list.set_all_statuses(ExtractionStatus::Queued);
+        list.set_status(Path::new("/path/to/a.ba2"), ExtractionStatus::Done);
+        list.set_status(Path::new("/path/to/b.ba2"), ExtractionStatus::Extracting);
+        list.skip_pending();
+
+        assert_eq!(list.entries()[0].status, ExtractionStatus::Done);
+        assert_eq!(list.entries()[1].status, ExtractionStatus::Skipped);
+        assert_eq!(list.entries()[2].status, ExtractionStatus::Skipped);
+    }
+}
@@ -0,0 +1,238 @@
+//! Aggregate statistics over scanned archives
+//!
+//! Computed once after a scan and rendered on the dashboard page.
+
+use super::FileEntry;
+use crate::operations::format_size;
+use std::collections::HashMap;
+
+/// Number of archives Fallout 4 can reliably load, the limit of its game profile
+pub const ENGINE_ARCHIVE_LIMIT: usize = 235;
+
+/// Number of mods listed in [`ArchiveStats::largest_mods`]
+const LARGEST_MODS_COUNT: usize = 5;
+
+/// Upper bounds (exclusive) of the size histogram buckets; the last bucket is open-ended
+const HISTOGRAM_BOUNDS: [u64; 4] = [1 << 20, 10 << 20, 100 << 20, 1 << 30];
+
+/// Size totals for a single mod folder
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModStats {
+    /// Mod folder name
+    pub name: String,
+
+    /// Number of archives in the mod
+    pub archive_count: usize,
+
+    /// Combined size of the mod's archives in bytes
+    pub total_size: u64,
+}
+
+/// One bar of the archive size histogram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistogramBucket {
+    /// Inclusive lower bound in bytes
+    pub min: u64,
+
+    /// Exclusive upper bound in bytes (`None` = no upper bound)
+    pub max: Option<u64>,
+
+    /// Number of archives in this size range
+    pub count: usize,
+}
+
+impl HistogramBucket {
+    /// Get a label for the bucket range (e.g., "1 MiB – 10 MiB")
+    pub fn label(&self) -> String {
+        match (self.min, self.max) {
+            (0, Some(max)) => format!("< {}", format_size(max)),
+            (min, Some(max)) => format!("{} – {}", format_size(min), format_size(max)),
+            (min, None) => format!("≥ {}", format_size(min)),
+        }
+    }
+}
+
+/// Summary of a scan result
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveStats {
+    /// Number of archives found
+    pub total_archives: usize,
+
+    /// General (GNRL) archives
+    pub general_archives: usize,
+
+    /// Texture (DX10) archives
+    pub texture_archives: usize,
+
+    /// Archives whose type could not be determined
+    pub unknown_archives: usize,
+
+    /// Archives whose header could not be parsed
+    pub corrupted_archives: usize,
+
+    /// Combined size of all archives in bytes
+    pub total_size: u64,
+
+    /// Average archive size in bytes
+    pub average_size: u64,
+
+    /// Combined number of files contained in all archives
+    pub total_contained_files: u64,
+
+    /// Archives beyond the game's archive limit (0 if it has none)
+    pub over_limit: usize,
+
+    /// Mods with the largest combined archive size, largest first
+    pub largest_mods: Vec<ModStats>,
+
+    /// Archive count per size range, smallest range first
+    pub histogram: Vec<HistogramBucket>,
+}
+
+impl ArchiveStats {
+    /// Compute statistics for a set of archives of a game loading at most `limit` archives
+    pub fn compute(entries: &[FileEntry], limit: Option<usize>) -> Self {
+        let mut stats = Self {
+            total_archives: entries.len(),
+            histogram: empty_histogram(),
+            ..Self::default()
+        };
+
+        let mut mods: HashMap<&str, ModStats> = HashMap::new();
+
+        for entry in entries {
+            match entry.archive_type.as_str() {
+                "GNRL" => stats.general_archives += 1,
+                "DX10" => stats.texture_archives += 1,
+                _ => stats.unknown_archives += 1,
+            }
+            if entry.is_corrupted() {
+                stats.corrupted_archives += 1;
+            }
+
+            stats.total_size += entry.file_size;
+            stats.total_contained_files += u64::from(entry.num_files);
+
+            if let Some(bucket) = stats
+                .histogram
+                .iter_mut()
+                .find(|b| b.max.is_none_or(|max| entry.file_size < max))
+            {
+                bucket.count += 1;
+            }
+
+            let mod_stats = mods.entry(&entry.dir_name).or_insert_with(|| ModStats {
+                name: entry.dir_name.clone(),
+                archive_count: 0,
+                total_size: 0,
+            });
+            mod_stats.archive_count += 1;
+            mod_stats.total_size += entry.file_size;
+        }
+
+        if stats.total_archives > 0 {
+            stats.average_size = stats.total_size / stats.total_archives as u64;
+        }
+        stats.over_limit = limit.map_or(0, |limit| stats.total_archives.saturating_sub(limit));
+
+        let mut largest: Vec<ModStats> = mods.into_values().collect();
+        largest.sort_by(|a, b| b.total_size.cmp(&a.total_size).then_with(|| a.name.cmp(&b.name)));
+        largest.truncate(LARGEST_MODS_COUNT);
+        stats.largest_mods = largest;
+
+        stats
+    }
+
+    /// Size of the largest histogram bucket (for scaling bars)
+    pub fn max_bucket_count(&self) -> usize {
+        self.histogram.iter().map(|b| b.count).max().unwrap_or(0)
+    }
+}
+
+/// Build histogram buckets with zero counts
+fn empty_histogram() -> Vec<HistogramBucket> {
+    let mut buckets = Vec::with_capacity(HISTOGRAM_BOUNDS.len() + 1);
+    let mut min = 0;
+    for max in HISTOGRAM_BOUNDS {
+        buckets.push(HistogramBucket {
+            min,
+            max: Some(max),
+            count: 0,
+        });
+        min = max;
+    }
+    buckets.push(HistogramBucket {
+        min,
+        max: None,
+        count: 0,
+    });
+    buckets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ArchiveHealth;
+    use std::path::PathBuf;
+
+    fn entry(name: &str, mod_name: &str, size: u64, archive_type: &str, is_bad: bool) -> FileEntry {
+        let mut entry = FileEntry::new(
+            name.to_string(),
+            size,
+            10,
+            mod_name.to_string(),
+            PathBuf::from(format!("/data/{mod_name}/{name}")),
+        );
+        if is_bad {
+            entry.health = ArchiveHealth::InvalidFormat("truncated header".to_string());
+        }
+        entry.archive_type = archive_type.to_string();
+        entry
+    }
+
+    #[test]
+    fn test_compute_totals_and_types() {
+        let entries = vec![
+            entry("a - Main.ba2", "ModA", 2 << 20, "GNRL", false),
+            entry("a - Textures.ba2", "ModA", 50 << 20, "DX10", false),
+            entry("b - Main.ba2", "ModB", 512, "", true),
+        ];
+
+        let stats = ArchiveStats::compute(&entries, Some(ENGINE_ARCHIVE_LIMIT));
+        assert_eq!(stats.total_archives, 3);
+        assert_eq!(stats.general_archives, 1);
+        assert_eq!(stats.texture_archives, 1);
+        assert_eq!(stats.unknown_archives, 1);
+        assert_eq!(stats.corrupted_archives, 1);
+        assert_eq!(stats.total_contained_files, 30);
+        assert_eq!(stats.total_size, (52 << 20) + 512);
+        assert_eq!(stats.over_limit, 0);
+
+        assert_eq!(stats.largest_mods[0].name, "ModA");
+        assert_eq!(stats.largest_mods[0].archive_count, 2);
+
+        let counts: Vec<usize> = stats.histogram.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 1, 1, 0, 0]);
+        assert_eq!(stats.max_bucket_count(), 1);
+    }
+
+    #[test]
+    fn test_over_limit() {
+        let entries: Vec<FileEntry> = (0..240)
+            .map(|i| entry(&format!("{i} - Main.ba2"), &format!("Mod{i}"), 1000, "GNRL", false))
+            .collect();
+
+        let stats = ArchiveStats::compute(&entries, Some(ENGINE_ARCHIVE_LIMIT));
+        assert_eq!(stats.over_limit, 5);
+        assert_eq!(stats.largest_mods.len(), LARGEST_MODS_COUNT);
+        assert_eq!(ArchiveStats::compute(&entries, None).over_limit, 0);
+    }
+
+    #[test]
+    fn test_empty() {
+        let stats = ArchiveStats::compute(&[], None);
+        assert_eq!(stats.total_archives, 0);
+        assert_eq!(stats.average_size, 0);
+        assert_eq!(stats.histogram.len(), HISTOGRAM_BOUNDS.len() + 1);
+    }
+}
@@ -0,0 +1,288 @@
+//! Recommendations of which archives to unpack
+//!
+//! Every archive unpacked into loose files frees one slot of the engine's
+//! archive limit, but leaves its files loose in the mod folder. The advisor
+//! picks the fewest archives needed to get under the limit, preferring the
+//! ones that unpack to the fewest loose files:
+//!
+//! - Only archives the game loads count; those of disabled plugins are left alone
+//! - Texture (DX10) archives weigh more, as loose textures load slower
+//! - Smaller archives win ties, then archives of later-loaded plugins, whose
+//!   loose files keep overriding the same assets as their archive did
+//! - Corrupted archives and archives installed by a Wabbajack modlist are never
+//!   recommended
+//!
+//! [`simulate`] predicts the archive count after extracting any selection, so
+//! users can check that it solves the limit problem before running.
+
+use crate::models::FileEntry;
+use crate::operations::loadorder::LoadOrder;
+use std::cmp::Reverse;
+use std::path::PathBuf;
+
+/// Cost multiplier of the loose files unpacked from texture archives
+const TEXTURE_WEIGHT: u64 = 2;
+
+/// Archives recommended for unpacking
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Recommendation {
+    /// Archives to unpack, most recommended first
+    pub archives: Vec<PathBuf>,
+
+    /// Archives the game loads before unpacking
+    pub loaded: usize,
+
+    /// Archive limit the recommendation aims for
+    pub limit: usize,
+
+    /// Loose files the recommended archives unpack to
+    pub loose_files: u64,
+
+    /// Combined size of the recommended archives in bytes
+    pub total_size: u64,
+}
+
+impl Recommendation {
+    /// Whether the loaded archives exceed the limit at all
+    pub const fn is_needed(&self) -> bool {
+        self.loaded > self.limit
+    }
+
+    /// Archives the game loads after unpacking the recommended ones
+    pub const fn remaining(&self) -> usize {
+        self.loaded - self.archives.len()
+    }
+
+    /// Archives still over the limit when there were not enough candidates
+    pub const fn shortfall(&self) -> usize {
+        self.remaining().saturating_sub(self.limit)
+    }
+}
+
+/// Recommend the archives to unpack to bring the loaded archives under `limit`
+pub fn recommend(
+    entries: &[FileEntry],
+    load_order: Option<&LoadOrder>,
+    limit: usize,
+) -> Recommendation {
+    let loaded: Vec<&FileEntry> = entries
+        .iter()
+        .filter(|e| load_order.is_none_or(|order| order.loads(&e.file_name)))
+        .collect();
+    let mut recommendation = Recommendation {
+        loaded: loaded.len(),
+        limit,
+        ..Recommendation::default()
+    };
+    let excess = loaded.len().saturating_sub(limit);
+    if excess == 0 {
+        return recommendation;
+    }
+
+    let mut candidates: Vec<&FileEntry> = loaded
+        .into_iter()
+        .filter(|e| !e.is_bad() && !e.is_excluded() && !e.in_modlist)
+        .collect();
+    candidates.sort_by_key(|e| {
+        (
+            unpack_cost(e),
+            e.file_size,
+            Reverse(e.load_order),
+            e.file_name.clone(),
+        )
+    });
+    candidates.truncate(excess);
+
+    for entry in candidates {
+        recommendation.loose_files += u64::from(entry.num_files);
+        recommendation.total_size += entry.file_size;
+        recommendation.archives.push(entry.full_path.clone());
+    }
+    recommendation
+}
+
+/// Predicted archive budget after an extraction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Simulation {
+    /// Archives the game loads after the extraction
+    pub archives_after: usize,
+
+    /// Archive limit of the engine
+    pub limit: usize,
+
+    /// Archives the extraction unpacks
+    pub unpacked: usize,
+
+    /// Loose files the extraction creates
+    pub loose_files: u64,
+}
+
+impl Simulation {
+    /// Whether the loaded archives fit the limit after the extraction
+    pub const fn within_limit(&self) -> bool {
+        self.archives_after <= self.limit
+    }
+}
+
+/// Predict the archive count after extracting the entries `extracts` accepts
+///
+/// Corrupted archives are expected to fail and stay packed; archives of
+/// disabled plugins produce loose files but never counted against the limit.
+pub fn simulate(
+    entries: &[FileEntry],
+    load_order: Option<&LoadOrder>,
+    limit: usize,
+    extracts: impl Fn(&FileEntry) -> bool,
+) -> Simulation {
+    let mut simulation = Simulation {
+        limit,
+        ..Simulation::default()
+    };
+    for entry in entries {
+        if !entry.is_bad() && !entry.is_excluded() && extracts(entry) {
+            simulation.unpacked += 1;
+            simulation.loose_files += u64::from(entry.num_files);
+        } else if load_order.is_none_or(|order| order.loads(&entry.file_name)) {
+            simulation.archives_after += 1;
+        }
+    }
+    simulation
+}
+
+/// Weighted number of loose files an archive unpacks to
+fn unpack_cost(entry: &FileEntry) -> u64 {
+    let weight = if entry.is_texture() {
+        TEXTURE_WEIGHT
+    } else {
+        1
+    };
+    u64::from(entry.num_files) * weight
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ArchiveHealth;
+    use crate::config::profile::FALLOUT4;
+
+    fn entry(name: &str, num_files: u32, archive_type: &str) -> FileEntry {
+        let mut entry = FileEntry::new(
+            name.to_string(),
+            u64::from(num_files) * 1000,
+            num_files,
+            "Mod".to_string(),
+            PathBuf::from(format!("/mods/Mod/{name}")),
+        );
+        entry.archive_type = archive_type.to_string();
+        entry
+    }
+
+    /// `count` general archives with 100 files each
+    fn dataset(count: usize) -> Vec<FileEntry> {
+        (0..count)
+            .map(|i| entry(&format!("Bulk{i:03} - Main.ba2"), 100, "GNRL"))
+            .collect()
+    }
+
+    fn names(recommendation: &Recommendation) -> Vec<String> {
+        recommendation
+            .archives
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_under_limit_needs_nothing() {
+        let recommendation = recommend(&dataset(10), None, 10);
+        assert!(!recommendation.is_needed());
+        assert!(recommendation.archives.is_empty());
+    }
+
+    #[test]
+    fn test_prefers_fewest_loose_files() {
+        let mut entries = dataset(10);
+        entries.push(entry("Tiny - Main.ba2", 3, "GNRL"));
+        entries.push(entry("Small - Textures.ba2", 5, "DX10"));
+        entries.push(entry("Small - Main.ba2", 8, "GNRL"));
+
+        let recommendation = recommend(&entries, None, 11);
+        assert!(recommendation.is_needed());
+        // Five textures weigh more than eight general files
+        assert_eq!(
+            names(&recommendation),
+            ["Tiny - Main.ba2", "Small - Main.ba2"]
+        );
+        assert_eq!(recommendation.loose_files, 11);
+        assert_eq!(recommendation.remaining(), 11);
+        assert_eq!(recommendation.shortfall(), 0);
+    }
+
+    #[test]
+    fn test_skips_unloaded_and_protected_archives() {
+        let mut entries = dataset(4);
+        entries.push(entry("Disabled - Main.ba2", 1, "GNRL"));
+        let mut bad = entry("Broken - Main.ba2", 1, "GNRL");
+        bad.health = ArchiveHealth::InvalidFormat(String::new());
+        entries.push(bad);
+        let mut listed = entry("Listed - Main.ba2", 1, "GNRL");
+        listed.in_modlist = true;
+        entries.push(listed);
+
+        let order = LoadOrder::parse("Disabled.esp\n*Broken.esp\n*Listed.esp\n", &FALLOUT4);
+        let recommendation = recommend(&entries, Some(&order), 5);
+        assert_eq!(recommendation.loaded, 6);
+        assert_eq!(recommendation.archives.len(), 1);
+        assert!(names(&recommendation)[0].starts_with("Bulk"));
+    }
+
+    #[test]
+    fn test_ties_prefer_later_plugins() {
+        let mut entries = vec![
+            entry("Early - Main.ba2", 10, "GNRL"),
+            entry("Late - Main.ba2", 10, "GNRL"),
+        ];
+        let order = LoadOrder::parse("*Early.esp\n*Late.esp\n", &FALLOUT4);
+        for e in &mut entries {
+            e.load_order = order.index_of(&e.file_name);
+        }
+
+        let recommendation = recommend(&entries, Some(&order), 1);
+        assert_eq!(names(&recommendation), ["Late - Main.ba2"]);
+    }
+
+    #[test]
+    fn test_simulates_selection() {
+        let mut entries = dataset(5);
+        entries.push(entry("Disabled - Main.ba2", 7, "GNRL"));
+        let mut bad = entry("Broken - Main.ba2", 1, "GNRL");
+        bad.health = ArchiveHealth::InvalidFormat(String::new());
+        entries.push(bad);
+        let order = LoadOrder::parse("Disabled.esp\n*Broken.esp\n", &FALLOUT4);
+
+        let everything = simulate(&entries, Some(&order), 3, |_| true);
+        assert_eq!(everything.archives_after, 1);
+        assert_eq!(everything.unpacked, 6);
+        assert_eq!(everything.loose_files, 507);
+        assert!(everything.within_limit());
+
+        let two = simulate(&entries, Some(&order), 3, |e| {
+            e.file_name.starts_with("Bulk000") || e.file_name.starts_with("Bulk001")
+        });
+        assert_eq!(two.archives_after, 4);
+        assert!(!two.within_limit());
+    }
+
+    #[test]
+    fn test_reports_shortfall() {
+        let mut entries = dataset(3);
+        for e in &mut entries {
+            e.in_modlist = true;
+        }
+        entries.push(entry("Own - Main.ba2", 10, "GNRL"));
+
+        let recommendation = recommend(&entries, None, 1);
+        assert_eq!(names(&recommendation), ["Own - Main.ba2"]);
+        assert_eq!(recommendation.shortfall(), 2);
+    }
+}
@@ -0,0 +1,344 @@
+//! Audit log of every change made to mod folders
+//!
+//! The diagnostic log says what the app was doing; the audit log says what
+//! it changed. Every extraction, restore, backup, journaled operation, undo
+//! and ignore is appended to `audit.jsonl` as one JSON line with its time and
+//! outcome, so users can check afterwards what happened to their files. The
+//! file is only ever appended to; unreadable lines are skipped when reading.
+
+use crate::error::{Error, Result};
+use crate::operations::{AppEvent, BackupEvent, ExtractionProgress};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+/// Name of the audit file in the data folder
+const AUDIT_FILE: &str = "audit.jsonl";
+
+/// Kind of change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    /// An archive was unpacked into its folder
+    Extracted,
+    /// An archive was restored from its backup
+    Restored,
+    /// Loose files were backed up before an archive overwrote them
+    BackedUp,
+    /// A journaled operation removed or moved files (cleaning, quarantine)
+    Operation,
+    /// A journaled operation was undone
+    Undone,
+    /// An archive was put on the ignore list
+    Ignored,
+    /// An archive was taken off the ignore list
+    Unignored,
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Extracted => "Extracted",
+            Self::Restored => "Restored",
+            Self::BackedUp => "Backed up",
+            Self::Operation => "Changed",
+            Self::Undone => "Undid",
+            Self::Ignored => "Ignored",
+            Self::Unignored => "Unignored",
+        })
+    }
+}
+
+/// Whether a change went through
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum AuditOutcome {
+    /// The change was made
+    Succeeded,
+    /// The change failed
+    Failed {
+        /// Why it failed
+        error: String,
+    },
+}
+
+/// A recorded change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Time of the change (seconds since the Unix epoch)
+    pub at: u64,
+
+    /// Kind of change
+    pub action: AuditAction,
+
+    /// What was changed: a path, or the description of an operation
+    pub target: String,
+
+    /// Where to, or how much (e.g. the folder an archive was unpacked into)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+
+    /// Whether the change went through
+    pub outcome: AuditOutcome,
+}
+
+impl AuditEntry {
+    /// A successful change made now
+    pub fn new(action: AuditAction, target: impl Into<String>) -> Self {
+        Self {
+            at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            action,
+            target: target.into(),
+            detail: String::new(),
+            outcome: AuditOutcome::Succeeded,
+        }
+    }
+
+    /// Add where to or how much
+    #[must_use]
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+
+    /// Mark the change as failed
+    #[must_use]
+    pub fn failed(mut self, error: impl Into<String>) -> Self {
+        self.outcome = AuditOutcome::Failed {
+            error: error.into(),
+        };
+        self
+    }
+
+    /// One line describing the change, without its time
+    ///
+    /// E.g. "Extracted Mod - Main.ba2 → C:\Mods\Mod" or "Ignored Mod.ba2
+    /// (failed: access denied)".
+    pub fn summary(&self) -> String {
+        let mut line = format!("{} {}", self.action, self.target);
+        if !self.detail.is_empty() {
+            let separator = if self.action == AuditAction::Extracted {
+                " → "
+            } else {
+                ": "
+            };
+            line.push_str(separator);
+            line.push_str(&self.detail);
+        }
+        if let AuditOutcome::Failed { error } = &self.outcome {
+            line.push_str(" (failed: ");
+            line.push_str(error);
+            line.push(')');
+        }
+        line
+    }
+}
+
+/// The append-only audit file
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+/// Get the default audit file
+pub fn default_audit_path() -> Option<PathBuf> {
+    ProjectDirs::from("com", "evildarkarchon", "unpackrr")
+        .map(|dirs| dirs.data_local_dir().join(AUDIT_FILE))
+}
+
+impl AuditLog {
+    /// Use the audit file at `path`
+    pub const fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Open the audit file in the default folder
+    pub fn open_default() -> Option<Self> {
+        default_audit_path().map(Self::new)
+    }
+
+    /// Path of the audit file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry
+    pub fn record(&self, entry: &AuditEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut line = serde_json::to_string(entry)
+            .map_err(|e| Error::Other(format!("Failed to serialize audit entry: {e}")))?;
+        line.push('\n');
+        // One write per entry, so concurrent writers don't interleave lines
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?
+            .write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Append an entry, logging a failure instead of returning it
+    ///
+    /// The change itself already happened; failing to audit it must not
+    /// fail the operation.
+    pub fn record_or_warn(&self, entry: &AuditEntry) {
+        if let Err(e) = self.record(entry) {
+            tracing::warn!("Failed to write the audit log: {}", e);
+        }
+    }
+
+    /// All entries, oldest first
+    pub fn entries(&self) -> Vec<AuditEntry> {
+        let Ok(content) = fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    tracing::debug!("Skipping unreadable audit line: {}", e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// The last `limit` entries, newest first
+    pub fn recent(&self, limit: usize) -> Vec<AuditEntry> {
+        let mut entries = self.entries();
+        let start = entries.len().saturating_sub(limit);
+        let mut recent = entries.split_off(start);
+        recent.reverse();
+        recent
+    }
+}
+
+/// Map an event to the change it reports, if it reports one
+pub fn audit_entry(event: &AppEvent) -> Option<AuditEntry> {
+    match event {
+        AppEvent::Extract(ExtractionProgress::Completed {
+            file_path,
+            success,
+            error,
+            ..
+        }) => {
+            let destination = file_path
+                .parent()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default();
+            let entry = AuditEntry::new(AuditAction::Extracted, file_path.display().to_string())
+                .with_detail(destination);
+            Some(if *success {
+                entry
+            } else {
+                entry.failed(error.clone().unwrap_or_else(|| "unknown error".to_string()))
+            })
+        }
+        AppEvent::Backup(BackupEvent::Restored { archive, backup }) => Some(
+            AuditEntry::new(AuditAction::Restored, archive.display().to_string())
+                .with_detail(format!("from {}", backup.display())),
+        ),
+        AppEvent::Backup(BackupEvent::ArchiveSaved { archive, backup }) => Some(
+            AuditEntry::new(AuditAction::BackedUp, archive.display().to_string())
+                .with_detail(format!("to {}", backup.display())),
+        ),
+        AppEvent::Backup(BackupEvent::LooseFilesSaved { archive, count }) => Some(
+            AuditEntry::new(AuditAction::BackedUp, archive.display().to_string())
+                .with_detail(format!("{count} loose files it overwrites")),
+        ),
+        _ => None,
+    }
+}
+
+/// Record the changes reported by `events` until the bus is dropped
+///
+/// Subscribe losslessly ([`EventBus::subscribe`](crate::operations::EventBus::subscribe)),
+/// so no change is missed.
+pub async fn record_events(log: AuditLog, mut events: mpsc::Receiver<AppEvent>) {
+    while let Some(event) = events.recv().await {
+        if let Some(entry) = audit_entry(&event) {
+            let log = log.clone();
+            let _ = tokio::task::spawn_blocking(move || log.record_or_warn(&entry)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_record_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::new(dir.path().join("audit").join(AUDIT_FILE));
+        assert!(log.entries().is_empty());
+
+        log.record(
+            &AuditEntry::new(AuditAction::Extracted, "Mod/Mod - Main.ba2").with_detail("Mod"),
+        )
+        .unwrap();
+        log.record(&AuditEntry::new(AuditAction::Ignored, "Mod/Mod - Textures.ba2").failed("no"))
+            .unwrap();
+        // A torn line from a crash doesn't hide the rest
+        fs::write(
+            log.path(),
+            fs::read_to_string(log.path()).unwrap() + "{\"at\":\n",
+        )
+        .unwrap();
+        log.record(&AuditEntry::new(AuditAction::Undone, "Clean extraction"))
+            .unwrap();
+
+        let entries = log.entries();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].summary(), "Extracted Mod/Mod - Main.ba2 → Mod");
+        assert_eq!(
+            entries[1].summary(),
+            "Ignored Mod/Mod - Textures.ba2 (failed: no)"
+        );
+        let recent = log.recent(2);
+        assert_eq!(recent[0].action, AuditAction::Undone);
+        assert_eq!(recent[1].action, AuditAction::Ignored);
+    }
+
+    #[test]
+    fn test_audit_entry_from_event() {
+        let event = AppEvent::Extract(ExtractionProgress::Completed {
+            file_name: "Mod - Main.ba2".to_string(),
+            file_path: PathBuf::from("Mods/Mod/Mod - Main.ba2"),
+            success: false,
+            error: Some("locked".to_string()),
+            warnings: Vec::new(),
+            output_size: None,
+            error_category: None,
+            duration: Duration::from_secs(3),
+        });
+        let entry = audit_entry(&event).unwrap();
+        assert_eq!(entry.action, AuditAction::Extracted);
+        assert_eq!(entry.detail, Path::new("Mods/Mod").display().to_string());
+        assert_eq!(
+            entry.outcome,
+            AuditOutcome::Failed {
+                error: "locked".to_string()
+            }
+        );
+
+        let progress = AppEvent::Extract(ExtractionProgress::FileProgress {
+            file_name: "Mod - Main.ba2".to_string(),
+            file_path: PathBuf::from("Mods/Mod/Mod - Main.ba2"),
+            percent: 50,
+        });
+        assert!(audit_entry(&progress).is_none());
+    }
+}
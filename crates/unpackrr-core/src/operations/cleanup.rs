@@ -0,0 +1,441 @@
+//! Cleaning up after interrupted runs
+//!
+//! A cancelled or failed extraction can leave things behind next to its
+//! archive: the staging folder of an extraction that was killed before it
+//! could remove it, output folders the tool created before it wrote anything
+//! into them, and temporary files the unpacking tool wrote in place of its
+//! files.
+//! [`find_leftovers`] lists them without changing anything;
+//! [`clean_leftovers`] removes them and reports what was removed.
+//!
+//! Only the archive's own staging folder and the folders its name table
+//! writes into are looked at, so the rest of a mod folder or of the game's
+//! Data folder is never touched. Every removal goes through a journal
+//! [`Operation`] if given, so it can be undone.
+
+use crate::ba2::read_file_names;
+use crate::config::AppConfig;
+use crate::error::{Result, ValidationError};
+use crate::operations::journal::Operation;
+use crate::operations::staging::staging_path;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Extensions the tool appends to a file while writing it
+const TEMP_EXTENSIONS: &[&str] = &["tmp"];
+
+/// What a leftover is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeftoverKind {
+    /// An output folder with nothing in it once the other leftovers are gone
+    EmptyFolder,
+
+    /// The staging folder of an interrupted extraction, with its partial files
+    Staging,
+
+    /// A temporary file written in place of one of the archive's files
+    TempFile,
+}
+
+impl LeftoverKind {
+    /// Name shown to the user
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::EmptyFolder => "Empty folder",
+            Self::Staging => "Staging folder",
+            Self::TempFile => "Temporary file",
+        }
+    }
+}
+
+/// Something an interrupted run left behind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leftover {
+    /// The file or folder
+    pub path: PathBuf,
+
+    /// What it is
+    pub kind: LeftoverKind,
+}
+
+/// What the archives of one folder write, lowercase and `/` separated
+#[derive(Debug, Default)]
+struct Outputs {
+    /// Folders files are written into
+    folders: BTreeSet<String>,
+
+    /// Files written
+    files: BTreeSet<String>,
+}
+
+impl Outputs {
+    /// Add an archive-internal file, e.g. `Meshes\Armor\a.nif`, which is
+    /// written into `meshes` and `meshes/armor`
+    fn add(&mut self, name: &str) {
+        let name = name.replace('\\', "/").to_lowercase();
+        let parts: Vec<&str> = name.split('/').filter(|part| !part.is_empty()).collect();
+        for depth in 1..parts.len() {
+            self.folders.insert(parts[..depth].join("/"));
+        }
+        self.files.insert(parts.join("/"));
+    }
+
+    /// Whether `path` is a temporary file written in place of one of the files
+    fn is_temp_file(&self, path: &str) -> bool {
+        path.rsplit_once('.').is_some_and(|(file, extension)| {
+            TEMP_EXTENSIONS.contains(&extension) && self.files.contains(file)
+        })
+    }
+}
+
+/// List the leftovers of extracting `archives` (a dry run)
+///
+/// Looks at each archive's staging folder and at the folders next to it that
+/// the archive's files are written into. Files are listed before the folders
+/// they are in, and inner folders before outer ones, the order
+/// [`clean_leftovers`] needs.
+pub fn find_leftovers(archives: &[PathBuf]) -> Vec<Leftover> {
+    let archives: BTreeSet<&PathBuf> = archives.iter().collect();
+
+    // Archives of the same folder write into the same output folders
+    let mut outputs: BTreeMap<&Path, Outputs> = BTreeMap::new();
+    let mut leftovers = Vec::new();
+    for archive in archives {
+        let Some(destination) = archive.parent() else {
+            continue;
+        };
+        let staging = staging_path(archive, destination);
+        if fs::symlink_metadata(&staging).is_ok_and(|metadata| metadata.is_dir()) {
+            leftovers.push(Leftover {
+                path: staging,
+                kind: LeftoverKind::Staging,
+            });
+        }
+        match read_file_names(archive) {
+            Ok(names) => {
+                let output = outputs.entry(destination).or_default();
+                for name in &names {
+                    output.add(name);
+                }
+            }
+            Err(e) => tracing::debug!(
+                "Not looking at the output folders of {}: {}",
+                archive.display(),
+                e
+            ),
+        }
+    }
+
+    for (destination, output) in outputs {
+        visit(destination, "", &output, &mut leftovers);
+    }
+    leftovers
+}
+
+/// Collect the leftovers in `dir`, returning whether it holds nothing else
+///
+/// `relative` is the lowercase path of `dir` below the archives' folder;
+/// only the folders of `output` are looked into.
+fn visit(dir: &Path, relative: &str, output: &Outputs, leftovers: &mut Vec<Leftover>) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    let mut only_leftovers = true;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // Links are never followed, and count as something to keep
+        let Ok(file_type) = entry.file_type() else {
+            only_leftovers = false;
+            continue;
+        };
+        let child = if relative.is_empty() {
+            name.to_lowercase()
+        } else {
+            format!("{relative}/{}", name.to_lowercase())
+        };
+        if file_type.is_dir() && output.folders.contains(&child) {
+            if visit(&path, &child, output, leftovers) {
+                leftovers.push(Leftover {
+                    path,
+                    kind: LeftoverKind::EmptyFolder,
+                });
+                continue;
+            }
+        } else if file_type.is_file() && output.is_temp_file(&child) {
+            leftovers.push(Leftover {
+                path,
+                kind: LeftoverKind::TempFile,
+            });
+            continue;
+        }
+        only_leftovers = false;
+    }
+    only_leftovers
+}
+
+/// What a cleanup removed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanupReport {
+    /// Leftovers removed
+    pub removed: Vec<Leftover>,
+
+    /// Leftovers that could not be removed, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl CleanupReport {
+    /// Whether nothing was removed or failed
+    pub const fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.failed.is_empty()
+    }
+
+    /// The report for the user, listing at most `limit` removed leftovers
+    pub fn summary(&self, limit: usize) -> String {
+        let mut text = format!("Removed {} leftovers.", self.removed.len());
+        for leftover in self.removed.iter().take(limit) {
+            let _ = write!(
+                text,
+                "\n{}: {}",
+                leftover.kind.label(),
+                leftover.path.display()
+            );
+        }
+        if self.removed.len() > limit {
+            let _ = write!(text, "\n... and {} more", self.removed.len() - limit);
+        }
+        if !self.failed.is_empty() {
+            let _ = write!(text, "\n\nCould not remove {}:", self.failed.len());
+            for (path, reason) in self.failed.iter().take(limit) {
+                let _ = write!(text, "\n{}: {reason}", path.display());
+            }
+        }
+        text
+    }
+}
+
+/// Remove `leftovers`, recording every removal in `operation` if given
+///
+/// Refused in analysis-only mode. A leftover that can't be removed, e.g. a
+/// folder that isn't empty anymore, is reported and the others still go.
+pub fn clean_leftovers(
+    leftovers: &[Leftover],
+    config: &AppConfig,
+    mut operation: Option<&mut Operation>,
+) -> Result<CleanupReport> {
+    if config.advanced.analysis_only {
+        return Err(ValidationError::ReadOnlyMode("Cleanup".to_string()).into());
+    }
+
+    let mut report = CleanupReport::default();
+    for leftover in leftovers {
+        let path = &leftover.path;
+        let operation = operation.as_deref_mut();
+        let result = match leftover.kind {
+            LeftoverKind::EmptyFolder => remove_folder(path, operation),
+            LeftoverKind::Staging => remove_tree(path, operation),
+            LeftoverKind::TempFile => remove_file(path, operation),
+        };
+        match result {
+            Ok(()) => report.removed.push(leftover.clone()),
+            Err(e) => {
+                tracing::warn!("Could not remove {}: {}", path.display(), e);
+                report.failed.push((path.clone(), e.to_string()));
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Remove a file, through `operation` if given
+fn remove_file(path: &Path, operation: Option<&mut Operation>) -> Result<()> {
+    match operation {
+        Some(operation) => operation.remove_file(path),
+        None => Ok(fs::remove_file(path)?),
+    }
+}
+
+/// Remove an empty folder, through `operation` if given
+///
+/// Fails, leaving the folder, if something turned up in it since it was listed.
+fn remove_folder(path: &Path, operation: Option<&mut Operation>) -> Result<()> {
+    match operation {
+        Some(operation) => operation.remove_folder(path),
+        None => Ok(fs::remove_dir(path)?),
+    }
+}
+
+/// Remove a folder and everything in it, innermost first
+fn remove_tree(dir: &Path, mut operation: Option<&mut Operation>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            remove_tree(&entry.path(), operation.as_deref_mut())?;
+        } else {
+            remove_file(&entry.path(), operation.as_deref_mut())?;
+        }
+    }
+    remove_folder(dir, operation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::journal::OperationJournal;
+    use crate::operations::staging::STAGING_PREFIX;
+    use crate::testing::fixtures::write_gnrl;
+    use tempfile::TempDir;
+
+    fn write(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, b"data").unwrap();
+    }
+
+    /// A mod folder with an archive writing into `meshes`, `sound` and `textures`
+    fn mod_folder(root: &Path) -> (PathBuf, PathBuf) {
+        let mod_dir = root.join("Mod");
+        let archive = mod_dir.join("Mod - Main.ba2");
+        write_gnrl(
+            &archive,
+            &[
+                "meshes\\kept.nif",
+                "Sound\\Voice\\a.fuz",
+                "textures\\armor\\iron\\a.dds",
+            ],
+        )
+        .unwrap();
+        (mod_dir, archive)
+    }
+
+    fn relative(leftovers: &[Leftover], root: &Path) -> Vec<(String, LeftoverKind)> {
+        leftovers
+            .iter()
+            .map(|leftover| {
+                let path = leftover.path.strip_prefix(root).unwrap();
+                (path.display().to_string().replace('\\', "/"), leftover.kind)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_and_clean_leftovers() {
+        let temp_dir = TempDir::new().unwrap();
+        let (mod_dir, archive) = mod_folder(temp_dir.path());
+        write(&mod_dir.join("meshes").join("kept.nif"));
+        fs::create_dir_all(mod_dir.join("textures").join("armor").join("iron")).unwrap();
+        write(&mod_dir.join("sound").join("voice").join("a.fuz.tmp"));
+        write(
+            &mod_dir
+                .join(format!("{STAGING_PREFIX}Mod - Main.ba2"))
+                .join("a.nif"),
+        );
+        // Not written by the archive
+        fs::create_dir_all(mod_dir.join("scripts")).unwrap();
+        write(&mod_dir.join("meshes").join("notes.tmp"));
+        fs::create_dir_all(mod_dir.join("textures").join("other")).unwrap();
+        write(
+            &mod_dir
+                .join(format!("{STAGING_PREFIX}Other - Main.ba2"))
+                .join("b.nif"),
+        );
+
+        let leftovers = find_leftovers(&[archive.clone(), archive]);
+        let mut listed = relative(&leftovers, &mod_dir);
+        listed.sort_by(|a, b| a.0.cmp(&b.0));
+        let expected = [
+            (
+                format!("{STAGING_PREFIX}Mod - Main.ba2"),
+                LeftoverKind::Staging,
+            ),
+            ("sound".to_string(), LeftoverKind::EmptyFolder),
+            ("sound/voice".to_string(), LeftoverKind::EmptyFolder),
+            ("sound/voice/a.fuz.tmp".to_string(), LeftoverKind::TempFile),
+            ("textures/armor".to_string(), LeftoverKind::EmptyFolder),
+            ("textures/armor/iron".to_string(), LeftoverKind::EmptyFolder),
+        ];
+        assert_eq!(listed, expected);
+
+        let report = clean_leftovers(&leftovers, &AppConfig::default(), None).unwrap();
+        assert_eq!(report.removed.len(), leftovers.len());
+        assert!(report.failed.is_empty());
+        let mut remaining: Vec<_> = fs::read_dir(&mod_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            [
+                format!("{STAGING_PREFIX}Other - Main.ba2").as_str(),
+                "Mod - Main.ba2",
+                "meshes",
+                "scripts",
+                "textures"
+            ]
+        );
+        assert!(mod_dir.join("textures").join("other").is_dir());
+    }
+
+    #[test]
+    fn test_cleanup_can_be_undone() {
+        let temp_dir = TempDir::new().unwrap();
+        let (mod_dir, archive) = mod_folder(temp_dir.path());
+        let staged = mod_dir
+            .join(format!("{STAGING_PREFIX}Mod - Main.ba2"))
+            .join("meshes")
+            .join("kept.nif");
+        write(&staged);
+        write(&mod_dir.join("sound").join("voice").join("a.fuz.tmp"));
+
+        let journal = OperationJournal::new(temp_dir.path().join("journal"));
+        let mut operation = journal.begin("Clean up leftovers");
+        let leftovers = find_leftovers(&[archive]);
+        let report =
+            clean_leftovers(&leftovers, &AppConfig::default(), Some(&mut operation)).unwrap();
+        journal.commit(operation).unwrap();
+        assert!(report.failed.is_empty());
+        assert!(!staged.exists());
+        assert!(!mod_dir.join("sound").exists());
+
+        journal.undo_last().unwrap().unwrap();
+        assert!(staged.is_file());
+        assert!(
+            mod_dir
+                .join("sound")
+                .join("voice")
+                .join("a.fuz.tmp")
+                .is_file()
+        );
+    }
+
+    #[test]
+    fn test_cleanup_refused_in_analysis_only_mode() {
+        let temp_dir = TempDir::new().unwrap();
+        let (mod_dir, archive) = mod_folder(temp_dir.path());
+        fs::create_dir(mod_dir.join("meshes")).unwrap();
+        let leftovers = find_leftovers(&[archive]);
+        assert_eq!(leftovers.len(), 1);
+
+        let mut config = AppConfig::default();
+        config.advanced.analysis_only = true;
+        assert!(clean_leftovers(&leftovers, &config, None).is_err());
+        assert!(mod_dir.join("meshes").exists());
+    }
+
+    #[test]
+    fn test_folder_filled_since_listed_is_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        let (mod_dir, archive) = mod_folder(temp_dir.path());
+        let folder = mod_dir.join("meshes");
+        fs::create_dir(&folder).unwrap();
+        let leftovers = find_leftovers(&[archive]);
+        write(&folder.join("new.nif"));
+
+        let report = clean_leftovers(&leftovers, &AppConfig::default(), None).unwrap();
+        assert!(report.removed.is_empty());
+        assert_eq!(report.failed.len(), 1);
+        assert!(folder.join("new.nif").is_file());
+    }
+}
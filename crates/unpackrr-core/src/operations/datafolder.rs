@@ -0,0 +1,146 @@
+//! Extracting into the game Data folder
+//!
+//! Without a mod manager, mod archives sit directly in the game's Data folder
+//! and unpacking them writes loose files next to the game's own, where nothing
+//! tracks which mod they came from. This is only done with
+//! `extraction.data_folder_mode` enabled, and then with safety rails:
+//!
+//! - The game's own archives are never listed ([`GameProfile::is_game_archive`])
+//! - Loose files an archive would overwrite are found before the run
+//!   ([`loose_conflicts`]) and copied to the backup folder before they are
+//!   replaced ([`back_up_loose_files`])
+//! - The UI asks for an explicit confirmation before the run starts
+//!
+//! [`GameProfile::is_game_archive`]: crate::config::profile::GameProfile::is_game_archive
+
+use crate::ba2::read_file_names;
+use crate::config::AppConfig;
+use crate::config::profile::Game;
+use crate::error::Result;
+use crate::platform::wine::{CaseFolder, host_path};
+use directories::ProjectDirs;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Get the default backup folder
+pub fn default_backup_root() -> Option<PathBuf> {
+    ProjectDirs::from("com", "evildarkarchon", "unpackrr")
+        .map(|dirs| dirs.data_local_dir().join("backups"))
+}
+
+/// Folder that archives and loose files of the Data folder are backed up to
+///
+/// Uses the configured backup path, falling back to [`default_backup_root`].
+pub fn backup_root(config: &AppConfig) -> Option<PathBuf> {
+    if config.advanced.backup_path.is_empty() {
+        default_backup_root()
+    } else {
+        Some(host_path(&config.advanced.backup_path))
+    }
+}
+
+/// Whether an archive lies directly in the Data folder of a game
+pub fn in_data_folder(archive: &Path) -> bool {
+    archive
+        .parent()
+        .is_some_and(|dir| Game::of_data_folder(dir).is_some())
+}
+
+/// Files of an archive that already exist as loose files in `data_dir`
+///
+/// Loose files are matched ignoring case, as the game does. Returns the
+/// archive-internal paths with `/` separators.
+pub fn loose_conflicts(archive: &Path, data_dir: &Path) -> Result<Vec<String>> {
+    let mut case = CaseFolder::new();
+    Ok(read_file_names(archive)?
+        .into_iter()
+        .map(|name| name.replace('\\', "/"))
+        .filter(|name| case.resolve(data_dir, name).is_file())
+        .collect())
+}
+
+/// Copy the loose files an archive would overwrite into the backup folder
+///
+/// Backups mirror the Data folder layout: `<backup_root>/Data/<file>`, like
+/// archive backups (see [`backup_location`](crate::operations::backup_location)).
+/// A file that was backed up before keeps its older backup, so the original
+/// survives repeated extractions. Returns the number of files copied.
+pub fn back_up_loose_files(archive: &Path, backup_root: &Path) -> Result<usize> {
+    let Some(data_dir) = archive.parent() else {
+        return Ok(0);
+    };
+    let backup_dir = backup_root.join(data_dir.file_name().unwrap_or_default());
+
+    let mut case = CaseFolder::new();
+    let mut copied = 0;
+    for name in loose_conflicts(archive, data_dir)? {
+        let backup = backup_dir.join(&name);
+        if backup.exists() {
+            tracing::debug!("Keeping the earlier backup of {}", name);
+            continue;
+        }
+        if let Some(parent) = backup.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(case.resolve(data_dir, &name), &backup)?;
+        copied += 1;
+    }
+
+    if copied > 0 {
+        tracing::debug!(
+            "Backed up {} loose files overwritten by {} to {}",
+            copied,
+            archive.display(),
+            backup_dir.display()
+        );
+    }
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures::write_gnrl;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_back_up_loose_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let game_dir = temp_dir.path().join("Fallout 4");
+        let data = game_dir.join("Data");
+        fs::create_dir_all(data.join("meshes")).unwrap();
+        fs::write(game_dir.join("Fallout4.exe"), b"").unwrap();
+        fs::write(data.join("meshes/a.nif"), b"original").unwrap();
+
+        let archive = data.join("Mod - Main.ba2");
+        write_gnrl(&archive, &[r"meshes\a.nif", r"meshes\b.nif"]).unwrap();
+        assert!(in_data_folder(&archive));
+        assert_eq!(loose_conflicts(&archive, &data).unwrap(), ["meshes/a.nif"]);
+
+        let backup_root = temp_dir.path().join("backups");
+        assert_eq!(back_up_loose_files(&archive, &backup_root).unwrap(), 1);
+        let backup = backup_root.join("Data/meshes/a.nif");
+        assert_eq!(fs::read(&backup).unwrap(), b"original");
+
+        // The first backup is kept when the file is overwritten again
+        fs::write(data.join("meshes/a.nif"), b"extracted").unwrap();
+        assert_eq!(back_up_loose_files(&archive, &backup_root).unwrap(), 0);
+        assert_eq!(fs::read(&backup).unwrap(), b"original");
+    }
+
+    #[test]
+    fn test_conflicts_ignore_case() {
+        let temp_dir = TempDir::new().unwrap();
+        let data = temp_dir.path().join("Data");
+        fs::create_dir_all(data.join("meshes")).unwrap();
+        fs::write(data.join("meshes/a.nif"), b"original").unwrap();
+        let archive = data.join("Mod - Main.ba2");
+        write_gnrl(&archive, &[r"Meshes\A.nif"]).unwrap();
+
+        assert_eq!(loose_conflicts(&archive, &data).unwrap(), ["Meshes/A.nif"]);
+        let backup_root = temp_dir.path().join("backups");
+        assert_eq!(back_up_loose_files(&archive, &backup_root).unwrap(), 1);
+        let backup = backup_root.join("Data/Meshes/A.nif");
+        assert_eq!(fs::read(backup).unwrap(), b"original");
+    }
+}
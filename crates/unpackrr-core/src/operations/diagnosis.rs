@@ -0,0 +1,317 @@
+//! Explaining why the extractions of a run failed
+//!
+//! A missing tool or a read-only mod folder makes hundreds of archives fail
+//! with the same error. [`diagnose`] groups the failures of an
+//! [`ExtractionResult`] by their cause, so the user reads one explanation
+//! with targeted suggestions per cause instead of every per-file error.
+
+use crate::error::{BA2Error, Error};
+use crate::operations::extract::ExtractionResult;
+use crate::operations::format::format_count;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+/// Cause of a failed extraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FailureKind {
+    /// The extraction tool is missing or is not an unpacker
+    ToolMissing,
+    /// The mod folder or archive cannot be written or read
+    PermissionDenied,
+    /// Another process (usually antivirus) holds the files open
+    FileLocked,
+    /// The archive is damaged or in a format the tool cannot read
+    CorruptArchive,
+    /// The tool or the drive did not respond in time
+    Timeout,
+    /// Extracted paths exceed the Windows path length limit
+    PathTooLong,
+    /// The destination drive is full
+    DiskFull,
+    /// Any other error
+    Other,
+}
+
+impl FailureKind {
+    /// Classify the error of a failed extraction
+    pub fn of(error: &Error) -> Self {
+        match error {
+            Error::BA2(BA2Error::BSArchNotFound { .. } | BA2Error::InvalidTool { .. }) => {
+                Self::ToolMissing
+            }
+            Error::BA2(BA2Error::FileLocked { .. }) => Self::FileLocked,
+            Error::BA2(
+                BA2Error::InvalidMagic { .. }
+                | BA2Error::UnsupportedVersion { .. }
+                | BA2Error::Corrupted { .. }
+                | BA2Error::UnsupportedCompression { .. },
+            ) => Self::CorruptArchive,
+            Error::BA2(BA2Error::PathTooLong { .. }) => Self::PathTooLong,
+            Error::IO(e) => match e.kind() {
+                std::io::ErrorKind::PermissionDenied => Self::PermissionDenied,
+                std::io::ErrorKind::TimedOut => Self::Timeout,
+                std::io::ErrorKind::StorageFull => Self::DiskFull,
+                _ if error.is_file_lock() => Self::FileLocked,
+                _ => Self::from_message(&e.to_string()),
+            },
+            // Tool failures only carry the tool's own output
+            _ => Self::from_message(&error.to_string()),
+        }
+    }
+
+    /// Classify an error from its message, for errors only known as text
+    pub fn from_message(message: &str) -> Self {
+        let message = message.to_lowercase();
+        let mentions = |needles: &[&str]| needles.iter().any(|n| message.contains(n));
+
+        if mentions(&["not found at expected location", "is not a ba2 unpacker"]) {
+            Self::ToolMissing
+        } else if mentions(&["locked by another process", "used by another process", "sharing violation"]) {
+            Self::FileLocked
+        } else if mentions(&["access is denied", "permission denied", "access denied"]) {
+            Self::PermissionDenied
+        } else if mentions(&["timed out", "timeout"]) {
+            Self::Timeout
+        } else if mentions(&["not enough space", "no space left", "disk full", "disk is full"]) {
+            Self::DiskFull
+        } else if mentions(&["character limit", "path too long", "filename or extension is too long"]) {
+            Self::PathTooLong
+        } else if mentions(&["corrupt", "invalid ba2", "unsupported ba2", "unsupported compression"]) {
+            Self::CorruptArchive
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Short description of the cause
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::ToolMissing => "Extraction tool missing",
+            Self::PermissionDenied => "Permission denied",
+            Self::FileLocked => "Files locked by another program",
+            Self::CorruptArchive => "Damaged or unsupported archives",
+            Self::Timeout => "Timed out",
+            Self::PathTooLong => "Paths too long",
+            Self::DiskFull => "Disk full",
+            Self::Other => "Other errors",
+        }
+    }
+
+    /// What the cause means for the run
+    pub const fn explanation(self) -> &'static str {
+        match self {
+            Self::ToolMissing => {
+                "BSArch could not be started, so every archive fails the same way."
+            }
+            Self::PermissionDenied => {
+                "The mod folders or archives cannot be written. They may be read-only or owned by another user."
+            }
+            Self::FileLocked => {
+                "Another program held the files open, usually an antivirus scanning the new files."
+            }
+            Self::CorruptArchive => {
+                "These archives are damaged or use a format the tool cannot read. Other archives are not affected."
+            }
+            Self::Timeout => {
+                "The tool or the drive stopped responding. Network and removable drives are the usual cause."
+            }
+            Self::PathTooLong => {
+                "The extracted files would exceed the Windows path length limit."
+            }
+            Self::DiskFull => "The drive ran out of space while writing the extracted files.",
+            Self::Other => "These archives failed for different reasons; see the log for details.",
+        }
+    }
+
+    /// What the user can do about the cause
+    pub const fn suggestions(self) -> &'static [&'static str] {
+        match self {
+            Self::ToolMissing => &[
+                "Specify the BA2 extraction tool path in Settings > Advanced",
+                "Clear the external tool setting to use the bundled BSArch.exe",
+                "Check whether an antivirus quarantined BSArch.exe",
+            ],
+            Self::PermissionDenied => &[
+                "Move the mods out of Program Files or other protected folders",
+                "Run the application as administrator",
+                "Clear the read-only attribute of the mod folders",
+            ],
+            Self::FileLocked => &[
+                "Add the mod folder to your antivirus exclusions",
+                "Close mod managers and xEdit, then retry the failed archives",
+                "Extract one archive per mod at a time (Settings > Extraction)",
+            ],
+            Self::CorruptArchive => &[
+                "Re-download the affected mods",
+                "Quarantine the archives so later runs skip them",
+            ],
+            Self::Timeout => &[
+                "Copy the mods to a local drive before extracting",
+                "Lower the number of archives extracted at once",
+            ],
+            Self::PathTooLong => &[
+                "Move the mod folder closer to the drive root (e.g., C:\\Mods)",
+                "Enable long path support in Windows (LongPathsEnabled)",
+            ],
+            Self::DiskFull => &[
+                "Free up space on the drive of the mod folders",
+                "Extract fewer archives per run",
+            ],
+            Self::Other => &["Retry the failed archives", "Open the log for the full errors"],
+        }
+    }
+}
+
+/// Failed archives that share a cause
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailureCluster {
+    /// Cause of the failures
+    pub kind: FailureKind,
+    /// Archives that failed this way
+    pub archives: Vec<PathBuf>,
+    /// Error of the first archive, as an example
+    pub example: String,
+}
+
+/// Failures of a run grouped by cause, largest group first
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diagnosis {
+    /// Groups of failed archives
+    pub clusters: Vec<FailureCluster>,
+}
+
+impl Diagnosis {
+    /// Whether the run had no failures
+    pub const fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+
+    /// Number of failed archives
+    pub fn failed(&self) -> usize {
+        self.clusters.iter().map(|c| c.archives.len()).sum()
+    }
+
+    /// Text explaining every cause with its suggestions, for the results dialog
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        for cluster in &self.clusters {
+            if !report.is_empty() {
+                report.push_str("\n\n");
+            }
+            let _ = writeln!(
+                report,
+                "{} ({} archive{})",
+                cluster.kind.title(),
+                format_count(cluster.archives.len() as u64),
+                if cluster.archives.len() == 1 { "" } else { "s" }
+            );
+            let _ = writeln!(report, "{}", cluster.kind.explanation());
+            let _ = write!(report, "Example: {}", cluster.example);
+            for suggestion in cluster.kind.suggestions() {
+                let _ = write!(report, "\n• {suggestion}");
+            }
+        }
+        report
+    }
+}
+
+/// Group the failed extractions of a run by cause
+///
+/// Failures without a recorded [`FailureKind`] are classified from their
+/// error message.
+pub fn diagnose(result: &ExtractionResult) -> Diagnosis {
+    let mut clusters: Vec<FailureCluster> = Vec::new();
+    for file in result.file_results.iter().filter(|r| !r.success) {
+        let error = file.error.as_deref().unwrap_or("Unknown error");
+        let kind = file
+            .failure
+            .unwrap_or_else(|| FailureKind::from_message(error));
+        match clusters.iter_mut().find(|c| c.kind == kind) {
+            Some(cluster) => cluster.archives.push(file.file_path.clone()),
+            None => clusters.push(FailureCluster {
+                kind,
+                archives: vec![file.file_path.clone()],
+                example: error.to_string(),
+            }),
+        }
+    }
+
+    // Most failures first; "other" always last as it explains the least
+    clusters.sort_by_key(|c| (c.kind == FailureKind::Other, std::cmp::Reverse(c.archives.len())));
+    Diagnosis { clusters }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::extract::FileExtractionResult;
+    use std::time::Duration;
+
+    fn failure(name: &str, error: &str, failure: Option<FailureKind>) -> FileExtractionResult {
+        FileExtractionResult {
+            file_path: PathBuf::from(name),
+            success: false,
+            error: Some(error.to_string()),
+            warnings: Vec::new(),
+            failure,
+            duration: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_classifies_errors() {
+        let missing = Error::from(BA2Error::BSArchNotFound {
+            path: PathBuf::from("BSArch.exe"),
+        });
+        assert_eq!(FailureKind::of(&missing), FailureKind::ToolMissing);
+
+        let denied = Error::from(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        assert_eq!(FailureKind::of(&denied), FailureKind::PermissionDenied);
+
+        let tool = Error::from(BA2Error::ExtractionFailed {
+            path: PathBuf::from("a.ba2"),
+            reason: "BSArch.exe failed: Access is denied".to_string(),
+        });
+        assert_eq!(FailureKind::of(&tool), FailureKind::PermissionDenied);
+
+        assert_eq!(FailureKind::from_message("Operation timed out"), FailureKind::Timeout);
+        assert_eq!(FailureKind::from_message("exited with 1"), FailureKind::Other);
+    }
+
+    #[test]
+    fn test_clusters_failures_by_cause() {
+        let mut result = ExtractionResult::new();
+        for name in ["a.ba2", "b.ba2", "c.ba2"] {
+            result.add_result(failure(name, "tool missing", Some(FailureKind::ToolMissing)));
+        }
+        result.add_result(failure("d.ba2", "exited with 1", None));
+        result.add_result(failure("e.ba2", "Corrupted BA2 file: e.ba2 - bad table", None));
+        result.add_result(FileExtractionResult {
+            file_path: PathBuf::from("ok.ba2"),
+            success: true,
+            error: None,
+            warnings: Vec::new(),
+            failure: None,
+            duration: Duration::ZERO,
+        });
+
+        let diagnosis = diagnose(&result);
+        assert_eq!(diagnosis.failed(), 5);
+        let kinds: Vec<_> = diagnosis.clusters.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            [FailureKind::ToolMissing, FailureKind::CorruptArchive, FailureKind::Other]
+        );
+        assert_eq!(diagnosis.clusters[0].archives.len(), 3);
+        assert_eq!(diagnosis.clusters[0].example, "tool missing");
+
+        let report = diagnosis.report();
+        assert!(report.starts_with("Extraction tool missing (3 archives)"), "{report}");
+        assert!(report.contains("Settings > Advanced"));
+    }
+
+    #[test]
+    fn test_successful_run_has_no_diagnosis() {
+        assert!(diagnose(&ExtractionResult::new()).is_empty());
+    }
+}
@@ -0,0 +1,527 @@
+//! Comparing the contents of two archives
+//!
+//! Shows what changed between two versions of a mod's archive: which files
+//! were added, which were removed and which changed. Paths are compared the
+//! way the game resolves them, ignoring case and separator style.
+//!
+//! The same comparison checks an extraction folder against its archive
+//! ([`compare_folder`]): files of the archive missing from the folder, loose
+//! files next to them that the archive does not contain, and files whose size
+//! differs.
+//!
+//! Archive contents come from the file records, so every entry carries its
+//! unpacked size. Hashing the contents as well (see [`archive_contents`])
+//! also catches files that changed without changing size, such as a texture
+//! repainted at the same resolution.
+
+use crate::ba2::BA2Archive;
+use crate::error::Result;
+use crate::operations::export::csv_field;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Size of the buffer files are streamed through while hashing
+const HASH_BUFFER_SIZE: usize = 64 * 1024;
+
+/// A file stored in an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentEntry {
+    /// Path of the file inside the archive
+    pub path: String,
+
+    /// Unpacked size in bytes, if known
+    pub size: Option<u64>,
+
+    /// Content hash, if known
+    pub hash: Option<u64>,
+}
+
+impl ContentEntry {
+    /// Create an entry known only by its path
+    pub fn from_path(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            size: None,
+            hash: None,
+        }
+    }
+}
+
+/// How an entry differs between two archives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// Only in the new archive
+    Added,
+    /// Only in the old archive
+    Removed,
+    /// In both, with a different size or hash
+    Changed,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Added => "Added",
+            Self::Removed => "Removed",
+            Self::Changed => "Changed",
+        })
+    }
+}
+
+/// An entry that differs between two archives
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryChange {
+    /// Kind of difference
+    pub kind: ChangeKind,
+
+    /// Path of the entry (as stored in the new archive, if present there)
+    pub path: String,
+
+    /// Size in the old archive, if known
+    pub old_size: Option<u64>,
+
+    /// Size in the new archive, if known
+    pub new_size: Option<u64>,
+}
+
+/// Differences between the contents of two archives
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveDiff {
+    /// Differing entries, sorted by path
+    pub changes: Vec<EntryChange>,
+
+    /// Number of entries found in both archives without a known difference
+    pub unchanged: usize,
+}
+
+impl ArchiveDiff {
+    /// Whether no difference was found
+    pub const fn is_identical(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Number of differences of one kind
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes.iter().filter(|c| c.kind == kind).count()
+    }
+
+    /// Render the differences as CSV with a header row
+    pub fn to_csv(&self) -> String {
+        let size = |size: Option<u64>| size.map(|s| s.to_string()).unwrap_or_default();
+        let mut out = String::from("change,path,old_size,new_size\n");
+        for change in &self.changes {
+            let fields = [
+                change.kind.to_string(),
+                csv_field(&change.path),
+                size(change.old_size),
+                size(change.new_size),
+            ];
+            out.push_str(&fields.join(","));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// List the files stored in an archive with their unpacked sizes
+///
+/// With `hash`, every file is also unpacked and hashed (textures including
+/// their rebuilt DDS header), which reads the whole archive.
+pub fn archive_contents(path: &Path, hash: bool) -> Result<Vec<ContentEntry>> {
+    let archive = BA2Archive::open(path)?;
+    let mut buffer = vec![0; HASH_BUFFER_SIZE];
+    let mut contents = Vec::with_capacity(archive.entries().len() + archive.textures().len());
+
+    for entry in archive.entries() {
+        let hash = if hash {
+            let mut hasher = ContentHasher::default();
+            archive.write_entry(entry, &mut hasher, &mut buffer)?;
+            Some(hasher.finish())
+        } else {
+            None
+        };
+        contents.push(ContentEntry {
+            path: entry.name.clone(),
+            size: Some(u64::from(entry.unpacked_size)),
+            hash,
+        });
+    }
+    for texture in archive.textures() {
+        let hash = if hash {
+            let mut hasher = ContentHasher::default();
+            archive.write_texture(texture, &mut hasher, &mut buffer)?;
+            Some(hasher.finish())
+        } else {
+            None
+        };
+        contents.push(ContentEntry {
+            path: texture.name.clone(),
+            size: Some(texture.unpacked_size()),
+            hash,
+        });
+    }
+    Ok(contents)
+}
+
+/// Compare the contents of an old and a new archive
+///
+/// With `hash`, files of the same size are also compared by content.
+pub fn compare_archives(old: &Path, new: &Path, hash: bool) -> Result<ArchiveDiff> {
+    let diff = diff_contents(&archive_contents(old, hash)?, &archive_contents(new, hash)?);
+    tracing::info!(
+        "Compared {} with {}: {} added, {} removed, {} changed",
+        old.display(),
+        new.display(),
+        diff.count(ChangeKind::Added),
+        diff.count(ChangeKind::Removed),
+        diff.count(ChangeKind::Changed)
+    );
+    Ok(diff)
+}
+
+/// Compare an extraction folder with the archive it was unpacked from
+///
+/// The archive is the old side: its files missing from the folder are
+/// reported as removed, loose files the archive does not contain as added,
+/// and files whose size on disk differs from the archive's record as
+/// changed. With `hash`, loose files of the right size are also hashed and
+/// compared with the archive's contents.
+///
+/// Only the folders the archive unpacks into are searched for extra files,
+/// so other mods' files and the files at the top of the folder (plugins,
+/// archives) are never reported.
+pub fn compare_folder(archive: &Path, folder: &Path, hash: bool) -> Result<ArchiveDiff> {
+    let expected = archive_contents(archive, hash)?;
+    let actual = folder_contents(folder, &expected)?;
+    let diff = diff_contents(&expected, &actual);
+    tracing::info!(
+        "Compared {} with {}: {} missing, {} extra, {} differing",
+        folder.display(),
+        archive.display(),
+        diff.count(ChangeKind::Removed),
+        diff.count(ChangeKind::Added),
+        diff.count(ChangeKind::Changed)
+    );
+    Ok(diff)
+}
+
+/// List the loose files in the folders that `expected` entries unpack into
+///
+/// Sizes come from the file system. A file is hashed only when its expected
+/// entry has a hash and the sizes already match. Entries at the top of the
+/// archive are only looked up by name.
+fn folder_contents(folder: &Path, expected: &[ContentEntry]) -> Result<Vec<ContentEntry>> {
+    let by_key: HashMap<String, &ContentEntry> =
+        expected.iter().map(|e| (path_key(&e.path), e)).collect();
+    let loose_entry = |path: String, file: &Path, size: u64| -> Result<ContentEntry> {
+        let wanted = by_key
+            .get(&path_key(&path))
+            .is_some_and(|entry| entry.hash.is_some() && entry.size == Some(size));
+        let hash = if wanted {
+            let mut hasher = ContentHasher::default();
+            io::copy(&mut fs::File::open(file)?, &mut hasher)?;
+            Some(hasher.finish())
+        } else {
+            None
+        };
+        Ok(ContentEntry {
+            path,
+            size: Some(size),
+            hash,
+        })
+    };
+
+    let mut dirs = BTreeSet::new();
+    let mut contents = Vec::new();
+    for entry in expected {
+        if let Some((dir, _)) = entry.path.rsplit_once(['\\', '/']) {
+            dirs.insert(dir);
+        } else {
+            let file = folder.join(&entry.path);
+            if let Ok(metadata) = fs::metadata(&file)
+                && metadata.is_file()
+            {
+                contents.push(loose_entry(entry.path.clone(), &file, metadata.len())?);
+            }
+        }
+    }
+
+    for dir in dirs {
+        let entries = match fs::read_dir(folder.join(dir.replace('\\', "/"))) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                let path = format!("{dir}\\{}", entry.file_name().to_string_lossy());
+                contents.push(loose_entry(path, &entry.path(), metadata.len())?);
+            }
+        }
+    }
+    Ok(contents)
+}
+
+/// Compare two lists of archive contents
+pub fn diff_contents(old: &[ContentEntry], new: &[ContentEntry]) -> ArchiveDiff {
+    let mut remaining: HashMap<String, &ContentEntry> =
+        old.iter().map(|e| (path_key(&e.path), e)).collect();
+    let mut diff = ArchiveDiff::default();
+
+    for entry in new {
+        let Some(previous) = remaining.remove(&path_key(&entry.path)) else {
+            diff.changes.push(EntryChange {
+                kind: ChangeKind::Added,
+                path: entry.path.clone(),
+                old_size: None,
+                new_size: entry.size,
+            });
+            continue;
+        };
+        let differs = |a: Option<u64>, b: Option<u64>| a.zip(b).is_some_and(|(a, b)| a != b);
+        if differs(previous.size, entry.size) || differs(previous.hash, entry.hash) {
+            diff.changes.push(EntryChange {
+                kind: ChangeKind::Changed,
+                path: entry.path.clone(),
+                old_size: previous.size,
+                new_size: entry.size,
+            });
+        } else {
+            diff.unchanged += 1;
+        }
+    }
+    diff.changes
+        .extend(remaining.into_values().map(|entry| EntryChange {
+            kind: ChangeKind::Removed,
+            path: entry.path.clone(),
+            old_size: entry.size,
+            new_size: None,
+        }));
+
+    diff.changes.sort_by_cached_key(|c| path_key(&c.path));
+    diff
+}
+
+/// Hashes file contents written into it
+#[derive(Default)]
+struct ContentHasher(Sha256);
+
+impl ContentHasher {
+    /// The first 64 bits of the SHA-256 digest
+    fn finish(self) -> u64 {
+        let digest = self.0.finalize();
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        u64::from_le_bytes(bytes)
+    }
+}
+
+impl Write for ContentHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Normalize a path for comparison (lowercase, backslash separators)
+fn path_key(path: &str) -> String {
+    path.replace('/', "\\").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures::{FixtureFile, FixtureTexture, dx10_archive, gnrl_archive};
+
+    fn sized(path: &str, size: u64) -> ContentEntry {
+        ContentEntry {
+            size: Some(size),
+            ..ContentEntry::from_path(path)
+        }
+    }
+
+    #[test]
+    fn test_diff_contents() {
+        let old = vec![
+            sized("Meshes\\Armor\\Helmet.nif", 100),
+            sized("Meshes\\Armor\\Boots.nif", 200),
+            sized("Sound\\Old.wav", 50),
+            ContentEntry::from_path("Scripts\\Quest.pex"),
+        ];
+        let new = vec![
+            sized("meshes/armor/helmet.nif", 100),
+            sized("Meshes\\Armor\\Boots.nif", 250),
+            sized("Meshes\\Armor\\Gloves.nif", 80),
+            sized("Scripts\\Quest.pex", 10),
+        ];
+
+        let diff = diff_contents(&old, &new);
+        let summary: Vec<(ChangeKind, &str)> = diff
+            .changes
+            .iter()
+            .map(|c| (c.kind, c.path.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (ChangeKind::Changed, "Meshes\\Armor\\Boots.nif"),
+                (ChangeKind::Added, "Meshes\\Armor\\Gloves.nif"),
+                (ChangeKind::Removed, "Sound\\Old.wav"),
+            ]
+        );
+        // An unknown size on one side is not a difference
+        assert_eq!(diff.unchanged, 2);
+        assert!(diff_contents(&old, &old).is_identical());
+    }
+
+    #[test]
+    fn test_compare_archives() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file = |name: &str, data: &[u8]| FixtureFile {
+            name: name.to_string(),
+            data: data.to_vec(),
+        };
+        let old = temp_dir.path().join("old.ba2");
+        let new = temp_dir.path().join("new.ba2");
+        fs::write(
+            &old,
+            gnrl_archive(&[
+                file("Meshes\\Helmet.nif", b"mesh"),
+                file("Meshes\\Boots.nif", b"boot"),
+                file("Scripts\\Quest.pex", b"script"),
+            ]),
+        )
+        .unwrap();
+        fs::write(
+            &new,
+            gnrl_archive(&[
+                file("Meshes\\Helmet.nif", b"mesh"),
+                file("Meshes\\Boots.nif", b"BOOT"),
+                file("Scripts\\Quest.pex", b"script v2"),
+            ]),
+        )
+        .unwrap();
+
+        let contents = archive_contents(&old, false).unwrap();
+        assert_eq!(contents[2].size, Some(6));
+        assert_eq!(contents[2].hash, None);
+
+        // Sizes alone only catch the script
+        let diff = compare_archives(&old, &new, false).unwrap();
+        let changed: Vec<&str> = diff.changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(changed, ["Scripts\\Quest.pex"]);
+        assert_eq!(diff.changes[0].old_size, Some(6));
+        assert_eq!(diff.changes[0].new_size, Some(9));
+
+        // Hashes also catch the boots, which kept their size
+        let diff = compare_archives(&old, &new, true).unwrap();
+        let changed: Vec<&str> = diff.changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(changed, ["Meshes\\Boots.nif", "Scripts\\Quest.pex"]);
+        assert_eq!(diff.unchanged, 1);
+
+        let textures = temp_dir.path().join("textures.ba2");
+        fs::write(
+            &textures,
+            dx10_archive(&[FixtureTexture::named("Textures\\Helmet_d.dds")]),
+        )
+        .unwrap();
+        let contents = archive_contents(&textures, true).unwrap();
+        let data = BA2Archive::open(&textures)
+            .and_then(|archive| archive.read_texture(&archive.textures()[0]))
+            .unwrap();
+        assert_eq!(contents[0].size, Some(data.len() as u64));
+        assert!(compare_archives(&textures, &textures, true).unwrap().is_identical());
+    }
+
+    #[test]
+    fn test_compare_folder() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let folder = temp_dir.path();
+        let expected = vec![
+            sized("Meshes\\Armor\\Helmet.nif", 4),
+            ContentEntry::from_path("Meshes\\Armor\\Boots.nif"),
+            ContentEntry::from_path("Textures\\Armor\\Helmet_d.dds"),
+            ContentEntry::from_path("Readme.txt"),
+            ContentEntry::from_path("Changelog.txt"),
+        ];
+        fs::create_dir_all(folder.join("Meshes/Armor")).unwrap();
+        fs::write(folder.join("Meshes/Armor/Helmet.nif"), b"mesh edited").unwrap();
+        fs::write(folder.join("Meshes/Armor/Boots.nif"), b"mesh").unwrap();
+        fs::write(folder.join("Meshes/Armor/Backup.nif"), b"mesh").unwrap();
+        fs::write(folder.join("Readme.txt"), b"readme").unwrap();
+        fs::write(folder.join("Other Mod.esp"), b"plugin").unwrap();
+
+        let actual = folder_contents(folder, &expected).unwrap();
+        let diff = diff_contents(&expected, &actual);
+        let summary: Vec<(ChangeKind, &str)> = diff
+            .changes
+            .iter()
+            .map(|c| (c.kind, c.path.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (ChangeKind::Removed, "Changelog.txt"),
+                (ChangeKind::Added, "Meshes\\Armor\\Backup.nif"),
+                (ChangeKind::Changed, "Meshes\\Armor\\Helmet.nif"),
+                (ChangeKind::Removed, "Textures\\Armor\\Helmet_d.dds"),
+            ]
+        );
+        assert_eq!(diff.unchanged, 2);
+    }
+
+    #[test]
+    fn test_compare_folder_with_archive() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let folder = temp_dir.path();
+        let archive = folder.join("Mod - Main.ba2");
+        let file = |name: &str, data: &[u8]| FixtureFile {
+            name: name.to_string(),
+            data: data.to_vec(),
+        };
+        fs::write(
+            &archive,
+            gnrl_archive(&[
+                file("Meshes\\Helmet.nif", b"mesh"),
+                file("Meshes\\Boots.nif", b"boot"),
+                file("Meshes\\Gloves.nif", b"glove"),
+            ]),
+        )
+        .unwrap();
+        fs::create_dir_all(folder.join("Meshes")).unwrap();
+        fs::write(folder.join("Meshes/Helmet.nif"), b"mesh").unwrap();
+        fs::write(folder.join("Meshes/Boots.nif"), b"BOOT").unwrap();
+        fs::write(folder.join("Meshes/Gloves.nif"), b"gloves").unwrap();
+
+        let changed = |hash: bool| -> Vec<String> {
+            compare_folder(&archive, folder, hash)
+                .unwrap()
+                .changes
+                .into_iter()
+                .map(|c| c.path)
+                .collect()
+        };
+        // The record sizes catch the gloves; the boots need hashing
+        assert_eq!(changed(false), ["Meshes\\Gloves.nif"]);
+        assert_eq!(changed(true), ["Meshes\\Boots.nif", "Meshes\\Gloves.nif"]);
+    }
+
+    #[test]
+    fn test_diff_to_csv() {
+        let diff = diff_contents(&[sized("a,b.nif", 1)], &[sized("c.nif", 2)]);
+        assert_eq!(
+            diff.to_csv(),
+            "change,path,old_size,new_size\nRemoved,\"a,b.nif\",1,\nAdded,c.nif,,2\n"
+        );
+    }
+}
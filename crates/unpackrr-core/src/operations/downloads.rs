@@ -0,0 +1,335 @@
+//! BA2 archives in pending mod downloads
+//!
+//! Mod managers keep downloads as `.zip` or `.7z` files until they are
+//! installed. Listing what is inside them, without unpacking anything, shows
+//! ahead of time which BA2 archives installing them adds to the load order.
+//!
+//! ZIP files are read directly. 7z files usually compress their file list,
+//! so they are listed by 7-Zip's command-line tool, if it is installed.
+//! Containers nested in a download (a `.zip` inside a `.7z`) aren't opened.
+
+use crate::error::{Error, Result, ValidationError};
+use crate::operations::encoding::decode_console_output;
+use crate::operations::zip;
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Names of 7-Zip's command-line tool, in order of preference
+#[cfg(windows)]
+const SEVEN_ZIP_NAMES: &[&str] = &["7z.exe", "7zz.exe", "7za.exe"];
+#[cfg(not(windows))]
+const SEVEN_ZIP_NAMES: &[&str] = &["7z", "7zz", "7za"];
+
+/// Kind of download container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerKind {
+    /// A `.zip` file
+    Zip,
+    /// A `.7z` file
+    SevenZip,
+}
+
+impl ContainerKind {
+    /// The kind of `path`, by its extension, if it is a download container
+    pub fn of(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        if extension.eq_ignore_ascii_case("zip") {
+            Some(Self::Zip)
+        } else if extension.eq_ignore_ascii_case("7z") {
+            Some(Self::SevenZip)
+        } else {
+            None
+        }
+    }
+}
+
+/// A BA2 archive inside a download
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingArchive {
+    /// Path of the archive inside the download
+    pub name: String,
+
+    /// Size of the archive once unpacked, in bytes
+    pub size: u64,
+}
+
+impl PendingArchive {
+    /// File name of the archive, without the folders it is in
+    pub fn file_name(&self) -> &str {
+        self.name.rsplit(['\\', '/']).next().unwrap_or(&self.name)
+    }
+}
+
+/// The BA2 archives of one download
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadContents {
+    /// The download
+    pub download: PathBuf,
+
+    /// BA2 archives inside it
+    pub archives: Vec<PendingArchive>,
+}
+
+/// What installing the downloads of a folder would add
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DownloadsPreview {
+    /// Downloads with BA2 archives, by file name
+    pub downloads: Vec<DownloadContents>,
+
+    /// Downloads looked inside, with or without BA2 archives
+    pub scanned: usize,
+
+    /// Downloads that could not be listed, with the reason
+    pub unreadable: Vec<(PathBuf, String)>,
+}
+
+impl DownloadsPreview {
+    /// Number of BA2 archives the downloads add
+    pub fn archive_count(&self) -> usize {
+        self.downloads
+            .iter()
+            .map(|download| download.archives.len())
+            .sum()
+    }
+
+    /// Combined size of the BA2 archives, in bytes
+    pub fn total_size(&self) -> u64 {
+        self.downloads
+            .iter()
+            .flat_map(|download| &download.archives)
+            .map(|archive| archive.size)
+            .sum()
+    }
+}
+
+fn is_ba2(name: &str) -> bool {
+    Path::new(name)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ba2"))
+}
+
+/// List the BA2 archives inside the download `path`
+///
+/// 7z files need `seven_zip`, the path of 7-Zip's command-line tool.
+pub fn list_download(path: &Path, seven_zip: Option<&Path>) -> Result<Vec<PendingArchive>> {
+    match ContainerKind::of(path) {
+        Some(ContainerKind::Zip) => list_zip(path),
+        Some(ContainerKind::SevenZip) => {
+            let tool = seven_zip.ok_or_else(|| {
+                Error::other("7-Zip is needed to look inside .7z files, but it was not found")
+            })?;
+            list_seven_zip(path, tool)
+        }
+        None => Err(ValidationError::InvalidInput(format!(
+            "{} is not a .zip or .7z file",
+            path.display()
+        ))
+        .into()),
+    }
+}
+
+fn list_zip(path: &Path) -> Result<Vec<PendingArchive>> {
+    let mut file = File::open(path)?;
+    Ok(zip::entries(&mut file)?
+        .into_iter()
+        .filter(|entry| is_ba2(&entry.name))
+        .map(|entry| PendingArchive {
+            name: entry.name,
+            size: entry.size,
+        })
+        .collect())
+}
+
+fn list_seven_zip(path: &Path, tool: &Path) -> Result<Vec<PendingArchive>> {
+    // Technical listing without headers: one block of "Key = Value" lines per entry
+    let mut command = Command::new(tool);
+    command.args(["l", "-slt", "-ba", "--"]).arg(path);
+
+    // On Windows, hide the console window to prevent flickering
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = command
+        .output()
+        .map_err(|e| Error::other(format!("Could not run {}: {e}", tool.display())))?;
+    if !output.status.success() {
+        let stderr = decode_console_output(&output.stderr);
+        return Err(Error::other(format!(
+            "7-Zip could not list {}: {}",
+            path.display(),
+            stderr.trim()
+        )));
+    }
+    Ok(parse_seven_zip_listing(&decode_console_output(
+        &output.stdout,
+    )))
+}
+
+/// The BA2 archives of 7-Zip's technical listing (`7z l -slt -ba`)
+fn parse_seven_zip_listing(listing: &str) -> Vec<PendingArchive> {
+    let mut archives = Vec::new();
+    let mut current: Option<PendingArchive> = None;
+    let mut folder = false;
+    for line in listing.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once(" = ") else {
+            continue;
+        };
+        match key {
+            "Path" => {
+                archives.extend(current.take().filter(|_| !folder));
+                folder = false;
+                current = Some(PendingArchive {
+                    name: value.to_string(),
+                    size: 0,
+                });
+            }
+            "Size" => {
+                if let Some(archive) = current.as_mut() {
+                    archive.size = value.parse().unwrap_or(0);
+                }
+            }
+            "Folder" => folder = value == "+",
+            "Attributes" => folder |= value.starts_with('D'),
+            _ => {}
+        }
+    }
+    archives.extend(current.filter(|_| !folder));
+    archives.retain(|archive| is_ba2(&archive.name));
+    archives
+}
+
+/// Find 7-Zip's command-line tool on `PATH` or in its install folder
+pub fn locate_seven_zip() -> Option<PathBuf> {
+    let install_dirs: Vec<PathBuf> = ["ProgramFiles", "ProgramW6432", "ProgramFiles(x86)"]
+        .into_iter()
+        .filter_map(std::env::var_os)
+        .map(|dir| PathBuf::from(dir).join("7-Zip"))
+        .collect();
+    let found = seven_zip_candidates(std::env::var_os("PATH").as_deref(), &install_dirs)
+        .into_iter()
+        .find(|candidate| candidate.is_file());
+    if let Some(tool) = &found {
+        tracing::debug!("Using 7-Zip at {}", tool.display());
+    } else {
+        tracing::debug!("7-Zip not found; .7z downloads can't be looked inside");
+    }
+    found
+}
+
+/// Candidate paths of 7-Zip in the folders of `path_var`, then in `install_dirs`
+fn seven_zip_candidates(path_var: Option<&OsStr>, install_dirs: &[PathBuf]) -> Vec<PathBuf> {
+    path_var
+        .map(std::env::split_paths)
+        .into_iter()
+        .flatten()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .chain(install_dirs.iter().cloned())
+        .flat_map(|dir| SEVEN_ZIP_NAMES.iter().map(move |name| dir.join(name)))
+        .collect()
+}
+
+/// Look inside every download in `folder` (not its subfolders)
+///
+/// Downloads that can't be listed, e.g. 7z files without 7-Zip or broken
+/// files, are reported and the others still listed.
+pub fn preview_downloads(folder: &Path, seven_zip: Option<&Path>) -> Result<DownloadsPreview> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(folder)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && ContainerKind::of(path).is_some())
+        .collect();
+    paths.sort();
+
+    let mut preview = DownloadsPreview::default();
+    for path in paths {
+        preview.scanned += 1;
+        match list_download(&path, seven_zip) {
+            Ok(archives) if archives.is_empty() => {}
+            Ok(archives) => preview.downloads.push(DownloadContents {
+                download: path,
+                archives,
+            }),
+            Err(e) => {
+                tracing::warn!("Could not look inside {}: {}", path.display(), e);
+                preview.unreadable.push((path, e.user_message()));
+            }
+        }
+    }
+    Ok(preview)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_preview_zip_downloads() {
+        let temp_dir = TempDir::new().unwrap();
+        let folder = temp_dir.path();
+        fs::write(
+            folder.join("Armor-123-1-0.zip"),
+            zip::stored_zip(&[
+                ("Armor.esp", b"plugin"),
+                ("Armor - Main.ba2", b"main"),
+                ("Optional/Armor - Textures.BA2", b"textures"),
+            ]),
+        )
+        .unwrap();
+        fs::write(
+            folder.join("Loose-456.zip"),
+            zip::stored_zip(&[("meshes/a.nif", b"nif")]),
+        )
+        .unwrap();
+        fs::write(folder.join("Broken-789.zip"), b"not a zip").unwrap();
+        fs::write(folder.join("readme.txt"), b"ignored").unwrap();
+
+        let preview = preview_downloads(folder, None).unwrap();
+        assert_eq!(preview.scanned, 3);
+        assert_eq!(preview.downloads.len(), 1);
+        assert_eq!(preview.archive_count(), 2);
+        assert_eq!(preview.total_size(), 12);
+        let names: Vec<&str> = preview.downloads[0]
+            .archives
+            .iter()
+            .map(PendingArchive::file_name)
+            .collect();
+        assert_eq!(names, ["Armor - Main.ba2", "Armor - Textures.BA2"]);
+        assert_eq!(preview.unreadable.len(), 1);
+    }
+
+    #[test]
+    fn test_seven_zip_needs_the_tool() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("Mod.7z");
+        fs::write(&path, b"7z").unwrap();
+        assert!(list_download(&path, None).is_err());
+    }
+
+    #[test]
+    fn test_parse_seven_zip_listing() {
+        let listing = "Path = Mod - Main.ba2\r\nSize = 1024\r\nFolder = -\r\n\r\n\
+                       Path = textures.ba2\r\nSize = 0\r\nFolder = +\r\n\r\n\
+                       Path = Optional\\Mod - Textures.ba2\r\nSize = 2048\r\nAttributes = A\r\n\r\n\
+                       Path = Mod.esp\r\nSize = 10\r\n";
+        let archives = parse_seven_zip_listing(listing);
+        assert_eq!(
+            archives,
+            [
+                PendingArchive {
+                    name: "Mod - Main.ba2".to_string(),
+                    size: 1024,
+                },
+                PendingArchive {
+                    name: r"Optional\Mod - Textures.ba2".to_string(),
+                    size: 2048,
+                },
+            ]
+        );
+    }
+}
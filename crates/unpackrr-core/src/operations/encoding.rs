@@ -0,0 +1,87 @@
+//! Decoding of external tool output
+//!
+//! Console tools such as `BSArch` write their messages in the console code page,
+//! which is not UTF-8 on most non-English Windows installations (e.g., GBK on
+//! Chinese systems). Decoding it as UTF-8 turns file names into garbage.
+
+use crate::platform::console_codepage;
+use encoding_rs::Encoding;
+
+/// Decode console output from an external tool
+///
+/// Valid UTF-8 is returned unchanged; otherwise the system console code page
+/// is used, falling back to lossy UTF-8.
+pub fn decode_console_output(bytes: &[u8]) -> String {
+    decode_with_codepage(bytes, console_codepage())
+}
+
+/// Decode bytes using a Windows code page, preferring UTF-8 when valid
+pub fn decode_with_codepage(bytes: &[u8], codepage: Option<u32>) -> String {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+
+    if let Some(encoding) = codepage.and_then(codepage_encoding) {
+        let (text, _, had_errors) = encoding.decode(bytes);
+        if !had_errors {
+            return text.into_owned();
+        }
+        tracing::debug!(
+            "Tool output is not valid {}, decoding lossily",
+            encoding.name()
+        );
+    }
+
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// Map a Windows code page number to an encoding
+///
+/// Covers the ANSI/OEM code pages used by the locales that report problems;
+/// DOS code pages without a WHATWG encoding (437, 850) return `None`.
+fn codepage_encoding(codepage: u32) -> Option<&'static Encoding> {
+    let encoding = match codepage {
+        866 => encoding_rs::IBM866,
+        874 => encoding_rs::WINDOWS_874,
+        932 => encoding_rs::SHIFT_JIS,
+        936 | 54936 => encoding_rs::GB18030,
+        949 => encoding_rs::EUC_KR,
+        950 => encoding_rs::BIG5,
+        1250 => encoding_rs::WINDOWS_1250,
+        1251 => encoding_rs::WINDOWS_1251,
+        1252 => encoding_rs::WINDOWS_1252,
+        1253 => encoding_rs::WINDOWS_1253,
+        1254 => encoding_rs::WINDOWS_1254,
+        1255 => encoding_rs::WINDOWS_1255,
+        1256 => encoding_rs::WINDOWS_1256,
+        1257 => encoding_rs::WINDOWS_1257,
+        1258 => encoding_rs::WINDOWS_1258,
+        65001 => encoding_rs::UTF_8,
+        _ => return None,
+    };
+    Some(encoding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_passthrough() {
+        assert_eq!(decode_with_codepage("模组 ok".as_bytes(), Some(936)), "模组 ok");
+    }
+
+    #[test]
+    fn test_decode_gbk() {
+        // "模组" encoded as GBK
+        let bytes = [0xC4, 0xA3, 0xD7, 0xE9];
+        assert_eq!(decode_with_codepage(&bytes, Some(936)), "模组");
+    }
+
+    #[test]
+    fn test_unknown_codepage_falls_back_to_lossy() {
+        let bytes = [b'a', 0xFF, b'b'];
+        assert_eq!(decode_with_codepage(&bytes, Some(437)), "a\u{fffd}b");
+        assert_eq!(decode_with_codepage(&bytes, None), "a\u{fffd}b");
+    }
+}
@@ -0,0 +1,288 @@
+//! Progress event bus
+//!
+//! Scans, extractions, backups and tool detection publish typed
+//! [`AppEvent`]s on an [`EventBus`] instead of each operation taking its own
+//! channel. Every consumer (the UI, logging, run metrics, the automation
+//! interface) subscribes to the bus the same way.
+//!
+//! There are two kinds of subscriptions:
+//!
+//! - [`EventBus::subscribe`] receives every event. Publishing waits while
+//!   the subscriber's queue is full, so a subscriber that stops reading
+//!   (e.g., while an extraction is paused) holds up the operation.
+//! - [`EventBus::monitor`] drops events while its queue is full, for
+//!   observers that must never slow down an operation.
+//!
+//! A bus made with [`EventBus::child`] passes its events on to the parent's
+//! subscribers. Operations get a child bus per run: its subscriptions end
+//! when the run drops the bus, while the application-wide subscribers of
+//! the parent keep receiving the events of later runs.
+
+use crate::ba2::extractor::BsarchVersion;
+use crate::operations::{ExtractionProgress, ScanProgress};
+use parking_lot::Mutex;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Events buffered per subscriber
+const SUBSCRIBER_CAPACITY: usize = 100;
+
+/// An event published by an operation
+#[derive(Debug, Clone)]
+pub enum AppEvent {
+    /// Progress of a scan
+    Scan(ScanProgress),
+    /// Progress of an extraction run
+    Extract(ExtractionProgress),
+    /// An archive backup was used
+    Backup(BackupEvent),
+    /// The extraction tool was checked
+    Tool(ToolEvent),
+}
+
+/// Events about archive backups
+#[derive(Debug, Clone)]
+pub enum BackupEvent {
+    /// An archive was restored from its backup
+    Restored {
+        /// Restored archive
+        archive: PathBuf,
+        /// Backup it was copied from
+        backup: PathBuf,
+    },
+    /// An archive was copied to the backup folder before extraction
+    ArchiveSaved {
+        /// Archive about to be extracted
+        archive: PathBuf,
+        /// Where the copy was written
+        backup: PathBuf,
+    },
+    /// Loose files in the game Data folder were backed up before an archive overwrote them
+    LooseFilesSaved {
+        /// Archive about to be extracted
+        archive: PathBuf,
+        /// Number of files backed up
+        count: usize,
+    },
+}
+
+/// Events about the extraction tool
+#[derive(Debug, Clone)]
+pub enum ToolEvent {
+    /// The tool will be used for extraction
+    Detected {
+        /// Path of the tool
+        path: PathBuf,
+        /// Version of the tool, if it could be detected
+        version: Option<BsarchVersion>,
+    },
+    /// The tool cannot unpack archives
+    Rejected {
+        /// Path of the tool
+        path: PathBuf,
+        /// Why the tool was rejected
+        reason: String,
+    },
+}
+
+impl From<ScanProgress> for AppEvent {
+    fn from(progress: ScanProgress) -> Self {
+        Self::Scan(progress)
+    }
+}
+
+impl From<ExtractionProgress> for AppEvent {
+    fn from(progress: ExtractionProgress) -> Self {
+        Self::Extract(progress)
+    }
+}
+
+impl From<BackupEvent> for AppEvent {
+    fn from(event: BackupEvent) -> Self {
+        Self::Backup(event)
+    }
+}
+
+impl From<ToolEvent> for AppEvent {
+    fn from(event: ToolEvent) -> Self {
+        Self::Tool(event)
+    }
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    tx: mpsc::Sender<AppEvent>,
+    /// Whether publishing waits for room in the queue
+    lossless: bool,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    subscribers: Mutex<Vec<Subscriber>>,
+    parent: Option<EventBus>,
+}
+
+/// Distributes [`AppEvent`]s to subscribers
+///
+/// Cloning the bus shares its subscribers.
+#[derive(Debug, Clone, Default)]
+pub struct EventBus {
+    inner: Arc<Inner>,
+}
+
+impl EventBus {
+    /// Create a bus without subscribers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a bus that also passes its events to the subscribers of this one
+    #[must_use]
+    pub fn child(&self) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                subscribers: Mutex::default(),
+                parent: Some(self.clone()),
+            }),
+        }
+    }
+
+    /// Receive every event published from now on
+    ///
+    /// The receiver ends once all clones of the bus are dropped.
+    pub fn subscribe(&self) -> mpsc::Receiver<AppEvent> {
+        self.add_subscriber(true)
+    }
+
+    /// Receive events published from now on, dropping them while behind
+    pub fn monitor(&self) -> mpsc::Receiver<AppEvent> {
+        self.add_subscriber(false)
+    }
+
+    fn add_subscriber(&self, lossless: bool) -> mpsc::Receiver<AppEvent> {
+        let (tx, rx) = mpsc::channel(SUBSCRIBER_CAPACITY);
+        let mut subscribers = self.inner.subscribers.lock();
+        subscribers.retain(|s| !s.tx.is_closed());
+        subscribers.push(Subscriber { tx, lossless });
+        rx
+    }
+
+    /// Publish an event, waiting for room at lossless subscribers
+    pub async fn publish(&self, event: impl Into<AppEvent>) {
+        let event = event.into();
+        for (tx, lossless) in self.senders() {
+            if lossless {
+                let _ = tx.send(event.clone()).await;
+            } else {
+                let _ = tx.try_send(event.clone());
+            }
+        }
+    }
+
+    /// Publish an event without waiting; subscribers that are behind miss it
+    ///
+    /// For frequent updates that are superseded by the next one, and for
+    /// publishing outside of async code.
+    pub fn try_publish(&self, event: impl Into<AppEvent>) {
+        let event = event.into();
+        for (tx, _) in self.senders() {
+            let _ = tx.try_send(event.clone());
+        }
+    }
+
+    /// Senders of this bus and its ancestors, without holding the locks
+    fn senders(&self) -> Vec<(mpsc::Sender<AppEvent>, bool)> {
+        let mut senders = Vec::new();
+        let mut bus = Some(self);
+        while let Some(current) = bus {
+            senders.extend(
+                current
+                    .inner
+                    .subscribers
+                    .lock()
+                    .iter()
+                    .filter(|s| !s.tx.is_closed())
+                    .map(|s| (s.tx.clone(), s.lossless)),
+            );
+            bus = current.inner.parent.as_ref();
+        }
+        senders
+    }
+}
+
+/// Log the events of `events` until the bus is dropped
+pub async fn log_events(mut events: mpsc::Receiver<AppEvent>) {
+    while let Some(event) = events.recv().await {
+        match &event {
+            AppEvent::Extract(ExtractionProgress::FileProgress { .. }) => {}
+            AppEvent::Backup(BackupEvent::Restored { archive, backup }) => {
+                tracing::info!("Restored {} from {}", archive.display(), backup.display());
+            }
+            AppEvent::Backup(BackupEvent::ArchiveSaved { archive, backup }) => {
+                tracing::info!("Backed up {} to {}", archive.display(), backup.display());
+            }
+            AppEvent::Backup(BackupEvent::LooseFilesSaved { archive, count }) => {
+                tracing::info!(
+                    "Backed up {} loose files before extracting {}",
+                    count,
+                    archive.display()
+                );
+            }
+            AppEvent::Tool(ToolEvent::Rejected { path, reason }) => {
+                tracing::warn!("Rejected BA2 tool {}: {}", path.display(), reason);
+            }
+            event => tracing::debug!("Event: {:?}", event),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan_complete(total_files: usize) -> ScanProgress {
+        ScanProgress::Complete { total_files }
+    }
+
+    #[tokio::test]
+    async fn test_child_bus_reaches_parent_subscribers() {
+        let bus = EventBus::new();
+        let mut app = bus.subscribe();
+        let mut monitor = bus.monitor();
+
+        let run = bus.child();
+        let mut run_events = run.subscribe();
+        run.publish(scan_complete(3)).await;
+        drop(run);
+
+        assert!(matches!(
+            run_events.recv().await,
+            Some(AppEvent::Scan(ScanProgress::Complete { total_files: 3 }))
+        ));
+        // The run's subscription ends with the run
+        assert!(run_events.recv().await.is_none());
+        assert!(matches!(app.recv().await, Some(AppEvent::Scan(_))));
+        assert!(matches!(monitor.recv().await, Some(AppEvent::Scan(_))));
+    }
+
+    #[tokio::test]
+    async fn test_monitor_drops_events_when_behind() {
+        let bus = EventBus::new();
+        let mut monitor = bus.monitor();
+        for total_files in 0..=SUBSCRIBER_CAPACITY {
+            bus.publish(scan_complete(total_files)).await;
+        }
+
+        let mut received = 0;
+        while monitor.try_recv().is_ok() {
+            received += 1;
+        }
+        assert_eq!(received, SUBSCRIBER_CAPACITY);
+
+        // Dropped subscribers are skipped
+        drop(monitor);
+        bus.try_publish(scan_complete(0));
+        assert!(bus.senders().is_empty());
+    }
+}
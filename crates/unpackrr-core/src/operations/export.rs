@@ -0,0 +1,507 @@
+//! Export of scan results to CSV, JSON or a shareable summary
+//!
+//! Lets users document the archives they are about to unpack, or share the
+//! list for support. The JSON form is also the input format for importing an
+//! extraction list on another machine. When local metrics are enabled, JSON
+//! exports also carry the metrics of the most recent extraction run.
+//!
+//! The Markdown and forum (`BBCode`) forms are a short summary rather than the full
+//! list, sized for a Reddit, Discord or forum post asking for help: the
+//! archive counts against the engine limit, the largest archives and, after an
+//! extraction run, the archives that took longest to extract.
+
+use crate::error::{Error, Result};
+use crate::models::{ExtractionStatus, FileEntry};
+use crate::operations::format::{format_count, format_size};
+use crate::operations::metrics::{RunMetrics, SLOWEST_ARCHIVES};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use tracing::info;
+
+/// Version of the exported JSON document layout
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+/// Number of leading bytes hashed by [`quick_fingerprint`]
+const FINGERPRINT_SAMPLE_SIZE: usize = 64 * 1024;
+
+/// Number of archives in the table of a shareable summary
+pub const SUMMARY_LARGEST_ARCHIVES: usize = 10;
+
+/// Output format for exported lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// JSON document with metadata and an entry array
+    Json,
+    /// Summary with a table of the largest archives, for Reddit or Discord
+    Markdown,
+    /// Summary with a table of the largest archives, for forums
+    BbCode,
+}
+
+impl ExportFormat {
+    /// Pick the format from a file extension (defaults to CSV)
+    ///
+    /// `.md` is Markdown; `.txt` and `.bbcode` are `BBCode`.
+    pub fn from_path(path: &Path) -> Self {
+        let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+            return Self::Csv;
+        };
+        if ext.eq_ignore_ascii_case("json") {
+            Self::Json
+        } else if ext.eq_ignore_ascii_case("md") {
+            Self::Markdown
+        } else if ext.eq_ignore_ascii_case("txt") || ext.eq_ignore_ascii_case("bbcode") {
+            Self::BbCode
+        } else {
+            Self::Csv
+        }
+    }
+
+    /// File extension for this format (without the dot)
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Json => "json",
+            Self::Markdown => "md",
+            Self::BbCode => "txt",
+        }
+    }
+
+    /// Whether the format is a summary rather than the full list
+    pub const fn is_summary(self) -> bool {
+        matches!(self, Self::Markdown | Self::BbCode)
+    }
+}
+
+/// A single archive in an exported list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedEntry {
+    /// Archive file name
+    pub name: String,
+
+    /// Archive size in bytes
+    pub size: u64,
+
+    /// Number of files contained in the archive
+    pub file_count: u32,
+
+    /// Mod folder name
+    pub mod_name: String,
+
+    /// Path relative to the scanned folder ("Mod/Archive.ba2")
+    pub relative_path: String,
+
+    /// Absolute path on the exporting machine
+    pub path: String,
+
+    /// Extraction status at export time
+    pub status: String,
+
+    /// Content fingerprint used to match renamed or moved archives (JSON only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>,
+
+    /// Extraction time in milliseconds, if extracted in the current run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+}
+
+impl From<&FileEntry> for ExportedEntry {
+    fn from(entry: &FileEntry) -> Self {
+        Self {
+            name: entry.file_name.clone(),
+            size: entry.file_size,
+            file_count: entry.num_files,
+            mod_name: entry.dir_name.clone(),
+            relative_path: format!("{}/{}", entry.dir_name, entry.file_name),
+            path: entry.full_path.to_string_lossy().into_owned(),
+            status: entry.status.as_str().to_string(),
+            fingerprint: None,
+            duration_ms: entry
+                .extraction_time
+                .map(|time| u64::try_from(time.as_millis()).unwrap_or(u64::MAX)),
+        }
+    }
+}
+
+/// Exported JSON document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportDocument {
+    /// Layout version ([`EXPORT_FORMAT_VERSION`])
+    pub version: u32,
+
+    /// Folder that was scanned
+    pub source_folder: String,
+
+    /// Exported archives
+    pub entries: Vec<ExportedEntry>,
+
+    /// Metrics of the most recent extraction run, if recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<RunMetrics>,
+}
+
+/// Export entries to `path` in the given format
+///
+/// `metrics` are only written to JSON exports; `archive_limit`, the engine's
+/// archive limit, only to summaries.
+pub fn export_entries(
+    entries: &[FileEntry],
+    source_folder: &str,
+    path: &Path,
+    format: ExportFormat,
+    metrics: Option<&RunMetrics>,
+    archive_limit: Option<usize>,
+) -> Result<()> {
+    let content = match format {
+        ExportFormat::Csv => entries_to_csv(entries),
+        ExportFormat::Json => document_to_json(&ExportDocument {
+            metrics: metrics.cloned(),
+            ..export_document(entries, source_folder)
+        })?,
+        ExportFormat::Markdown | ExportFormat::BbCode => {
+            entries_to_summary(entries, archive_limit, format)
+        }
+    };
+
+    fs::write(path, content)?;
+    info!(
+        "Exported {} entries to {} ({})",
+        entries.len(),
+        path.display(),
+        format.extension()
+    );
+    Ok(())
+}
+
+/// Render entries as CSV with a header row
+pub fn entries_to_csv(entries: &[FileEntry]) -> String {
+    let mut out = String::from("name,size,file_count,mod,relative_path,path,status,duration_ms\n");
+    for entry in entries.iter().map(ExportedEntry::from) {
+        let fields = [
+            csv_field(&entry.name),
+            entry.size.to_string(),
+            entry.file_count.to_string(),
+            csv_field(&entry.mod_name),
+            csv_field(&entry.relative_path),
+            csv_field(&entry.path),
+            entry.status,
+            entry
+                .duration_ms
+                .map(|ms| ms.to_string())
+                .unwrap_or_default(),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Render entries as a pretty-printed JSON document
+pub fn entries_to_json(entries: &[FileEntry], source_folder: &str) -> Result<String> {
+    document_to_json(&export_document(entries, source_folder))
+}
+
+/// Render a shareable summary of entries as Markdown or `BBCode`
+///
+/// Archives extracted in a run no longer count against `archive_limit`; the
+/// first table lists the largest of the others, the second the archives of
+/// the run that took longest to extract. CSV and JSON render as Markdown.
+pub fn entries_to_summary(
+    entries: &[FileEntry],
+    archive_limit: Option<usize>,
+    format: ExportFormat,
+) -> String {
+    let bbcode = format == ExportFormat::BbCode;
+    let (extracted, mut packed): (Vec<&FileEntry>, Vec<&FileEntry>) = entries
+        .iter()
+        .partition(|entry| entry.status == ExtractionStatus::Done);
+    packed.sort_by_key(|entry| std::cmp::Reverse(entry.file_size));
+    let mut timed: Vec<&FileEntry> = entries
+        .iter()
+        .filter(|entry| entry.extraction_time.is_some())
+        .collect();
+    timed.sort_by_key(|entry| std::cmp::Reverse(entry.extraction_time));
+
+    let mut counts = vec![format!("{} archives", format_count(entries.len() as u64))];
+    if let Some(limit) = archive_limit {
+        counts.push(format!(
+            "{} over the limit of {}",
+            format_count(packed.len().saturating_sub(limit) as u64),
+            format_count(limit as u64)
+        ));
+    }
+    if !extracted.is_empty() {
+        counts.push(format!(
+            "extracted {}",
+            format_size(extracted.iter().map(|entry| entry.file_size).sum())
+        ));
+    }
+
+    let title = "BA2 archive summary";
+    let mut out = if bbcode {
+        format!("[b]{title}[/b]\n\n")
+    } else {
+        format!("**{title}**\n\n")
+    };
+    out.push_str(&counts.join(", "));
+    out.push('\n');
+
+    if !packed.is_empty() {
+        let _ = write!(
+            out,
+            "\nLargest archives ({} of {}):\n\n",
+            packed.len().min(SUMMARY_LARGEST_ARCHIVES),
+            format_count(packed.len() as u64)
+        );
+        let rows = packed.iter().take(SUMMARY_LARGEST_ARCHIVES).map(|entry| {
+            [
+                entry.file_name.clone(),
+                entry.dir_name.clone(),
+                format_size(entry.file_size),
+                format_count(u64::from(entry.num_files)),
+            ]
+        });
+        write_summary_table(&mut out, ["Archive", "Mod", "Size", "Files"], rows, bbcode);
+    }
+
+    if !timed.is_empty() {
+        let _ = write!(
+            out,
+            "\nSlowest extractions ({} of {}):\n\n",
+            timed.len().min(SLOWEST_ARCHIVES),
+            format_count(timed.len() as u64)
+        );
+        let rows = timed.iter().take(SLOWEST_ARCHIVES).map(|entry| {
+            [
+                entry.file_name.clone(),
+                entry.dir_name.clone(),
+                format_size(entry.file_size),
+                entry.extraction_time_display(),
+            ]
+        });
+        write_summary_table(&mut out, ["Archive", "Mod", "Size", "Time"], rows, bbcode);
+    }
+    out
+}
+
+/// Append a table of a summary: names on the left, numbers on the right
+fn write_summary_table(
+    out: &mut String,
+    header: [&str; 4],
+    rows: impl Iterator<Item = [String; 4]>,
+    bbcode: bool,
+) {
+    if bbcode {
+        out.push_str("[table]\n[tr]");
+        for cell in header {
+            let _ = write!(out, "[th]{cell}[/th]");
+        }
+        out.push_str("[/tr]\n");
+        for row in rows {
+            out.push_str("[tr]");
+            for cell in row {
+                let _ = write!(out, "[td]{cell}[/td]");
+            }
+            out.push_str("[/tr]\n");
+        }
+        out.push_str("[/table]\n");
+    } else {
+        let _ = writeln!(out, "| {} |\n|---|---|--:|--:|", header.join(" | "));
+        for row in rows {
+            let cells: Vec<String> = row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+        }
+    }
+}
+
+/// Build the JSON document for entries, without metrics
+fn export_document(entries: &[FileEntry], source_folder: &str) -> ExportDocument {
+    ExportDocument {
+        version: EXPORT_FORMAT_VERSION,
+        source_folder: source_folder.to_string(),
+        entries: entries
+            .iter()
+            .map(|entry| ExportedEntry {
+                fingerprint: quick_fingerprint(&entry.full_path, entry.file_size),
+                ..ExportedEntry::from(entry)
+            })
+            .collect(),
+        metrics: None,
+    }
+}
+
+fn document_to_json(document: &ExportDocument) -> Result<String> {
+    serde_json::to_string_pretty(document)
+        .map_err(|e| Error::Other(format!("Failed to serialize export: {e}")))
+}
+
+/// Compute a cheap content fingerprint for an archive
+///
+/// Combines the file size with an FNV-1a hash of the first
+/// [`FINGERPRINT_SAMPLE_SIZE`] bytes. Stable across machines and builds, and
+/// fast enough to run over a whole load order. Returns `None` if the file
+/// cannot be read.
+pub fn quick_fingerprint(path: &Path, size: u64) -> Option<String> {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+    let file = fs::File::open(path).ok()?;
+    let mut sample = Vec::with_capacity(FINGERPRINT_SAMPLE_SIZE);
+    file.take(FINGERPRINT_SAMPLE_SIZE as u64)
+        .read_to_end(&mut sample)
+        .ok()?;
+
+    let hash = sample.iter().fold(FNV_OFFSET, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    });
+    Some(format!("{size:x}-{hash:016x}"))
+}
+
+/// Quote a CSV field if it contains separators, quotes or line breaks
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn sample_entries() -> Vec<FileEntry> {
+        vec![
+            FileEntry::new(
+                "Mod - Main.ba2".to_string(),
+                2048,
+                12,
+                "My Mod".to_string(),
+                PathBuf::from("/data/My Mod/Mod - Main.ba2"),
+            ),
+            FileEntry::new(
+                "Quote\"d, Name.ba2".to_string(),
+                10,
+                1,
+                "Other".to_string(),
+                PathBuf::from("/data/Other/Quote\"d, Name.ba2"),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_csv_escaping() {
+        let csv = entries_to_csv(&sample_entries());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "name,size,file_count,mod,relative_path,path,status,duration_ms"
+        );
+        assert!(lines[1].starts_with("Mod - Main.ba2,2048,12,My Mod,My Mod/Mod - Main.ba2,"));
+        assert!(lines[2].starts_with("\"Quote\"\"d, Name.ba2\",10,1,Other,"));
+        assert!(lines[2].ends_with(",idle,"));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let json = entries_to_json(&sample_entries(), "/data").unwrap();
+        let document: ExportDocument = serde_json::from_str(&json).unwrap();
+        assert_eq!(document.version, EXPORT_FORMAT_VERSION);
+        assert_eq!(document.source_folder, "/data");
+        assert_eq!(document.entries.len(), 2);
+        assert_eq!(document.entries[0].relative_path, "My Mod/Mod - Main.ba2");
+    }
+
+    #[test]
+    fn test_quick_fingerprint() {
+        let temp_dir = TempDir::new().unwrap();
+        let a = temp_dir.path().join("a.ba2");
+        let b = temp_dir.path().join("b.ba2");
+        fs::write(&a, b"BTDX-same-content").unwrap();
+        fs::write(&b, b"BTDX-same-content").unwrap();
+
+        let fp_a = quick_fingerprint(&a, 17).unwrap();
+        assert_eq!(Some(fp_a.clone()), quick_fingerprint(&b, 17));
+        assert!(fp_a.starts_with("11-"));
+        assert!(quick_fingerprint(&temp_dir.path().join("missing.ba2"), 0).is_none());
+    }
+
+    #[test]
+    fn test_export_to_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("list.json");
+        let format = ExportFormat::from_path(&path);
+        assert_eq!(format, ExportFormat::Json);
+
+        let metrics = RunMetrics {
+            succeeded: 2,
+            ..RunMetrics::default()
+        };
+        export_entries(
+            &sample_entries(),
+            "/data",
+            &path,
+            format,
+            Some(&metrics),
+            None,
+        )
+        .unwrap();
+        let document: ExportDocument =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(document.entries.len(), 2);
+        assert_eq!(document.metrics, Some(metrics));
+    }
+
+    #[test]
+    fn test_summary_formats() {
+        let mut entries = sample_entries();
+        entries[0].status = ExtractionStatus::Done;
+        entries[0].extraction_time = Some(Duration::from_secs(75));
+        entries[1].extraction_time = Some(Duration::from_millis(1_200));
+        entries.push(FileEntry::new(
+            "Big | Textures.ba2".to_string(),
+            4096,
+            300,
+            "Big".to_string(),
+            PathBuf::from("/data/Big/Big | Textures.ba2"),
+        ));
+        assert_eq!(
+            ExportFormat::from_path(Path::new("help.md")),
+            ExportFormat::Markdown
+        );
+        assert_eq!(
+            ExportFormat::from_path(Path::new("help.TXT")),
+            ExportFormat::BbCode
+        );
+
+        let markdown = entries_to_summary(&entries, Some(1), ExportFormat::Markdown);
+        assert!(markdown.starts_with("**BA2 archive summary**"));
+        assert!(markdown.contains("3 archives, 1 over the limit of 1, extracted "));
+        // Extracted archives are left out of the table, largest first
+        let rows: Vec<&str> = markdown.lines().filter(|l| l.starts_with("| ")).collect();
+        assert_eq!(rows.len(), 6);
+        assert!(rows[1].starts_with("| Big \\| Textures.ba2 | Big |"));
+        assert!(rows[2].starts_with("| Quote\"d, Name.ba2 | Other |"));
+        // Timed archives, slowest first, whether extracted or failed
+        assert!(markdown.contains("Slowest extractions (2 of 2):"));
+        assert_eq!(rows[3], "| Archive | Mod | Size | Time |");
+        assert!(rows[4].starts_with("| Mod - Main.ba2 | My Mod |"));
+        assert!(rows[4].ends_with("| 1m 15s |"));
+        assert!(rows[5].ends_with("| 1.2s |"));
+
+        let bbcode = entries_to_summary(&entries, None, ExportFormat::BbCode);
+        assert!(bbcode.starts_with("[b]BA2 archive summary[/b]"));
+        assert!(!bbcode.contains("over the limit"));
+        assert!(bbcode.contains("[tr][td]Big | Textures.ba2[/td][td]Big[/td]"));
+        assert!(bbcode.trim_end().ends_with("[/table]"));
+    }
+}
@@ -0,0 +1,1886 @@
+//! BA2 file extraction operations
+//!
+//! This module handles the orchestration of BA2 file extraction using BSArch.exe.
+//! It provides progress tracking, error handling, and batch extraction capabilities.
+
+use crate::ba2::extractor::{
+    BsarchCapabilities, BsarchLine, BsarchOutcome, BsarchVersion, STARFIELD_MIN_VERSION,
+    known_non_unpacker, looks_like_unpacker, version_from_output,
+};
+use crate::ba2::locate::locate_bsarch;
+use crate::ba2::{BA2Header, read_file_names};
+use crate::config::abort::AbortThreshold;
+use crate::config::{AppConfig, ScheduleOrder, TexturePolicy};
+use crate::error::{BA2Error, Error, Result, ValidationError};
+use crate::models::FileEntry;
+use crate::operations::datafolder::{back_up_loose_files, backup_root, in_data_folder};
+use crate::operations::diagnosis::FailureKind;
+use crate::operations::encoding::decode_console_output;
+use crate::operations::events::{BackupEvent, EventBus, ToolEvent};
+use crate::operations::manifest::{back_up_archive, build_manifest, save_manifest};
+use crate::operations::native::NativeBackend;
+use crate::operations::path::{MAX_PATH, longest_output_path, to_extended_length};
+use crate::operations::linking::{
+    LinkMode, cache_entry, default_cache_root, is_cached, link_tree, mark_complete,
+    symlinks_supported,
+};
+use crate::operations::retry::{CancellationToken, RetryConfig, retry_async_with};
+use crate::operations::staging::{StagingDir, remove_abandoned};
+use crate::platform::capabilities::PlatformCapabilities;
+use crate::platform::drive::{DriveKind, StorageMedium, drive_kind, is_reachable, storage_medium};
+use crate::platform::is_valid_executable;
+use crate::platform::wine::host_path;
+use crate::platform::priority;
+use futures::future::BoxFuture;
+use futures::stream::{self, StreamExt};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::{Mutex, Semaphore};
+
+/// Maximum time to wait for a tool to print its usage
+const VERSION_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Random variation applied to retry delays of parallel extractions
+const RETRY_JITTER: f64 = 0.25;
+
+/// Interval at which a disconnected drive is polled for reconnection
+const RECONNECT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Where extracted files are written
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum OutputMode {
+    /// Directly into the mod folder
+    InPlace,
+    /// Into a staging folder first, see [`extract_via_staging`]
+    Staged,
+    /// Into the shared cache, then linked, see [`extract_via_cache`]
+    Linked {
+        /// How files are placed into the mod folder
+        mode: LinkMode,
+        /// Root folder of the extraction cache
+        cache_root: PathBuf,
+    },
+}
+
+impl OutputMode {
+    /// Pick the output mode for a run from the configuration
+    ///
+    /// Symlinks fall back to hardlinks without the privilege to create them.
+    fn from_config(config: &AppConfig) -> Self {
+        let mut mode = config.extraction.link_mode;
+        if mode == LinkMode::Symlink && !symlinks_supported() {
+            tracing::warn!(
+                "Creating symbolic links requires administrator rights or Developer Mode, using hardlinks"
+            );
+            mode = LinkMode::Hardlink;
+        }
+
+        if mode.uses_cache() {
+            if let Some(cache_root) = configured_cache_root(config) {
+                return Self::Linked { mode, cache_root };
+            }
+            tracing::warn!("No extraction cache folder available, extracting directly");
+        }
+
+        if config.extraction.use_temp {
+            Self::Staged
+        } else {
+            Self::InPlace
+        }
+    }
+}
+
+/// Cache folder for linked extractions, from the configuration or the default
+fn configured_cache_root(config: &AppConfig) -> Option<PathBuf> {
+    if config.advanced.cache_path.is_empty() {
+        default_cache_root()
+    } else {
+        Some(host_path(&config.advanced.cache_path))
+    }
+}
+
+/// Whether a run unpacks with the built-in reader instead of `BSArch`
+///
+/// Besides `advanced.performance.native_extraction`, the reader is used where `BSArch` does
+/// not run natively (ARM64 Windows, Linux), unless a tool is set in Settings.
+pub fn uses_builtin_reader(config: &AppConfig, platform: &PlatformCapabilities) -> bool {
+    config.advanced.performance.native_extraction
+        || (platform.prefers_builtin_reader() && config.advanced.ext_ba2_exe.is_empty())
+}
+
+/// Storage medium that paces a run
+///
+/// The configured medium if set, otherwise the slower of the drive holding
+/// the archives and the cache drive of linked extractions (other output is
+/// written next to the archives).
+fn run_storage_medium(files: &[FileEntry], config: &AppConfig) -> StorageMedium {
+    if let Some(medium) = config.advanced.performance.storage_medium {
+        return medium;
+    }
+    let source = files
+        .first()
+        .and_then(|f| f.full_path.parent())
+        .map_or(StorageMedium::Unknown, storage_medium);
+    if config.extraction.link_mode.uses_cache()
+        && let Some(cache_root) = configured_cache_root(config)
+    {
+        // The cache folder may not exist yet; its drive is that of the nearest parent
+        let cache = cache_root.ancestors().find(|dir| dir.exists());
+        return source.slower(cache.map_or(StorageMedium::Unknown, storage_medium));
+    }
+    source
+}
+
+/// Progress updates during extraction
+#[derive(Debug, Clone)]
+pub enum ExtractionProgress {
+    /// Started extraction of a file
+    Started {
+        /// File being extracted
+        file_name: String,
+        /// Full path to the file being extracted
+        file_path: PathBuf,
+        /// Current file number (1-indexed)
+        current: usize,
+        /// Total number of files to extract
+        total: usize,
+        /// Size of the archive in bytes
+        file_size: u64,
+        /// Total size of the archives to extract in bytes, to weight overall progress
+        total_size: u64,
+    },
+
+    /// Progress within the archive currently being extracted
+    FileProgress {
+        /// File being extracted
+        file_name: String,
+        /// Full path to the file being extracted
+        file_path: PathBuf,
+        /// Completion percentage of this archive
+        percent: u8,
+    },
+
+    /// File extraction completed
+    Completed {
+        /// File that was extracted
+        file_name: String,
+        /// Full path to the file that was extracted
+        file_path: PathBuf,
+        /// Whether extraction was successful
+        success: bool,
+        /// Error message if extraction failed
+        error: Option<String>,
+        /// Warnings printed by the extraction tool
+        warnings: Vec<String>,
+        /// Total size of the extracted files, if known (from the manifest)
+        output_size: Option<u64>,
+        /// Category of the error if extraction failed ([`Error::category`])
+        error_category: Option<&'static str>,
+        /// Wall-clock time the extraction took, including retries
+        duration: Duration,
+    },
+
+    /// File was not extracted
+    Skipped {
+        /// File that was skipped
+        file_name: String,
+        /// Full path to the file that was skipped
+        file_path: PathBuf,
+        /// Why the file was skipped
+        reason: String,
+    },
+
+    /// A transient failure is about to be retried
+    Retrying {
+        /// File being retried
+        file_name: String,
+        /// Full path to the file being retried
+        file_path: PathBuf,
+        /// Number of the attempt that failed (1-indexed)
+        attempt: usize,
+        /// Maximum number of retries
+        max_attempts: usize,
+        /// Time until the next attempt
+        delay: Duration,
+        /// Whether the attempt failed on files locked by another process
+        likely_antivirus: bool,
+    },
+
+    /// The drive holding the archives became unreachable; extraction waits
+    DriveDisconnected {
+        /// Folder that can no longer be reached
+        folder: PathBuf,
+    },
+
+    /// The drive is reachable again and extraction resumes
+    DriveReconnected {
+        /// Folder that is reachable again
+        folder: PathBuf,
+    },
+
+    /// Too many extractions failed in a row; the rest of the batch is not started
+    Aborted {
+        /// Failures in a row that ended the batch
+        consecutive_failures: usize,
+        /// Most common error of those failures
+        cause: String,
+    },
+
+    /// All extractions finished
+    Finished {
+        /// Number of successful extractions
+        successful: usize,
+        /// Number of failed extractions
+        failed: usize,
+        /// Number of skipped files
+        skipped: usize,
+    },
+}
+
+/// Result of a single file extraction
+#[derive(Debug, Clone)]
+pub struct FileExtractionResult {
+    /// Path to the BA2 file
+    pub file_path: PathBuf,
+    /// Whether extraction was successful
+    pub success: bool,
+    /// Error message if extraction failed
+    pub error: Option<String>,
+    /// Warnings printed by the extraction tool
+    pub warnings: Vec<String>,
+    /// Cause of the failure if extraction failed (see [`diagnose`](crate::operations::diagnose))
+    pub failure: Option<FailureKind>,
+    /// Wall-clock time the extraction took, including retries
+    pub duration: Duration,
+}
+
+/// Result of batch extraction
+#[derive(Debug, Clone)]
+pub struct ExtractionResult {
+    /// Individual file results
+    pub file_results: Vec<FileExtractionResult>,
+    /// Number of successful extractions
+    pub successful: usize,
+    /// Number of failed extractions
+    pub failed: usize,
+    /// Number of files skipped without attempting extraction
+    pub skipped: usize,
+    /// Why the batch stopped early, if too many extractions failed in a row
+    pub aborted: Option<BatchAbort>,
+}
+
+/// A batch stopped after too many failures in a row (see [`AbortThreshold`])
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchAbort {
+    /// Failures in a row that ended the batch
+    pub consecutive_failures: usize,
+    /// Archives that were not started (also counted as skipped)
+    pub not_started: usize,
+    /// Most common error of the failures in a row
+    pub cause: String,
+    /// What the user can do about the cause
+    pub suggestions: Vec<String>,
+}
+
+impl ExtractionResult {
+    /// Create a new empty result
+    pub const fn new() -> Self {
+        Self {
+            file_results: Vec::new(),
+            successful: 0,
+            failed: 0,
+            skipped: 0,
+            aborted: None,
+        }
+    }
+
+    /// Add a file result
+    pub fn add_result(&mut self, result: FileExtractionResult) {
+        if result.success {
+            self.successful += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.file_results.push(result);
+    }
+
+    /// Get list of successfully extracted files
+    pub fn successful_files(&self) -> Vec<&PathBuf> {
+        self.file_results
+            .iter()
+            .filter(|r| r.success)
+            .map(|r| &r.file_path)
+            .collect()
+    }
+
+    /// Get list of failed files
+    pub fn failed_files(&self) -> Vec<&PathBuf> {
+        self.file_results
+            .iter()
+            .filter(|r| !r.success)
+            .map(|r| &r.file_path)
+            .collect()
+    }
+}
+
+impl Default for ExtractionResult {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extract a single BA2 file using BSArch.exe
+///
+/// # Arguments
+///
+/// * `ba2_path` - Path to the BA2 file to extract
+/// * `output_dir` - Directory to extract files to (defaults to BA2's parent directory)
+/// * `bsarch_path` - Path to BSArch.exe
+///
+/// # Returns
+///
+/// The parsed tool output if extraction succeeds, `Err` otherwise
+///
+pub async fn extract_ba2_file(
+    ba2_path: &Path,
+    output_dir: Option<&Path>,
+    bsarch_path: &Path,
+) -> Result<BsarchOutcome> {
+    extract_ba2_file_with_progress(
+        ba2_path,
+        output_dir,
+        bsarch_path,
+        BsarchCapabilities::default(),
+        |_| {},
+    )
+    .await
+}
+
+/// Extract a single BA2 file, reporting intra-archive progress
+///
+/// `on_progress` receives the completion percentage whenever `BSArch` reports
+/// progress. Success is decided from the parsed output as well as the exit
+/// code, since `BSArch` can print errors and still exit with 0. Optional flags
+/// are only passed if `capabilities` says the tool supports them.
+pub async fn extract_ba2_file_with_progress<P>(
+    ba2_path: &Path,
+    output_dir: Option<&Path>,
+    bsarch_path: &Path,
+    capabilities: BsarchCapabilities,
+    mut on_progress: P,
+) -> Result<BsarchOutcome>
+where
+    P: FnMut(u8),
+{
+    // Validate BA2 file exists
+    if !ba2_path.exists() {
+        return Err(BA2Error::ExtractionFailed {
+            path: ba2_path.to_path_buf(),
+            reason: "File not found".to_string(),
+        }
+        .into());
+    }
+
+    // Validate BSArch.exe exists
+    if !bsarch_path.exists() {
+        return Err(BA2Error::BSArchNotFound {
+            path: bsarch_path.to_path_buf(),
+        }
+        .into());
+    }
+
+    // Determine output directory
+    let Some(output_path) = output_dir.or_else(|| ba2_path.parent()) else {
+        return Err(BA2Error::ExtractionFailed {
+            path: ba2_path.to_path_buf(),
+            reason: "BA2 file path has no parent directory".to_string(),
+        }
+        .into());
+    };
+
+    // Deep archive paths inside deep mod folders can exceed MAX_PATH; pass
+    // extended-length paths so the tool can still create the files
+    let longest = read_file_names(ba2_path)
+        .map_or(0, |names| longest_output_path(output_path, &names));
+    let long_paths = longest > MAX_PATH;
+    if long_paths {
+        tracing::info!(
+            "Output paths for {} reach {} characters, using extended-length paths",
+            ba2_path.display(),
+            longest
+        );
+    }
+
+    // Build BSArch command
+    // Format: BSArch.exe unpack <ba2_file> <output_dir>
+    let mut cmd = Command::new(bsarch_path);
+    if long_paths {
+        cmd.arg("unpack")
+            .arg(to_extended_length(ba2_path))
+            .arg(to_extended_length(output_path));
+    } else {
+        cmd.arg("unpack").arg(ba2_path).arg(output_path);
+    }
+    if capabilities.multithreaded {
+        cmd.arg("-mt");
+    }
+
+    // On Windows, hide the console window to prevent flickering
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    // A spawn failure (tool busy, locked by antivirus) is transient and retried
+    let exec_failed = |e: std::io::Error| {
+        BA2Error::BSArchExecFailed(format!("Failed to spawn for {}: {e}", ba2_path.display()))
+    };
+    let mut child = cmd.spawn().map_err(exec_failed)?;
+    // Follows the low-priority mode, which can be switched during the run
+    let _priority = child.id().map(priority::track_process);
+    let (mut outcome, stderr) = read_tool_output(&mut child, &mut on_progress).await;
+    outcome.record_stderr(&decode_console_output(&stderr));
+    let status = child.wait().await.map_err(exec_failed)?;
+
+    // Check if extraction was successful
+    if !outcome.is_success(status.success()) {
+        // The tool could not cope with the long paths; say so instead of echoing its output
+        if long_paths {
+            return Err(BA2Error::PathTooLong {
+                path: ba2_path.to_path_buf(),
+                longest,
+                limit: MAX_PATH,
+            }
+            .into());
+        }
+
+        let reason = if outcome.errors.is_empty() {
+            format!("exited with {status}")
+        } else {
+            outcome.errors.join("; ")
+        };
+        if outcome.is_file_lock() {
+            return Err(BA2Error::FileLocked {
+                path: ba2_path.to_path_buf(),
+                reason,
+            }
+            .into());
+        }
+        return Err(BA2Error::ExtractionFailed {
+            path: ba2_path.to_path_buf(),
+            reason: format!("BSArch.exe failed: {reason}"),
+        }
+        .into());
+    }
+
+    for warning in &outcome.warnings {
+        tracing::warn!("BSArch warning for {}: {}", ba2_path.display(), warning);
+    }
+
+    Ok(outcome)
+}
+
+/// Parse the tool's stdout line by line while draining stderr so neither pipe
+/// fills up, returning the parsed outcome and the raw stderr
+async fn read_tool_output<P>(child: &mut Child, on_progress: &mut P) -> (BsarchOutcome, Vec<u8>)
+where
+    P: FnMut(u8),
+{
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let read_stdout = async {
+        let mut outcome = BsarchOutcome::default();
+        let Some(stdout) = stdout else {
+            return outcome;
+        };
+        let mut reader = BufReader::new(stdout);
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line).await.is_ok_and(|n| n > 0) {
+            let parsed = BsarchLine::parse(&decode_console_output(&line));
+            outcome.record(&parsed);
+            match parsed {
+                BsarchLine::Percent(percent) => on_progress(percent),
+                BsarchLine::Progress { .. } => {
+                    if let Some(percent) = outcome.percent() {
+                        on_progress(percent);
+                    }
+                }
+                _ => {}
+            }
+            line.clear();
+        }
+        outcome
+    };
+    let read_stderr = async {
+        let mut bytes = Vec::new();
+        if let Some(mut stderr) = stderr {
+            let _ = stderr.read_to_end(&mut bytes).await;
+        }
+        bytes
+    };
+    tokio::join!(read_stdout, read_stderr)
+}
+
+/// Unpacks single archives for [`extract_all_with`]
+///
+/// [`BsarchBackend`] runs `BSArch` and [`NativeBackend`] uses the built-in
+/// archive reader; the `test-support` feature provides a fake one with
+/// failure injection.
+pub trait ExtractorBackend: Send + Sync {
+    /// Unpack `archive` into `output_dir` (next to the archive if `None`)
+    ///
+    /// `on_progress` receives the completion percentage of the archive.
+    fn extract<'a>(
+        &'a self,
+        archive: &'a Path,
+        output_dir: Option<&'a Path>,
+        on_progress: &'a mut (dyn FnMut(u8) + Send),
+    ) -> BoxFuture<'a, Result<BsarchOutcome>>;
+
+    /// Version of the tool, if known; archives it cannot unpack are skipped
+    fn version(&self) -> Option<BsarchVersion> {
+        None
+    }
+
+    /// Largest mip levels dropped from the textures it unpacks (0 = full size)
+    fn texture_skip_mips(&self) -> u8 {
+        0
+    }
+}
+
+/// Extraction with `BSArch` (or a configured external tool)
+#[derive(Debug, Clone)]
+pub struct BsarchBackend {
+    path: PathBuf,
+    version: Option<BsarchVersion>,
+}
+
+impl BsarchBackend {
+    /// Use the tool at `path`, enabling the optional features of `version`
+    pub const fn new(path: PathBuf, version: Option<BsarchVersion>) -> Self {
+        Self { path, version }
+    }
+}
+
+impl ExtractorBackend for BsarchBackend {
+    fn extract<'a>(
+        &'a self,
+        archive: &'a Path,
+        output_dir: Option<&'a Path>,
+        on_progress: &'a mut (dyn FnMut(u8) + Send),
+    ) -> BoxFuture<'a, Result<BsarchOutcome>> {
+        let capabilities = self
+            .version
+            .map(BsarchVersion::capabilities)
+            .unwrap_or_default();
+        Box::pin(extract_ba2_file_with_progress(
+            archive,
+            output_dir,
+            &self.path,
+            capabilities,
+            on_progress,
+        ))
+    }
+
+    fn version(&self) -> Option<BsarchVersion> {
+        self.version
+    }
+}
+
+/// Extraction that unpacks texture archives with the built-in reader
+///
+/// Used for [`TexturePolicy::Convert`]: the reader writes every texture with a
+/// DDS header rebuilt from its record (see [`dds`](crate::ba2::dds)) instead
+/// of relying on the external tool. Other archives go to the wrapped backend.
+pub struct ConvertTextures {
+    textures: NativeBackend,
+    other: Arc<dyn ExtractorBackend>,
+}
+
+impl ConvertTextures {
+    /// Send texture archives to `textures` and all others to `other`
+    pub fn new(textures: NativeBackend, other: Arc<dyn ExtractorBackend>) -> Self {
+        Self { textures, other }
+    }
+}
+
+impl ExtractorBackend for ConvertTextures {
+    fn extract<'a>(
+        &'a self,
+        archive: &'a Path,
+        output_dir: Option<&'a Path>,
+        on_progress: &'a mut (dyn FnMut(u8) + Send),
+    ) -> BoxFuture<'a, Result<BsarchOutcome>> {
+        // Archives whose header cannot be read fail in the wrapped backend as before
+        if BA2Header::parse(archive).is_ok_and(|header| header.is_texture()) {
+            self.textures.extract(archive, output_dir, on_progress)
+        } else {
+            self.other.extract(archive, output_dir, on_progress)
+        }
+    }
+
+    fn version(&self) -> Option<BsarchVersion> {
+        self.other.version()
+    }
+
+    fn texture_skip_mips(&self) -> u8 {
+        self.textures.texture_skip_mips()
+    }
+}
+
+/// Resolve the `BSArch` executable to use
+///
+/// Uses the external BA2 tool if one is configured, otherwise the first
+/// BSArch.exe found by [`locate_bsarch`].
+pub fn resolve_bsarch_path(config: &AppConfig) -> PathBuf {
+    locate_bsarch(config).path
+}
+
+/// Detect the version of a `BSArch` executable
+///
+/// Runs the tool without arguments, which prints its banner and usage, and
+/// parses the version from it. Returns `Ok(None)` if the tool ran but did not
+/// report a recognizable version.
+pub async fn detect_bsarch_version(bsarch_path: &Path) -> Result<Option<BsarchVersion>> {
+    let usage = probe_tool(bsarch_path).await?;
+    Ok(log_version(bsarch_path, &usage))
+}
+
+/// Check that a user-configured or auto-detected tool can unpack BA2 files
+///
+/// Rejects files that are not executable and programs known to be something
+/// else (e.g., `NifSkope` registered as the .ba2 handler), then runs the tool
+/// without arguments and requires its usage to offer the `unpack` command.
+/// Returns the detected version of an accepted tool.
+pub async fn validate_ba2_tool(tool_path: &Path) -> Result<Option<BsarchVersion>> {
+    let invalid = |reason: String| BA2Error::InvalidTool {
+        path: tool_path.to_path_buf(),
+        reason,
+    };
+
+    if !tool_path.exists() {
+        return Err(BA2Error::BSArchNotFound {
+            path: tool_path.to_path_buf(),
+        }
+        .into());
+    }
+    if !is_valid_executable(tool_path) {
+        return Err(invalid("not an executable file".to_string()).into());
+    }
+    if let Some(name) = known_non_unpacker(tool_path) {
+        return Err(invalid(format!("{name} is not an archive unpacker")).into());
+    }
+
+    let usage = probe_tool(tool_path).await?;
+    if !looks_like_unpacker(&usage) {
+        return Err(invalid("it does not offer an unpack command".to_string()).into());
+    }
+    Ok(log_version(tool_path, &usage))
+}
+
+/// Run a tool without arguments and return its decoded stdout and stderr
+///
+/// `BSArch` only prints its usage in this case, so the probe has no side effects.
+async fn probe_tool(tool_path: &Path) -> Result<String> {
+    if !tool_path.exists() {
+        return Err(BA2Error::BSArchNotFound {
+            path: tool_path.to_path_buf(),
+        }
+        .into());
+    }
+
+    let mut cmd = Command::new(tool_path);
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let exec_failed = |reason: String| {
+        BA2Error::BSArchExecFailed(format!("{}: {reason}", tool_path.display()))
+    };
+    let output = tokio::time::timeout(VERSION_PROBE_TIMEOUT, cmd.output())
+        .await
+        .map_err(|_| exec_failed("timed out while reading its usage".to_string()))?
+        .map_err(|e| exec_failed(e.to_string()))?;
+
+    Ok(format!(
+        "{}\n{}",
+        decode_console_output(&output.stdout),
+        decode_console_output(&output.stderr)
+    ))
+}
+
+/// Parse and log the version from the usage output of a tool
+fn log_version(tool_path: &Path, usage: &str) -> Option<BsarchVersion> {
+    let version = version_from_output(usage);
+    if let Some(version) = version {
+        tracing::info!("Detected BSArch {} at {}", version, tool_path.display());
+    } else {
+        tracing::warn!("Could not determine BSArch version of {}", tool_path.display());
+    }
+    version
+}
+
+/// Extract multiple BA2 files with progress reporting and parallelism
+///
+/// # Arguments
+///
+/// * `files` - List of file entries to extract
+/// * `config` - Application configuration (for `BSArch` path, or the built-in
+///   reader, see [`uses_builtin_reader`])
+/// * `events` - Optional bus for publishing progress and tool events
+/// * `cancel` - Optional token that aborts pending retries and drive waits
+///
+/// # Returns
+///
+/// `ExtractionResult` with details about successful and failed extractions
+///
+pub async fn extract_all(
+    files: Vec<FileEntry>,
+    config: AppConfig,
+    events: Option<EventBus>,
+    cancel: Option<CancellationToken>,
+) -> Result<ExtractionResult> {
+    // Refuse before probing the tool
+    if config.advanced.analysis_only {
+        return Err(ValidationError::ReadOnlyMode("Extraction".to_string()).into());
+    }
+
+    if uses_builtin_reader(&config, &PlatformCapabilities::detect()) {
+        tracing::info!("Unpacking with the built-in archive reader");
+        let backend = NativeBackend::from_config(&config, run_storage_medium(&files, &config));
+        return extract_all_with(files, config, Arc::new(backend), events, cancel).await;
+    }
+
+    let tool = locate_bsarch(&config);
+    let bsarch_path = tool.path;
+
+    // A configured tool or .ba2 handler that is not an unpacker would fail
+    // every archive, so refuse it up front. Optional features are only used
+    // if the installed tool is new enough.
+    let tool_version = if tool.origin.needs_validation() {
+        match validate_ba2_tool(&bsarch_path).await {
+            Ok(version) => version,
+            Err(e @ Error::BA2(BA2Error::InvalidTool { .. })) => {
+                if let Some(ref events) = events {
+                    events
+                        .publish(ToolEvent::Rejected {
+                            path: bsarch_path.clone(),
+                            reason: e.to_string(),
+                        })
+                        .await;
+                }
+                return Err(e);
+            }
+            Err(_) => None,
+        }
+    } else {
+        detect_bsarch_version(&bsarch_path).await.ok().flatten()
+    };
+    if let Some(ref events) = events {
+        events
+            .publish(ToolEvent::Detected {
+                path: bsarch_path.clone(),
+                version: tool_version,
+            })
+            .await;
+    }
+
+    let mut backend: Arc<dyn ExtractorBackend> =
+        Arc::new(BsarchBackend::new(bsarch_path, tool_version));
+    if config.extraction.texture_policy == TexturePolicy::Convert {
+        let textures = NativeBackend::from_config(&config, run_storage_medium(&files, &config));
+        backend = Arc::new(ConvertTextures::new(textures, backend));
+    }
+    extract_all_with(files, config, backend, events, cancel).await
+}
+
+/// Extract multiple BA2 files with the given backend
+///
+/// Does everything [`extract_all`] does after picking the tool: skipping,
+/// scheduling, parallelism, retries, drive reconnects and manifests.
+/// [`extract_all`] runs `BSArch`; tests pass a fake backend.
+pub async fn extract_all_with(
+    files: Vec<FileEntry>,
+    config: AppConfig,
+    backend: Arc<dyn ExtractorBackend>,
+    events: Option<EventBus>,
+    cancel: Option<CancellationToken>,
+) -> Result<ExtractionResult> {
+    if config.advanced.analysis_only {
+        return Err(ValidationError::ReadOnlyMode("Extraction".to_string()).into());
+    }
+    priority::set_low_priority(config.advanced.performance.low_priority);
+
+    // Archives are backed up if auto backup is on, and loose files of the game
+    // Data folder before they are overwritten
+    let backup = backup_root(&config);
+    let archive_backup = backup.clone().filter(|_| config.extraction.auto_backup);
+    let loose_backup = backup.filter(|_| config.extraction.data_folder_mode);
+
+    let (files, skipped_files) = preflight(
+        files,
+        &config,
+        backend.as_ref(),
+        loose_backup.as_deref(),
+        events.as_ref(),
+    )
+    .await;
+    let serialize_per_mod = config.extraction.serialize_per_mod;
+
+    // Network and removable drives are slower and may drop out during the run
+    let drive = files
+        .first()
+        .and_then(|f| f.full_path.parent())
+        .map_or(DriveKind::Local, drive_kind);
+
+    // Hard disks thrash when many jobs read at once
+    let medium = run_storage_medium(&files, &config);
+
+    // Determine concurrency limit
+    // Use number of logical cores, capped between 1 and 8 to avoid resource exhaustion
+    // and lowered for slow drives and media, unless the user set the number of jobs
+    let concurrency_limit = if config.advanced.performance.max_jobs > 0 {
+        config.advanced.performance.max_jobs
+    } else {
+        drive.max_jobs(
+            medium.max_jobs(
+                std::thread::available_parallelism()
+                    .map_or(4, std::num::NonZero::get)
+                    .clamp(1, 8),
+            ),
+        )
+    };
+
+    tracing::debug!(
+        "Extracting from {} ({}) with concurrency limit: {}",
+        drive.description(),
+        medium.description(),
+        concurrency_limit
+    );
+
+    let texture_skip_mips = backend.texture_skip_mips();
+    let mut output_mode = OutputMode::from_config(&config);
+    // Downscaled textures are cached apart from full-size ones
+    if let OutputMode::Linked { cache_root, .. } = &mut output_mode
+        && texture_skip_mips > 0
+    {
+        *cache_root = cache_root.join(format!("mips-{texture_skip_mips}"));
+    }
+    // Nothing writes into the mod folders yet, so their staging folders are
+    // left over from a run that crashed before it could clean up
+    let folders: BTreeSet<PathBuf> = files
+        .iter()
+        .filter_map(|f| f.full_path.parent().map(Path::to_path_buf))
+        .collect();
+    let abandoned =
+        tokio::task::spawn_blocking(move || folders.iter().map(|f| remove_abandoned(f)).sum())
+            .await
+            .unwrap_or(0usize);
+    if abandoned > 0 {
+        tracing::info!("Removed {} abandoned staging folders", abandoned);
+    }
+    // One lock per mod folder when archives of a mod must not be extracted concurrently
+    let mut mod_locks: HashMap<PathBuf, Arc<Mutex<()>>> = HashMap::new();
+    if serialize_per_mod {
+        for folder in files.iter().filter_map(|f| f.full_path.parent()) {
+            mod_locks.entry(folder.to_path_buf()).or_default();
+        }
+    }
+
+    let run = Arc::new(Run {
+        backend,
+        events,
+        cancel,
+        // Jitter keeps parallel jobs that failed together from retrying in lockstep
+        retry_config: RetryConfig::for_drive(drive).with_jitter(RETRY_JITTER),
+        output_mode,
+        loose_backup,
+        archive_backup,
+        drive,
+        texture_skip_mips,
+        write_manifests: config.extraction.write_manifest,
+        abort_threshold: config.extraction.abort_after_failures,
+        total: files.len(),
+        total_size: files.iter().map(|f| f.file_size).sum(),
+        semaphore: Semaphore::new(concurrency_limit),
+        current_counter: std::sync::atomic::AtomicUsize::new(0),
+        reconnect_gate: Mutex::new(()),
+        streak: Mutex::new(FailureStreak::default()),
+    });
+
+    // Create a stream of extraction futures; archives that were not started
+    // because the batch was aborted yield `None`
+    let results: Vec<Option<FileExtractionResult>> = stream::iter(files)
+        .map(|file_entry| {
+            let mod_lock = file_entry
+                .full_path
+                .parent()
+                .and_then(|folder| mod_locks.get(folder))
+                .cloned();
+            Arc::clone(&run).extract_archive(file_entry, mod_lock)
+        })
+        .buffer_unordered(concurrency_limit) // Run up to concurrency_limit futures in parallel
+        .collect()
+        .await;
+
+    Ok(run.summarize(results, skipped_files.len()).await)
+}
+
+/// Drop the archives a run must not extract and order the rest
+///
+/// Skipped archives are published as [`ExtractionProgress::Skipped`] and
+/// returned as (name, path, reason).
+async fn preflight(
+    files: Vec<FileEntry>,
+    config: &AppConfig,
+    backend: &dyn ExtractorBackend,
+    loose_backup: Option<&Path>,
+    events: Option<&EventBus>,
+) -> (Vec<FileEntry>, Vec<(String, PathBuf, String)>) {
+    let tool_version = backend.version();
+    let mut skipped_files = Vec::new();
+    let mut files: Vec<FileEntry> = files
+        .into_iter()
+        .filter(|f| {
+            let Some(reason) = skip_reason(f, config, tool_version, loose_backup) else {
+                return true;
+            };
+            skipped_files.push((f.file_name.clone(), f.full_path.clone(), reason));
+            false
+        })
+        .collect();
+    schedule(&mut files, config.extraction.schedule);
+    if config.extraction.serialize_per_mod {
+        files = interleave_by_mod(files);
+    }
+
+    // Only the built-in reader drops mip levels; other backends unpack textures at full size
+    if config.extraction.texture_skip_mips > backend.texture_skip_mips()
+        && files.iter().any(FileEntry::is_texture)
+    {
+        tracing::warn!(
+            "Skipping mip levels needs the built-in archive reader, textures are extracted at full size"
+        );
+    }
+
+    if let Some(events) = events {
+        for (file_name, file_path, reason) in &skipped_files {
+            events
+                .publish(ExtractionProgress::Skipped {
+                    file_name: file_name.clone(),
+                    file_path: file_path.clone(),
+                    reason: reason.clone(),
+                })
+                .await;
+        }
+    }
+    (files, skipped_files)
+}
+
+/// Why an archive is skipped up front, if it is
+///
+/// Corrupted archives are skipped when the user asked to ignore them (unless
+/// extraction was forced from the Quarantine view), known exclusions unless
+/// they were overridden, archives of unknown format versions unless they are
+/// allowed, texture archives when the texture policy says so, Starfield
+/// archives when the tool is known to be too old for them, and archives in
+/// the game Data folder unless Data folder mode is on and their overwritten
+/// loose files can be backed up.
+fn skip_reason(
+    f: &FileEntry,
+    config: &AppConfig,
+    tool_version: Option<BsarchVersion>,
+    loose_backup: Option<&Path>,
+) -> Option<String> {
+    let reason = if config.extraction.ignore_bad_files && f.is_bad() && !f.overrides.bad_file {
+        f.health.to_string()
+    } else if let Some(exclusion) = f.exclusion.as_ref().filter(|_| f.is_excluded()) {
+        format!("Must stay packed: {exclusion}")
+    } else if f.has_unknown_version(config.profile()) && !config.extraction.allow_unknown_versions
+    {
+        format!(
+            "Unsupported BA2 version {} for {} (allow unknown versions in Settings to extract anyway)",
+            f.format_version, config.game
+        )
+    } else if loose_backup.is_none() && in_data_folder(&f.full_path) {
+        if config.extraction.data_folder_mode {
+            "No backup folder for the loose files of the Data folder (set a backup path in Settings)".to_string()
+        } else {
+            "Archive is in the game Data folder (enable Data folder mode in Settings to extract there)".to_string()
+        }
+    } else if f.is_texture() && config.extraction.texture_policy == TexturePolicy::Skip {
+        "Texture archive (skipped by texture policy)".to_string()
+    } else if f.is_starfield()
+        && let Some(version) = tool_version.filter(|v| !v.capabilities().starfield)
+    {
+        format!("BSArch {version} cannot unpack Starfield archives (needs {STARFIELD_MIN_VERSION})")
+    } else {
+        return None;
+    };
+    Some(reason)
+}
+
+/// Settings and shared state of the parallel jobs of a run
+struct Run {
+    backend: Arc<dyn ExtractorBackend>,
+    events: Option<EventBus>,
+    cancel: Option<CancellationToken>,
+    retry_config: RetryConfig,
+    output_mode: OutputMode,
+    loose_backup: Option<PathBuf>,
+    archive_backup: Option<PathBuf>,
+    drive: DriveKind,
+    /// Mip levels dropped from texture archives
+    texture_skip_mips: u8,
+    write_manifests: bool,
+    abort_threshold: AbortThreshold,
+    /// Number and total size of the archives to extract
+    total: usize,
+    total_size: u64,
+    semaphore: Semaphore,
+    current_counter: std::sync::atomic::AtomicUsize,
+    /// Serializes waiting for a disconnected drive so the prompt is only raised once
+    reconnect_gate: Mutex<()>,
+    streak: Mutex<FailureStreak>,
+}
+
+impl Run {
+    /// Extract one archive of the run, or `None` if the batch was aborted
+    /// before it could start
+    async fn extract_archive(
+        self: Arc<Self>,
+        file_entry: FileEntry,
+        mod_lock: Option<Arc<Mutex<()>>>,
+    ) -> Option<FileExtractionResult> {
+        let file_path = file_entry.full_path.clone();
+        let skip_mips = if file_entry.is_texture() {
+            self.texture_skip_mips
+        } else {
+            0
+        };
+        let file_size = file_entry.file_size;
+        let file_name = file_entry.file_name;
+
+        // Wait for other archives of the same mod before taking a job slot
+        let _mod_guard = match &mod_lock {
+            Some(lock) => Some(lock.lock().await),
+            None => None,
+        };
+
+        // Acquire permit to limit concurrency
+        let Ok(_permit) = self.semaphore.acquire().await else {
+            // Semaphore was closed unexpectedly - treat as extraction failure
+            return Some(FileExtractionResult {
+                file_path,
+                success: false,
+                error: Some("Extraction semaphore was closed unexpectedly".to_string()),
+                warnings: Vec::new(),
+                failure: Some(FailureKind::Other),
+                duration: Duration::ZERO,
+            });
+        };
+
+        // Don't start archives once the batch was aborted
+        if self.skip_after_abort(&file_name, &file_path).await {
+            return None;
+        }
+
+        let current = self
+            .current_counter
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+            + 1;
+        let started = Instant::now();
+        self.publish(ExtractionProgress::Started {
+            file_name: file_name.clone(),
+            file_path: file_path.clone(),
+            current,
+            total: self.total,
+            file_size,
+            total_size: self.total_size,
+        })
+        .await;
+
+        let outcome = self.extract_with_reconnect(&file_path, &file_name).await;
+
+        let newly_aborted = self.streak.lock().await.record(
+            outcome.as_ref().err(),
+            self.abort_threshold,
+            self.total,
+        );
+        if let Some(abort) = newly_aborted {
+            self.publish(ExtractionProgress::Aborted {
+                consecutive_failures: abort.consecutive_failures,
+                cause: abort.cause,
+            })
+            .await;
+        }
+
+        let duration = started.elapsed();
+        let mut output_size = None;
+        let error_category = outcome.as_ref().err().map(Error::category);
+        let extraction_result = match outcome {
+            Ok(tool_outcome) => {
+                if self.write_manifests {
+                    output_size = write_archive_manifest(&file_path, skip_mips).await;
+                }
+                FileExtractionResult {
+                    file_path: file_path.clone(),
+                    success: true,
+                    error: None,
+                    warnings: tool_outcome.warnings,
+                    failure: None,
+                    duration,
+                }
+            }
+            Err(e) => FileExtractionResult {
+                file_path: file_path.clone(),
+                success: false,
+                error: Some(e.to_string()),
+                warnings: Vec::new(),
+                failure: Some(FailureKind::of(&e)),
+                duration,
+            },
+        };
+
+        self.publish(ExtractionProgress::Completed {
+            file_name,
+            file_path,
+            success: extraction_result.success,
+            error: extraction_result.error.clone(),
+            warnings: extraction_result.warnings.clone(),
+            output_size,
+            error_category,
+            duration,
+        })
+        .await;
+
+        Some(extraction_result)
+    }
+
+    /// Whether the batch was aborted, publishing the archive as skipped if so
+    async fn skip_after_abort(&self, file_name: &str, file_path: &Path) -> bool {
+        let aborted_after = self
+            .streak
+            .lock()
+            .await
+            .abort
+            .as_ref()
+            .map(|abort| abort.consecutive_failures);
+        let Some(failures) = aborted_after else {
+            return false;
+        };
+        self.publish(ExtractionProgress::Skipped {
+            file_name: file_name.to_string(),
+            file_path: file_path.to_path_buf(),
+            reason: format!("Not started after {failures} extractions in a row failed"),
+        })
+        .await;
+        true
+    }
+
+    /// Extract an archive, retrying transient failures and waiting out drive
+    /// disconnects instead of failing
+    async fn extract_with_reconnect(&self, file_path: &Path, file_name: &str) -> Result<BsarchOutcome> {
+        let attempt = || {
+            extract_with_retry(
+                file_path,
+                file_name,
+                self.backend.as_ref(),
+                &self.output_mode,
+                &self.retry_config,
+                self.loose_backup.as_deref(),
+                self.archive_backup.as_deref(),
+                self.cancel.as_ref(),
+                self.events.as_ref(),
+            )
+        };
+        let mut outcome = attempt().await;
+        while outcome.is_err() && self.drive.is_slow() {
+            let Some(folder) = file_path.parent() else {
+                break;
+            };
+            let reconnected = wait_for_reconnect(
+                folder,
+                &self.reconnect_gate,
+                self.cancel.as_ref(),
+                self.events.as_ref(),
+            )
+            .await;
+            if !reconnected {
+                break;
+            }
+            outcome = attempt().await;
+        }
+        outcome
+    }
+
+    /// Aggregate the results of the run and publish the final progress
+    async fn summarize(
+        &self,
+        results: Vec<Option<FileExtractionResult>>,
+        skipped: usize,
+    ) -> ExtractionResult {
+        let mut final_result = ExtractionResult::new();
+        let mut not_started = 0;
+        for res in results {
+            match res {
+                Some(res) => final_result.add_result(res),
+                None => not_started += 1,
+            }
+        }
+        final_result.skipped = skipped + not_started;
+        final_result.aborted = self.streak.lock().await.abort.take().map(|abort| BatchAbort {
+            not_started,
+            ..abort
+        });
+        if let Some(ref abort) = final_result.aborted {
+            tracing::warn!(
+                "Aborted extraction after {} failures in a row, {} archives not started: {}",
+                abort.consecutive_failures,
+                abort.not_started,
+                abort.cause
+            );
+        }
+
+        self.publish(ExtractionProgress::Finished {
+            successful: final_result.successful,
+            failed: final_result.failed,
+            skipped: final_result.skipped,
+        })
+        .await;
+        final_result
+    }
+
+    async fn publish(&self, progress: ExtractionProgress) {
+        if let Some(ref events) = self.events {
+            events.publish(progress).await;
+        }
+    }
+}
+
+/// Extractions failing in a row across the parallel jobs of a batch
+#[derive(Debug, Default)]
+struct FailureStreak {
+    /// Category, message and suggestions of each failure since the last success
+    failures: Vec<(&'static str, String, Vec<String>)>,
+    /// Set once the streak exceeded the threshold
+    abort: Option<BatchAbort>,
+}
+
+impl FailureStreak {
+    /// Record the outcome of an extraction (`None` for a success)
+    ///
+    /// Returns the abort if this failure made the streak exceed `threshold`.
+    /// Cancellations neither extend nor end the streak.
+    fn record(
+        &mut self,
+        error: Option<&Error>,
+        threshold: AbortThreshold,
+        total: usize,
+    ) -> Option<BatchAbort> {
+        let Some(error) = error else {
+            self.failures.clear();
+            return None;
+        };
+        if error.is_cancelled() || self.abort.is_some() {
+            return None;
+        }
+
+        self.failures.push((
+            error.category(),
+            error.user_message(),
+            error.recovery_suggestions(),
+        ));
+        if !threshold.is_exceeded(self.failures.len(), total) {
+            return None;
+        }
+
+        // The latest failure of the most common category stands for the streak
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for (category, ..) in &self.failures {
+            *counts.entry(*category).or_default() += 1;
+        }
+        let (_, cause, suggestions) = self
+            .failures
+            .iter()
+            .max_by_key(|(category, ..)| counts[category])?
+            .clone();
+        let abort = BatchAbort {
+            consecutive_failures: self.failures.len(),
+            not_started: 0,
+            cause,
+            suggestions,
+        };
+        self.abort = Some(abort.clone());
+        Some(abort)
+    }
+}
+
+/// Extract a single archive, retrying transient failures
+///
+/// Intra-archive progress is reported as [`ExtractionProgress::FileProgress`]
+/// and each retry as [`ExtractionProgress::Retrying`]. Each attempt writes its
+/// output as chosen by `output_mode`. The archive is first copied into
+/// `archive_backup`, and archives in the game Data folder back up the loose
+/// files they overwrite into `loose_backup`.
+#[allow(clippy::too_many_arguments)] // Per-run settings threaded from extract_all
+async fn extract_with_retry(
+    file_path: &Path,
+    file_name: &str,
+    backend: &dyn ExtractorBackend,
+    output_mode: &OutputMode,
+    retry_config: &RetryConfig,
+    loose_backup: Option<&Path>,
+    archive_backup: Option<&Path>,
+    cancel: Option<&CancellationToken>,
+    events: Option<&EventBus>,
+) -> Result<BsarchOutcome> {
+    if let Some(backup_root) = archive_backup {
+        let (archive, backup_root) = (file_path.to_path_buf(), backup_root.to_path_buf());
+        let backup = tokio::task::spawn_blocking(move || back_up_archive(&archive, &backup_root))
+            .await
+            .map_err(|e| Error::other(format!("Backup task failed: {e}")))??;
+        if let Some(backup) = backup
+            && let Some(events) = events
+        {
+            events
+                .publish(BackupEvent::ArchiveSaved {
+                    archive: file_path.to_path_buf(),
+                    backup,
+                })
+                .await;
+        }
+    }
+
+    if let Some(backup_root) = loose_backup
+        && in_data_folder(file_path)
+    {
+        let (archive, backup_root) = (file_path.to_path_buf(), backup_root.to_path_buf());
+        let count =
+            tokio::task::spawn_blocking(move || back_up_loose_files(&archive, &backup_root))
+                .await
+                .map_err(|e| Error::other(format!("Backup task failed: {e}")))??;
+        if count > 0
+            && let Some(events) = events
+        {
+            events
+                .publish(BackupEvent::LooseFilesSaved {
+                    archive: file_path.to_path_buf(),
+                    count,
+                })
+                .await;
+        }
+    }
+
+    retry_async_with(
+        retry_config,
+        cancel,
+        || {
+            let mut on_progress = move |percent| {
+                if let Some(events) = events {
+                    // Progress is best-effort; drop updates rather than stall the tool
+                    events.try_publish(ExtractionProgress::FileProgress {
+                        file_name: file_name.to_string(),
+                        file_path: file_path.to_path_buf(),
+                        percent,
+                    });
+                }
+            };
+            async move {
+                match output_mode {
+                    OutputMode::InPlace => {
+                        backend.extract(file_path, None, &mut on_progress).await
+                    }
+                    OutputMode::Staged => {
+                        extract_via_staging(file_path, backend, &mut on_progress).await
+                    }
+                    OutputMode::Linked { mode, cache_root } => {
+                        extract_via_cache(file_path, backend, *mode, cache_root, &mut on_progress)
+                            .await
+                    }
+                }
+            }
+        },
+        |attempt| {
+            if let Some(events) = events {
+                events.try_publish(ExtractionProgress::Retrying {
+                    file_name: file_name.to_string(),
+                    file_path: file_path.to_path_buf(),
+                    attempt: attempt.attempt,
+                    max_attempts: attempt.max_attempts,
+                    delay: attempt.delay,
+                    likely_antivirus: attempt.error.is_file_lock(),
+                });
+            }
+        },
+    )
+    .await
+}
+
+/// Extract an archive into a staging folder and move the result into place
+///
+/// The extracted files are checked against the archive's name table before
+/// they are moved next to the archive. If the tool fails or files are
+/// missing, the staging folder is discarded and the mod folder is untouched.
+async fn extract_via_staging(
+    ba2_path: &Path,
+    backend: &dyn ExtractorBackend,
+    on_progress: &mut (dyn FnMut(u8) + Send),
+) -> Result<BsarchOutcome> {
+    let Some(destination) = ba2_path.parent() else {
+        return Err(BA2Error::ExtractionFailed {
+            path: ba2_path.to_path_buf(),
+            reason: "BA2 file path has no parent directory".to_string(),
+        }
+        .into());
+    };
+    let staging = StagingDir::create(ba2_path, destination)?;
+
+    let outcome = backend
+        .extract(ba2_path, Some(staging.path()), on_progress)
+        .await?;
+
+    let archive = ba2_path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let names = read_file_names(&archive)?;
+        staging.verify(&archive, &names)?;
+        staging.commit()
+    })
+    .await
+    .map_err(|e| Error::other(format!("Staging task failed: {e}")))??;
+
+    Ok(outcome)
+}
+
+/// Extract an archive into the shared cache and link the files into place
+///
+/// Archives already in the cache are not unpacked again. New cache entries are
+/// staged and verified like [`extract_via_staging`] before they are marked
+/// complete, so an interrupted run never leaves a partial entry to be reused.
+async fn extract_via_cache(
+    ba2_path: &Path,
+    backend: &dyn ExtractorBackend,
+    mode: LinkMode,
+    cache_root: &Path,
+    on_progress: &mut (dyn FnMut(u8) + Send),
+) -> Result<BsarchOutcome> {
+    let Some(destination) = ba2_path.parent().map(Path::to_path_buf) else {
+        return Err(BA2Error::ExtractionFailed {
+            path: ba2_path.to_path_buf(),
+            reason: "BA2 file path has no parent directory".to_string(),
+        }
+        .into());
+    };
+
+    let archive = ba2_path.to_path_buf();
+    let root = cache_root.to_path_buf();
+    let entry = tokio::task::spawn_blocking(move || cache_entry(&root, &archive))
+        .await
+        .map_err(|e| Error::other(format!("Cache task failed: {e}")))??;
+
+    let outcome = if is_cached(&entry) {
+        tracing::info!(
+            "Reusing cached extraction of {} from {}",
+            ba2_path.display(),
+            entry.display()
+        );
+        BsarchOutcome::default()
+    } else {
+        let staging = StagingDir::create(ba2_path, &entry)?;
+        let outcome = backend
+            .extract(ba2_path, Some(staging.path()), on_progress)
+            .await?;
+
+        let archive = ba2_path.to_path_buf();
+        let entry = entry.clone();
+        tokio::task::spawn_blocking(move || {
+            let names = read_file_names(&archive)?;
+            staging.verify(&archive, &names)?;
+            staging.commit()?;
+            mark_complete(&entry)
+        })
+        .await
+        .map_err(|e| Error::other(format!("Cache task failed: {e}")))??;
+        outcome
+    };
+
+    let report = tokio::task::spawn_blocking(move || link_tree(&entry, &destination, mode))
+        .await
+        .map_err(|e| Error::other(format!("Linking task failed: {e}")))??;
+    if report.copied > 0 {
+        tracing::info!(
+            "Copied {} files of {} that could not be linked",
+            report.copied,
+            ba2_path.display()
+        );
+    }
+
+    Ok(outcome)
+}
+
+/// Order the archives of a run by the configured schedule
+///
+/// With a fixed number of parallel jobs, starting the largest archives first
+/// keeps a single multi-gigabyte archive from running alone at the end.
+fn schedule(files: &mut [FileEntry], order: ScheduleOrder) {
+    match order {
+        ScheduleOrder::LargestFirst => files.sort_by_key(|f| std::cmp::Reverse(f.file_size)),
+        ScheduleOrder::SmallestFirst => files.sort_by_key(|f| f.file_size),
+        ScheduleOrder::AsListed => {}
+    }
+}
+
+/// Reorder archives so archives of the same mod are spread across the run
+///
+/// Archives are taken round-robin from each mod folder, keeping the scheduled
+/// order within a mod. When extraction is serialized per mod, this keeps the
+/// parallel jobs busy with different mods instead of queueing on one mod.
+fn interleave_by_mod(files: Vec<FileEntry>) -> Vec<FileEntry> {
+    let mut groups: Vec<VecDeque<FileEntry>> = Vec::new();
+    let mut group_index: HashMap<PathBuf, usize> = HashMap::new();
+    for file in files {
+        let folder = file.full_path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let index = *group_index.entry(folder).or_insert_with(|| {
+            groups.push(VecDeque::new());
+            groups.len() - 1
+        });
+        groups[index].push_back(file);
+    }
+
+    let mut interleaved = Vec::with_capacity(groups.iter().map(VecDeque::len).sum());
+    while !groups.is_empty() {
+        for group in &mut groups {
+            interleaved.extend(group.pop_front());
+        }
+        groups.retain(|group| !group.is_empty());
+    }
+    interleaved
+}
+
+/// Wait until `folder` is reachable again after a failed extraction
+///
+/// Returns `false` without waiting if the folder is reachable, i.e., the
+/// failure was not caused by a disconnected drive, or if `cancel` fires while
+/// waiting. Only the first task to notice the disconnect reports it; the
+/// others wait on the same gate.
+async fn wait_for_reconnect(
+    folder: &Path,
+    gate: &Mutex<()>,
+    cancel: Option<&CancellationToken>,
+    events: Option<&EventBus>,
+) -> bool {
+    if is_reachable(folder) || cancel.is_some_and(CancellationToken::is_cancelled) {
+        return false;
+    }
+
+    let _guard = gate.lock().await;
+    if cancel.is_some_and(CancellationToken::is_cancelled) {
+        return false;
+    }
+    if is_reachable(folder) {
+        // Another task already waited for the reconnect
+        return true;
+    }
+
+    tracing::warn!("{} became unreachable, waiting for reconnect", folder.display());
+    if let Some(events) = events {
+        events
+            .publish(ExtractionProgress::DriveDisconnected {
+                folder: folder.to_path_buf(),
+            })
+            .await;
+    }
+
+    while !is_reachable(folder) {
+        tokio::time::sleep(RECONNECT_POLL_INTERVAL).await;
+        if cancel.is_some_and(CancellationToken::is_cancelled) {
+            tracing::info!("Stopped waiting for {}", folder.display());
+            return false;
+        }
+    }
+
+    tracing::info!("{} is reachable again, resuming", folder.display());
+    if let Some(events) = events {
+        events
+            .publish(ExtractionProgress::DriveReconnected {
+                folder: folder.to_path_buf(),
+            })
+            .await;
+    }
+    true
+}
+
+/// Write the checksum manifest for an archive extracted next to itself
+///
+/// `texture_skip_mips` records how far its textures were downscaled. Returns
+/// the total size of the extracted files. A manifest failure does not fail
+/// the extraction; it is only logged.
+async fn write_archive_manifest(archive: &Path, texture_skip_mips: u8) -> Option<u64> {
+    let output_dir = archive.parent()?.to_path_buf();
+    let archive = archive.to_path_buf();
+
+    let result = tokio::task::spawn_blocking(move || {
+        let mut manifest = build_manifest(&archive, &output_dir)?;
+        manifest.texture_skip_mips = texture_skip_mips;
+        save_manifest(&manifest, &archive, &output_dir).map(|path| (path, manifest.total_size()))
+    })
+    .await;
+    match result {
+        Ok(Ok((path, size))) => {
+            tracing::debug!("Manifest written to {}", path.display());
+            Some(size)
+        }
+        Ok(Err(e)) => {
+            tracing::warn!("Failed to write extraction manifest: {}", e);
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Manifest task failed: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ArchiveHealth;
+    use crate::operations::events::AppEvent;
+    use crate::platform::capabilities::BsarchSupport;
+
+    #[test]
+    fn test_extraction_result_creation() {
+        let result = ExtractionResult::new();
+        assert_eq!(result.successful, 0);
+        assert_eq!(result.failed, 0);
+        assert!(result.file_results.is_empty());
+    }
+
+    #[test]
+    fn test_extraction_result_add_success() {
+        let mut result = ExtractionResult::new();
+        result.add_result(FileExtractionResult {
+            file_path: PathBuf::from("/test/file.ba2"),
+            success: true,
+            error: None,
+            warnings: Vec::new(),
+            failure: None,
+            duration: Duration::ZERO,
+        });
+
+        assert_eq!(result.successful, 1);
+        assert_eq!(result.failed, 0);
+        assert_eq!(result.file_results.len(), 1);
+    }
+
+    #[test]
+    fn test_extraction_result_add_failure() {
+        let mut result = ExtractionResult::new();
+        result.add_result(FileExtractionResult {
+            file_path: PathBuf::from("/test/file.ba2"),
+            success: false,
+            error: Some("Test error".to_string()),
+            warnings: Vec::new(),
+            failure: Some(FailureKind::Other),
+            duration: Duration::ZERO,
+        });
+
+        assert_eq!(result.successful, 0);
+        assert_eq!(result.failed, 1);
+        assert_eq!(result.file_results.len(), 1);
+    }
+
+    #[test]
+    fn test_extraction_result_filtering() {
+        let mut result = ExtractionResult::new();
+
+        result.add_result(FileExtractionResult {
+            file_path: PathBuf::from("/test/success.ba2"),
+            success: true,
+            error: None,
+            warnings: Vec::new(),
+            failure: None,
+            duration: Duration::ZERO,
+        });
+
+        result.add_result(FileExtractionResult {
+            file_path: PathBuf::from("/test/failure.ba2"),
+            success: false,
+            error: Some("Error".to_string()),
+            warnings: Vec::new(),
+            failure: Some(FailureKind::Other),
+            duration: Duration::ZERO,
+        });
+
+        let successful = result.successful_files();
+        let failed = result.failed_files();
+
+        assert_eq!(successful.len(), 1);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(
+            successful[0].file_name().unwrap().to_str().unwrap(),
+            "success.ba2"
+        );
+        assert_eq!(
+            failed[0].file_name().unwrap().to_str().unwrap(),
+            "failure.ba2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_all_refused_in_analysis_only_mode() {
+        let mut config = AppConfig::default();
+        config.advanced.analysis_only = true;
+
+        let result = extract_all(Vec::new(), config, None, None).await;
+        assert!(matches!(
+            result,
+            Err(crate::error::Error::Validation(ValidationError::ReadOnlyMode(_)))
+        ));
+    }
+
+    #[test]
+    fn test_uses_builtin_reader() {
+        let platform = |bsarch| PlatformCapabilities {
+            os: "windows",
+            build_arch: "aarch64",
+            machine_arch: "aarch64",
+            bsarch,
+            file_associations: true,
+        };
+        let native = platform(BsarchSupport::Native);
+        let emulated = platform(BsarchSupport::Emulated);
+        let mut config = AppConfig::default();
+        assert!(!uses_builtin_reader(&config, &native));
+        assert!(uses_builtin_reader(&config, &emulated));
+
+        config.advanced.ext_ba2_exe = "C:/Tools/BSArch.exe".to_string();
+        assert!(!uses_builtin_reader(&config, &emulated));
+        config.advanced.performance.native_extraction = true;
+        assert!(uses_builtin_reader(&config, &native));
+    }
+
+    #[tokio::test]
+    async fn test_extract_all_skips_bad_files() {
+        let mut bad = FileEntry::new(
+            "bad.ba2".to_string(),
+            100,
+            0,
+            "TestMod".to_string(),
+            PathBuf::from("/nonexistent/bad.ba2"),
+        );
+        bad.health = ArchiveHealth::InvalidFormat("truncated header".to_string());
+
+        let mut config = AppConfig::default();
+        config.extraction.ignore_bad_files = true;
+        // A tool set in Settings is used on every platform
+        config.advanced.ext_ba2_exe = "/nonexistent/BSArch.exe".to_string();
+
+        let events = EventBus::new();
+        let mut rx = events.subscribe();
+        let result = extract_all(vec![bad], config, Some(events), None).await.unwrap();
+
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.successful, 0);
+        assert_eq!(result.failed, 0);
+        // The tool check comes first
+        assert!(matches!(rx.recv().await, Some(AppEvent::Tool(_))));
+        assert!(matches!(
+            rx.recv().await,
+            Some(AppEvent::Extract(ExtractionProgress::Skipped { .. }))
+        ));
+    }
+
+    #[test]
+    fn test_schedule_order() {
+        let entry = |name: &str, size| {
+            FileEntry::new(
+                name.to_string(),
+                size,
+                1,
+                "TestMod".to_string(),
+                PathBuf::from(name),
+            )
+        };
+        let names = |files: &[FileEntry]| {
+            files.iter().map(|f| f.file_name.clone()).collect::<Vec<_>>()
+        };
+        let listed = vec![entry("a.ba2", 10), entry("b.ba2", 3000), entry("c.ba2", 200)];
+
+        let mut files = listed.clone();
+        schedule(&mut files, ScheduleOrder::LargestFirst);
+        assert_eq!(names(&files), ["b.ba2", "c.ba2", "a.ba2"]);
+
+        schedule(&mut files, ScheduleOrder::SmallestFirst);
+        assert_eq!(names(&files), ["a.ba2", "c.ba2", "b.ba2"]);
+
+        let mut files = listed;
+        schedule(&mut files, ScheduleOrder::AsListed);
+        assert_eq!(names(&files), ["a.ba2", "b.ba2", "c.ba2"]);
+    }
+
+    #[test]
+    fn test_interleave_by_mod() {
+        let entry = |path: &str| {
+            FileEntry::new(
+                path.to_string(),
+                1,
+                1,
+                String::new(),
+                PathBuf::from(path),
+            )
+        };
+        let files = vec![
+            entry("A/1.ba2"),
+            entry("A/2.ba2"),
+            entry("A/3.ba2"),
+            entry("B/1.ba2"),
+            entry("C/1.ba2"),
+            entry("C/2.ba2"),
+        ];
+
+        let names: Vec<String> = interleave_by_mod(files)
+            .into_iter()
+            .map(|f| f.file_name)
+            .collect();
+        assert_eq!(
+            names,
+            ["A/1.ba2", "B/1.ba2", "C/1.ba2", "A/2.ba2", "C/2.ba2", "A/3.ba2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_ba2_file_not_found() {
+        let result = extract_ba2_file(
+            Path::new("/nonexistent/file.ba2"),
+            None,
+            Path::new("/fake/bsarch.exe"),
+        )
+        .await;
+
+        assert!(result.is_err());
+        // Should fail with ExtractionFailed error since file doesn't exist
+        match result {
+            Err(crate::error::Error::BA2(BA2Error::ExtractionFailed { .. })) => {
+                // Expected error type
+            }
+            _ => panic!("Expected BA2Error::ExtractionFailed error"),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Locale-aware formatting for sizes, counts, rates and durations
+//!
+//! All user-facing numbers go through a process-wide [`NumberFormat`] that is
+//! configured from `AppearanceConfig` (language and size units), so the file
+//! table, progress text and status messages always agree with each other.
+
+use crate::config::{AppearanceConfig, SizeUnits};
+use parking_lot::RwLock;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+/// Active number format used by the free formatting functions
+static CURRENT_FORMAT: LazyLock<RwLock<NumberFormat>> =
+    LazyLock::new(|| RwLock::new(NumberFormat::default()));
+
+/// Binary (IEC) size unit labels, powers of 1024
+const BINARY_UNITS: [&str; 6] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB"];
+
+/// Decimal (SI) size unit labels, powers of 1000
+const DECIMAL_UNITS: [&str; 6] = ["B", "kB", "MB", "GB", "TB", "PB"];
+
+/// Number formatting rules for a locale
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Character placed between the integer and fractional parts
+    pub decimal_separator: char,
+
+    /// Character placed between groups of three integer digits
+    pub group_separator: char,
+
+    /// Unit system used for sizes
+    pub units: SizeUnits,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            group_separator: ',',
+            units: SizeUnits::default(),
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Build the format for a locale tag such as "en", "de-DE" or "fr_FR.UTF-8"
+    ///
+    /// Unknown locales fall back to English conventions.
+    pub fn for_locale(locale: &str, units: SizeUnits) -> Self {
+        let language = locale
+            .split(['-', '_', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        let (decimal_separator, group_separator) = match language.as_str() {
+            "de" | "nl" | "it" | "es" | "pt" | "id" | "tr" | "da" | "el" | "ro" | "hr" | "sl"
+            | "sr" => (',', '.'),
+            "fr" | "ru" | "pl" | "cs" | "sk" | "uk" | "fi" | "sv" | "nb" | "no" | "hu" | "bg"
+            | "lt" | "lv" | "et" => (',', '\u{a0}'),
+            _ => ('.', ','),
+        };
+
+        Self {
+            decimal_separator,
+            group_separator,
+            units,
+        }
+    }
+
+    /// Build the format from appearance settings
+    ///
+    /// A language of "auto" uses the system locale.
+    pub fn from_appearance(appearance: &AppearanceConfig) -> Self {
+        if appearance.language.eq_ignore_ascii_case("auto") {
+            Self::for_locale(&system_locale(), appearance.size_units)
+        } else {
+            Self::for_locale(&appearance.language, appearance.size_units)
+        }
+    }
+
+    /// Format an integer count with digit grouping (e.g., "12,345")
+    pub fn format_count(&self, count: u64) -> String {
+        self.group_digits(&count.to_string())
+    }
+
+    /// Format a decimal number with a fixed number of fractional digits
+    pub fn format_decimal(&self, value: f64, precision: usize) -> String {
+        let raw = format!("{:.precision$}", value.abs());
+        let (int_part, frac_part) = raw.split_once('.').unwrap_or((raw.as_str(), ""));
+
+        let mut out = String::with_capacity(raw.len() + 4);
+        if value < 0.0 {
+            out.push('-');
+        }
+        out.push_str(&self.group_digits(int_part));
+        if !frac_part.is_empty() {
+            out.push(self.decimal_separator);
+            out.push_str(frac_part);
+        }
+        out
+    }
+
+    /// Format a size in bytes using the configured unit system (e.g., "1.5 MiB")
+    #[allow(clippy::cast_precision_loss)] // Display only, precision loss is acceptable
+    pub fn format_size(&self, bytes: u64) -> String {
+        let (base, labels) = match self.units {
+            SizeUnits::Binary => (1024.0, &BINARY_UNITS),
+            SizeUnits::Decimal => (1000.0, &DECIMAL_UNITS),
+        };
+
+        let mut value = bytes as f64;
+        let mut unit = 0;
+        while value >= base && unit < labels.len() - 1 {
+            value /= base;
+            unit += 1;
+        }
+
+        if unit == 0 {
+            return format!("{} {}", self.format_count(bytes), labels[0]);
+        }
+
+        let number = self.format_decimal(value, 2);
+        let number = number
+            .trim_end_matches('0')
+            .trim_end_matches(self.decimal_separator);
+        format!("{number} {}", labels[unit])
+    }
+
+    /// Format a transfer speed in bytes per second (e.g., "12.5 MiB/s")
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Speeds are non-negative
+    pub fn format_speed(&self, bytes_per_sec: f64) -> String {
+        format!("{}/s", self.format_size(bytes_per_sec.max(0.0) as u64))
+    }
+
+    /// Format a rate with one fractional digit and a unit suffix (e.g., "2.5 files/s")
+    pub fn format_rate(&self, value: f64, unit: &str) -> String {
+        format!("{} {unit}", self.format_decimal(value, 1))
+    }
+
+    /// Insert group separators into a string of ASCII digits
+    fn group_digits(&self, digits: &str) -> String {
+        let len = digits.len();
+        let mut out = String::with_capacity(len + len / 3);
+        for (i, ch) in digits.chars().enumerate() {
+            if i > 0 && (len - i).is_multiple_of(3) {
+                out.push(self.group_separator);
+            }
+            out.push(ch);
+        }
+        out
+    }
+}
+
+/// Detect the system locale from the environment
+///
+/// Checks `LC_ALL`, `LC_NUMERIC` and `LANG` in that order, falling back to "en".
+pub fn system_locale() -> String {
+    ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+        .unwrap_or_else(|| "en".to_string())
+}
+
+/// Replace the process-wide number format
+pub fn set_number_format(format: NumberFormat) {
+    *CURRENT_FORMAT.write() = format;
+}
+
+/// Get the process-wide number format
+pub fn number_format() -> NumberFormat {
+    *CURRENT_FORMAT.read()
+}
+
+/// Format a size in bytes to human-readable form using the active locale and units
+pub fn format_size(bytes: u64) -> String {
+    number_format().format_size(bytes)
+}
+
+/// Format a count with digit grouping using the active locale
+pub fn format_count(count: u64) -> String {
+    number_format().format_count(count)
+}
+
+/// Format a duration in whole seconds compactly (e.g., "1h 5m", "3m 12s", "42s")
+pub fn format_duration(secs: u64) -> String {
+    let hours = secs / 3600;
+    let mins = (secs % 3600) / 60;
+    let secs = secs % 60;
+
+    if hours > 0 {
+        format!("{hours}h {mins}m")
+    } else if mins > 0 {
+        format!("{mins}m {secs}s")
+    } else {
+        format!("{secs}s")
+    }
+}
+
+/// Format how long something took, with tenths of a second below ten seconds
+/// (e.g., "0.4s", "7.2s", "3m 12s")
+pub fn format_elapsed(elapsed: Duration) -> String {
+    if elapsed < Duration::from_secs(10) {
+        let secs = number_format().format_decimal(elapsed.as_secs_f64(), 1);
+        format!("{secs}s")
+    } else {
+        format_duration((elapsed + Duration::from_millis(500)).as_secs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_count_grouping() {
+        let en = NumberFormat::for_locale("en-US", SizeUnits::Binary);
+        assert_eq!(en.format_count(0), "0");
+        assert_eq!(en.format_count(999), "999");
+        assert_eq!(en.format_count(1_234_567), "1,234,567");
+
+        let de = NumberFormat::for_locale("de_DE.UTF-8", SizeUnits::Binary);
+        assert_eq!(de.format_count(1_234_567), "1.234.567");
+    }
+
+    #[test]
+    fn test_format_decimal_separator() {
+        let de = NumberFormat::for_locale("de", SizeUnits::Binary);
+        assert_eq!(de.format_decimal(1234.5, 1), "1.234,5");
+
+        let fr = NumberFormat::for_locale("fr-FR", SizeUnits::Binary);
+        assert_eq!(fr.format_decimal(1234.5, 2), "1\u{a0}234,50");
+    }
+
+    #[test]
+    fn test_format_size_units() {
+        let binary = NumberFormat::for_locale("en", SizeUnits::Binary);
+        assert_eq!(binary.format_size(512), "512 B");
+        assert_eq!(binary.format_size(1024), "1 KiB");
+        assert_eq!(binary.format_size(1536), "1.5 KiB");
+
+        let decimal = NumberFormat::for_locale("en", SizeUnits::Decimal);
+        assert_eq!(decimal.format_size(1000), "1 kB");
+        assert_eq!(decimal.format_size(2_500_000), "2.5 MB");
+    }
+
+    #[test]
+    fn test_format_size_localized() {
+        let de = NumberFormat::for_locale("de", SizeUnits::Decimal);
+        assert_eq!(de.format_size(1_250_000), "1,25 MB");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(192), "3m 12s");
+        assert_eq!(format_duration(3900), "1h 5m");
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(Duration::from_millis(420)), "0.4s");
+        assert_eq!(format_elapsed(Duration::from_millis(9_940)), "9.9s");
+        assert_eq!(format_elapsed(Duration::from_millis(12_600)), "13s");
+        assert_eq!(format_elapsed(Duration::from_secs(192)), "3m 12s");
+    }
+}
@@ -0,0 +1,170 @@
+//! Import of a previously exported extraction list
+//!
+//! Reads the JSON document written by [`super::export`] and matches its
+//! entries against the current scan, first by path relative to the scanned
+//! folder and then by content fingerprint (for renamed mod folders).
+
+use crate::error::{Error, Result, ValidationError};
+use crate::models::FileEntry;
+use crate::operations::export::{
+    EXPORT_FORMAT_VERSION, ExportDocument, ExportedEntry, quick_fingerprint,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// Outcome of matching an imported list against the current scan
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    /// Paths of scanned archives that matched an imported entry
+    pub matched: Vec<PathBuf>,
+
+    /// Imported entries with no counterpart in the current scan
+    pub unmatched: Vec<ExportedEntry>,
+}
+
+impl ImportReport {
+    /// Total number of entries in the imported list
+    pub const fn total(&self) -> usize {
+        self.matched.len() + self.unmatched.len()
+    }
+}
+
+/// Load an exported JSON document from disk
+pub fn load_export_document(path: &Path) -> Result<ExportDocument> {
+    let content = fs::read_to_string(path)?;
+    let document: ExportDocument = serde_json::from_str(&content).map_err(|e| {
+        Error::from(ValidationError::InvalidInput(format!(
+            "{} is not an exported archive list: {e}",
+            path.display()
+        )))
+    })?;
+
+    if document.version > EXPORT_FORMAT_VERSION {
+        return Err(ValidationError::InvalidInput(format!(
+            "Archive list version {} is newer than supported version {EXPORT_FORMAT_VERSION}",
+            document.version
+        ))
+        .into());
+    }
+
+    info!(
+        "Loaded archive list with {} entries from {}",
+        document.entries.len(),
+        path.display()
+    );
+    Ok(document)
+}
+
+/// Match imported entries against scanned entries
+pub fn match_entries(document: &ExportDocument, entries: &[FileEntry]) -> ImportReport {
+    let by_relative_path: HashMap<String, &FileEntry> = entries
+        .iter()
+        .map(|e| (relative_key(&e.dir_name, &e.file_name), e))
+        .collect();
+
+    // Fingerprints are computed lazily, only for archives with a candidate size
+    let mut fingerprints: HashMap<&Path, Option<String>> = HashMap::new();
+    let mut report = ImportReport::default();
+
+    for imported in &document.entries {
+        let key = imported.relative_path.replace('\\', "/").to_lowercase();
+        if let Some(entry) = by_relative_path.get(&key) {
+            report.matched.push(entry.full_path.clone());
+            continue;
+        }
+
+        let by_fingerprint = imported.fingerprint.as_ref().and_then(|wanted| {
+            entries
+                .iter()
+                .filter(|e| e.file_size == imported.size)
+                .find(|e| {
+                    fingerprints
+                        .entry(e.full_path.as_path())
+                        .or_insert_with(|| quick_fingerprint(&e.full_path, e.file_size))
+                        .as_ref()
+                        == Some(wanted)
+                })
+        });
+
+        if let Some(entry) = by_fingerprint {
+            debug!(
+                "Matched {} by fingerprint to {}",
+                imported.relative_path,
+                entry.full_path.display()
+            );
+            report.matched.push(entry.full_path.clone());
+        } else {
+            report.unmatched.push(imported.clone());
+        }
+    }
+
+    report.matched.sort();
+    report.matched.dedup();
+    report
+}
+
+/// Normalized lookup key for a mod-relative archive path
+fn relative_key(dir_name: &str, file_name: &str) -> String {
+    format!("{dir_name}/{file_name}").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::export::entries_to_json;
+    use tempfile::TempDir;
+
+    fn entry_at(root: &Path, mod_name: &str, name: &str, content: &[u8]) -> FileEntry {
+        let dir = root.join(mod_name);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        FileEntry::new(
+            name.to_string(),
+            content.len() as u64,
+            1,
+            mod_name.to_string(),
+            path,
+        )
+    }
+
+    #[test]
+    fn test_match_by_relative_path_and_fingerprint() {
+        let source = TempDir::new().unwrap();
+        let exported = vec![
+            entry_at(source.path(), "ModA", "A - Main.ba2", b"archive-a"),
+            entry_at(source.path(), "ModB", "B - Main.ba2", b"archive-b"),
+            entry_at(source.path(), "ModC", "C - Main.ba2", b"archive-c"),
+        ];
+        let json = entries_to_json(&exported, &source.path().to_string_lossy()).unwrap();
+        let document: ExportDocument = serde_json::from_str(&json).unwrap();
+
+        // Target machine: ModA identical, ModB folder renamed, ModC missing
+        let target = TempDir::new().unwrap();
+        let scanned = vec![
+            entry_at(target.path(), "moda", "A - Main.ba2", b"archive-a"),
+            entry_at(target.path(), "ModB v2", "B - Main.ba2", b"archive-b"),
+        ];
+
+        let report = match_entries(&document, &scanned);
+        assert_eq!(report.matched.len(), 2);
+        assert_eq!(report.unmatched.len(), 1);
+        assert_eq!(report.unmatched[0].relative_path, "ModC/C - Main.ba2");
+        assert_eq!(report.total(), 3);
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_documents() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let not_a_list = temp_dir.path().join("bad.json");
+        fs::write(&not_a_list, "{\"hello\": 1}").unwrap();
+        assert!(load_export_document(&not_a_list).is_err());
+
+        let future = temp_dir.path().join("future.json");
+        fs::write(&future, "{\"version\": 99, \"source_folder\": \"\", \"entries\": []}").unwrap();
+        assert!(load_export_document(&future).is_err());
+    }
+}
@@ -0,0 +1,595 @@
+//! Journal of undoable file operations
+//!
+//! Destructive operations (cleaning an extraction, quarantining an archive)
+//! run through an [`Operation`] that records the inverse of every change:
+//!
+//! - Removed files are moved into the journal folder instead of being deleted
+//! - Moved files are recorded with their original location
+//! - Created files are recorded so undoing removes them again
+//! - Removed empty folders are recorded so undoing creates them again
+//!
+//! Committed operations are kept in `journal.json`; the last
+//! [`MAX_JOURNAL_ENTRIES`] of them can be undone, newest first. Older
+//! operations are dropped together with the files they kept.
+//!
+//! Committed and undone operations are also written to the [audit
+//! log](crate::operations::audit), which keeps them after they leave the
+//! journal.
+//!
+//! A move to another volume takes two steps, copying and removing the
+//! original. When only the first one succeeds, the move fails with
+//! [`Error::PartialMove`] telling where the file is now, and
+//! [`finish_partial_move`] or [`revert_partial_move`] settle it later.
+
+use crate::error::{BackupOutcome, Error, Result, ValidationError};
+use crate::operations::audit::{AuditAction, AuditEntry, AuditLog};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of operations that can be undone
+pub const MAX_JOURNAL_ENTRIES: usize = 20;
+
+/// Name of the journal file in the journal folder
+const JOURNAL_FILE: &str = "journal.json";
+
+/// A change recorded by an operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum JournalAction {
+    /// A file was moved (removed files are moved into the journal folder)
+    Moved {
+        /// Original location
+        from: PathBuf,
+        /// Current location
+        to: PathBuf,
+    },
+    /// A file was created
+    Created {
+        /// Location of the new file
+        path: PathBuf,
+    },
+    /// An empty folder was removed
+    RemovedFolder {
+        /// Location of the folder
+        path: PathBuf,
+    },
+}
+
+/// A committed operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Unique ID, also the name of the folder keeping its removed files
+    pub id: u64,
+
+    /// What the operation did (e.g., "Clean extraction of Mod - Main.ba2")
+    pub description: String,
+
+    /// Time of the operation (seconds since the Unix epoch)
+    pub performed_at: u64,
+
+    /// Recorded changes, in the order they were made
+    pub actions: Vec<JournalAction>,
+}
+
+/// An operation in progress, recording its changes
+#[derive(Debug)]
+pub struct Operation {
+    id: u64,
+    description: String,
+    trash_dir: PathBuf,
+    actions: Vec<JournalAction>,
+}
+
+/// Keeps the journal of undoable operations
+#[derive(Debug, Clone)]
+pub struct OperationJournal {
+    root: PathBuf,
+    audit: Option<AuditLog>,
+}
+
+/// Get the default journal folder
+pub fn default_journal_root() -> Option<PathBuf> {
+    ProjectDirs::from("com", "evildarkarchon", "unpackrr")
+        .map(|dirs| dirs.data_local_dir().join("journal"))
+}
+
+impl Operation {
+    /// Remove a file, keeping it in the journal folder so it can be restored
+    pub fn remove_file(&mut self, path: &Path) -> Result<()> {
+        fs::create_dir_all(&self.trash_dir)?;
+        let name = path
+            .file_name()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy();
+        let target = self
+            .trash_dir
+            .join(format!("{}-{name}", self.actions.len()));
+        if let Err(e) = move_file(path, &target) {
+            // A kept copy is of no use while the file is still in place
+            if let Error::PartialMove { outcome, .. } = &e
+                && revert_partial_move(path, &target).is_ok()
+            {
+                return Err(Error::Other(format!(
+                    "Could not remove {}: {}",
+                    path.display(),
+                    outcome.reason()
+                )));
+            }
+            return Err(e);
+        }
+        self.actions.push(JournalAction::Moved {
+            from: path.to_path_buf(),
+            to: target,
+        });
+        Ok(())
+    }
+
+    /// Move a file
+    pub fn move_file(&mut self, from: &Path, to: &Path) -> Result<()> {
+        move_file(from, to)?;
+        self.actions.push(JournalAction::Moved {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    /// Finish a move that stopped after copying, see [`finish_partial_move`]
+    pub fn finish_move(&mut self, from: &Path, to: &Path) -> Result<()> {
+        finish_partial_move(from, to)?;
+        self.actions.push(JournalAction::Moved {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    /// Record a file the operation created
+    pub fn record_created(&mut self, path: &Path) {
+        self.actions.push(JournalAction::Created {
+            path: path.to_path_buf(),
+        });
+    }
+
+    /// Remove an empty folder, recording it so undoing creates it again
+    pub fn remove_folder(&mut self, path: &Path) -> Result<()> {
+        fs::remove_dir(path)?;
+        self.actions.push(JournalAction::RemovedFolder {
+            path: path.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    /// Whether the operation changed anything yet
+    pub const fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+}
+
+impl OperationJournal {
+    /// Create a journal kept in the folder `root`
+    pub const fn new(root: PathBuf) -> Self {
+        Self { root, audit: None }
+    }
+
+    /// Open the journal in the default folder, auditing to the default audit log
+    pub fn open_default() -> Option<Self> {
+        default_journal_root().map(|root| Self {
+            root,
+            audit: AuditLog::open_default(),
+        })
+    }
+
+    /// Write committed and undone operations to `audit`
+    #[must_use]
+    pub fn with_audit(mut self, audit: AuditLog) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Start recording an operation
+    pub fn begin(&self, description: impl Into<String>) -> Operation {
+        let id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX));
+        Operation {
+            id,
+            description: description.into(),
+            trash_dir: self.root.join(id.to_string()),
+            actions: Vec::new(),
+        }
+    }
+
+    /// Load the committed operations, oldest first
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        let path = self.root.join(JOURNAL_FILE);
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Vec::new();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable journal {}: {}", path.display(), e);
+            Vec::new()
+        })
+    }
+
+    /// Get the operation [`undo_last`](Self::undo_last) would undo
+    pub fn last(&self) -> Option<JournalEntry> {
+        self.entries().pop()
+    }
+
+    /// Add a finished operation to the journal
+    ///
+    /// Operations that changed nothing are not recorded. The oldest
+    /// operations beyond [`MAX_JOURNAL_ENTRIES`] are dropped.
+    pub fn commit(&self, operation: Operation) -> Result<()> {
+        if operation.is_empty() {
+            return Ok(());
+        }
+        if let Some(audit) = &self.audit {
+            audit.record_or_warn(
+                &AuditEntry::new(AuditAction::Operation, operation.description.as_str())
+                    .with_detail(format!("{} files", operation.actions.len())),
+            );
+        }
+        let mut entries = self.entries();
+        entries.push(JournalEntry {
+            id: operation.id,
+            description: operation.description,
+            performed_at: operation.id / 1_000_000_000,
+            actions: operation.actions,
+        });
+        let excess = entries.len().saturating_sub(MAX_JOURNAL_ENTRIES);
+        for dropped in entries.drain(..excess) {
+            self.remove_trash(dropped.id);
+        }
+        self.save(&entries)
+    }
+
+    /// Undo the most recent operation
+    ///
+    /// Fails without changing anything if a file to restore is gone or its
+    /// original location is taken again. Returns `None` if there is nothing
+    /// to undo.
+    pub fn undo_last(&self) -> Result<Option<JournalEntry>> {
+        let description = self.last().map(|entry| entry.description);
+        let result = self.undo();
+        if let (Some(audit), Some(description)) = (&self.audit, description) {
+            let entry = AuditEntry::new(AuditAction::Undone, description);
+            audit.record_or_warn(&match &result {
+                Ok(_) => entry,
+                Err(e) => entry.failed(e.to_string()),
+            });
+        }
+        result
+    }
+
+    /// Undo the most recent operation, see [`undo_last`](Self::undo_last)
+    fn undo(&self) -> Result<Option<JournalEntry>> {
+        let mut entries = self.entries();
+        let Some(entry) = entries.pop() else {
+            return Ok(None);
+        };
+
+        for action in &entry.actions {
+            if let JournalAction::Moved { from, to } = action {
+                let conflict = if !to.exists() {
+                    Some(format!("{} no longer exists", to.display()))
+                } else if from.exists() {
+                    Some(format!("{} already exists", from.display()))
+                } else {
+                    None
+                };
+                if let Some(reason) = conflict {
+                    return Err(Error::from(ValidationError::InvalidInput(format!(
+                        "Cannot undo \"{}\": {reason}",
+                        entry.description
+                    ))));
+                }
+            }
+        }
+
+        for action in entry.actions.iter().rev() {
+            match action {
+                JournalAction::Moved { from, to } => {
+                    if let Some(parent) = from.parent() {
+                        fs::create_dir_all(parent)?;
+                    }
+                    move_file(to, from)?;
+                }
+                JournalAction::Created { path } => {
+                    if path.exists() {
+                        fs::remove_file(path)?;
+                    }
+                }
+                JournalAction::RemovedFolder { path } => fs::create_dir_all(path)?,
+            }
+        }
+
+        self.save(&entries)?;
+        self.remove_trash(entry.id);
+        tracing::info!("Undid \"{}\"", entry.description);
+        Ok(Some(entry))
+    }
+
+    /// Write the journal file
+    fn save(&self, entries: &[JournalEntry]) -> Result<()> {
+        fs::create_dir_all(&self.root)?;
+        let content = serde_json::to_string_pretty(entries)
+            .map_err(|e| Error::Other(format!("Failed to serialize journal: {e}")))?;
+        fs::write(self.root.join(JOURNAL_FILE), content)?;
+        Ok(())
+    }
+
+    /// Delete the removed files kept for an operation
+    fn remove_trash(&self, id: u64) {
+        let dir = self.root.join(id.to_string());
+        if dir.exists()
+            && let Err(e) = fs::remove_dir_all(&dir)
+        {
+            tracing::warn!("Failed to remove {}: {}", dir.display(), e);
+        }
+    }
+}
+
+/// Move a file, copying it when the target is on another volume
+///
+/// Fails with [`Error::PartialMove`] if the file was copied but the original
+/// could not be removed, or if an incomplete copy was left behind.
+pub(crate) fn move_file(from: &Path, to: &Path) -> Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_and_remove(from, to)
+}
+
+/// The two steps of a move to another volume
+///
+/// The copy is written next to the target and only renamed to the target's
+/// name once complete, so the target never holds half a file.
+fn copy_and_remove(from: &Path, to: &Path) -> Result<()> {
+    let partial = partial_path(to);
+    if let Err(e) = fs::copy(from, &partial) {
+        // The move changed nothing once the incomplete copy is gone
+        if !partial.exists() || fs::remove_file(&partial).is_ok() {
+            return Err(e.into());
+        }
+        return Err(partial_move(
+            from,
+            &partial,
+            BackupOutcome::PartialCopyLeft {
+                reason: e.to_string(),
+            },
+        ));
+    }
+    if let Err(e) = fs::rename(&partial, to) {
+        if fs::remove_file(&partial).is_ok() {
+            return Err(e.into());
+        }
+        return Err(partial_move(
+            from,
+            &partial,
+            BackupOutcome::PartialCopyLeft {
+                reason: e.to_string(),
+            },
+        ));
+    }
+    fs::remove_file(from).map_err(|e| {
+        partial_move(
+            from,
+            to,
+            BackupOutcome::CopiedNotRemoved {
+                reason: e.to_string(),
+            },
+        )
+    })
+}
+
+/// Sibling of `to` that a copy is written to before it takes its place
+fn partial_path(to: &Path) -> PathBuf {
+    let mut name = to.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    to.with_file_name(name)
+}
+
+fn partial_move(from: &Path, to: &Path, outcome: BackupOutcome) -> Error {
+    tracing::warn!(
+        "Moving {} to {} stopped partway: {}",
+        from.display(),
+        to.display(),
+        outcome
+    );
+    Error::PartialMove {
+        from: from.to_path_buf(),
+        to: to.to_path_buf(),
+        outcome,
+    }
+}
+
+/// Finish a move that stopped after copying by removing the original
+///
+/// The copy at `to` must be complete, i.e. as large as the original.
+pub fn finish_partial_move(from: &Path, to: &Path) -> Result<()> {
+    let original = fs::metadata(from)?.len();
+    let copied = fs::metadata(to).map_or(None, |m| Some(m.len()));
+    if copied != Some(original) {
+        return Err(ValidationError::InvalidInput(format!(
+            "{} is not a complete copy of {}",
+            to.display(),
+            from.display()
+        ))
+        .into());
+    }
+    fs::remove_file(from)?;
+    Ok(())
+}
+
+/// Undo a move that stopped partway by removing the copy at `to`
+///
+/// The original at `from` must still exist, so the file is never lost.
+pub fn revert_partial_move(from: &Path, to: &Path) -> Result<()> {
+    if !from.is_file() {
+        return Err(ValidationError::PathNotFound(from.to_path_buf()).into());
+    }
+    match fs::remove_file(to) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_copy_and_remove_replaces_target_once_complete() {
+        let temp_dir = TempDir::new().unwrap();
+        let from = temp_dir.path().join("Mod - Main.ba2");
+        let to = temp_dir.path().join("quarantine").join("Mod - Main.ba2");
+        fs::create_dir_all(to.parent().unwrap()).unwrap();
+        fs::write(&from, b"archive").unwrap();
+        fs::write(&to, b"older copy").unwrap();
+
+        copy_and_remove(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(fs::read(&to).unwrap(), b"archive");
+        assert!(!partial_path(&to).exists());
+
+        // A failed copy leaves the target as it was
+        assert!(copy_and_remove(&from, &to).is_err());
+        assert_eq!(fs::read(&to).unwrap(), b"archive");
+        assert!(!partial_path(&to).exists());
+    }
+
+    #[test]
+    fn test_undo_restores_removed_and_moved_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal = OperationJournal::new(temp_dir.path().join("journal"));
+        let mods = temp_dir.path().join("mods");
+        fs::create_dir_all(mods.join("meshes")).unwrap();
+        fs::create_dir_all(mods.join("textures")).unwrap();
+        let loose = mods.join("meshes/a.nif");
+        let archive = mods.join("Mod - Main.ba2");
+        let created = mods.join("Restored - Main.ba2");
+        fs::write(&loose, b"mesh").unwrap();
+        fs::write(&archive, b"archive").unwrap();
+
+        let mut operation = journal.begin("Clean up");
+        operation.remove_file(&loose).unwrap();
+        operation
+            .move_file(&archive, &temp_dir.path().join("moved.ba2"))
+            .unwrap();
+        fs::write(&created, b"copy").unwrap();
+        operation.record_created(&created);
+        operation.remove_folder(&mods.join("textures")).unwrap();
+        journal.commit(operation).unwrap();
+        assert!(!loose.exists() && !archive.exists());
+        assert!(!mods.join("textures").exists());
+        assert_eq!(journal.last().unwrap().description, "Clean up");
+
+        let undone = journal.undo_last().unwrap().unwrap();
+        assert_eq!(undone.actions.len(), 4);
+        assert_eq!(fs::read(&loose).unwrap(), b"mesh");
+        assert!(mods.join("textures").is_dir());
+        assert_eq!(fs::read(&archive).unwrap(), b"archive");
+        assert!(!created.exists());
+        assert!(journal.entries().is_empty());
+        assert!(journal.undo_last().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_undo_refuses_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let audit = AuditLog::new(temp_dir.path().join("audit.jsonl"));
+        let journal =
+            OperationJournal::new(temp_dir.path().join("journal")).with_audit(audit.clone());
+        let file = temp_dir.path().join("a.txt");
+        fs::write(&file, b"old").unwrap();
+
+        let mut operation = journal.begin("Remove a.txt");
+        operation.remove_file(&file).unwrap();
+        journal.commit(operation).unwrap();
+
+        // The original location is taken again: nothing is overwritten
+        fs::write(&file, b"new").unwrap();
+        assert!(journal.undo_last().is_err());
+        assert_eq!(fs::read(&file).unwrap(), b"new");
+        assert_eq!(journal.entries().len(), 1);
+
+        // Both the operation and the failed undo are audited
+        let audited = audit.entries();
+        assert_eq!(audited.len(), 2);
+        assert_eq!(audited[0].summary(), "Changed Remove a.txt: 1 files");
+        assert_eq!(audited[1].action, AuditAction::Undone);
+        assert!(matches!(
+            audited[1].outcome,
+            crate::operations::audit::AuditOutcome::Failed { .. }
+        ));
+    }
+
+    #[test]
+    fn test_copy_and_remove() {
+        let temp_dir = TempDir::new().unwrap();
+        let from = temp_dir.path().join("Mod - Main.ba2");
+        let to = temp_dir.path().join("copy.ba2");
+        fs::write(&from, b"archive").unwrap();
+
+        copy_and_remove(&from, &to).unwrap();
+        assert!(!from.exists());
+        assert_eq!(fs::read(&to).unwrap(), b"archive");
+
+        // A missing original leaves nothing behind
+        assert!(matches!(copy_and_remove(&from, &to), Err(Error::IO(_))));
+        assert!(to.exists());
+    }
+
+    #[test]
+    fn test_settle_partial_move() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal = OperationJournal::new(temp_dir.path().join("journal"));
+        let from = temp_dir.path().join("Mod - Main.ba2");
+        let to = temp_dir.path().join("copy.ba2");
+        fs::write(&from, b"archive").unwrap();
+
+        // An incomplete copy is not finished, but can be reverted
+        fs::write(&to, b"arch").unwrap();
+        assert!(finish_partial_move(&from, &to).is_err());
+        assert!(from.exists());
+        revert_partial_move(&from, &to).unwrap();
+        assert!(from.exists() && !to.exists());
+
+        // A complete copy is finished and the move can be undone
+        fs::copy(&from, &to).unwrap();
+        let mut operation = journal.begin("Move");
+        operation.finish_move(&from, &to).unwrap();
+        journal.commit(operation).unwrap();
+        assert!(!from.exists());
+        journal.undo_last().unwrap();
+        assert_eq!(fs::read(&from).unwrap(), b"archive");
+
+        // Without the original the copy is the only one left and stays
+        fs::rename(&from, &to).unwrap();
+        assert!(revert_partial_move(&from, &to).is_err());
+        assert!(to.exists());
+    }
+
+    #[test]
+    fn test_commit_keeps_recent_operations() {
+        let temp_dir = TempDir::new().unwrap();
+        let journal = OperationJournal::new(temp_dir.path().join("journal"));
+        journal.commit(journal.begin("Nothing")).unwrap();
+        assert!(journal.entries().is_empty());
+
+        for i in 0..=MAX_JOURNAL_ENTRIES {
+            let mut operation = journal.begin(format!("Operation {i}"));
+            operation.record_created(&temp_dir.path().join(format!("{i}.txt")));
+            journal.commit(operation).unwrap();
+        }
+        let entries = journal.entries();
+        assert_eq!(entries.len(), MAX_JOURNAL_ENTRIES);
+        assert_eq!(entries[0].description, "Operation 1");
+    }
+}
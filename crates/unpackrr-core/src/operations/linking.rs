@@ -0,0 +1,230 @@
+//! Linking extracted files from a shared cache
+//!
+//! The same archives are often installed in several mod manager profiles or
+//! instances. Instead of unpacking each copy, archives can be extracted once
+//! into a central cache and the files hardlinked or symlinked into every mod
+//! folder, so repeated extractions cost no extra disk space.
+//!
+//! Cache entries are keyed by archive name, size and a hash of the archive's
+//! first and last bytes, and are only reused once marked complete.
+
+use crate::error::Result;
+use crate::platform::elevation::is_elevated;
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Marker file written into a cache entry once it is fully extracted
+pub const COMPLETE_MARKER: &str = ".unpackrr-complete";
+
+/// Number of bytes hashed at the start and end of an archive for its cache key
+const KEY_SAMPLE_SIZE: u64 = 64 * 1024;
+
+/// How extracted files are placed into the mod folder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkMode {
+    /// Extract directly into the mod folder
+    #[default]
+    Off,
+    /// Hardlink files from the cache (falls back to copying across volumes)
+    Hardlink,
+    /// Symlink files from the cache
+    Symlink,
+}
+
+impl LinkMode {
+    /// Whether extraction goes through the cache
+    pub const fn uses_cache(self) -> bool {
+        !matches!(self, Self::Off)
+    }
+}
+
+/// Result of linking a cache entry into a mod folder
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinkReport {
+    /// Files linked from the cache
+    pub linked: usize,
+    /// Files copied because they could not be hardlinked
+    pub copied: usize,
+}
+
+/// Get the default cache folder for extracted archives
+pub fn default_cache_root() -> Option<PathBuf> {
+    ProjectDirs::from("com", "evildarkarchon", "unpackrr")
+        .map(|dirs| dirs.cache_dir().join("extracted"))
+}
+
+/// Check whether this process may create symbolic links
+///
+/// Windows only allows it to elevated processes or with Developer Mode on.
+#[allow(clippy::missing_const_for_fn)] // the checks are only const outside Windows
+pub fn symlinks_supported() -> bool {
+    !cfg!(windows) || is_elevated() || crate::platform::developer_mode_enabled()
+}
+
+/// Get the cache folder for an archive below `cache_root`
+pub fn cache_entry(cache_root: &Path, archive: &Path) -> Result<PathBuf> {
+    Ok(cache_root.join(cache_key(archive)?))
+}
+
+/// Whether a cache entry holds a complete extraction
+pub fn is_cached(entry: &Path) -> bool {
+    entry.join(COMPLETE_MARKER).is_file()
+}
+
+/// Mark a cache entry as complete so later runs reuse it
+pub fn mark_complete(entry: &Path) -> Result<()> {
+    fs::write(entry.join(COMPLETE_MARKER), b"")?;
+    Ok(())
+}
+
+/// Link every file of a cache entry into `destination`
+///
+/// Existing files in the destination are replaced. Hardlinks that fail (e.g.,
+/// because the cache is on another volume) fall back to copying.
+pub fn link_tree(entry: &Path, destination: &Path, mode: LinkMode) -> Result<LinkReport> {
+    let mut report = LinkReport::default();
+    link_dir(entry, destination, mode, &mut report)?;
+    tracing::debug!(
+        "Linked {} and copied {} files from {} into {}",
+        report.linked,
+        report.copied,
+        entry.display(),
+        destination.display()
+    );
+    Ok(report)
+}
+
+fn link_dir(
+    source: &Path,
+    destination: &Path,
+    mode: LinkMode,
+    report: &mut LinkReport,
+) -> Result<()> {
+    fs::create_dir_all(destination)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        if entry.file_name() == COMPLETE_MARKER {
+            continue;
+        }
+        let target = destination.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            link_dir(&entry.path(), &target, mode, report)?;
+            continue;
+        }
+
+        if fs::symlink_metadata(&target).is_ok() {
+            fs::remove_file(&target)?;
+        }
+        match mode {
+            LinkMode::Off => {
+                fs::copy(entry.path(), &target)?;
+                report.copied += 1;
+            }
+            LinkMode::Hardlink => {
+                if let Err(e) = fs::hard_link(entry.path(), &target) {
+                    tracing::debug!("Hardlink to {} failed ({}), copying", target.display(), e);
+                    fs::copy(entry.path(), &target)?;
+                    report.copied += 1;
+                } else {
+                    report.linked += 1;
+                }
+            }
+            LinkMode::Symlink => {
+                symlink_file(&entry.path(), &target)?;
+                report.linked += 1;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn symlink_file(original: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(original, link)
+}
+
+#[cfg(unix)]
+fn symlink_file(original: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(original, link)
+}
+
+#[cfg(not(any(windows, unix)))]
+fn symlink_file(_original: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symbolic links are not supported on this platform",
+    ))
+}
+
+/// Build the cache key of an archive
+///
+/// Hashing only the ends keeps keying multi-gigabyte archives cheap; together
+/// with the size this tells different releases of the same archive apart.
+fn cache_key(archive: &Path) -> Result<String> {
+    let mut file = File::open(archive)?;
+    let size = file.metadata()?.len();
+
+    let mut hasher = Sha256::new();
+    let mut buffer = Vec::new();
+    (&mut file).take(KEY_SAMPLE_SIZE).read_to_end(&mut buffer)?;
+    if size > KEY_SAMPLE_SIZE {
+        // The tail, without hashing bytes of the head twice
+        file.seek(SeekFrom::Start((size - KEY_SAMPLE_SIZE).max(KEY_SAMPLE_SIZE)))?;
+        file.read_to_end(&mut buffer)?;
+    }
+    hasher.update(&buffer);
+
+    let stem = archive
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let mut key = format!("{stem}-{size:x}-");
+    for byte in &hasher.finalize()[..8] {
+        let _ = write!(key, "{byte:02x}");
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_key_changes_with_contents() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("Mod - Main.ba2");
+
+        fs::write(&archive, b"BTDX first").unwrap();
+        let first = cache_key(&archive).unwrap();
+        assert!(first.starts_with("mod - main-a-"));
+        assert_eq!(first, cache_key(&archive).unwrap());
+
+        fs::write(&archive, b"BTDX other").unwrap();
+        assert_ne!(first, cache_key(&archive).unwrap());
+    }
+
+    #[test]
+    fn test_hardlink_tree() {
+        let temp_dir = TempDir::new().unwrap();
+        let entry = temp_dir.path().join("cache/entry");
+        let mod_dir = temp_dir.path().join("mod");
+        fs::create_dir_all(entry.join("meshes")).unwrap();
+        fs::write(entry.join("meshes/a.nif"), "nif").unwrap();
+        mark_complete(&entry).unwrap();
+        fs::create_dir_all(mod_dir.join("meshes")).unwrap();
+        fs::write(mod_dir.join("meshes/a.nif"), "stale").unwrap();
+
+        assert!(is_cached(&entry));
+        let report = link_tree(&entry, &mod_dir, LinkMode::Hardlink).unwrap();
+        assert_eq!(report.linked + report.copied, 1);
+        assert_eq!(fs::read_to_string(mod_dir.join("meshes/a.nif")).unwrap(), "nif");
+        assert!(!mod_dir.join(COMPLETE_MARKER).exists());
+    }
+}
@@ -0,0 +1,201 @@
+//! Plugin load order
+//!
+//! The game only loads the archives of active plugins, named after the
+//! plugin they belong to ("Plugin - Main.ba2", "Plugin - Textures.ba2").
+//! Reading the game's `plugins.txt` (or a plain `loadorder.txt`) gives each
+//! scanned archive the load order index of its plugin, and tells which
+//! archives never load because their plugin is disabled.
+//!
+//! The base game masters are always loaded first and are not listed in
+//! `plugins.txt`, so they are placed in front of the listed plugins. Which
+//! masters these are and where `plugins.txt` lives depends on the game
+//! ([`GameProfile`]).
+
+use crate::config::profile::GameProfile;
+use crate::error::{Error, Result, ValidationError};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Plugin file extensions
+const PLUGIN_EXTENSIONS: &[&str] = &["esm", "esp", "esl"];
+
+/// Load order of the game's plugins
+#[derive(Debug, Clone, Default)]
+pub struct LoadOrder {
+    /// Lowercase stems of active plugins and their load order index
+    active: HashMap<String, usize>,
+    /// Lowercase stems of disabled plugins
+    disabled: HashSet<String>,
+}
+
+impl LoadOrder {
+    /// Default location of the game's `plugins.txt` (e.g. `%LOCALAPPDATA%\Fallout4`)
+    pub fn default_path(profile: &GameProfile) -> Option<PathBuf> {
+        profile.plugins_path()
+    }
+
+    /// Load a `plugins.txt` or `loadorder.txt` file of the game of `profile`
+    pub fn load(path: &Path, profile: &GameProfile) -> Result<Self> {
+        let bytes = fs::read(path)?;
+        let content = String::from_utf8_lossy(&bytes);
+        let order = Self::parse(&content, profile);
+        // The base game masters are always present, so check for anything beyond them
+        if order.active.len() + order.disabled.len() <= profile.masters.len() {
+            return Err(Error::from(ValidationError::InvalidInput(format!(
+                "{} does not list any plugins",
+                path.display()
+            ))));
+        }
+        tracing::info!(
+            "Loaded load order with {} active plugins from {}",
+            order.len(),
+            path.display()
+        );
+        Ok(order)
+    }
+
+    /// Parse the content of a `plugins.txt` or `loadorder.txt` file
+    ///
+    /// In `plugins.txt`, active plugins are marked with a leading `*`. A list
+    /// without any marker is a `loadorder.txt`, where every plugin is active.
+    pub fn parse(content: &str, profile: &GameProfile) -> Self {
+        let lines: Vec<&str> = content
+            .lines()
+            .map(|line| line.trim_start_matches('\u{feff}').trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .collect();
+        let has_markers = lines.iter().any(|line| line.starts_with('*'));
+
+        let mut order = Self::default();
+        for master in profile.masters {
+            order.push(master, true);
+        }
+        for line in lines {
+            let (name, enabled) = line
+                .strip_prefix('*')
+                .map_or((line, !has_markers), |name| (name, true));
+            if let Some(stem) = plugin_stem(name) {
+                order.push(stem, enabled);
+            }
+        }
+        order
+    }
+
+    /// Add a plugin at the end of the order, keeping the first occurrence
+    fn push(&mut self, stem: &str, enabled: bool) {
+        let key = stem.to_lowercase();
+        if self.active.contains_key(&key) || self.disabled.contains(&key) {
+            return;
+        }
+        if enabled {
+            self.active.insert(key, self.active.len());
+        } else {
+            self.disabled.insert(key);
+        }
+    }
+
+    /// Number of active plugins, including the base game masters
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Whether no plugin is active
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// Load order index of the plugin an archive belongs to, if it is active
+    pub fn index_of(&self, archive_name: &str) -> Option<usize> {
+        self.plugin_of(archive_name)
+            .and_then(|plugin| self.active.get(&plugin).copied())
+    }
+
+    /// Whether the game loads an archive
+    ///
+    /// Only archives of plugins listed as disabled are known not to load;
+    /// archives of unlisted plugins (e.g. Creation Club content) are assumed
+    /// to load.
+    pub fn loads(&self, archive_name: &str) -> bool {
+        self.plugin_of(archive_name)
+            .is_none_or(|plugin| !self.disabled.contains(&plugin))
+    }
+
+    /// Find the listed plugin of an archive ("Plugin - Main.ba2" -> "plugin")
+    fn plugin_of(&self, archive_name: &str) -> Option<String> {
+        let stem = Path::new(archive_name)
+            .file_stem()?
+            .to_str()?
+            .to_lowercase();
+        let is_listed =
+            |plugin: &str| self.active.contains_key(plugin) || self.disabled.contains(plugin);
+        // The plugin name itself may contain " - ", so try the longest prefix first
+        match stem.rsplit_once(" - ") {
+            Some((plugin, _)) if is_listed(plugin) => Some(plugin.to_string()),
+            _ => is_listed(&stem).then_some(stem),
+        }
+    }
+}
+
+/// Get the stem of a plugin file name ("Plugin.esp" -> "Plugin")
+fn plugin_stem(name: &str) -> Option<&str> {
+    let (stem, ext) = name.rsplit_once('.')?;
+    PLUGIN_EXTENSIONS
+        .iter()
+        .any(|known| ext.eq_ignore_ascii_case(known))
+        .then_some(stem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::profile::{FALLOUT4, STARFIELD};
+
+    const PLUGINS_TXT: &str = "# This file is used by Fallout4 to keep track of your downloaded content.\n\
+        *Unofficial Fallout 4 Patch.esp\n\
+        Disabled Mod.esp\n\
+        *Armor - Extended.esp\n\
+        *Weapons.esl\n";
+
+    #[test]
+    fn test_parse_plugins_txt() {
+        let order = LoadOrder::parse(PLUGINS_TXT, &FALLOUT4);
+        let masters = FALLOUT4.masters.len();
+        assert_eq!(order.len(), masters + 3);
+        assert_eq!(order.index_of("Fallout4 - Textures1.ba2"), Some(0));
+        assert_eq!(
+            order.index_of("unofficial fallout 4 patch - Main.BA2"),
+            Some(masters)
+        );
+        assert_eq!(
+            order.index_of("Armor - Extended - Textures.ba2"),
+            Some(masters + 1)
+        );
+        assert_eq!(order.index_of("Weapons - Main.ba2"), Some(masters + 2));
+        assert_eq!(order.index_of("Disabled Mod - Main.ba2"), None);
+        assert!(!order.loads("Disabled Mod - Main.ba2"));
+        assert!(order.loads("ccBGSFO4001-PipBoy(Black) - Main.ba2"));
+    }
+
+    #[test]
+    fn test_parse_loadorder_txt() {
+        let order = LoadOrder::parse("Fallout4.esm\nFirst.esp\r\nSecond.esm\n", &FALLOUT4);
+        assert_eq!(order.len(), FALLOUT4.masters.len() + 2);
+        assert_eq!(order.index_of("Fallout4 - Main.ba2"), Some(0));
+        assert!(order.index_of("First - Main.ba2") < order.index_of("Second - Main.ba2"));
+        assert!(order.loads("Second - Textures.ba2"));
+    }
+
+    #[test]
+    fn test_parse_starfield_plugins_txt() {
+        let order = LoadOrder::parse("*Outpost.esm\nDisabled.esp\n", &STARFIELD);
+        assert_eq!(order.len(), STARFIELD.masters.len() + 1);
+        assert_eq!(order.index_of("Starfield - Textures01.ba2"), Some(0));
+        assert_eq!(
+            order.index_of("Outpost - Main.ba2"),
+            Some(STARFIELD.masters.len())
+        );
+        assert_eq!(order.index_of("Fallout4 - Main.ba2"), None);
+        assert!(!order.loads("Disabled - Main.ba2"));
+    }
+}
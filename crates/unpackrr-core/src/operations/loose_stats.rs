@@ -0,0 +1,245 @@
+//! Loose files already in mod folders
+//!
+//! Mod managers such as MO2 list every loose file of every mod whenever the
+//! game starts, and mods with tens of thousands of files slow that down
+//! noticeably. Before an extraction the loose files already in each target
+//! folder are counted, so the run can warn when it would push a folder past
+//! [`ExtractionConfig::loose_file_limit`](crate::config::ExtractionConfig::loose_file_limit).
+//!
+//! Counting walks the whole folder, so counts are kept in a
+//! [`LooseStatsCache`] until the folder changes: a folder whose modification
+//! time moved is counted again, and extractions invalidate the folders they
+//! wrote to, since files added deeper down don't touch the folder itself.
+
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Loose files a mod folder may hold before extractions into it warn
+pub const DEFAULT_LOOSE_FILE_LIMIT: usize = 20_000;
+
+/// Loose files in a folder
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LooseStats {
+    /// Number of files, without archives
+    pub files: usize,
+
+    /// Combined size of the files in bytes
+    pub bytes: u64,
+}
+
+/// Count the loose files in `folder` and its subfolders
+///
+/// Archives are not loose files and are left out. Unreadable subfolders are
+/// skipped; a missing folder has no files.
+pub fn count_loose_files(folder: &Path) -> LooseStats {
+    let mut stats = LooseStats::default();
+    let mut pending = vec![folder.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::debug!("Could not list {}: {}", dir.display(), e);
+                }
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            // Not followed through links, which could loop
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if !is_archive(&entry.path()) {
+                stats.files += 1;
+                stats.bytes += entry.metadata().map_or(0, |m| m.len());
+            }
+        }
+    }
+    stats
+}
+
+fn is_archive(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ba2"))
+}
+
+/// A count, with the folder's modification time when it was counted
+type CachedCount = (Option<SystemTime>, LooseStats);
+
+/// Counts of loose files by folder, shared between clones
+#[derive(Debug, Clone, Default)]
+pub struct LooseStatsCache {
+    counts: Arc<Mutex<HashMap<PathBuf, CachedCount>>>,
+}
+
+impl LooseStatsCache {
+    /// An empty cache
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loose files in `folder`, counted unless a count is cached
+    pub fn stats(&self, folder: &Path) -> LooseStats {
+        let modified = modified(folder);
+        if let Some((cached_at, stats)) = self.counts.lock().get(folder)
+            && *cached_at == modified
+        {
+            return *stats;
+        }
+        // Counted without the lock, so other folders can be counted meanwhile
+        let stats = count_loose_files(folder);
+        self.counts
+            .lock()
+            .insert(folder.to_path_buf(), (modified, stats));
+        stats
+    }
+
+    /// Forget the count of `folder`, e.g. after extracting into it
+    pub fn invalidate(&self, folder: &Path) {
+        self.counts.lock().remove(folder);
+    }
+
+    /// Forget all counts
+    pub fn clear(&self) {
+        self.counts.lock().clear();
+    }
+
+    /// The `count` folders with the most loose files, most first
+    ///
+    /// Folders are counted in parallel; folders without loose files are left out.
+    pub fn busiest(&self, folders: &[PathBuf], count: usize) -> Vec<(PathBuf, LooseStats)> {
+        let mut stats: Vec<(PathBuf, LooseStats)> = folders
+            .par_iter()
+            .map(|folder| (folder.clone(), self.stats(folder)))
+            .filter(|(_, stats)| stats.files > 0)
+            .collect();
+        stats.sort_by(|a, b| b.1.files.cmp(&a.1.files).then_with(|| a.0.cmp(&b.0)));
+        stats.truncate(count);
+        stats
+    }
+}
+
+fn modified(folder: &Path) -> Option<SystemTime> {
+    fs::metadata(folder).and_then(|m| m.modified()).ok()
+}
+
+/// A mod folder an extraction would push past the loose file limit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrowdedFolder {
+    /// The mod folder
+    pub folder: PathBuf,
+
+    /// Loose files in it now
+    pub existing: usize,
+
+    /// Loose files in it after the extraction
+    pub after: usize,
+}
+
+/// Find the folders that would hold more than `limit` loose files once the
+/// given numbers of new files are added to them
+///
+/// `added` pairs each target folder with the files an extraction adds to it.
+/// A `limit` of 0 turns the check off.
+pub fn crowded_folders<'a>(
+    added: impl IntoIterator<Item = (&'a Path, usize)>,
+    limit: usize,
+    cache: &LooseStatsCache,
+) -> Vec<CrowdedFolder> {
+    if limit == 0 {
+        return Vec::new();
+    }
+    let added: Vec<(&Path, usize)> = added.into_iter().collect();
+    let mut crowded: Vec<CrowdedFolder> = added
+        .par_iter()
+        .filter_map(|&(folder, new_files)| {
+            let existing = cache.stats(folder).files;
+            let after = existing + new_files;
+            (after > limit).then(|| CrowdedFolder {
+                folder: folder.to_path_buf(),
+                existing,
+                after,
+            })
+        })
+        .collect();
+    crowded.sort_by_key(|crowded| std::cmp::Reverse(crowded.after));
+    crowded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_files(folder: &Path, names: &[&str]) {
+        for name in names {
+            let path = folder.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"data").unwrap();
+        }
+    }
+
+    #[test]
+    fn test_count_and_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let folder = temp_dir.path().join("Mod");
+        write_files(
+            &folder,
+            &[
+                "Mod - Main.ba2",
+                "Mod.esp",
+                "meshes/a.nif",
+                "textures/a/b.dds",
+            ],
+        );
+        assert_eq!(
+            count_loose_files(&folder),
+            LooseStats {
+                files: 3,
+                bytes: 12
+            }
+        );
+        assert_eq!(count_loose_files(&temp_dir.path().join("Missing")).files, 0);
+
+        let cache = LooseStatsCache::new();
+        assert_eq!(cache.stats(&folder).files, 3);
+        // Deeper files don't change the folder, so the cached count is kept
+        write_files(&folder, &["meshes/b.nif"]);
+        assert_eq!(cache.stats(&folder).files, 3);
+        cache.invalidate(&folder);
+        assert_eq!(cache.stats(&folder).files, 4);
+    }
+
+    #[test]
+    fn test_crowded_folders() {
+        let temp_dir = TempDir::new().unwrap();
+        let big = temp_dir.path().join("Big");
+        let small = temp_dir.path().join("Small");
+        write_files(&big, &["a.nif", "b.nif", "c.nif"]);
+        write_files(&small, &["a.nif"]);
+
+        let cache = LooseStatsCache::new();
+        let added = [(big.as_path(), 2), (small.as_path(), 2)];
+        let crowded = crowded_folders(added, 4, &cache);
+        assert_eq!(
+            crowded,
+            [CrowdedFolder {
+                folder: big.clone(),
+                existing: 3,
+                after: 5,
+            }]
+        );
+        assert!(crowded_folders(added, 0, &cache).is_empty());
+
+        let busiest = cache.busiest(&[small.clone(), big.clone()], 1);
+        assert_eq!(busiest.len(), 1);
+        assert_eq!(busiest[0].0, big);
+    }
+}
@@ -0,0 +1,513 @@
+//! Checksum manifests for extracted files
+//!
+//! After an archive is unpacked, a manifest listing every extracted file with
+//! its size and SHA-256 hash can be written next to the output. It allows the
+//! extraction to be verified later and the loose files to be removed cleanly
+//! ([`undo_extraction`]).
+
+use crate::ba2::read_file_names;
+use crate::error::{Error, Result, ValidationError};
+use crate::operations::journal::Operation;
+use crate::platform::wine::CaseFolder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+/// Version of the manifest document layout
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Suffix appended to the archive file name to form the manifest file name
+pub const MANIFEST_SUFFIX: &str = ".manifest.json";
+
+/// Buffer size used when hashing files
+const HASH_BUFFER_SIZE: usize = 256 * 1024;
+
+/// A single extracted file recorded in a manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path relative to the output folder, with '/' separators
+    pub path: String,
+
+    /// File size in bytes
+    pub size: u64,
+
+    /// Lowercase hex SHA-256 of the file contents
+    pub sha256: String,
+}
+
+/// Manifest of the files extracted from one archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractionManifest {
+    /// Layout version ([`MANIFEST_VERSION`])
+    pub version: u32,
+
+    /// File name of the source archive
+    pub archive: String,
+
+    /// Creation time (seconds since the Unix epoch)
+    pub created: u64,
+
+    /// Extracted files
+    pub files: Vec<ManifestEntry>,
+
+    /// Largest mip levels dropped from textures when they were unpacked
+    /// (0 = full size), see [`crate::ba2::texture_ops`]
+    #[serde(default)]
+    pub texture_skip_mips: u8,
+}
+
+/// Result of verifying extracted files against a manifest
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Number of files whose size and hash match
+    pub verified: usize,
+
+    /// Files listed in the manifest that no longer exist
+    pub missing: Vec<String>,
+
+    /// Files whose size or hash differ from the manifest
+    pub modified: Vec<String>,
+
+    /// Mip levels dropped from the textures when they were unpacked
+    ///
+    /// The hashes are of the downscaled files, so they verify as long as the
+    /// textures were not replaced; re-extracting at full size does not match.
+    pub texture_skip_mips: u8,
+}
+
+impl ExtractionManifest {
+    /// Total size of the extracted files in bytes
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+}
+
+impl VerifyReport {
+    /// Whether every file in the manifest is present and unchanged
+    pub const fn is_clean(&self) -> bool {
+        self.missing.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Result of undoing an extraction
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UndoReport {
+    /// Number of loose files deleted
+    pub removed: usize,
+
+    /// Files left in place because they changed since extraction
+    pub kept_modified: Vec<String>,
+
+    /// Files listed in the manifest that were already gone
+    pub missing: usize,
+
+    /// Archive restored from backup, if any
+    pub restored: Option<PathBuf>,
+}
+
+/// Get the manifest path for an archive extracted into `output_dir`
+pub fn manifest_path(archive: &Path, output_dir: &Path) -> PathBuf {
+    let name = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    output_dir.join(format!("{name}{MANIFEST_SUFFIX}"))
+}
+
+/// Build a manifest for an archive that was extracted into `output_dir`
+///
+/// The file list comes from the archive's name table, so only files that
+/// belong to this archive are recorded even if several archives share the
+/// output folder.
+pub fn build_manifest(archive: &Path, output_dir: &Path) -> Result<ExtractionManifest> {
+    let names = read_file_names(archive)?;
+    let mut files = Vec::with_capacity(names.len());
+    let mut case = CaseFolder::new();
+
+    for name in names {
+        // Recorded with the case the file has on disk
+        let path = case.resolve(output_dir, &name);
+        let relative = path.strip_prefix(output_dir).map_or_else(
+            |_| normalize_relative(&name),
+            |p| normalize_relative(&p.to_string_lossy()),
+        );
+        match hash_file(&path) {
+            Ok((size, sha256)) => files.push(ManifestEntry {
+                path: relative,
+                size,
+                sha256,
+            }),
+            Err(e) => warn!(
+                "Extracted file {} missing from {}: {}",
+                relative,
+                output_dir.display(),
+                e
+            ),
+        }
+    }
+
+    Ok(ExtractionManifest {
+        version: MANIFEST_VERSION,
+        archive: archive
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        created: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+        files,
+        texture_skip_mips: 0,
+    })
+}
+
+/// Build and write the manifest for an extracted archive
+///
+/// Returns the path of the written manifest.
+pub fn write_manifest(archive: &Path, output_dir: &Path) -> Result<PathBuf> {
+    let manifest = build_manifest(archive, output_dir)?;
+    save_manifest(&manifest, archive, output_dir)
+}
+
+/// Save a built manifest next to the output of `archive`
+///
+/// Returns the path of the written manifest.
+pub fn save_manifest(
+    manifest: &ExtractionManifest,
+    archive: &Path,
+    output_dir: &Path,
+) -> Result<PathBuf> {
+    let path = manifest_path(archive, output_dir);
+
+    let content = serde_json::to_string_pretty(manifest)
+        .map_err(|e| Error::Other(format!("Failed to serialize manifest: {e}")))?;
+    fs::write(&path, content)?;
+
+    info!(
+        "Wrote manifest with {} files to {}",
+        manifest.files.len(),
+        path.display()
+    );
+    Ok(path)
+}
+
+/// Load a manifest from disk
+pub fn load_manifest(path: &Path) -> Result<ExtractionManifest> {
+    let content = fs::read_to_string(path)?;
+    let manifest: ExtractionManifest = serde_json::from_str(&content).map_err(|e| {
+        Error::from(ValidationError::InvalidInput(format!(
+            "{} is not an extraction manifest: {e}",
+            path.display()
+        )))
+    })?;
+
+    if manifest.version > MANIFEST_VERSION {
+        return Err(ValidationError::InvalidInput(format!(
+            "Manifest version {} is newer than supported version {MANIFEST_VERSION}",
+            manifest.version
+        ))
+        .into());
+    }
+
+    Ok(manifest)
+}
+
+/// Verify the files listed in a manifest against the files on disk
+///
+/// Paths in the manifest are resolved relative to the manifest's folder.
+pub fn verify_manifest(path: &Path) -> Result<VerifyReport> {
+    let manifest = load_manifest(path)?;
+    let root = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut report = VerifyReport {
+        texture_skip_mips: manifest.texture_skip_mips,
+        ..VerifyReport::default()
+    };
+
+    for entry in &manifest.files {
+        match hash_file(&root.join(&entry.path)) {
+            Ok((size, sha256)) if size == entry.size && sha256 == entry.sha256 => {
+                report.verified += 1;
+            }
+            Ok(_) => report.modified.push(entry.path.clone()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                report.missing.push(entry.path.clone());
+            }
+            Err(e) => {
+                debug!("Failed to hash {}: {}", entry.path, e);
+                report.modified.push(entry.path.clone());
+            }
+        }
+    }
+
+    info!(
+        "Verified {}: {} ok, {} missing, {} modified",
+        manifest.archive,
+        report.verified,
+        report.missing.len(),
+        report.modified.len()
+    );
+    Ok(report)
+}
+
+/// Undo an extraction recorded by a manifest
+///
+/// Deletes exactly the files listed in the manifest, skipping any whose size
+/// or hash changed since extraction, then removes folders left empty and the
+/// manifest itself. If `backup` is given and the archive is no longer next to
+/// the manifest, the archive is copied back from the backup.
+///
+/// If `operation` is given, removed files are kept in its journal folder and
+/// the restored archive is recorded, so the undo itself can be undone.
+pub fn undo_extraction(
+    path: &Path,
+    backup: Option<&Path>,
+    mut operation: Option<&mut Operation>,
+) -> Result<UndoReport> {
+    let manifest = load_manifest(path)?;
+    let root = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut report = UndoReport::default();
+
+    for entry in &manifest.files {
+        let file = root.join(&entry.path);
+        match hash_file(&file) {
+            Ok((size, sha256)) if size == entry.size && sha256 == entry.sha256 => {
+                remove_file(&file, operation.as_deref_mut())?;
+                report.removed += 1;
+                remove_empty_parents(&file, root);
+            }
+            Ok(_) => report.kept_modified.push(entry.path.clone()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => report.missing += 1,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    remove_file(path, operation.as_deref_mut())?;
+
+    let archive = root.join(&manifest.archive);
+    if let Some(backup) = backup.filter(|b| b.is_file())
+        && !archive.exists()
+    {
+        fs::copy(backup, &archive)?;
+        if let Some(operation) = operation {
+            operation.record_created(&archive);
+        }
+        info!("Restored {} from {}", archive.display(), backup.display());
+        report.restored = Some(archive);
+    }
+
+    info!(
+        "Undid extraction of {}: {} removed, {} kept (modified), {} already missing",
+        manifest.archive,
+        report.removed,
+        report.kept_modified.len(),
+        report.missing
+    );
+    Ok(report)
+}
+
+/// Get where an archive's backup is stored under `backup_root`
+///
+/// Backups mirror the mod folder layout: `<backup_root>/<mod>/<archive>`.
+pub fn backup_location(backup_root: &Path, archive: &Path) -> Option<PathBuf> {
+    let file_name = archive.file_name()?;
+    let mod_name = archive.parent()?.file_name()?;
+    Some(backup_root.join(mod_name).join(file_name))
+}
+
+/// Copy an archive to its [`backup_location`] under `backup_root`
+///
+/// An earlier backup is kept, so repeated runs never replace the original
+/// with a copy of an already modified archive. Returns the backup if one
+/// was written.
+pub fn back_up_archive(archive: &Path, backup_root: &Path) -> Result<Option<PathBuf>> {
+    let Some(backup) = backup_location(backup_root, archive) else {
+        return Ok(None);
+    };
+    if backup.exists() {
+        debug!("Keeping the earlier backup of {}", archive.display());
+        return Ok(None);
+    }
+    if let Some(parent) = backup.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Copy under a temporary name so an interrupted copy is never taken for a backup
+    let partial = backup.with_extension("ba2.partial");
+    fs::copy(archive, &partial)?;
+    fs::rename(&partial, &backup)?;
+    debug!("Backed up {} to {}", archive.display(), backup.display());
+    Ok(Some(backup))
+}
+
+/// Remove a file, through `operation` if given
+fn remove_file(file: &Path, operation: Option<&mut Operation>) -> Result<()> {
+    match operation {
+        Some(operation) => operation.remove_file(file),
+        None => Ok(fs::remove_file(file)?),
+    }
+}
+
+/// Remove folders between `file` and `root` that are now empty
+fn remove_empty_parents(file: &Path, root: &Path) {
+    let mut dir = file.parent();
+    while let Some(current) = dir {
+        if current == root || !current.starts_with(root) || fs::remove_dir(current).is_err() {
+            break;
+        }
+        dir = current.parent();
+    }
+}
+
+/// Hash a file with SHA-256, streaming its contents
+///
+/// Returns the file size and the lowercase hex digest.
+pub(crate) fn hash_file(path: &Path) -> io::Result<(u64, String)> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; HASH_BUFFER_SIZE];
+    let mut size = 0u64;
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+
+    let digest = hasher.finalize();
+    let mut hex = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    Ok((size, hex))
+}
+
+/// Normalize an archive path to a relative path with '/' separators
+fn normalize_relative(name: &str) -> String {
+    name.replace('\\', "/").trim_start_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Write a minimal GNRL archive whose name table lists `names`
+    fn write_archive(path: &Path, names: &[&str]) {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"BTDX");
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(b"GNRL");
+        data.extend_from_slice(&u32::try_from(names.len()).unwrap().to_le_bytes());
+        data.extend_from_slice(&24u64.to_le_bytes());
+        for name in names {
+            data.extend_from_slice(&u16::try_from(name.len()).unwrap().to_le_bytes());
+            data.extend_from_slice(name.as_bytes());
+        }
+        fs::write(path, data).unwrap();
+    }
+
+    #[test]
+    fn test_write_and_verify_manifest() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let archive = root.join("Mod - Main.ba2");
+        write_archive(&archive, &["meshes\\a.nif", "scripts\\b.pex"]);
+
+        fs::create_dir_all(root.join("meshes")).unwrap();
+        fs::create_dir_all(root.join("scripts")).unwrap();
+        fs::write(root.join("meshes/a.nif"), b"mesh").unwrap();
+        fs::write(root.join("scripts/b.pex"), b"script").unwrap();
+
+        let path = write_manifest(&archive, root).unwrap();
+        assert_eq!(path, root.join("Mod - Main.ba2.manifest.json"));
+
+        let manifest = load_manifest(&path).unwrap();
+        assert_eq!(manifest.archive, "Mod - Main.ba2");
+        assert_eq!(manifest.files.len(), 2);
+        assert_eq!(manifest.files[0].path, "meshes/a.nif");
+        assert_eq!(manifest.files[0].size, 4);
+
+        assert!(verify_manifest(&path).unwrap().is_clean());
+
+        fs::write(root.join("meshes/a.nif"), b"changed").unwrap();
+        fs::remove_file(root.join("scripts/b.pex")).unwrap();
+        let report = verify_manifest(&path).unwrap();
+        assert_eq!(report.verified, 0);
+        assert_eq!(report.modified, vec!["meshes/a.nif".to_string()]);
+        assert_eq!(report.missing, vec!["scripts/b.pex".to_string()]);
+    }
+
+    #[test]
+    fn test_undo_extraction_keeps_modified_and_restores_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path().join("My Mod");
+        let backup_root = temp_dir.path().join("backup");
+        fs::create_dir_all(mod_dir.join("meshes/sub")).unwrap();
+        fs::create_dir_all(mod_dir.join("scripts")).unwrap();
+
+        let archive = mod_dir.join("Mod - Main.ba2");
+        write_archive(&archive, &["meshes\\sub\\a.nif", "scripts\\b.pex"]);
+        fs::write(mod_dir.join("meshes/sub/a.nif"), b"mesh").unwrap();
+        fs::write(mod_dir.join("scripts/b.pex"), b"script").unwrap();
+        let manifest = write_manifest(&archive, &mod_dir).unwrap();
+
+        // Back up and remove the archive, then edit one extracted file
+        let backup = backup_location(&backup_root, &archive).unwrap();
+        assert_eq!(backup, backup_root.join("My Mod").join("Mod - Main.ba2"));
+        fs::create_dir_all(backup.parent().unwrap()).unwrap();
+        fs::rename(&archive, &backup).unwrap();
+        fs::write(mod_dir.join("scripts/b.pex"), b"edited").unwrap();
+
+        let report = undo_extraction(&manifest, Some(&backup), None).unwrap();
+        assert_eq!(report.removed, 1);
+        assert_eq!(report.kept_modified, vec!["scripts/b.pex".to_string()]);
+        assert_eq!(report.restored, Some(archive.clone()));
+
+        assert!(!mod_dir.join("meshes").exists());
+        assert!(mod_dir.join("scripts/b.pex").exists());
+        assert!(archive.exists());
+        assert!(!manifest.exists());
+    }
+
+    #[test]
+    fn test_back_up_archive_keeps_earlier_backup() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path().join("My Mod");
+        let backup_root = temp_dir.path().join("backup");
+        fs::create_dir_all(&mod_dir).unwrap();
+        let archive = mod_dir.join("Mod - Main.ba2");
+        fs::write(&archive, b"original").unwrap();
+
+        let backup = back_up_archive(&archive, &backup_root).unwrap();
+        assert_eq!(backup, backup_location(&backup_root, &archive));
+        assert_eq!(fs::read(backup.unwrap()).unwrap(), b"original");
+
+        // A second run keeps the first copy
+        fs::write(&archive, b"changed").unwrap();
+        assert_eq!(back_up_archive(&archive, &backup_root).unwrap(), None);
+        let backup = backup_location(&backup_root, &archive).unwrap();
+        assert_eq!(fs::read(&backup).unwrap(), b"original");
+        assert!(!backup.with_extension("ba2.partial").exists());
+    }
+
+    #[test]
+    fn test_hash_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("abc.txt");
+        fs::write(&path, b"abc").unwrap();
+
+        let (size, hash) = hash_file(&path).unwrap();
+        assert_eq!(size, 3);
+        assert_eq!(
+            hash,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
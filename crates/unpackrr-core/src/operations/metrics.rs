@@ -0,0 +1,354 @@
+//! Local extraction metrics
+//!
+//! When enabled in the advanced settings, every extraction run records its
+//! throughput, retry count and failure categories. The numbers are kept in
+//! `metrics.json` next to the configuration and are never sent anywhere; they
+//! are shown on the Performance page and included in exported JSON lists, so
+//! users tuning their setup can compare runs.
+
+use crate::config::AppConfig;
+use crate::error::{Error, Result};
+use crate::operations::extract::ExtractionProgress;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Number of runs kept in the metrics history
+pub const MAX_RUNS: usize = 20;
+
+/// Number of archives listed as the slowest of a run
+pub const SLOWEST_ARCHIVES: usize = 10;
+
+/// Name of the metrics history file in the configuration folder
+const METRICS_FILE: &str = "metrics.json";
+
+/// Metrics of a single extraction run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunMetrics {
+    /// Start of the run (seconds since the Unix epoch)
+    pub started_at: u64,
+
+    /// Wall-clock duration of the run in seconds
+    pub duration_secs: f64,
+
+    /// Archives that were extracted successfully
+    pub succeeded: usize,
+
+    /// Archives that failed
+    pub failed: usize,
+
+    /// Archives that were skipped
+    pub skipped: usize,
+
+    /// Size of the archives that were processed (successful or not)
+    pub bytes_processed: u64,
+
+    /// Number of retried attempts
+    pub retries: usize,
+
+    /// Retries caused by files locked by another process
+    pub lock_retries: usize,
+
+    /// Highest number of archives extracted at the same time
+    pub peak_parallel: usize,
+
+    /// Failed archives by error category
+    pub failure_categories: BTreeMap<String, usize>,
+
+    /// Archives that took longest to extract, slowest first
+    #[serde(default)]
+    pub slowest: Vec<ArchiveTiming>,
+}
+
+/// How long an archive took to extract
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveTiming {
+    /// Archive file name
+    pub name: String,
+
+    /// Mod folder name
+    pub mod_name: String,
+
+    /// Archive size in bytes
+    pub size: u64,
+
+    /// Wall-clock duration of the extraction in seconds, including retries
+    pub duration_secs: f64,
+}
+
+impl RunMetrics {
+    /// Archives processed per second
+    #[allow(clippy::cast_precision_loss)] // Counts won't exceed f64 precision
+    pub fn archives_per_sec(&self) -> f64 {
+        if self.duration_secs > 0.0 {
+            (self.succeeded + self.failed) as f64 / self.duration_secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Archive bytes processed per second
+    #[allow(clippy::cast_precision_loss)] // Byte counts won't exceed f64 precision
+    pub fn bytes_per_sec(&self) -> f64 {
+        if self.duration_secs > 0.0 {
+            self.bytes_processed as f64 / self.duration_secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Collects the metrics of a run from its progress events
+#[derive(Debug)]
+pub struct MetricsRecorder {
+    started: Instant,
+    sizes: HashMap<PathBuf, u64>,
+    in_flight: HashSet<PathBuf>,
+    metrics: RunMetrics,
+}
+
+impl MetricsRecorder {
+    /// Start recording a run over archives given as (path, size) pairs
+    pub fn new(archives: impl IntoIterator<Item = (PathBuf, u64)>) -> Self {
+        Self {
+            started: Instant::now(),
+            sizes: archives.into_iter().collect(),
+            in_flight: HashSet::new(),
+            metrics: RunMetrics {
+                started_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs()),
+                ..RunMetrics::default()
+            },
+        }
+    }
+
+    /// Update the metrics from a progress event
+    pub fn record(&mut self, progress: &ExtractionProgress) {
+        let metrics = &mut self.metrics;
+        match progress {
+            ExtractionProgress::Started { file_path, .. } => {
+                self.in_flight.insert(file_path.clone());
+                metrics.peak_parallel = metrics.peak_parallel.max(self.in_flight.len());
+            }
+            ExtractionProgress::Completed {
+                file_name,
+                file_path,
+                success,
+                error_category,
+                duration,
+                ..
+            } => {
+                self.in_flight.remove(file_path);
+                let size = self.sizes.get(file_path).copied().unwrap_or(0);
+                metrics.bytes_processed += size;
+
+                let timing = ArchiveTiming {
+                    name: file_name.clone(),
+                    mod_name: file_path
+                        .parent()
+                        .and_then(|folder| folder.file_name())
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    size,
+                    duration_secs: duration.as_secs_f64(),
+                };
+                let rank = metrics
+                    .slowest
+                    .partition_point(|slower| slower.duration_secs >= timing.duration_secs);
+                if rank < SLOWEST_ARCHIVES {
+                    metrics.slowest.insert(rank, timing);
+                    metrics.slowest.truncate(SLOWEST_ARCHIVES);
+                }
+                if *success {
+                    metrics.succeeded += 1;
+                } else {
+                    metrics.failed += 1;
+                    let category = error_category.unwrap_or("other");
+                    *metrics
+                        .failure_categories
+                        .entry(category.to_string())
+                        .or_default() += 1;
+                }
+            }
+            ExtractionProgress::Skipped { .. } => metrics.skipped += 1,
+            ExtractionProgress::Retrying {
+                likely_antivirus, ..
+            } => {
+                metrics.retries += 1;
+                metrics.lock_retries += usize::from(*likely_antivirus);
+            }
+            _ => {}
+        }
+    }
+
+    /// Finish the run and return its metrics
+    pub fn finish(mut self) -> RunMetrics {
+        self.metrics.duration_secs = self.started.elapsed().as_secs_f64();
+        self.metrics
+    }
+}
+
+/// Metrics of the most recent runs, oldest first
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsHistory {
+    /// Recorded runs
+    pub runs: Vec<RunMetrics>,
+}
+
+impl MetricsHistory {
+    /// Get the path of the metrics history file
+    pub fn file_path() -> Result<PathBuf> {
+        Ok(AppConfig::config_dir()?.join(METRICS_FILE))
+    }
+
+    /// Load the history, or an empty one if there is none yet
+    pub fn load() -> Self {
+        let Ok(path) = Self::file_path() else {
+            return Self::default();
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable metrics file {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// Save the history to the configuration folder
+    pub fn save(&self) -> Result<()> {
+        let path = Self::file_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::Other(format!("Failed to serialize metrics: {e}")))?;
+        fs::write(&path, content)?;
+        Ok(())
+    }
+
+    /// Add a run, dropping the oldest ones beyond [`MAX_RUNS`]
+    pub fn push(&mut self, run: RunMetrics) {
+        self.runs.push(run);
+        let excess = self.runs.len().saturating_sub(MAX_RUNS);
+        self.runs.drain(..excess);
+    }
+
+    /// Most recent run
+    pub fn latest(&self) -> Option<&RunMetrics> {
+        self.runs.last()
+    }
+
+    /// Delete the history file
+    pub fn clear() -> Result<()> {
+        let path = Self::file_path()?;
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn completed(
+        path: &str,
+        success: bool,
+        error_category: Option<&'static str>,
+        secs: u64,
+    ) -> ExtractionProgress {
+        let path = PathBuf::from(path);
+        ExtractionProgress::Completed {
+            file_name: path.file_name().unwrap().to_string_lossy().into_owned(),
+            file_path: path.clone(),
+            success,
+            error: None,
+            warnings: Vec::new(),
+            output_size: None,
+            error_category,
+            duration: Duration::from_secs(secs),
+        }
+    }
+
+    #[test]
+    fn test_recorder_counts_run() {
+        let mut recorder =
+            MetricsRecorder::new([(PathBuf::from("a.ba2"), 300), (PathBuf::from("b.ba2"), 700)]);
+        for path in ["a.ba2", "b.ba2"] {
+            recorder.record(&ExtractionProgress::Started {
+                file_name: path.to_string(),
+                file_path: PathBuf::from(path),
+                current: 1,
+                total: 2,
+                file_size: 0,
+                total_size: 0,
+            });
+        }
+        recorder.record(&ExtractionProgress::Retrying {
+            file_name: "b.ba2".to_string(),
+            file_path: PathBuf::from("b.ba2"),
+            attempt: 1,
+            max_attempts: 3,
+            delay: Duration::from_secs(2),
+            likely_antivirus: true,
+        });
+        recorder.record(&completed("a.ba2", true, None, 4));
+        recorder.record(&completed("b.ba2", false, Some("file-locked"), 9));
+
+        let metrics = recorder.finish();
+        assert_eq!((metrics.succeeded, metrics.failed), (1, 1));
+        assert_eq!(metrics.bytes_processed, 1000);
+        assert_eq!((metrics.retries, metrics.lock_retries), (1, 1));
+        assert_eq!(metrics.peak_parallel, 2);
+        assert_eq!(metrics.failure_categories.get("file-locked"), Some(&1));
+        let slowest: Vec<_> = metrics.slowest.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(slowest, ["b.ba2", "a.ba2"]);
+        assert_eq!(metrics.slowest[0].size, 700);
+    }
+
+    #[test]
+    fn test_recorder_keeps_slowest_archives() {
+        let mut recorder = MetricsRecorder::new([]);
+        for secs in [3, 40, 1, 25, 7, 12, 90, 2, 5, 60, 8, 30] {
+            let path = format!("Mod {secs}/{secs}.ba2");
+            recorder.record(&completed(&path, true, None, secs));
+        }
+
+        let metrics = recorder.finish();
+        let durations: Vec<f64> = metrics.slowest.iter().map(|t| t.duration_secs).collect();
+        assert_eq!(
+            durations,
+            [90.0, 60.0, 40.0, 30.0, 25.0, 12.0, 8.0, 7.0, 5.0, 3.0]
+        );
+        assert_eq!(metrics.slowest[0].mod_name, "Mod 90");
+
+        // Metrics recorded before per-archive times still load
+        let mut old = serde_json::to_value(RunMetrics::default()).unwrap();
+        old.as_object_mut().unwrap().remove("slowest");
+        let old: RunMetrics = serde_json::from_value(old).unwrap();
+        assert!(old.slowest.is_empty());
+    }
+
+    #[test]
+    fn test_history_keeps_recent_runs() {
+        let mut history = MetricsHistory::default();
+        for started_at in 0..(MAX_RUNS as u64 + 5) {
+            history.push(RunMetrics {
+                started_at,
+                ..RunMetrics::default()
+            });
+        }
+        assert_eq!(history.runs.len(), MAX_RUNS);
+        assert_eq!(history.runs[0].started_at, 5);
+        assert_eq!(
+            history.latest().map(|r| r.started_at),
+            Some(MAX_RUNS as u64 + 4)
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! File system operations for BA2 processing
+//!
+//! This module provides:
+//! - Directory scanning for BA2 files
+//! - BA2 extraction orchestration
+//! - File validation
+//! - Size parsing and locale-aware formatting utilities
+//! - Path handling utilities
+//! - Decoding of external tool output
+//! - Retry logic for transient failures
+//! - Exporting scan results to CSV/JSON and importing extraction lists
+//! - Staging extractions in a temporary folder before moving them into place
+//! - Linking extracted files from a shared cache
+//! - Byte-based extraction speed and ETA
+//! - Opt-in local metrics of extraction runs
+//! - Tagging archives installed by a Wabbajack modlist
+//! - Reading the plugin load order from `plugins.txt`
+//! - Quarantining corrupted archives
+//! - Finding the download page of a mod from mod manager metadata
+//! - Recommending which archives to unpack and predicting the archive count afterwards
+//! - Comparing the contents of two archives
+//! - Undoing file operations from a journal
+//! - Publishing progress events to subscribers
+//! - Unpacking archives without `BSArch`
+//! - Checking an extraction for problems before it starts
+//! - Explaining why the extractions of a run failed
+//! - Checking that the environment can run extractions
+//! - Backing up loose files before extracting into the game Data folder
+//! - Writing progress snapshots of a run for external tools
+//! - Notes and tags on mods and archives
+//! - Auditing every change made to mod folders
+//! - Counting the loose files already in mod folders
+//! - Reorganizing extracted files into the layout mod managers expect
+//! - Removing what interrupted runs left behind in mod folders
+//! - Listing the archives inside pending mod downloads
+
+pub mod advisor;
+pub mod audit;
+pub mod cleanup;
+pub mod datafolder;
+pub mod diagnosis;
+pub mod diff;
+pub mod downloads;
+pub mod encoding;
+pub mod events;
+pub mod export;
+pub mod extract;
+pub mod format;
+pub mod import;
+pub mod journal;
+pub mod linking;
+pub mod loadorder;
+pub mod loose_stats;
+pub mod manifest;
+pub mod metrics;
+pub mod modpage;
+pub mod native;
+pub mod notes;
+pub mod path;
+pub mod preflight;
+pub mod quarantine;
+pub mod reorganize;
+pub mod retry;
+pub mod scan;
+pub mod selftest;
+pub mod size;
+pub mod staging;
+pub mod status;
+pub mod throughput;
+pub mod wabbajack;
+mod zip;
+
+use crate::error::Result;
+use crate::models::ArchiveHealth;
+use std::path::PathBuf;
+
+// Re-export scan module types and functions
+pub use scan::{ScanProgress, list_archives, scan_for_ba2, scan_with_ignored};
+
+// Re-export extract module types and functions
+pub use extract::{
+    BatchAbort, BsarchBackend, ConvertTextures, ExtractionProgress, ExtractionResult,
+    ExtractorBackend, FileExtractionResult, detect_bsarch_version, extract_all, extract_all_with,
+    extract_ba2_file, extract_ba2_file_with_progress, resolve_bsarch_path, uses_builtin_reader,
+    validate_ba2_tool,
+};
+
+// Re-export the event bus
+pub use events::{AppEvent, BackupEvent, EventBus, ToolEvent};
+
+// Re-export the built-in extractor
+pub use native::{FsyncPolicy, NativeBackend, WriterConfig};
+
+// Re-export console output decoding
+pub use encoding::decode_console_output;
+
+// Re-export list export/import helpers
+pub use export::{ExportFormat, export_entries};
+pub use import::{ImportReport, load_export_document, match_entries};
+
+// Re-export manifest types
+pub use manifest::{
+    UndoReport, VerifyReport, back_up_archive, backup_location, manifest_path, undo_extraction,
+    verify_manifest, write_manifest,
+};
+
+// Re-export locale-aware formatting helpers
+pub use format::{
+    NumberFormat, format_count, format_duration, format_elapsed, format_size, number_format,
+    set_number_format,
+};
+
+// Re-export size specification type
+pub use size::SizeSpec;
+
+// Re-export path utilities
+pub use path::{
+    canonicalize_path, get_parent, is_valid_directory, is_valid_file, normalize_separators,
+    paths_equal, resolve_path,
+};
+
+// Re-export retry utilities (Phase 2.8)
+pub use retry::{CancellationToken, RetryConfig, retry, retry_async, retry_with_config};
+
+// Re-export temporary extraction folders
+pub use staging::StagingDir;
+
+// Re-export cache linking
+pub use linking::{LinkMode, LinkReport};
+
+// Re-export extraction throughput tracking
+pub use throughput::ThroughputTracker;
+
+// Re-export local run metrics
+pub use metrics::{ArchiveTiming, MetricsHistory, MetricsRecorder, RunMetrics};
+
+// Re-export progress snapshots for external tools
+pub use status::{ProgressSnapshot, RunState, status_file_path, write_status};
+
+// Re-export the notes of mods and archives
+pub use notes::{ModNotes, Note};
+
+// Re-export Wabbajack modlist lookup
+pub use wabbajack::ModlistIndex;
+
+// Re-export plugin load order lookup
+pub use loadorder::LoadOrder;
+
+// Re-export the quarantine of corrupted archives
+pub use quarantine::{QuarantineManager, QuarantineRecord};
+
+// Re-export mod page lookup
+pub use modpage::{ModPage, find_mod_page};
+
+// Re-export unpacking recommendations
+pub use advisor::{Recommendation, Simulation, recommend, simulate};
+
+// Re-export archive comparison
+pub use diff::{ArchiveDiff, ChangeKind, compare_archives, compare_folder};
+
+// Re-export the checks before extraction
+pub use preflight::{PreflightReport, PreflightWarning, preflight};
+
+// Re-export failure diagnosis
+pub use diagnosis::{Diagnosis, FailureCluster, FailureKind, diagnose};
+
+// Re-export the environment self-test
+pub use selftest::{CheckStatus, SelfTestCheck, SelfTestPaths, SelfTestReport, self_test};
+
+// Re-export the Data folder safety rails
+pub use datafolder::{back_up_loose_files, backup_root, in_data_folder, loose_conflicts};
+
+// Re-export the operation journal
+pub use journal::{
+    JournalEntry, Operation, OperationJournal, finish_partial_move, revert_partial_move,
+};
+
+/// Information about a discovered BA2 file
+#[derive(Debug, Clone)]
+pub struct BA2FileInfo {
+    /// File name (without path)
+    pub file_name: String,
+
+    /// File size in bytes
+    pub file_size: u64,
+
+    /// Number of files in the archive
+    pub num_files: u32,
+
+    /// Parent directory name
+    pub dir_name: String,
+
+    /// Full path to the file
+    pub full_path: PathBuf,
+
+    /// Whether the header could be read, and why not
+    pub health: ArchiveHealth,
+
+    /// Archive type from the header ("GNRL", "DX10"), empty if unreadable
+    pub archive_type: String,
+
+    /// BA2 format version from the header, 0 if unreadable
+    pub format_version: u32,
+
+    /// Whether the file matches an ignore pattern
+    pub ignored: bool,
+
+    /// Why the archive must stay packed, if it is a known exclusion
+    pub exclusion: Option<String>,
+}
+
+/// Parse a size string (e.g., "10MB", "1.5GiB") into bytes
+///
+/// Thin wrapper around [`SizeSpec::parse`]:
+/// - SI units (KB, MB, GB, TB) use base-1000, matching the Python implementation
+/// - IEC units (KiB, MiB, GiB, TiB) use base-1024, matching the displayed sizes
+/// - Case-insensitive
+/// - Handles floating point numbers
+///
+/// # Examples
+///
+/// ```
+/// use unpackrr_core::operations::parse_size;
+///
+/// assert_eq!(parse_size("100B").unwrap(), 100);
+/// assert_eq!(parse_size("1KB").unwrap(), 1000);
+/// assert_eq!(parse_size("1KiB").unwrap(), 1024);
+/// assert_eq!(parse_size("1.5MB").unwrap(), 1_500_000);
+/// assert_eq!(parse_size("10GB").unwrap(), 10_000_000_000);
+/// ```
+pub fn parse_size(size_str: &str) -> Result<u64> {
+    SizeSpec::parse(size_str).map(SizeSpec::bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("100B").unwrap(), 100);
+        assert_eq!(parse_size("1KB").unwrap(), 1_000);
+        assert_eq!(parse_size("1MB").unwrap(), 1_000_000);
+        assert_eq!(parse_size("1.5MB").unwrap(), 1_500_000);
+        assert_eq!(parse_size("10GB").unwrap(), 10_000_000_000);
+    }
+
+    #[test]
+    fn test_parse_size_case_insensitive() {
+        assert_eq!(parse_size("1mb").unwrap(), parse_size("1MB").unwrap());
+        assert_eq!(parse_size("1kb").unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_parse_size_no_suffix() {
+        // Should add 'B' suffix if not present
+        assert_eq!(parse_size("100").unwrap(), 100);
+        assert_eq!(parse_size("1K").unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_parse_size_with_spaces() {
+        assert_eq!(parse_size("1 MB").unwrap(), 1_000_000);
+        assert_eq!(parse_size(" 100 KB ").unwrap(), 100_000);
+    }
+
+    #[test]
+    fn test_parse_size_invalid() {
+        assert!(parse_size("invalid").is_err());
+        assert!(parse_size("").is_err());
+        assert!(parse_size("MB").is_err());
+    }
+
+    #[test]
+    fn test_parse_size_binary_units() {
+        assert_eq!(parse_size("1KiB").unwrap(), 1_024);
+        assert_eq!(parse_size("2 MiB").unwrap(), 2_097_152);
+    }
+
+    #[test]
+    fn test_format_size() {
+        let formatted = format_size(1024);
+        assert!(formatted.contains("1"));
+        assert!(formatted.contains("Ki")); // Binary units by default
+    }
+}
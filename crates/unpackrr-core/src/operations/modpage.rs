@@ -0,0 +1,181 @@
+//! Finding the download page of a mod
+//!
+//! A corrupted archive usually means a broken download, but archive names
+//! rarely tell which download they came from. Mod managers keep that
+//! information next to the installed mod:
+//!
+//! - Mod Organizer 2 writes a `meta.ini` into every mod folder with the Nexus
+//!   mod ID, the game and the name of the downloaded file
+//! - Vortex names its staging folders after the downloaded file, which ends
+//!   with the Nexus mod ID, the version and an upload timestamp
+//!   (`Some Mod-12345-1-2-1690000000`)
+
+use std::fs;
+use std::path::Path;
+
+/// Name of Mod Organizer 2's per-mod metadata file
+const MO2_META_FILE: &str = "meta.ini";
+
+/// Marker file Vortex puts into its staging folder
+const VORTEX_MARKER: &str = "__folder_managed_by_vortex";
+
+/// Nexus Mods site used for mod IDs
+const NEXUS_URL: &str = "https://www.nexusmods.com";
+
+/// Where a mod can be downloaded again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModPage {
+    /// Web page of the mod
+    pub url: String,
+
+    /// Name of the file the mod was installed from, if known
+    pub download_file: Option<String>,
+}
+
+/// Find the download page of the mod installed in `mod_folder`
+///
+/// `starfield` picks the Nexus game section when the mod manager does not
+/// record the game.
+pub fn find_mod_page(mod_folder: &Path, starfield: bool) -> Option<ModPage> {
+    let default_game = if starfield { "starfield" } else { "fallout4" };
+    mo2_mod_page(mod_folder, default_game).or_else(|| vortex_mod_page(mod_folder, default_game))
+}
+
+/// Read the mod page from Mod Organizer 2's `meta.ini`
+fn mo2_mod_page(mod_folder: &Path, default_game: &str) -> Option<ModPage> {
+    let bytes = fs::read(mod_folder.join(MO2_META_FILE)).ok()?;
+    let content = String::from_utf8_lossy(&bytes);
+    let value = |key: &str| general_value(&content, key).filter(|v| !v.is_empty());
+
+    let download_file = value("installationFile").map(|file| {
+        // MO2 stores the path of the download, sometimes with escaped separators
+        file.rsplit(['/', '\\']).next().unwrap_or(file).to_string()
+    });
+    let url = if let Some(url) = value("url").filter(|_| value("hasCustomURL") == Some("true")) {
+        url.to_string()
+    } else {
+        let mod_id = value("modid")?.parse::<u64>().ok().filter(|&id| id > 0)?;
+        let game = value("gameName").map_or_else(|| default_game.to_string(), nexus_domain);
+        format!("{NEXUS_URL}/{game}/mods/{mod_id}")
+    };
+    Some(ModPage { url, download_file })
+}
+
+/// Derive the mod page from the name of a Vortex staging folder
+fn vortex_mod_page(mod_folder: &Path, default_game: &str) -> Option<ModPage> {
+    let staging = mod_folder.parent()?;
+    if !staging.join(VORTEX_MARKER).exists() {
+        return None;
+    }
+    let name = mod_folder.file_name()?.to_str()?;
+    let mod_id = nexus_id_from_download_name(name)?;
+    Some(ModPage {
+        url: format!("{NEXUS_URL}/{default_game}/mods/{mod_id}"),
+        download_file: None,
+    })
+}
+
+/// Get the Nexus mod ID from a download name ("Some Mod-12345-1-2-1690000000")
+///
+/// The name ends with the mod ID, one or more version parts and a Unix
+/// timestamp, all numeric.
+fn nexus_id_from_download_name(name: &str) -> Option<u64> {
+    let parts: Vec<&str> = name.split('-').collect();
+    let is_number = |part: &&str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    let timestamp = parts.last()?;
+    if !(9..=11).contains(&timestamp.len()) || !is_number(timestamp) {
+        return None;
+    }
+    // The mod ID starts the numeric tail: mod ID, version parts, timestamp
+    let tail = parts.iter().rev().take_while(|part| is_number(part)).count();
+    if tail < 3 || tail == parts.len() {
+        return None;
+    }
+    parts[parts.len() - tail].parse().ok()
+}
+
+/// Get a value from the `[General]` section of an INI file
+fn general_value<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    let mut in_general = false;
+    for line in content.lines() {
+        let line = line.trim_start_matches('\u{feff}').trim();
+        if line.starts_with('[') {
+            in_general = line.eq_ignore_ascii_case("[General]");
+        } else if in_general
+            && let Some((name, value)) = line.split_once('=')
+            && name.trim().eq_ignore_ascii_case(key)
+        {
+            return Some(value.trim().trim_matches('"'));
+        }
+    }
+    None
+}
+
+/// Map Mod Organizer 2's game name to its Nexus Mods section
+fn nexus_domain(game: &str) -> String {
+    match game.to_lowercase().as_str() {
+        "fallout4vr" => "fallout4".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mo2_meta_ini() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_folder = temp_dir.path().join("Weird Name");
+        fs::create_dir(&mod_folder).unwrap();
+        fs::write(
+            mod_folder.join(MO2_META_FILE),
+            "[General]\r\ngameName=Fallout4\r\nmodid=1234\r\n\
+             installationFile=C:/Downloads/Better Mod-1234-1-0.7z\r\n\
+             url=\r\nhasCustomURL=false\r\n\r\n[installedFiles]\r\nsize=0\r\n",
+        )
+        .unwrap();
+
+        let page = find_mod_page(&mod_folder, false).unwrap();
+        assert_eq!(page.url, "https://www.nexusmods.com/fallout4/mods/1234");
+        assert_eq!(
+            page.download_file.as_deref(),
+            Some("Better Mod-1234-1-0.7z")
+        );
+
+        fs::write(
+            mod_folder.join(MO2_META_FILE),
+            "[General]\nmodid=0\nurl=https://example.com/mod\nhasCustomURL=true\n",
+        )
+        .unwrap();
+        let page = find_mod_page(&mod_folder, false).unwrap();
+        assert_eq!(page.url, "https://example.com/mod");
+
+        fs::write(mod_folder.join(MO2_META_FILE), "[General]\nmodid=0\n").unwrap();
+        assert_eq!(find_mod_page(&mod_folder, false), None);
+    }
+
+    #[test]
+    fn test_vortex_folder_name() {
+        assert_eq!(
+            nexus_id_from_download_name("Some Mod-12345-1-2-1690000000"),
+            Some(12345)
+        );
+        assert_eq!(
+            nexus_id_from_download_name("Mod 2-Part-678-1-0-1-1600000000"),
+            Some(678)
+        );
+        assert_eq!(nexus_id_from_download_name("My Own Mod"), None);
+        assert_eq!(nexus_id_from_download_name("Mod-1-1600000000"), None);
+
+        let temp_dir = TempDir::new().unwrap();
+        let mod_folder = temp_dir.path().join("Some Mod-12345-1-2-1690000000");
+        fs::create_dir(&mod_folder).unwrap();
+        assert_eq!(find_mod_page(&mod_folder, true), None);
+
+        fs::write(temp_dir.path().join(VORTEX_MARKER), "").unwrap();
+        let page = find_mod_page(&mod_folder, true).unwrap();
+        assert_eq!(page.url, "https://www.nexusmods.com/starfield/mods/12345");
+    }
+}
@@ -0,0 +1,559 @@
+//! Unpacking archives without `BSArch`
+//!
+//! [`NativeBackend`] reads archives with [`BA2Archive`] and streams every file
+//! to disk through a fixed-size buffer, so the memory used per job does not
+//! depend on the size of the files:
+//!
+//! - Each job takes one copy buffer from a shared pool and returns it when
+//!   the archive is done
+//! - Files up to [`WriterConfig::coalesce_below`] bytes (most of a script or
+//!   misc archive) are unpacked into a batch buffer and written together once
+//!   the batch is full, and folders are only created once per archive
+//! - [`FsyncPolicy`] chooses whether written files are flushed to disk
+//! - Textures can be downscaled by dropping their largest mip levels
+//!   ([`texture_ops`](crate::ba2::texture_ops))
+
+use crate::ba2::archive::{BA2Archive, ReadMode};
+use crate::ba2::extractor::BsarchOutcome;
+use crate::config::AppConfig;
+use crate::error::{BA2Error, Error, Result};
+use crate::operations::extract::ExtractorBackend;
+use crate::platform::drive::StorageMedium;
+use crate::platform::priority::ThreadPriority;
+use crate::platform::wine::CaseFolder;
+use futures::future::BoxFuture;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Copy buffers kept for reuse when jobs finish
+const MAX_IDLE_BUFFERS: usize = 16;
+
+/// When written files are flushed to disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum FsyncPolicy {
+    /// Leave flushing to the operating system
+    #[default]
+    Never,
+    /// Flush all files of an archive before it is reported as done
+    Archive,
+    /// Flush every file as soon as it is written
+    File,
+}
+
+/// Buffer sizes and flushing of the native writer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WriterConfig {
+    /// Size of the buffer files are unpacked through
+    pub buffer_size: usize,
+    /// Files up to this size are written in batches (0 = never)
+    pub coalesce_below: usize,
+    /// Bytes of small files collected before a batch is written
+    pub batch_size: usize,
+    /// When written files are flushed to disk
+    pub fsync: FsyncPolicy,
+}
+
+impl Default for WriterConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: 1024 * 1024,
+            coalesce_below: 64 * 1024,
+            batch_size: 4 * 1024 * 1024,
+            fsync: FsyncPolicy::Never,
+        }
+    }
+}
+
+impl WriterConfig {
+    /// Writer settings from the advanced configuration
+    ///
+    /// An automatic buffer size is picked for the storage medium written to.
+    pub fn from_config(config: &AppConfig, medium: StorageMedium) -> Self {
+        let kib = |value: u32| {
+            usize::try_from(value)
+                .unwrap_or(usize::MAX)
+                .saturating_mul(1024)
+        };
+        let buffer_kb = match config.advanced.performance.write_buffer_kb {
+            0 => medium.buffer_kb(),
+            kb => kb.max(4),
+        };
+        Self {
+            buffer_size: kib(buffer_kb),
+            coalesce_below: kib(config.advanced.performance.coalesce_below_kb),
+            fsync: config.advanced.performance.fsync,
+            ..Self::default()
+        }
+    }
+}
+
+/// Copy buffers shared by the jobs of a backend
+#[derive(Debug)]
+struct BufferPool {
+    idle: Mutex<Vec<Vec<u8>>>,
+    size: usize,
+}
+
+impl BufferPool {
+    fn take(self: &Arc<Self>) -> PooledBuffer {
+        let buffer = self.idle.lock().pop().unwrap_or_else(|| vec![0; self.size]);
+        PooledBuffer {
+            pool: Arc::clone(self),
+            buffer,
+        }
+    }
+}
+
+/// A buffer that goes back to its pool when dropped
+struct PooledBuffer {
+    pool: Arc<BufferPool>,
+    buffer: Vec<u8>,
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        let mut idle = self.pool.idle.lock();
+        if idle.len() < MAX_IDLE_BUFFERS {
+            idle.push(std::mem::take(&mut self.buffer));
+        }
+    }
+}
+
+/// Extraction with the built-in archive reader
+#[derive(Debug)]
+pub struct NativeBackend {
+    read_mode: ReadMode,
+    writer: WriterConfig,
+    texture_skip_mips: u8,
+    pool: Arc<BufferPool>,
+}
+
+impl NativeBackend {
+    /// Read archives as chosen by `read_mode` and write them with `writer`
+    pub fn new(read_mode: ReadMode, writer: WriterConfig) -> Self {
+        let pool = Arc::new(BufferPool {
+            idle: Mutex::default(),
+            size: writer.buffer_size.max(1),
+        });
+        Self {
+            read_mode,
+            writer,
+            texture_skip_mips: 0,
+            pool,
+        }
+    }
+
+    /// Drop up to `levels` of the largest mip levels of every texture
+    #[must_use]
+    pub const fn with_texture_skip_mips(mut self, levels: u8) -> Self {
+        self.texture_skip_mips = levels;
+        self
+    }
+
+    /// Backend with the settings of the advanced configuration for a run on `medium`
+    pub fn from_config(config: &AppConfig, medium: StorageMedium) -> Self {
+        Self::new(
+            config.advanced.performance.archive_read,
+            WriterConfig::from_config(config, medium),
+        )
+        .with_texture_skip_mips(config.extraction.texture_skip_mips)
+    }
+}
+
+impl ExtractorBackend for NativeBackend {
+    fn extract<'a>(
+        &'a self,
+        archive: &'a Path,
+        output_dir: Option<&'a Path>,
+        on_progress: &'a mut (dyn FnMut(u8) + Send),
+    ) -> BoxFuture<'a, Result<BsarchOutcome>> {
+        Box::pin(async move {
+            let Some(output) = output_dir.or_else(|| archive.parent()) else {
+                return Err(BA2Error::ExtractionFailed {
+                    path: archive.to_path_buf(),
+                    reason: "BA2 file path has no parent directory".to_string(),
+                }
+                .into());
+            };
+
+            let job = Job {
+                archive: archive.to_path_buf(),
+                output: output.to_path_buf(),
+                read_mode: self.read_mode,
+                writer: self.writer.clone(),
+                texture_skip_mips: self.texture_skip_mips,
+                pool: Arc::clone(&self.pool),
+                stop: Arc::new(AtomicBool::new(false)),
+            };
+            // Stop the blocking job if the extraction is cancelled (dropped)
+            let _stop = StopOnDrop(Arc::clone(&job.stop));
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+            let task = tokio::task::spawn_blocking(move || {
+                // Follows the low-priority mode, which can be switched during the run
+                let mut priority = ThreadPriority::new();
+                job.run(|percent| {
+                    priority.sync();
+                    let _ = tx.send(percent);
+                })
+            });
+            while let Some(percent) = rx.recv().await {
+                on_progress(percent);
+            }
+            task.await
+                .map_err(|e| Error::other(format!("Unpacking task failed: {e}")))?
+        })
+    }
+
+    fn texture_skip_mips(&self) -> u8 {
+        self.texture_skip_mips
+    }
+}
+
+/// Sets the stop flag of a job when dropped
+struct StopOnDrop(Arc<AtomicBool>);
+
+impl Drop for StopOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Unpacking of one archive on a blocking thread
+struct Job {
+    archive: PathBuf,
+    output: PathBuf,
+    read_mode: ReadMode,
+    writer: WriterConfig,
+    texture_skip_mips: u8,
+    pool: Arc<BufferPool>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Job {
+    fn run(&self, mut on_progress: impl FnMut(u8)) -> Result<BsarchOutcome> {
+        let archive = BA2Archive::open_with(&self.archive, self.read_mode)?;
+        let total = archive.entries().len() + archive.textures().len();
+        let mut writer = ArchiveWriter::new(&self.archive, &self.output, &self.writer);
+        let mut pooled = self.pool.take();
+        let buffer = &mut pooled.buffer;
+
+        let mut done = 0;
+        let mut last_percent = 0;
+        let mut file_done = |done: usize| {
+            let percent = u8::try_from(done * 100 / total.max(1)).unwrap_or(100);
+            if percent != last_percent {
+                last_percent = percent;
+                on_progress(percent);
+            }
+        };
+        for entry in archive.entries() {
+            self.check_stop()?;
+            writer.add(&entry.name, u64::from(entry.unpacked_size), |out| {
+                archive.write_entry(entry, out, buffer)
+            })?;
+            done += 1;
+            file_done(done);
+        }
+        for texture in archive.textures() {
+            self.check_stop()?;
+            writer.add(&texture.name, texture.unpacked_size(), |out| {
+                archive.write_texture_downscaled(texture, self.texture_skip_mips, out, buffer)
+            })?;
+            done += 1;
+            file_done(done);
+        }
+        writer.finish()?;
+
+        let files = u32::try_from(total).unwrap_or(u32::MAX);
+        Ok(BsarchOutcome {
+            file_count: Some(files),
+            files_done: files,
+            ..BsarchOutcome::default()
+        })
+    }
+
+    fn check_stop(&self) -> Result<()> {
+        if self.stop.load(Ordering::Relaxed) {
+            Err(Error::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Writes the files of one archive below an output folder
+struct ArchiveWriter<'a> {
+    archive: &'a Path,
+    root: &'a Path,
+    config: &'a WriterConfig,
+    /// Folders already created
+    dirs: HashSet<PathBuf>,
+    /// Output paths matched to existing files and folders ignoring case
+    case: CaseFolder,
+    /// Data of small files waiting to be written
+    batch: Vec<u8>,
+    pending: Vec<(PathBuf, Range<usize>)>,
+    /// Files to flush when the archive is done ([`FsyncPolicy::Archive`])
+    unsynced: Vec<PathBuf>,
+}
+
+impl<'a> ArchiveWriter<'a> {
+    fn new(archive: &'a Path, root: &'a Path, config: &'a WriterConfig) -> Self {
+        Self {
+            archive,
+            root,
+            config,
+            dirs: HashSet::new(),
+            case: CaseFolder::new(),
+            batch: Vec::new(),
+            pending: Vec::new(),
+            unsynced: Vec::new(),
+        }
+    }
+
+    /// Write the file `name` of `size` bytes, whose data `write_data` writes
+    fn add(
+        &mut self,
+        name: &str,
+        size: u64,
+        write_data: impl FnOnce(&mut dyn Write) -> Result<u64>,
+    ) -> Result<()> {
+        let path = self.path_for(name)?;
+        if size <= self.config.coalesce_below as u64 {
+            let start = self.batch.len();
+            write_data(&mut self.batch)?;
+            self.pending.push((path, start..self.batch.len()));
+            if self.batch.len() >= self.config.batch_size {
+                self.flush_batch()?;
+            }
+            return Ok(());
+        }
+
+        self.create_parent(&path)?;
+        let mut file = File::create(&path)?;
+        write_data(&mut file)?;
+        self.written(&file, path)
+    }
+
+    /// Write the collected small files
+    fn flush_batch(&mut self) -> Result<()> {
+        let pending = std::mem::take(&mut self.pending);
+        for (path, range) in pending {
+            self.create_parent(&path)?;
+            let mut file = File::create(&path)?;
+            file.write_all(&self.batch[range])?;
+            self.written(&file, path)?;
+        }
+        self.batch.clear();
+        Ok(())
+    }
+
+    fn written(&mut self, file: &File, path: PathBuf) -> Result<()> {
+        match self.config.fsync {
+            FsyncPolicy::Never => {}
+            FsyncPolicy::Archive => self.unsynced.push(path),
+            FsyncPolicy::File => file.sync_all()?,
+        }
+        Ok(())
+    }
+
+    /// Write what is left and flush the files if the policy asks for it
+    fn finish(mut self) -> Result<()> {
+        self.flush_batch()?;
+        for path in &self.unsynced {
+            OpenOptions::new().write(true).open(path)?.sync_all()?;
+        }
+        Ok(())
+    }
+
+    fn create_parent(&mut self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent()
+            && !self.dirs.contains(parent)
+        {
+            fs::create_dir_all(parent)?;
+            self.dirs.insert(parent.to_path_buf());
+        }
+        Ok(())
+    }
+
+    /// Output path of an archive-internal name, refusing names that leave the output folder
+    ///
+    /// Folders that exist with another case are reused, as on Windows.
+    fn path_for(&mut self, name: &str) -> Result<PathBuf> {
+        let mut path = self.root.to_path_buf();
+        for part in name
+            .split(['\\', '/'])
+            .filter(|p| !p.is_empty() && *p != ".")
+        {
+            let mut components = Path::new(part).components();
+            if !matches!(
+                (components.next(), components.next()),
+                (Some(Component::Normal(_)), None)
+            ) {
+                return Err(BA2Error::Corrupted {
+                    path: self.archive.to_path_buf(),
+                    reason: format!("File name '{name}' points outside the output folder"),
+                }
+                .into());
+            }
+            path = self.case.child(&path, OsStr::new(part));
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ba2::dds;
+    use crate::testing::fixtures::{
+        FixtureFile, FixtureTexture, TEXTURE_FORMAT, dx10_archive, entry_data, gnrl_archive,
+        write_dx10, write_gnrl,
+    };
+
+    const NAMES: &[&str] = &[
+        "meshes\\armor\\helmet.nif",
+        "meshes\\armor\\boots.nif",
+        "scripts\\quest.pex",
+    ];
+
+    async fn extract(backend: &NativeBackend, archive: &Path) -> Result<(BsarchOutcome, Vec<u8>)> {
+        let mut progress = Vec::new();
+        let outcome = backend
+            .extract(archive, None, &mut |percent| progress.push(percent))
+            .await?;
+        Ok((outcome, progress))
+    }
+
+    fn check_files(root: &Path, names: &[&str]) {
+        for name in names {
+            let path = name
+                .split('\\')
+                .fold(root.to_path_buf(), |p, part| p.join(part));
+            assert_eq!(fs::read(&path).unwrap(), entry_data(name), "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_native_extraction() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("Mod - Main.ba2");
+        write_gnrl(&archive, NAMES).unwrap();
+
+        // Batched small files, files written one by one, and flushing
+        let configs = [
+            WriterConfig::default(),
+            WriterConfig {
+                buffer_size: 3,
+                coalesce_below: 0,
+                fsync: FsyncPolicy::File,
+                ..WriterConfig::default()
+            },
+            WriterConfig {
+                batch_size: 1,
+                fsync: FsyncPolicy::Archive,
+                ..WriterConfig::default()
+            },
+        ];
+        for config in configs {
+            fs::remove_dir_all(dir.path().join("meshes")).ok();
+            let backend = NativeBackend::new(ReadMode::Buffered, config);
+            let (outcome, progress) = extract(&backend, &archive).await.unwrap();
+            assert_eq!(outcome.files_done, 3);
+            assert_eq!(progress.last(), Some(&100));
+            check_files(dir.path(), NAMES);
+        }
+    }
+
+    #[test]
+    fn test_writer_buffer_follows_medium() {
+        let mut config = AppConfig::default();
+        let hdd = WriterConfig::from_config(&config, StorageMedium::Hdd);
+        let nvme = WriterConfig::from_config(&config, StorageMedium::Nvme);
+        assert!(hdd.buffer_size > nvme.buffer_size);
+
+        config.advanced.performance.write_buffer_kb = 256;
+        assert_eq!(
+            WriterConfig::from_config(&config, StorageMedium::Hdd).buffer_size,
+            256 * 1024
+        );
+    }
+
+    #[tokio::test]
+    async fn test_native_texture_extraction() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("Mod - Textures.ba2");
+        write_dx10(&archive, &["textures\\sky.dds"]).unwrap();
+        let backend = NativeBackend::new(ReadMode::Mapped, WriterConfig::default());
+
+        extract(&backend, &archive).await.unwrap();
+        let dds = fs::read(dir.path().join("textures").join("sky.dds")).unwrap();
+        assert_eq!(&dds[..4], b"DDS ");
+        assert!(dds.ends_with(&FixtureTexture::named("textures\\sky.dds").data));
+    }
+
+    #[tokio::test]
+    async fn test_native_texture_downscale() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("Mod - Textures.ba2");
+        let fixture = FixtureTexture::with_mips("textures\\sky.dds", 16, 16, 3);
+        fs::write(&archive, dx10_archive(std::slice::from_ref(&fixture))).unwrap();
+        let backend =
+            NativeBackend::new(ReadMode::Mapped, WriterConfig::default()).with_texture_skip_mips(1);
+        assert_eq!(backend.texture_skip_mips(), 1);
+
+        extract(&backend, &archive).await.unwrap();
+        let dds = fs::read(dir.path().join("textures").join("sky.dds")).unwrap();
+        let pixels = &fixture.data[16 * 16 * 4..];
+        assert_eq!(dds.len(), dds::header_size(TEXTURE_FORMAT) + pixels.len());
+        assert!(dds.ends_with(pixels));
+    }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn test_folders_are_reused_ignoring_case() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("Mod - Main.ba2");
+        let names = [
+            "Meshes\\a.nif",
+            "meshes\\Armor\\b.nif",
+            "MESHES\\armor\\c.nif",
+        ];
+        write_gnrl(&archive, &names).unwrap();
+        fs::create_dir(dir.path().join("meshes")).unwrap();
+
+        let backend = NativeBackend::new(ReadMode::Buffered, WriterConfig::default());
+        extract(&backend, &archive).await.unwrap();
+        for (name, path) in names.iter().zip(["a.nif", "Armor/b.nif", "Armor/c.nif"]) {
+            let written = fs::read(dir.path().join("meshes").join(path)).unwrap();
+            assert_eq!(written, entry_data(name), "{name}");
+        }
+        assert!(!dir.path().join("Meshes").exists());
+    }
+
+    #[tokio::test]
+    async fn test_names_outside_output_folder_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("Mod").join("Evil.ba2");
+        fs::create_dir_all(archive.parent().unwrap()).unwrap();
+        fs::write(
+            &archive,
+            gnrl_archive(&[FixtureFile::named("..\\..\\outside.txt")]),
+        )
+        .unwrap();
+        let backend = NativeBackend::new(ReadMode::Buffered, WriterConfig::default());
+
+        let error = extract(&backend, &archive).await.unwrap_err();
+        assert!(error.is_ba2_corrupted());
+        assert!(!dir.path().join("outside.txt").exists());
+    }
+}
@@ -0,0 +1,218 @@
+//! Notes and tags on mods and archives
+//!
+//! Users can attach a short note ("check after the next update") and tags
+//! ("keep packed", "test later") to a mod folder or a single archive, to
+//! remember decisions across sessions. Notes are kept in `notes.json` next
+//! to the configuration, keyed by the path relative to the scanned folder:
+//! the mod folder name for a mod, `Mod/Archive - Main.ba2` for an archive.
+//!
+//! An archive shows its own note, or its mod's without one, along with the
+//! tags of both.
+
+use crate::config::AppConfig;
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Name of the notes file in the configuration folder
+const NOTES_FILE: &str = "notes.json";
+
+/// A note and tags attached to a mod or an archive
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Note {
+    /// Free text, empty if there is none
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub text: String,
+
+    /// Tags, in the order they were given
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl Note {
+    /// An empty note
+    pub const fn new() -> Self {
+        Self {
+            text: String::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    /// A note from its text and comma-separated tags, both trimmed
+    pub fn parse(text: &str, tags: &str) -> Self {
+        let mut note = Self {
+            text: text.trim().to_string(),
+            tags: Vec::new(),
+        };
+        for tag in tags.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+            note.add_tag(tag);
+        }
+        note
+    }
+
+    /// Whether there is neither text nor tags
+    pub const fn is_empty(&self) -> bool {
+        self.text.is_empty() && self.tags.is_empty()
+    }
+
+    /// Tags as typed in the editor ("keep packed, test later")
+    pub fn tags_text(&self) -> String {
+        self.tags.join(", ")
+    }
+
+    /// Whether the text or a tag contains `query`, ignoring case
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        query.is_empty()
+            || self.text.to_lowercase().contains(&query)
+            || self
+                .tags
+                .iter()
+                .any(|tag| tag.to_lowercase().contains(&query))
+    }
+
+    /// Add a tag unless the note has it already, ignoring case
+    fn add_tag(&mut self, tag: &str) {
+        if !self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            self.tags.push(tag.to_string());
+        }
+    }
+}
+
+/// Notes of all mods and archives, by relative path
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModNotes {
+    /// Notes by mod folder name or `Mod/Archive.ba2`
+    #[serde(default)]
+    pub notes: BTreeMap<String, Note>,
+}
+
+impl ModNotes {
+    /// Get the path of the notes file
+    pub fn file_path() -> Result<PathBuf> {
+        Ok(AppConfig::config_dir()?.join(NOTES_FILE))
+    }
+
+    /// Load the notes, or none if there are none yet
+    pub fn load() -> Self {
+        let Ok(path) = Self::file_path() else {
+            return Self::default();
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring unreadable notes file {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// Save the notes to the configuration folder
+    pub fn save(&self) -> Result<()> {
+        let path = Self::file_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| Error::Other(format!("Failed to serialize notes: {e}")))?;
+        fs::write(&path, content)?;
+        Ok(())
+    }
+
+    /// Key of an archive's note
+    pub fn archive_key(dir_name: &str, file_name: &str) -> String {
+        format!("{dir_name}/{file_name}")
+    }
+
+    /// The note stored under `key`, if any
+    pub fn get(&self, key: &str) -> Option<&Note> {
+        self.notes.get(key)
+    }
+
+    /// Store a note under `key`, removing it if it is empty
+    pub fn set(&mut self, key: impl Into<String>, note: Note) {
+        let key = key.into();
+        if note.is_empty() {
+            self.notes.remove(&key);
+        } else {
+            self.notes.insert(key, note);
+        }
+    }
+
+    /// The note shown for an archive: its own text, or its mod's, and the
+    /// tags of both
+    pub fn for_archive(&self, dir_name: &str, file_name: &str) -> Note {
+        let own = self.get(&Self::archive_key(dir_name, file_name));
+        let of_mod = self.get(dir_name);
+        let mut note = Note::new();
+        if let Some(text) = [own, of_mod]
+            .into_iter()
+            .flatten()
+            .map(|n| &n.text)
+            .find(|text| !text.is_empty())
+        {
+            note.text.clone_from(text);
+        }
+        for tag in [own, of_mod].into_iter().flatten().flat_map(|n| &n.tags) {
+            note.add_tag(tag);
+        }
+        note
+    }
+
+    /// Every tag in use, sorted, for suggestions
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.notes.values().flat_map(|n| &n.tags) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
+            }
+        }
+        tags.sort_by_key(|tag| tag.to_lowercase());
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_note() {
+        let note = Note::parse(
+            "  retest after update ",
+            "keep packed, , Test later,KEEP PACKED",
+        );
+        assert_eq!(note.text, "retest after update");
+        assert_eq!(note.tags, ["keep packed", "Test later"]);
+        assert_eq!(note.tags_text(), "keep packed, Test later");
+        assert!(note.matches("LATER"));
+        assert!(note.matches("after"));
+        assert!(!note.matches("broken"));
+        assert!(Note::parse(" ", " , ").is_empty());
+    }
+
+    #[test]
+    fn test_archive_note() {
+        let mut notes = ModNotes::default();
+        notes.set("Mod", Note::parse("whole mod", "test later"));
+        notes.set(
+            ModNotes::archive_key("Mod", "Mod - Textures.ba2"),
+            Note::parse("", "keep packed"),
+        );
+
+        let note = notes.for_archive("Mod", "Mod - Textures.ba2");
+        assert_eq!(note.text, "whole mod");
+        assert_eq!(note.tags, ["keep packed", "test later"]);
+        assert!(notes.for_archive("Other", "Other - Main.ba2").is_empty());
+        assert_eq!(notes.tags(), ["keep packed", "test later"]);
+
+        // Empty notes are removed
+        notes.set("Mod", Note::new());
+        assert!(notes.get("Mod").is_none());
+
+        let json = serde_json::to_string(&notes).unwrap();
+        assert_eq!(serde_json::from_str::<ModNotes>(&json).unwrap(), notes);
+    }
+}
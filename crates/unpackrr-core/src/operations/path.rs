@@ -5,10 +5,15 @@
 //! - Path canonicalization
 //! - Relative/absolute path resolution
 //! - Case-insensitive path comparison (Windows)
+//! - Extended-length (`\\?\`) paths for outputs beyond `MAX_PATH` (Windows)
 
 use crate::error::Result;
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 
+/// Classic Windows path length limit (in UTF-16 code units, including the drive)
+pub const MAX_PATH: usize = 260;
+
 /// Canonicalize a path, handling Windows UNC paths correctly
 ///
 /// This function uses the `dunce` crate to strip the `\\?\` prefix that
@@ -26,7 +31,7 @@ use std::path::{Path, PathBuf};
 ///
 /// ```no_run
 /// use std::path::Path;
-/// use unpackrr::operations::path::canonicalize_path;
+/// use unpackrr_core::operations::path::canonicalize_path;
 ///
 /// # fn example() -> anyhow::Result<()> {
 /// let path = Path::new("C:/Games/../Games/Fallout4");
@@ -68,7 +73,7 @@ pub fn canonicalize_path(path: &Path) -> Result<PathBuf> {
 ///
 /// ```no_run
 /// use std::path::Path;
-/// use unpackrr::operations::path::resolve_path;
+/// use unpackrr_core::operations::path::resolve_path;
 ///
 /// # fn example() -> anyhow::Result<()> {
 /// let base = Path::new("C:/Games/Fallout4/Data");
@@ -105,7 +110,7 @@ pub fn resolve_path(path: &Path, base: &Path) -> Result<PathBuf> {
 ///
 /// ```
 /// use std::path::Path;
-/// use unpackrr::operations::path::paths_equal;
+/// use unpackrr_core::operations::path::paths_equal;
 ///
 /// let path1 = Path::new("C:/Games/Fallout4");
 /// let path2 = Path::new("c:/games/fallout4");
@@ -146,7 +151,7 @@ pub fn paths_equal(a: &Path, b: &Path) -> bool {
 /// # Example
 ///
 /// ```
-/// use unpackrr::operations::path::normalize_separators;
+/// use unpackrr_core::operations::path::normalize_separators;
 ///
 /// let path = "C:\\Games\\Fallout4\\Data";
 /// let normalized = normalize_separators(path);
@@ -197,12 +202,112 @@ pub fn get_parent(path: &Path) -> Option<PathBuf> {
     path.parent().map(std::path::Path::to_path_buf)
 }
 
+/// Convert a file name to a displayable string without dropping it
+///
+/// Valid Unicode names are returned as-is. Names that aren't (unpaired
+/// surrogates on Windows, non-UTF-8 bytes elsewhere) are converted lossily and
+/// logged, so callers must keep the original path for file system access.
+pub fn display_name(name: &OsStr) -> String {
+    name.to_str().map_or_else(
+        || {
+            let lossy = name.to_string_lossy().into_owned();
+            tracing::warn!("File name is not valid Unicode, displaying as {}", lossy);
+            lossy
+        },
+        str::to_string,
+    )
+}
+
+/// Length of a path as Windows counts it (UTF-16 code units)
+pub fn windows_path_len(path: &str) -> usize {
+    path.encode_utf16().count()
+}
+
+/// Get the length of the longest path produced by extracting `names` into `output_dir`
+///
+/// `names` are archive-internal paths; returns 0 if there are none.
+pub fn longest_output_path(output_dir: &Path, names: &[String]) -> usize {
+    let base = windows_path_len(&output_dir.to_string_lossy());
+    names
+        .iter()
+        .map(|name| base + 1 + windows_path_len(name))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Convert a Windows path string to its extended-length form
+///
+/// - `C:\dir` becomes `\\?\C:\dir`
+/// - `\\server\share` becomes `\\?\UNC\server\share`
+/// - Already-prefixed and relative paths are returned unchanged
+///
+/// Forward slashes are converted to backslashes, since extended-length paths
+/// are passed to the file system without normalization.
+pub fn extended_length_path(path: &str) -> String {
+    if path.starts_with(r"\\?\") || path.starts_with(r"\\.\") {
+        return path.to_string();
+    }
+
+    let path = path.replace('/', "\\");
+    match path.strip_prefix(r"\\") {
+        Some(unc) => format!(r"\\?\UNC\{unc}"),
+        None if path.as_bytes().get(1) == Some(&b':') => format!(r"\\?\{path}"),
+        None => path,
+    }
+}
+
+/// Prepare a path for tools that must handle outputs longer than [`MAX_PATH`]
+///
+/// On Windows this returns the extended-length form; elsewhere the path is
+/// returned unchanged.
+pub fn to_extended_length(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        PathBuf::from(extended_length_path(&path.to_string_lossy()))
+    }
+
+    #[cfg(not(windows))]
+    {
+        path.to_path_buf()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_extended_length_path() {
+        assert_eq!(
+            extended_length_path(r"C:\Games\Mods"),
+            r"\\?\C:\Games\Mods"
+        );
+        assert_eq!(
+            extended_length_path("C:/Games/Mods"),
+            r"\\?\C:\Games\Mods"
+        );
+        assert_eq!(
+            extended_length_path(r"\\nas\share\Mods"),
+            r"\\?\UNC\nas\share\Mods"
+        );
+        assert_eq!(
+            extended_length_path(r"\\?\C:\Games"),
+            r"\\?\C:\Games"
+        );
+        assert_eq!(extended_length_path(r"relative\dir"), r"relative\dir");
+    }
+
+    #[test]
+    fn test_longest_output_path() {
+        let names = vec!["a\\b.nif".to_string(), "textures\\模组.dds".to_string()];
+        let output = Path::new("/mods/ModA");
+        assert_eq!(longest_output_path(output, &names), 10 + 1 + 15);
+        assert_eq!(longest_output_path(output, &[]), 0);
+        assert_eq!(windows_path_len("🎮"), 2);
+    }
+
     #[test]
     fn test_canonicalize_path() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,404 @@
+//! Checks before an extraction starts
+//!
+//! [`preflight`] reads the file tables of the archives about to be extracted
+//! and summarizes the run for the confirmation dialog:
+//!
+//! - The size of the files once unpacked, from the archive tables (archives
+//!   that cannot be read count with their own size)
+//! - The folders files are written to
+//! - Loose files the run would overwrite, and files packed in more than one
+//!   archive of the same mod, where the last archive extracted wins
+//! - Whether the drive has room for the unpacked files
+//! - Archives that unpack straight into the game Data folder
+//! - Mod folders the run pushes past the loose file limit, checked separately
+//!   with [`PreflightReport::check_loose_files`] since it walks the folders
+
+use crate::ba2::archive::BA2Archive;
+use crate::config::profile::Game;
+use crate::models::FileEntry;
+use crate::operations::format::{format_count, format_size};
+use crate::operations::loose_stats::{CrowdedFolder, LooseStatsCache, crowded_folders};
+use crate::platform::drive::available_space;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Something to look at before extracting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreflightWarning {
+    /// The drive of a destination has less free space than the run needs
+    LowDiskSpace {
+        /// Destination on the drive
+        destination: PathBuf,
+        /// Bytes the run writes
+        needed: u64,
+        /// Bytes free on the drive
+        available: u64,
+    },
+    /// Loose files already in the mod folders would be overwritten
+    OverwritesLooseFiles {
+        /// Number of existing files
+        count: usize,
+    },
+    /// Files packed in more than one archive of the same mod
+    ConflictingFiles {
+        /// Number of files
+        count: usize,
+    },
+    /// Archives unpack into the game Data folder, outside any mod manager
+    DataFolder {
+        /// The Data folder
+        folder: PathBuf,
+        /// Number of archives extracted there
+        archives: usize,
+    },
+    /// Mod folders would hold more loose files than mod managers list quickly
+    CrowdedFolders {
+        /// The folders, most files first
+        folders: Vec<CrowdedFolder>,
+        /// The configured limit
+        limit: usize,
+    },
+}
+
+impl PreflightWarning {
+    /// Text shown in the confirmation dialog
+    pub fn message(&self) -> String {
+        match self {
+            Self::LowDiskSpace {
+                destination,
+                needed,
+                available,
+            } => format!(
+                "Only {} free on the drive of {}, but about {} will be written",
+                format_size(*available),
+                destination.display(),
+                format_size(*needed)
+            ),
+            Self::OverwritesLooseFiles { count } => format!(
+                "{} loose files already in the mod folders will be overwritten",
+                format_count(*count as u64)
+            ),
+            Self::ConflictingFiles { count } => format!(
+                "{} files are packed in more than one archive of the same mod; the last one extracted wins",
+                format_count(*count as u64)
+            ),
+            Self::DataFolder { folder, archives } => format!(
+                "{} archives unpack straight into the game Data folder {}. Overwritten loose files are backed up first, but the new loose files can only be removed by hand",
+                format_count(*archives as u64),
+                folder.display()
+            ),
+            Self::CrowdedFolders { folders, limit } => {
+                const MAX_LISTED: usize = 3;
+                let mut listed: Vec<String> = folders
+                    .iter()
+                    .take(MAX_LISTED)
+                    .map(|crowded| {
+                        format!(
+                            "{} ({} → {})",
+                            crowded.folder.file_name().map_or_else(
+                                || crowded.folder.display().to_string(),
+                                |name| { name.to_string_lossy().into_owned() }
+                            ),
+                            format_count(crowded.existing as u64),
+                            format_count(crowded.after as u64)
+                        )
+                    })
+                    .collect();
+                if folders.len() > MAX_LISTED {
+                    listed.push(format!(
+                        "and {} more",
+                        format_count((folders.len() - MAX_LISTED) as u64)
+                    ));
+                }
+                format!(
+                    "{} mod folders will hold more than {} loose files, which slows down mod managers listing them at every game start: {}",
+                    format_count(folders.len() as u64),
+                    format_count(*limit as u64),
+                    listed.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Summary of an extraction about to start
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PreflightReport {
+    /// Archives to extract
+    pub archives: usize,
+
+    /// Combined size of the archives in bytes
+    pub total_size: u64,
+
+    /// Estimated size of the unpacked files in bytes
+    pub estimated_output: u64,
+
+    /// Folders the files are written to, sorted
+    pub destinations: Vec<PathBuf>,
+
+    /// Files each destination gains: packed files that are not there yet
+    pub new_files: BTreeMap<PathBuf, usize>,
+
+    /// Problems found
+    pub warnings: Vec<PreflightWarning>,
+}
+
+impl PreflightReport {
+    /// Whether the run writes into a game Data folder and needs an explicit confirmation
+    pub fn writes_to_data_folder(&self) -> bool {
+        self.warnings
+            .iter()
+            .any(|warning| matches!(warning, PreflightWarning::DataFolder { .. }))
+    }
+
+    /// Warn about mod folders the run would push past `limit` loose files
+    /// (0 = no limit)
+    ///
+    /// The game Data folder is not checked; it is not listed by mod managers.
+    pub fn check_loose_files(&mut self, limit: usize, cache: &LooseStatsCache) {
+        let added = self
+            .new_files
+            .iter()
+            .filter(|(folder, _)| Game::of_data_folder(folder).is_none())
+            .map(|(folder, count)| (folder.as_path(), *count));
+        let folders = crowded_folders(added, limit, cache);
+        if !folders.is_empty() {
+            self.warnings
+                .push(PreflightWarning::CrowdedFolders { folders, limit });
+        }
+    }
+}
+
+/// What one archive writes
+struct ArchivePlan {
+    destination: PathBuf,
+    output_size: u64,
+    /// Paths of the packed files with `/` separators
+    files: Vec<String>,
+}
+
+impl ArchivePlan {
+    fn read(file: &FileEntry) -> Self {
+        let destination = file
+            .full_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        match BA2Archive::open(&file.full_path) {
+            Ok(archive) => Self {
+                destination,
+                output_size: archive.unpacked_size(),
+                files: archive
+                    .names()
+                    .iter()
+                    .map(|name| name.replace('\\', "/"))
+                    .collect(),
+            },
+            Err(e) => {
+                tracing::debug!(
+                    "Could not read the file table of {}: {}",
+                    file.full_path.display(),
+                    e
+                );
+                Self {
+                    destination,
+                    output_size: file.file_size,
+                    files: Vec::new(),
+                }
+            }
+        }
+    }
+
+    /// Packed files that already exist as loose files
+    fn existing_files(&self) -> usize {
+        self.files
+            .iter()
+            .filter(|name| self.destination.join(name).exists())
+            .count()
+    }
+}
+
+/// Summarize an extraction of `files` and check it for problems
+///
+/// Free space is checked against each destination's drive, assuming all
+/// output lands on the drive with the least room.
+pub fn preflight(files: &[FileEntry]) -> PreflightReport {
+    let plans: Vec<ArchivePlan> = files.par_iter().map(ArchivePlan::read).collect();
+    let estimated_output = plans.iter().map(|plan| plan.output_size).sum();
+    let destinations: Vec<PathBuf> = plans
+        .iter()
+        .map(|plan| plan.destination.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let mut warnings = Vec::new();
+
+    if let Some((destination, available)) = destinations
+        .iter()
+        .filter_map(|destination| Some((destination, available_space(destination)?)))
+        .min_by_key(|(_, available)| *available)
+        && available < estimated_output
+    {
+        warnings.push(PreflightWarning::LowDiskSpace {
+            destination: destination.clone(),
+            needed: estimated_output,
+            available,
+        });
+    }
+
+    let existing_per_plan: Vec<usize> = plans.par_iter().map(ArchivePlan::existing_files).collect();
+    let existing: usize = existing_per_plan.iter().sum();
+    let mut new_files: BTreeMap<PathBuf, usize> = BTreeMap::new();
+    for (plan, existing) in plans.iter().zip(&existing_per_plan) {
+        *new_files.entry(plan.destination.clone()).or_default() += plan.files.len() - existing;
+    }
+    if existing > 0 {
+        warnings.push(PreflightWarning::OverwritesLooseFiles { count: existing });
+    }
+
+    for destination in destinations
+        .iter()
+        .filter(|destination| Game::of_data_folder(destination).is_some())
+    {
+        warnings.push(PreflightWarning::DataFolder {
+            folder: destination.clone(),
+            archives: plans
+                .iter()
+                .filter(|plan| plan.destination == *destination)
+                .count(),
+        });
+    }
+
+    // Archive paths are case-insensitive, like the game's
+    let mut packed: HashMap<(&Path, String), usize> = HashMap::new();
+    for plan in &plans {
+        for name in &plan.files {
+            *packed
+                .entry((plan.destination.as_path(), name.to_lowercase()))
+                .or_default() += 1;
+        }
+    }
+    let conflicting = packed.values().filter(|&&count| count > 1).count();
+    if conflicting > 0 {
+        warnings.push(PreflightWarning::ConflictingFiles { count: conflicting });
+    }
+
+    PreflightReport {
+        archives: files.len(),
+        total_size: files.iter().map(|file| file.file_size).sum(),
+        estimated_output,
+        destinations,
+        new_files,
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::fixtures::{file_entry, write_gnrl};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_preflight_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path().join("Mod");
+        std::fs::create_dir_all(mod_dir.join("meshes")).unwrap();
+        std::fs::write(mod_dir.join("meshes/a.nif"), b"loose").unwrap();
+
+        let main = mod_dir.join("Mod - Main.ba2");
+        let patch = mod_dir.join("Mod - Patch.ba2");
+        write_gnrl(&main, &[r"meshes\a.nif", r"meshes\b.nif"]).unwrap();
+        write_gnrl(&patch, &[r"Meshes\B.nif"]).unwrap();
+        let files = [file_entry(&main).unwrap(), file_entry(&patch).unwrap()];
+
+        let report = preflight(&files);
+        assert_eq!(report.archives, 2);
+        assert_eq!(report.destinations, [mod_dir]);
+        assert!(report.estimated_output > 0);
+        assert!(
+            report
+                .warnings
+                .contains(&PreflightWarning::OverwritesLooseFiles { count: 1 })
+        );
+        assert!(
+            report
+                .warnings
+                .contains(&PreflightWarning::ConflictingFiles { count: 1 })
+        );
+    }
+
+    #[test]
+    fn test_check_loose_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path().join("Mod");
+        std::fs::create_dir_all(mod_dir.join("meshes")).unwrap();
+        std::fs::write(mod_dir.join("meshes/a.nif"), b"loose").unwrap();
+        let archive = mod_dir.join("Mod - Main.ba2");
+        write_gnrl(
+            &archive,
+            &[r"meshes\a.nif", r"meshes\b.nif", r"meshes\c.nif"],
+        )
+        .unwrap();
+
+        let mut report = preflight(&[file_entry(&archive).unwrap()]);
+        assert_eq!(report.new_files.get(&mod_dir), Some(&2));
+
+        let cache = LooseStatsCache::new();
+        let before = report.warnings.len();
+        report.check_loose_files(5, &cache);
+        assert_eq!(report.warnings.len(), before);
+        report.check_loose_files(2, &cache);
+        assert_eq!(
+            report.warnings[before..],
+            [PreflightWarning::CrowdedFolders {
+                folders: vec![CrowdedFolder {
+                    folder: mod_dir,
+                    existing: 1,
+                    after: 3,
+                }],
+                limit: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_preflight_data_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let data = temp_dir.path().join("Data");
+        std::fs::create_dir_all(&data).unwrap();
+        let archive = data.join("Mod - Main.ba2");
+        write_gnrl(&archive, &[r"meshes\a.nif"]).unwrap();
+        let files = [file_entry(&archive).unwrap()];
+        assert!(!preflight(&files).writes_to_data_folder());
+
+        std::fs::write(temp_dir.path().join("Starfield.exe"), b"").unwrap();
+        let report = preflight(&files);
+        assert!(report.writes_to_data_folder());
+        assert!(report.warnings.contains(&PreflightWarning::DataFolder {
+            folder: data,
+            archives: 1,
+        }));
+    }
+
+    #[test]
+    fn test_preflight_unreadable_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("Mod").join("Mod - Main.ba2");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, b"not an archive").unwrap();
+        let entry = FileEntry::new("Mod - Main.ba2".to_string(), 14, 0, "Mod".to_string(), path);
+
+        // An unreadable archive counts with its own size
+        let report = preflight(&[entry]);
+        assert_eq!(report.estimated_output, 14);
+        assert!(
+            !report
+                .warnings
+                .iter()
+                .any(|w| matches!(w, PreflightWarning::ConflictingFiles { .. }))
+        );
+    }
+}
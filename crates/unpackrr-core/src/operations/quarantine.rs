@@ -0,0 +1,264 @@
+//! Quarantine of corrupted archives
+//!
+//! Archives whose header cannot be read are skipped when `ignore_bad_files`
+//! is on. From the Quarantine view they can be moved out of the mod folder
+//! into a quarantine folder, so the game and the next scan no longer see
+//! them. Every move is recorded with its corruption reason in
+//! `quarantine.json` inside the quarantine folder.
+//!
+//! A move that stops after copying the archive is recorded once
+//! [`QuarantineManager::finish`] removes the original.
+//!
+//! After re-downloading a mod, its archives can be validated again without a
+//! full rescan.
+
+use crate::ba2::BA2Header;
+use crate::error::{Error, Result};
+use crate::models::FileEntry;
+use crate::operations::journal::{Operation, finish_partial_move, move_file};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name of the record file in the quarantine folder
+const RECORD_FILE: &str = "quarantine.json";
+
+/// Numbered names tried for an archive whose name is taken
+const MAX_NUMBERED_NAMES: u32 = 1000;
+
+/// A quarantined archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuarantineRecord {
+    /// Where the archive was found
+    pub original_path: PathBuf,
+
+    /// Where the archive was moved to
+    pub quarantined_path: PathBuf,
+
+    /// Why the archive is considered corrupted
+    pub reason: String,
+
+    /// Time of the move (seconds since the Unix epoch)
+    pub quarantined_at: u64,
+}
+
+/// Moves corrupted archives into a quarantine folder and keeps their records
+#[derive(Debug, Clone)]
+pub struct QuarantineManager {
+    root: PathBuf,
+}
+
+/// Get the default quarantine folder
+pub fn default_quarantine_root() -> Option<PathBuf> {
+    ProjectDirs::from("com", "evildarkarchon", "unpackrr")
+        .map(|dirs| dirs.data_local_dir().join("quarantine"))
+}
+
+/// Read an archive's header again, e.g. after re-downloading it
+pub fn revalidate(path: &Path) -> Result<BA2Header> {
+    BA2Header::parse(path)
+}
+
+impl QuarantineManager {
+    /// Create a manager for the quarantine folder `root`
+    pub const fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Get the quarantine folder
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Load the records of quarantined archives, oldest first
+    pub fn records(&self) -> Vec<QuarantineRecord> {
+        let path = self.root.join(RECORD_FILE);
+        let Ok(content) = fs::read_to_string(&path) else {
+            return Vec::new();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!(
+                "Ignoring unreadable quarantine records {}: {}",
+                path.display(),
+                e
+            );
+            Vec::new()
+        })
+    }
+
+    /// Move a corrupted archive into the quarantine folder
+    ///
+    /// The archive is placed below a folder named after its mod; an existing
+    /// file of the same name is kept and the new one gets a numbered name.
+    /// If `operation` is given, the move is recorded so it can be undone.
+    pub fn quarantine(
+        &self,
+        entry: &FileEntry,
+        operation: Option<&mut Operation>,
+    ) -> Result<QuarantineRecord> {
+        let folder = self.root.join(&entry.dir_name);
+        fs::create_dir_all(&folder)?;
+        let target = unique_path(&folder, &entry.full_path)?;
+        match operation {
+            Some(operation) => operation.move_file(&entry.full_path, &target)?,
+            None => move_file(&entry.full_path, &target)?,
+        }
+        self.record(entry, target)
+    }
+
+    /// Finish quarantining an archive whose move stopped after copying it to
+    /// `quarantined_path`, removing the original
+    pub fn finish(
+        &self,
+        entry: &FileEntry,
+        quarantined_path: &Path,
+        operation: Option<&mut Operation>,
+    ) -> Result<QuarantineRecord> {
+        match operation {
+            Some(operation) => operation.finish_move(&entry.full_path, quarantined_path)?,
+            None => finish_partial_move(&entry.full_path, quarantined_path)?,
+        }
+        self.record(entry, quarantined_path.to_path_buf())
+    }
+
+    /// Add the record of an archive moved to `target`
+    fn record(&self, entry: &FileEntry, target: PathBuf) -> Result<QuarantineRecord> {
+        let record = QuarantineRecord {
+            original_path: entry.full_path.clone(),
+            quarantined_path: target,
+            reason: entry.health.reason(),
+            quarantined_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        };
+        let mut records = self.records();
+        records.push(record.clone());
+        let content = serde_json::to_string_pretty(&records)
+            .map_err(|e| Error::Other(format!("Failed to serialize quarantine records: {e}")))?;
+        fs::write(self.root.join(RECORD_FILE), content)?;
+
+        tracing::info!(
+            "Quarantined {} to {}",
+            record.original_path.display(),
+            record.quarantined_path.display()
+        );
+        Ok(record)
+    }
+}
+
+/// Get a path in `folder` for the file, numbering it if the name is taken
+///
+/// Fails once [`MAX_NUMBERED_NAMES`] numbered names are taken as well.
+fn unique_path(folder: &Path, file: &Path) -> Result<PathBuf> {
+    let name = file.file_name().unwrap_or(file.as_os_str());
+    let candidate = folder.join(name);
+    if !candidate.exists() {
+        return Ok(candidate);
+    }
+    let stem = file.file_stem().unwrap_or(name);
+    for n in 1..=MAX_NUMBERED_NAMES {
+        let mut numbered = stem.to_os_string();
+        numbered.push(format!(" ({n})"));
+        if let Some(extension) = file.extension() {
+            numbered.push(".");
+            numbered.push(extension);
+        }
+        let path = folder.join(numbered);
+        if !path.exists() {
+            return Ok(path);
+        }
+    }
+    Err(Error::other(format!(
+        "{} already holds {MAX_NUMBERED_NAMES} copies of {}",
+        folder.display(),
+        name.display()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ArchiveHealth;
+    use tempfile::TempDir;
+
+    fn corrupted_entry(dir: &Path, name: &str) -> FileEntry {
+        let path = dir.join(name);
+        fs::write(&path, b"not an archive").unwrap();
+        let mut entry = FileEntry::new(
+            name.to_string(),
+            14,
+            0,
+            "Broken Mod".to_string(),
+            path,
+        );
+        entry.health = ArchiveHealth::from_error(&revalidate(&entry.full_path).unwrap_err());
+        entry
+    }
+
+    #[test]
+    fn test_quarantine_moves_and_records() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = QuarantineManager::new(temp_dir.path().join("quarantine"));
+
+        let first = manager
+            .quarantine(&corrupted_entry(temp_dir.path(), "Broken - Main.ba2"), None)
+            .unwrap();
+        assert!(!first.original_path.exists());
+        assert!(first.quarantined_path.is_file());
+        assert!(first.reason.contains("Failed to read header"));
+
+        // A second archive of the same name does not replace the first
+        let second = manager
+            .quarantine(&corrupted_entry(temp_dir.path(), "Broken - Main.ba2"), None)
+            .unwrap();
+        assert_ne!(first.quarantined_path, second.quarantined_path);
+        assert_eq!(manager.records(), vec![first, second]);
+    }
+
+    #[test]
+    fn test_finish_partial_quarantine() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = QuarantineManager::new(temp_dir.path().join("quarantine"));
+        let entry = corrupted_entry(temp_dir.path(), "Broken - Main.ba2");
+        let copy = manager.root().join("Broken Mod/Broken - Main.ba2");
+        fs::create_dir_all(copy.parent().unwrap()).unwrap();
+        fs::copy(&entry.full_path, &copy).unwrap();
+
+        let record = manager.finish(&entry, &copy, None).unwrap();
+        assert!(!entry.full_path.exists());
+        assert_eq!(record.quarantined_path, copy);
+        assert_eq!(manager.records(), vec![record]);
+    }
+
+    #[test]
+    fn test_unique_path_numbers_taken_names() {
+        let dir = TempDir::new().unwrap();
+        let file = Path::new("Mod - Main.ba2");
+        assert_eq!(
+            unique_path(dir.path(), file).unwrap(),
+            dir.path().join(file)
+        );
+
+        fs::write(dir.path().join(file), b"").unwrap();
+        fs::write(dir.path().join("Mod - Main (1).ba2"), b"").unwrap();
+        assert_eq!(
+            unique_path(dir.path(), file).unwrap(),
+            dir.path().join("Mod - Main (2).ba2")
+        );
+    }
+
+    #[test]
+    fn test_corruption_reason_omits_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("Bad - Main.ba2");
+        fs::write(&path, [b'X'; BA2Header::HEADER_SIZE]).unwrap();
+        let health = ArchiveHealth::from_error(&revalidate(&path).unwrap_err());
+        assert_eq!(health.reason(), "Not a BA2 archive (invalid magic number)");
+
+        // A file that can't be opened is not reported as corrupted
+        let missing = ArchiveHealth::from_error(&revalidate(&path.with_extension("gone")).unwrap_err());
+        assert!(matches!(missing, ArchiveHealth::Unreadable(_)));
+    }
+}
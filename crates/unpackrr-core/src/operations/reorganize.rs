@@ -0,0 +1,515 @@
+//! Reorganizing extracted files
+//!
+//! Some archives unpack into a layout mod managers don't expect: a wrapper
+//! folder around the game's folders (`Data/meshes/...`), an output folder
+//! below the mod folder (`Mod/Optional/textures/...`), or asset folders
+//! named in any case (`Textures` next to another mod's `textures`). A
+//! [`Strategy`] moves the files of an extraction into the expected layout.
+//!
+//! [`plan`] is a dry run: it lists every move without touching anything, so
+//! it can be previewed, and leaves out files whose target is taken. [`apply`]
+//! makes the moves, through a journal [`Operation`] if given so the whole
+//! reorganization can be undone. Only files that came out of the archive
+//! are moved; anything else in the folders stays where it is.
+
+use crate::ba2::read_file_names;
+use crate::error::Result;
+use crate::operations::journal::{Operation, move_file};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Top-level folders of the game's data, in lowercase
+const ASSET_FOLDERS: &[&str] = &[
+    "meshes",
+    "textures",
+    "materials",
+    "sound",
+    "music",
+    "interface",
+    "scripts",
+    "strings",
+    "vis",
+    "lodsettings",
+    "shadersfx",
+    "programs",
+    "seq",
+    "video",
+    "geometries",
+    "planetdata",
+    "terrain",
+    "particles",
+    "misc",
+    "f4se",
+    "sfse",
+];
+
+/// Whether `name` is one of the game's top-level data folders, in any case
+pub fn is_asset_folder(name: &str) -> bool {
+    ASSET_FOLDERS
+        .iter()
+        .any(|folder| folder.eq_ignore_ascii_case(name))
+}
+
+/// How extracted files are reorganized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Move the contents of a wrapper folder every file of the archive is in
+    /// (e.g. `Data`) up into the output folder
+    UpOneLevel,
+
+    /// Move files extracted into a subfolder of a mod into the mod folder
+    MergeIntoModRoot,
+
+    /// Give the game's folders the lowercase names MO2 lists them under
+    Mo2Layout,
+}
+
+impl Strategy {
+    /// All strategies, in the order they are offered
+    pub const ALL: [Self; 3] = [Self::UpOneLevel, Self::MergeIntoModRoot, Self::Mo2Layout];
+
+    /// Short name shown to the user
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::UpOneLevel => "Move up a level",
+            Self::MergeIntoModRoot => "Merge into mod folder",
+            Self::Mo2Layout => "MO2 folder names",
+        }
+    }
+
+    /// What the strategy does, shown to the user
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::UpOneLevel => {
+                "Moves files out of a folder that wraps the game's folders, e.g. Data\\meshes becomes meshes"
+            }
+            Self::MergeIntoModRoot => {
+                "Moves files extracted into a subfolder of a mod, e.g. Mod\\Optional\\textures, into the mod folder"
+            }
+            Self::Mo2Layout => {
+                "Renames the game's folders to lowercase, e.g. Textures becomes textures, as MO2 lists them"
+            }
+        }
+    }
+
+    /// Where the file `name` of `output` belongs, if it moves
+    fn target(self, output: &ExtractedOutput, name: &[&str]) -> Option<PathBuf> {
+        match self {
+            Self::UpOneLevel => {
+                output.wrapper()?;
+                Some(join(&output.folder, &name[1..]))
+            }
+            Self::MergeIntoModRoot => {
+                (output.folder != output.mod_root).then(|| join(&output.mod_root, name))
+            }
+            Self::Mo2Layout => {
+                let (first, rest) = name.split_first()?;
+                let lowercase = first.to_ascii_lowercase();
+                (is_asset_folder(first) && *first != lowercase)
+                    .then(|| join(&output.folder.join(lowercase), rest))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+fn join(base: &Path, parts: &[&str]) -> PathBuf {
+    parts
+        .iter()
+        .fold(base.to_path_buf(), |path, part| path.join(part))
+}
+
+fn split(name: &str) -> Vec<&str> {
+    name.split(['\\', '/'])
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// The files an archive was extracted to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedOutput {
+    /// The archive
+    pub archive: PathBuf,
+
+    /// Folder the archive was extracted into, next to it
+    pub folder: PathBuf,
+
+    /// The mod folder: the folder below the scanned folder that holds the archive
+    pub mod_root: PathBuf,
+
+    /// Paths of the files in the archive, as stored in it
+    pub names: Vec<String>,
+}
+
+impl ExtractedOutput {
+    /// Describe the extraction of `archive`, scanned from the folder `scanned`
+    pub fn read(archive: &Path, scanned: &Path) -> Result<Self> {
+        let names = read_file_names(archive)?;
+        Ok(Self::new(archive, scanned, names))
+    }
+
+    /// Describe an extraction of the files `names` from `archive`
+    pub fn new(archive: &Path, scanned: &Path, names: Vec<String>) -> Self {
+        let folder = archive.parent().unwrap_or(scanned).to_path_buf();
+        let mod_root = folder
+            .strip_prefix(scanned)
+            .ok()
+            .and_then(|relative| relative.components().next())
+            .map_or_else(|| folder.clone(), |first| scanned.join(first));
+        Self {
+            archive: archive.to_path_buf(),
+            folder,
+            mod_root,
+            names,
+        }
+    }
+
+    /// Where the files are once `strategy` has been applied, to plan the
+    /// next strategy
+    #[must_use]
+    pub fn reorganized(&self, strategy: Strategy) -> Self {
+        let mut output = self.clone();
+        match strategy {
+            Strategy::UpOneLevel => {
+                if self.wrapper().is_some() {
+                    for name in &mut output.names {
+                        *name = split(name)[1..].join("\\");
+                    }
+                }
+            }
+            Strategy::MergeIntoModRoot => output.folder.clone_from(&self.mod_root),
+            Strategy::Mo2Layout => {
+                for name in &mut output.names {
+                    let parts = split(name);
+                    if let Some((first, rest)) = parts.split_first()
+                        && is_asset_folder(first)
+                    {
+                        *name = std::iter::once(first.to_ascii_lowercase().as_str())
+                            .chain(rest.iter().copied())
+                            .collect::<Vec<_>>()
+                            .join("\\");
+                    }
+                }
+            }
+        }
+        output
+    }
+
+    /// The folder all files are in, if it isn't one of the game's folders
+    fn wrapper(&self) -> Option<&str> {
+        let mut wrapper = None;
+        for name in &self.names {
+            let parts = split(name);
+            let [first, _, ..] = parts.as_slice() else {
+                return None;
+            };
+            match wrapper {
+                None => wrapper = Some(*first),
+                Some(seen) if seen.eq_ignore_ascii_case(first) => {}
+                Some(_) => return None,
+            }
+        }
+        wrapper.filter(|folder| !is_asset_folder(folder))
+    }
+}
+
+/// A file to move
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedMove {
+    /// Where the file is
+    pub from: PathBuf,
+
+    /// Where it goes
+    pub to: PathBuf,
+}
+
+/// The moves a strategy makes, computed without changing anything
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReorganizePlan {
+    /// The strategy
+    pub strategy: Strategy,
+
+    /// Files to move
+    pub moves: Vec<PlannedMove>,
+
+    /// Files left in place because their target is taken
+    pub conflicts: Vec<PlannedMove>,
+}
+
+impl ReorganizePlan {
+    /// Whether the strategy has nothing to do
+    pub const fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// The plan for the user: the moves, with paths relative to `root`
+    ///
+    /// At most `limit` moves and conflicts are listed.
+    pub fn preview(&self, root: &Path, limit: usize) -> String {
+        let relative = |path: &Path| {
+            path.strip_prefix(root)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        };
+        let mut text = format!("{}: {} files", self.strategy, self.moves.len());
+        for planned in self.moves.iter().take(limit) {
+            let _ = write!(
+                text,
+                "\n{} → {}",
+                relative(&planned.from),
+                relative(&planned.to)
+            );
+        }
+        if self.moves.len() > limit {
+            let _ = write!(text, "\n... and {} more", self.moves.len() - limit);
+        }
+        if !self.conflicts.is_empty() {
+            let _ = write!(
+                text,
+                "\n\nLeft in place, the target exists: {} files",
+                self.conflicts.len()
+            );
+            for planned in self.conflicts.iter().take(limit) {
+                let _ = write!(text, "\n{}", relative(&planned.from));
+            }
+        }
+        text
+    }
+}
+
+/// Plan how `strategy` reorganizes the files of `outputs` (a dry run)
+///
+/// Files that are gone, e.g. removed since the extraction, are left out, as
+/// are renames a case-insensitive file system makes no difference for.
+/// Files whose target exists, or is the target of another file, are listed
+/// as conflicts and stay where they are.
+pub fn plan(strategy: Strategy, outputs: &[ExtractedOutput]) -> ReorganizePlan {
+    let mut moves = Vec::new();
+    let mut conflicts = Vec::new();
+    let mut targets = HashSet::new();
+    for output in outputs {
+        for name in &output.names {
+            let parts = split(name);
+            let Some(to) = strategy.target(output, &parts) else {
+                continue;
+            };
+            let from = join(&output.folder, &parts);
+            if !from.is_file() {
+                continue;
+            }
+            let planned = PlannedMove { from, to };
+            match target_state(&planned) {
+                TargetState::Same => {}
+                TargetState::Taken => conflicts.push(planned),
+                TargetState::Free if !targets.insert(planned.to.clone()) => conflicts.push(planned),
+                TargetState::Free => moves.push(planned),
+            }
+        }
+    }
+    ReorganizePlan {
+        strategy,
+        moves,
+        conflicts,
+    }
+}
+
+/// What is at the target of a move
+enum TargetState {
+    /// Nothing
+    Free,
+    /// The file itself, e.g. when the paths only differ in case on a
+    /// case-insensitive file system, where moving it would change nothing
+    Same,
+    /// Another file
+    Taken,
+}
+
+fn target_state(planned: &PlannedMove) -> TargetState {
+    if !planned.to.exists() {
+        return TargetState::Free;
+    }
+    match (
+        fs::canonicalize(&planned.from),
+        fs::canonicalize(&planned.to),
+    ) {
+        (Ok(from), Ok(to)) if from == to => TargetState::Same,
+        _ => TargetState::Taken,
+    }
+}
+
+/// What applying a plan did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReorganizeReport {
+    /// Files moved
+    pub moved: usize,
+
+    /// Files that could not be moved, with the reason
+    pub failed: Vec<(PathBuf, String)>,
+
+    /// Folders removed because the moves emptied them
+    pub removed_folders: usize,
+}
+
+/// Make the moves of `plan`, recording them in `operation` if given
+///
+/// Targets taken since the plan was made are skipped as failures, as are
+/// files that can't be moved; the other moves still go through. Folders the
+/// moves left empty are removed afterwards.
+pub fn apply(plan: &ReorganizePlan, mut operation: Option<&mut Operation>) -> ReorganizeReport {
+    let mut report = ReorganizeReport::default();
+    let mut emptied = BTreeSet::new();
+    for planned in &plan.moves {
+        let result = if planned.to.exists() {
+            Err(format!("{} already exists", planned.to.display()))
+        } else {
+            move_planned(planned, operation.as_deref_mut()).map_err(|e| e.to_string())
+        };
+        match result {
+            Ok(()) => {
+                report.moved += 1;
+                if let Some(parent) = planned.from.parent() {
+                    emptied.insert((parent.to_path_buf(), common_ancestor(planned)));
+                }
+            }
+            Err(reason) => {
+                tracing::warn!("Could not move {}: {}", planned.from.display(), reason);
+                report.failed.push((planned.from.clone(), reason));
+            }
+        }
+    }
+    for (folder, stop) in emptied {
+        report.removed_folders += remove_empty_folders(&folder, &stop);
+    }
+    report
+}
+
+fn move_planned(planned: &PlannedMove, operation: Option<&mut Operation>) -> Result<()> {
+    if let Some(parent) = planned.to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    operation.map_or_else(
+        || move_file(&planned.from, &planned.to),
+        |operation| operation.move_file(&planned.from, &planned.to),
+    )
+}
+
+/// The deepest folder both ends of a move are in
+fn common_ancestor(planned: &PlannedMove) -> PathBuf {
+    planned
+        .from
+        .ancestors()
+        .find(|folder| planned.to.starts_with(folder))
+        .unwrap_or(&planned.from)
+        .to_path_buf()
+}
+
+/// Remove `folder` and its parents up to `stop` while they are empty,
+/// returning how many were removed
+fn remove_empty_folders(folder: &Path, stop: &Path) -> usize {
+    let mut removed = 0;
+    let mut current = Some(folder);
+    while let Some(dir) = current
+        && dir != stop
+        && dir.starts_with(stop)
+    {
+        // Fails for folders that still hold something
+        if fs::remove_dir(dir).is_err() {
+            break;
+        }
+        removed += 1;
+        current = dir.parent();
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn extract(folder: &Path, names: &[&str]) -> Vec<String> {
+        for name in names {
+            let path = join(folder, &split(name));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, name.as_bytes()).unwrap();
+        }
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_up_one_level() {
+        let temp_dir = TempDir::new().unwrap();
+        let folder = temp_dir.path().join("Mod");
+        let names = extract(&folder, &[r"Data\meshes\a.nif", r"Data\textures\a.dds"]);
+        fs::write(folder.join("meshes.txt"), b"not from the archive").unwrap();
+        let output = ExtractedOutput::new(&folder.join("Mod - Main.ba2"), temp_dir.path(), names);
+
+        let plan = plan(Strategy::UpOneLevel, std::slice::from_ref(&output));
+        assert_eq!(plan.moves.len(), 2);
+        assert!(folder.join("Data").exists(), "a plan changes nothing");
+        assert!(plan.preview(&folder, 1).contains("... and 1 more"));
+
+        let report = apply(&plan, None);
+        assert_eq!(report.moved, 2);
+        assert!(report.failed.is_empty());
+        assert!(folder.join("meshes").join("a.nif").is_file());
+        assert!(folder.join("textures").join("a.dds").is_file());
+        assert!(!folder.join("Data").exists());
+        assert!(folder.join("meshes.txt").exists());
+
+        // Once in the game's layout, the files have no wrapper
+        let moved = output.reorganized(Strategy::UpOneLevel);
+        assert_eq!(moved.names, [r"meshes\a.nif", r"textures\a.dds"]);
+        assert!(super::plan(Strategy::UpOneLevel, std::slice::from_ref(&moved)).is_empty());
+        assert!(super::plan(Strategy::Mo2Layout, &[moved]).is_empty());
+    }
+
+    #[test]
+    fn test_merge_into_mod_root_with_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_root = temp_dir.path().join("Mod");
+        let folder = mod_root.join("Optional");
+        let names = extract(&folder, &[r"meshes\a.nif", r"meshes\b.nif"]);
+        extract(&mod_root, &[r"meshes\b.nif"]);
+        let output = ExtractedOutput::new(&folder.join("Opt - Main.ba2"), temp_dir.path(), names);
+        assert_eq!(output.mod_root, mod_root);
+
+        let plan = plan(Strategy::MergeIntoModRoot, &[output]);
+        assert_eq!(
+            plan.moves,
+            [PlannedMove {
+                from: folder.join("meshes").join("a.nif"),
+                to: mod_root.join("meshes").join("a.nif"),
+            }]
+        );
+        assert_eq!(plan.conflicts.len(), 1);
+
+        let report = apply(&plan, None);
+        assert_eq!(report.moved, 1);
+        assert!(mod_root.join("meshes").join("a.nif").is_file());
+        // The conflicting file keeps its folder
+        assert!(folder.join("meshes").join("b.nif").is_file());
+    }
+
+    #[test]
+    fn test_mo2_layout() {
+        let temp_dir = TempDir::new().unwrap();
+        let folder = temp_dir.path().join("Mod");
+        let names = extract(&folder, &[r"Textures\a.dds", r"meshes\a.nif"]);
+        let output = ExtractedOutput::new(&folder.join("Mod - Main.ba2"), temp_dir.path(), names);
+
+        let plan = plan(Strategy::Mo2Layout, &[output]);
+        let report = apply(&plan, None);
+        assert!(report.failed.is_empty());
+        assert!(folder.join("textures").join("a.dds").is_file());
+        assert!(folder.join("meshes").join("a.nif").is_file());
+    }
+}
@@ -0,0 +1,547 @@
+//! Retry logic for transient failures (Phase 2.8)
+//!
+//! Provides utilities for retrying operations that might fail due to
+//! temporary issues like file locks, network timeouts, or busy resources.
+//! [`retry_with_config`] blocks the calling thread between attempts and is
+//! meant for sync code (e.g., rayon scanning); async code uses
+//! [`retry_async`], which sleeps on the Tokio timer instead and can be aborted
+//! through a [`CancellationToken`].
+
+use crate::error::Error;
+use crate::platform::drive::DriveKind;
+use std::future::Future;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+/// Minimum delay before retrying an operation on a locked file
+pub const FILE_LOCK_MIN_DELAY: Duration = Duration::from_secs(2);
+
+/// Configuration for retry behavior
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Maximum number of retry attempts (not including the initial attempt)
+    pub max_attempts: usize,
+    /// Initial delay before the first retry
+    pub initial_delay: Duration,
+    /// Multiplier for exponential backoff (1.0 = no backoff, 2.0 = double each time)
+    pub backoff_multiplier: f64,
+    /// Maximum delay between retries
+    pub max_delay: Duration,
+    /// Random variation applied to each delay, as a fraction (0.0 = none, 0.5 = ±50%)
+    ///
+    /// Spreads out retries of parallel jobs that failed at the same moment.
+    pub jitter: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay: Duration::from_millis(100),
+            backoff_multiplier: 2.0,
+            max_delay: Duration::from_secs(5),
+            jitter: 0.0,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Create a configuration for quick retries (short delays, few attempts)
+    #[must_use]
+    pub const fn quick() -> Self {
+        Self {
+            max_attempts: 2,
+            initial_delay: Duration::from_millis(50),
+            backoff_multiplier: 1.5,
+            max_delay: Duration::from_secs(1),
+            jitter: 0.0,
+        }
+    }
+
+    /// Create a configuration for persistent retries (long delays, many attempts)
+    #[must_use]
+    pub const fn persistent() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(200),
+            backoff_multiplier: 2.0,
+            max_delay: Duration::from_secs(10),
+            jitter: 0.0,
+        }
+    }
+
+    /// Set the random variation applied to each delay (clamped to 0.0..=1.0)
+    #[must_use]
+    pub const fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Delay to wait after a failed attempt, with jitter applied
+    fn jittered(&self, delay: Duration) -> Duration {
+        if self.jitter <= 0.0 {
+            return delay;
+        }
+
+        // Uniform factor in [1 - jitter, 1 + jitter]; RandomState is randomly keyed
+        #[allow(clippy::cast_precision_loss)]
+        let unit = RandomState::new().hash_one(delay) as f64 / u64::MAX as f64;
+        let factor = self.jitter.mul_add(unit.mul_add(2.0, -1.0), 1.0);
+        Duration::from_secs_f64(delay.as_secs_f64() * factor)
+    }
+
+    /// Delay to wait after an attempt failed with `error`
+    ///
+    /// Files locked by an antivirus scan stay locked for seconds, so retrying
+    /// those sooner than [`FILE_LOCK_MIN_DELAY`] only wastes attempts.
+    fn wait_after(&self, delay: Duration, error: &Error) -> Duration {
+        if error.is_file_lock() {
+            self.jittered(delay.max(FILE_LOCK_MIN_DELAY))
+        } else {
+            self.jittered(delay)
+        }
+    }
+
+    /// Delay before the attempt after one that waited `delay` (exponential backoff)
+    fn next_delay(&self, delay: Duration) -> Duration {
+        Duration::from_secs_f64(
+            (delay.as_secs_f64() * self.backoff_multiplier).min(self.max_delay.as_secs_f64()),
+        )
+    }
+
+    /// Pick a configuration suited to the drive an operation runs against
+    ///
+    /// Network and removable drives see latency spikes and short dropouts, so
+    /// they use [`Self::persistent`].
+    #[must_use]
+    pub fn for_drive(kind: DriveKind) -> Self {
+        if kind.is_slow() {
+            Self::persistent()
+        } else {
+            Self::default()
+        }
+    }
+}
+
+/// Retry a fallible operation with exponential backoff
+///
+/// This function will retry the operation if it fails with a transient error.
+/// Non-transient errors are returned immediately without retrying.
+///
+/// # Arguments
+///
+/// * `config` - Retry configuration (attempts, delays, backoff)
+/// * `operation` - The operation to retry (must be `FnMut`)
+///
+/// # Returns
+///
+/// The result of the operation, or the last error if all retries failed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use unpackrr_core::operations::retry::{retry_with_config, RetryConfig};
+/// use std::fs::File;
+///
+/// let config = RetryConfig::default();
+/// let result = retry_with_config(&config, || {
+///     File::open("/path/to/file.txt")
+///         .map_err(|e| e.into())
+/// });
+/// ```
+pub fn retry_with_config<F, T>(config: &RetryConfig, mut operation: F) -> Result<T, Error>
+where
+    F: FnMut() -> Result<T, Error>,
+{
+    let mut attempts = 0;
+    let mut delay = config.initial_delay;
+
+    loop {
+        match operation() {
+            Ok(result) => return Ok(result),
+            Err(e) => {
+                attempts += 1;
+
+                // Check if we should retry
+                if !e.is_transient() || attempts > config.max_attempts {
+                    tracing::debug!("Operation failed after {} attempts: {}", attempts, e);
+                    return Err(e);
+                }
+
+                let wait = config.wait_after(delay, &e);
+
+                // Log the retry
+                tracing::warn!(
+                    "Transient error detected (attempt {}/{}): {}. Retrying in {:?}...",
+                    attempts,
+                    config.max_attempts,
+                    e,
+                    wait
+                );
+
+                // Wait before retrying
+                std::thread::sleep(wait);
+
+                // Calculate next delay with exponential backoff
+                delay = config.next_delay(delay);
+            }
+        }
+    }
+}
+
+/// Retry an operation with default configuration
+///
+/// Convenience function that uses `RetryConfig::default()`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use unpackrr_core::operations::retry::retry;
+/// use std::fs;
+///
+/// let result = retry(|| {
+///     fs::remove_file("/path/to/file.txt")
+///         .map_err(|e| e.into())
+/// });
+/// ```
+pub fn retry<F, T>(operation: F) -> Result<T, Error>
+where
+    F: FnMut() -> Result<T, Error>,
+{
+    retry_with_config(&RetryConfig::default(), operation)
+}
+
+/// Token used to abort long-running operations such as retry sequences
+///
+/// Clones share the same state; cancelling any clone cancels all of them.
+#[derive(Debug, Clone)]
+pub struct CancellationToken {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl CancellationToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self {
+            sender: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Cancel the token, waking everything waiting on [`Self::cancelled`]
+    pub fn cancel(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Check whether the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Wait until the token is cancelled
+    pub async fn cancelled(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender lives in `self`, so the channel cannot close while waiting
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Information about a failed attempt that is about to be retried
+#[derive(Debug)]
+pub struct RetryAttempt<'a> {
+    /// Number of the attempt that failed (1-indexed)
+    pub attempt: usize,
+    /// Maximum number of retries ([`RetryConfig::max_attempts`])
+    pub max_attempts: usize,
+    /// Time to wait before the next attempt
+    pub delay: Duration,
+    /// Error returned by the failed attempt
+    pub error: &'a Error,
+}
+
+/// Retry an async operation with exponential backoff
+///
+/// Async counterpart of [`retry_with_config`]; waits with `tokio::time::sleep`
+/// so the executor thread stays free between attempts.
+///
+/// # Examples
+///
+/// ```no_run
+/// use unpackrr_core::operations::retry::{retry_async, RetryConfig};
+///
+/// # async fn example() -> unpackrr_core::error::Result<()> {
+/// let content = retry_async(&RetryConfig::default(), || async {
+///     Ok(tokio::fs::read("/path/to/file.txt").await?)
+/// })
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn retry_async<F, Fut, T>(config: &RetryConfig, operation: F) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    retry_async_with(config, None, operation, |_| {}).await
+}
+
+/// Retry an async operation with cancellation and progress reporting
+///
+/// `on_retry` receives the failed attempt and the delay before the next one,
+/// which lets callers report retries (e.g., "Retrying 3/5 in 4s…") instead of
+/// appearing hung. If `cancel` is cancelled before or during an attempt or
+/// while waiting, the sequence stops with [`Error::Cancelled`].
+pub async fn retry_async_with<F, Fut, T, C>(
+    config: &RetryConfig,
+    cancel: Option<&CancellationToken>,
+    mut operation: F,
+    mut on_retry: C,
+) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+    C: FnMut(&RetryAttempt<'_>),
+{
+    let mut attempts = 0;
+    let mut delay = config.initial_delay;
+
+    loop {
+        let outcome = match cancel {
+            Some(token) if token.is_cancelled() => return Err(Error::Cancelled),
+            Some(token) => tokio::select! {
+                outcome = operation() => outcome,
+                () = token.cancelled() => return Err(Error::Cancelled),
+            },
+            None => operation().await,
+        };
+
+        match outcome {
+            Ok(result) => return Ok(result),
+            Err(e) => {
+                attempts += 1;
+
+                if !e.is_transient() || attempts > config.max_attempts {
+                    tracing::debug!("Operation failed after {} attempts: {}", attempts, e);
+                    return Err(e);
+                }
+
+                let wait = config.wait_after(delay, &e);
+                tracing::warn!(
+                    "Transient error detected (attempt {}/{}): {}. Retrying in {:?}...",
+                    attempts,
+                    config.max_attempts,
+                    e,
+                    wait
+                );
+                on_retry(&RetryAttempt {
+                    attempt: attempts,
+                    max_attempts: config.max_attempts,
+                    delay: wait,
+                    error: &e,
+                });
+
+                if let Some(token) = cancel {
+                    tokio::select! {
+                        () = tokio::time::sleep(wait) => {}
+                        () = token.cancelled() => {
+                            tracing::debug!("Retry cancelled after {} attempts", attempts);
+                            return Err(Error::Cancelled);
+                        }
+                    }
+                } else {
+                    tokio::time::sleep(wait).await;
+                }
+                delay = config.next_delay(delay);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_retry_succeeds_on_first_attempt() {
+        let result = retry(|| Ok(42));
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[test]
+    fn test_retry_succeeds_after_transient_failures() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = Arc::clone(&counter);
+
+        let result = retry_with_config(&RetryConfig::quick(), move || {
+            let count = counter_clone.fetch_add(1, Ordering::SeqCst);
+            if count < 2 {
+                // Fail with transient error first two times
+                Err(Error::IO(std::io::Error::from(
+                    std::io::ErrorKind::Interrupted,
+                )))
+            } else {
+                Ok(42)
+            }
+        });
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(counter.load(Ordering::SeqCst), 3); // 2 failures + 1 success
+    }
+
+    #[test]
+    fn test_retry_fails_on_permanent_error() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = Arc::clone(&counter);
+
+        let result: Result<i32, Error> = retry(move || {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+            Err(Error::other("permanent error"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(counter.load(Ordering::SeqCst), 1); // No retries for permanent errors
+    }
+
+    #[test]
+    fn test_retry_exhausts_attempts() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = Arc::clone(&counter);
+
+        let config = RetryConfig {
+            max_attempts: 2,
+            initial_delay: Duration::from_millis(10),
+            backoff_multiplier: 1.0,
+            max_delay: Duration::from_millis(10),
+            jitter: 0.0,
+        };
+
+        let result: Result<i32, Error> = retry_with_config(&config, move || {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+            Err(Error::IO(std::io::Error::from(
+                std::io::ErrorKind::Interrupted,
+            )))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(counter.load(Ordering::SeqCst), 3); // 1 initial + 2 retries
+    }
+
+    #[test]
+    fn test_jitter_stays_in_range() {
+        let config = RetryConfig::default().with_jitter(0.5);
+        for _ in 0..100 {
+            let wait = config.jittered(Duration::from_millis(100));
+            assert!(wait >= Duration::from_millis(50) && wait <= Duration::from_millis(150));
+        }
+        assert_eq!(
+            RetryConfig::default().jittered(Duration::from_millis(100)),
+            Duration::from_millis(100)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_async_reports_attempts() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = Arc::clone(&counter);
+        let mut reported = Vec::new();
+
+        let result = retry_async_with(
+            &RetryConfig::quick(),
+            None,
+            || {
+                let counter = Arc::clone(&counter_clone);
+                async move {
+                    if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                        Err(Error::IO(std::io::Error::from(std::io::ErrorKind::TimedOut)))
+                    } else {
+                        Ok(42)
+                    }
+                }
+            },
+            |attempt| reported.push(attempt.attempt),
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(reported, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_async_fails_on_permanent_error() {
+        let counter = AtomicUsize::new(0);
+        let result: Result<i32, Error> = retry_async(&RetryConfig::quick(), || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { Err(Error::other("permanent error")) }
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_async_cancelled_while_waiting() {
+        let token = CancellationToken::new();
+        let config = RetryConfig {
+            max_attempts: 5,
+            initial_delay: Duration::from_mins(1),
+            backoff_multiplier: 1.0,
+            max_delay: Duration::from_mins(1),
+            jitter: 0.0,
+        };
+
+        let canceller = token.clone();
+        let result: Result<i32, Error> = retry_async_with(
+            &config,
+            Some(&token),
+            || async { Err(Error::IO(std::io::Error::from(std::io::ErrorKind::TimedOut))) },
+            |attempt| {
+                assert_eq!(attempt.delay, Duration::from_mins(1));
+                canceller.cancel();
+            },
+        )
+        .await;
+
+        assert!(result.unwrap_err().is_cancelled());
+        assert!(token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_retry_async_not_started_when_cancelled() {
+        let token = CancellationToken::new();
+        token.cancel();
+        let counter = AtomicUsize::new(0);
+
+        let result: Result<i32, Error> = retry_async_with(
+            &RetryConfig::quick(),
+            Some(&token),
+            || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { Ok(1) }
+            },
+            |_| {},
+        )
+        .await;
+
+        assert!(result.unwrap_err().is_cancelled());
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_retry_config_for_drive() {
+        assert_eq!(RetryConfig::for_drive(DriveKind::Local).max_attempts, 3);
+        assert_eq!(
+            RetryConfig::for_drive(DriveKind::Network).max_attempts,
+            RetryConfig::persistent().max_attempts
+        );
+    }
+}
@@ -0,0 +1,852 @@
+//! BA2 file scanning operations
+//!
+//! This module provides directory scanning functionality for discovering BA2 files
+//! in a directory structure. It follows the Python version's logic of scanning
+//! second-tier directories (mod folders) to avoid scanning BA2 files that won't
+//! be loaded by the game. In Data folder mode, the archives of the game's Data
+//! folder itself are listed as well, without the game's own archives.
+//!
+//! A scan runs in two stages at once: a walker lists the candidate archives
+//! of each mod folder, which is fast, while a bounded pool of workers reads
+//! their sizes and headers. Archives are published as they are parsed, so
+//! frontends can fill their tables while a huge folder is still being
+//! scanned.
+
+use crate::ba2::BA2Header;
+use crate::config::AppConfig;
+use crate::config::ignore::IgnoreMatcher;
+use crate::config::postfix::PostfixMatcher;
+use crate::config::rules::known_exclusions::KnownExclusions;
+use crate::error::{Result, ValidationError};
+use crate::models::ArchiveHealth;
+use crate::operations::BA2FileInfo;
+use crate::operations::events::EventBus;
+use crate::operations::path::display_name;
+use crate::operations::retry::{RetryConfig, retry_with_config};
+use crate::platform::drive::drive_kind;
+use rayon::prelude::*;
+use std::fs;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::SyncSender;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+/// Archives that may wait between the stages of a scan
+///
+/// Bounds the memory of huge folders: the walker waits for the header
+/// workers, and the workers for whoever receives the results.
+const PENDING_ARCHIVES: usize = 256;
+
+/// Progress update for scanning operations
+#[derive(Debug, Clone)]
+pub enum ScanProgress {
+    /// Started scanning a directory
+    Started {
+        /// Total number of directories to scan
+        total_dirs: usize,
+    },
+
+    /// Scanning a specific mod folder
+    ScanningFolder {
+        /// Name of the folder being scanned
+        folder: String,
+        /// Current directory index
+        current: usize,
+        /// Total number of directories
+        total: usize,
+    },
+
+    /// Found a BA2 file
+    FoundBA2 {
+        /// Name of the BA2 file found
+        file_name: String,
+    },
+
+    /// Archives whose headers were read since the last update
+    ///
+    /// Arrive in no particular order while the scan goes on; the result of
+    /// the scan lists them all again in folder order.
+    Found {
+        /// The archives, as they will be returned by the scan
+        archives: Vec<BA2FileInfo>,
+    },
+
+    /// Finished scanning
+    Complete {
+        /// Total number of BA2 files discovered
+        total_files: usize,
+    },
+}
+
+/// Scan a directory for BA2 files matching the configured postfixes
+///
+/// This function scans second-tier directories (mod folders) for BA2 files.
+/// It filters files based on:
+/// - Postfix patterns (e.g., "- Main.ba2", "* - Textures.ba2", see
+///   [`postfix`](crate::config::postfix))
+/// - Ignored file patterns (exact, substring, glob, regex, see
+///   [`ignore`](crate::config::ignore))
+/// - File validity (corrupt BA2 files are marked as bad)
+///
+/// Archives of BA2 versions the selected game ([`AppConfig::game`]) does not
+/// write are listed with their version, so they can be flagged before
+/// extraction.
+///
+/// # Arguments
+///
+/// * `path` - The root directory to scan (typically the game's Data folder)
+/// * `config` - Application configuration containing postfixes and ignored patterns
+/// * `events` - Optional bus for publishing progress updates and the archives
+///   found so far ([`ScanProgress::Found`])
+///
+/// # Returns
+///
+/// A vector of `BA2FileInfo` structs for each discovered BA2 file
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::Path;
+/// use unpackrr_core::operations::scan::scan_for_ba2;
+/// use unpackrr_core::config::AppConfig;
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let config = AppConfig::load()?;
+/// let path = Path::new("C:/Games/Fallout4/Data");
+/// let files = scan_for_ba2(path, &config, None).await?;
+/// println!("Found {} BA2 files", files.len());
+/// # Ok(())
+/// # }
+/// ```
+pub async fn scan_for_ba2(
+    path: &Path,
+    config: &AppConfig,
+    events: Option<EventBus>,
+) -> Result<Vec<BA2FileInfo>> {
+    scan(path, config, events, false).await
+}
+
+/// Scan a directory for BA2 files, keeping the ignored ones
+///
+/// Same as [`scan_for_ba2`], except archives matching an ignore pattern are
+/// listed too, with [`BA2FileInfo::ignored`] set, so they can be shown
+/// greyed out and un-ignored.
+pub async fn scan_with_ignored(
+    path: &Path,
+    config: &AppConfig,
+    events: Option<EventBus>,
+) -> Result<Vec<BA2FileInfo>> {
+    scan(path, config, events, true).await
+}
+
+async fn scan(
+    path: &Path,
+    config: &AppConfig,
+    events: Option<EventBus>,
+    keep_ignored: bool,
+) -> Result<Vec<BA2FileInfo>> {
+    debug!("Starting BA2 scan in: {}", path.display());
+
+    // Verify the path exists and is a directory
+    if !path.exists() {
+        return Err(ValidationError::PathNotFound(path.to_path_buf()).into());
+    }
+
+    if !path.is_dir() {
+        return Err(ValidationError::NotADirectory(path.to_path_buf()).into());
+    }
+
+    let postfixes = PostfixMatcher::new(&config.extraction.postfixes)?;
+    let ignored = config.ignore_matcher()?;
+    let exclusions = KnownExclusions::load();
+
+    // List all first-tier directories (mod folders)
+    let entries = fs::read_dir(path).map_err(|e| {
+        std::io::Error::new(
+            e.kind(),
+            format!("Failed to read directory {}: {}", path.display(), e),
+        )
+    })?;
+
+    let mut mod_folders: Vec<PathBuf> = Vec::new();
+
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+
+        // Skip files, only process directories
+        if path.is_dir() {
+            mod_folders.push(path);
+        }
+    }
+
+    // Without a mod manager, mod archives sit in the Data folder itself
+    let data_folder = (config.extraction.data_folder_mode && config.profile().is_data_folder(path))
+        .then(|| path.to_path_buf());
+    if let Some(data_folder) = &data_folder {
+        debug!(
+            "Listing the archives of the Data folder {}",
+            data_folder.display()
+        );
+        mod_folders.push(data_folder.clone());
+    }
+
+    let total_folders = mod_folders.len();
+    debug!("Found {} mod folders to scan", total_folders);
+
+    // Send started progress
+    if let Some(ref events) = events {
+        events
+            .publish(ScanProgress::Started {
+                total_dirs: total_folders,
+            })
+            .await;
+    }
+
+    // Network and removable drives get patient retries and fewer parallel readers
+    let drive = drive_kind(path);
+    let retry_config = RetryConfig::for_drive(drive);
+    let threads = drive.max_jobs(rayon::current_num_threads());
+
+    // The stages block, so they run outside the async executor; parsed
+    // archives come back here to be published
+    let walk = Walk {
+        mod_folders,
+        data_folder,
+        postfixes,
+        ignored,
+        keep_ignored,
+        events: events.clone(),
+    };
+    let config_clone = config.clone();
+    let (found_tx, mut found_rx) = mpsc::channel(PENDING_ARCHIVES);
+    let pipeline = tokio::task::spawn_blocking(move || {
+        run_pipeline(
+            &walk,
+            &config_clone,
+            &exclusions,
+            &retry_config,
+            threads,
+            &found_tx,
+        );
+    });
+
+    let mut found: Vec<(usize, BA2FileInfo)> = Vec::new();
+    while let Some(first) = found_rx.recv().await {
+        // Everything parsed in the meantime goes out as one update
+        let start = found.len();
+        found.push(first);
+        while let Ok(next) = found_rx.try_recv() {
+            found.push(next);
+        }
+        if let Some(ref events) = events {
+            let archives = found[start..]
+                .iter()
+                .map(|(_, info)| info.clone())
+                .collect();
+            events.publish(ScanProgress::Found { archives }).await;
+        }
+    }
+    pipeline
+        .await
+        .map_err(|e| std::io::Error::other(format!("Scan task failed: {e}")))?;
+
+    // Back in the order the walker listed them, however the workers finished
+    found.sort_unstable_by_key(|(index, _)| *index);
+    let all_ba2: Vec<BA2FileInfo> = found.into_iter().map(|(_, info)| info).collect();
+
+    // Send completion progress
+    if let Some(ref events) = events {
+        events
+            .publish(ScanProgress::Complete {
+                total_files: all_ba2.len(),
+            })
+            .await;
+    }
+
+    debug!("Scan complete. Found {} BA2 files", all_ba2.len());
+    Ok(all_ba2)
+}
+
+/// Run both stages of a scan, sending each parsed archive to `found` with
+/// its position in walking order
+///
+/// Headers are read on `threads` threads. Returns once every archive was
+/// sent, or early if `found` is closed.
+fn run_pipeline(
+    walk: &Walk,
+    config: &AppConfig,
+    exclusions: &KnownExclusions,
+    retry_config: &RetryConfig,
+    threads: usize,
+    found: &mpsc::Sender<(usize, BA2FileInfo)>,
+) {
+    let (candidates_tx, candidates_rx) = std::sync::mpsc::sync_channel(PENDING_ARCHIVES);
+    std::thread::scope(|scope| {
+        scope.spawn(move || walk.run(config, &candidates_tx));
+
+        let parse = move || {
+            candidates_rx
+                .into_iter()
+                .par_bridge()
+                .for_each(|(index, candidate)| {
+                    let info = inspect(candidate, config, exclusions, retry_config);
+                    // Closed when the scan was abandoned; the walker stops too
+                    let _ = found.blocking_send((index, info));
+                });
+        };
+        if threads < rayon::current_num_threads() {
+            match rayon::ThreadPoolBuilder::new().num_threads(threads).build() {
+                Ok(pool) => return pool.install(parse),
+                Err(e) => warn!("Failed to create limited scan pool: {}", e),
+            }
+        }
+        parse();
+    });
+}
+
+/// The first stage of a scan: listing the archives of the mod folders
+struct Walk {
+    mod_folders: Vec<PathBuf>,
+    /// The game's Data folder, if it is scanned too
+    data_folder: Option<PathBuf>,
+    postfixes: PostfixMatcher,
+    ignored: IgnoreMatcher,
+    keep_ignored: bool,
+    events: Option<EventBus>,
+}
+
+/// An archive listed by the walker, whose header is still to be read
+struct Candidate {
+    path: PathBuf,
+    file_name: String,
+    dir_name: String,
+    ignored: bool,
+}
+
+impl Walk {
+    /// Send the candidates of every mod folder, numbered in walking order
+    fn run(&self, config: &AppConfig, candidates: &SyncSender<(usize, Candidate)>) {
+        let total = self.mod_folders.len();
+        let mut index = 0;
+        for (current, mod_folder) in self.mod_folders.iter().enumerate() {
+            let dir_name = mod_folder
+                .file_name()
+                .map_or_else(|| "unknown".to_string(), display_name);
+            if let Some(events) = &self.events {
+                // Dropped if the receiver is behind; the next folder is reported anyway
+                events.try_publish(ScanProgress::ScanningFolder {
+                    folder: dir_name.clone(),
+                    current: current + 1,
+                    total,
+                });
+            }
+
+            let is_data_folder = self.data_folder.as_ref() == Some(mod_folder);
+            for candidate in self.list_mod_folder(mod_folder, &dir_name) {
+                // Never offer to unpack the game's own archives
+                if is_data_folder && config.profile().is_game_archive(&candidate.file_name) {
+                    continue;
+                }
+                if candidates.send((index, candidate)).is_err() {
+                    return;
+                }
+                index += 1;
+            }
+        }
+    }
+
+    /// List the archives of a single mod folder that match the postfixes
+    fn list_mod_folder(&self, mod_folder: &Path, dir_name: &str) -> Vec<Candidate> {
+        let mut candidates = Vec::new();
+
+        // List all files in the mod folder
+        let entries = match fs::read_dir(mod_folder) {
+            Ok(entries) => entries,
+            Err(e) => {
+                warn!("Failed to read mod folder {}: {}", mod_folder.display(), e);
+                return candidates;
+            }
+        };
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(e) => e,
+                Err(e) => {
+                    warn!("Failed to read directory entry: {}", e);
+                    continue;
+                }
+            };
+
+            let path = entry.path();
+
+            // Skip directories
+            if path.is_dir() {
+                continue;
+            }
+
+            // Only process .ba2 files (extension compared without decoding the name)
+            if !path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("ba2"))
+            {
+                continue;
+            }
+
+            // Names that aren't valid Unicode are kept for display only; the exact
+            // OS path is preserved in `full_path` for extraction
+            let Some(file_name) = path.file_name().map(display_name) else {
+                continue;
+            };
+
+            // Check if file matches postfix patterns
+            if !self.postfixes.matches(&file_name) {
+                debug!("Skipping {} (doesn't match postfix patterns)", file_name);
+                continue;
+            }
+
+            // Check if file should be ignored
+            let is_ignored = self.ignored.ignores(&path);
+            if is_ignored && !self.keep_ignored {
+                debug!("Skipping {} (matches ignored pattern)", file_name);
+                continue;
+            }
+
+            candidates.push(Candidate {
+                path,
+                file_name,
+                dir_name: dir_name.to_string(),
+                ignored: is_ignored,
+            });
+        }
+
+        candidates
+    }
+}
+
+/// The second stage of a scan: read an archive's size and header, and check
+/// it against the known exclusions
+fn inspect(
+    candidate: Candidate,
+    config: &AppConfig,
+    exclusions: &KnownExclusions,
+    retry_config: &RetryConfig,
+) -> BA2FileInfo {
+    let Candidate {
+        path,
+        file_name,
+        dir_name,
+        ignored,
+    } = candidate;
+
+    // Get file size
+    let file_size = match retry_with_config(retry_config, || Ok(fs::metadata(&path)?)) {
+        Ok(metadata) => metadata.len(),
+        Err(e) => {
+            warn!("Failed to get metadata for {}: {}", path.display(), e);
+            0
+        }
+    };
+
+    // Try to read BA2 header to get file count and validate
+    let (num_files, archive_type, format_version, health) = match read_header(&path, retry_config) {
+        Ok(header) => {
+            if !config.profile().expects_version(header.version) {
+                warn!(
+                    "{} uses BA2 version {}, which {} does not write",
+                    path.display(),
+                    header.version,
+                    config.game
+                );
+            }
+            (
+                header.file_count,
+                header.archive_type,
+                header.version,
+                ArchiveHealth::Ok,
+            )
+        }
+        Err(e) => {
+            warn!("Failed to parse BA2 header for {}: {}", path.display(), e);
+            (0, String::new(), 0, ArchiveHealth::from_error(&e))
+        }
+    };
+
+    let exclusion = exclusions
+        .check(&path, &file_name, file_size)
+        .map(|entry| entry.reason.clone());
+    if exclusion.is_some() {
+        debug!("{} is a known exclusion", path.display());
+    }
+
+    BA2FileInfo {
+        file_name,
+        file_size,
+        num_files,
+        dir_name,
+        full_path: path,
+        health,
+        archive_type,
+        format_version,
+        ignored,
+        exclusion,
+    }
+}
+
+/// List all BA2 files in the mod folders of a directory
+///
+/// Unlike [`scan_for_ba2`] this applies no postfix or ignore filters and
+/// reads no headers, so postfix and ignore patterns can be tested against
+/// every archive.
+pub fn list_archives(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut archives = Vec::new();
+    for mod_folder in fs::read_dir(path)? {
+        let mod_folder = mod_folder?.path();
+        if !mod_folder.is_dir() {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&mod_folder) else {
+            continue;
+        };
+        archives.extend(entries.flatten().map(|entry| entry.path()).filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("ba2"))
+        }));
+    }
+    archives.sort_unstable();
+    Ok(archives)
+}
+
+/// Read a BA2 header, retrying transient failures to open the file
+fn read_header(path: &Path, retry_config: &RetryConfig) -> Result<BA2Header> {
+    let file = retry_with_config(retry_config, || Ok(fs::File::open(path)?))?;
+    BA2Header::parse_from_reader(&mut BufReader::new(file), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::config::ignore::{IgnorePattern, PatternKind};
+    use crate::operations::events::AppEvent;
+    use std::fs::File;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    /// Create a test directory structure with BA2 files
+    fn create_test_structure() -> (TempDir, PathBuf) {
+        let temp_dir = TempDir::new().unwrap();
+        let data_path = temp_dir.path().to_path_buf();
+
+        // Create mod folders
+        let mod1 = data_path.join("TestMod1");
+        let mod2 = data_path.join("TestMod2");
+        fs::create_dir(&mod1).unwrap();
+        fs::create_dir(&mod2).unwrap();
+
+        // Create test BA2 files with valid headers
+        let ba2_1 = mod1.join("TestMod1_Main.ba2");
+        let ba2_2 = mod1.join("TestMod1_Textures.ba2");
+        let ba2_3 = mod2.join("TestMod2_Main.ba2");
+        let ba2_ignored = mod2.join("TestMod2_Sounds.ba2"); // Won't match postfix
+
+        for path in &[&ba2_1, &ba2_2, &ba2_3, &ba2_ignored] {
+            create_test_ba2(path, 10);
+        }
+
+        // Create a non-BA2 file that should be ignored
+        let _txt_file = mod1.join("readme.txt");
+        File::create(&_txt_file).unwrap();
+
+        (temp_dir, data_path)
+    }
+
+    /// A walker over no folders, to list single mod folders
+    fn walk(postfixes: PostfixMatcher) -> Walk {
+        Walk {
+            mod_folders: Vec::new(),
+            data_folder: None,
+            postfixes,
+            ignored: IgnoreMatcher::default(),
+            keep_ignored: false,
+            events: None,
+        }
+    }
+
+    /// Create a test BA2 file with a valid header
+    fn create_test_ba2(path: &Path, file_count: u32) {
+        let mut file = File::create(path).unwrap();
+
+        // Write BA2 header
+        file.write_all(b"BTDX").unwrap(); // Magic
+        file.write_all(&1u32.to_le_bytes()).unwrap(); // Version
+        file.write_all(b"GNRL").unwrap(); // Type
+        file.write_all(&file_count.to_le_bytes()).unwrap(); // File count
+        file.write_all(&0u64.to_le_bytes()).unwrap(); // Names offset
+
+        // Write some dummy data to make the file look real
+        file.write_all(&vec![0u8; 100]).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_scan_flags_known_exclusions() {
+        let (_temp_dir, data_path) = create_test_structure();
+        create_test_ba2(&data_path.join("TestMod2").join("Fallout4 - Main.ba2"), 10);
+
+        let mut config = AppConfig::default();
+        config.extraction.postfixes = vec!["main.ba2".to_string()];
+        let result = scan_for_ba2(&data_path, &config, None).await.unwrap();
+
+        let copy = result
+            .iter()
+            .find(|f| f.file_name == "Fallout4 - Main.ba2")
+            .unwrap();
+        assert!(copy.exclusion.is_some());
+        assert!(copy.health.is_ok());
+        assert!(
+            result
+                .iter()
+                .filter(|f| f.file_name != "Fallout4 - Main.ba2")
+                .all(|f| f.exclusion.is_none())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_for_ba2_basic() {
+        let (_temp_dir, data_path) = create_test_structure();
+
+        let mut config = AppConfig::default();
+        config.extraction.postfixes = vec!["_main.ba2".to_string(), "_textures.ba2".to_string()];
+
+        let result = scan_for_ba2(&data_path, &config, None).await;
+        assert!(result.is_ok());
+
+        let files = result.unwrap();
+        assert_eq!(files.len(), 3); // Should find 3 BA2 files matching postfixes
+
+        // Verify file names
+        let file_names: Vec<String> = files.iter().map(|f| f.file_name.clone()).collect();
+        assert!(file_names.contains(&"TestMod1_Main.ba2".to_string()));
+        assert!(file_names.contains(&"TestMod1_Textures.ba2".to_string()));
+        assert!(file_names.contains(&"TestMod2_Main.ba2".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_scan_for_ba2_with_ignored() {
+        let (_temp_dir, data_path) = create_test_structure();
+
+        let mut config = AppConfig::default();
+        config.extraction.postfixes = vec!["_main.ba2".to_string(), "_textures.ba2".to_string()];
+        config.extraction.ignored_files =
+            vec![IgnorePattern::new(PatternKind::Exact, "TestMod1_Main.ba2")];
+
+        let result = scan_for_ba2(&data_path, &config, None).await;
+        assert!(result.is_ok());
+
+        let files = result.unwrap();
+        assert_eq!(files.len(), 2); // Should find 2 files (one ignored)
+
+        let file_names: Vec<String> = files.iter().map(|f| f.file_name.clone()).collect();
+        assert!(!file_names.contains(&"TestMod1_Main.ba2".to_string()));
+        assert!(file_names.contains(&"TestMod1_Textures.ba2".to_string()));
+        assert!(file_names.contains(&"TestMod2_Main.ba2".to_string()));
+
+        // Ignored archives can be listed too, flagged as such
+        let files = scan_with_ignored(&data_path, &config, None).await.unwrap();
+        assert_eq!(files.len(), 3);
+        let ignored: Vec<&str> = files
+            .iter()
+            .filter(|f| f.ignored)
+            .map(|f| f.file_name.as_str())
+            .collect();
+        assert_eq!(ignored, ["TestMod1_Main.ba2"]);
+    }
+
+    #[tokio::test]
+    async fn test_scan_data_folder_mode() {
+        let (_temp_dir, data_path) = create_test_structure();
+        let data = data_path.join("Data");
+        fs::create_dir(&data).unwrap();
+        File::create(data_path.join("Fallout4.exe")).unwrap();
+        create_test_ba2(&data.join("Loose_Main.ba2"), 10);
+        create_test_ba2(&data.join("Fallout4 - Main.ba2"), 10);
+
+        let mut config = AppConfig::default();
+        config.extraction.postfixes = vec!["main.ba2".to_string()];
+        assert!(scan_for_ba2(&data, &config, None).await.unwrap().is_empty());
+
+        // The game's own archives are never listed
+        config.extraction.data_folder_mode = true;
+        let files = scan_for_ba2(&data, &config, None).await.unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].file_name, "Loose_Main.ba2");
+    }
+
+    #[tokio::test]
+    async fn test_scan_for_ba2_progress() {
+        let (_temp_dir, data_path) = create_test_structure();
+
+        let mut config = AppConfig::default();
+        config.extraction.postfixes = vec!["_main.ba2".to_string()];
+
+        let events = EventBus::new();
+        let mut rx = events.subscribe();
+
+        // Run scan in background task
+        let scan_task =
+            tokio::spawn(async move { scan_for_ba2(&data_path, &config, Some(events)).await });
+
+        // Collect progress updates
+        let mut progress_updates = Vec::new();
+        while let Some(AppEvent::Scan(progress)) = rx.recv().await {
+            progress_updates.push(progress);
+        }
+
+        let result = scan_task.await.unwrap();
+        assert!(result.is_ok());
+
+        // Verify we got progress updates
+        assert!(!progress_updates.is_empty());
+
+        // Check for Started and Complete messages
+        assert!(
+            progress_updates
+                .iter()
+                .any(|p| matches!(p, ScanProgress::Started { .. }))
+        );
+        assert!(
+            progress_updates
+                .iter()
+                .any(|p| matches!(p, ScanProgress::Complete { .. }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_streams_archives() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut expected = Vec::new();
+        for m in 0..20 {
+            let mod_dir = temp_dir.path().join(format!("Mod{m:02}"));
+            fs::create_dir(&mod_dir).unwrap();
+            for a in 0..3 {
+                let name = format!("Mod{m:02} {a} - Main.ba2");
+                create_test_ba2(&mod_dir.join(&name), 1);
+                expected.push(name);
+            }
+        }
+        expected.sort();
+
+        let mut config = AppConfig::default();
+        config.extraction.postfixes = vec!["main.ba2".to_string()];
+        let events = EventBus::new();
+        let mut rx = events.subscribe();
+        let path = temp_dir.path().to_path_buf();
+        let scan_task =
+            tokio::spawn(async move { scan_for_ba2(&path, &config, Some(events)).await });
+
+        // Every archive is published before the scan completes
+        let mut streamed = Vec::new();
+        while let Some(AppEvent::Scan(progress)) = rx.recv().await {
+            match progress {
+                ScanProgress::Found { archives } => {
+                    streamed.extend(archives.into_iter().map(|info| info.file_name));
+                }
+                ScanProgress::Complete { total_files } => {
+                    assert_eq!(streamed.len(), total_files);
+                }
+                _ => {}
+            }
+        }
+        streamed.sort();
+        assert_eq!(streamed, expected);
+
+        // The result keeps the order of the folders
+        let files = scan_task.await.unwrap().unwrap();
+        let mut dirs: Vec<&str> = files.iter().map(|f| f.dir_name.as_str()).collect();
+        dirs.dedup();
+        assert_eq!(dirs.len(), 20);
+    }
+
+    #[tokio::test]
+    async fn test_scan_nonexistent_path() {
+        let config = AppConfig::default();
+        let result = scan_for_ba2(Path::new("/nonexistent/path"), &config, None).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scan_unicode_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path().join("中文模组 🎮");
+        fs::create_dir(&mod_dir).unwrap();
+        create_test_ba2(&mod_dir.join("武器 - Main.ba2"), 3);
+        create_test_ba2(&mod_dir.join("Ωmega - Textures.BA2"), 4);
+
+        let mut config = AppConfig::default();
+        config.extraction.postfixes = vec!["main.ba2".to_string(), "textures.ba2".to_string()];
+        let files = scan_for_ba2(temp_dir.path(), &config, None).await.unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files.iter().all(|f| f.dir_name == "中文模组 🎮"));
+        assert!(files.iter().any(|f| f.file_name == "武器 - Main.ba2"));
+        assert!(files.iter().any(|f| f.file_name == "Ωmega - Textures.BA2"));
+        assert!(files.iter().all(|f| f.full_path.exists()));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_scan_non_utf8_name() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp_dir = TempDir::new().unwrap();
+        let name = OsStr::from_bytes(b"Caf\xe9 - Main.ba2");
+        create_test_ba2(&temp_dir.path().join(name), 1);
+
+        let config = AppConfig::default();
+        let postfixes = PostfixMatcher::new(&config.extraction.postfixes).unwrap();
+        let result = walk(postfixes).list_mod_folder(temp_dir.path(), "Mod");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].path.file_name(), Some(name));
+        assert!(result[0].file_name.contains('\u{fffd}'));
+    }
+
+    #[test]
+    fn test_scan_mod_folder_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = AppConfig::default();
+
+        let postfixes = PostfixMatcher::new(&config.extraction.postfixes).unwrap();
+
+        let result = walk(postfixes).list_mod_folder(temp_dir.path(), "Mod");
+        assert_eq!(result.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_scan_postfix_patterns() {
+        let (_temp_dir, data_path) = create_test_structure();
+
+        let mut config = AppConfig::default();
+        config.extraction.postfixes = vec![
+            "TestMod1_*.ba2".to_string(),
+            r"re:_sounds\.ba2$".to_string(),
+        ];
+        let files = scan_for_ba2(&data_path, &config, None).await.unwrap();
+        let mut file_names: Vec<String> = files.into_iter().map(|f| f.file_name).collect();
+        file_names.sort();
+        assert_eq!(
+            file_names,
+            [
+                "TestMod1_Main.ba2",
+                "TestMod1_Textures.ba2",
+                "TestMod2_Sounds.ba2"
+            ]
+        );
+
+        // Every archive is listed for testing patterns, whatever the postfixes
+        assert_eq!(list_archives(&data_path).unwrap().len(), 4);
+
+        config.extraction.postfixes = vec!["Main".to_string()];
+        assert!(scan_for_ba2(&data_path, &config, None).await.is_err());
+    }
+}
@@ -0,0 +1,407 @@
+//! Checking that the environment can run extractions
+//!
+//! [`self_test`] verifies what support requests usually come down to: the
+//! platform the build runs on, the settings and log folders are writable,
+//! the extraction tool starts and offers an unpack command, the temporary folder has room, Windows accepts
+//! long paths, and the mod folder can be read. The [`SelfTestReport`] renders
+//! as plain text that users can paste into a bug report.
+//!
+//! With `defender_benchmark` enabled, small files are also written into the
+//! mod folder to tell whether antivirus scans slow extractions down (see
+//! [`defender`](crate::platform::defender)).
+
+use crate::ba2::locate::{ToolOrigin, locate_bsarch};
+use crate::config::AppConfig;
+use crate::operations::extract::{uses_builtin_reader, validate_ba2_tool};
+use crate::operations::format::{format_count, format_size};
+use crate::platform::capabilities::PlatformCapabilities;
+use crate::platform::defender::benchmark;
+use crate::platform::drive::{available_space, storage_medium};
+use crate::platform::elevation::can_write;
+use crate::platform::long_paths_enabled;
+use std::fmt::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Free space in the temporary folder below which staged extractions may fail
+const MIN_TEMP_SPACE: u64 = 1 << 30;
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// Works as expected
+    Pass,
+    /// Works, but may cause problems
+    Warning,
+    /// Broken; extractions will fail
+    Fail,
+}
+
+impl fmt::Display for CheckStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Pass => "OK",
+            Self::Warning => "WARN",
+            Self::Fail => "FAIL",
+        })
+    }
+}
+
+/// A single check of the self-test
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfTestCheck {
+    /// What was checked
+    pub name: &'static str,
+    /// Outcome
+    pub status: CheckStatus,
+    /// What was found
+    pub detail: String,
+}
+
+impl SelfTestCheck {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Folders checked by [`self_test`] that the core library cannot locate itself
+#[derive(Debug, Clone, Default)]
+pub struct SelfTestPaths {
+    /// Folder of the settings file
+    pub config_dir: Option<PathBuf>,
+    /// Folder the log files are written to
+    pub log_dir: Option<PathBuf>,
+    /// Mod folder to scan (`None` if none was chosen yet)
+    pub scan_folder: Option<PathBuf>,
+}
+
+/// Results of all checks
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SelfTestReport {
+    /// Checks in the order they ran
+    pub checks: Vec<SelfTestCheck>,
+}
+
+impl SelfTestReport {
+    /// Number of checks with the given outcome
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.checks.iter().filter(|c| c.status == status).count()
+    }
+
+    /// One-line summary of the outcomes
+    pub fn summary(&self) -> String {
+        match (self.count(CheckStatus::Fail), self.count(CheckStatus::Warning)) {
+            (0, 0) => "All checks passed".to_string(),
+            (failed, warnings) => format!(
+                "{} failed, {} warning(s)",
+                format_count(failed as u64),
+                format_count(warnings as u64)
+            ),
+        }
+    }
+
+    /// Plain-text report for support requests
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "Unpackrr {} self-test ({} {})\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            self.summary()
+        );
+        for check in &self.checks {
+            let _ = write!(text, "\n[{}] {}: {}", check.status, check.name, check.detail);
+        }
+        text
+    }
+}
+
+/// Run every check of the self-test
+///
+/// Only the extraction tool is started (without arguments, so it just prints
+/// its usage); every other check is read-only apart from a probe file that is
+/// created and removed again in the folders that must be writable.
+pub async fn self_test(config: &AppConfig, paths: &SelfTestPaths) -> SelfTestReport {
+    let mut checks = Vec::new();
+    let platform = PlatformCapabilities::detect();
+
+    checks.push(platform_check(&platform));
+    checks.push(writable_check("Settings folder", paths.config_dir.as_deref()));
+    checks.push(writable_check("Log folder", paths.log_dir.as_deref()));
+    checks.push(tool_check(config, &platform).await);
+    checks.push(temp_space_check(&std::env::temp_dir()));
+    checks.push(long_path_check());
+    checks.push(scan_folder_check(paths.scan_folder.as_deref()));
+    if config.advanced.diagnostics.defender_benchmark {
+        checks.push(benchmark_check(paths.scan_folder.as_deref()));
+    }
+
+    let report = SelfTestReport { checks };
+    tracing::info!("Self-test: {}", report.summary());
+    report
+}
+
+/// Report the platform and how it affects extraction
+fn platform_check(platform: &PlatformCapabilities) -> SelfTestCheck {
+    const NAME: &str = "Platform";
+    if platform.is_emulated() {
+        SelfTestCheck::new(
+            NAME,
+            CheckStatus::Warning,
+            format!(
+                "{platform}; the {} build of Unpackrr runs faster",
+                platform.machine_arch
+            ),
+        )
+    } else {
+        SelfTestCheck::new(NAME, CheckStatus::Pass, platform.to_string())
+    }
+}
+
+/// Check that a folder exists (or can be created) and accepts new files
+fn writable_check(name: &'static str, dir: Option<&Path>) -> SelfTestCheck {
+    let Some(dir) = dir else {
+        return SelfTestCheck::new(name, CheckStatus::Fail, "Folder could not be determined");
+    };
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        return SelfTestCheck::new(
+            name,
+            CheckStatus::Fail,
+            format!("{} cannot be created: {e}", dir.display()),
+        );
+    }
+    if can_write(dir) {
+        SelfTestCheck::new(name, CheckStatus::Pass, format!("{} is writable", dir.display()))
+    } else {
+        SelfTestCheck::new(
+            name,
+            CheckStatus::Fail,
+            format!("{} is not writable", dir.display()),
+        )
+    }
+}
+
+/// Check that the extraction tool exists and responds like an unpacker
+async fn tool_check(config: &AppConfig, platform: &PlatformCapabilities) -> SelfTestCheck {
+    const NAME: &str = "Extraction tool";
+    if config.advanced.performance.native_extraction {
+        return SelfTestCheck::new(NAME, CheckStatus::Pass, "Built-in archive reader");
+    }
+    if uses_builtin_reader(config, platform) {
+        return SelfTestCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("Built-in archive reader ({})", platform.bsarch),
+        );
+    }
+
+    let tool = locate_bsarch(config);
+    if tool.origin == ToolOrigin::NotFound {
+        return SelfTestCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            "BSArch.exe not found next to the application, in the settings or tools folder, on PATH or as .ba2 handler",
+        );
+    }
+    let (path, origin) = (tool.path.display(), tool.origin);
+    match validate_ba2_tool(&tool.path).await {
+        Ok(Some(version)) => SelfTestCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("BSArch {version} at {path} ({origin})"),
+        ),
+        Ok(None) => SelfTestCheck::new(
+            NAME,
+            CheckStatus::Warning,
+            format!("{path} ({origin}) responds, but its version is unknown"),
+        ),
+        Err(e) => SelfTestCheck::new(NAME, CheckStatus::Fail, e.user_message()),
+    }
+}
+
+/// Check that the temporary folder has room for staged extractions
+fn temp_space_check(temp_dir: &Path) -> SelfTestCheck {
+    const NAME: &str = "Temporary folder space";
+    match available_space(temp_dir) {
+        Some(free) if free >= MIN_TEMP_SPACE => SelfTestCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!("{} free in {}", format_size(free), temp_dir.display()),
+        ),
+        Some(free) => SelfTestCheck::new(
+            NAME,
+            CheckStatus::Warning,
+            format!(
+                "Only {} free in {}; extracting via a temporary folder may fail",
+                format_size(free),
+                temp_dir.display()
+            ),
+        ),
+        None => SelfTestCheck::new(
+            NAME,
+            CheckStatus::Warning,
+            format!("Free space of {} is unknown", temp_dir.display()),
+        ),
+    }
+}
+
+/// Check whether Windows accepts paths over `MAX_PATH`
+fn long_path_check() -> SelfTestCheck {
+    const NAME: &str = "Long path support";
+    if long_paths_enabled() {
+        SelfTestCheck::new(NAME, CheckStatus::Pass, "Enabled")
+    } else {
+        SelfTestCheck::new(
+            NAME,
+            CheckStatus::Warning,
+            "Disabled (LongPathsEnabled); archives with deep folder structures may fail to extract",
+        )
+    }
+}
+
+/// Check that the mod folder can be listed
+fn scan_folder_check(folder: Option<&Path>) -> SelfTestCheck {
+    const NAME: &str = "Mod folder";
+    let Some(folder) = folder else {
+        return SelfTestCheck::new(NAME, CheckStatus::Warning, "No folder selected");
+    };
+    match std::fs::read_dir(folder) {
+        Ok(entries) => SelfTestCheck::new(
+            NAME,
+            CheckStatus::Pass,
+            format!(
+                "{} is readable ({} entries)",
+                folder.display(),
+                format_count(entries.count() as u64)
+            ),
+        ),
+        Err(e) => SelfTestCheck::new(
+            NAME,
+            CheckStatus::Fail,
+            format!("{} cannot be read: {e}", folder.display()),
+        ),
+    }
+}
+
+/// Measure how fast small files are written into the mod folder
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Rounded for display
+fn benchmark_check(folder: Option<&Path>) -> SelfTestCheck {
+    const NAME: &str = "Write speed";
+    let Some(folder) = folder else {
+        return SelfTestCheck::new(NAME, CheckStatus::Warning, "No folder selected");
+    };
+    let result = match benchmark(folder) {
+        Ok(result) => result,
+        Err(e) => {
+            return SelfTestCheck::new(
+                NAME,
+                CheckStatus::Warning,
+                format!(
+                    "Could not write to {}: {}",
+                    folder.display(),
+                    e.user_message()
+                ),
+            );
+        }
+    };
+    if let Some(advice) = result.advice(storage_medium(folder)) {
+        let steps = advice.steps().join("; ");
+        return SelfTestCheck::new(
+            NAME,
+            CheckStatus::Warning,
+            format!(
+                "{}. Add a Defender exclusion for the folder: {steps}",
+                advice.summary()
+            ),
+        );
+    }
+    let scan = result
+        .antimalware_cpu
+        .map(|cpu| format!(", Antimalware Service at {}% of a core", cpu.round() as u64))
+        .unwrap_or_default();
+    SelfTestCheck::new(
+        NAME,
+        CheckStatus::Pass,
+        format!(
+            "{} files written at {}/s{scan}",
+            format_count(result.files as u64),
+            format_size(result.bytes_per_sec() as u64)
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::capabilities::BsarchSupport;
+
+    #[tokio::test]
+    async fn test_self_test_reports_every_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = AppConfig::default();
+        config.advanced.performance.native_extraction = true;
+        let paths = SelfTestPaths {
+            config_dir: Some(dir.path().join("config")),
+            log_dir: None,
+            scan_folder: Some(dir.path().to_path_buf()),
+        };
+
+        let report = self_test(&config, &paths).await;
+
+        assert_eq!(report.checks.len(), 7);
+        assert_eq!(report.checks[0].name, "Platform");
+        assert_eq!(report.checks[1].status, CheckStatus::Pass);
+        assert!(dir.path().join("config").is_dir());
+        assert_eq!(report.checks[2].status, CheckStatus::Fail);
+        assert_eq!(report.checks[3].detail, "Built-in archive reader");
+        assert_eq!(report.checks[6].status, CheckStatus::Pass);
+        assert!(report.count(CheckStatus::Fail) >= 1);
+
+        let text = report.to_text();
+        assert!(text.contains("[FAIL] Log folder: Folder could not be determined"), "{text}");
+        assert!(text.contains("[OK] Settings folder"), "{text}");
+    }
+
+    #[tokio::test]
+    async fn test_benchmark_is_optional() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = AppConfig::default();
+        config.advanced.performance.native_extraction = true;
+        let paths = SelfTestPaths {
+            scan_folder: Some(dir.path().to_path_buf()),
+            ..SelfTestPaths::default()
+        };
+
+        config.advanced.diagnostics.defender_benchmark = true;
+        let report = self_test(&config, &paths).await;
+        assert_eq!(report.checks.len(), 8);
+        assert_eq!(report.checks[7].name, "Write speed");
+        assert_ne!(report.checks[7].status, CheckStatus::Fail);
+        assert_eq!(benchmark_check(None).status, CheckStatus::Warning);
+    }
+
+    #[test]
+    fn test_emulated_build_warns() {
+        let platform = PlatformCapabilities {
+            os: "windows",
+            build_arch: "x86_64",
+            machine_arch: "aarch64",
+            bsarch: BsarchSupport::Emulated,
+            file_associations: true,
+        };
+        let check = platform_check(&platform);
+        assert_eq!(check.status, CheckStatus::Warning);
+        assert!(check.detail.contains("the aarch64 build"));
+    }
+
+    #[test]
+    fn test_missing_scan_folder_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let check = scan_folder_check(Some(&dir.path().join("missing")));
+        assert_eq!(check.status, CheckStatus::Fail);
+        assert_eq!(scan_folder_check(None).status, CheckStatus::Warning);
+    }
+}
@@ -0,0 +1,257 @@
+//! Size specifications shared by the threshold input, config and display
+//!
+//! [`SizeSpec`] accepts both SI ("500MB", 1 KB = 1000 bytes) and IEC
+//! ("1.5 GiB", 1 KiB = 1024 bytes) notations, so any value shown in the UI can
+//! be typed back in and filter the same way.
+
+use crate::config::SizeUnits;
+use crate::error::{Error, Result, ValidationError};
+use crate::operations::format::number_format;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A size in bytes that can be parsed from and displayed as human-readable text
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(try_from = "SizeSpecRepr", into = "u64")]
+pub struct SizeSpec {
+    bytes: u64,
+}
+
+/// Accepted serialized forms: a raw byte count or a size string
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SizeSpecRepr {
+    Bytes(u64),
+    Text(String),
+}
+
+impl SizeSpec {
+    /// Create a size from a byte count
+    pub const fn from_bytes(bytes: u64) -> Self {
+        Self { bytes }
+    }
+
+    /// Get the size in bytes
+    pub const fn bytes(self) -> u64 {
+        self.bytes
+    }
+
+    /// Whether this size is zero (used as "no threshold")
+    pub const fn is_zero(self) -> bool {
+        self.bytes == 0
+    }
+
+    /// Parse a size string such as "500MB", "1.5 GiB", "1,5 MB" or "1024"
+    ///
+    /// - Case-insensitive
+    /// - SI units (B, KB, MB, GB, TB) use powers of 1000
+    /// - IEC units (KiB, MiB, GiB, TiB) use powers of 1024
+    /// - A missing unit means bytes; a bare prefix ("1K") is treated as SI
+    /// - The active locale's decimal separator is accepted alongside '.'
+    /// - Whole numbers are exact up to `u64::MAX` bytes
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    pub fn parse(input: &str) -> Result<Self> {
+        let invalid = || Error::from(ValidationError::InvalidSize(input.trim().to_string()));
+
+        let trimmed = input.trim();
+        let unit_start = trimmed
+            .find(|c: char| c.is_ascii_alphabetic())
+            .unwrap_or(trimmed.len());
+        let (number_str, unit_str) = trimmed.split_at(unit_start);
+
+        let multiplier = unit_multiplier(unit_str.trim()).ok_or_else(invalid)?;
+        let number = normalize_number(number_str);
+
+        // Whole numbers don't go through a float, which is exact to 2^53 only
+        if !number.is_empty() && number.bytes().all(|b| b.is_ascii_digit()) {
+            return number
+                .parse::<u64>()
+                .ok()
+                .and_then(|whole| whole.checked_mul(multiplier))
+                .map(Self::from_bytes)
+                .ok_or_else(invalid);
+        }
+
+        let number: f64 = number.parse().map_err(|_| invalid())?;
+        if !number.is_finite() || number.is_sign_negative() {
+            return Err(invalid());
+        }
+        let bytes = (number * multiplier as f64).round();
+        if bytes >= u64::MAX as f64 {
+            return Err(invalid());
+        }
+
+        Ok(Self::from_bytes(bytes as u64))
+    }
+
+    /// Render the size for editing, using the active locale and unit system
+    ///
+    /// The value is rounded *up* to two decimals so that parsing the text back
+    /// never yields a smaller size than the original.
+    #[allow(clippy::cast_precision_loss)] // Display only, precision loss is acceptable
+    pub fn to_display_string(self) -> String {
+        let format = number_format();
+        let (base, labels): (f64, [&str; 5]) = match format.units {
+            SizeUnits::Binary => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB"]),
+            SizeUnits::Decimal => (1000.0, ["B", "kB", "MB", "GB", "TB"]),
+        };
+
+        let mut value = self.bytes as f64;
+        let mut unit = 0;
+        while value >= base && unit < labels.len() - 1 {
+            value /= base;
+            unit += 1;
+        }
+
+        if unit == 0 {
+            return format!("{} B", self.bytes);
+        }
+
+        let rounded = (value * 100.0).ceil() / 100.0;
+        let number = format!("{rounded:.2}");
+        let number = number.trim_end_matches('0').trim_end_matches('.');
+        let number = number.replace('.', &format.decimal_separator.to_string());
+        format!("{number} {}", labels[unit])
+    }
+}
+
+/// Remove spaces from the numeric part of a size and use '.' as its decimal
+/// separator, accepting the locale's
+fn normalize_number(text: &str) -> String {
+    let decimal_separator = number_format().decimal_separator;
+    let mut normalized: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '\u{a0}' && *c != '\u{202f}')
+        .collect();
+
+    if decimal_separator == '.' {
+        normalized.retain(|c| c != ',');
+    } else if normalized.contains(decimal_separator) {
+        normalized.retain(|c| c != '.');
+        normalized = normalized.replace(decimal_separator, ".");
+    }
+    normalized
+}
+
+/// Get the byte multiplier for a unit string (case-insensitive)
+fn unit_multiplier(unit: &str) -> Option<u64> {
+    let multiplier = match unit.to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1_000,
+        "M" | "MB" => 1_000_000,
+        "G" | "GB" => 1_000_000_000,
+        "T" | "TB" => 1_000_000_000_000,
+        "KI" | "KIB" => 1 << 10,
+        "MI" | "MIB" => 1 << 20,
+        "GI" | "GIB" => 1 << 30,
+        "TI" | "TIB" => 1 << 40,
+        _ => return None,
+    };
+    Some(multiplier)
+}
+
+impl FromStr for SizeSpec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for SizeSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_display_string())
+    }
+}
+
+impl From<u64> for SizeSpec {
+    fn from(bytes: u64) -> Self {
+        Self::from_bytes(bytes)
+    }
+}
+
+impl From<SizeSpec> for u64 {
+    fn from(size: SizeSpec) -> Self {
+        size.bytes
+    }
+}
+
+impl TryFrom<SizeSpecRepr> for SizeSpec {
+    type Error = Error;
+
+    fn try_from(repr: SizeSpecRepr) -> Result<Self> {
+        match repr {
+            SizeSpecRepr::Bytes(bytes) => Ok(Self::from_bytes(bytes)),
+            SizeSpecRepr::Text(text) => Self::parse(&text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_si_and_iec_units() {
+        assert_eq!(SizeSpec::parse("1KB").unwrap().bytes(), 1_000);
+        assert_eq!(SizeSpec::parse("1KiB").unwrap().bytes(), 1_024);
+        assert_eq!(SizeSpec::parse("1.5 MiB").unwrap().bytes(), 1_572_864);
+        assert_eq!(SizeSpec::parse("2gb").unwrap().bytes(), 2_000_000_000);
+        assert_eq!(SizeSpec::parse("1 kB").unwrap().bytes(), 1_000);
+        assert_eq!(SizeSpec::parse("100").unwrap().bytes(), 100);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(SizeSpec::parse("").is_err());
+        assert!(SizeSpec::parse("MB").is_err());
+        assert!(SizeSpec::parse("12 XB").is_err());
+        assert!(SizeSpec::parse("-5MB").is_err());
+        assert!(SizeSpec::parse("-0").is_err());
+        assert!(SizeSpec::parse("18446744073709551616").is_err());
+        assert!(SizeSpec::parse("18446744073709551615 KB").is_err());
+    }
+
+    #[test]
+    fn test_parse_whole_numbers_exactly() {
+        assert_eq!(
+            SizeSpec::parse("9007199254740993").unwrap().bytes(),
+            9_007_199_254_740_993
+        );
+        assert_eq!(SizeSpec::parse(&u64::MAX.to_string()).unwrap().bytes(), u64::MAX);
+        assert_eq!(SizeSpec::parse("1 000 KB").unwrap().bytes(), 1_000_000);
+    }
+
+    #[test]
+    fn test_display_round_trip_never_shrinks() {
+        for bytes in [1, 999, 1_023, 1_025, 1_572_864, 734_003_201, 5_368_709_121] {
+            let size = SizeSpec::from_bytes(bytes);
+            let parsed = SizeSpec::parse(&size.to_display_string()).unwrap();
+            assert!(
+                parsed >= size,
+                "{bytes} displayed as {size} parsed back to {}",
+                parsed.bytes()
+            );
+            // Rounding up to two decimals stays within 1% of a unit step
+            assert!(parsed.bytes() - bytes <= bytes / 100 + 1);
+        }
+    }
+
+    #[test]
+    fn test_serde_accepts_bytes_and_strings() {
+        let from_number: SizeSpec = serde_json::from_str("1024").unwrap();
+        assert_eq!(from_number.bytes(), 1024);
+
+        let from_text: SizeSpec = serde_json::from_str("\"1 KiB\"").unwrap();
+        assert_eq!(from_text.bytes(), 1024);
+
+        assert_eq!(serde_json::to_string(&from_text).unwrap(), "1024");
+    }
+}
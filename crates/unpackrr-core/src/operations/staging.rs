@@ -0,0 +1,376 @@
+//! Temporary extraction folders
+//!
+//! When an extraction dies partway (tool crash, full disk, cancelled run), the
+//! loose files written so far are left in the mod folder, where the game loads
+//! them over the archive. With `use_temp` enabled (the default), archives are
+//! unpacked into a hidden [`StagingDir`] first; the result is checked against
+//! the archive's name table and only then moved into the mod folder. Failed
+//! extractions are discarded.
+//!
+//! Committing renames each top-level folder (`meshes`, `textures`, ...) into
+//! place in one step, so the mod folder never shows half of a folder. Only
+//! folders the mod folder already has are merged file by file. Archives of the
+//! same mod are extracted in parallel, so commits into one destination take
+//! turns. Staging folders abandoned by a crashed run are removed by
+//! [`remove_abandoned`] before the next run.
+
+use crate::error::{BA2Error, Result};
+use crate::platform::wine::CaseFolder;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Prefix of staging folder names, followed by the archive file name
+pub const STAGING_PREFIX: &str = ".unpackrr-staging-";
+
+/// Locks of the destinations commits are moving files into
+static DESTINATIONS: Mutex<BTreeMap<PathBuf, Arc<Mutex<()>>>> = Mutex::new(BTreeMap::new());
+
+/// Temporary folder an archive is extracted into before it is committed
+///
+/// The folder is created inside the destination so the final move is a cheap
+/// rename on the same volume. It is deleted when dropped, so an extraction
+/// that is not committed leaves nothing behind. A commit that fails partway
+/// keeps it, since it holds the files that were not moved yet.
+#[derive(Debug)]
+pub struct StagingDir {
+    path: PathBuf,
+    destination: PathBuf,
+    keep: bool,
+}
+
+impl StagingDir {
+    /// Create an empty staging folder for `archive` inside `destination`
+    ///
+    /// Leftovers of an earlier interrupted run for the same archive are removed.
+    pub fn create(archive: &Path, destination: &Path) -> Result<Self> {
+        let path = staging_path(archive, destination);
+
+        if path.exists() {
+            tracing::debug!("Removing stale staging folder {}", path.display());
+            fs::remove_dir_all(&path)?;
+        }
+        fs::create_dir_all(&path)?;
+
+        Ok(Self {
+            path,
+            destination: destination.to_path_buf(),
+            keep: false,
+        })
+    }
+
+    /// Folder the tool should extract into
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check that every file listed in the archive was extracted
+    ///
+    /// `expected` are archive-internal paths as returned by
+    /// [`crate::ba2::read_file_names`], matched ignoring case.
+    pub fn verify(&self, archive: &Path, expected: &[String]) -> Result<()> {
+        let mut case = CaseFolder::new();
+        let missing: Vec<&String> = expected
+            .iter()
+            .filter(|name| !case.resolve(&self.path, name).is_file())
+            .collect();
+
+        let Some(first) = missing.first() else {
+            return Ok(());
+        };
+        Err(BA2Error::ExtractionFailed {
+            path: archive.to_path_buf(),
+            reason: format!(
+                "{} of {} files are missing after extraction (e.g., {})",
+                missing.len(),
+                expected.len(),
+                first
+            ),
+        }
+        .into())
+    }
+
+    /// Move the extracted files into the destination, replacing existing files
+    ///
+    /// Top-level entries the destination doesn't have yet are renamed into
+    /// place whole; existing folders are merged file by file. Only one commit
+    /// into a destination runs at a time. Returns the number of files moved.
+    /// The staging folder is removed afterwards, unless the commit failed
+    /// partway: then it is kept with the files that were not moved.
+    pub fn commit(mut self) -> Result<usize> {
+        let lock = Arc::clone(
+            DESTINATIONS
+                .lock()
+                .entry(self.destination.clone())
+                .or_default(),
+        );
+        let _turn = lock.lock();
+
+        let result = self.move_into_destination();
+        if let Err(e) = &result {
+            self.keep = true;
+            tracing::warn!(
+                "Keeping staging folder {} after a failed commit: {}",
+                self.path.display(),
+                e
+            );
+        }
+        result
+    }
+
+    /// Move the top-level entries into the destination
+    fn move_into_destination(&self) -> Result<usize> {
+        let mut case = CaseFolder::new();
+        let mut moved = 0;
+        for entry in fs::read_dir(&self.path)? {
+            let entry = entry?;
+            let target = case.child(&self.destination, &entry.file_name());
+            let is_dir = entry.file_type()?.is_dir();
+            if is_dir && target.is_dir() {
+                move_tree(&entry.path(), &target, &mut case, &mut moved)?;
+            } else {
+                let files = if is_dir { count_files(&entry.path()) } else { 1 };
+                fs::rename(entry.path(), &target)?;
+                moved += files;
+            }
+        }
+        tracing::debug!(
+            "Moved {} extracted files from {} into {}",
+            moved,
+            self.path.display(),
+            self.destination.display()
+        );
+        Ok(moved)
+    }
+}
+
+impl Drop for StagingDir {
+    fn drop(&mut self) {
+        if !self.keep
+            && self.path.exists()
+            && let Err(e) = fs::remove_dir_all(&self.path)
+        {
+            tracing::warn!(
+                "Failed to remove staging folder {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// Staging folder of `archive` inside `destination`
+pub fn staging_path(archive: &Path, destination: &Path) -> PathBuf {
+    let name = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    destination.join(format!("{STAGING_PREFIX}{name}"))
+}
+
+/// Remove staging folders in `folder` left behind by an interrupted run
+///
+/// Only call this while no extraction writes into `folder`. Returns the
+/// number of folders removed.
+pub fn remove_abandoned(folder: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return 0;
+    };
+    entries
+        .filter_map(std::result::Result::ok)
+        .filter(|entry| {
+            entry.file_name().to_string_lossy().starts_with(STAGING_PREFIX)
+                && entry.file_type().is_ok_and(|t| t.is_dir())
+        })
+        .filter(|entry| match fs::remove_dir_all(entry.path()) {
+            Ok(()) => {
+                tracing::info!("Removed abandoned staging folder {}", entry.path().display());
+                true
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to remove abandoned staging folder {}: {}",
+                    entry.path().display(),
+                    e
+                );
+                false
+            }
+        })
+        .count()
+}
+
+/// Number of files below `folder`
+fn count_files(folder: &Path) -> usize {
+    fs::read_dir(folder).map_or(0, |entries| {
+        entries
+            .filter_map(std::result::Result::ok)
+            .map(|entry| match entry.file_type() {
+                Ok(t) if t.is_dir() => count_files(&entry.path()),
+                _ => 1,
+            })
+            .sum()
+    })
+}
+
+/// Move the contents of `from` into `to`, merging into existing folders
+///
+/// Files and folders that exist with another case are replaced and merged.
+fn move_tree(from: &Path, to: &Path, case: &mut CaseFolder, moved: &mut usize) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = case.child(to, &entry.file_name());
+        if entry.file_type()?.is_dir() {
+            move_tree(&entry.path(), &target, case, moved)?;
+        } else {
+            fs::rename(entry.path(), &target)?;
+            *moved += 1;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn test_commit_moves_and_replaces_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path();
+        let archive = mod_dir.join("Mod - Main.ba2");
+        write(&mod_dir.join("meshes/old.nif"), "old");
+
+        let staging = StagingDir::create(&archive, mod_dir).unwrap();
+        write(&staging.path().join("meshes/old.nif"), "new");
+        write(&staging.path().join("scripts/a.pex"), "pex");
+
+        let names = vec!["meshes\\old.nif".to_string(), "scripts\\a.pex".to_string()];
+        staging.verify(&archive, &names).unwrap();
+        let staging_path = staging.path().to_path_buf();
+        assert_eq!(staging.commit().unwrap(), 2);
+
+        assert_eq!(fs::read_to_string(mod_dir.join("meshes/old.nif")).unwrap(), "new");
+        assert!(mod_dir.join("scripts/a.pex").is_file());
+        assert!(!staging_path.exists());
+    }
+
+    #[test]
+    fn test_commit_renames_new_folders_whole() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path();
+        let archive = mod_dir.join("Mod - Main.ba2");
+
+        let staging = StagingDir::create(&archive, mod_dir).unwrap();
+        write(&staging.path().join("meshes/a/b.nif"), "nif");
+        write(&staging.path().join("meshes/c.nif"), "nif");
+        write(&staging.path().join("readme.txt"), "txt");
+
+        assert_eq!(staging.commit().unwrap(), 3);
+        assert!(mod_dir.join("meshes/a/b.nif").is_file());
+        assert!(mod_dir.join("meshes/c.nif").is_file());
+        assert!(mod_dir.join("readme.txt").is_file());
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_commit_merges_folders_ignoring_case() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path();
+        let archive = mod_dir.join("Mod - Main.ba2");
+        write(&mod_dir.join("meshes/old.nif"), "old");
+
+        let staging = StagingDir::create(&archive, mod_dir).unwrap();
+        write(&staging.path().join("Meshes/Old.nif"), "new");
+        write(&staging.path().join("Meshes/b.nif"), "nif");
+        let names = vec!["MESHES\\old.nif".to_string(), "Meshes\\B.nif".to_string()];
+        staging.verify(&archive, &names).unwrap();
+
+        assert_eq!(staging.commit().unwrap(), 2);
+        assert_eq!(fs::read_to_string(mod_dir.join("meshes/old.nif")).unwrap(), "new");
+        assert!(mod_dir.join("meshes/b.nif").is_file());
+        assert!(!mod_dir.join("Meshes").exists());
+    }
+
+    #[test]
+    fn test_parallel_commits_merge_into_one_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path();
+
+        let stagings: Vec<StagingDir> = (0..8)
+            .map(|i| {
+                let archive = mod_dir.join(format!("Mod - Part{i}.ba2"));
+                let staging = StagingDir::create(&archive, mod_dir).unwrap();
+                write(&staging.path().join(format!("meshes/part{i}.nif")), "nif");
+                staging
+            })
+            .collect();
+
+        std::thread::scope(|scope| {
+            for staging in stagings {
+                scope.spawn(move || assert_eq!(staging.commit().unwrap(), 1));
+            }
+        });
+
+        for i in 0..8 {
+            assert!(mod_dir.join(format!("meshes/part{i}.nif")).is_file());
+        }
+    }
+
+    #[test]
+    fn test_failed_commit_keeps_staging_folder() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path();
+        let archive = mod_dir.join("Mod - Main.ba2");
+        write(&mod_dir.join("meshes"), "a file where the folder goes");
+
+        let staging = StagingDir::create(&archive, mod_dir).unwrap();
+        write(&staging.path().join("meshes/a.nif"), "nif");
+        let staging_path = staging.path().to_path_buf();
+
+        assert!(staging.commit().is_err());
+        assert!(staging_path.join("meshes/a.nif").is_file());
+    }
+
+    #[test]
+    fn test_remove_abandoned() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path();
+        write(&mod_dir.join(format!("{STAGING_PREFIX}Gone - Main.ba2/meshes/a.nif")), "nif");
+        write(&mod_dir.join(format!("{STAGING_PREFIX}notes.txt")), "not a folder");
+        write(&mod_dir.join("meshes/b.nif"), "nif");
+
+        assert_eq!(remove_abandoned(mod_dir), 1);
+        assert!(!mod_dir.join(format!("{STAGING_PREFIX}Gone - Main.ba2")).exists());
+        assert!(mod_dir.join(format!("{STAGING_PREFIX}notes.txt")).exists());
+        assert!(mod_dir.join("meshes/b.nif").exists());
+        assert_eq!(remove_abandoned(&mod_dir.join("missing")), 0);
+    }
+
+    #[test]
+    fn test_incomplete_extraction_is_discarded() {
+        let temp_dir = TempDir::new().unwrap();
+        let mod_dir = temp_dir.path();
+        let archive = mod_dir.join("Mod - Main.ba2");
+
+        let staging = StagingDir::create(&archive, mod_dir).unwrap();
+        write(&staging.path().join("meshes/a.nif"), "nif");
+
+        let names = vec!["meshes\\a.nif".to_string(), "meshes\\b.nif".to_string()];
+        let err = staging.verify(&archive, &names).unwrap_err();
+        assert!(err.to_string().contains("1 of 2 files are missing"));
+
+        let staging_path = staging.path().to_path_buf();
+        drop(staging);
+        assert!(!staging_path.exists());
+        assert!(!mod_dir.join("meshes").exists());
+    }
+}
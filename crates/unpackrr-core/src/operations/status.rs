@@ -0,0 +1,392 @@
+//! Progress snapshots for external tools
+//!
+//! With `advanced.diagnostics.write_status_file` enabled, [`write_status`] follows the
+//! events of an extraction run and keeps a small JSON file up to date with
+//! the archive being unpacked, the overall percentage, the speed and the
+//! failures so far. Desktop widgets (e.g., Rainmeter) and scripts can read it
+//! to follow a long run while the window is minimized.
+//!
+//! The file is rewritten at most once per [`WRITE_INTERVAL`] and once more
+//! when the run ends. It is replaced atomically, so readers never see half a
+//! snapshot.
+
+use crate::config::AppConfig;
+use crate::error::{Error, Result};
+use crate::operations::events::AppEvent;
+use crate::operations::extract::ExtractionProgress;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+
+/// Name of the status file in the configuration folder
+pub const STATUS_FILE: &str = "status.json";
+
+/// Shortest time between two writes of the status file
+pub const WRITE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Failed archives listed in a snapshot; the count includes all of them
+const MAX_LISTED_FAILURES: usize = 50;
+
+/// State of the run a snapshot describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RunState {
+    /// Archives are being extracted
+    #[default]
+    Running,
+    /// Every archive was processed
+    Finished,
+    /// The batch ended after too many failures in a row
+    Aborted,
+    /// The run ended without finishing (cancelled or closed)
+    Stopped,
+}
+
+/// Progress of an extraction run as written to the status file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgressSnapshot {
+    /// State of the run
+    pub state: RunState,
+
+    /// Start of the run (seconds since the Unix epoch)
+    pub started: u64,
+
+    /// Time of the snapshot (seconds since the Unix epoch)
+    pub updated: u64,
+
+    /// File name of the archive started last
+    pub current_file: String,
+
+    /// Archives finished (extracted or failed)
+    pub done: usize,
+
+    /// Archives in the run, without skipped ones
+    pub total: usize,
+
+    /// Overall progress, including archives being unpacked (0-100)
+    pub percent: u8,
+
+    /// Archive bytes processed per second, once known
+    pub bytes_per_sec: Option<u64>,
+
+    /// Estimated seconds until the run finishes, once known
+    pub eta_secs: Option<u64>,
+
+    /// Archives extracted successfully
+    pub succeeded: usize,
+
+    /// Archives that failed
+    pub failed: usize,
+
+    /// Archives skipped before extraction
+    pub skipped: usize,
+
+    /// File names of the first failed archives
+    pub failures: Vec<String>,
+}
+
+/// Builds the snapshot of a run from its progress events
+#[derive(Debug)]
+pub struct StatusTracker {
+    snapshot: ProgressSnapshot,
+    /// Size and reported percentage of archives being unpacked
+    in_flight: HashMap<PathBuf, (u64, u8)>,
+    completed_bytes: u64,
+    /// Size of all archives in the run, which weights the overall percentage
+    total_bytes: u64,
+    started: Instant,
+}
+
+impl Default for StatusTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatusTracker {
+    /// Start tracking a run
+    pub fn new() -> Self {
+        let now = unix_now();
+        Self {
+            snapshot: ProgressSnapshot {
+                started: now,
+                updated: now,
+                ..ProgressSnapshot::default()
+            },
+            in_flight: HashMap::new(),
+            completed_bytes: 0,
+            total_bytes: 0,
+            started: Instant::now(),
+        }
+    }
+
+    /// Record a progress event
+    pub fn record(&mut self, progress: &ExtractionProgress) {
+        let snapshot = &mut self.snapshot;
+        match progress {
+            ExtractionProgress::Started {
+                file_name,
+                file_path,
+                total,
+                file_size,
+                total_size,
+                ..
+            } => {
+                snapshot.current_file.clone_from(file_name);
+                snapshot.total = *total;
+                self.total_bytes = *total_size;
+                self.in_flight.insert(file_path.clone(), (*file_size, 0));
+            }
+            ExtractionProgress::FileProgress {
+                file_path, percent, ..
+            } => {
+                if let Some((_, done)) = self.in_flight.get_mut(file_path) {
+                    *done = (*percent).min(100);
+                }
+            }
+            ExtractionProgress::Completed {
+                file_name,
+                file_path,
+                success,
+                ..
+            } => {
+                if let Some((size, _)) = self.in_flight.remove(file_path) {
+                    self.completed_bytes += size;
+                }
+                snapshot.done += 1;
+                if *success {
+                    snapshot.succeeded += 1;
+                } else {
+                    snapshot.failed += 1;
+                    if snapshot.failures.len() < MAX_LISTED_FAILURES {
+                        snapshot.failures.push(file_name.clone());
+                    }
+                }
+            }
+            ExtractionProgress::Skipped { .. } => snapshot.skipped += 1,
+            ExtractionProgress::Aborted { .. } => snapshot.state = RunState::Aborted,
+            ExtractionProgress::Finished { .. } if snapshot.state == RunState::Running => {
+                snapshot.state = RunState::Finished;
+            }
+            _ => {}
+        }
+    }
+
+    /// Mark the run as ended, as stopped unless it finished or was aborted
+    pub fn end(&mut self) {
+        if self.snapshot.state == RunState::Running {
+            self.snapshot.state = RunState::Stopped;
+        }
+    }
+
+    /// The current snapshot
+    pub fn snapshot(&self) -> ProgressSnapshot {
+        self.snapshot_at(self.started.elapsed())
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )] // Rates and durations are small, positive and rounded
+    fn snapshot_at(&self, elapsed: Duration) -> ProgressSnapshot {
+        let mut snapshot = self.snapshot.clone();
+        snapshot.updated = unix_now();
+
+        // Archives in flight count with their reported percentage, and each
+        // archive with its size, so many small archives don't hide a big one
+        let processed = self.completed_bytes
+            + self
+                .in_flight
+                .values()
+                .map(|(size, percent)| size * u64::from(*percent) / 100)
+                .sum::<u64>();
+        let fraction = if self.total_bytes > 0 {
+            (processed as f64 / self.total_bytes as f64).min(1.0)
+        } else if snapshot.total > 0 {
+            let partial: f64 = self
+                .in_flight
+                .values()
+                .map(|(_, percent)| f64::from(*percent) / 100.0)
+                .sum();
+            ((snapshot.done as f64 + partial) / snapshot.total as f64).min(1.0)
+        } else {
+            0.0
+        };
+        snapshot.percent = (fraction * 100.0).round() as u8;
+
+        let secs = elapsed.as_secs_f64();
+        if secs >= 1.0 && processed > 0 {
+            snapshot.bytes_per_sec = Some((processed as f64 / secs) as u64);
+        }
+        if snapshot.state == RunState::Running && fraction > 0.0 && secs >= 1.0 {
+            snapshot.eta_secs = Some((secs * (1.0 - fraction) / fraction).round() as u64);
+        }
+        snapshot
+    }
+}
+
+/// Get the path of the status file
+pub fn status_file_path() -> Result<PathBuf> {
+    Ok(AppConfig::config_dir()?.join(STATUS_FILE))
+}
+
+/// Read a status file
+pub fn load_status(path: &Path) -> Result<ProgressSnapshot> {
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| Error::Other(format!("Failed to read status file: {e}")))
+}
+
+/// Write a snapshot to `path`, replacing the previous one atomically
+pub fn save_status(snapshot: &ProgressSnapshot, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(snapshot)
+        .map_err(|e| Error::Other(format!("Failed to serialize status: {e}")))?;
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, content)?;
+    fs::rename(&temp, path)?;
+    Ok(())
+}
+
+/// Keep the status file at `path` up to date with the events of a run
+///
+/// Give it a [`monitor`](crate::operations::EventBus::monitor) of the run's
+/// bus: it returns once the run drops the bus, after writing the final state.
+pub async fn write_status(mut events: mpsc::Receiver<AppEvent>, path: PathBuf) {
+    let mut tracker = StatusTracker::new();
+    let mut last_write: Option<Instant> = None;
+    let write = |tracker: &StatusTracker| {
+        if let Err(e) = save_status(&tracker.snapshot(), &path) {
+            tracing::debug!("Failed to write status file {}: {}", path.display(), e);
+        }
+    };
+
+    write(&tracker);
+    while let Some(event) = events.recv().await {
+        let AppEvent::Extract(progress) = event else {
+            continue;
+        };
+        tracker.record(&progress);
+        if matches!(progress, ExtractionProgress::Finished { .. }) {
+            break;
+        }
+        if last_write.is_none_or(|at| at.elapsed() >= WRITE_INTERVAL) {
+            last_write = Some(Instant::now());
+            write(&tracker);
+        }
+    }
+    tracker.end();
+    write(&tracker);
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operations::EventBus;
+
+    fn started(name: &str, current: usize, total: usize) -> ExtractionProgress {
+        sized(name, current, total, 100, 100 * total as u64)
+    }
+
+    fn sized(
+        name: &str,
+        current: usize,
+        total: usize,
+        file_size: u64,
+        total_size: u64,
+    ) -> ExtractionProgress {
+        ExtractionProgress::Started {
+            file_name: name.to_string(),
+            file_path: PathBuf::from(name),
+            current,
+            total,
+            file_size,
+            total_size,
+        }
+    }
+
+    fn completed(name: &str, success: bool) -> ExtractionProgress {
+        ExtractionProgress::Completed {
+            file_name: name.to_string(),
+            file_path: PathBuf::from(name),
+            success,
+            error: None,
+            warnings: Vec::new(),
+            output_size: None,
+            error_category: None,
+            duration: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_tracker_snapshot() {
+        let mut tracker = StatusTracker::new();
+        tracker.record(&started("a.ba2", 1, 2));
+        tracker.record(&completed("a.ba2", false));
+        tracker.record(&started("b.ba2", 2, 2));
+        tracker.record(&ExtractionProgress::FileProgress {
+            file_name: "b.ba2".to_string(),
+            file_path: PathBuf::from("b.ba2"),
+            percent: 50,
+        });
+
+        let snapshot = tracker.snapshot_at(Duration::from_secs(30));
+        assert_eq!(snapshot.state, RunState::Running);
+        assert_eq!(snapshot.current_file, "b.ba2");
+        assert_eq!((snapshot.done, snapshot.total), (1, 2));
+        assert_eq!(snapshot.percent, 75);
+        assert_eq!(snapshot.eta_secs, Some(10));
+        assert_eq!(snapshot.failures, ["a.ba2"]);
+
+        tracker.record(&completed("b.ba2", true));
+        tracker.end();
+        assert_eq!(tracker.snapshot().state, RunState::Stopped);
+    }
+
+    #[test]
+    fn test_percent_weighted_by_size() {
+        // A finished script archive barely counts next to a big texture archive
+        let mut tracker = StatusTracker::new();
+        tracker.record(&sized("scripts.ba2", 1, 2, 10, 1_000));
+        tracker.record(&completed("scripts.ba2", true));
+        tracker.record(&sized("textures.ba2", 2, 2, 990, 1_000));
+
+        let snapshot = tracker.snapshot_at(Duration::from_secs(30));
+        assert_eq!((snapshot.done, snapshot.percent), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_write_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(STATUS_FILE);
+        let bus = EventBus::new();
+        let writer = tokio::spawn(write_status(bus.monitor(), path.clone()));
+
+        bus.publish(started("a.ba2", 1, 1)).await;
+        bus.publish(completed("a.ba2", true)).await;
+        bus.publish(ExtractionProgress::Finished {
+            successful: 1,
+            failed: 0,
+            skipped: 0,
+        })
+        .await;
+        writer.await.unwrap();
+
+        let snapshot = load_status(&path).unwrap();
+        assert_eq!(snapshot.state, RunState::Finished);
+        assert_eq!((snapshot.succeeded, snapshot.percent), (1, 100));
+        assert!(!dir.path().join("status.json.tmp").exists());
+    }
+}
@@ -0,0 +1,191 @@
+//! Byte-based extraction speed and ETA
+//!
+//! Archives in a run range from a few kilobytes to several gigabytes, so a
+//! files-per-second rate says little about the time left. The tracker counts
+//! the bytes of source archives processed, including the reported progress of
+//! archives still being unpacked, and derives the speed and ETA from that.
+//! Where the unpacked size of finished archives is known (from their checksum
+//! manifests), it also estimates how many bytes are being written.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Minimum time before a rate is reported, so the first archive does not skew it
+const MIN_ELAPSED: Duration = Duration::from_millis(500);
+
+/// Tracks processed bytes of an extraction run
+#[derive(Debug, Clone)]
+pub struct ThroughputTracker {
+    /// Size of every archive in the run that has not finished yet
+    pending: HashMap<PathBuf, u64>,
+    /// Reported completion percentage of archives being unpacked
+    partial: HashMap<PathBuf, u8>,
+    /// Total bytes of archives in the run (excluding skipped ones)
+    total_bytes: u64,
+    /// Bytes of archives that finished (successfully or not)
+    completed_bytes: u64,
+    /// Source bytes of finished archives whose unpacked size is known
+    measured_source_bytes: u64,
+    /// Unpacked bytes of those archives
+    measured_output_bytes: u64,
+    started: Instant,
+}
+
+impl ThroughputTracker {
+    /// Start tracking a run over archives given as (path, size) pairs
+    pub fn new(archives: impl IntoIterator<Item = (PathBuf, u64)>) -> Self {
+        let pending: HashMap<PathBuf, u64> = archives.into_iter().collect();
+        Self {
+            total_bytes: pending.values().sum(),
+            pending,
+            partial: HashMap::new(),
+            completed_bytes: 0,
+            measured_source_bytes: 0,
+            measured_output_bytes: 0,
+            started: Instant::now(),
+        }
+    }
+
+    /// Remove a skipped archive from the run
+    pub fn skip(&mut self, path: &Path) {
+        if let Some(size) = self.pending.remove(path) {
+            self.total_bytes = self.total_bytes.saturating_sub(size);
+        }
+        self.partial.remove(path);
+    }
+
+    /// Record the reported progress of an archive being unpacked
+    pub fn progress(&mut self, path: &Path, percent: u8) {
+        if self.pending.contains_key(path) {
+            self.partial.insert(path.to_path_buf(), percent.min(100));
+        }
+    }
+
+    /// Record a finished archive and, if known, the size of its unpacked files
+    pub fn complete(&mut self, path: &Path, output_size: Option<u64>) {
+        self.partial.remove(path);
+        let Some(size) = self.pending.remove(path) else {
+            return;
+        };
+        self.completed_bytes += size;
+        if let Some(output_size) = output_size {
+            self.measured_source_bytes += size;
+            self.measured_output_bytes += output_size;
+        }
+    }
+
+    /// Bytes of source archives processed so far
+    pub fn processed_bytes(&self) -> u64 {
+        let in_flight: u64 = self
+            .partial
+            .iter()
+            .filter_map(|(path, percent)| {
+                self.pending
+                    .get(path)
+                    .map(|size| size * u64::from(*percent) / 100)
+            })
+            .sum();
+        self.completed_bytes + in_flight
+    }
+
+    /// Bytes of source archives left to process
+    pub fn remaining_bytes(&self) -> u64 {
+        self.total_bytes.saturating_sub(self.processed_bytes())
+    }
+
+    /// Share of the run's source bytes processed, 0-100
+    pub fn percent(&self) -> u8 {
+        if self.total_bytes == 0 {
+            return 0;
+        }
+        u8::try_from(self.processed_bytes().saturating_mul(100) / self.total_bytes)
+            .unwrap_or(100)
+    }
+
+    /// Processing speed in source bytes per second
+    pub fn bytes_per_sec(&self) -> Option<f64> {
+        self.bytes_per_sec_at(self.started.elapsed())
+    }
+
+    /// Estimated time until the run finishes
+    pub fn eta(&self) -> Option<Duration> {
+        self.eta_at(self.started.elapsed())
+    }
+
+    /// Ratio of unpacked to archive bytes, if known for any finished archive
+    #[allow(clippy::cast_precision_loss)] // Byte counts won't exceed f64 precision
+    pub fn expansion_ratio(&self) -> Option<f64> {
+        (self.measured_source_bytes > 0)
+            .then(|| self.measured_output_bytes as f64 / self.measured_source_bytes as f64)
+    }
+
+    /// Estimated bytes written so far, assuming unknown archives expand like known ones
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )] // Byte counts won't exceed f64 precision
+    pub fn written_bytes(&self) -> u64 {
+        let processed = self.processed_bytes();
+        self.expansion_ratio()
+            .map_or(processed, |ratio| (processed as f64 * ratio) as u64)
+    }
+
+    #[allow(clippy::cast_precision_loss)] // Byte counts won't exceed f64 precision
+    fn bytes_per_sec_at(&self, elapsed: Duration) -> Option<f64> {
+        let processed = self.processed_bytes();
+        (elapsed >= MIN_ELAPSED && processed > 0)
+            .then(|| processed as f64 / elapsed.as_secs_f64())
+    }
+
+    #[allow(clippy::cast_precision_loss)] // Byte counts won't exceed f64 precision
+    fn eta_at(&self, elapsed: Duration) -> Option<Duration> {
+        let remaining = self.remaining_bytes();
+        if remaining == 0 {
+            return None;
+        }
+        let rate = self.bytes_per_sec_at(elapsed)?;
+        Some(Duration::from_secs_f64(remaining as f64 / rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> ThroughputTracker {
+        ThroughputTracker::new([
+            (PathBuf::from("big.ba2"), 900),
+            (PathBuf::from("small.ba2"), 100),
+            (PathBuf::from("skipped.ba2"), 5000),
+        ])
+    }
+
+    #[test]
+    fn test_bytes_drive_eta() {
+        let mut tracker = tracker();
+        tracker.skip(Path::new("skipped.ba2"));
+        assert_eq!(tracker.remaining_bytes(), 1000);
+
+        // The small archive finishing barely moves the estimate
+        tracker.complete(Path::new("small.ba2"), None);
+        tracker.progress(Path::new("big.ba2"), 50);
+        assert_eq!(tracker.processed_bytes(), 550);
+        assert_eq!(tracker.percent(), 55);
+
+        let eta = tracker.eta_at(Duration::from_secs(11)).unwrap();
+        assert_eq!(eta.as_secs(), 9);
+        assert!(tracker.eta_at(Duration::from_millis(100)).is_none());
+    }
+
+    #[test]
+    fn test_expansion_ratio() {
+        let mut tracker = tracker();
+        assert_eq!(tracker.expansion_ratio(), None);
+        tracker.complete(Path::new("big.ba2"), Some(2700));
+        tracker.complete(Path::new("small.ba2"), None);
+        assert_eq!(tracker.expansion_ratio(), Some(3.0));
+        assert_eq!(tracker.written_bytes(), 3000);
+    }
+}
@@ -0,0 +1,187 @@
+//! Wabbajack modlist awareness
+//!
+//! Wabbajack installs a curated list of mods, and its users are usually only
+//! allowed to change their own additions. Loading the list's `.wabbajack` file
+//! (or the `modlist` JSON inside it) tags the scanned archives that the list
+//! installs, so they can be filtered out before extracting.
+//!
+//! A `.wabbajack` file is a ZIP archive; only the `modlist` entry is read,
+//! which is either stored or deflated.
+
+use crate::error::{Error, Result, ValidationError};
+use crate::models::FileEntry;
+use crate::operations::zip;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Name of the entry holding the modlist JSON inside a `.wabbajack` file
+const MODLIST_ENTRY: &str = "modlist";
+
+/// Archives installed by a Wabbajack modlist
+#[derive(Debug, Clone, Default)]
+pub struct ModlistIndex {
+    /// Name of the modlist
+    pub name: String,
+    /// Installed archives as lowercase "mod folder/archive name" keys
+    archives: HashSet<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Modlist {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    directives: Vec<Directive>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Directive {
+    #[serde(default)]
+    to: String,
+}
+
+impl ModlistIndex {
+    /// Load a modlist from a `.wabbajack` file or an extracted `modlist` JSON file
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut file = File::open(path)?;
+        let json = if zip::is_zip(&mut file)? {
+            read_zip_entry(&mut file, MODLIST_ENTRY)?
+        } else {
+            let mut json = Vec::new();
+            file.read_to_end(&mut json)?;
+            json
+        };
+
+        let index = Self::from_json(&json).map_err(|e| {
+            Error::from(ValidationError::InvalidInput(format!(
+                "{} is not a Wabbajack modlist: {e}",
+                path.display()
+            )))
+        })?;
+        tracing::info!(
+            "Loaded Wabbajack modlist '{}' with {} archives from {}",
+            index.name,
+            index.len(),
+            path.display()
+        );
+        Ok(index)
+    }
+
+    /// Parse the modlist JSON, keeping the BA2 archives it installs
+    fn from_json(json: &[u8]) -> serde_json::Result<Self> {
+        let json = json.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(json);
+        let modlist: Modlist = serde_json::from_slice(json)?;
+        let archives = modlist
+            .directives
+            .iter()
+            .filter_map(|directive| archive_key(&directive.to))
+            .collect();
+        Ok(Self {
+            name: modlist.name,
+            archives,
+        })
+    }
+
+    /// Number of archives the modlist installs
+    pub fn len(&self) -> usize {
+        self.archives.len()
+    }
+
+    /// Whether the modlist installs no archives
+    pub fn is_empty(&self) -> bool {
+        self.archives.is_empty()
+    }
+
+    /// Whether a scanned archive is installed by the modlist
+    pub fn contains(&self, entry: &FileEntry) -> bool {
+        let key = format!("{}/{}", entry.dir_name, entry.file_name).to_lowercase();
+        self.archives.contains(&key)
+    }
+}
+
+/// Build the lookup key of an installed path ("mods\\Mod\\Mod - Main.ba2")
+fn archive_key(to: &str) -> Option<String> {
+    let mut parts = to.rsplit(['\\', '/']).filter(|part| !part.is_empty());
+    let file_name = parts.next()?;
+    let is_ba2 = Path::new(file_name)
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("ba2"));
+    if !is_ba2 {
+        return None;
+    }
+    let mod_name = parts.next()?;
+    Some(format!("{mod_name}/{file_name}").to_lowercase())
+}
+
+/// Read and decompress a single entry of a ZIP archive
+fn read_zip_entry(file: &mut File, name: &str) -> Result<Vec<u8>> {
+    let entry = zip::entries(file)?
+        .into_iter()
+        .find(|entry| entry.name == name)
+        .ok_or_else(|| {
+            Error::from(ValidationError::InvalidInput(format!(
+                "No '{name}' entry in the Wabbajack file"
+            )))
+        })?;
+    zip::read_entry(file, &entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    const MODLIST_JSON: &str = r#"{
+        "Name": "Test List",
+        "Directives": [
+            { "$type": "FromArchive", "To": "mods\\Listed Mod\\Listed Mod - Main.ba2" },
+            { "$type": "CreateBSA", "To": "mods\\Built Mod\\Built - Textures.BA2" },
+            { "$type": "InlineFile", "To": "mods\\Listed Mod\\meta.ini" }
+        ]
+    }"#;
+
+    fn stored_zip(name: &str, data: &[u8]) -> Vec<u8> {
+        zip::stored_zip(&[(name, data)])
+    }
+
+    fn entry(dir_name: &str, file_name: &str) -> FileEntry {
+        FileEntry::new(
+            file_name.to_string(),
+            0,
+            0,
+            dir_name.to_string(),
+            PathBuf::from(format!("/mods/{dir_name}/{file_name}")),
+        )
+    }
+
+    #[test]
+    fn test_modlist_tags_listed_archives() {
+        let index = ModlistIndex::from_json(MODLIST_JSON.as_bytes()).unwrap();
+        assert_eq!(index.name, "Test List");
+        assert_eq!(index.len(), 2);
+        assert!(index.contains(&entry("Listed Mod", "Listed Mod - Main.ba2")));
+        assert!(index.contains(&entry("built mod", "Built - Textures.ba2")));
+        assert!(!index.contains(&entry("My Own Mod", "Listed Mod - Main.ba2")));
+    }
+
+    #[test]
+    fn test_load_wabbajack_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("Test.wabbajack");
+        std::fs::write(&path, stored_zip(MODLIST_ENTRY, MODLIST_JSON.as_bytes())).unwrap();
+        assert_eq!(ModlistIndex::load(&path).unwrap().len(), 2);
+
+        let json_path = temp_dir.path().join("modlist");
+        std::fs::write(&json_path, MODLIST_JSON).unwrap();
+        assert_eq!(ModlistIndex::load(&json_path).unwrap().name, "Test List");
+
+        std::fs::write(&path, stored_zip("other", b"{}")).unwrap();
+        assert!(ModlistIndex::load(&path).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+//! Minimal ZIP reading
+//!
+//! Enough of the format to list the entries of a ZIP file from its central
+//! directory and to read a stored or deflated entry. Used for `.wabbajack`
+//! modlists and for looking inside `.zip` mod downloads; ZIP64 files are not
+//! supported.
+
+use crate::error::{Error, Result, ValidationError};
+use flate2::read::DeflateDecoder;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+
+/// Signature of the ZIP end of central directory record
+const EOCD_SIGNATURE: u32 = 0x0605_4b50;
+/// Signature of a ZIP central directory entry
+const CENTRAL_SIGNATURE: u32 = 0x0201_4b50;
+/// Signature of a ZIP local file header
+const LOCAL_SIGNATURE: u32 = 0x0403_4b50;
+/// Size of the end of central directory record without its comment
+const EOCD_SIZE: usize = 22;
+/// Maximum length of the ZIP archive comment
+const MAX_COMMENT: u64 = 0xFFFF;
+
+/// An entry of the central directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZipEntry {
+    /// Path of the entry, '/' separated
+    pub name: String,
+    /// Compression method (0 stored, 8 deflated)
+    pub method: u16,
+    /// Size of the entry in the ZIP file
+    pub compressed_size: u64,
+    /// Size of the entry once unpacked
+    pub size: u64,
+    /// Offset of the entry's local header
+    local_offset: u64,
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::from(ValidationError::InvalidInput(reason.to_string()))
+}
+
+/// Whether `file` starts like a ZIP file, leaving it at the start
+pub fn is_zip(file: &mut File) -> Result<bool> {
+    let mut magic = [0; 4];
+    let is_zip = file.read_exact(&mut magic).is_ok() && magic == LOCAL_SIGNATURE.to_le_bytes();
+    file.seek(SeekFrom::Start(0))?;
+    Ok(is_zip)
+}
+
+/// List the entries of a ZIP file
+pub fn entries(file: &mut File) -> Result<Vec<ZipEntry>> {
+    // The end of central directory record sits at the end, before an optional comment
+    let len = file.metadata()?.len();
+    let tail_len = len.min(EOCD_SIZE as u64 + MAX_COMMENT);
+    file.seek(SeekFrom::Start(len - tail_len))?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)?;
+    let eocd = tail
+        .windows(4)
+        .rposition(|w| w == EOCD_SIGNATURE.to_le_bytes())
+        .filter(|&pos| pos + EOCD_SIZE <= tail.len())
+        .ok_or_else(|| invalid("ZIP end of central directory not found"))?;
+    let record = &tail[eocd..];
+    let count = u16_at(record, 10);
+    let directory_offset = u32_at(record, 16);
+    if count == u16::MAX || directory_offset == u32::MAX {
+        return Err(invalid("ZIP64 files are not supported"));
+    }
+
+    file.seek(SeekFrom::Start(u64::from(directory_offset)))?;
+    let mut entries = Vec::with_capacity(usize::from(count));
+    for _ in 0..count {
+        let mut header = [0; 46];
+        file.read_exact(&mut header)?;
+        if u32_at(&header, 0) != CENTRAL_SIGNATURE {
+            return Err(invalid("Corrupted ZIP central directory"));
+        }
+        let name_len = usize::from(u16_at(&header, 28));
+        let skip = i64::from(u16_at(&header, 30)) + i64::from(u16_at(&header, 32));
+
+        let mut name = vec![0; name_len];
+        file.read_exact(&mut name)?;
+        file.seek(SeekFrom::Current(skip))?;
+        entries.push(ZipEntry {
+            name: String::from_utf8_lossy(&name).into_owned(),
+            method: u16_at(&header, 10),
+            compressed_size: u64::from(u32_at(&header, 20)),
+            size: u64::from(u32_at(&header, 24)),
+            local_offset: u64::from(u32_at(&header, 42)),
+        });
+    }
+    Ok(entries)
+}
+
+/// Read and decompress a single entry of a ZIP file
+pub fn read_entry(file: &mut File, entry: &ZipEntry) -> Result<Vec<u8>> {
+    // The data follows the local header, whose extra field may differ
+    file.seek(SeekFrom::Start(entry.local_offset))?;
+    let mut local = [0; 30];
+    file.read_exact(&mut local)?;
+    if u32_at(&local, 0) != LOCAL_SIGNATURE {
+        return Err(invalid("Corrupted ZIP local header"));
+    }
+    let skip = i64::from(u16_at(&local, 26)) + i64::from(u16_at(&local, 28));
+    file.seek(SeekFrom::Current(skip))?;
+
+    let mut data = file.by_ref().take(entry.compressed_size);
+    let mut out = Vec::new();
+    match entry.method {
+        0 => data.read_to_end(&mut out)?,
+        8 => DeflateDecoder::new(data).read_to_end(&mut out)?,
+        method => {
+            return Err(invalid(&format!(
+                "Unsupported ZIP compression method {method}"
+            )));
+        }
+    };
+    Ok(out)
+}
+
+const fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+const fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+/// Build a ZIP file with stored entries
+#[cfg(test)]
+pub fn stored_zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut zip = Vec::new();
+    let mut central = Vec::new();
+    for (name, data) in files {
+        let size = u32::try_from(data.len()).unwrap().to_le_bytes();
+        let name_len = u16::try_from(name.len()).unwrap().to_le_bytes();
+        let local_offset = u32::try_from(zip.len()).unwrap();
+
+        zip.extend(LOCAL_SIGNATURE.to_le_bytes());
+        zip.extend([0; 14]); // version, flags, method, time, date, crc
+        zip.extend(size);
+        zip.extend(size);
+        zip.extend(name_len);
+        zip.extend([0; 2]);
+        zip.extend(name.as_bytes());
+        zip.extend(*data);
+
+        central.extend(CENTRAL_SIGNATURE.to_le_bytes());
+        central.extend([0; 16]); // versions, flags, method, time, date, crc
+        central.extend(size);
+        central.extend(size);
+        central.extend(name_len);
+        central.extend([0; 12]); // extra, comment, disk, attributes
+        central.extend(local_offset.to_le_bytes());
+        central.extend(name.as_bytes());
+    }
+
+    let count = u16::try_from(files.len()).unwrap().to_le_bytes();
+    let directory_offset = u32::try_from(zip.len()).unwrap();
+    let directory_size = u32::try_from(central.len()).unwrap();
+    zip.extend(central);
+    zip.extend(EOCD_SIGNATURE.to_le_bytes());
+    zip.extend([0; 4]);
+    zip.extend(count);
+    zip.extend(count);
+    zip.extend(directory_size.to_le_bytes());
+    zip.extend(directory_offset.to_le_bytes());
+    zip.extend([0; 2]);
+    zip
+}
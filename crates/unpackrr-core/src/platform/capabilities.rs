@@ -0,0 +1,200 @@
+//! What the running build can do on this machine
+//!
+//! Unpackrr builds for `x86_64` and ARM64 Windows and for Linux, but `BSArch` is
+//! an x86 Windows program: ARM64 Windows runs it through its x64 emulation
+//! and Linux only through Wine. [`PlatformCapabilities`] tells the extraction
+//! which unpacker to prefer and lists what was detected in the diagnostics.
+
+use std::fmt;
+
+/// How `BSArch` can be started on this platform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BsarchSupport {
+    /// As a regular Windows program
+    Native,
+    /// Through the x64 emulation of ARM64 Windows
+    Emulated,
+    /// Only through Wine, e.g. from a wrapper script set in Settings
+    Wine,
+}
+
+impl fmt::Display for BsarchSupport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Native => "BSArch runs natively",
+            Self::Emulated => "BSArch runs under x64 emulation",
+            Self::Wine => "BSArch needs Wine",
+        })
+    }
+}
+
+/// Platform of the running build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlatformCapabilities {
+    /// Operating system, as in [`std::env::consts::OS`]
+    pub os: &'static str,
+    /// Architecture the application was built for
+    pub build_arch: &'static str,
+    /// Architecture of the processor, which differs from `build_arch` when
+    /// an `x86_64` build runs under emulation
+    pub machine_arch: &'static str,
+    /// How `BSArch` can be started
+    pub bsarch: BsarchSupport,
+    /// Whether the program opening `.ba2` files can be read from the registry
+    pub file_associations: bool,
+}
+
+impl PlatformCapabilities {
+    /// Detect the capabilities of the running build
+    pub fn detect() -> Self {
+        let build_arch = std::env::consts::ARCH;
+        let machine_arch = native_machine().unwrap_or(build_arch);
+        let bsarch = if !cfg!(windows) {
+            BsarchSupport::Wine
+        } else if matches!(machine_arch, "x86_64" | "x86") {
+            BsarchSupport::Native
+        } else {
+            BsarchSupport::Emulated
+        };
+        Self {
+            os: std::env::consts::OS,
+            build_arch,
+            machine_arch,
+            bsarch,
+            file_associations: cfg!(windows),
+        }
+    }
+
+    /// Whether the application itself runs under emulation
+    pub fn is_emulated(&self) -> bool {
+        self.build_arch != self.machine_arch
+    }
+
+    /// Whether the built-in reader is preferred over `BSArch`
+    pub fn prefers_builtin_reader(&self) -> bool {
+        self.bsarch != BsarchSupport::Native
+    }
+
+    /// Operating system and processor, e.g. "Windows aarch64"
+    pub fn platform(&self) -> String {
+        let os = match self.os {
+            "windows" => "Windows",
+            "linux" => "Linux",
+            "macos" => "macOS",
+            other => other,
+        };
+        if self.is_emulated() {
+            format!(
+                "{os} {} ({} build under emulation)",
+                self.machine_arch, self.build_arch
+            )
+        } else {
+            format!("{os} {}", self.machine_arch)
+        }
+    }
+}
+
+impl fmt::Display for PlatformCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}; {}", self.platform(), self.bsarch)?;
+        if self.prefers_builtin_reader() {
+            f.write_str(", so the built-in reader is used unless a tool is set in Settings")?;
+        }
+        if !self.file_associations {
+            f.write_str("; no .ba2 file associations")?;
+        }
+        Ok(())
+    }
+}
+
+/// Architecture of the processor, as in [`std::env::consts::ARCH`]
+#[cfg(windows)]
+fn native_machine() -> Option<&'static str> {
+    use windows_sys::Win32::System::SystemInformation::{
+        IMAGE_FILE_MACHINE, IMAGE_FILE_MACHINE_AMD64, IMAGE_FILE_MACHINE_ARM64,
+        IMAGE_FILE_MACHINE_I386,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, IsWow64Process2};
+
+    let mut process: IMAGE_FILE_MACHINE = 0;
+    let mut native: IMAGE_FILE_MACHINE = 0;
+    // SAFETY: the pseudo handle of the current process needs no closing and
+    // both outputs are valid for the synchronous call
+    let ok = unsafe { IsWow64Process2(GetCurrentProcess(), &raw mut process, &raw mut native) };
+    if ok == 0 {
+        return None;
+    }
+    match native {
+        IMAGE_FILE_MACHINE_AMD64 => Some("x86_64"),
+        IMAGE_FILE_MACHINE_ARM64 => Some("aarch64"),
+        IMAGE_FILE_MACHINE_I386 => Some("x86"),
+        _ => None,
+    }
+}
+
+/// Architecture of the processor (the build's own outside Windows)
+#[cfg(not(windows))]
+const fn native_machine() -> Option<&'static str> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn windows(build_arch: &'static str, machine_arch: &'static str) -> PlatformCapabilities {
+        PlatformCapabilities {
+            os: "windows",
+            build_arch,
+            machine_arch,
+            bsarch: if machine_arch == "x86_64" {
+                BsarchSupport::Native
+            } else {
+                BsarchSupport::Emulated
+            },
+            file_associations: true,
+        }
+    }
+
+    #[test]
+    fn test_detect() {
+        let detected = PlatformCapabilities::detect();
+        assert_eq!(detected.os, std::env::consts::OS);
+        assert_eq!(detected.build_arch, std::env::consts::ARCH);
+        assert_eq!(detected.file_associations, cfg!(windows));
+        if !cfg!(windows) {
+            assert_eq!(detected.bsarch, BsarchSupport::Wine);
+            assert!(!detected.is_emulated());
+        }
+    }
+
+    #[test]
+    fn test_description() {
+        let x64 = windows("x86_64", "x86_64");
+        assert!(!x64.prefers_builtin_reader());
+        assert_eq!(x64.to_string(), "Windows x86_64; BSArch runs natively");
+
+        let arm = windows("aarch64", "aarch64");
+        assert!(arm.prefers_builtin_reader());
+        assert!(
+            arm.to_string()
+                .starts_with("Windows aarch64; BSArch runs under x64 emulation")
+        );
+
+        let emulated = windows("x86_64", "aarch64");
+        assert!(emulated.is_emulated());
+        assert_eq!(
+            emulated.platform(),
+            "Windows aarch64 (x86_64 build under emulation)"
+        );
+
+        let linux = PlatformCapabilities {
+            os: "linux",
+            build_arch: "aarch64",
+            machine_arch: "aarch64",
+            bsarch: BsarchSupport::Wine,
+            file_associations: false,
+        };
+        assert!(linux.to_string().ends_with("; no .ba2 file associations"));
+    }
+}
@@ -0,0 +1,407 @@
+//! Microsoft Defender slowing down extractions
+//!
+//! Real-time protection scans every file as it is written, which can slow an
+//! extraction of thousands of small files to a fraction of what the drive
+//! manages. [`AntimalwareSampler`] measures the CPU time the Antimalware
+//! Service (`MsMpEng.exe`) spends during a run, and [`ExclusionAdvice::assess`]
+//! compares the run's speed with what the drive should manage. When both point
+//! at Defender, the user is shown how to add a folder exclusion themselves;
+//! nothing is changed on their behalf.
+//!
+//! [`benchmark`] writes a batch of small files to measure the same without an
+//! extraction, for the diagnostics.
+//!
+//! The Antimalware Service only exists on Windows; elsewhere no CPU use is
+//! measured and no advice is given.
+
+use crate::error::Result;
+use crate::operations::format::{format_count, format_size};
+use crate::platform::drive::StorageMedium;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Process name of the Antimalware Service
+pub const ANTIMALWARE_PROCESS: &str = "MsMpEng.exe";
+
+/// Share of the drive's speed below which a run counts as slowed down
+const SLOW_FRACTION: f64 = 0.25;
+
+/// CPU use of the Antimalware Service, in percent of one core, above which it
+/// counts as busy scanning
+const BUSY_CPU_PERCENT: f64 = 25.0;
+
+/// Shortest run whose speed says anything about the drive
+const MIN_SAMPLE: Duration = Duration::from_secs(10);
+
+/// Number of files written by [`benchmark`]
+pub const BENCHMARK_FILES: usize = 500;
+
+/// Size of each file written by [`benchmark`]
+const BENCHMARK_FILE_SIZE: usize = 64 * 1024;
+
+/// Folder [`benchmark`] writes into, inside the tested folder
+const BENCHMARK_DIR: &str = ".unpackrr-benchmark";
+
+/// Measures the CPU time of the Antimalware Service from its creation on
+#[derive(Debug, Clone, Copy)]
+pub struct AntimalwareSampler {
+    started: Instant,
+    cpu_at_start: Option<Duration>,
+}
+
+impl AntimalwareSampler {
+    /// Start measuring
+    pub fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            cpu_at_start: antimalware_cpu_time(),
+        }
+    }
+
+    /// Time since measuring started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Average CPU use of the Antimalware Service since the start, in percent
+    /// of one core
+    ///
+    /// `None` if the service is not running or its CPU time can't be read.
+    pub fn cpu_percent(&self) -> Option<f64> {
+        let start = self.cpu_at_start?;
+        let now = antimalware_cpu_time()?;
+        let wall = self.elapsed().as_secs_f64();
+        (wall > 0.0).then(|| now.saturating_sub(start).as_secs_f64() / wall * 100.0)
+    }
+}
+
+/// Suggestion to exclude a folder from real-time protection
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExclusionAdvice {
+    /// Folder files were written to
+    pub folder: PathBuf,
+
+    /// Measured write speed in bytes per second
+    pub bytes_per_sec: f64,
+
+    /// Medium of the folder's drive
+    pub medium: StorageMedium,
+
+    /// CPU use of the Antimalware Service, in percent of one core
+    pub antimalware_cpu: f64,
+}
+
+impl ExclusionAdvice {
+    /// Suggest an exclusion for `folder` if writing `bytes` to it in `elapsed`
+    /// was far slower than its drive manages while the Antimalware Service was
+    /// busy
+    ///
+    /// Short runs, unknown media and runs without a measured service give no
+    /// advice.
+    #[allow(clippy::cast_precision_loss)] // Byte counts won't exceed f64 precision
+    pub fn assess(
+        folder: &Path,
+        bytes: u64,
+        elapsed: Duration,
+        medium: StorageMedium,
+        antimalware_cpu: Option<f64>,
+    ) -> Option<Self> {
+        if elapsed < MIN_SAMPLE {
+            return None;
+        }
+        let bytes_per_sec = bytes as f64 / elapsed.as_secs_f64();
+        Self::assess_speed(folder, bytes_per_sec, medium, antimalware_cpu)
+    }
+
+    #[allow(clippy::cast_precision_loss)] // Speeds won't exceed f64 precision
+    fn assess_speed(
+        folder: &Path,
+        bytes_per_sec: f64,
+        medium: StorageMedium,
+        antimalware_cpu: Option<f64>,
+    ) -> Option<Self> {
+        let antimalware_cpu = antimalware_cpu.filter(|&cpu| cpu >= BUSY_CPU_PERCENT)?;
+        let expected = medium.typical_write_speed()? as f64;
+        (bytes_per_sec < expected * SLOW_FRACTION).then(|| Self {
+            folder: folder.to_path_buf(),
+            bytes_per_sec,
+            medium,
+            antimalware_cpu,
+        })
+    }
+
+    /// What was measured, in one sentence
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Rounded for display
+    pub fn summary(&self) -> String {
+        format!(
+            "Files were written at {}/s, far below the {}/s {} manages, while Microsoft Defender's Antimalware Service used {}% of a CPU core",
+            format_size(self.bytes_per_sec as u64),
+            format_size(self.medium.typical_write_speed().unwrap_or_default()),
+            self.medium.description(),
+            format_count(self.antimalware_cpu.round() as u64)
+        )
+    }
+
+    /// Steps to add the exclusion in Windows Security
+    pub fn steps(&self) -> Vec<String> {
+        vec![
+            "Open Windows Security from the Start menu".to_string(),
+            "Go to Virus & threat protection > Manage settings (under Virus & threat protection settings)"
+                .to_string(),
+            "Scroll to Exclusions and click Add or remove exclusions".to_string(),
+            format!("Click Add an exclusion > Folder and choose {}", self.folder.display()),
+            "Only exclude folders of mods you trust, and remove the exclusion when you no longer need it"
+                .to_string(),
+        ]
+    }
+
+    /// Summary and numbered steps, for dialogs and reports
+    pub fn to_text(&self) -> String {
+        let steps: Vec<String> = self
+            .steps()
+            .iter()
+            .enumerate()
+            .map(|(i, step)| format!("{}. {step}", i + 1))
+            .collect();
+        format!(
+            "{}.\n\nAdding an exclusion for the folder usually restores the full speed:\n{}",
+            self.summary(),
+            steps.join("\n")
+        )
+    }
+}
+
+/// Result of [`benchmark`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkResult {
+    /// Folder the files were written to
+    pub folder: PathBuf,
+
+    /// Number of files written
+    pub files: usize,
+
+    /// Bytes written
+    pub bytes: u64,
+
+    /// Time taken to write them
+    pub elapsed: Duration,
+
+    /// CPU use of the Antimalware Service meanwhile, in percent of one core
+    pub antimalware_cpu: Option<f64>,
+}
+
+impl BenchmarkResult {
+    /// Write speed in bytes per second
+    #[allow(clippy::cast_precision_loss)] // Byte counts won't exceed f64 precision
+    pub fn bytes_per_sec(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.bytes as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Exclusion advice for the folder, if the benchmark was slowed down
+    ///
+    /// Unlike runs, the benchmark is short by design, so its length is not
+    /// checked.
+    pub fn advice(&self, medium: StorageMedium) -> Option<ExclusionAdvice> {
+        ExclusionAdvice::assess_speed(
+            &self.folder,
+            self.bytes_per_sec(),
+            medium,
+            self.antimalware_cpu,
+        )
+    }
+}
+
+/// Write [`BENCHMARK_FILES`] small files into a temporary folder inside
+/// `folder`, like an extraction does, and measure how fast that goes
+///
+/// The files are removed again afterwards.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Rounded for display
+pub fn benchmark(folder: &Path) -> Result<BenchmarkResult> {
+    let dir = folder.join(BENCHMARK_DIR);
+    fs::create_dir_all(&dir)?;
+
+    // Varied contents, so nothing can skip them as blank
+    let data: Vec<u8> = (0..BENCHMARK_FILE_SIZE)
+        .map(|i| (i.wrapping_mul(31) ^ (i >> 8)).to_le_bytes()[0])
+        .collect();
+    let sampler = AntimalwareSampler::start();
+    let written =
+        (0..BENCHMARK_FILES).try_for_each(|i| fs::write(dir.join(format!("file{i}.nif")), &data));
+    let elapsed = sampler.elapsed();
+    let antimalware_cpu = sampler.cpu_percent();
+
+    if let Err(e) = fs::remove_dir_all(&dir) {
+        tracing::warn!("Failed to remove benchmark folder {}: {}", dir.display(), e);
+    }
+    written?;
+
+    let result = BenchmarkResult {
+        folder: folder.to_path_buf(),
+        files: BENCHMARK_FILES,
+        bytes: (BENCHMARK_FILES * BENCHMARK_FILE_SIZE) as u64,
+        elapsed,
+        antimalware_cpu,
+    };
+    tracing::info!(
+        "Write benchmark in {}: {}/s, Antimalware Service at {:?}% of a core",
+        folder.display(),
+        format_size(result.bytes_per_sec() as u64),
+        antimalware_cpu.map(f64::round)
+    );
+    Ok(result)
+}
+
+/// CPU time (kernel and user) the Antimalware Service used since it started
+#[cfg(windows)]
+fn antimalware_cpu_time() -> Option<Duration> {
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows_sys::Win32::System::Threading::{
+        GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    let pid = find_process(ANTIMALWARE_PROCESS)?;
+    // SAFETY: Only limited query rights are requested, which protected
+    // processes grant; a failure returns a null handle, checked below
+    let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+    if process.is_null() {
+        return None;
+    }
+    let empty = FILETIME {
+        dwLowDateTime: 0,
+        dwHighDateTime: 0,
+    };
+    let (mut creation, mut exit, mut kernel, mut user) = (empty, empty, empty, empty);
+    // SAFETY: `process` was opened above with query rights and the times
+    // point to valid FILETIMEs
+    let ok = unsafe {
+        GetProcessTimes(
+            process,
+            &raw mut creation,
+            &raw mut exit,
+            &raw mut kernel,
+            &raw mut user,
+        )
+    };
+    // SAFETY: `process` was opened above and is not used afterwards
+    unsafe { CloseHandle(process) };
+    if ok == 0 {
+        return None;
+    }
+    // FILETIMEs count 100-nanosecond intervals
+    let ticks =
+        |time: FILETIME| u64::from(time.dwHighDateTime) << 32 | u64::from(time.dwLowDateTime);
+    Some(Duration::from_nanos(
+        (ticks(kernel) + ticks(user)).saturating_mul(100),
+    ))
+}
+
+/// ID of the first running process named `name`
+#[cfg(windows)]
+fn find_process(name: &str) -> Option<u32> {
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+        TH32CS_SNAPPROCESS,
+    };
+
+    // SAFETY: A failed snapshot returns INVALID_HANDLE_VALUE, checked below
+    let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
+    if snapshot == INVALID_HANDLE_VALUE {
+        return None;
+    }
+    // SAFETY: PROCESSENTRY32W is plain data, for which all zeroes are valid
+    let mut entry: PROCESSENTRY32W = unsafe { std::mem::zeroed() };
+    entry.dwSize = u32::try_from(size_of::<PROCESSENTRY32W>()).unwrap_or(u32::MAX);
+
+    let mut pid = None;
+    // SAFETY: `snapshot` is valid and `entry` has its size set
+    let mut more = unsafe { Process32FirstW(snapshot, &raw mut entry) } != 0;
+    while more {
+        let len = entry
+            .szExeFile
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(entry.szExeFile.len());
+        if String::from_utf16_lossy(&entry.szExeFile[..len]).eq_ignore_ascii_case(name) {
+            pid = Some(entry.th32ProcessID);
+            break;
+        }
+        // SAFETY: As for Process32FirstW above
+        more = unsafe { Process32NextW(snapshot, &raw mut entry) } != 0;
+    }
+    // SAFETY: `snapshot` was created above and is not used afterwards
+    unsafe { CloseHandle(snapshot) };
+    pid
+}
+
+#[cfg(not(windows))]
+const fn antimalware_cpu_time() -> Option<Duration> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assess() {
+        let folder = Path::new("D:/Mods/Armor");
+        let run = |bytes: u64, secs: u64, cpu: Option<f64>| {
+            ExclusionAdvice::assess(
+                folder,
+                bytes,
+                Duration::from_secs(secs),
+                StorageMedium::Ssd,
+                cpu,
+            )
+        };
+
+        // 20 MB/s on a SATA SSD with the service busy
+        let advice = run(20_000_000 * 60, 60, Some(80.0)).unwrap();
+        assert!((advice.bytes_per_sec - 20_000_000.0).abs() < 1.0);
+        assert!(
+            advice.summary().contains("a SATA SSD"),
+            "{}",
+            advice.summary()
+        );
+        assert!(
+            advice
+                .to_text()
+                .contains("4. Click Add an exclusion > Folder and choose D:/Mods/Armor")
+        );
+
+        // Fast enough, idle service, too short or nothing measured
+        assert!(run(200_000_000 * 60, 60, Some(80.0)).is_none());
+        assert!(run(20_000_000 * 60, 60, Some(5.0)).is_none());
+        assert!(run(20_000_000 * 5, 5, Some(80.0)).is_none());
+        assert!(run(20_000_000 * 60, 60, None).is_none());
+        assert!(
+            ExclusionAdvice::assess(
+                folder,
+                1,
+                Duration::from_mins(1),
+                StorageMedium::Unknown,
+                Some(80.0)
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_benchmark_cleans_up() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let result = benchmark(temp_dir.path()).unwrap();
+        assert_eq!(result.files, BENCHMARK_FILES);
+        assert_eq!(result.bytes, (BENCHMARK_FILES * BENCHMARK_FILE_SIZE) as u64);
+        assert!(result.bytes_per_sec() > 0.0);
+        assert!(!temp_dir.path().join(BENCHMARK_DIR).exists());
+        #[cfg(not(windows))]
+        assert_eq!(result.advice(StorageMedium::Nvme), None);
+    }
+}
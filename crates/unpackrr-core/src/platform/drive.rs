@@ -0,0 +1,603 @@
+//! Detection of network and removable drives
+//!
+//! Mod folders on NAS shares or USB drives have much higher latency than local
+//! disks and can disappear mid-run. Scanning and extraction use this to retry
+//! more patiently, run fewer jobs in parallel and wait for a reconnect instead
+//! of failing every remaining archive.
+//!
+//! The free space of a drive is checked before extraction starts.
+//!
+//! Local drives are told apart by their [`StorageMedium`]: hard disks seek
+//! for every file read by a parallel job, so runs against them use fewer jobs
+//! and larger buffers than runs against SSDs.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Maximum number of parallel jobs on network and removable drives
+pub const SLOW_DRIVE_MAX_JOBS: usize = 2;
+
+/// Kind of drive a folder resides on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriveKind {
+    /// Local fixed disk (or unknown)
+    Local,
+    /// Network share (SMB, NFS, mapped drive)
+    Network,
+    /// Removable media (USB drive, SD card, optical disc)
+    Removable,
+}
+
+impl DriveKind {
+    /// Whether the drive is slow or may be disconnected during a run
+    pub const fn is_slow(self) -> bool {
+        !matches!(self, Self::Local)
+    }
+
+    /// Number of parallel jobs to run against the drive
+    ///
+    /// Slow drives are limited to [`SLOW_DRIVE_MAX_JOBS`] to avoid saturating
+    /// the link; local drives keep `default`.
+    pub fn max_jobs(self, default: usize) -> usize {
+        if self.is_slow() {
+            default.clamp(1, SLOW_DRIVE_MAX_JOBS)
+        } else {
+            default
+        }
+    }
+
+    /// Human-readable description for warnings
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::Local => "a local drive",
+            Self::Network => "a network drive",
+            Self::Removable => "a removable drive",
+        }
+    }
+}
+
+/// Storage hardware behind a local drive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageMedium {
+    /// Spinning hard disk
+    Hdd,
+    /// SATA (or other non-NVMe) solid state drive
+    Ssd,
+    /// NVM Express solid state drive
+    Nvme,
+    /// Could not be determined (network shares, virtual disks, macOS)
+    Unknown,
+}
+
+impl StorageMedium {
+    /// Number of parallel jobs to run against the medium
+    ///
+    /// Hard disks are limited to 2 jobs and SATA SSDs to 4; NVM Express
+    /// drives and unknown media keep `default`.
+    pub fn max_jobs(self, default: usize) -> usize {
+        match self {
+            Self::Hdd => default.clamp(1, 2),
+            Self::Ssd => default.clamp(1, 4),
+            Self::Nvme | Self::Unknown => default,
+        }
+    }
+
+    /// Rough sustained write speed of the medium in bytes per second
+    ///
+    /// Conservative figures for writing many files; `None` if unknown.
+    pub const fn typical_write_speed(self) -> Option<u64> {
+        match self {
+            Self::Hdd => Some(80_000_000),
+            Self::Ssd => Some(300_000_000),
+            Self::Nvme => Some(800_000_000),
+            Self::Unknown => None,
+        }
+    }
+
+    /// Buffer size in KiB for the built-in reader, larger for fewer seeks on hard disks
+    pub const fn buffer_kb(self) -> u32 {
+        match self {
+            Self::Hdd => 4096,
+            Self::Ssd | Self::Nvme | Self::Unknown => 1024,
+        }
+    }
+
+    /// The medium that limits a run reading from `self` and writing to `other`
+    #[must_use]
+    pub fn slower(self, other: Self) -> Self {
+        if other.max_jobs(usize::MAX) < self.max_jobs(usize::MAX) {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Human-readable description for logs and the Settings page
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::Hdd => "a hard disk",
+            Self::Ssd => "a SATA SSD",
+            Self::Nvme => "an NVMe SSD",
+            Self::Unknown => "an unknown medium",
+        }
+    }
+}
+
+/// Determine the storage medium of the drive `path` resides on
+///
+/// Falls back to [`StorageMedium::Unknown`] when it cannot be determined.
+pub fn storage_medium(path: &Path) -> StorageMedium {
+    let medium = detect_storage_medium(path);
+    tracing::debug!("{} is on {}", path.display(), medium.description());
+    medium
+}
+
+/// Determine the kind of drive `path` resides on
+///
+/// Falls back to [`DriveKind::Local`] when the drive type cannot be determined.
+pub fn drive_kind(path: &Path) -> DriveKind {
+    let kind = detect_drive_kind(path);
+    if kind.is_slow() {
+        tracing::debug!("{} is on {}", path.display(), kind.description());
+    }
+    kind
+}
+
+/// Free space available to the user on the drive of `path`, in bytes
+///
+/// Returns `None` when the free space cannot be determined.
+pub fn available_space(path: &Path) -> Option<u64> {
+    let space = query_available_space(path);
+    if space.is_none() {
+        tracing::debug!("Could not determine the free space of {}", path.display());
+    }
+    space
+}
+
+#[cfg(windows)]
+fn query_available_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let path: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut available = 0u64;
+
+    // SAFETY: `path` is a valid, NUL-terminated UTF-16 string and `available`
+    // outlives the call; the other outputs are optional
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(
+            path.as_ptr(),
+            &raw mut available,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+    };
+    (ok != 0).then_some(available)
+}
+
+#[cfg(not(windows))]
+fn query_available_space(path: &Path) -> Option<u64> {
+    let output = std::process::Command::new("df")
+        .arg("-Pk")
+        .arg(path)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| parse_df_available(&String::from_utf8_lossy(&output.stdout)))?
+}
+
+/// Parse the available space from the output of `df -Pk`
+#[cfg_attr(windows, allow(dead_code))]
+fn parse_df_available(output: &str) -> Option<u64> {
+    // POSIX format: Filesystem 1024-blocks Used Available Capacity Mounted-on
+    let kib: u64 = output
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .nth(3)?
+        .parse()
+        .ok()?;
+    kib.checked_mul(1024)
+}
+
+/// Check whether a folder is still reachable
+///
+/// Used after a failure on a slow drive to tell a disconnected drive apart
+/// from an ordinary extraction error.
+pub fn is_reachable(path: &Path) -> bool {
+    path.is_dir()
+}
+
+#[cfg(windows)]
+fn detect_drive_kind(path: &Path) -> DriveKind {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDriveTypeW;
+
+    const DRIVE_REMOVABLE: u32 = 2;
+    const DRIVE_REMOTE: u32 = 4;
+    const DRIVE_CDROM: u32 = 5;
+
+    let text = path.to_string_lossy();
+    let text = text.strip_prefix(r"\\?\").unwrap_or(&text);
+
+    // UNC paths (\\server\share) are always network locations
+    if text.starts_with(r"\\") || text.starts_with("UNC\\") {
+        return DriveKind::Network;
+    }
+
+    let Some(letter) = text.chars().next().filter(char::is_ascii_alphabetic) else {
+        return DriveKind::Local;
+    };
+    if !text[1..].starts_with(':') {
+        return DriveKind::Local;
+    }
+
+    let root: Vec<u16> = std::ffi::OsStr::new(&format!("{letter}:\\"))
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    // SAFETY: `root` is a valid, NUL-terminated UTF-16 string that outlives the call
+    match unsafe { GetDriveTypeW(root.as_ptr()) } {
+        DRIVE_REMOTE => DriveKind::Network,
+        DRIVE_REMOVABLE | DRIVE_CDROM => DriveKind::Removable,
+        _ => DriveKind::Local,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn detect_drive_kind(path: &Path) -> DriveKind {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    std::fs::read_to_string("/proc/self/mounts").map_or(DriveKind::Local, |mounts| {
+        classify_mount(&path.to_string_lossy(), &mounts)
+    })
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn detect_drive_kind(path: &Path) -> DriveKind {
+    // External volumes are mounted under /Volumes on macOS
+    if path.starts_with("/Volumes") {
+        DriveKind::Removable
+    } else {
+        DriveKind::Local
+    }
+}
+
+/// Find the mount holding `path` in a mount table in `/proc/mounts` format
+///
+/// The mount point with the longest matching prefix wins. Returns the device,
+/// mount point and file system type.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn find_mount(path: &str, mounts: &str) -> Option<(String, String, String)> {
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = fields.next()?;
+            // Spaces in mount points are escaped as \040
+            let mount_point = fields.next()?.replace("\\040", " ");
+            let fs_type = fields.next()?;
+            let inside = path == mount_point
+                || mount_point == "/"
+                || path
+                    .strip_prefix(&mount_point)
+                    .is_some_and(|rest| rest.starts_with('/'));
+            inside.then(|| (device.to_string(), mount_point, fs_type.to_string()))
+        })
+        .max_by_key(|(_, mount_point, _)| mount_point.len())
+}
+
+/// Classify `path` using the mount table in `/proc/mounts` format
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn classify_mount(path: &str, mounts: &str) -> DriveKind {
+    const NETWORK_FS: &[&str] = &[
+        "nfs",
+        "nfs4",
+        "cifs",
+        "smb3",
+        "smbfs",
+        "9p",
+        "afs",
+        "ceph",
+        "fuse.sshfs",
+        "fuse.rclone",
+    ];
+    const REMOVABLE_ROOTS: &[&str] = &["/media/", "/run/media/"];
+
+    let Some((_, mount_point, fs_type)) = find_mount(path, mounts) else {
+        return DriveKind::Local;
+    };
+
+    if NETWORK_FS.contains(&fs_type.as_str()) {
+        DriveKind::Network
+    } else if REMOVABLE_ROOTS
+        .iter()
+        .any(|root| format!("{mount_point}/").starts_with(root))
+    {
+        DriveKind::Removable
+    } else {
+        DriveKind::Local
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn detect_storage_medium(path: &Path) -> StorageMedium {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let Some((device, _, _)) = std::fs::read_to_string("/proc/self/mounts")
+        .ok()
+        .and_then(|mounts| find_mount(&path.to_string_lossy(), &mounts))
+    else {
+        return StorageMedium::Unknown;
+    };
+    // Follow /dev/disk/by-uuid/... and /dev/mapper/... links to the block device
+    let device = std::fs::canonicalize(&device).unwrap_or_else(|_| device.into());
+    let Some(name) = device
+        .strip_prefix("/dev")
+        .ok()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().into_owned())
+    else {
+        return StorageMedium::Unknown;
+    };
+
+    // Partitions have no queue of their own; their parent disk does
+    let rotational = [
+        format!("/sys/class/block/{name}/queue/rotational"),
+        format!("/sys/class/block/{name}/../queue/rotational"),
+    ]
+    .iter()
+    .find_map(|flag| std::fs::read_to_string(flag).ok())
+    .map(|flag| flag.trim() == "1");
+    classify_block_device(&name, rotational)
+}
+
+/// Storage property queries of Windows volumes
+#[cfg(windows)]
+mod storage_query {
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+
+    const IOCTL_STORAGE_QUERY_PROPERTY: u32 = 0x002D_1400;
+    pub(super) const STORAGE_ADAPTER_PROPERTY: u32 = 1;
+    pub(super) const STORAGE_DEVICE_SEEK_PENALTY_PROPERTY: u32 = 7;
+    pub(super) const BUS_TYPE_NVME: u8 = 17;
+
+    /// `STORAGE_PROPERTY_QUERY` with a standard query
+    #[repr(C)]
+    #[allow(dead_code)] // Read and filled in by the system
+    pub(super) struct PropertyQuery {
+        property_id: u32,
+        query_type: u32,
+        additional_parameters: [u8; 4],
+    }
+
+    /// `DEVICE_SEEK_PENALTY_DESCRIPTOR`
+    #[repr(C)]
+    #[allow(dead_code)] // Read and filled in by the system
+    #[derive(Default)]
+    pub(super) struct SeekPenalty {
+        version: u32,
+        size: u32,
+        pub(super) incurs_penalty: u8,
+    }
+
+    /// `STORAGE_ADAPTER_DESCRIPTOR`
+    #[repr(C)]
+    #[allow(dead_code)] // Read and filled in by the system
+    #[derive(Default)]
+    pub(super) struct Adapter {
+        version: u32,
+        size: u32,
+        maximum_transfer_length: u32,
+        maximum_physical_pages: u32,
+        alignment_mask: u32,
+        uses_pio: u8,
+        scans_down: u8,
+        command_queueing: u8,
+        accelerated_transfer: u8,
+        pub(super) bus_type: u8,
+        bus_major_version: u16,
+        bus_minor_version: u16,
+        srb_type: u8,
+        address_type: u8,
+    }
+
+    /// Query a storage property of an open volume into `out`
+    pub(super) fn query<T>(
+        volume: windows_sys::Win32::Foundation::HANDLE,
+        property_id: u32,
+        out: &mut T,
+    ) -> bool {
+        let query = PropertyQuery {
+            property_id,
+            query_type: 0, // PropertyStandardQuery
+            additional_parameters: [0; 4],
+        };
+        let size = |bytes: usize| u32::try_from(bytes).unwrap_or(u32::MAX);
+        let mut returned = 0u32;
+        // SAFETY: `volume` is an open handle, the query and output buffers
+        // are valid for the sizes passed and outlive the synchronous call
+        unsafe {
+            DeviceIoControl(
+                volume,
+                IOCTL_STORAGE_QUERY_PROPERTY,
+                std::ptr::from_ref(&query).cast(),
+                size(std::mem::size_of::<PropertyQuery>()),
+                std::ptr::from_mut(out).cast(),
+                size(std::mem::size_of::<T>()),
+                &raw mut returned,
+                std::ptr::null_mut(),
+            ) != 0
+        }
+    }
+}
+
+#[cfg(windows)]
+fn detect_storage_medium(path: &Path) -> StorageMedium {
+    use std::os::windows::ffi::OsStrExt;
+    use storage_query::{
+        Adapter, BUS_TYPE_NVME, STORAGE_ADAPTER_PROPERTY, STORAGE_DEVICE_SEEK_PENALTY_PROPERTY,
+        SeekPenalty, query,
+    };
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+    };
+
+    let text = path.to_string_lossy();
+    let text = text.strip_prefix(r"\\?\").unwrap_or(&text);
+    let Some(letter) = text.chars().next().filter(char::is_ascii_alphabetic) else {
+        return StorageMedium::Unknown;
+    };
+    if !text[1..].starts_with(':') {
+        return StorageMedium::Unknown;
+    }
+
+    let volume: Vec<u16> = std::ffi::OsStr::new(&format!(r"\\.\{letter}:"))
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    // SAFETY: `volume` is a valid, NUL-terminated UTF-16 string; no access
+    // rights are requested, which is enough to query storage properties
+    let handle = unsafe {
+        CreateFileW(
+            volume.as_ptr(),
+            0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            std::ptr::null(),
+            OPEN_EXISTING,
+            0,
+            std::ptr::null_mut(),
+        )
+    };
+    if handle == INVALID_HANDLE_VALUE {
+        return StorageMedium::Unknown;
+    }
+
+    let mut adapter = Adapter::default();
+    let mut seek_penalty = SeekPenalty::default();
+    let medium = if query(handle, STORAGE_ADAPTER_PROPERTY, &mut adapter)
+        && adapter.bus_type == BUS_TYPE_NVME
+    {
+        StorageMedium::Nvme
+    } else if query(
+        handle,
+        STORAGE_DEVICE_SEEK_PENALTY_PROPERTY,
+        &mut seek_penalty,
+    ) {
+        if seek_penalty.incurs_penalty != 0 {
+            StorageMedium::Hdd
+        } else {
+            StorageMedium::Ssd
+        }
+    } else {
+        StorageMedium::Unknown
+    };
+
+    // SAFETY: `handle` was opened above and is not used afterwards
+    unsafe { CloseHandle(handle) };
+    medium
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn detect_storage_medium(_path: &Path) -> StorageMedium {
+    StorageMedium::Unknown
+}
+
+/// Classify a Linux block device by its name and rotational flag
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn classify_block_device(name: &str, rotational: Option<bool>) -> StorageMedium {
+    if name.starts_with("nvme") {
+        return StorageMedium::Nvme;
+    }
+    match rotational {
+        Some(true) => StorageMedium::Hdd,
+        Some(false) => StorageMedium::Ssd,
+        None => StorageMedium::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_mount() {
+        let mounts = "\
+/dev/sda1 / ext4 rw 0 0
+//nas/mods /mnt/nas cifs rw 0 0
+/dev/sdb1 /run/media/me/USB\\040Stick vfat rw 0 0
+/dev/sda2 /home ext4 rw 0 0
+";
+        assert_eq!(classify_mount("/home/me/Mods", mounts), DriveKind::Local);
+        assert_eq!(
+            classify_mount("/mnt/nas/Fallout 4/Mods", mounts),
+            DriveKind::Network
+        );
+        assert_eq!(
+            classify_mount("/run/media/me/USB Stick/Mods", mounts),
+            DriveKind::Removable
+        );
+        assert_eq!(classify_mount("/mnt/nasty", mounts), DriveKind::Local);
+    }
+
+    #[test]
+    fn test_storage_medium() {
+        let mounts = "\
+/dev/nvme0n1p2 / ext4 rw 0 0
+/dev/sdb1 /mnt/games ext4 rw 0 0
+";
+        assert_eq!(
+            find_mount("/mnt/games/Mods", mounts).map(|(device, ..)| device),
+            Some("/dev/sdb1".to_string())
+        );
+        assert_eq!(
+            classify_block_device("nvme0n1p2", None),
+            StorageMedium::Nvme
+        );
+        assert_eq!(
+            classify_block_device("sdb1", Some(true)),
+            StorageMedium::Hdd
+        );
+        assert_eq!(
+            classify_block_device("sda", Some(false)),
+            StorageMedium::Ssd
+        );
+        assert_eq!(classify_block_device("dm-0", None), StorageMedium::Unknown);
+
+        assert_eq!(StorageMedium::Hdd.max_jobs(8), 2);
+        assert_eq!(StorageMedium::Ssd.max_jobs(8), 4);
+        assert_eq!(StorageMedium::Nvme.max_jobs(8), 8);
+        assert_eq!(
+            StorageMedium::Nvme.slower(StorageMedium::Hdd),
+            StorageMedium::Hdd
+        );
+        assert_eq!(
+            StorageMedium::Ssd.slower(StorageMedium::Unknown),
+            StorageMedium::Ssd
+        );
+    }
+
+    #[test]
+    fn test_parse_df_available() {
+        let output = "\
+Filesystem     1024-blocks      Used Available Capacity Mounted on
+/dev/sda1        479151816 300000000 154775016      66% /
+";
+        assert_eq!(parse_df_available(output), Some(154_775_016 * 1024));
+        assert_eq!(parse_df_available("df: /missing: No such file"), None);
+    }
+
+    #[test]
+    fn test_is_reachable() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert!(is_reachable(temp_dir.path()));
+        assert!(!is_reachable(&temp_dir.path().join("unplugged")));
+    }
+}
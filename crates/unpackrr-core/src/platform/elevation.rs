@@ -0,0 +1,283 @@
+//! Detection of protected folders and process elevation
+//!
+//! Extracting into folders such as Program Files fails with permission errors
+//! unless the process runs elevated. These helpers let the UI warn before a
+//! run starts instead of reporting a wall of failures afterwards.
+
+use crate::error::{Error, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Kind of protected location a folder resides in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProtectedLocation {
+    /// `Program Files` or `Program Files (x86)`
+    ProgramFiles,
+    /// The Windows system folder
+    Windows,
+    /// A OneDrive-synchronized folder (may be read-only or cloud-only)
+    OneDrive,
+}
+
+impl ProtectedLocation {
+    /// Human-readable description for warnings
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::ProgramFiles => "Program Files",
+            Self::Windows => "the Windows folder",
+            Self::OneDrive => "a OneDrive folder",
+        }
+    }
+}
+
+/// Result of checking whether a folder can be written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FolderAccess {
+    /// Protected location the folder resides in, if any
+    pub location: Option<ProtectedLocation>,
+
+    /// Whether a test file could be created in the folder
+    pub writable: bool,
+}
+
+impl FolderAccess {
+    /// Whether extraction into the folder needs an elevated process
+    #[allow(clippy::missing_const_for_fn)] // is_elevated is only const outside Windows
+    pub fn needs_elevation(&self) -> bool {
+        !self.writable && !is_elevated()
+    }
+}
+
+/// Check whether `path` is protected and writable by the current process
+pub fn check_folder_access(path: &Path) -> FolderAccess {
+    FolderAccess {
+        location: protected_location(path),
+        writable: can_write(path),
+    }
+}
+
+/// First of `folders` that can only be written to by an elevated process
+///
+/// Extraction writes next to each archive, so pass the folders of the
+/// archives of a run; each folder is probed once.
+pub fn find_unwritable<'a>(
+    folders: impl IntoIterator<Item = &'a Path>,
+) -> Option<(&'a Path, FolderAccess)> {
+    let mut checked = std::collections::HashSet::new();
+    folders
+        .into_iter()
+        .filter(|folder| checked.insert(*folder))
+        .map(|folder| (folder, check_folder_access(folder)))
+        .find(|(_, access)| access.needs_elevation())
+}
+
+/// Get the protected location `path` resides in, if any
+pub fn protected_location(path: &Path) -> Option<ProtectedLocation> {
+    classify_path(path, &protected_roots())
+}
+
+/// Match `path` against a list of protected roots (case-insensitive)
+fn classify_path(
+    path: &Path,
+    roots: &[(PathBuf, ProtectedLocation)],
+) -> Option<ProtectedLocation> {
+    let normalize = |p: &Path| {
+        p.to_string_lossy()
+            .replace('\\', "/")
+            .trim_end_matches('/')
+            .to_lowercase()
+    };
+    let path = normalize(path);
+
+    roots.iter().find_map(|(root, location)| {
+        let root = normalize(root);
+        let inside = !root.is_empty()
+            && path
+                .strip_prefix(&root)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+        inside.then_some(*location)
+    })
+}
+
+/// Protected roots taken from the environment
+fn protected_roots() -> Vec<(PathBuf, ProtectedLocation)> {
+    [
+        ("ProgramFiles", ProtectedLocation::ProgramFiles),
+        ("ProgramFiles(x86)", ProtectedLocation::ProgramFiles),
+        ("ProgramW6432", ProtectedLocation::ProgramFiles),
+        ("SystemRoot", ProtectedLocation::Windows),
+        ("OneDrive", ProtectedLocation::OneDrive),
+        ("OneDriveConsumer", ProtectedLocation::OneDrive),
+        ("OneDriveCommercial", ProtectedLocation::OneDrive),
+    ]
+    .into_iter()
+    .filter_map(|(var, location)| {
+        std::env::var_os(var)
+            .filter(|value| !value.is_empty())
+            .map(|value| (PathBuf::from(value), location))
+    })
+    .collect()
+}
+
+/// Prefix of the probe files [`can_write`] creates, followed by the process ID
+const WRITE_TEST_PREFIX: &str = ".unpackrr-write-test-";
+
+/// Check whether a file can be created in `dir`
+///
+/// Creates and removes a small probe file, which is more reliable than
+/// inspecting permissions (ACLs, UAC virtualization, controlled folder access).
+pub fn can_write(dir: &Path) -> bool {
+    let probe = dir.join(format!("{WRITE_TEST_PREFIX}{}", std::process::id()));
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(e) => {
+            tracing::debug!("{} is not writable: {}", dir.display(), e);
+            false
+        }
+    }
+}
+
+/// Check whether the current process runs with administrator rights
+#[cfg(windows)]
+pub fn is_elevated() -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::Security::{
+        GetTokenInformation, TOKEN_ELEVATION, TOKEN_QUERY, TokenElevation,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    let mut token: HANDLE = std::ptr::null_mut();
+    // SAFETY: the pseudo handle of the current process needs no closing and
+    // `token` is valid for the synchronous call
+    if unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &raw mut token) } == 0 {
+        tracing::debug!(
+            "Failed to open the process token: {}",
+            std::io::Error::last_os_error()
+        );
+        return false;
+    }
+
+    let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+    let mut returned = 0u32;
+    // SAFETY: `token` was opened with TOKEN_QUERY and `elevation` is a
+    // TOKEN_ELEVATION of the size passed in
+    let ok = unsafe {
+        GetTokenInformation(
+            token,
+            TokenElevation,
+            (&raw mut elevation).cast(),
+            u32::try_from(size_of::<TOKEN_ELEVATION>()).unwrap_or(u32::MAX),
+            &raw mut returned,
+        )
+    };
+    // SAFETY: `token` is a handle this function opened
+    unsafe { CloseHandle(token) };
+    ok != 0 && elevation.TokenIsElevated != 0
+}
+
+/// Check whether the current process runs with administrator rights
+///
+/// Elevation is only offered on Windows, so this always returns `false` here.
+#[cfg(not(windows))]
+pub const fn is_elevated() -> bool {
+    false
+}
+
+/// Whether relaunching elevated is supported on this platform
+pub const fn can_relaunch_elevated() -> bool {
+    cfg!(windows)
+}
+
+/// Start a new elevated instance of the application
+///
+/// Shows the UAC prompt; returns an error if the user declines it. The caller
+/// is responsible for exiting the current instance afterwards.
+#[cfg(windows)]
+pub fn relaunch_elevated() -> Result<()> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let exe = std::env::current_exe()?;
+    let command = format!(
+        "Start-Process -FilePath '{}' -Verb RunAs",
+        exe.to_string_lossy().replace('\'', "''")
+    );
+
+    let status = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &command])
+        .creation_flags(CREATE_NO_WINDOW)
+        .status()?;
+
+    if status.success() {
+        tracing::info!("Relaunched elevated: {}", exe.display());
+        Ok(())
+    } else {
+        Err(Error::other("Elevation was declined or failed"))
+    }
+}
+
+/// Start a new elevated instance of the application (unsupported here)
+#[cfg(not(windows))]
+pub fn relaunch_elevated() -> Result<()> {
+    Err(Error::other(
+        "Relaunching with elevated rights is only supported on Windows",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_classify_path() {
+        let roots = vec![
+            (
+                PathBuf::from(r"C:\Program Files"),
+                ProtectedLocation::ProgramFiles,
+            ),
+            (
+                PathBuf::from(r"C:\Users\me\OneDrive"),
+                ProtectedLocation::OneDrive,
+            ),
+        ];
+
+        assert_eq!(
+            classify_path(Path::new(r"c:\program files\Steam\Fallout 4\Data"), &roots),
+            Some(ProtectedLocation::ProgramFiles)
+        );
+        assert_eq!(
+            classify_path(Path::new("C:/Users/me/OneDrive/Mods"), &roots),
+            Some(ProtectedLocation::OneDrive)
+        );
+        assert_eq!(
+            classify_path(Path::new(r"C:\Program Files Extra\Mods"), &roots),
+            None
+        );
+        assert_eq!(classify_path(Path::new(r"D:\Mods"), &roots), None);
+    }
+
+    #[test]
+    fn test_can_write() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(can_write(temp_dir.path()));
+        assert!(!can_write(&temp_dir.path().join("missing")));
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_find_unwritable() {
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("missing");
+        let folders = [temp_dir.path(), missing.as_path(), temp_dir.path()];
+        assert_eq!(find_unwritable(folders[..1].iter().copied()), None);
+        if !is_elevated() {
+            let (folder, access) = find_unwritable(folders).unwrap();
+            assert_eq!(folder, missing);
+            assert!(!access.writable);
+        }
+    }
+}
@@ -3,6 +3,14 @@
 //! This module provides platform-specific implementations for Windows integration
 //! and stubs for other platforms.
 
+pub mod capabilities;
+pub mod defender;
+pub mod drive;
+pub mod elevation;
+pub mod priority;
+pub mod session;
+pub mod wine;
+
 #[cfg(windows)]
 mod windows;
 
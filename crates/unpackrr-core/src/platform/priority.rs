@@ -0,0 +1,237 @@
+//! Running extractions at low priority
+//!
+//! Long runs can go on while the user plays or works if the extraction yields
+//! the CPU and disk to everything else. The mode is global and can be switched
+//! during a run: `BSArch` processes are registered with [`track_process`] while
+//! they run, and the worker threads of the built-in reader follow the mode
+//! through [`ThreadPriority`].
+//!
+//! On Windows, processes get the below-normal priority class and threads the
+//! background mode, which also lowers their disk priority; both are restored
+//! when the mode is switched off. Elsewhere processes and threads are reniced
+//! with `renice`, and on Linux put in the idle I/O class with `ionice`.
+//! Raising their priority again needs privileges most users don't have, so
+//! switching the mode off there only affects archives started afterwards.
+
+use crate::error::Result;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether extractions run at low priority
+static LOW_PRIORITY: AtomicBool = AtomicBool::new(false);
+
+/// Processes of running extractions, by ID
+static PROCESSES: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+/// Whether extractions run at low priority
+pub fn is_low_priority() -> bool {
+    LOW_PRIORITY.load(Ordering::Relaxed)
+}
+
+/// Switch the low-priority mode, applying it to the running processes
+///
+/// Worker threads pick the change up with [`ThreadPriority::sync`].
+pub fn set_low_priority(enabled: bool) {
+    if LOW_PRIORITY.swap(enabled, Ordering::Relaxed) == enabled {
+        return;
+    }
+    tracing::info!(
+        "Low-priority extraction {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    for &pid in PROCESSES.lock().iter() {
+        if let Err(e) = set_process_priority(pid, enabled) {
+            tracing::debug!("Could not change the priority of process {}: {}", pid, e);
+        }
+    }
+}
+
+/// A running process whose priority follows the mode
+///
+/// The process is forgotten when this is dropped.
+#[derive(Debug)]
+pub struct TrackedProcess {
+    pid: u32,
+}
+
+impl Drop for TrackedProcess {
+    fn drop(&mut self) {
+        PROCESSES.lock().retain(|&pid| pid != self.pid);
+    }
+}
+
+/// Register a spawned process, lowering its priority if the mode is on
+pub fn track_process(pid: u32) -> TrackedProcess {
+    let mut processes = PROCESSES.lock();
+    if is_low_priority()
+        && let Err(e) = set_process_priority(pid, true)
+    {
+        tracing::debug!("Could not lower the priority of process {}: {}", pid, e);
+    }
+    // Registered under the same lock, so a switch of the mode can't miss it
+    processes.push(pid);
+    drop(processes);
+    TrackedProcess { pid }
+}
+
+/// Priority of the current worker thread, following the mode
+///
+/// A lowered priority is restored when this is dropped, as the thread goes
+/// back to a pool.
+#[derive(Debug, Default)]
+pub struct ThreadPriority {
+    lowered: bool,
+}
+
+impl ThreadPriority {
+    /// Follow the mode on the current thread
+    pub fn new() -> Self {
+        let mut priority = Self::default();
+        priority.sync();
+        priority
+    }
+
+    /// Lower or restore the priority of the current thread if the mode changed
+    ///
+    /// Cheap enough to call for every progress update.
+    pub fn sync(&mut self) {
+        let low = is_low_priority();
+        if low == self.lowered {
+            return;
+        }
+        // Not retried on failure, which would run the tools for every update
+        self.lowered = low;
+        if let Err(e) = set_thread_priority(low) {
+            tracing::debug!("Could not change the priority of a worker thread: {}", e);
+        }
+    }
+}
+
+impl Drop for ThreadPriority {
+    fn drop(&mut self) {
+        if self.lowered
+            && let Err(e) = set_thread_priority(false)
+        {
+            tracing::debug!("Could not restore the priority of a worker thread: {}", e);
+        }
+    }
+}
+
+#[cfg(windows)]
+fn set_process_priority(pid: u32, low: bool) -> Result<()> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        BELOW_NORMAL_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS, OpenProcess, PROCESS_SET_INFORMATION,
+        SetPriorityClass,
+    };
+
+    // SAFETY: Only the right to change the priority is requested; a failure
+    // returns a null handle, checked below
+    let process = unsafe { OpenProcess(PROCESS_SET_INFORMATION, 0, pid) };
+    if process.is_null() {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let class = if low {
+        BELOW_NORMAL_PRIORITY_CLASS
+    } else {
+        NORMAL_PRIORITY_CLASS
+    };
+    // SAFETY: `process` was opened above with the right to set its priority
+    let ok = unsafe { SetPriorityClass(process, class) };
+    let error = std::io::Error::last_os_error();
+    // SAFETY: `process` was opened above and is not used afterwards
+    unsafe { CloseHandle(process) };
+    if ok == 0 {
+        return Err(error.into());
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn set_thread_priority(low: bool) -> Result<()> {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_BEGIN,
+        THREAD_MODE_BACKGROUND_END,
+    };
+
+    let mode = if low {
+        THREAD_MODE_BACKGROUND_BEGIN
+    } else {
+        THREAD_MODE_BACKGROUND_END
+    };
+    // SAFETY: The pseudo handle of the current thread is always valid
+    if unsafe { SetThreadPriority(GetCurrentThread(), mode) } == 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn set_process_priority(pid: u32, low: bool) -> Result<()> {
+    renice(pid, low)
+}
+
+#[cfg(target_os = "linux")]
+fn set_thread_priority(low: bool) -> Result<()> {
+    // Linux schedules threads by their own ID, e.g. "/proc/1234/task/1240"
+    let link = std::fs::read_link("/proc/thread-self")?;
+    let tid = link
+        .file_name()
+        .and_then(|name| name.to_str()?.parse().ok())
+        .ok_or_else(|| crate::error::Error::other("Could not read the thread ID"))?;
+    renice(tid, low)
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn set_thread_priority(_low: bool) -> Result<()> {
+    Err(crate::error::Error::other(
+        "Thread priorities are not supported on this platform",
+    ))
+}
+
+/// Niceness of low-priority processes and threads
+#[cfg(not(windows))]
+const LOW_NICENESS: &str = "10";
+
+#[cfg(not(windows))]
+fn renice(id: u32, low: bool) -> Result<()> {
+    let id = id.to_string();
+    // The priority without `-n` is absolute with both the BSD and Linux tools
+    run("renice", &[if low { LOW_NICENESS } else { "0" }, "-p", &id])?;
+    #[cfg(target_os = "linux")]
+    run("ionice", &["-c", if low { "3" } else { "0" }, "-p", &id])?;
+    Ok(())
+}
+
+/// Run a tool quietly, failing if it does not succeed
+#[cfg(not(windows))]
+fn run(program: &str, args: &[&str]) -> Result<()> {
+    use std::process::{Command, Stdio};
+
+    let status = Command::new(program)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| crate::error::Error::other(format!("Could not run {program}: {e}")))?;
+    if !status.success() {
+        return Err(crate::error::Error::other(format!(
+            "{program} exited with {status}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracked_process() {
+        // No priority is changed while the mode is off
+        let tracked = track_process(u32::MAX);
+        assert!(PROCESSES.lock().contains(&u32::MAX));
+        drop(tracked);
+        assert!(!PROCESSES.lock().contains(&u32::MAX));
+    }
+}
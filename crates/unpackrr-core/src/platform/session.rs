@@ -0,0 +1,155 @@
+//! Desktop notifications and shutting down after a run
+//!
+//! Long extractions are often left running unattended. These helpers let the
+//! frontends tell the user when a run is done, or power the machine off once
+//! it finishes. Both go through the tools every desktop already has
+//! (PowerShell, `notify-send`, `osascript`, `shutdown`), so no extra
+//! dependencies are needed.
+
+use crate::error::{Error, Result};
+use std::process::Command;
+use std::time::Duration;
+
+/// Time between a scheduled shutdown and the machine powering off
+///
+/// Gives the user a chance to cancel it with [`cancel_shutdown`].
+pub const SHUTDOWN_GRACE: Duration = Duration::from_mins(1);
+
+/// Show a desktop notification
+pub fn notify_desktop(title: &str, message: &str) -> Result<()> {
+    run(notify_command(title, message))
+}
+
+/// Shut the machine down after `delay`
+///
+/// Outside Windows the delay is rounded up to whole minutes, and shutting
+/// down may need rights the user does not have (e.g., on macOS).
+pub fn schedule_shutdown(delay: Duration) -> Result<()> {
+    run(shutdown_command(delay))
+}
+
+/// Cancel a shutdown scheduled with [`schedule_shutdown`]
+pub fn cancel_shutdown() -> Result<()> {
+    #[cfg(windows)]
+    let command = hidden_command("shutdown", &["/a"]);
+    #[cfg(not(windows))]
+    let command = {
+        let mut command = Command::new("shutdown");
+        command.arg("-c");
+        command
+    };
+    run(command)
+}
+
+#[cfg(windows)]
+fn notify_command(title: &str, message: &str) -> Command {
+    // PowerShell's registered app ID, so the toast shows without installing a shortcut
+    let script = format!(
+        "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+         $template = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+         $text = $template.GetElementsByTagName('text'); \
+         $text.Item(0).AppendChild($template.CreateTextNode({})) > $null; \
+         $text.Item(1).AppendChild($template.CreateTextNode({})) > $null; \
+         [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('{{1AC14E77-02E7-4E5D-B744-2EB1AE5198B7}}\\WindowsPowerShell\\v1.0\\powershell.exe').Show([Windows.UI.Notifications.ToastNotification]::new($template))",
+        powershell_string(title),
+        powershell_string(message)
+    );
+    hidden_command(
+        "powershell",
+        &["-NoProfile", "-NonInteractive", "-Command", &script],
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn notify_command(title: &str, message: &str) -> Command {
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_string(message),
+        applescript_string(title)
+    );
+    let mut command = Command::new("osascript");
+    command.args(["-e", &script]);
+    command
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn notify_command(title: &str, message: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name=Unpackrr", title, message]);
+    command
+}
+
+#[cfg(windows)]
+fn shutdown_command(delay: Duration) -> Command {
+    let seconds = delay.as_secs().to_string();
+    hidden_command(
+        "shutdown",
+        &[
+            "/s",
+            "/t",
+            &seconds,
+            "/c",
+            "Unpackrr finished extracting. Run 'shutdown /a' to cancel.",
+        ],
+    )
+}
+
+#[cfg(not(windows))]
+fn shutdown_command(delay: Duration) -> Command {
+    let minutes = delay.as_secs().div_ceil(60).max(1);
+    let mut command = Command::new("shutdown");
+    command.args(["-h", &format!("+{minutes}")]);
+    command
+}
+
+#[cfg(windows)]
+fn hidden_command(program: &str, args: &[&str]) -> Command {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let mut command = Command::new(program);
+    command.args(args).creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+/// Run a command, failing with its error output if it does not succeed
+fn run(mut command: Command) -> Result<()> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command
+        .output()
+        .map_err(|e| Error::other(format!("Could not run {program}: {e}")))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::other(format!(
+            "{program} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// Quote text as a PowerShell string literal
+#[cfg_attr(not(windows), allow(dead_code))]
+fn powershell_string(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Quote text as a string literal for `osascript`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn applescript_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoting() {
+        assert_eq!(powershell_string("Mod's files"), "'Mod''s files'");
+        assert_eq!(
+            applescript_string(r#"3 "failed" in C:\Mods"#),
+            r#""3 \"failed\" in C:\\Mods""#
+        );
+    }
+}
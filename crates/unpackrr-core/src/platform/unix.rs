@@ -4,7 +4,6 @@
 //! These functions return None or appropriate defaults since BA2 files
 //! are primarily a Windows gaming format.
 
-use anyhow::Result;
 use std::path::PathBuf;
 
 /// Get the default application for .ba2 files (stub for non-Windows platforms)
@@ -12,21 +11,16 @@ use std::path::PathBuf;
 /// On Unix-like systems, BA2 files don't have system-wide file associations
 /// in the same way as Windows. This function always returns `None`.
 ///
-/// # Returns
-///
-/// Always returns `Ok(None)` on non-Windows platforms.
-///
 /// # Examples
 ///
 /// ```
-/// use unpackrr::platform::get_default_ba2_handler;
+/// use unpackrr_core::platform::get_default_ba2_handler;
 ///
-/// let handler = get_default_ba2_handler().unwrap();
-/// assert_eq!(handler, None); // Always None on Unix
+/// assert_eq!(get_default_ba2_handler(), None); // Always None on Unix
 /// ```
-pub fn get_default_ba2_handler() -> Result<Option<PathBuf>> {
+pub fn get_default_ba2_handler() -> Option<PathBuf> {
     tracing::debug!("get_default_ba2_handler() called on non-Windows platform - returning None");
-    Ok(None)
+    None
 }
 
 /// Check if a file is a valid executable (Unix implementation)
@@ -57,14 +51,34 @@ pub fn is_valid_executable(path: &std::path::Path) -> bool {
     }
 }
 
+/// Get the console code page (stub for non-Windows platforms)
+///
+/// Console output is UTF-8 on Unix-like systems, so this always returns `None`.
+pub const fn console_codepage() -> Option<u32> {
+    None
+}
+
+/// Check whether Windows Developer Mode is enabled (stub for non-Windows platforms)
+///
+/// Symbolic links need no special privileges here, so this always returns `false`.
+pub const fn developer_mode_enabled() -> bool {
+    false
+}
+
+/// Check whether long paths are supported (stub for non-Windows platforms)
+///
+/// There is no `MAX_PATH` limit here, so this always returns `true`.
+pub const fn long_paths_enabled() -> bool {
+    true
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_get_default_ba2_handler_returns_none() {
-        let result = get_default_ba2_handler().unwrap();
-        assert_eq!(result, None);
+        assert_eq!(get_default_ba2_handler(), None);
     }
 
     #[test]
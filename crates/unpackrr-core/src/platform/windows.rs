@@ -4,7 +4,7 @@
 
 use std::path::PathBuf;
 use winreg::RegKey;
-use winreg::enums::{HKEY_CLASSES_ROOT, HKEY_CURRENT_USER};
+use winreg::enums::{HKEY_CLASSES_ROOT, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
 
 /// Get the default application for .ba2 files from Windows registry
 ///
@@ -25,7 +25,7 @@ use winreg::enums::{HKEY_CLASSES_ROOT, HKEY_CURRENT_USER};
 /// # Examples
 ///
 /// ```no_run
-/// use unpackrr::platform::get_default_ba2_handler;
+/// use unpackrr_core::platform::get_default_ba2_handler;
 ///
 /// match get_default_ba2_handler() {
 ///     Some(path) => println!("Default BA2 handler: {}", path.display()),
@@ -170,6 +170,39 @@ pub fn is_valid_executable(path: &std::path::Path) -> bool {
     })
 }
 
+/// Get the OEM code page used by console programs
+///
+/// Console tools such as `BSArch` write their output in this code page.
+/// Returns `None` if the registry value is missing or unparsable.
+pub fn console_codepage() -> Option<u32> {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    let key = hklm
+        .open_subkey("SYSTEM\\CurrentControlSet\\Control\\Nls\\CodePage")
+        .ok()?;
+    let value: String = key.get_value("OEMCP").ok()?;
+    value.trim().parse().ok()
+}
+
+/// Check whether Windows Developer Mode is enabled
+///
+/// Developer Mode lets unelevated processes create symbolic links.
+pub fn developer_mode_enabled() -> bool {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    hklm.open_subkey("SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\AppModelUnlock")
+        .and_then(|key| key.get_value::<u32, _>("AllowDevelopmentWithoutDevLicense"))
+        .is_ok_and(|value| value != 0)
+}
+
+/// Check whether Windows accepts paths over `MAX_PATH` (`LongPathsEnabled`)
+///
+/// Tools that are not long-path aware still need extended-length paths.
+pub fn long_paths_enabled() -> bool {
+    let hklm = RegKey::predef(HKEY_LOCAL_MACHINE);
+    hklm.open_subkey("SYSTEM\\CurrentControlSet\\Control\\FileSystem")
+        .and_then(|key| key.get_value::<u32, _>("LongPathsEnabled"))
+        .is_ok_and(|value| value != 0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
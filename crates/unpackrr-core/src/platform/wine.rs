@@ -0,0 +1,337 @@
+//! Windows paths and file name case under Wine and Proton
+//!
+//! On Linux the game runs in a Wine prefix (Proton creates one per game in
+//! `steamapps/compatdata/<app id>/pfx`). Settings files written on Windows
+//! and paths pasted from programs running in the prefix, such as Mod
+//! Organizer, look like `C:\Modding\MO2\mods`; [`host_path`] translates them
+//! through the drive letters of the prefix.
+//!
+//! Windows also finds `Textures\Armor.dds` at `textures/armor.dds`, but Linux
+//! file systems don't, so archive-internal paths are looked up with a
+//! [`CaseFolder`] when files are written, checked or backed up.
+
+use crate::config::AppConfig;
+use crate::config::profile::GameProfile;
+use directories::BaseDirs;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Prefix Windows paths are translated through by [`host_path`]
+static CURRENT_PREFIX: LazyLock<RwLock<Option<WinePrefix>>> =
+    LazyLock::new(|| RwLock::new(WinePrefix::from_env()));
+
+/// Steam libraries below the home folder, including the Flatpak one
+const STEAM_DIRS: &[&str] = &[
+    ".steam/steam",
+    ".local/share/Steam",
+    ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+];
+
+/// A Wine or Proton prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WinePrefix {
+    root: PathBuf,
+}
+
+impl WinePrefix {
+    /// Open the prefix in `root`, the folder holding `drive_c`
+    ///
+    /// A Proton `compatdata/<app id>` folder may be given for its `pfx` folder.
+    pub fn open(root: &Path) -> Option<Self> {
+        [root.to_path_buf(), root.join("pfx")]
+            .into_iter()
+            .find(|dir| dir.join("drive_c").is_dir())
+            .map(|root| Self { root })
+    }
+
+    /// Prefix named by `WINEPREFIX` or, under Proton, `STEAM_COMPAT_DATA_PATH`
+    pub fn from_env() -> Option<Self> {
+        ["WINEPREFIX", "STEAM_COMPAT_DATA_PATH"]
+            .into_iter()
+            .filter_map(std::env::var_os)
+            .find_map(|dir| Self::open(Path::new(&dir)))
+    }
+
+    /// Proton prefix Steam created for the game with `app_id`
+    pub fn of_steam_game(app_id: u32) -> Option<Self> {
+        let home = BaseDirs::new()?.home_dir().to_path_buf();
+        STEAM_DIRS.iter().find_map(|steam| {
+            Self::open(
+                &home
+                    .join(steam)
+                    .join("steamapps/compatdata")
+                    .join(app_id.to_string()),
+            )
+        })
+    }
+
+    /// Folder holding `drive_c`
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Folder a drive letter is mapped to
+    ///
+    /// Uses the links in `dosdevices`, falling back to Wine's defaults of
+    /// `drive_c` for `C:` and `/` for `Z:`.
+    pub fn drive(&self, letter: char) -> Option<PathBuf> {
+        let letter = letter.to_ascii_lowercase();
+        let device = self.root.join("dosdevices").join(format!("{letter}:"));
+        if device.exists() {
+            return Some(device);
+        }
+        match letter {
+            'c' => Some(self.root.join("drive_c")),
+            'z' => Some(PathBuf::from("/")),
+            _ => None,
+        }
+    }
+
+    /// Translate a Windows path like `C:\Games\Fallout 4` into this system's
+    ///
+    /// The folders are matched ignoring case, like Windows does. Returns
+    /// `None` for paths without a drive letter or on unmapped drives.
+    pub fn to_host(&self, windows_path: &str) -> Option<PathBuf> {
+        let (letter, rest) = split_drive(windows_path)?;
+        let drive = self.drive(letter)?;
+        Some(CaseFolder::new().resolve(&drive, rest))
+    }
+
+    /// `%LOCALAPPDATA%` of the prefix's user (`steamuser` under Proton)
+    pub fn local_app_data(&self) -> Option<PathBuf> {
+        let users = self.root.join("drive_c").join("users");
+        let local = |user: &Path| Some(user.join("AppData").join("Local")).filter(|d| d.is_dir());
+        let named = [
+            "steamuser".to_string(),
+            std::env::var("USER").unwrap_or_default(),
+        ];
+        named
+            .iter()
+            .filter(|name| !name.is_empty())
+            .find_map(|name| local(&users.join(name)))
+            .or_else(|| {
+                let mut others: Vec<PathBuf> = fs::read_dir(&users)
+                    .ok()?
+                    .filter_map(std::result::Result::ok)
+                    .map(|entry| entry.path())
+                    .collect();
+                others.sort();
+                others.iter().find_map(|user| local(user))
+            })
+    }
+}
+
+/// Split `C:\rest` into the drive letter and the rest
+///
+/// Extended-length paths (`\\?\C:\rest`) are accepted too.
+fn split_drive(path: &str) -> Option<(char, &str)> {
+    let path = path.trim();
+    let path = path.strip_prefix(r"\\?\").unwrap_or(path);
+    let mut chars = path.chars();
+    let letter = chars.next().filter(char::is_ascii_alphabetic)?;
+    let rest = chars.as_str().strip_prefix(':')?;
+    (rest.is_empty() || rest.starts_with(['\\', '/'])).then_some((letter, rest))
+}
+
+/// Whether `value` is a Windows path with a drive letter
+pub fn is_windows_path(value: &str) -> bool {
+    split_drive(value).is_some()
+}
+
+/// Path of a folder or file setting on this system
+///
+/// Outside Windows, paths with a drive letter are translated through the
+/// current prefix (see [`set_wine_prefix`]); without one only `Z:`, which
+/// Wine maps to `/`, is known. Other values are used as they are.
+pub fn host_path(value: &str) -> PathBuf {
+    let value = value.trim();
+    if cfg!(windows) {
+        return PathBuf::from(value);
+    }
+    let Some((letter, rest)) = split_drive(value) else {
+        return PathBuf::from(value);
+    };
+    let translated = CURRENT_PREFIX.read().as_ref().map_or_else(
+        || {
+            letter
+                .eq_ignore_ascii_case(&'z')
+                .then(|| CaseFolder::new().resolve(Path::new("/"), rest))
+        },
+        |prefix| prefix.to_host(value),
+    );
+    translated.unwrap_or_else(|| PathBuf::from(value))
+}
+
+/// `%LOCALAPPDATA%` inside the current prefix, if there is one
+pub fn local_app_data() -> Option<PathBuf> {
+    CURRENT_PREFIX
+        .read()
+        .as_ref()
+        .and_then(WinePrefix::local_app_data)
+}
+
+/// Translate Windows paths through the prefix in `root` from now on
+///
+/// An empty `root` uses the prefix of the environment or, failing that, the
+/// Proton prefix of the game. Does nothing on Windows.
+pub fn set_wine_prefix(root: &str, profile: &GameProfile) {
+    if cfg!(windows) {
+        return;
+    }
+    let prefix = if root.trim().is_empty() {
+        WinePrefix::from_env().or_else(|| WinePrefix::of_steam_game(profile.steam_app_id))
+    } else {
+        let prefix = WinePrefix::open(Path::new(root.trim()));
+        if prefix.is_none() {
+            tracing::warn!("No Wine prefix (drive_c folder) in {}", root.trim());
+        }
+        prefix
+    };
+    if let Some(prefix) = &prefix {
+        tracing::info!(
+            "Translating Windows paths through the Wine prefix {}",
+            prefix.root.display()
+        );
+    }
+    *CURRENT_PREFIX.write() = prefix;
+}
+
+/// Translate Windows paths through the prefix of the settings from now on
+pub fn set_wine_prefix_from_config(config: &AppConfig) {
+    set_wine_prefix(&config.advanced.wine_prefix, config.profile());
+}
+
+/// Case-insensitive lookups of archive-internal paths
+///
+/// Each folder is listed once. Names that don't exist yet are remembered as
+/// first asked for, so files written later with another case
+/// (`Textures\a.dds`, then `textures\b.dds`) end up in the same folder. On
+/// Windows, whose file systems ignore case already, paths are only joined.
+#[derive(Debug, Default)]
+pub struct CaseFolder {
+    /// Entries of the folders looked into, by their lowercase name
+    listings: HashMap<PathBuf, HashMap<String, OsString>>,
+}
+
+impl CaseFolder {
+    /// Start with no folders listed
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Path of the entry `name` of `dir`
+    pub fn child(&mut self, dir: &Path, name: &OsStr) -> PathBuf {
+        let Some(key) = name
+            .to_str()
+            .filter(|_| !cfg!(windows))
+            .map(str::to_lowercase)
+        else {
+            return dir.join(name);
+        };
+        let listing = self
+            .listings
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| list_folder(dir));
+        dir.join(listing.entry(key).or_insert_with(|| name.to_os_string()))
+    }
+
+    /// Path of the archive-internal `name` ('\\' or '/' separated) below `base`
+    pub fn resolve(&mut self, base: &Path, name: &str) -> PathBuf {
+        name.split(['\\', '/'])
+            .filter(|part| !part.is_empty())
+            .fold(base.to_path_buf(), |path, part| {
+                self.child(&path, OsStr::new(part))
+            })
+    }
+}
+
+/// Entries of `dir` by their lowercase name
+///
+/// Of names that differ only in case, the first in byte order is used.
+fn list_folder(dir: &Path) -> HashMap<String, OsString> {
+    let mut names: Vec<OsString> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(std::result::Result::ok)
+                .map(|entry| entry.file_name())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    let mut listing = HashMap::with_capacity(names.len());
+    for name in names {
+        if let Some(key) = name.to_str().map(str::to_lowercase) {
+            listing.entry(key).or_insert(name);
+        }
+    }
+    listing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_split_drive() {
+        assert_eq!(
+            split_drive(r"C:\Games\Fallout 4"),
+            Some(('C', r"\Games\Fallout 4"))
+        );
+        assert_eq!(split_drive("d:/Mods"), Some(('d', "/Mods")));
+        assert_eq!(split_drive(r"\\?\E:\Mods"), Some(('E', r"\Mods")));
+        assert_eq!(split_drive("Z:"), Some(('Z', "")));
+        assert!(!is_windows_path("/home/user/Mods"));
+        assert!(!is_windows_path("C:relative"));
+        assert!(!is_windows_path(r"\\server\share"));
+    }
+
+    #[test]
+    fn test_prefix_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let compatdata = temp_dir.path().join("377160");
+        let mods = compatdata.join("pfx/drive_c/Modding/MO2/mods");
+        fs::create_dir_all(&mods).unwrap();
+        fs::create_dir_all(compatdata.join("pfx/drive_c/users/steamuser/AppData/Local")).unwrap();
+
+        let prefix = WinePrefix::open(&compatdata).unwrap();
+        assert_eq!(prefix.root(), compatdata.join("pfx"));
+        assert_eq!(prefix.to_host(r"C:\Modding\MO2\mods").unwrap(), mods);
+        assert_eq!(prefix.drive('z').unwrap(), Path::new("/"));
+        assert_eq!(prefix.to_host(r"Q:\Mods"), None);
+        assert_eq!(
+            prefix.local_app_data().unwrap(),
+            compatdata.join("pfx/drive_c/users/steamuser/AppData/Local")
+        );
+        assert!(WinePrefix::open(temp_dir.path()).is_none());
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_case_folding() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("Meshes/Armor")).unwrap();
+        fs::write(root.join("Meshes/Armor/Helmet.nif"), b"").unwrap();
+
+        let mut case = CaseFolder::new();
+        assert_eq!(
+            case.resolve(root, r"meshes\armor\HELMET.NIF"),
+            root.join("Meshes/Armor/Helmet.nif")
+        );
+        // New names keep the case they were first asked for
+        assert_eq!(
+            case.resolve(root, r"Textures\a.dds"),
+            root.join("Textures/a.dds")
+        );
+        assert_eq!(
+            case.resolve(root, "textures/B.dds"),
+            root.join("Textures/B.dds")
+        );
+        assert_eq!(case.child(root, OsStr::new("MESHES")), root.join("Meshes"));
+    }
+}
@@ -0,0 +1,309 @@
+//! Generated BA2 archives
+//!
+//! Builds small but complete archives: a header, real file records (with
+//! name hashes, offsets, sizes and sentinels), uncompressed data and the
+//! name table. The contents of every file are [`entry_data`] of its name, so
+//! tests can check what was extracted.
+
+use crate::ba2::BA2Header;
+use crate::error::Result;
+use crate::models::FileEntry;
+use std::fs;
+use std::path::Path;
+
+/// Format version written by Fallout 4
+pub const FALLOUT4_VERSION: u32 = 1;
+
+/// DXGI format of generated textures (`R8G8B8A8_UNORM`)
+pub const TEXTURE_FORMAT: u8 = 28;
+
+/// Marker ending every file record and texture chunk
+const SENTINEL: u32 = 0xBAAD_F00D;
+
+/// Size of a GNRL file record
+const GNRL_RECORD_SIZE: usize = 36;
+
+/// Size of a DX10 texture record without its chunks
+const DX10_RECORD_SIZE: usize = 24;
+
+/// Size of a DX10 texture chunk
+const DX10_CHUNK_SIZE: usize = 24;
+
+/// A file stored in a general archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixtureFile {
+    /// Path inside the archive, with '\\' separators
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+impl FixtureFile {
+    /// A file whose contents are [`entry_data`] of its name
+    pub fn named(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            data: entry_data(name),
+        }
+    }
+}
+
+/// A texture stored in a DX10 archive, as a single chunk
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixtureTexture {
+    /// Path inside the archive, with '\\' separators
+    pub name: String,
+    pub width: u16,
+    pub height: u16,
+    /// Number of mip levels
+    pub mips: u8,
+    /// Pixel data of all mip levels without a DDS header
+    pub data: Vec<u8>,
+}
+
+impl FixtureTexture {
+    /// A 4x4 texture whose pixels are [`entry_data`] of its name, repeated
+    pub fn named(name: &str) -> Self {
+        let data = entry_data(name)
+            .into_iter()
+            .cycle()
+            .take(4 * 4 * 4)
+            .collect();
+        Self {
+            name: name.to_string(),
+            width: 4,
+            height: 4,
+            mips: 1,
+            data,
+        }
+    }
+
+    /// A texture with a full chain of `mips` levels, each filled with its level number
+    pub fn with_mips(name: &str, width: u16, height: u16, mips: u8) -> Self {
+        let mut data = Vec::new();
+        for level in 0..mips {
+            let pixels =
+                (usize::from(width) >> level).max(1) * (usize::from(height) >> level).max(1);
+            data.resize(data.len() + pixels * 4, level);
+        }
+        Self {
+            name: name.to_string(),
+            width,
+            height,
+            mips,
+            data,
+        }
+    }
+}
+
+/// Contents of a fixture file, derived from its name
+pub fn entry_data(name: &str) -> Vec<u8> {
+    format!("fixture:{name}").into_bytes()
+}
+
+/// Build a general (GNRL) archive
+pub fn gnrl_archive(files: &[FixtureFile]) -> Vec<u8> {
+    let data_start = BA2Header::HEADER_SIZE + files.len() * GNRL_RECORD_SIZE;
+    let mut records = Vec::new();
+    let mut data = Vec::new();
+    for file in files {
+        let (name_hash, extension, dir_hash) = name_hashes(&file.name);
+        records.extend_from_slice(&name_hash.to_le_bytes());
+        records.extend_from_slice(&extension);
+        records.extend_from_slice(&dir_hash.to_le_bytes());
+        records.extend_from_slice(&0u32.to_le_bytes()); // Flags
+        records.extend_from_slice(&le64(data_start + data.len()));
+        records.extend_from_slice(&0u32.to_le_bytes()); // Packed size (stored uncompressed)
+        records.extend_from_slice(&le32(file.data.len()));
+        records.extend_from_slice(&SENTINEL.to_le_bytes());
+        data.extend_from_slice(&file.data);
+    }
+    let names: Vec<&str> = files.iter().map(|f| f.name.as_str()).collect();
+    assemble("GNRL", &names, &records, &data)
+}
+
+/// Build a texture (DX10) archive
+pub fn dx10_archive(textures: &[FixtureTexture]) -> Vec<u8> {
+    let data_start = BA2Header::HEADER_SIZE + textures.len() * (DX10_RECORD_SIZE + DX10_CHUNK_SIZE);
+    let mut records = Vec::new();
+    let mut data = Vec::new();
+    for texture in textures {
+        let (name_hash, extension, dir_hash) = name_hashes(&texture.name);
+        records.extend_from_slice(&name_hash.to_le_bytes());
+        records.extend_from_slice(&extension);
+        records.extend_from_slice(&dir_hash.to_le_bytes());
+        records.push(0); // Unknown
+        records.push(1); // Chunk count
+        records.extend_from_slice(&le16(DX10_CHUNK_SIZE));
+        records.extend_from_slice(&texture.height.to_le_bytes());
+        records.extend_from_slice(&texture.width.to_le_bytes());
+        records.push(texture.mips);
+        records.push(TEXTURE_FORMAT);
+        records.push(0); // Not a cubemap
+        records.push(8); // Tile mode
+        records.extend_from_slice(&le64(data_start + data.len()));
+        records.extend_from_slice(&0u32.to_le_bytes()); // Packed size (stored uncompressed)
+        records.extend_from_slice(&le32(texture.data.len()));
+        records.extend_from_slice(&0u16.to_le_bytes()); // First mip
+        records.extend_from_slice(&u16::from(texture.mips.saturating_sub(1)).to_le_bytes()); // Last mip
+        records.extend_from_slice(&SENTINEL.to_le_bytes());
+        data.extend_from_slice(&texture.data);
+    }
+    let names: Vec<&str> = textures.iter().map(|t| t.name.as_str()).collect();
+    assemble("DX10", &names, &records, &data)
+}
+
+/// Write a general archive holding [`FixtureFile::named`] files
+pub fn write_gnrl(path: &Path, names: &[&str]) -> Result<()> {
+    let files: Vec<FixtureFile> = names.iter().map(|name| FixtureFile::named(name)).collect();
+    write(path, &gnrl_archive(&files))
+}
+
+/// Write a texture archive holding [`FixtureTexture::named`] textures
+pub fn write_dx10(path: &Path, names: &[&str]) -> Result<()> {
+    let textures: Vec<FixtureTexture> = names
+        .iter()
+        .map(|name| FixtureTexture::named(name))
+        .collect();
+    write(path, &dx10_archive(&textures))
+}
+
+/// Describe a written archive like a scan would
+pub fn file_entry(path: &Path) -> Result<FileEntry> {
+    let header = BA2Header::parse(path)?;
+    let mod_name = path
+        .parent()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut entry = FileEntry::new(
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        fs::metadata(path)?.len(),
+        header.file_count,
+        mod_name,
+        path.to_path_buf(),
+    );
+    entry.archive_type = header.archive_type;
+    entry.format_version = header.version;
+    Ok(entry)
+}
+
+fn write(path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Put the header, records, data and name table together
+fn assemble(archive_type: &str, names: &[&str], records: &[u8], data: &[u8]) -> Vec<u8> {
+    let names_offset = BA2Header::HEADER_SIZE + records.len() + data.len();
+    let mut archive = Vec::with_capacity(names_offset);
+    archive.extend_from_slice(BA2Header::MAGIC);
+    archive.extend_from_slice(&FALLOUT4_VERSION.to_le_bytes());
+    archive.extend_from_slice(archive_type.as_bytes());
+    archive.extend_from_slice(&le32(names.len()));
+    archive.extend_from_slice(&le64(names_offset));
+    archive.extend_from_slice(records);
+    archive.extend_from_slice(data);
+    for name in names {
+        archive.extend_from_slice(&le16(name.len()));
+        archive.extend_from_slice(name.as_bytes());
+    }
+    archive
+}
+
+/// Hash of the file stem, extension and hash of the folder, as stored in records
+fn name_hashes(name: &str) -> (u32, [u8; 4], u32) {
+    let lower = name.to_lowercase().replace('/', "\\");
+    let (dir, file) = lower.rsplit_once('\\').unwrap_or(("", lower.as_str()));
+    let (stem, extension) = file.rsplit_once('.').unwrap_or((file, ""));
+    let mut extension_bytes = [0u8; 4];
+    for (slot, byte) in extension_bytes.iter_mut().zip(extension.bytes()) {
+        *slot = byte;
+    }
+    (
+        crc32(stem.as_bytes()),
+        extension_bytes,
+        crc32(dir.as_bytes()),
+    )
+}
+
+/// CRC-32 (IEEE), the hash BA2 archives use for names
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn le16(value: usize) -> [u8; 2] {
+    u16::try_from(value)
+        .expect("fixture value fits in u16")
+        .to_le_bytes()
+}
+
+fn le32(value: usize) -> [u8; 4] {
+    u32::try_from(value)
+        .expect("fixture value fits in u32")
+        .to_le_bytes()
+}
+
+const fn le64(value: usize) -> [u8; 8] {
+    (value as u64).to_le_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ba2::read_file_names;
+
+    #[test]
+    fn test_fixture_archives_parse() {
+        let dir = tempfile::tempdir().unwrap();
+        let general = dir.path().join("Mod").join("Mod - Main.ba2");
+        let textures = dir.path().join("Mod").join("Mod - Textures.ba2");
+        write_gnrl(&general, &["meshes\\a.nif", "scripts\\b.pex"]).unwrap();
+        write_dx10(&textures, &["textures\\c.dds"]).unwrap();
+
+        let entry = file_entry(&general).unwrap();
+        assert_eq!(entry.num_files, 2);
+        assert_eq!(entry.dir_name, "Mod");
+        assert!(!entry.is_texture());
+        assert_eq!(
+            read_file_names(&general).unwrap(),
+            ["meshes\\a.nif", "scripts\\b.pex"]
+        );
+
+        let entry = file_entry(&textures).unwrap();
+        assert!(entry.is_texture());
+        assert_eq!(read_file_names(&textures).unwrap(), ["textures\\c.dds"]);
+    }
+
+    #[test]
+    fn test_gnrl_records() {
+        let archive = gnrl_archive(&[FixtureFile::named("meshes\\a.nif")]);
+        let record = &archive[BA2Header::HEADER_SIZE..BA2Header::HEADER_SIZE + GNRL_RECORD_SIZE];
+        assert_eq!(&record[4..8], b"nif\0");
+        assert_eq!(record[32..36], SENTINEL.to_le_bytes());
+
+        // The offset points at the file's data
+        let offset =
+            usize::try_from(u64::from_le_bytes(record[16..24].try_into().unwrap())).unwrap();
+        let data = entry_data("meshes\\a.nif");
+        assert_eq!(&archive[offset..offset + data.len()], data.as_slice());
+
+        // Known CRC-32 check value
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+}
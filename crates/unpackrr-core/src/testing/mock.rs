@@ -0,0 +1,207 @@
+//! Fake extractor with failure injection
+
+use crate::ba2::extractor::{BsarchOutcome, BsarchVersion};
+use crate::ba2::read_file_names;
+use crate::error::{BA2Error, Error, Result};
+use crate::operations::ExtractorBackend;
+use crate::testing::fixtures::entry_data;
+use futures::future::BoxFuture;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How the fake extractor handles an archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Behavior {
+    /// Write every file of the archive
+    #[default]
+    Succeed,
+    /// Succeed after waiting
+    Delay(Duration),
+    /// Fail with a transient error on the first `times` attempts, then succeed
+    FailTransient { times: usize },
+    /// Fail with a permanent error
+    Fail,
+    /// Fail as if the tool timed out (transient)
+    TimedOut,
+    /// Write the first `files` files, then fail with a permanent error
+    PartialWrite { files: usize },
+    /// Never finish; the run has to be cancelled
+    Hang,
+}
+
+#[derive(Debug, Default)]
+struct Calls {
+    /// Attempts per archive
+    attempts: HashMap<PathBuf, usize>,
+    /// Archives in the order their attempts started
+    order: Vec<PathBuf>,
+    running: usize,
+    max_running: usize,
+}
+
+/// An [`ExtractorBackend`] that writes [`entry_data`] of every name in the
+/// archive's name table instead of running a tool
+///
+/// Archives behave as [`Behavior::Succeed`] unless configured otherwise by
+/// file name. Every attempt is recorded.
+#[derive(Debug, Default)]
+pub struct MockExtractor {
+    behaviors: HashMap<String, Behavior>,
+    default: Behavior,
+    version: Option<BsarchVersion>,
+    calls: Mutex<Calls>,
+}
+
+impl MockExtractor {
+    /// Create an extractor that unpacks every archive
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handle the archive named `file_name` as `behavior`
+    #[must_use]
+    pub fn with_behavior(mut self, file_name: &str, behavior: Behavior) -> Self {
+        self.behaviors.insert(file_name.to_string(), behavior);
+        self
+    }
+
+    /// Handle archives without a configured behavior as `behavior`
+    #[must_use]
+    pub const fn with_default(mut self, behavior: Behavior) -> Self {
+        self.default = behavior;
+        self
+    }
+
+    /// Report `version` as the tool version
+    #[must_use]
+    pub const fn with_version(mut self, version: BsarchVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Number of attempts made on `archive`
+    pub fn attempts(&self, archive: &Path) -> usize {
+        self.calls
+            .lock()
+            .attempts
+            .get(archive)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Archives in the order their attempts started
+    pub fn calls(&self) -> Vec<PathBuf> {
+        self.calls.lock().order.clone()
+    }
+
+    /// Most attempts that ran at the same time
+    pub fn max_concurrent(&self) -> usize {
+        self.calls.lock().max_running
+    }
+
+    fn behavior(&self, archive: &Path) -> Behavior {
+        archive
+            .file_name()
+            .and_then(|name| self.behaviors.get(name.to_string_lossy().as_ref()))
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Record the start of an attempt, returning its number (1-indexed)
+    fn start(&self, archive: &Path) -> usize {
+        let mut calls = self.calls.lock();
+        let attempts = calls.attempts.entry(archive.to_path_buf()).or_default();
+        *attempts += 1;
+        let attempt = *attempts;
+        calls.order.push(archive.to_path_buf());
+        calls.running += 1;
+        calls.max_running = calls.max_running.max(calls.running);
+        attempt
+    }
+}
+
+/// Marks an attempt as finished when dropped, including when it is aborted
+struct Running<'a>(&'a Mutex<Calls>);
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        self.0.lock().running -= 1;
+    }
+}
+
+impl ExtractorBackend for MockExtractor {
+    fn extract<'a>(
+        &'a self,
+        archive: &'a Path,
+        output_dir: Option<&'a Path>,
+        on_progress: &'a mut (dyn FnMut(u8) + Send),
+    ) -> BoxFuture<'a, Result<BsarchOutcome>> {
+        Box::pin(async move {
+            let attempt = self.start(archive);
+            let _running = Running(&self.calls);
+            let failed = |reason: &str| -> Error {
+                BA2Error::ExtractionFailed {
+                    path: archive.to_path_buf(),
+                    reason: reason.to_string(),
+                }
+                .into()
+            };
+
+            let mut limit = None;
+            match self.behavior(archive) {
+                Behavior::FailTransient { times } if attempt <= times => {
+                    return Err(BA2Error::BSArchExecFailed(format!(
+                        "Injected failure {attempt} of {times}"
+                    ))
+                    .into());
+                }
+                Behavior::Succeed | Behavior::FailTransient { .. } => {}
+                Behavior::Delay(delay) => tokio::time::sleep(delay).await,
+                Behavior::Fail => return Err(failed("Injected failure")),
+                Behavior::TimedOut => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::TimedOut,
+                        "Injected timeout",
+                    )
+                    .into());
+                }
+                Behavior::PartialWrite { files } => limit = Some(files),
+                Behavior::Hang => std::future::pending::<()>().await,
+            }
+
+            let Some(output_dir) = output_dir.or_else(|| archive.parent()) else {
+                return Err(failed("BA2 file path has no parent directory"));
+            };
+            let names = read_file_names(archive)?;
+            let total = names.len();
+            for (index, name) in names.iter().enumerate() {
+                if limit.is_some_and(|limit| index >= limit) {
+                    return Err(failed("Injected partial write"));
+                }
+                let path = name
+                    .split(['\\', '/'])
+                    .filter(|part| !part.is_empty())
+                    .fold(output_dir.to_path_buf(), |path, part| path.join(part));
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&path, entry_data(name))?;
+                on_progress(u8::try_from((index + 1) * 100 / total).unwrap_or(100));
+            }
+
+            let files = u32::try_from(total).unwrap_or(u32::MAX);
+            Ok(BsarchOutcome {
+                file_count: Some(files),
+                files_done: files,
+                ..BsarchOutcome::default()
+            })
+        })
+    }
+
+    fn version(&self) -> Option<BsarchVersion> {
+        self.version
+    }
+}
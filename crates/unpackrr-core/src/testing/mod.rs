@@ -0,0 +1,13 @@
+//! Test support
+//!
+//! Only built for this crate's tests or with the `test-support` feature.
+//! [`MockExtractor`] stands in for `BSArch` behind the
+//! [`ExtractorBackend`](crate::operations::ExtractorBackend) trait and can
+//! inject failures per archive, and [`fixtures`] writes small archives with
+//! known contents, so extraction runs can be tested without the tool or game
+//! files.
+
+pub mod fixtures;
+mod mock;
+
+pub use mock::{Behavior, MockExtractor};
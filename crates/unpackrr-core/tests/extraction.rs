@@ -0,0 +1,445 @@
+//! Integration tests for extraction runs
+//!
+//! Runs `extract_all_with` against generated archives and the fake extractor
+//! from `unpackrr_core::testing`, so retries, cancellation, pausing and
+//! staging are tested without `BSArch`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use unpackrr_core::ba2::ReadMode;
+use unpackrr_core::config::abort::AbortThreshold;
+use unpackrr_core::config::{AppConfig, ScheduleOrder, TexturePolicy};
+use unpackrr_core::models::FileEntry;
+use unpackrr_core::operations::{
+    AppEvent, BackupEvent, CancellationToken, ConvertTextures, EventBus, ExtractionProgress,
+    ExtractionResult, NativeBackend, WriterConfig, extract_all_with, manifest_path,
+};
+use unpackrr_core::testing::fixtures::{entry_data, file_entry, write_dx10, write_gnrl};
+use unpackrr_core::testing::{Behavior, MockExtractor};
+
+const FILES: &[&str] = &["meshes\\armor\\helmet.nif", "scripts\\quest.pex"];
+
+fn config() -> AppConfig {
+    let mut config = AppConfig::default();
+    config.extraction.texture_policy = TexturePolicy::Extract;
+    // Keep the tests from writing into the user's backup folder
+    config.extraction.auto_backup = false;
+    config
+}
+
+/// Write a general archive into its own mod folder
+fn archive(root: &Path, mod_name: &str) -> (PathBuf, FileEntry) {
+    let path = root.join(mod_name).join(format!("{mod_name} - Main.ba2"));
+    write_gnrl(&path, FILES).unwrap();
+    let entry = file_entry(&path).unwrap();
+    (path, entry)
+}
+
+async fn run(
+    files: Vec<FileEntry>,
+    config: AppConfig,
+    extractor: &Arc<MockExtractor>,
+    events: Option<EventBus>,
+    cancel: Option<CancellationToken>,
+) -> ExtractionResult {
+    extract_all_with(files, config, extractor.clone(), events, cancel)
+        .await
+        .unwrap()
+}
+
+fn extracted(mod_dir: &Path, name: &str) -> PathBuf {
+    name.split('\\')
+        .fold(mod_dir.to_path_buf(), |path, part| path.join(part))
+}
+
+#[tokio::test]
+async fn test_extracts_files_and_writes_manifests() {
+    let dir = tempfile::tempdir().unwrap();
+    let (general, general_entry) = archive(dir.path(), "Armor");
+    let textures = dir.path().join("Armor").join("Armor - Textures.ba2");
+    write_dx10(&textures, &["textures\\armor\\helmet_d.dds"]).unwrap();
+
+    let mut config = config();
+    config.extraction.write_manifest = true;
+    let extractor = Arc::new(MockExtractor::new());
+    let result = run(
+        vec![general_entry, file_entry(&textures).unwrap()],
+        config,
+        &extractor,
+        None,
+        None,
+    )
+    .await;
+
+    assert_eq!(result.successful, 2);
+    assert_eq!(result.failed, 0);
+    let mod_dir = general.parent().unwrap();
+    for name in FILES.iter().chain(&["textures\\armor\\helmet_d.dds"]) {
+        let path = extracted(mod_dir, name);
+        assert_eq!(std::fs::read(&path).unwrap(), entry_data(name), "{name}");
+    }
+    assert!(manifest_path(&general, mod_dir).exists());
+    assert!(manifest_path(&textures, mod_dir).exists());
+}
+
+#[tokio::test]
+async fn test_transient_failures_are_retried() {
+    let dir = tempfile::tempdir().unwrap();
+    let (path, entry) = archive(dir.path(), "Flaky");
+    let extractor = Arc::new(
+        MockExtractor::new()
+            .with_behavior("Flaky - Main.ba2", Behavior::FailTransient { times: 2 }),
+    );
+    let events = EventBus::new();
+    let mut rx = events.subscribe();
+
+    let result = run(vec![entry], config(), &extractor, Some(events), None).await;
+
+    assert_eq!(result.successful, 1);
+    assert_eq!(extractor.attempts(&path), 3);
+    let mut retries = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::Extract(ExtractionProgress::Retrying { attempt, .. }) = event {
+            retries.push(attempt);
+        }
+    }
+    assert_eq!(retries, [1, 2]);
+}
+
+#[tokio::test]
+async fn test_converted_textures_use_the_builtin_reader() {
+    let dir = tempfile::tempdir().unwrap();
+    let (general, general_entry) = archive(dir.path(), "Armor");
+    let textures = dir.path().join("Armor").join("Armor - Textures.ba2");
+    write_dx10(&textures, &["textures\\armor\\helmet_d.dds"]).unwrap();
+
+    let mut config = config();
+    config.extraction.texture_policy = TexturePolicy::Convert;
+    let extractor = Arc::new(MockExtractor::new());
+    let native = NativeBackend::new(ReadMode::Auto, WriterConfig::default());
+    let backend = Arc::new(ConvertTextures::new(native, extractor.clone()));
+    let result = extract_all_with(
+        vec![general_entry, file_entry(&textures).unwrap()],
+        config,
+        backend,
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.successful, 2);
+    assert_eq!(extractor.calls(), std::slice::from_ref(&general));
+    let mod_dir = general.parent().unwrap();
+    let texture = extracted(mod_dir, "textures\\armor\\helmet_d.dds");
+    assert!(std::fs::read(texture).unwrap().starts_with(b"DDS "));
+}
+
+#[tokio::test]
+async fn test_permanent_failures_are_not_retried() {
+    let dir = tempfile::tempdir().unwrap();
+    let (broken, broken_entry) = archive(dir.path(), "Broken");
+    let (_, working_entry) = archive(dir.path(), "Working");
+    let extractor =
+        Arc::new(MockExtractor::new().with_behavior("Broken - Main.ba2", Behavior::Fail));
+
+    let result = run(
+        vec![broken_entry, working_entry],
+        config(),
+        &extractor,
+        None,
+        None,
+    )
+    .await;
+
+    assert_eq!(result.successful, 1);
+    assert_eq!(result.failed, 1);
+    assert_eq!(extractor.attempts(&broken), 1);
+    let failure = result.file_results.iter().find(|r| !r.success).unwrap();
+    assert_eq!(failure.file_path, broken);
+    assert!(
+        failure
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("Injected failure")
+    );
+}
+
+#[tokio::test]
+async fn test_cancel_during_retry_wait() {
+    let dir = tempfile::tempdir().unwrap();
+    let (path, entry) = archive(dir.path(), "Timeout");
+    let extractor = Arc::new(MockExtractor::new().with_default(Behavior::TimedOut));
+    let events = EventBus::new();
+    let mut rx = events.subscribe();
+    let cancel = CancellationToken::new();
+
+    let task = tokio::spawn({
+        let extractor = extractor.clone();
+        let cancel = cancel.clone();
+        async move {
+            run(
+                vec![entry],
+                config(),
+                &extractor,
+                Some(events),
+                Some(cancel),
+            )
+            .await
+        }
+    });
+    // Cancel once the first retry is scheduled
+    while let Some(event) = rx.recv().await {
+        if matches!(
+            event,
+            AppEvent::Extract(ExtractionProgress::Retrying { .. })
+        ) {
+            cancel.cancel();
+            break;
+        }
+    }
+    while rx.recv().await.is_some() {}
+
+    let result = tokio::time::timeout(Duration::from_secs(5), task)
+        .await
+        .expect("cancelled run finishes")
+        .unwrap();
+    assert_eq!(result.failed, 1);
+    assert_eq!(extractor.attempts(&path), 1);
+}
+
+#[tokio::test]
+async fn test_cancel_hung_extraction() {
+    let dir = tempfile::tempdir().unwrap();
+    let (_, entry) = archive(dir.path(), "Hung");
+    let extractor = Arc::new(MockExtractor::new().with_default(Behavior::Hang));
+    let cancel = CancellationToken::new();
+
+    let task = tokio::spawn({
+        let extractor = extractor.clone();
+        let cancel = cancel.clone();
+        async move { run(vec![entry], config(), &extractor, None, Some(cancel)).await }
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    cancel.cancel();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), task)
+        .await
+        .expect("cancelled run finishes")
+        .unwrap();
+    assert_eq!(result.failed, 1);
+    assert_eq!(extractor.calls().len(), 1);
+}
+
+#[tokio::test]
+async fn test_subscriber_that_stops_reading_pauses_the_run() {
+    let dir = tempfile::tempdir().unwrap();
+    // Started and Completed events of every archive overflow the subscriber queue
+    let files: Vec<FileEntry> = (0..60)
+        .map(|i| archive(dir.path(), &format!("Mod{i:02}")).1)
+        .collect();
+    let extractor = Arc::new(MockExtractor::new());
+    let events = EventBus::new();
+    let mut rx = events.subscribe();
+
+    let mut task = tokio::spawn({
+        let extractor = extractor.clone();
+        async move { run(files, config(), &extractor, Some(events), None).await }
+    });
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), &mut task)
+            .await
+            .is_err(),
+        "run waits for the subscriber"
+    );
+    let paused_at = extractor.calls().len();
+    assert!(paused_at < 60);
+
+    // Reading again resumes the run
+    let mut finished = false;
+    while let Some(event) = rx.recv().await {
+        finished |= matches!(
+            event,
+            AppEvent::Extract(ExtractionProgress::Finished { .. })
+        );
+    }
+    let result = task.await.unwrap();
+    assert!(finished);
+    assert_eq!(result.successful, 60);
+}
+
+#[tokio::test]
+async fn test_failed_staged_extraction_leaves_mod_folder_untouched() {
+    let dir = tempfile::tempdir().unwrap();
+    let (path, entry) = archive(dir.path(), "Partial");
+    let extractor =
+        Arc::new(MockExtractor::new().with_default(Behavior::PartialWrite { files: 1 }));
+    let mut config = config();
+    config.extraction.use_temp = true;
+
+    let result = run(vec![entry], config, &extractor, None, None).await;
+
+    assert_eq!(result.failed, 1);
+    let contents: Vec<PathBuf> = std::fs::read_dir(path.parent().unwrap())
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(contents, [path]);
+}
+
+#[tokio::test]
+async fn test_abandoned_staging_folders_are_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let (path, entry) = archive(dir.path(), "Crashed");
+    let mod_dir = path.parent().unwrap();
+    let abandoned = mod_dir.join(".unpackrr-staging-Old - Main.ba2");
+    std::fs::create_dir_all(abandoned.join("meshes")).unwrap();
+    std::fs::write(abandoned.join("meshes").join("half.nif"), b"half").unwrap();
+    let extractor = Arc::new(MockExtractor::new());
+
+    let result = run(vec![entry], config(), &extractor, None, None).await;
+
+    assert_eq!(result.successful, 1);
+    assert!(!abandoned.exists());
+    for name in FILES {
+        assert!(extracted(mod_dir, name).is_file());
+    }
+}
+
+#[tokio::test]
+async fn test_unknown_versions_are_skipped_unless_allowed() {
+    let dir = tempfile::tempdir().unwrap();
+    let (path, _) = archive(dir.path(), "Future");
+    let mut data = std::fs::read(&path).unwrap();
+    data[4..8].copy_from_slice(&9u32.to_le_bytes());
+    std::fs::write(&path, data).unwrap();
+    let entry = file_entry(&path).unwrap();
+    assert!(entry.has_unknown_version(config().profile()));
+
+    let extractor = Arc::new(MockExtractor::new());
+    let events = EventBus::new();
+    let mut rx = events.subscribe();
+    let result = run(
+        vec![entry.clone()],
+        config(),
+        &extractor,
+        Some(events),
+        None,
+    )
+    .await;
+    assert_eq!(result.skipped, 1);
+    assert!(extractor.calls().is_empty());
+    let mut reasons = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::Extract(ExtractionProgress::Skipped { reason, .. }) = event {
+            reasons.push(reason);
+        }
+    }
+    assert_eq!(reasons.len(), 1);
+    assert!(
+        reasons[0].starts_with("Unsupported BA2 version 9"),
+        "{reasons:?}"
+    );
+
+    let mut config = config();
+    config.extraction.allow_unknown_versions = true;
+    let result = run(vec![entry], config, &extractor, None, None).await;
+    assert_eq!(result.successful, 1);
+    assert_eq!(extractor.attempts(&path), 1);
+}
+
+#[tokio::test]
+async fn test_batch_aborts_after_consecutive_failures() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut extractor = MockExtractor::new();
+    let mut entries = Vec::new();
+    for name in ["A", "B", "C", "D", "E"] {
+        entries.push(archive(dir.path(), name).1);
+        extractor = extractor.with_behavior(&format!("{name} - Main.ba2"), Behavior::Fail);
+    }
+    let extractor = Arc::new(extractor);
+
+    let mut config = config();
+    config.extraction.abort_after_failures = AbortThreshold::Count(2);
+    config.extraction.schedule = ScheduleOrder::AsListed;
+    config.advanced.performance.max_jobs = 1;
+    let result = run(entries, config, &extractor, None, None).await;
+
+    assert_eq!(result.failed, 3);
+    assert_eq!(result.skipped, 2);
+    assert_eq!(extractor.calls().len(), 3);
+    let abort = result.aborted.unwrap();
+    assert_eq!(abort.consecutive_failures, 3);
+    assert_eq!(abort.not_started, 2);
+    assert!(!abort.cause.is_empty());
+}
+
+#[tokio::test]
+async fn test_data_folder_extraction_backs_up_loose_files() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("Fallout4.exe"), b"").unwrap();
+    let (path, entry) = archive(dir.path(), "Data");
+    let data = path.parent().unwrap();
+    let loose = extracted(data, FILES[0]);
+    std::fs::create_dir_all(loose.parent().unwrap()).unwrap();
+    std::fs::write(&loose, b"loose").unwrap();
+
+    // Archives of the Data folder are left alone unless Data folder mode is on
+    let extractor = Arc::new(MockExtractor::new());
+    let result = run(vec![entry.clone()], config(), &extractor, None, None).await;
+    assert_eq!(result.skipped, 1);
+    assert!(extractor.calls().is_empty());
+
+    let backups = dir.path().join("Backups");
+    let mut config = config();
+    config.extraction.data_folder_mode = true;
+    config.advanced.backup_path = backups.to_string_lossy().into_owned();
+    let events = EventBus::new();
+    let mut rx = events.subscribe();
+    let result = run(vec![entry], config, &extractor, Some(events), None).await;
+
+    assert_eq!(result.successful, 1);
+    assert_eq!(std::fs::read(&loose).unwrap(), entry_data(FILES[0]));
+    assert_eq!(
+        std::fs::read(extracted(&backups.join("Data"), FILES[0])).unwrap(),
+        b"loose"
+    );
+    let mut saved = 0;
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::Backup(BackupEvent::LooseFilesSaved { count, .. }) = event {
+            saved += count;
+        }
+    }
+    assert_eq!(saved, 1);
+}
+
+#[tokio::test]
+async fn test_auto_backup_copies_archives_before_extraction() {
+    let dir = tempfile::tempdir().unwrap();
+    let (path, entry) = archive(dir.path(), "ModA");
+    let backups = dir.path().join("Backups");
+    let mut config = config();
+    config.extraction.auto_backup = true;
+    config.advanced.backup_path = backups.to_string_lossy().into_owned();
+    let events = EventBus::new();
+    let mut rx = events.subscribe();
+
+    let extractor = Arc::new(MockExtractor::new());
+    let result = run(vec![entry], config, &extractor, Some(events), None).await;
+
+    assert_eq!(result.successful, 1);
+    let backup = backups.join("ModA").join("ModA - Main.ba2");
+    assert_eq!(
+        std::fs::read(&backup).unwrap(),
+        std::fs::read(&path).unwrap()
+    );
+    let mut saved = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        if let AppEvent::Backup(BackupEvent::ArchiveSaved { backup, .. }) = event {
+            saved.push(backup);
+        }
+    }
+    assert_eq!(saved, [backup]);
+}
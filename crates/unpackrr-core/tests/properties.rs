@@ -0,0 +1,216 @@
+//! Property tests of size parsing and archive name matching
+//!
+//! Generated inputs (mixed case, Unicode names, odd spacing, huge values)
+//! pin down how sizes are parsed and which archives postfixes and ignore
+//! patterns match, beyond the examples of the unit tests.
+
+use proptest::prelude::*;
+use std::path::Path;
+use unpackrr_core::config::ignore::{IgnoreMatcher, IgnorePattern, PatternKind};
+use unpackrr_core::config::postfix::{PostfixMatcher, PostfixPattern};
+use unpackrr_core::operations::{SizeSpec, parse_size};
+
+/// A size unit as typed and its multiplier
+fn unit() -> impl Strategy<Value = (&'static str, u64)> {
+    prop::sample::select(vec![
+        ("", 1),
+        ("B", 1),
+        ("K", 1_000),
+        ("KB", 1_000),
+        ("MB", 1_000_000),
+        ("GB", 1_000_000_000),
+        ("TB", 1_000_000_000_000),
+        ("KiB", 1 << 10),
+        ("MiB", 1 << 20),
+        ("GiB", 1 << 30),
+        ("TiB", 1 << 40),
+    ])
+}
+
+/// Spacing users type around numbers: spaces, tabs and no-break spaces
+fn spacing() -> impl Strategy<Value = String> {
+    "[ \t\u{a0}]{0,3}"
+}
+
+/// Flip the case of the ASCII letters of `text` where `flips` says so
+fn flip_case(text: &str, flips: &[bool]) -> String {
+    text.chars()
+        .zip(flips.iter().chain(std::iter::repeat(&false)))
+        .map(|(c, &flip)| match (flip, c.is_ascii_lowercase()) {
+            (false, _) => c,
+            (true, true) => c.to_ascii_uppercase(),
+            (true, false) => c.to_ascii_lowercase(),
+        })
+        .collect()
+}
+
+/// An archive file name, with letters and digits of any script
+fn archive_name() -> impl Strategy<Value = String> {
+    "[\\p{L}\\p{N} _().-]{0,24}".prop_map(|stem| format!("{} - Main.ba2", stem.trim()))
+}
+
+fn ignores(pattern: IgnorePattern, path: &str) -> bool {
+    IgnoreMatcher::new(&[pattern])
+        .unwrap()
+        .ignores(Path::new(path))
+}
+
+proptest! {
+    #[test]
+    fn whole_numbers_parse_exactly(bytes: u64) {
+        prop_assert_eq!(parse_size(&bytes.to_string()).unwrap(), bytes);
+    }
+
+    #[test]
+    fn units_ignore_case_and_spacing(
+        number in 0u64..1_000_000,
+        (unit, multiplier) in unit(),
+        flips in prop::collection::vec(any::<bool>(), 3),
+        before in spacing(),
+        between in spacing(),
+        after in spacing(),
+    ) {
+        let text = format!("{before}{number}{between}{}{after}", flip_case(unit, &flips));
+        prop_assert_eq!(parse_size(&text).unwrap(), number * multiplier);
+    }
+
+    #[test]
+    fn decimals_round_to_the_nearest_byte(
+        whole in 0u64..10_000,
+        hundredths in 0u64..100,
+        (unit, multiplier) in unit(),
+    ) {
+        let text = format!("{whole}.{hundredths:02} {unit}");
+        let parsed = parse_size(&text).unwrap();
+        let exact = u128::from(whole * 100 + hundredths) * u128::from(multiplier);
+        let nearest = (exact + 50) / 100;
+        prop_assert!(u128::from(parsed).abs_diff(nearest) <= 1, "{parsed} != {nearest}");
+    }
+
+    #[test]
+    fn negative_sizes_are_rejected(number in 0u64..u64::MAX, (unit, _) in unit()) {
+        let whole = format!("-{number}{unit}");
+        let decimal = format!("-{number}.5 {unit}");
+        prop_assert!(parse_size(&whole).is_err());
+        prop_assert!(parse_size(&decimal).is_err());
+    }
+
+    #[test]
+    fn sizes_beyond_u64_are_rejected(number in (u64::MAX / 10 + 1)..u64::MAX) {
+        let in_kilobytes = format!("{number} KB");
+        let one_digit_more = format!("{number}9");
+        prop_assert!(parse_size(&in_kilobytes).is_err());
+        prop_assert!(parse_size(&one_digit_more).is_err());
+    }
+
+    #[test]
+    fn parsing_never_panics(text in "\\PC*") {
+        let _ = parse_size(&text);
+    }
+
+    #[test]
+    fn displayed_sizes_parse_back_without_shrinking(bytes in 0u64..(1 << 50)) {
+        let size = SizeSpec::from_bytes(bytes);
+        let parsed = SizeSpec::parse(&size.to_display_string()).unwrap().bytes();
+        prop_assert!(parsed >= bytes, "{bytes} displayed as {size} parsed back to {parsed}");
+        prop_assert!(parsed - bytes <= bytes / 100 + 1);
+    }
+
+    #[test]
+    fn suffixes_match_in_any_case(
+        name in archive_name(),
+        flips in prop::collection::vec(any::<bool>(), 0..40),
+    ) {
+        let postfix = flip_case("- Main.ba2", &flips);
+        let pattern = PostfixPattern::parse(&postfix).unwrap();
+        prop_assert!(pattern.matches(&name));
+        prop_assert!(pattern.matches(&flip_case(&name, &flips)));
+        prop_assert!(!pattern.matches(&name.replace(" - Main", " - Textures")));
+    }
+
+    #[test]
+    fn trailing_whitespace_of_postfixes_is_ignored(name in archive_name(), padding in "[ \t]{1,3}") {
+        let postfix = format!("- Main.ba2{padding}");
+        let pattern = PostfixPattern::parse(&postfix).unwrap();
+        prop_assert!(pattern.matches(&name));
+    }
+
+    #[test]
+    fn question_marks_match_one_character_each(name in "\\PC{1,16}") {
+        let count = name.chars().count();
+        let exact = PostfixPattern::parse(&"?".repeat(count)).unwrap();
+        let longer = PostfixPattern::parse(&"?".repeat(count + 1)).unwrap();
+        prop_assert!(exact.matches(&name));
+        prop_assert!(!longer.matches(&name));
+    }
+
+    #[test]
+    fn glob_and_suffix_postfixes_agree(
+        name in archive_name(),
+        suffix in "[A-Za-z0-9 _-]{0,12}",
+    ) {
+        let suffix = format!("{suffix}.ba2");
+        let plain = PostfixPattern::parse(&suffix).unwrap();
+        let glob = PostfixPattern::parse(&format!("*{suffix}")).unwrap();
+        prop_assert_eq!(plain.matches(&name), glob.matches(&name));
+    }
+
+    #[test]
+    fn ignore_patterns_match_in_any_case(
+        name in archive_name(),
+        flips in prop::collection::vec(any::<bool>(), 0..40),
+        start in 0usize..8,
+    ) {
+        let path = format!("/mods/Mod/{name}");
+        let typed = flip_case(&name, &flips);
+        let part: String = typed.chars().skip(start).take(6).collect();
+
+        prop_assert!(ignores(IgnorePattern::new(PatternKind::Exact, typed.clone()), &path));
+        prop_assert!(ignores(IgnorePattern::new(PatternKind::Substring, part), &path));
+        prop_assert!(ignores(IgnorePattern::new(PatternKind::Glob, typed.clone()), &path));
+        let escaped = format!("^{}$", regex::escape(&typed));
+        prop_assert!(ignores(IgnorePattern::new(PatternKind::Regex, escaped), &path));
+    }
+
+    #[test]
+    fn exact_paths_match_with_either_slash(
+        folder in "[\\p{L}\\p{N} _-]{1,12}",
+        name in archive_name(),
+        padding in "[ \t]{0,2}",
+    ) {
+        let path = format!("/mods/{}/{name}", folder.trim());
+        let typed = format!("{}{padding}", path.replace('/', "\\").to_ascii_uppercase());
+        prop_assert!(ignores(IgnorePattern::new(PatternKind::Exact, typed), &path));
+    }
+
+    #[test]
+    fn matching_does_not_depend_on_pattern_order(
+        patterns in prop::collection::vec(
+            (prop::sample::select(PatternKind::ALL.to_vec()), "[A-Za-z*?. -]{1,8}"),
+            0..6,
+        ),
+        name in archive_name(),
+    ) {
+        let patterns: Vec<IgnorePattern> = patterns
+            .into_iter()
+            .map(|(kind, pattern)| IgnorePattern::new(kind, pattern))
+            .collect();
+        let mut reversed = patterns.clone();
+        reversed.reverse();
+        let path = Path::new("/mods/Mod").join(&name);
+        let (Ok(forward), Ok(backward)) = (IgnoreMatcher::new(&patterns), IgnoreMatcher::new(&reversed)) else {
+            return Ok(());
+        };
+        prop_assert_eq!(forward.ignores(&path), backward.ignores(&path));
+
+        let postfixes: Vec<String> = patterns
+            .iter()
+            .map(|pattern| format!("{}.ba2", pattern.pattern))
+            .collect();
+        let mut reversed: Vec<String> = postfixes.clone();
+        reversed.reverse();
+        if let (Ok(forward), Ok(backward)) = (PostfixMatcher::new(&postfixes), PostfixMatcher::new(&reversed)) {
+            prop_assert_eq!(forward.matches(&name), backward.matches(&name));
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Crash reports of panics
+//!
+//! The panic hook logs every panic and writes a report with the message,
+//! where it happened and a backtrace to the `crashes` folder next to the
+//! logs. Background tasks that catch a panic, e.g. a scan whose task
+//! panicked, pick its report up with [`take_report`] to show it to the user.
+
+use crate::logging;
+use anyhow::Result;
+use parking_lot::Mutex;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::{self, PanicHookInfo};
+use std::path::PathBuf;
+
+/// Reports kept for background tasks to pick up
+const KEPT_REPORTS: usize = 8;
+
+/// Reports of recent panics, oldest first
+static RECENT: Mutex<Vec<CrashReport>> = parking_lot::const_mutex(Vec::new());
+
+/// A panic, as reported to the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrashReport {
+    /// The panic message
+    pub message: String,
+
+    /// Where the panic happened (`file:line:column`), if known
+    pub location: Option<String>,
+
+    /// The written report, if it could be written
+    pub path: Option<PathBuf>,
+}
+
+/// Log panics and write crash reports for them
+pub fn install_hook() {
+    panic::set_hook(Box::new(|info| {
+        let report = record(info);
+        tracing::error!(
+            "PANIC occurred at {}: {}",
+            report.location.as_deref().unwrap_or("Unknown location"),
+            report.message
+        );
+    }));
+}
+
+fn record(info: &PanicHookInfo) -> CrashReport {
+    let message = panic_message(info.payload());
+    let location = info
+        .location()
+        .map(|loc| format!("{}:{}:{}", loc.file(), loc.line(), loc.column()));
+    let thread = std::thread::current()
+        .name()
+        .unwrap_or("unnamed")
+        .to_string();
+    let text = format!(
+        "Unpackrr-rs v{}\nThread: {}\nLocation: {}\nMessage: {}\n\nBacktrace:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        thread,
+        location.as_deref().unwrap_or("unknown"),
+        message,
+        Backtrace::force_capture()
+    );
+    let path = write_report(&text)
+        .inspect_err(|e| tracing::error!("Failed to write a crash report: {}", e))
+        .ok();
+
+    let report = CrashReport {
+        message,
+        location,
+        path,
+    };
+    let mut recent = RECENT.lock();
+    if recent.len() == KEPT_REPORTS {
+        recent.remove(0);
+    }
+    recent.push(report.clone());
+    report
+}
+
+/// Folder the crash reports are written to
+pub fn crash_dir() -> Result<PathBuf> {
+    Ok(logging::get_log_dir()?.join("crashes"))
+}
+
+fn write_report(text: &str) -> Result<PathBuf> {
+    let dir = crash_dir()?;
+    fs::create_dir_all(&dir)?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.3f");
+    let path = dir.join(format!("crash-{stamp}.txt"));
+    fs::write(&path, text)?;
+    Ok(path)
+}
+
+/// The message a panic was raised with
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload.downcast_ref::<&str>().map_or_else(
+        || {
+            payload
+                .downcast_ref::<String>()
+                .cloned()
+                .unwrap_or_else(|| "Unknown panic payload".to_string())
+        },
+        |s| (*s).to_string(),
+    )
+}
+
+/// The report of a caught panic, by its payload
+///
+/// Falls back to a report with just the message if the hook didn't see the
+/// panic, e.g. when it isn't installed.
+pub fn take_report(payload: &(dyn Any + Send)) -> CrashReport {
+    let message = panic_message(payload);
+    let mut recent = RECENT.lock();
+    let index = recent.iter().rposition(|report| report.message == message);
+    let recorded = index.map(|index| recent.remove(index));
+    drop(recent);
+    recorded.unwrap_or(CrashReport {
+        message,
+        location: None,
+        path: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_report() {
+        let payload = panic::catch_unwind(|| panic!("broken {}", 42)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "broken 42");
+
+        // Without the hook, only the message is known
+        let report = take_report(payload.as_ref());
+        assert_eq!(report.message, "broken 42");
+        assert_eq!(report.path, None);
+
+        let payload: Box<dyn Any + Send> = Box::new(7);
+        assert_eq!(panic_message(payload.as_ref()), "Unknown panic payload");
+    }
+}
@@ -0,0 +1,95 @@
+//! Discord Rich Presence
+//!
+//! Shows the progress of a running extraction, e.g. "Unpacking 42/180
+//! archives (63%)", on the user's Discord profile. Talks to the Discord
+//! client running on the same computer over its local IPC socket, and does
+//! nothing if Discord isn't running.
+//!
+//! Discord needs the ID of a registered application, set at build time with
+//! the `UNPACKRR_DISCORD_CLIENT_ID` environment variable. Builds without it
+//! never connect.
+
+use crate::operations::status::{ProgressSnapshot, StatusTracker};
+use crate::operations::{AppEvent, ExtractionProgress};
+use discord_rich_presence::activity::{Activity, Timestamps};
+use discord_rich_presence::{DiscordIpc, DiscordIpcClient};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+/// Discord application ID the presence is shown under
+pub const CLIENT_ID: Option<&str> = option_env!("UNPACKRR_DISCORD_CLIENT_ID");
+
+/// Shortest time between two presence updates (Discord's rate limit)
+const UPDATE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Text shown for a run in progress
+pub fn progress_text(snapshot: &ProgressSnapshot) -> String {
+    format!(
+        "Unpacking {}/{} archives ({}%)",
+        snapshot.done, snapshot.total, snapshot.percent
+    )
+}
+
+/// Show the progress of a run on the user's Discord profile
+///
+/// Give it a [`monitor`](crate::operations::EventBus::monitor) of the run's
+/// bus. Blocks until the run drops the bus, then clears the presence, so
+/// call it from its own thread.
+pub fn show_progress(mut events: mpsc::Receiver<AppEvent>) {
+    let Some(client_id) = CLIENT_ID else {
+        tracing::debug!("Built without a Discord application ID; no Rich Presence");
+        return;
+    };
+    let mut client = DiscordIpcClient::new(client_id);
+    if let Err(e) = client.connect() {
+        tracing::debug!("Discord is not available for Rich Presence: {}", e);
+        return;
+    }
+
+    let mut tracker = StatusTracker::new();
+    let mut last_update: Option<Instant> = None;
+    while let Some(event) = events.blocking_recv() {
+        let AppEvent::Extract(progress) = event else {
+            continue;
+        };
+        tracker.record(&progress);
+        if matches!(progress, ExtractionProgress::Finished { .. }) {
+            break;
+        }
+        if last_update.is_some_and(|at| at.elapsed() < UPDATE_INTERVAL) {
+            continue;
+        }
+        last_update = Some(Instant::now());
+
+        let snapshot = tracker.snapshot();
+        let details = progress_text(&snapshot);
+        let started = i64::try_from(snapshot.started).unwrap_or_default();
+        let activity = Activity::new()
+            .details(details.as_str())
+            .timestamps(Timestamps::new().start(started));
+        if let Err(e) = client.set_activity(activity) {
+            tracing::debug!("Failed to update Discord Rich Presence: {}", e);
+            return;
+        }
+    }
+
+    if let Err(e) = client.clear_activity().and_then(|()| client.close()) {
+        tracing::debug!("Failed to clear Discord Rich Presence: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_text() {
+        let snapshot = ProgressSnapshot {
+            done: 42,
+            total: 180,
+            percent: 63,
+            ..StatusTracker::new().snapshot()
+        };
+        assert_eq!(progress_text(&snapshot), "Unpacking 42/180 archives (63%)");
+    }
+}
@@ -0,0 +1,9 @@
+//! Integrations with other applications
+//!
+//! Optional links to programs running next to Unpackrr. Each one is built
+//! only with its feature enabled and switched on in Settings.
+//!
+//! - `discord`: Rich Presence showing extraction progress (`discord` feature)
+
+#[cfg(feature = "discord")]
+pub mod discord;
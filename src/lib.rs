@@ -5,32 +5,44 @@
 //!
 //! # Architecture
 //!
+//! Re-exported from the `unpackrr-core` crate (no UI dependencies):
+//!
 //! - `error`: Custom error types and error handling
 //! - `config`: Configuration management and persistence
 //! - `ba2`: BA2 file format support and BSArch.exe integration
 //! - `operations`: File system operations (scanning, extraction, validation)
 //! - `models`: Data models for UI display
+//! - `platform`: Platform-specific functionality (Windows registry, etc.)
+//! - `ipc`: Local automation interface (socket or named pipe)
+//!
+//! Frontends and application services:
+//!
 //! - `ui`: Slint UI components and integration
+//! - `tui`: Terminal frontend (optional `tui` feature)
+//! - `integrations`: Links to other applications, like Discord (optional features)
 //! - `logging`: Logging configuration and file rotation
+//! - `crash`: Crash reports of panics
+//! - `safe_mode`: Safe mode after a broken configuration or crashing startups
 //! - `log_viewer`: Log viewer for displaying and filtering application logs
+//! - `nexus`: Nexus Mods API, used to check the stored API key
 //! - `update_checker`: GitHub release update checking
-//! - `platform`: Platform-specific functionality (Windows registry, etc.)
 
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
 #![allow(clippy::must_use_candidate, clippy::missing_errors_doc)]
 
-pub mod ba2;
-pub mod config;
-pub mod error;
+pub mod crash;
+pub mod integrations;
 pub mod log_viewer;
 pub mod logging;
-pub mod models;
-pub mod operations;
-pub mod platform;
+pub mod nexus;
+pub mod safe_mode;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod ui;
 pub mod update_checker;
 
-pub use error::{Error, Result};
+// The core modules live in the unpackrr-core crate
+pub use unpackrr_core::{Error, Result, ba2, config, error, ipc, models, operations, platform};
 
 use std::sync::OnceLock;
 use tokio::runtime::Runtime;
@@ -100,7 +100,7 @@ impl LogEntry {
         // Note: there may be multiple spaces between timestamp and level
         let parts: Vec<&str> = line.split_whitespace().collect();
 
-        if parts.len() >= 3 {
+        if parts.len() >= 2 {
             // Try to parse timestamp (ISO 8601 format)
             if parts[0].contains('T') && (parts[0].contains('Z') || parts[0].contains('+')) {
                 timestamp = Some(parts[0].to_string());
@@ -115,12 +115,12 @@ impl LogEntry {
                     _ => None,
                 };
 
-                if level.is_some() && parts.len() >= 3 {
+                if level.is_some() {
                     // Parse target and message
-                    // Find the position after timestamp and level in the original string
-                    let after_level = line.find(parts[1]).map_or(0, |pos| pos + parts[1].len());
-
-                    let rest = line[after_level..].trim_start();
+                    // Skip the timestamp and level tokens rather than searching for
+                    // the level, which could also appear inside the timestamp
+                    let rest = line.trim_start()[parts[0].len()..].trim_start()[parts[1].len()..]
+                        .trim_start();
 
                     // Check if there's a colon-space indicating target: message format
                     // We look for ": " to avoid matching "::" in module paths
@@ -270,6 +270,7 @@ impl Default for LogViewer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_parse_structured_log() {
@@ -306,6 +307,50 @@ mod tests {
         assert_eq!(entry.raw_line, line);
     }
 
+    #[test]
+    fn test_parse_level_inside_timestamp() {
+        // The level must be read after the timestamp, not found inside it
+        let line = "INFOT10:30:45Z  WARN test: message".to_string();
+        let entry = LogEntry::parse(line);
+
+        assert_eq!(entry.level, Some(LogLevel::Warn));
+        assert_eq!(entry.target, Some("test".to_string()));
+        assert_eq!(entry.message, "message");
+    }
+
+    #[test]
+    fn test_parse_empty_message() {
+        let entry = LogEntry::parse("2025-01-22T10:30:45.123456Z DEBUG".to_string());
+
+        assert_eq!(entry.level, Some(LogLevel::Debug));
+        assert_eq!(entry.message, "");
+    }
+
+    proptest! {
+        #[test]
+        fn parse_keeps_any_line(line in "\\PC*") {
+            let entry = LogEntry::parse(line.clone());
+            prop_assert_eq!(entry.raw_line, line);
+        }
+
+        #[test]
+        fn parse_recovers_structured_fields(
+            timestamp in "[0-9A-Z:.-]{0,12}T[0-9A-Z:.-]{0,12}Z",
+            padding in " {1,3}",
+            level in prop::sample::select(vec!["TRACE", "DEBUG", "INFO", "WARN", "ERROR"]),
+            target in "[a-z_]{1,8}(::[a-z_]{1,8}){0,3}",
+            message in "\\PC*",
+        ) {
+            let line = format!("{timestamp}{padding}{level} {target}: {message}");
+            let entry = LogEntry::parse(line);
+
+            prop_assert_eq!(entry.timestamp, Some(timestamp));
+            prop_assert_eq!(entry.level.map(|level| level.as_str()), Some(level));
+            prop_assert_eq!(entry.target, Some(target));
+            prop_assert_eq!(entry.message, message.trim());
+        }
+    }
+
     #[test]
     fn test_log_level_ordering() {
         assert!(LogLevel::Error > LogLevel::Warn);
@@ -51,6 +51,19 @@ use tracing_subscriber::{
 /// }
 /// ```
 pub fn init(config: Option<&AppConfig>) -> Result<Option<WorkerGuard>> {
+    init_layers(config, true)
+}
+
+/// Initialize the logging system without console output
+///
+/// Used by the terminal UI, where log lines would garble the screen. Logs
+/// still go to the rotating log file.
+pub fn init_file_only(config: Option<&AppConfig>) -> Result<Option<WorkerGuard>> {
+    init_layers(config, false)
+}
+
+/// Set up the file layer and, if `console` is set, the console layer
+fn init_layers(config: Option<&AppConfig>, console: bool) -> Result<Option<WorkerGuard>> {
     // Determine log level from config or default to INFO
     let log_level = config.map_or(Level::INFO, |c| {
         config_log_level_to_tracing(c.advanced.log_level)
@@ -91,6 +104,7 @@ pub fn init(config: Option<&AppConfig>) -> Result<Option<WorkerGuard>> {
         })
         .with_ansi(true)
         .with_filter(env_filter.clone());
+    let console_layer = console.then_some(console_layer);
 
     // File layer with rotation
     let (file_layer, guard) = match create_file_appender()? {
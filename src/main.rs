@@ -1,35 +1,26 @@
-use std::panic;
-use unpackrr::{config::AppConfig, logging, ui};
+use unpackrr::{config::AppConfig, crash, logging, ui};
 
 fn main() -> anyhow::Result<()> {
+    // `--tui [folder]` starts the terminal frontend instead of the window
+    let mut args = std::env::args().skip(1);
+    let tui = args.next().is_some_and(|arg| arg == "--tui");
+    let tui_folder = args.next().map(std::path::PathBuf::from);
+
     // Load configuration (if available)
     let config = AppConfig::load().ok();
 
     // Initialize logging system
-    // This sets up both console and file logging with rotation
+    // This sets up both console and file logging with rotation (file only
+    // in the terminal UI, where console output would garble the screen)
     // Hold the guard for the application lifetime to ensure logs are flushed on shutdown
-    let _log_guard = logging::init(config.as_ref())?;
-
-    // Phase 3.3: Set up panic handler to log panics
-    panic::set_hook(Box::new(|panic_info| {
-        let payload = panic_info.payload();
-        let message = payload.downcast_ref::<&str>().map_or_else(
-            || {
-                payload
-                    .downcast_ref::<String>()
-                    .cloned()
-                    .unwrap_or_else(|| "Unknown panic payload".to_string())
-            },
-            |s| (*s).to_string(),
-        );
-
-        let location = panic_info.location().map_or_else(
-            || "Unknown location".to_string(),
-            |loc| format!("{}:{}:{}", loc.file(), loc.line(), loc.column()),
-        );
-
-        tracing::error!("PANIC occurred at {}: {}", location, message);
-    }));
+    let _log_guard = if tui {
+        logging::init_file_only(config.as_ref())?
+    } else {
+        logging::init(config.as_ref())?
+    };
+
+    // Phase 3.3: Log panics and write crash reports for them
+    crash::install_hook();
 
     tracing::info!("Starting Unpackrr-rs v{}", env!("CARGO_PKG_VERSION"));
     tracing::info!(
@@ -45,10 +36,24 @@ fn main() -> anyhow::Result<()> {
         tracing::warn!("Configuration not found, using defaults");
     }
 
-    // Run the UI (this will initialize and run the Slint event loop)
-    ui::run()?;
+    if tui {
+        run_tui(tui_folder)?;
+    } else {
+        // Run the UI (this will initialize and run the Slint event loop)
+        ui::run()?;
+    }
 
     tracing::info!("Application shutting down");
 
     Ok(())
 }
+
+#[cfg(feature = "tui")]
+fn run_tui(folder: Option<std::path::PathBuf>) -> anyhow::Result<()> {
+    unpackrr::tui::run(folder)
+}
+
+#[cfg(not(feature = "tui"))]
+fn run_tui(_folder: Option<std::path::PathBuf>) -> anyhow::Result<()> {
+    anyhow::bail!("This build has no terminal UI; rebuild with `--features tui`")
+}
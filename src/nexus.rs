@@ -0,0 +1,76 @@
+//! Nexus Mods API
+//!
+//! Checks the personal API key kept in the credential store (see
+//! [`crate::config::secrets`]) against the Nexus Mods API, which reports the
+//! account the key belongs to.
+
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+/// Endpoint returning the account of an API key
+const VALIDATE_URL: &str = "https://api.nexusmods.com/v1/users/validate.json";
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Nexus Mods account an API key belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct NexusUser {
+    /// User name
+    pub name: String,
+    /// Whether the account has Premium membership
+    #[serde(default)]
+    pub is_premium: bool,
+}
+
+/// Ask Nexus Mods whose API key `key` is
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Nexus Mods rejects the key
+/// - The request fails or the response cannot be parsed
+pub async fn validate_key(key: &str) -> Result<NexusUser> {
+    tracing::info!("Checking the Nexus Mods API key...");
+
+    let client = reqwest::Client::builder()
+        .user_agent(format!("unpackrr/{CURRENT_VERSION}"))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let response = client
+        .get(VALIDATE_URL)
+        .header("apikey", key)
+        .send()
+        .await
+        .context("Failed to reach Nexus Mods")?;
+
+    match response.status() {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            return Err(anyhow::anyhow!("Nexus Mods rejected the API key"));
+        }
+        status if !status.is_success() => {
+            return Err(anyhow::anyhow!("Nexus Mods API returned error: {status}"));
+        }
+        _ => {}
+    }
+
+    let user: NexusUser = response
+        .json()
+        .await
+        .context("Failed to parse Nexus Mods API response")?;
+    tracing::info!("The API key belongs to {}", user.name);
+    Ok(user)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_validate_response() {
+        let json = r#"{"user_id":1,"key":"abc","name":"Modder","is_premium":true,"email":"m@example.com"}"#;
+        let user: NexusUser = serde_json::from_str(json).unwrap();
+        assert_eq!(user.name, "Modder");
+        assert!(user.is_premium);
+    }
+}
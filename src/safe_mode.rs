@@ -0,0 +1,116 @@
+//! Safe mode after a broken configuration or crashing startups
+//!
+//! Unpackrr starts in safe mode, on default settings, when `config.json`
+//! can't be loaded or when the last [`CRASHED_STARTS_LIMIT`] startups
+//! crashed before the window was up for [`STARTUP_GRACE`]. The broken file is
+//! left alone until the user backs it up, repairs or resets it.
+//!
+//! Startups are counted in a small file in the data folder: each start adds
+//! one, and a start that lasts past the grace period sets it back to zero.
+
+use crate::logging;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Name of the file counting unfinished startups, in the data folder
+const STARTUP_FILE: &str = "startup-attempts";
+
+/// Unfinished startups in a row after which the app starts in safe mode
+pub const CRASHED_STARTS_LIMIT: u32 = 2;
+
+/// How long the window must be up for a startup to count as finished
+pub const STARTUP_GRACE: Duration = Duration::from_secs(10);
+
+/// Why the app started in safe mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SafeModeReason {
+    /// The configuration file could not be loaded, with the reason
+    ConfigUnreadable(String),
+    /// The last startups, this many, crashed
+    StartupCrashes(u32),
+}
+
+impl SafeModeReason {
+    /// Explanation shown in the safe mode banner
+    pub fn explanation(&self) -> String {
+        match self {
+            Self::ConfigUnreadable(error) => format!(
+                "Safe mode: config.json could not be loaded ({error}). Unpackrr runs on \
+                 default settings and leaves the file alone; repair it to keep its valid \
+                 settings, or back it up first."
+            ),
+            Self::StartupCrashes(count) => format!(
+                "Safe mode: Unpackrr closed unexpectedly during the last {count} startups, \
+                 so it runs on default settings and doesn't save them. Reset the settings \
+                 if the problem comes back, or restart to use yours again."
+            ),
+        }
+    }
+}
+
+fn startup_file() -> Result<PathBuf> {
+    // The logs are in the data folder
+    let log_dir = logging::get_log_dir()?;
+    let data_dir = log_dir.parent().context("No data folder")?;
+    Ok(data_dir.join(STARTUP_FILE))
+}
+
+/// Record the start of the app, returning how many startups before it didn't finish
+pub fn begin_startup() -> u32 {
+    let Ok(path) = startup_file() else {
+        return 0;
+    };
+    let unfinished = fs::read_to_string(&path)
+        .ok()
+        .and_then(|count| count.trim().parse().ok())
+        .unwrap_or(0);
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|()| fs::write(&path, (unfinished + 1).to_string()));
+    if let Err(e) = written {
+        tracing::debug!("Failed to record the startup in {}: {}", path.display(), e);
+    }
+    unfinished
+}
+
+/// Record that the app started successfully
+pub fn startup_finished() {
+    let Ok(path) = startup_file() else {
+        return;
+    };
+    if let Err(e) = fs::write(&path, "0") {
+        tracing::debug!("Failed to record the startup in {}: {}", path.display(), e);
+    }
+}
+
+/// Whether the app should start in safe mode
+///
+/// `config_error` is the reason the configuration could not be loaded;
+/// `unfinished` the count returned by [`begin_startup`].
+pub fn reason(config_error: Option<String>, unfinished: u32) -> Option<SafeModeReason> {
+    config_error.map(SafeModeReason::ConfigUnreadable).or_else(|| {
+        (unfinished >= CRASHED_STARTS_LIMIT).then_some(SafeModeReason::StartupCrashes(unfinished))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reason() {
+        assert_eq!(reason(None, 0), None);
+        assert_eq!(reason(None, CRASHED_STARTS_LIMIT - 1), None);
+        assert_eq!(
+            reason(None, CRASHED_STARTS_LIMIT),
+            Some(SafeModeReason::StartupCrashes(CRASHED_STARTS_LIMIT))
+        );
+        assert_eq!(
+            reason(Some("bad JSON".to_string()), 5),
+            Some(SafeModeReason::ConfigUnreadable("bad JSON".to_string()))
+        );
+    }
+}
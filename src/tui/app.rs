@@ -0,0 +1,481 @@
+//! State of the terminal UI
+//!
+//! Key presses and background progress update an [`App`]; anything that
+//! needs to run in the background is returned as a [`Command`] for the event
+//! loop to start. Nothing here touches the terminal.
+
+use crate::config::AppConfig;
+use crate::config::rules::selected_paths;
+use crate::error::Result;
+use crate::models::{ExtractionStatus, FileEntry, FileEntryList};
+use crate::operations::{
+    AppEvent, BA2FileInfo, CancellationToken, ExtractionProgress, ExtractionResult, LoadOrder,
+    ScanProgress, ThroughputTracker, diagnose, format_count,
+};
+use crate::ui::view_model::{MainViewModel, ProgressText, TableFilter};
+use ratatui::crossterm::event::KeyCode;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+/// Rows skipped by Page Up/Page Down
+const PAGE_ROWS: usize = 10;
+
+/// What the application is doing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Idle,
+    Scanning,
+    Extracting,
+}
+
+/// Work the event loop starts on behalf of the [`App`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Scan the mod folder
+    Scan,
+    /// Extract the given archives
+    Extract(Vec<FileEntry>),
+}
+
+/// Progress and results sent from background tasks
+#[derive(Debug)]
+pub enum Message {
+    Event(AppEvent),
+    ScanFinished(Result<Vec<BA2FileInfo>>),
+    ExtractionFinished(Result<ExtractionResult>),
+}
+
+/// State of the terminal UI
+#[derive(Debug)]
+pub struct App {
+    /// Loaded configuration (selection rules, extraction settings)
+    pub config: AppConfig,
+
+    /// Mod folder being scanned
+    pub folder: PathBuf,
+
+    /// Scanned archives
+    pub entries: FileEntryList,
+
+    /// Plugin load order, if it could be read
+    pub load_order: Option<LoadOrder>,
+
+    /// Index of the highlighted row among the shown rows
+    pub cursor: usize,
+
+    /// Only show archives whose extraction failed
+    pub failed_only: bool,
+
+    /// Current activity
+    pub mode: Mode,
+
+    /// Message shown in the status line
+    pub status: String,
+
+    /// Scanned and total mod folders while scanning
+    pub scan_progress: Option<(usize, usize)>,
+
+    /// Bytes processed by the running extraction
+    pub throughput: Option<ThroughputTracker>,
+
+    /// Finished and total archives of the running extraction
+    pub run_progress: (usize, usize),
+
+    /// Cancels the running extraction
+    pub cancel: Option<CancellationToken>,
+
+    /// Set when the user asked to quit
+    pub quit: bool,
+}
+
+impl App {
+    /// Create the state for a mod folder
+    pub const fn new(config: AppConfig, folder: PathBuf, load_order: Option<LoadOrder>) -> Self {
+        Self {
+            config,
+            folder,
+            entries: FileEntryList::new(),
+            load_order,
+            cursor: 0,
+            failed_only: false,
+            mode: Mode::Idle,
+            status: String::new(),
+            scan_progress: None,
+            throughput: None,
+            run_progress: (0, 0),
+            cancel: None,
+            quit: false,
+        }
+    }
+
+    /// Build the table as currently filtered
+    pub fn view(&self) -> MainViewModel {
+        MainViewModel::build(
+            self.entries.entries(),
+            self.load_order.as_ref(),
+            &self.config,
+            &TableFilter {
+                failed_only: self.failed_only,
+                ..TableFilter::default()
+            },
+        )
+    }
+
+    /// Speed and ETA of the running extraction
+    pub fn progress_text(&self) -> ProgressText {
+        self.throughput
+            .as_ref()
+            .map_or_else(ProgressText::default, |t| {
+                ProgressText::new(t.bytes_per_sec(), t.expansion_ratio(), t.eta())
+            })
+    }
+
+    /// Fraction of the current scan or extraction that is done
+    #[allow(clippy::cast_precision_loss)] // Display-only ratio
+    pub fn progress_ratio(&self) -> f64 {
+        match self.mode {
+            Mode::Idle => 0.0,
+            Mode::Scanning => self
+                .scan_progress
+                .filter(|(_, total)| *total > 0)
+                .map_or(0.0, |(current, total)| current as f64 / total as f64),
+            Mode::Extracting => self.throughput.as_ref().map_or(0.0, |t| {
+                let processed = t.processed_bytes();
+                let total = processed + t.remaining_bytes();
+                if total == 0 {
+                    0.0
+                } else {
+                    processed as f64 / total as f64
+                }
+            }),
+        }
+    }
+
+    /// Handle a key press, returning work to start
+    pub fn handle_key(&mut self, key: KeyCode) -> Option<Command> {
+        let shown = self.view().rows.len();
+        match key {
+            KeyCode::Char('q') => {
+                if let Some(cancel) = &self.cancel {
+                    cancel.cancel();
+                }
+                self.quit = true;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_cursor(1, shown),
+            KeyCode::PageUp => self.cursor = self.cursor.saturating_sub(PAGE_ROWS),
+            KeyCode::PageDown => self.move_cursor(PAGE_ROWS, shown),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = shown.saturating_sub(1),
+            KeyCode::Char(' ') if self.mode == Mode::Idle => self.toggle_selected(),
+            KeyCode::Char('a') if self.mode == Mode::Idle => self.toggle_all(),
+            KeyCode::Char('f') => {
+                self.failed_only = !self.failed_only;
+                self.cursor = 0;
+            }
+            KeyCode::Char('s') if self.mode == Mode::Idle => return Some(self.start_scan()),
+            KeyCode::Char('e') if self.mode == Mode::Idle => return self.start_extraction(),
+            KeyCode::Esc | KeyCode::Char('c') if self.mode == Mode::Extracting => {
+                if let Some(cancel) = &self.cancel {
+                    cancel.cancel();
+                    self.status = "Cancelling extraction...".to_string();
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// Apply progress or a result from a background task
+    pub fn handle_message(&mut self, message: Message) {
+        match message {
+            Message::Event(AppEvent::Scan(ScanProgress::ScanningFolder {
+                folder,
+                current,
+                total,
+            })) => {
+                self.scan_progress = Some((current, total));
+                self.status = format!("Scanning {folder}");
+            }
+            Message::Event(AppEvent::Extract(progress)) => {
+                self.apply_extraction_progress(&progress);
+            }
+            Message::Event(_) => {}
+            Message::ScanFinished(result) => self.finish_scan(result),
+            Message::ExtractionFinished(result) => {
+                self.entries.skip_pending();
+                self.mode = Mode::Idle;
+                self.cancel = None;
+                self.throughput = None;
+                self.status = match result {
+                    Ok(result) => {
+                        let mut status = format!(
+                            "Extraction finished: {} succeeded, {} failed, {} skipped",
+                            format_count(result.successful as u64),
+                            format_count(result.failed as u64),
+                            format_count(result.skipped as u64)
+                        );
+                        // The most common cause of the failures, if any
+                        if let Some(cluster) = diagnose(&result).clusters.first() {
+                            let _ = write!(
+                                status,
+                                " - {} ({}): {}",
+                                cluster.kind.title(),
+                                format_count(cluster.archives.len() as u64),
+                                cluster.kind.suggestions()[0]
+                            );
+                        }
+                        status
+                    }
+                    Err(e) => format!("Extraction failed: {}", e.user_message()),
+                };
+            }
+        }
+    }
+
+    /// Start scanning the mod folder
+    pub fn start_scan(&mut self) -> Command {
+        self.mode = Mode::Scanning;
+        self.scan_progress = None;
+        self.status = format!("Scanning {}...", self.folder.display());
+        Command::Scan
+    }
+
+    fn move_cursor(&mut self, rows: usize, shown: usize) {
+        self.cursor = (self.cursor + rows).min(shown.saturating_sub(1));
+    }
+
+    /// Path of the highlighted archive
+    fn cursor_path(&self) -> Option<PathBuf> {
+        self.view()
+            .rows
+            .get(self.cursor)
+            .map(|row| PathBuf::from(&row.path))
+    }
+
+    fn toggle_selected(&mut self) {
+        let Some(path) = self.cursor_path() else {
+            return;
+        };
+        if let Some(entry) = self.entries.find_mut(&path) {
+            entry.selected = !entry.selected;
+        }
+    }
+
+    /// Select every archive, or clear the selection if all are selected
+    fn toggle_all(&mut self) {
+        if self.entries.selected_count() == self.entries.len() {
+            self.entries.clear_selection();
+        } else {
+            let all: HashSet<PathBuf> = self
+                .entries
+                .entries()
+                .iter()
+                .map(|e| e.full_path.clone())
+                .collect();
+            self.entries.select_paths(&all);
+        }
+    }
+
+    fn start_extraction(&mut self) -> Option<Command> {
+        if self.config.advanced.analysis_only {
+            self.status = "Extraction is disabled in analysis-only mode".to_string();
+            return None;
+        }
+        let files = self.entries.extraction_candidates();
+        if files.is_empty() {
+            self.status = "Nothing to extract - scan a folder first".to_string();
+            return None;
+        }
+
+        self.entries.set_all_statuses(ExtractionStatus::Idle);
+        for file in &files {
+            self.entries
+                .set_status(&file.full_path, ExtractionStatus::Queued);
+        }
+        self.throughput = Some(ThroughputTracker::new(
+            files.iter().map(|f| (f.full_path.clone(), f.file_size)),
+        ));
+        self.run_progress = (0, files.len());
+        self.mode = Mode::Extracting;
+        self.status = format!(
+            "Extracting {} archives...",
+            format_count(files.len() as u64)
+        );
+        Some(Command::Extract(files))
+    }
+
+    fn finish_scan(&mut self, result: Result<Vec<BA2FileInfo>>) {
+        self.mode = Mode::Idle;
+        self.scan_progress = None;
+        self.cursor = 0;
+        match result {
+            Ok(files) => {
+                self.entries = FileEntryList::from_scan_results(files);
+                self.entries.apply_load_order(self.load_order.as_ref());
+                let rules = &self.config.extraction.selection_rules;
+                let selected = if rules.is_empty() {
+                    0
+                } else {
+                    let paths: HashSet<PathBuf> = selected_paths(rules, self.entries.entries())
+                        .into_iter()
+                        .collect();
+                    self.entries.select_paths(&paths)
+                };
+                self.status = format!(
+                    "Found {} archives ({} selected by rules)",
+                    format_count(self.entries.len() as u64),
+                    format_count(selected as u64)
+                );
+            }
+            Err(e) => {
+                tracing::error!("Scan failed: {}", e);
+                self.status = format!("Scan failed: {}", e.user_message());
+            }
+        }
+    }
+
+    fn apply_extraction_progress(&mut self, progress: &ExtractionProgress) {
+        match progress {
+            ExtractionProgress::Started {
+                file_name,
+                file_path,
+                ..
+            } => {
+                self.entries
+                    .set_status(file_path, ExtractionStatus::Extracting);
+                self.status = format!("Extracting {file_name}");
+            }
+            ExtractionProgress::FileProgress {
+                file_path, percent, ..
+            } => {
+                if let Some(throughput) = self.throughput.as_mut() {
+                    throughput.progress(file_path, *percent);
+                }
+            }
+            ExtractionProgress::Completed {
+                file_path,
+                success,
+                output_size,
+                duration,
+                ..
+            } => {
+                if let Some(throughput) = self.throughput.as_mut() {
+                    throughput.complete(file_path, *output_size);
+                }
+                let status = if *success {
+                    ExtractionStatus::Done
+                } else {
+                    ExtractionStatus::Failed
+                };
+                self.entries.set_status(file_path, status);
+                self.entries.set_extraction_time(file_path, *duration);
+                self.run_progress.0 += 1;
+            }
+            ExtractionProgress::Skipped { file_path, .. } => {
+                if let Some(throughput) = self.throughput.as_mut() {
+                    throughput.skip(file_path);
+                }
+                self.entries
+                    .set_status(file_path, ExtractionStatus::Skipped);
+                self.run_progress.0 += 1;
+            }
+            ExtractionProgress::DriveDisconnected { folder } => {
+                self.status = format!(
+                    "{} can no longer be reached - waiting for the drive",
+                    folder.display()
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ArchiveHealth;
+    use std::time::Duration;
+
+    fn scanned_app(names: &[&str]) -> App {
+        let mut app = App::new(AppConfig::default(), PathBuf::from("Mods"), None);
+        let files = names
+            .iter()
+            .map(|name| BA2FileInfo {
+                file_name: (*name).to_string(),
+                file_size: 1_000,
+                num_files: 10,
+                dir_name: "Mod".to_string(),
+                full_path: PathBuf::from("Mods/Mod").join(name),
+                health: ArchiveHealth::Ok,
+                archive_type: "GNRL".to_string(),
+                format_version: 1,
+                ignored: false,
+                exclusion: None,
+            })
+            .collect();
+        app.handle_message(Message::ScanFinished(Ok(files)));
+        app
+    }
+
+    #[test]
+    fn test_keys_move_cursor_and_select() {
+        let mut app = scanned_app(&["A - Main.ba2", "B - Main.ba2", "C - Main.ba2"]);
+        assert_eq!(app.view().rows.len(), 3);
+
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Char(' '));
+        assert!(app.view().rows[1].marked);
+        app.handle_key(KeyCode::PageDown);
+        assert_eq!(app.cursor, 2);
+        app.handle_key(KeyCode::Up);
+        app.handle_key(KeyCode::Up);
+        app.handle_key(KeyCode::Up);
+        assert_eq!(app.cursor, 0);
+
+        app.handle_key(KeyCode::Char('a'));
+        assert_eq!(app.entries.selected_count(), 3);
+        app.handle_key(KeyCode::Char('a'));
+        assert_eq!(app.entries.selected_count(), 0);
+    }
+
+    #[test]
+    fn test_extraction_commands_and_progress() {
+        let mut app = scanned_app(&["A - Main.ba2", "B - Main.ba2"]);
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Char(' '));
+
+        // Only the selection is extracted
+        let path = PathBuf::from("Mods/Mod/B - Main.ba2");
+        let Some(Command::Extract(files)) = app.handle_key(KeyCode::Char('e')) else {
+            panic!("expected an extraction");
+        };
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].full_path, path);
+        assert_eq!(app.mode, Mode::Extracting);
+        assert_eq!(app.handle_key(KeyCode::Char('s')), None);
+
+        app.handle_message(Message::Event(AppEvent::Extract(
+            ExtractionProgress::Completed {
+                file_name: "B - Main.ba2".to_string(),
+                file_path: path,
+                success: false,
+                error: Some("broken".to_string()),
+                warnings: Vec::new(),
+                output_size: None,
+                error_category: None,
+                duration: Duration::from_secs(2),
+            },
+        )));
+        assert_eq!(app.run_progress, (1, 1));
+        app.handle_message(Message::ExtractionFinished(Ok(ExtractionResult::new())));
+        assert_eq!(app.mode, Mode::Idle);
+
+        app.handle_key(KeyCode::Char('f'));
+        let rows = app.view().rows;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].status, ExtractionStatus::Failed);
+        assert_eq!(rows[0].extraction_time, "2.0s");
+    }
+}
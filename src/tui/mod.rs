@@ -0,0 +1,132 @@
+//! Terminal frontend (`--tui`)
+//!
+//! A ratatui frontend for systems without a desktop environment (e.g., a
+//! headless Linux box managing a Proton prefix). It shows the scan table,
+//! the selection and the progress of a run, reusing the scan and extraction
+//! operations, the configuration and the view models of the Slint UI.
+//!
+//! Only built with the `tui` feature.
+
+pub mod app;
+mod render;
+
+use crate::config::AppConfig;
+use crate::platform::wine::{host_path, set_wine_prefix_from_config};
+use crate::operations::events::log_events;
+use crate::operations::{
+    CancellationToken, EventBus, LoadOrder, extract_all, scan_for_ba2, status_file_path,
+    write_status,
+};
+use anyhow::{Context, Result};
+use app::{App, Command, Message};
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How long to wait for a key press before redrawing progress
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Run the terminal UI on `folder` (or the last used folder) until the user quits
+pub fn run(folder: Option<PathBuf>) -> Result<()> {
+    let config = AppConfig::load().unwrap_or_default();
+    set_wine_prefix_from_config(&config);
+    let folder = folder
+        .or_else(|| {
+            (!config.saved.directory.is_empty()).then(|| host_path(&config.saved.directory))
+        })
+        .context("No mod folder given; start with: unpackrr --tui <folder>")?;
+    let load_order = read_load_order(&config);
+
+    let mut app = App::new(config, folder, load_order);
+    let events = EventBus::new();
+    crate::get_runtime().spawn(log_events(events.monitor()));
+    let (tx, rx) = mpsc::channel();
+    // Start with a scan of the folder
+    let scan = app.start_scan();
+    start(&mut app, scan, &events, &tx);
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, &events, &tx, &rx);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(
+    terminal: &mut ratatui::DefaultTerminal,
+    app: &mut App,
+    events: &EventBus,
+    tx: &mpsc::Sender<Message>,
+    rx: &mpsc::Receiver<Message>,
+) -> Result<()> {
+    while !app.quit {
+        terminal.draw(|frame| render::draw(frame, app))?;
+
+        if event::poll(POLL_INTERVAL)?
+            && let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && let Some(command) = app.handle_key(key.code)
+        {
+            start(app, command, events, tx);
+        }
+        while let Ok(message) = rx.try_recv() {
+            app.handle_message(message);
+        }
+    }
+    Ok(())
+}
+
+/// Start a command in the background, reporting through `tx`
+///
+/// The command publishes on a child of `events`; its events are forwarded
+/// to `tx` until the command drops the bus.
+fn start(app: &mut App, command: Command, events: &EventBus, tx: &mpsc::Sender<Message>) {
+    let config = app.config.clone();
+    let tx = tx.clone();
+    let run = events.child();
+    let mut run_events = run.subscribe();
+    let forward = tx.clone();
+    let forwarding = crate::get_runtime().spawn(async move {
+        while let Some(event) = run_events.recv().await {
+            let _ = forward.send(Message::Event(event));
+        }
+    });
+    match command {
+        Command::Scan => {
+            let folder = app.folder.clone();
+            crate::get_runtime().spawn(async move {
+                let result = scan_for_ba2(&folder, &config, Some(run)).await;
+                // Deliver all progress before the result
+                let _ = forwarding.await;
+                let _ = tx.send(Message::ScanFinished(result));
+            });
+        }
+        Command::Extract(files) => {
+            let cancel = CancellationToken::new();
+            app.cancel = Some(cancel.clone());
+            if config.advanced.diagnostics.write_status_file
+                && let Ok(path) = status_file_path()
+            {
+                crate::get_runtime().spawn(write_status(run.monitor(), path));
+            }
+            crate::get_runtime().spawn(async move {
+                let result = extract_all(files, config, Some(run), Some(cancel)).await;
+                // Deliver all progress before the result
+                let _ = forwarding.await;
+                let _ = tx.send(Message::ExtractionFinished(result));
+            });
+        }
+    }
+}
+
+/// Read the configured plugin list, or the game's plugins.txt
+fn read_load_order(config: &AppConfig) -> Option<LoadOrder> {
+    let path = if config.saved.plugins_path.is_empty() {
+        LoadOrder::default_path(config.profile()).filter(|path| path.is_file())?
+    } else {
+        host_path(&config.saved.plugins_path)
+    };
+    LoadOrder::load(&path, config.profile())
+        .inspect_err(|e| tracing::warn!("Could not load plugin list {}: {}", path.display(), e))
+        .ok()
+}
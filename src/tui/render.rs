@@ -0,0 +1,152 @@
+//! Drawing the terminal UI
+
+use crate::models::{ArchiveHealth, ExtractionStatus};
+use crate::operations::format_count;
+use crate::tui::app::{App, Mode};
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Gauge, Paragraph, Row, Table, TableState};
+
+/// Key help shown at the bottom
+const HELP: &str =
+    "s scan · space select · a all/none · e extract · f failed only · c cancel · q quit";
+
+/// Draw the whole screen
+pub fn draw(frame: &mut Frame, app: &App) {
+    let view = app.view();
+    let [header, table, progress, status, help] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(5),
+        Constraint::Length(3),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let mut summary = format!(
+        "{} archives · {} · {} selected",
+        view.total_files_text(),
+        view.total_size_text(),
+        format_count(app.entries.selected_count() as u64)
+    );
+    if app.failed_only {
+        summary.push_str(" · failed only");
+    }
+    let simulation = view.simulation_text();
+    if !simulation.is_empty() {
+        summary.push_str(" · ");
+        summary.push_str(&simulation);
+    }
+    let summary_style = if view.simulation_over_limit() {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
+    frame.render_widget(
+        Paragraph::new(Line::styled(summary, summary_style))
+            .block(Block::bordered().title(format!(" Unpackrr · {} ", app.folder.display()))),
+        header,
+    );
+
+    let rows = view.rows.iter().map(|row| {
+        // Damaged archives in red, ones that merely can't be read in yellow,
+        // archives that must stay packed in magenta
+        let style = match row.health {
+            ArchiveHealth::Ok if !row.exclusion.is_empty() && !row.exclusion_overridden => {
+                Style::default().fg(Color::Magenta)
+            }
+            ArchiveHealth::Ok => Style::default(),
+            ArchiveHealth::InvalidFormat(_) => Style::default().fg(Color::Red),
+            ArchiveHealth::Unreadable(_) | ArchiveHealth::UnsupportedVersion(_) => {
+                Style::default().fg(Color::Yellow)
+            }
+        };
+        Row::new([
+            Cell::from(if row.marked { "[x]" } else { "[ ]" }),
+            Cell::from(row.file_name.clone()),
+            Cell::from(row.file_size.clone()),
+            Cell::from(row.num_files.clone()),
+            Cell::from(row.mod_name.clone()),
+            Cell::from(row.status.label()).style(status_style(row.status)),
+            Cell::from(row.extraction_time.clone()),
+        ])
+        .style(style)
+    });
+    let widths = [
+        Constraint::Length(3),
+        Constraint::Fill(3),
+        Constraint::Length(11),
+        Constraint::Length(8),
+        Constraint::Fill(2),
+        Constraint::Length(10),
+        Constraint::Length(8),
+    ];
+    let table_widget = Table::new(rows, widths)
+        .header(
+            Row::new(["", "File", "Size", "Files", "Mod", "Status", "Time"])
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+        )
+        .block(Block::bordered())
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state =
+        TableState::default().with_selected((!view.rows.is_empty()).then_some(app.cursor));
+    frame.render_stateful_widget(table_widget, table, &mut state);
+
+    frame.render_widget(
+        Gauge::default()
+            .block(Block::bordered())
+            .gauge_style(Style::default().fg(Color::Cyan))
+            .ratio(app.progress_ratio().clamp(0.0, 1.0))
+            .label(progress_label(app)),
+        progress,
+    );
+
+    frame.render_widget(Paragraph::new(app.status.as_str()), status);
+    frame.render_widget(
+        Paragraph::new(HELP).style(Style::default().fg(Color::DarkGray)),
+        help,
+    );
+}
+
+/// Text on the progress bar
+fn progress_label(app: &App) -> String {
+    match app.mode {
+        Mode::Idle => String::new(),
+        Mode::Scanning => app
+            .scan_progress
+            .map(|(current, total)| format!("{current}/{total} folders"))
+            .unwrap_or_default(),
+        Mode::Extracting => {
+            let text = app.progress_text();
+            let (done, total) = app.run_progress;
+            let mut label = format!("{done}/{total} archives");
+            let finish = if text.finish.is_empty() {
+                String::new()
+            } else {
+                format!("done around {}", text.finish)
+            };
+            for part in [text.speed, text.eta, finish] {
+                if !part.is_empty() {
+                    label.push_str(" · ");
+                    label.push_str(&part);
+                }
+            }
+            label
+        }
+    }
+}
+
+/// Color of the status column
+fn status_style(status: ExtractionStatus) -> Style {
+    let color = match status {
+        ExtractionStatus::Extracting => Color::Cyan,
+        ExtractionStatus::Done => Color::Green,
+        ExtractionStatus::Failed => Color::Red,
+        ExtractionStatus::Queued | ExtractionStatus::Skipped | ExtractionStatus::Idle => {
+            Color::DarkGray
+        }
+    };
+    Style::default().fg(color)
+}
@@ -4,27 +4,98 @@
 //! - Slint UI initialization and lifecycle
 //! - UI callbacks and event handling
 //! - State management between Rust backend and Slint frontend
+//! - Copying view models (see [`view_model`]) into the Slint types
+//! - Accepting only the requests the current operation allows (see [`phase`])
 //! - Slint + Tokio integration via async-compat
+//! - Driving the window headlessly in UI flow tests (see [`testing`], `ui-testing` feature)
 
 pub mod notifications;
-
-use crate::config::AppConfig;
-use crate::models::{FileEntry, FileEntryList, SortBy};
-use crate::operations::{ExtractionProgress, ScanProgress, extract_all, scan_for_ba2};
+pub mod phase;
+#[cfg(feature = "ui-testing")]
+pub mod testing;
+pub mod view_model;
+
+use crate::ba2::extractor::{BsarchVersion, STARFIELD_MIN_VERSION};
+use crate::ba2::dds::format_name;
+use crate::ba2::inspect::{ArchiveInspection, AudioInfo, NifInfo, inspect_archive};
+use crate::ba2::preview::{PREVIEW_EDGE, PreviewCache, can_preview, preview_texture};
+use crate::ba2::{BA2Archive, TextureEntry};
+use crate::ba2::locate::{BSARCH_EXE, ToolOrigin, locate_bsarch};
+use crate::config::rules::{format_rules, parse_rules, preview_rules, selected_paths};
+use crate::config::{
+    AppConfig, AppearanceConfig, SavedConfig, SavedDirectory, ScheduleOrder, SizeUnits, TexturePolicy,
+};
+use crate::config::abort::AbortThreshold;
+use crate::config::ignore::{IgnoreMatcher, IgnorePattern, PatternKind, preview_ignored};
+use crate::config::overrides::{EffectiveConfig, RunOverrides};
+use crate::config::postfix::{PostfixMatcher, preview_postfixes};
+use crate::config::profile::Game;
+use crate::config::secrets::{self, KeyringStore, Secret, SecretStore};
+use crate::config::tools::{ExternalTool, format_tools, open_with_tools, parse_tools};
+use crate::config::watch::{ConfigChange, ConfigWatcher, POLL_INTERVAL};
+use crate::config::backups::ConfigBackup;
+use crate::config::repair::{self, Repair};
+use crate::crash::{self, CrashReport};
+use crate::error::{BA2Error, Error};
+use crate::ipc;
+use crate::safe_mode::{self, SafeModeReason};
+use crate::models::collation::set_name_collation_from_appearance;
+use crate::models::stats::ArchiveStats;
+use crate::models::{ArchiveHealth, ExtractionStatus, FileEntry, FileEntryList, SortBy};
+use crate::operations::audit::{AuditAction, AuditEntry, AuditLog, record_events};
+use crate::operations::cleanup::{Leftover, clean_leftovers, find_leftovers};
+use crate::operations::downloads::{
+    DownloadsPreview, PendingArchive, locate_seven_zip, preview_downloads,
+};
+use crate::operations::events::log_events;
+use crate::operations::loose_stats::LooseStatsCache;
+use crate::operations::notes::{ModNotes, Note};
+use crate::operations::path::display_name;
+use crate::operations::quarantine::{default_quarantine_root, revalidate};
+use crate::operations::reorganize::{self, ExtractedOutput, ReorganizePlan, Strategy};
+use crate::platform::defender::{AntimalwareSampler, ExclusionAdvice};
+use crate::platform::drive::{StorageMedium, drive_kind, storage_medium};
+use crate::platform::get_default_ba2_handler;
+use crate::platform::wine::{host_path, set_wine_prefix_from_config};
+use crate::ui::phase::{AppPhase, PhaseEvent};
+use crate::ui::view_model::{
+    FileRow, MainViewModel, ProgressText, REPORT_MAX_LISTED_PATHS, TableFilter, audit_text,
+    click_rows, config_backup_labels, leftover_removal_message, preflight_message,
+    undo_description, undo_message,
+};
+use crate::platform::elevation::{self, FolderAccess, find_unwritable};
+use crate::platform::priority;
+use crate::platform::session::{SHUTDOWN_GRACE, cancel_shutdown, notify_desktop, schedule_shutdown};
+use crate::operations::{
+    AppEvent, ArchiveDiff, BackupEvent, BatchAbort, CheckStatus, Diagnosis, SelfTestPaths,
+    SelfTestReport, diagnose, self_test, CancellationToken, ChangeKind, ExportFormat, ExtractionProgress, LinkMode, LoadOrder, MetricsHistory,
+    MetricsRecorder, ModlistIndex, NumberFormat, Operation, OperationJournal, QuarantineManager, Recommendation, RunMetrics,
+    EventBus, ExtractorBackend, ScanProgress, SizeSpec, ThroughputTracker, UndoReport, VerifyReport,
+    backup_location, backup_root, simulate, compare_archives, compare_folder, detect_bsarch_version, export_entries, extract_all, extract_all_with, find_mod_page,
+    format_count, format_duration, format_elapsed, format_size, list_archives, load_export_document, manifest_path, match_entries,
+    PreflightReport, number_format, preflight, recommend, revert_partial_move, scan_with_ignored, set_number_format,
+    status_file_path, undo_extraction, validate_ba2_tool, verify_manifest, write_status,
+};
 use anyhow::Result;
-use humansize::{BINARY, format_size};
 use parking_lot::Mutex;
-use slint::{ComponentHandle, Model, ModelRc, SharedString, VecModel};
-use std::path::PathBuf;
+use slint::{
+    ComponentHandle, Model, ModelRc, Rgba8Pixel, SharedPixelBuffer, SharedString, VecModel,
+};
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::sync::{Arc, LazyLock, mpsc};
+use std::time::Duration;
 
 // Include the generated Slint code
 slint::include_modules!();
 
 // Re-export notification types for convenience
-pub use notifications::{DialogConfig, ToastData, show_dialog, show_toast};
+pub use notifications::{
+    DialogConfig, ToastData, View, show_choice, show_confirmation, show_dialog, show_toast,
+    show_toast_with_action,
+};
 
 /// Initialize and run the UI
 ///
@@ -45,8 +116,17 @@ pub fn run() -> Result<()> {
     // Create the main window
     let main_window = MainWindow::new()?;
 
-    // Set up callbacks and state (to be implemented in Phase 1.8)
-    setup_callbacks(&main_window);
+    // Set up callbacks and state
+    let (state, safe_mode) = load_state(safe_mode::begin_startup());
+    let state = Arc::new(Mutex::new(state));
+    setup_callbacks(&main_window, &state);
+    match safe_mode {
+        Some(reason) => enter_safe_mode(&main_window, &state, reason),
+        None => restore_saved_directory(&main_window, &state),
+    }
+
+    // A startup that lasts this long didn't crash
+    slint::Timer::single_shot(safe_mode::STARTUP_GRACE, safe_mode::startup_finished);
 
     // Run the Slint event loop
     main_window.run()?;
@@ -59,22 +139,105 @@ pub fn run() -> Result<()> {
 struct AppState {
     config: AppConfig,
     file_entries: FileEntryList,
+    /// Archives of the last scan hidden by the ignore list
+    ignored_entries: FileEntryList,
     sort_column: i32,
     sort_ascending: bool,
+    /// Version of the `BSArch` executable in use, if it could be detected
+    bsarch_version: Option<BsarchVersion>,
+    /// Local metrics of recent extraction runs
+    metrics: MetricsHistory,
+    /// Loaded Wabbajack modlist, used to tag the archives it installs
+    modlist: Option<ModlistIndex>,
+    /// Plugin load order, read from plugins.txt at every scan
+    load_order: Option<LoadOrder>,
+    /// Application-wide event bus; each scan and extraction publishes on a child
+    events: EventBus,
+    /// Every archive of the scanned folder, hidden ones included, for previewing ignore patterns
+    folder_archives: Vec<PathBuf>,
+    /// Settings overridden for the next extraction only, from the Run options flyout
+    run_overrides: RunOverrides,
+    /// Notes and tags of mods and archives
+    notes: ModNotes,
+    /// Mod folder and file name of the archive in the note editor
+    note_target: Option<(String, String)>,
+    /// Loose files counted in mod folders, for preflight warnings and the dashboard
+    loose_stats: LooseStatsCache,
+    /// Extractor used instead of the configured tool (headless UI tests)
+    backend: Option<Arc<dyn ExtractorBackend>>,
+    /// What the app is doing, deciding which requests it accepts
+    phase: AppPhase,
+    /// Restore points of the configuration listed in Settings, newest first
+    config_backups: Vec<ConfigBackup>,
 }
 
 impl AppState {
-    fn new() -> Result<Self> {
-        let config = AppConfig::load()?;
-        Ok(Self {
+    fn with_config(config: AppConfig) -> Self {
+        Self {
             config,
             file_entries: FileEntryList::new(),
+            ignored_entries: FileEntryList::new(),
             sort_column: -1,
             sort_ascending: true,
-        })
+            bsarch_version: None,
+            metrics: MetricsHistory::load(),
+            modlist: None,
+            load_order: None,
+            events: EventBus::new(),
+            folder_archives: Vec::new(),
+            run_overrides: RunOverrides::default(),
+            notes: ModNotes::load(),
+            note_target: None,
+            loose_stats: LooseStatsCache::new(),
+            backend: None,
+            phase: AppPhase::Idle,
+            config_backups: Vec::new(),
+        }
+    }
+
+    /// Store a loaded modlist, returning how many scanned archives it installs
+    fn set_modlist(&mut self, modlist: ModlistIndex) -> usize {
+        let tagged = self.file_entries.tag_modlist(Some(&modlist));
+        self.modlist = Some(modlist);
+        tagged
+    }
+
+    /// Store the plugin load order, returning how many scanned archives it matches
+    fn set_load_order(&mut self, load_order: Option<LoadOrder>) -> usize {
+        let matched = self.file_entries.apply_load_order(load_order.as_ref());
+        self.load_order = load_order;
+        matched
+    }
+
+    /// Store a note and show it on the archives it belongs to
+    fn set_note(&mut self, key: String, note: Note) {
+        self.notes.set(key, note);
+        self.file_entries.apply_notes(&self.notes);
+        self.ignored_entries.apply_notes(&self.notes);
     }
 }
 
+/// Load the settings and everything else the app remembers
+///
+/// `unfinished` is the number of startups in a row that didn't finish. When
+/// the settings can't be loaded or too many startups crashed, the state uses
+/// the default settings and saving them is held, returning why.
+fn load_state(unfinished: u32) -> (AppState, Option<SafeModeReason>) {
+    let loaded = AppConfig::load();
+    let config_error = loaded.as_ref().err().map(|e| {
+        tracing::error!("Failed to load configuration: {}", e);
+        e.to_string()
+    });
+    safe_mode::reason(config_error, unfinished).map_or_else(
+        || (AppState::with_config(loaded.unwrap_or_default()), None),
+        |reason| {
+            tracing::warn!("Starting in safe mode: {:?}", reason);
+            crate::config::hold_saves(true);
+            (AppState::with_config(AppConfig::default()), Some(reason))
+        },
+    )
+}
+
 /// Control signals for extraction (Phase 2.3)
 #[derive(Debug, Clone)]
 enum ExtractionControl {
@@ -86,62 +249,563 @@ enum ExtractionControl {
 /// Extraction control state (Phase 2.3)
 struct ExtractionControlState {
     control_tx: Option<tokio::sync::mpsc::UnboundedSender<ExtractionControl>>,
+    /// Aborts retries and drive waits of the running extraction
+    cancel_token: Option<CancellationToken>,
+    /// What to do once the running extraction finishes
+    after_run: AfterRun,
+}
+
+/// Actions after an extraction, chosen when it starts or from the progress view
+#[derive(Debug, Clone, Copy, Default)]
+struct AfterRun {
+    /// Show a desktop notification
+    notify: bool,
+    /// Shut the computer down (this run only)
+    shutdown: bool,
 }
 
 /// Set up UI callbacks
 ///
 /// This function wires up all the callbacks between the UI and backend logic.
 /// It handles folder selection, scanning, extraction, and sorting.
-fn setup_callbacks(main_window: &MainWindow) {
-    // Load application state
-    let state = match AppState::new() {
-        Ok(s) => Arc::new(Mutex::new(s)),
-        Err(e) => {
-            tracing::error!("Failed to load configuration: {}", e);
-            // Use default config if loading fails
-            Arc::new(Mutex::new(AppState {
-                config: AppConfig::default(),
-                file_entries: FileEntryList::new(),
-                sort_column: -1,
-                sort_ascending: true,
-            }))
-        }
-    };
-
+fn setup_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
     // Phase 2.3: Create extraction control state
-    let extraction_control = Arc::new(Mutex::new(ExtractionControlState { control_tx: None }));
-
-    // Initialize theme from config
-    {
-        let config_theme = state.lock().config.appearance.theme_mode.clone();
-        let theme_mode = match config_theme.to_lowercase().as_str() {
-            "dark" => 1,
-            "light" => 0,
-            _ => 2, // System
-        };
-        main_window.set_theme_mode(theme_mode);
+    let extraction_control = Arc::new(Mutex::new(ExtractionControlState {
+        control_tx: None,
+        cancel_token: None,
+        after_run: AfterRun::default(),
+    }));
+
+    // Log the events of all operations
+    crate::get_runtime().spawn(log_events(state.lock().events.monitor()));
+    // Audit the changes of all operations, without missing any
+    if let Some(audit) = AuditLog::open_default() {
+        crate::get_runtime().spawn(record_events(audit, state.lock().events.subscribe()));
     }
 
-    setup_browse_folder_callback(main_window, Arc::clone(&state));
-    setup_scan_callback(main_window, Arc::clone(&state));
+    // Initialize theme, accessibility and the other settings from config
+    let games: Vec<SharedString> =
+        Game::ALL.iter().map(|game| SharedString::from(game.to_string())).collect();
+    main_window.set_games(ModelRc::new(VecModel::from(games)));
+    apply_settings(main_window, state);
+    update_performance(main_window, state);
+
+    setup_browse_folder_callback(main_window, Arc::clone(state));
+    setup_scan_callback(main_window, Arc::clone(state));
     setup_extraction_callback(
         main_window,
-        Arc::clone(&state),
+        Arc::clone(state),
         Arc::clone(&extraction_control),
     );
-    setup_sort_callback(main_window, Arc::clone(&state));
-    setup_threshold_callbacks(main_window, &state); // Phase 2.3
-    setup_file_actions_callback(main_window, &state); // Phase 2.3
-    setup_open_folder_callback(main_window, Arc::clone(&state)); // Phase 2.3
-    setup_extraction_control_callbacks(main_window, &extraction_control); // Phase 2.3
-    setup_settings_callbacks(main_window, &state); // Phase 2.2
+    setup_sort_callback(main_window, Arc::clone(state));
+    setup_failed_filter_callback(main_window, Arc::clone(state));
+    setup_export_callback(main_window, Arc::clone(state));
+    setup_import_callbacks(main_window, state);
+    setup_recommendation_callback(main_window, state);
+    setup_downloads_callback(main_window, state);
+    setup_compare_callback(main_window);
+    setup_modlist_callbacks(main_window, state);
+    setup_load_order_callbacks(main_window, state);
+    setup_game_callback(main_window, state);
+    setup_run_options_callbacks(main_window, state);
+    setup_note_callbacks(main_window, state);
+    setup_threshold_callbacks(main_window, state); // Phase 2.3
+    setup_file_actions_callback(main_window, state); // Phase 2.3
+    setup_bulk_actions_callback(main_window, state);
+    setup_open_folder_callback(main_window, Arc::clone(state)); // Phase 2.3
+    setup_undo_extraction_callback(main_window, state);
+    setup_undo_operation_callback(main_window, state);
+    setup_reorganize_callback(main_window, state);
+    setup_metrics_callbacks(main_window, state);
+    setup_extraction_control_callbacks(main_window, state, &extraction_control); // Phase 2.3
+    setup_settings_callbacks(main_window, state); // Phase 2.2
+    setup_external_tool_callbacks(main_window, state);
+    setup_nexus_key_callbacks(main_window);
     setup_update_checker_callback(main_window);
-    setup_platform_integration(main_window, &state); // Phase 2.9
+    setup_diagnostics_callback(main_window, state);
+    setup_audit_log_callback(main_window);
+    setup_cleanup_callback(main_window, state);
+    setup_restore_backup_callback(main_window, state);
+    setup_ipc_server(main_window, state);
+    setup_config_watch(main_window, state);
     setup_log_viewer_callbacks(main_window); // Phase 3.3
+    setup_texture_preview_callbacks(main_window);
+    notifications::setup_dialog_callbacks(main_window);
+    notifications::setup_notification_callbacks(main_window);
 
     tracing::info!("UI callbacks initialized");
 }
 
+/// Show the settings of the configuration in the window
+///
+/// Used at startup and when the configuration file is reloaded.
+fn apply_settings(ui: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let config = state.lock().config.clone();
+    let appearance = &config.appearance;
+    let theme_mode = match appearance.theme_mode.to_lowercase().as_str() {
+        "dark" => 1,
+        "light" => 0,
+        _ => 2, // System
+    };
+    ui.set_theme_mode(theme_mode);
+    ui.set_settings_language(match appearance.language.as_str() {
+        "en" => 1,
+        "zh-CN" => 2,
+        "zh-TW" => 3,
+        _ => 0, // Auto
+    });
+    ui.set_settings_size_units(match appearance.size_units {
+        SizeUnits::Binary => 0,
+        SizeUnits::Decimal => 1,
+    });
+    set_number_format(NumberFormat::from_appearance(appearance));
+    set_name_collation_from_appearance(appearance);
+    set_wine_prefix_from_config(&config);
+    ui.set_high_contrast(appearance.high_contrast);
+    ui.set_font_scale(appearance.font_scale);
+    ui.set_settings_font_scale_index(font_scale_index(appearance.font_scale));
+
+    let extraction = &config.extraction;
+    ui.set_settings_postfixes(SharedString::from(extraction.postfixes.join(", ")));
+    ui.set_settings_ignore_bad(extraction.ignore_bad_files);
+    ui.set_settings_auto_backup(extraction.auto_backup);
+    ui.set_settings_confirm_extraction(extraction.confirm_extraction);
+    ui.set_settings_write_manifest(extraction.write_manifest);
+    ui.set_settings_use_temp(extraction.use_temp);
+    ui.set_settings_serialize_per_mod(extraction.serialize_per_mod);
+    ui.set_settings_allow_unknown_versions(extraction.allow_unknown_versions);
+    ui.set_settings_data_folder_mode(extraction.data_folder_mode);
+    ui.set_settings_link_mode(match extraction.link_mode {
+        LinkMode::Off => 0,
+        LinkMode::Hardlink => 1,
+        LinkMode::Symlink => 2,
+    });
+    ui.set_settings_texture_policy(match extraction.texture_policy {
+        TexturePolicy::Skip => 0,
+        TexturePolicy::Extract => 1,
+        TexturePolicy::Convert => 2,
+    });
+    ui.set_settings_schedule(match extraction.schedule {
+        ScheduleOrder::LargestFirst => 0,
+        ScheduleOrder::SmallestFirst => 1,
+        ScheduleOrder::AsListed => 2,
+    });
+    ui.set_settings_selection_rules(SharedString::from(format_rules(&extraction.selection_rules)));
+    ui.set_settings_abort_after_failures(SharedString::from(
+        extraction.abort_after_failures.to_string(),
+    ));
+    ui.set_settings_loose_file_limit(SharedString::from(
+        extraction.loose_file_limit.to_string(),
+    ));
+    ui.set_game_index(
+        Game::ALL
+            .iter()
+            .position(|&g| g == config.game)
+            .map_or(0, |i| i32::try_from(i).unwrap_or(0)),
+    );
+    update_ignore_patterns(ui, state);
+
+    let advanced = &config.advanced;
+    ui.set_settings_storage_medium(match advanced.performance.storage_medium {
+        None | Some(StorageMedium::Unknown) => 0,
+        Some(StorageMedium::Hdd) => 1,
+        Some(StorageMedium::Ssd) => 2,
+        Some(StorageMedium::Nvme) => 3,
+    });
+    ui.set_settings_show_debug(advanced.show_debug);
+    ui.set_settings_analysis_only(advanced.analysis_only);
+    ui.set_settings_collect_metrics(advanced.diagnostics.collect_metrics);
+    ui.set_settings_defender_benchmark(advanced.diagnostics.defender_benchmark);
+    ui.set_settings_write_status_file(advanced.diagnostics.write_status_file);
+    ui.set_settings_notify_when_done(advanced.integrations.notify_when_done);
+    ui.set_settings_low_priority(advanced.performance.low_priority);
+    ui.set_settings_discord_presence(advanced.integrations.discord_presence);
+    ui.set_settings_discord_available(cfg!(feature = "discord"));
+    ui.set_settings_extraction_path(SharedString::from(advanced.extraction_path.as_str()));
+    ui.set_settings_backup_path(SharedString::from(advanced.backup_path.as_str()));
+    ui.set_settings_open_with(SharedString::from(format_tools(&advanced.open_with)));
+    show_open_tools(ui, &open_tools(&config));
+    ui.set_settings_check_updates(config.update.check_at_startup);
+    ui.set_settings_auto_scan_on_start(advanced.integrations.auto_scan_on_start);
+    ui.set_settings_status_file_path(SharedString::from(
+        status_file_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default(),
+    ));
+    show_config_backups(ui, state);
+}
+
+/// List the restore points of the configuration in Settings
+fn show_config_backups(ui: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let backups = AppConfig::backups().unwrap_or_default();
+    let labels: Vec<SharedString> = config_backup_labels(&backups)
+        .into_iter()
+        .map(SharedString::from)
+        .collect();
+    ui.set_settings_config_backups(ModelRc::new(VecModel::from(labels)));
+    ui.set_settings_config_backup_index(0);
+    state.lock().config_backups = backups;
+}
+
+/// List the restore points again once a setting was saved
+fn refresh_config_backups(weak: slint::Weak<MainWindow>, state: Arc<Mutex<AppState>>) {
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(ui) = weak.upgrade() {
+            show_config_backups(&ui, &state);
+        }
+    });
+}
+
+/// Set up "Restore", which brings back the settings of a restore point
+fn setup_restore_backup_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+    main_window.on_settings_restore_config_backup(move |index| {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let backup = usize::try_from(index)
+            .ok()
+            .and_then(|index| state.lock().config_backups.get(index).cloned());
+        let Some(backup) = backup else {
+            return;
+        };
+        let config = match backup.load() {
+            Ok(config) => config,
+            Err(e) => {
+                show_toast(
+                    &ui,
+                    &ToastData::error(format!("Failed to read the restore point: {e}")),
+                );
+                return;
+            }
+        };
+        let label = config_backup_labels(std::slice::from_ref(&backup)).remove(0);
+
+        let state = Arc::clone(&state);
+        show_confirmation(
+            &ui,
+            DialogConfig::confirm(
+                "Restore previous settings?",
+                format!(
+                    "Every setting goes back to how it was saved before {label}. The \
+                     current settings become a restore point, so this can be undone."
+                ),
+            )
+            .with_primary_button("Restore")
+            .with_secondary_button("Cancel"),
+            move |ui| {
+                // Restoring settings also ends safe mode
+                let held = !ui.get_safe_mode_message().is_empty();
+                crate::config::hold_saves(false);
+                if let Err(e) = config.save_keeping_backup() {
+                    crate::config::hold_saves(held);
+                    show_toast(
+                        ui,
+                        &ToastData::error(format!("Failed to restore the settings: {e}")),
+                    );
+                    return;
+                }
+                reload_config(ui, &state, config);
+                show_toast(
+                    ui,
+                    &ToastData::success(format!("Settings restored from {label}")),
+                );
+            },
+        );
+    });
+}
+
+/// Programs the row menu opens archives with: the configured ones, then the `.ba2` handler
+fn open_tools(config: &AppConfig) -> Vec<ExternalTool> {
+    open_with_tools(&config.advanced.open_with, get_default_ba2_handler())
+}
+
+/// Show the programs of the row menu's Open submenu
+fn show_open_tools(ui: &MainWindow, tools: &[ExternalTool]) {
+    let names: Vec<SharedString> = tools
+        .iter()
+        .map(|tool| SharedString::from(tool.name.as_str()))
+        .collect();
+    ui.set_open_tools(ModelRc::new(VecModel::from(names)));
+}
+
+/// Reload the settings when the configuration file is edited outside the app
+fn setup_config_watch(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let watcher = ConfigWatcher::new(&state.lock().config);
+    let mut watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            tracing::warn!("Not watching the configuration file: {}", e);
+            return;
+        }
+    };
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            // Polled under the lock, so a setting changed and saved meanwhile
+            // isn't taken for an unsaved one
+            let Some(change) = watcher.poll(&state.lock().config) else {
+                continue;
+            };
+            let weak = weak.clone();
+            let state = Arc::clone(&state);
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = weak.upgrade() {
+                    apply_config_change(&ui, &state, change);
+                }
+            });
+        }
+    });
+}
+
+/// Apply a change of the configuration file, asking first if the app has
+/// settings that were not saved
+fn apply_config_change(ui: &MainWindow, state: &Arc<Mutex<AppState>>, change: ConfigChange) {
+    match change {
+        ConfigChange::Reloaded(config) => {
+            reload_config(ui, state, config);
+            show_toast(ui, &ToastData::info("Settings reloaded from config.json"));
+        }
+        ConfigChange::Conflict(config) => {
+            let state = Arc::clone(state);
+            show_confirmation(
+                ui,
+                DialogConfig::confirm(
+                    "Settings changed on disk",
+                    "config.json was edited outside Unpackrr while some settings here \
+                     were not saved yet.\n\nReload the file to use its settings, or keep \
+                     yours; they replace the file the next time a setting is saved.",
+                )
+                .with_primary_button("Reload From File")
+                .with_secondary_button("Keep Mine"),
+                move |ui| {
+                    reload_config(ui, &state, config);
+                    show_toast(ui, &ToastData::info("Settings reloaded from config.json"));
+                },
+            );
+        }
+        ConfigChange::Invalid(error) => show_toast(
+            ui,
+            &ToastData::warning(format!(
+                "config.json was changed but can't be used, keeping the current settings: {error}"
+            )),
+        ),
+    }
+}
+
+/// Replace the settings with a reloaded configuration
+fn reload_config(ui: &MainWindow, state: &Arc<Mutex<AppState>>, config: AppConfig) {
+    state.lock().config = config;
+    apply_settings(ui, state);
+    leave_safe_mode(ui);
+}
+
+/// Show the safe mode banner and handle its buttons
+fn enter_safe_mode(ui: &MainWindow, state: &Arc<Mutex<AppState>>, reason: SafeModeReason) {
+    ui.set_safe_mode_message(reason.explanation().into());
+    ui.set_safe_mode_repair_label(
+        match reason {
+            SafeModeReason::ConfigUnreadable(_) => "Repair Config",
+            SafeModeReason::StartupCrashes(_) => "Reset Settings",
+        }
+        .into(),
+    );
+
+    let weak = ui.as_weak();
+    let state = Arc::clone(state);
+    ui.on_safe_mode_action(move |action| {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let path = match AppConfig::config_file_path() {
+            Ok(path) => path,
+            Err(e) => {
+                show_toast(
+                    &ui,
+                    &ToastData::error(format!("No configuration file: {e}")),
+                );
+                return;
+            }
+        };
+        match action.as_str() {
+            "view" => {
+                if let Err(e) = open::that(&path) {
+                    show_toast(
+                        &ui,
+                        &ToastData::error(format!("Failed to open config.json: {e}")),
+                    );
+                }
+            }
+            "backup" => match repair::back_up(&path) {
+                Ok(backup) => show_toast(
+                    &ui,
+                    &ToastData::success(format!("Backed up config.json to {}", backup.display())),
+                ),
+                Err(e) => show_toast(
+                    &ui,
+                    &ToastData::error(format!("Failed to back up config.json: {e}")),
+                ),
+            },
+            "repair" => {
+                let state = Arc::clone(&state);
+                let reason = reason.clone();
+                show_confirmation(&ui, repair_dialog(&reason), move |ui| {
+                    repair_config(ui, &state, &reason, &path);
+                });
+            }
+            _ => {}
+        }
+    });
+}
+
+/// Hide the safe mode banner and save settings again
+fn leave_safe_mode(ui: &MainWindow) {
+    crate::config::hold_saves(false);
+    ui.set_safe_mode_message(SharedString::new());
+}
+
+/// Confirmation of the safe mode repair or reset
+fn repair_dialog(reason: &SafeModeReason) -> DialogConfig {
+    match reason {
+        SafeModeReason::ConfigUnreadable(_) => DialogConfig::confirm(
+            "Repair config.json?",
+            "Settings that can't be read are reset to their defaults; all the others are \
+             kept. The broken file is backed up next to it first.",
+        )
+        .with_primary_button("Repair"),
+        SafeModeReason::StartupCrashes(_) => DialogConfig::confirm(
+            "Reset all settings?",
+            "config.json is replaced by the default settings. The current file is backed \
+             up next to it first.",
+        )
+        .with_primary_button("Reset"),
+    }
+    .with_secondary_button("Cancel")
+}
+
+/// Back up the configuration file, then save its repaired or default settings
+fn repair_config(
+    ui: &MainWindow,
+    state: &Arc<Mutex<AppState>>,
+    reason: &SafeModeReason,
+    path: &Path,
+) {
+    let backup = if path.exists() {
+        match repair::back_up(path) {
+            Ok(backup) => Some(backup),
+            Err(e) => {
+                show_toast(
+                    ui,
+                    &ToastData::error(format!(
+                        "Failed to back up config.json, leaving it alone: {e}"
+                    )),
+                );
+                return;
+            }
+        }
+    } else {
+        None
+    };
+
+    let Repair { config, reset } = match reason {
+        SafeModeReason::ConfigUnreadable(_) => {
+            repair::repair(&std::fs::read_to_string(path).unwrap_or_default())
+        }
+        SafeModeReason::StartupCrashes(_) => Repair {
+            config: AppConfig::default(),
+            reset: vec!["all settings".to_string()],
+        },
+    };
+
+    crate::config::hold_saves(false);
+    if let Err(e) = config.save() {
+        crate::config::hold_saves(true);
+        show_toast(
+            ui,
+            &ToastData::error(format!("Failed to save the settings: {e}")),
+        );
+        return;
+    }
+    reload_config(ui, state, config);
+
+    let mut message = if reset.is_empty() {
+        "Every setting was kept.".to_string()
+    } else {
+        format!("Reset to their defaults:\n{}", reset.join("\n"))
+    };
+    if let Some(backup) = backup {
+        let _ = write!(message, "\n\nThe previous file is at {}", backup.display());
+    }
+    show_dialog(ui, DialogConfig::success("Settings repaired", message));
+}
+
+/// Put the last used folder back in the folder field, scanning it if enabled
+///
+/// A folder that is gone, like one on an unplugged drive, can be looked for
+/// again or replaced.
+fn restore_saved_directory(ui: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let (saved, auto_scan) = {
+        let config = &state.lock().config;
+        (config.saved.saved_directory(), config.advanced.integrations.auto_scan_on_start)
+    };
+    match saved {
+        SavedDirectory::None => {}
+        SavedDirectory::Available(path) => {
+            ui.set_selected_folder(SharedString::from(path.to_string_lossy().as_ref()));
+            if auto_scan {
+                tracing::info!("Scanning the last used folder: {}", path.display());
+                ui.invoke_start_scan();
+            }
+        }
+        SavedDirectory::DriveMissing(path) => {
+            tracing::warn!("The drive of the last used folder is gone: {}", path.display());
+            let state = Arc::clone(state);
+            show_choice(
+                ui,
+                DialogConfig::warning(
+                    "Drive not connected",
+                    format!(
+                        "The last used folder, {}, is on a drive that isn't connected.\n\n\
+                         Connect the drive and try again, or choose another folder.",
+                        path.display()
+                    ),
+                )
+                .with_primary_button("Try Again")
+                .with_secondary_button("Choose Folder"),
+                move |ui| restore_saved_directory(ui, &state),
+                MainWindow::invoke_browse_folder,
+            );
+        }
+        SavedDirectory::Missing(path) => {
+            tracing::warn!("The last used folder is gone: {}", path.display());
+            let state = Arc::clone(state);
+            show_choice(
+                ui,
+                DialogConfig::warning(
+                    "Last folder not found",
+                    format!(
+                        "The last used folder, {}, was moved or deleted.\n\n\
+                         Choose another folder, or forget this one.",
+                        path.display()
+                    ),
+                )
+                .with_primary_button("Choose Folder")
+                .with_secondary_button("Forget It"),
+                MainWindow::invoke_browse_folder,
+                move |_| {
+                    update_saved_config(&state, |saved| {
+                        !std::mem::take(&mut saved.directory).is_empty()
+                    });
+                },
+            );
+        }
+    }
+}
+
 /// Set up browse folder callback
 fn setup_browse_folder_callback(main_window: &MainWindow, state: Arc<Mutex<AppState>>) {
     let weak = main_window.as_weak();
@@ -201,51 +865,108 @@ fn setup_scan_callback(main_window: &MainWindow, state: Arc<Mutex<AppState>>) {
             tracing::warn!("Scan requested but no folder selected");
             return;
         }
+        if let Some(ui) = weak.upgrade()
+            && !phase_accepts(&ui, &state, PhaseEvent::StartScan)
+        {
+            return;
+        }
+
+        // The scan lists archives with the run options applied
+        let result = effective_config(&state.lock());
+        let config = match result {
+            Ok(config) => config,
+            Err(e) => {
+                if let Some(ui) = weak.upgrade() {
+                    show_run_options_error(&ui, &e);
+                }
+                return;
+            }
+        };
 
         tracing::info!("Starting BA2 scan in: {}", folder);
 
         // Set scanning state
         if let Some(ui) = weak.upgrade() {
-            ui.set_scanning(true);
+            advance_phase(&ui, &state, PhaseEvent::StartScan);
             ui.set_status_text(SharedString::from("Scanning for BA2 files..."));
+
+            let drive = drive_kind(Path::new(&folder));
+            if drive.is_slow() {
+                show_toast(
+                    &ui,
+                    &ToastData::warning(format!(
+                        "This folder is on {}. Scanning and extraction will be slower and run fewer jobs at once.",
+                        drive.description()
+                    )),
+                );
+            }
         }
 
         // Run scan in background task using global runtime
-        crate::get_runtime().spawn(async move {
+        spawn_guarded(&weak, "Scan", fail_phase(Arc::clone(&state)), async move {
             let path = PathBuf::from(&folder);
-            let (tx, mut rx) = mpsc::channel(100);
 
-            // Get config
-            let config = {
-                let app_state = state_clone.lock();
-                app_state.config.clone()
-            };
+            // Get a bus for this scan's progress
+            let events = state_clone.lock().events.child();
+            let mut rx = events.subscribe();
+            let row_config = config.clone();
 
             // Spawn scan task
-            // Note: scan_for_ba2 uses rayon internally which blocks, so we use the global runtime
-            // which is multi-threaded. Ideally this would be spawn_blocking if scan_for_ba2 was sync.
+            // Note: the scan uses rayon internally which blocks, so we use the global runtime
+            // which is multi-threaded. Ideally this would be spawn_blocking if the scan was sync.
+            // Ignored archives are kept, to be counted and shown on demand.
             let scan_task =
-                tokio::spawn(async move { scan_for_ba2(&path, &config, Some(tx)).await });
+                tokio::spawn(async move { scan_with_ignored(&path, &config, Some(events)).await });
 
-            // Process progress updates
-            while let Some(progress) = rx.recv().await {
+            // Process progress updates, listing archives as they are found
+            let mut found = 0;
+            while let Some(event) = rx.recv().await {
+                let AppEvent::Scan(progress) = event else {
+                    continue;
+                };
                 let weak = weak_clone.clone();
                 let status = match progress {
                     ScanProgress::Started { total_dirs } => {
+                        let weak = weak.clone();
+                        let _ = slint::invoke_from_event_loop(move || {
+                            if let Some(ui) = weak.upgrade() {
+                                clear_file_table(&ui);
+                            }
+                        });
                         format!("Starting scan of {total_dirs} directories...")
                     }
+                    ScanProgress::Found { archives } => {
+                        // Ignored archives are only shown on demand, once the scan is done
+                        let rows: Vec<FileRow> = archives
+                            .into_iter()
+                            .filter(|info| !info.ignored)
+                            .map(|info| FileRow::from_entry(&FileEntry::from(info), &row_config))
+                            .collect();
+                        found += rows.len();
+                        let weak = weak.clone();
+                        let _ = slint::invoke_from_event_loop(move || {
+                            if let Some(ui) = weak.upgrade() {
+                                append_file_rows(&ui, &rows);
+                            }
+                        });
+                        format!("Scanning... {} files found so far", format_count(found as u64))
+                    }
                     ScanProgress::ScanningFolder {
                         folder,
                         current,
                         total,
                     } => {
-                        format!("Scanning {folder} ({current}/{total})")
+                        format!(
+                            "Scanning {folder} ({}/{})",
+                            format_count(current as u64),
+                            format_count(total as u64)
+                        )
                     }
                     ScanProgress::FoundBA2 { file_name } => {
                         format!("Found: {file_name}")
                     }
                     ScanProgress::Complete { total_files } => {
-                        format!("Scan complete: {total_files} files found")
+                        format!("Scan complete: {} files found", format_count(total_files as u64))
                     }
                 };
 
@@ -259,6 +980,8 @@ fn setup_scan_callback(main_window: &MainWindow, state: Arc<Mutex<AppState>>) {
             // Get scan results
             match scan_task.await {
                 Ok(Ok(files)) => {
+                    let (ignored, files): (Vec<_>, Vec<_>) =
+                        files.into_iter().partition(|f| f.ignored);
                     let total_files = files.len();
                     let total_size = files.iter().map(|f| f.file_size).sum::<u64>();
 
@@ -271,39 +994,101 @@ fn setup_scan_callback(main_window: &MainWindow, state: Arc<Mutex<AppState>>) {
                     // Convert to FileEntry and store in state
                     let entries: Vec<FileEntry> = files.into_iter().map(FileEntry::from).collect();
 
-                    let corrupted_count = entries.iter().filter(|e| e.is_corrupted()).count();
+                    let corrupted_count = entries.iter().filter(|e| e.is_bad()).count();
                     if corrupted_count > 0 {
-                        tracing::warn!("Found {} corrupted BA2 files", corrupted_count);
+                        tracing::warn!("Found {} unreadable or corrupted BA2 files", corrupted_count);
                     }
 
-                    // Convert to FileRowData for UI
-                    let row_data: Vec<FileRowData> = entries
-                        .iter()
-                        .map(|e| FileRowData {
-                            file_name: SharedString::from(&e.file_name),
-                            file_size: SharedString::from(e.size_display()),
-                            num_files: SharedString::from(e.file_count_display()),
-                            mod_name: SharedString::from(e.mod_display()),
-                            is_bad: e.is_corrupted(),
-                        })
-                        .collect();
+                    // Re-read the load order, which may have changed in the mod manager
+                    let load_order = read_load_order(&state_clone);
+                    let folder_archives = list_archives(Path::new(&folder))
+                        .inspect_err(|e| tracing::debug!("Could not list archives: {}", e))
+                        .unwrap_or_default();
 
-                    // Update state
-                    {
-                        let mut app_state = state_clone.lock();
+                    // Update state, tagging the archives of the loaded modlist
+                    let (modlist_count, selected, view) = {
+                        let mut guard = state_clone.lock();
+                        let app_state = &mut *guard;
                         app_state.file_entries = FileEntryList::from_vec(entries);
-                    }
+                        app_state.ignored_entries = FileEntryList::from_scan_results(ignored);
+                        let modlist_count =
+                            app_state.file_entries.tag_modlist(app_state.modlist.as_ref());
+                        app_state.ignored_entries.tag_modlist(app_state.modlist.as_ref());
+                        app_state.load_order = load_order;
+                        app_state.file_entries.apply_load_order(app_state.load_order.as_ref());
+                        app_state.ignored_entries.apply_load_order(app_state.load_order.as_ref());
+                        app_state.file_entries.apply_notes(&app_state.notes);
+                        app_state.ignored_entries.apply_notes(&app_state.notes);
+                        app_state.folder_archives = folder_archives;
+
+                        // Build the default selection from the configured rules
+                        let rules = &app_state.config.extraction.selection_rules;
+                        let selected = if rules.is_empty() {
+                            0
+                        } else {
+                            let paths: HashSet<PathBuf> =
+                                selected_paths(rules, app_state.file_entries.entries())
+                                    .into_iter()
+                                    .collect();
+                            app_state.file_entries.select_paths(&paths)
+                        };
+                        let filter = TableFilter::default();
+                        let view = MainViewModel::build(
+                            app_state.file_entries.entries(),
+                            app_state.load_order.as_ref(),
+                            &app_state.config,
+                            &filter,
+                        )
+                        .with_ignored(
+                            app_state.ignored_entries.entries(),
+                            &app_state.config,
+                            &filter,
+                        );
+                        drop(guard);
+                        (modlist_count, selected, view)
+                    };
 
                     // Update UI
+                    let state_for_restore = Arc::clone(&state_clone);
                     let _ = slint::invoke_from_event_loop(move || {
                         if let Some(ui) = weak_clone.upgrade() {
-                            ui.set_file_list(ModelRc::new(VecModel::from(row_data)));
-                            ui.set_total_files(total_files.try_into().unwrap_or(i32::MAX));
-                            ui.set_total_size(SharedString::from(format_size(total_size, BINARY)));
-                            ui.set_scanning(false);
+                            show_main_view(&ui, &view);
+                            ui.set_failed_only(false);
+                            ui.set_failed_count(0);
+                            ui.set_marked_count(selected.try_into().unwrap_or(i32::MAX));
+                            ui.set_modlist_count(modlist_count.try_into().unwrap_or(i32::MAX));
+                            ui.set_own_mods_only(false);
+                            ui.set_show_ignored(false);
+                            ui.set_note_filter(SharedString::new());
+                            ui.set_corrupted_only(false);
+                            ui.set_corrupted_count(corrupted_count.try_into().unwrap_or(i32::MAX));
+                            let found = PhaseEvent::ScanFinished {
+                                found: total_files > 0,
+                            };
+                            advance_phase(&ui, &state_for_restore, found);
                             ui.set_status_text(SharedString::from(format!(
-                                "Ready - {total_files} files found"
+                                "Ready - {} files found",
+                                format_count(total_files as u64)
                             )));
+
+                            update_dashboard(&ui, &state_for_restore);
+                            update_ignore_patterns(&ui, &state_for_restore);
+                            restore_saved_threshold(&ui, &state_for_restore);
+                            warn_if_tool_too_old(&ui, &state_for_restore);
+                            warn_if_unknown_versions(&ui, &state_for_restore);
+
+                            // Point out skipped bad archives instead of dropping them silently
+                            let ignore_bad_files =
+                                state_for_restore.lock().config.extraction.ignore_bad_files;
+                            if ignore_bad_files && corrupted_count > 0 {
+                                show_toast(
+                                    &ui,
+                                    &ToastData::warning(format!(
+                                        "{} archives that could not be read will be skipped - review them with the Problems filter",
+                                        format_count(corrupted_count as u64)
+                                    )),
+                                );
+                            }
                         }
                     });
                 }
@@ -313,28 +1098,18 @@ fn setup_scan_callback(main_window: &MainWindow, state: Arc<Mutex<AppState>>) {
 
                     let _ = slint::invoke_from_event_loop(move || {
                         if let Some(ui) = weak_clone.upgrade() {
-                            ui.set_scanning(false);
+                            advance_phase(&ui, &state_clone, PhaseEvent::Failed);
                             ui.set_status_text(SharedString::from(error_msg));
                         }
                     });
                 }
-                Err(e) => {
-                    tracing::error!("Scan task failed: {}", e);
-
-                    let _ = slint::invoke_from_event_loop(move || {
-                        if let Some(ui) = weak_clone.upgrade() {
-                            ui.set_scanning(false);
-                            ui.set_status_text(SharedString::from("Scan task failed"));
-                        }
-                    });
-                }
+                Err(e) => recover_from_panic(&weak_clone, "Scan", fail_phase(state_clone), e),
             }
         });
     });
 }
 
 /// Set up extraction callback
-#[allow(clippy::too_many_lines)] // Complex extraction flow with progress tracking
 fn setup_extraction_callback(
     main_window: &MainWindow,
     state: Arc<Mutex<AppState>>,
@@ -343,863 +1118,4799 @@ fn setup_extraction_callback(
     let weak = main_window.as_weak();
 
     main_window.on_start_extraction(move || {
-        let weak_clone = weak.clone();
-        let state_clone = Arc::clone(&state);
-        let extraction_control_clone = Arc::clone(&extraction_control);
+        if state.lock().config.advanced.analysis_only {
+            if let Some(ui) = weak.upgrade() {
+                show_toast(&ui, &ToastData::warning("Extraction is disabled in analysis-only mode"));
+            }
+            return;
+        }
+        if let Some(ui) = weak.upgrade()
+            && !phase_accepts(&ui, &state, PhaseEvent::StartExtraction)
+        {
+            return;
+        }
 
-        // Set extracting state
+        // Warn up front if an output folder needs elevation instead of failing
+        // every archive; archives are extracted into their own folders
         if let Some(ui) = weak.upgrade() {
-            ui.set_extracting(true);
-            ui.set_extraction_complete(false); // Phase 2.3: Reset completion state
-            ui.set_paused(false); // Phase 2.3: Reset pause state
-            ui.set_status_text(SharedString::from("Starting extraction..."));
+            let own_mods_only = ui.get_own_mods_only();
+            let files = {
+                let app_state = state.lock();
+                effective_config(&app_state)
+                    .map(|config| run_candidates(&app_state, &config, own_mods_only))
+                    .unwrap_or_default()
+            };
+            let folders = files.iter().filter_map(|f| f.full_path.parent());
+            if let Some((folder, access)) = find_unwritable(folders) {
+                let folder = folder.display().to_string();
+                tracing::warn!("No write access to {}", folder);
+                show_elevation_warning(&ui, &folder, access);
+                return;
+            }
         }
 
-        // Run extraction in background task using global runtime
-        crate::get_runtime().spawn(async move {
-            let (tx, mut rx) = mpsc::channel(100);
-
-                // Phase 2.3: Create control channel
-                let (control_tx, mut control_rx) = tokio::sync::mpsc::unbounded_channel();
+        // Confirm with a summary of the run, unless the user opted out; runs
+        // that may write into the game Data folder are always confirmed
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let confirm = {
+            let extraction = &state.lock().config.extraction;
+            extraction.confirm_extraction || extraction.data_folder_mode
+        };
+        if confirm {
+            confirm_extraction(&ui, &state, &extraction_control);
+        } else {
+            run_extraction(&ui, &state, &extraction_control);
+        }
+    });
+}
 
-                // Phase 2.3: Store control sender in shared state
-                {
-                    let mut ctrl_state = extraction_control_clone.lock();
-                    ctrl_state.control_tx = Some(control_tx);
-                }
+/// Archives the next extraction processes: the explicit selection, if any, else all
+/// candidates, without the modlist's archives when only own mods are shown, and still
+/// picked by the run options
+fn run_candidates(app_state: &AppState, config: &EffectiveConfig, own_mods_only: bool) -> Vec<FileEntry> {
+    let mut files = app_state.file_entries.extraction_candidates();
+    files.retain(|f| !own_mods_only || !f.in_modlist);
+    if !config.overrides().is_empty() {
+        files.retain(|f| config.includes(&f.full_path));
+    }
+    files
+}
 
-                // Get files and config from state
-                let (files, config) = {
-                    let app_state = state_clone.lock();
-                    (
-                        app_state.file_entries.entries().to_vec(),
-                        app_state.config.clone(),
-                    )
-                };
+/// Check the next extraction in the background, then ask to confirm it with a summary
+fn confirm_extraction(
+    ui: &MainWindow,
+    state: &Arc<Mutex<AppState>>,
+    extraction_control: &Arc<Mutex<ExtractionControlState>>,
+) {
+    let own_mods_only = ui.get_own_mods_only();
+    let (files, config, loose_stats) = {
+        let app_state = state.lock();
+        match effective_config(&app_state) {
+            Ok(config) => (
+                run_candidates(&app_state, &config, own_mods_only),
+                config,
+                app_state.loose_stats.clone(),
+            ),
+            Err(e) => {
+                drop(app_state);
+                show_run_options_error(ui, &e);
+                return;
+            }
+        }
+    };
 
-                tracing::info!("Starting extraction of {} BA2 files", files.len());
+    let previous_status = ui.get_status_text();
+    ui.set_status_text(SharedString::from("Checking the extraction..."));
 
-                // Spawn extraction task
-                let extract_task = tokio::spawn(async move {
-                    extract_all(files, config, Some(tx)).await
+    let weak = ui.as_weak();
+    let state = Arc::clone(state);
+    let extraction_control = Arc::clone(extraction_control);
+    std::thread::spawn(move || {
+        let mut report = preflight(&files);
+        report.check_loose_files(config.extraction.loose_file_limit, &loose_stats);
+        let _ = slint::invoke_from_event_loop(move || {
+            let Some(ui) = weak.upgrade() else {
+                return;
+            };
+            ui.set_status_text(previous_status);
+            if report.writes_to_data_folder() {
+                let dialog = data_folder_dialog(&report, &config);
+                show_confirmation(&ui, dialog, move |ui| {
+                    if ui.get_dialog_checkbox_checked() {
+                        run_extraction(ui, &state, &extraction_control);
+                    } else {
+                        show_toast(
+                            ui,
+                            &ToastData::warning(
+                                "Extraction into the Data folder needs the confirmation box checked",
+                            ),
+                        );
+                    }
                 });
+                return;
+            }
+            if !config.extraction.confirm_extraction {
+                run_extraction(&ui, &state, &extraction_control);
+                return;
+            }
+            let dialog = preflight_dialog(&report, &config);
+            show_confirmation(&ui, dialog, move |ui| {
+                if ui.get_dialog_checkbox_checked() {
+                    skip_extraction_confirmation(ui, &state);
+                }
+                run_extraction(ui, &state, &extraction_control);
+            });
+        });
+    });
+}
 
-                // Phase 2.3: Track pause state
-                let mut is_paused = false;
-                let mut should_cancel = false;
-
-                // Phase 2.3: Track extraction timing for speed/ETA calculation
-                let extraction_start_time = std::time::Instant::now();
-                let mut last_update_time = std::time::Instant::now();
-
-                // Process progress updates and control signals
-                loop {
-                    tokio::select! {
-                        // Handle extraction progress
-                        progress_opt = rx.recv() => {
-                            // Check if progress channel closed
-                            let Some(progress) = progress_opt else {
-                                tracing::info!("Progress channel closed, extraction finished");
-                                break;
-                            };
-                            // Check if we should cancel
-                            if should_cancel {
-                                tracing::info!("Cancelling extraction...");
-                                break;
-                            }
-
-                            // Wait while paused
-                            while is_paused && !should_cancel {
-                                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-                                // Check for control signals while paused
-                                if let Ok(control) = control_rx.try_recv() {
-                                    match control {
-                                        ExtractionControl::Resume => {
-                                            tracing::info!("Resuming extraction");
-                                            is_paused = false;
-                                            let weak = weak_clone.clone();
-                                            let _ = slint::invoke_from_event_loop(move || {
-                                                if let Some(ui) = weak.upgrade() {
-                                                    ui.set_paused(false);
-                                                }
-                                            });
-                                        }
-                                        ExtractionControl::Cancel => {
-                                            should_cancel = true;
-                                            break;
-                                        }
-                                        ExtractionControl::Pause => {}
-                                    }
-                                }
-                            }
+/// Build the red-flag dialog for a run that writes into the game Data folder
+///
+/// Unlike the regular summary it cannot be turned off and only starts the run
+/// with its checkbox ticked.
+fn data_folder_dialog(report: &PreflightReport, config: &EffectiveConfig) -> DialogConfig {
+    let summary = preflight_dialog(report, config);
+    DialogConfig::error("Extract into the game Data folder?", summary.message)
+        .with_primary_button("Extract into Data")
+        .with_secondary_button("Cancel")
+        .with_checkbox("I understand the extracted loose files are not managed by any mod manager")
+}
 
-                            if should_cancel {
-                                break;
-                            }
+/// Build the dialog summarizing an extraction about to start
+fn preflight_dialog(report: &PreflightReport, config: &EffectiveConfig) -> DialogConfig {
+    let message = preflight_message(report, config);
+    let dialog = if report.warnings.is_empty() {
+        DialogConfig::info("Start extraction?", message)
+    } else {
+        DialogConfig::warning("Start extraction despite warnings?", message)
+    };
+    dialog
+        .with_primary_button("Extract")
+        .with_secondary_button("Cancel")
+        .with_checkbox("Don't ask again")
+}
 
-                    let weak = weak_clone.clone();
-                    let status = match &progress {  // Changed to &progress to avoid move
-                        ExtractionProgress::Started {
-                            file_name,
-                            current,
-                            total,
-                        } => {
-                            // Phase 2.3: Update progress properties in UI
-                            let file_name_clone = file_name.clone();
-                            let current_val = *current;
-                            let total_val = *total;
-
-                            // Phase 2.3: Calculate speed and ETA
-                            let elapsed = extraction_start_time.elapsed();
-                            let elapsed_secs = elapsed.as_secs_f64();
-
-                            // Only update speed/ETA every second to avoid flickering
-                            let should_update_timing = last_update_time.elapsed().as_secs() >= 1;
-                            if should_update_timing {
-                                last_update_time = std::time::Instant::now();
-                            }
+/// Start extractions without confirming them from now on
+fn skip_extraction_confirmation(ui: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    state.lock().config.extraction.confirm_extraction = false;
+    ui.set_settings_confirm_extraction(false);
+    show_toast(
+        ui,
+        &ToastData::info("Extractions start without confirmation - turn it back on in Settings")
+            .with_view(View::Settings),
+    );
 
-                            #[allow(clippy::cast_precision_loss)] // File counts won't exceed f64 precision
-                            let speed_str = if should_update_timing && current_val > 0 && elapsed_secs > 0.0 {
-                                let files_per_sec = current_val as f64 / elapsed_secs;
-                                if files_per_sec >= 1.0 {
-                                    format!("{files_per_sec:.1} files/s")
-                                } else {
-                                    format!("{:.1} s/file", 1.0 / files_per_sec)
-                                }
-                            } else {
-                                String::new()
-                            };
+    let state = Arc::clone(state);
+    std::thread::spawn(move || {
+        let config = state.lock().config.clone();
+        if let Err(e) = config.save() {
+            tracing::error!("Failed to save configuration: {}", e);
+        }
+    });
+}
 
-                            #[allow(
-                                clippy::cast_precision_loss, // File counts won't exceed f64 precision
-                                clippy::cast_possible_truncation, // ETA seconds fit in u64
-                                clippy::cast_sign_loss // All values are positive
-                            )]
-                            let eta_str = if should_update_timing && current_val > 0 && elapsed_secs > 0.0 {
-                                let remaining = total_val.saturating_sub(current_val);
-                                if remaining > 0 {
-                                    let avg_time_per_file = elapsed_secs / current_val as f64;
-                                    let eta_secs = (remaining as f64 * avg_time_per_file) as u64;
-
-                                    let hours = eta_secs / 3600;
-                                    let mins = (eta_secs % 3600) / 60;
-                                    let secs = eta_secs % 60;
-
-                                    if hours > 0 {
-                                        format!("{hours}h {mins}m")
-                                    } else if mins > 0 {
-                                        format!("{mins}m {secs}s")
-                                    } else {
-                                        format!("{secs}s")
-                                    }
-                                } else {
-                                    String::new()
-                                }
-                            } else {
-                                String::new()
-                            };
+/// Start extracting the archives of the run
+#[allow(clippy::too_many_lines)] // Complex extraction flow with progress tracking
+fn run_extraction(
+    ui: &MainWindow,
+    state: &Arc<Mutex<AppState>>,
+    extraction_control: &Arc<Mutex<ExtractionControlState>>,
+) {
+    // The confirmation may have stayed open while another operation started
+    if !phase_accepts(ui, state, PhaseEvent::StartExtraction) {
+        return;
+    }
 
-                            let weak_progress = weak.clone();
-                            let _ = slint::invoke_from_event_loop(move || {
-                                if let Some(ui) = weak_progress.upgrade() {
-                                    ui.set_current_extracting_file(SharedString::from(file_name_clone));
-                                    ui.set_current_file_index(current_val.try_into().unwrap_or(i32::MAX));
-                                    ui.set_total_extraction_files(total_val.try_into().unwrap_or(i32::MAX));
-
-                                    // Calculate progress percentage (avoid division by zero)
-                                    let progress_pct = if total_val > 0 {
-                                        ((current_val * 100) / total_val).try_into().unwrap_or(0)
-                                    } else {
-                                        0
-                                    };
-                                    ui.set_extraction_progress(progress_pct);
-
-                                    // Phase 2.3: Update speed and ETA
-                                    if should_update_timing {
-                                        ui.set_extraction_speed(SharedString::from(speed_str));
-                                        ui.set_extraction_eta(SharedString::from(eta_str));
-                                    }
-                                }
-                            });
+    let weak = ui.as_weak();
+    let weak_clone = weak.clone();
+    let state_clone = Arc::clone(state);
+    let extraction_control_clone = Arc::clone(extraction_control);
+
+    // Archives in the run (the explicit selection, if any) start out queued.
+    // With the own-mods filter on, archives of the Wabbajack modlist stay untouched.
+    // The run options apply to this extraction only and are used up by it.
+    let own_mods_only = weak.upgrade().is_some_and(|ui| ui.get_own_mods_only());
+    let (files, config, backend) = {
+        let mut app_state = state.lock();
+        let config = match effective_config(&app_state) {
+            Ok(config) => config,
+            Err(e) => {
+                drop(app_state);
+                if let Some(ui) = weak.upgrade() {
+                    show_run_options_error(&ui, &e);
+                }
+                return;
+            }
+        };
+        let files = run_candidates(&app_state, &config, own_mods_only);
+        if !config.overrides().is_empty() {
+            app_state.run_overrides = RunOverrides::default();
+        }
+        app_state.file_entries.set_all_statuses(ExtractionStatus::Idle);
+        for file in &files {
+            app_state.file_entries.set_status(&file.full_path, ExtractionStatus::Queued);
+        }
+        (files, config, app_state.backend.clone())
+    };
+
+    // Shutting down is never carried over from an earlier run
+    let after_run = AfterRun {
+        notify: config.advanced.integrations.notify_when_done,
+        shutdown: false,
+    };
+    extraction_control.lock().after_run = after_run;
+
+    // Set extracting state
+    if let Some(ui) = weak.upgrade() {
+        if ui.get_run_options_active() {
+            reset_run_options(&ui);
+            show_toast(&ui, &ToastData::info("Extracting with this run's options; later runs use the saved settings"));
+        }
+        advance_phase(&ui, state, PhaseEvent::StartExtraction);
+        ui.set_extraction_complete(false); // Phase 2.3: Reset completion state
+        ui.set_notify_when_done(after_run.notify);
+        ui.set_shutdown_when_done(after_run.shutdown);
+        ui.set_low_priority(config.advanced.performance.low_priority);
+        ui.set_failed_only(false);
+        ui.set_failed_count(0);
+        ui.set_status_text(SharedString::from("Starting extraction..."));
+        refresh_file_table(&ui, state, current_threshold(&ui));
+    }
+
+    // Run extraction in background task using global runtime
+    let reset = reset_extraction(Arc::clone(state));
+    spawn_guarded(&weak, "Extraction", reset, async move {
+        let events = state_clone.lock().events.child();
+        let mut rx = events.subscribe();
+
+            // Phase 2.3: Create control channel
+            let (control_tx, mut control_rx) = tokio::sync::mpsc::unbounded_channel();
+
+            // Phase 2.3: Store control sender in shared state
+            let cancel_token = CancellationToken::new();
+            {
+                let mut ctrl_state = extraction_control_clone.lock();
+                ctrl_state.control_tx = Some(control_tx);
+                ctrl_state.cancel_token = Some(cancel_token.clone());
+            }
+
+            // Folder for the "Open Folder" button, which the run options may override
+            let extraction_path = config.advanced.extraction_path.clone();
+            let config = config.into_config();
+
+            tracing::info!("Starting extraction of {} BA2 files", files.len());
+
+            // Speed and ETA are based on the archive sizes, not file counts
+            let archive_sizes: Vec<(PathBuf, u64)> = files
+                .iter()
+                .map(|f| (f.full_path.clone(), f.file_size))
+                .collect();
+
+            // A slow run with a busy Antimalware Service suggests excluding the mod folder
+            let written_folder = files
+                .first()
+                .and_then(|f| f.full_path.parent())
+                .map(Path::to_path_buf);
+            let medium_override = config.advanced.performance.storage_medium;
+
+            // External tools follow the run through the status file when enabled
+            if config.advanced.diagnostics.write_status_file {
+                match status_file_path() {
+                    Ok(path) => {
+                        tokio::spawn(write_status(events.monitor(), path));
+                    }
+                    Err(e) => tracing::warn!("No folder for the status file: {}", e),
+                }
+            }
 
-                            format!("Extracting {file_name} ({current}/{total})")
+            // Discord shows the run on the user's profile when enabled
+            #[cfg(feature = "discord")]
+            if config.advanced.integrations.discord_presence {
+                let monitor = events.monitor();
+                std::thread::spawn(move || crate::integrations::discord::show_progress(monitor));
+            }
+
+            // Local metrics are only recorded when the user opted in
+            let mut metrics = config
+                .advanced
+                .diagnostics
+                .collect_metrics
+                .then(|| MetricsRecorder::new(archive_sizes.iter().cloned()));
+
+            // Spawn extraction task
+            let antimalware = AntimalwareSampler::start();
+            let extract_task = tokio::spawn(async move {
+                match backend {
+                    Some(backend) => {
+                        extract_all_with(files, config, backend, Some(events), Some(cancel_token))
+                            .await
+                    }
+                    None => extract_all(files, config, Some(events), Some(cancel_token)).await,
+                }
+            });
+
+            // Phase 2.3: Track pause state
+            let mut is_paused = false;
+            let mut should_cancel = false;
+
+            // Phase 2.3: Track extraction timing for speed/ETA calculation
+            let mut throughput = ThroughputTracker::new(archive_sizes);
+            let mut last_update_time = std::time::Instant::now();
+
+            // Process progress updates and control signals
+            loop {
+                tokio::select! {
+                    // Handle extraction progress
+                    progress_opt = rx.recv() => {
+                        // Check if progress channel closed
+                        let Some(event) = progress_opt else {
+                            tracing::info!("Progress channel closed, extraction finished");
+                            break;
+                        };
+                        let AppEvent::Extract(progress) = event else {
+                            continue;
+                        };
+                        // Check if we should cancel
+                        if should_cancel {
+                            tracing::info!("Cancelling extraction...");
+                            break;
                         }
-                        ExtractionProgress::Completed {
-                            file_name,
-                            success,
-                            error,
-                        } => {
-                            if *success {  // Dereference since we're now matching on &progress
-                                format!("Completed: {file_name}")
-                            } else {
-                                format!(
-                                    "Failed: {} - {}",
-                                    file_name,
-                                    error.as_ref().map_or("Unknown error", std::string::String::as_str)
-                                )
+
+                        // Wait while paused
+                        while is_paused && !should_cancel {
+                            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+                            // Check for control signals while paused
+                            if let Ok(control) = control_rx.try_recv() {
+                                match control {
+                                    ExtractionControl::Resume => {
+                                        tracing::info!("Resuming extraction");
+                                        is_paused = false;
+                                    }
+                                    ExtractionControl::Cancel => {
+                                        should_cancel = true;
+                                        break;
+                                    }
+                                    ExtractionControl::Pause => {}
+                                }
                             }
                         }
-                        ExtractionProgress::Finished {
-                            successful,
-                            failed,
-                        } => {
-                            // Phase 2.3: Reset progress properties
-                            let weak_progress = weak.clone();
-                            let _ = slint::invoke_from_event_loop(move || {
-                                if let Some(ui) = weak_progress.upgrade() {
-                                    ui.set_current_extracting_file(SharedString::from(""));
-                                    ui.set_current_file_index(0);
-                                    ui.set_total_extraction_files(0);
-                                    ui.set_extraction_progress(0);
-                                    ui.set_extraction_speed(SharedString::from("")); // Phase 2.3: Reset speed
-                                    ui.set_extraction_eta(SharedString::from("")); // Phase 2.3: Reset ETA
-                                }
-                            });
 
-                            format!(
-                                "Extraction complete: {successful} successful, {failed} failed"
-                            )
+                        if should_cancel {
+                            break;
                         }
-                    };
 
+                if let Some(metrics) = metrics.as_mut() {
+                    metrics.record(&progress);
+                }
+
+                // Update speed and ETA at most once per second to avoid flickering
+                let bytes_changed = match &progress {
+                    ExtractionProgress::FileProgress { file_path, percent, .. } => {
+                        throughput.progress(file_path, *percent);
+                        true
+                    }
+                    ExtractionProgress::Completed { file_path, output_size, .. } => {
+                        throughput.complete(file_path, *output_size);
+                        true
+                    }
+                    ExtractionProgress::Skipped { file_path, .. } => {
+                        throughput.skip(file_path);
+                        true
+                    }
+                    _ => false,
+                };
+
+                // Overall progress is weighted by archive size, so a big archive
+                // left for last doesn't sit behind a nearly full bar
+                if bytes_changed {
+                    let percent = i32::from(throughput.percent());
+                    let weak_percent = weak_clone.clone();
                     let _ = slint::invoke_from_event_loop(move || {
-                        if let Some(ui) = weak.upgrade() {
-                            ui.set_status_text(SharedString::from(status));
+                        if let Some(ui) = weak_percent.upgrade() {
+                            ui.set_extraction_progress(percent);
                         }
                     });
-                        } // End of Some(progress) arm
-
-                        // Handle control signals
-                        Some(control) = control_rx.recv() => {
-                            tracing::info!("Received control signal: {:?}", control);
-                            match control {
-                                ExtractionControl::Pause => {
-                                    tracing::info!("Pausing extraction");
-                                    is_paused = true;
-                                    let weak = weak_clone.clone();
-                                    let _ = slint::invoke_from_event_loop(move || {
-                                        if let Some(ui) = weak.upgrade() {
-                                            ui.set_paused(true);
-                                            ui.set_status_text(SharedString::from("Extraction paused"));
-                                        }
-                                    });
-                                }
-                                ExtractionControl::Resume => {
-                                    tracing::info!("Resuming extraction");
-                                    is_paused = false;
-                                    let weak = weak_clone.clone();
-                                    let _ = slint::invoke_from_event_loop(move || {
-                                        if let Some(ui) = weak.upgrade() {
-                                            ui.set_paused(false);
-                                            ui.set_status_text(SharedString::from("Extraction resumed"));
-                                        }
-                                    });
-                                }
-                                ExtractionControl::Cancel => {
-                                    tracing::info!("Cancelling extraction");
-                                    let weak = weak_clone.clone();
-                                    let _ = slint::invoke_from_event_loop(move || {
-                                        if let Some(ui) = weak.upgrade() {
-                                            ui.set_status_text(SharedString::from("Extraction cancelled"));
-                                        }
-                                    });
-                                    break;
-                                }
-                            }
+                }
+                if last_update_time.elapsed().as_secs() >= 1 {
+                    last_update_time = std::time::Instant::now();
+                    let text = ProgressText::new(
+                        throughput.bytes_per_sec(),
+                        throughput.expansion_ratio(),
+                        throughput.eta(),
+                    );
+                    let weak_timing = weak_clone.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak_timing.upgrade() {
+                            ui.set_extraction_speed(SharedString::from(text.speed));
+                            ui.set_extraction_eta(SharedString::from(text.eta));
+                            ui.set_extraction_finish(SharedString::from(text.finish));
+                            ui.set_extraction_long_run(text.long_run);
                         }
-                    } // End of tokio::select!
-
-                    // Check if we should break (control signals or extraction finished)
-                    if should_cancel {
-                        break;
-                    }
-                } // End of loop
+                    });
+                }
 
-                // Get extraction results
-                match extract_task.await {
-                    Ok(Ok(result)) => {
-                        tracing::info!(
-                            "Extraction complete: {} successful, {} failed",
-                            result.successful,
-                            result.failed
-                        );
+                let weak = weak_clone.clone();
+                let status = match &progress {  // Changed to &progress to avoid move
+                    ExtractionProgress::Started {
+                        file_name,
+                        file_path,
+                        current,
+                        total,
+                        ..
+                    } => {
+                        set_row_status(&weak, &state_clone, file_path, ExtractionStatus::Extracting, None);
 
-                        if result.failed > 0 {
-                            tracing::warn!(
-                                "Failed files: {:?}",
-                                result
-                                    .failed_files()
-                                    .iter()
-                                    .map(|p| p.display().to_string())
-                                    .collect::<Vec<_>>()
-                            );
-                        }
+                        // Phase 2.3: Update progress properties in UI
+                        let file_name_clone = file_name.clone();
+                        let current_val = *current;
+                        let total_val = *total;
 
-                        let final_status = format!(
-                            "Extraction complete: {} successful, {} failed",
-                            result.successful, result.failed
-                        );
+                        let weak_progress = weak.clone();
+                        let _ = slint::invoke_from_event_loop(move || {
+                            if let Some(ui) = weak_progress.upgrade() {
+                                ui.set_current_extracting_file(SharedString::from(file_name_clone));
+                                ui.set_current_file_index(current_val.try_into().unwrap_or(i32::MAX));
+                                ui.set_total_extraction_files(total_val.try_into().unwrap_or(i32::MAX));
+                            }
+                        });
 
-                        // Phase 2.3: Get extraction path for "Open Folder" button
-                        let extraction_path = {
-                            let app_state = state_clone.lock();
-                            app_state.config.advanced.extraction_path.clone()
-                        };
+                        format!(
+                            "Extracting {file_name} ({}/{})",
+                            format_count(*current as u64),
+                            format_count(*total as u64)
+                        )
+                    }
+                    ExtractionProgress::FileProgress {
+                        file_name,
+                        percent,
+                        ..
+                    } => format!("Extracting {file_name} ({percent}%)"),
+                    ExtractionProgress::Completed {
+                        file_name,
+                        file_path,
+                        success,
+                        error,
+                        warnings,
+                        duration,
+                        ..
+                    } => {
+                        let status = if *success { ExtractionStatus::Done } else { ExtractionStatus::Failed };
+                        set_row_status(&weak, &state_clone, file_path, status, Some(*duration));
 
+                        if *success && !warnings.is_empty() {
+                            format!(
+                                "Completed with {} warning(s): {file_name} - {}",
+                                format_count(warnings.len() as u64),
+                                warnings[0]
+                            )
+                        } else if *success {  // Dereference since we're now matching on &progress
+                            format!("Completed: {file_name}")
+                        } else {
+                            format!(
+                                "Failed: {} - {}",
+                                file_name,
+                                error.as_ref().map_or("Unknown error", std::string::String::as_str)
+                            )
+                        }
+                    }
+                    ExtractionProgress::Skipped {
+                        file_name,
+                        file_path,
+                        reason,
+                    } => {
+                        set_row_status(&weak, &state_clone, file_path, ExtractionStatus::Skipped, None);
+                        format!("Skipped: {file_name} - {reason}")
+                    }
+                    ExtractionProgress::Retrying {
+                        file_name,
+                        attempt,
+                        max_attempts,
+                        delay,
+                        likely_antivirus,
+                        ..
+                    } => format!(
+                        "Retrying {file_name} ({attempt}/{max_attempts}) in {}…{}",
+                        format_duration(delay.as_secs().max(1)),
+                        if *likely_antivirus {
+                            " (files locked, likely antivirus)"
+                        } else {
+                            ""
+                        }
+                    ),
+                    ExtractionProgress::DriveDisconnected { folder } => {
+                        let folder = folder.display().to_string();
+                        let weak_dialog = weak.clone();
+                        let message = folder.clone();
                         let _ = slint::invoke_from_event_loop(move || {
-                            if let Some(ui) = weak_clone.upgrade() {
-                                ui.set_extracting(false);
-                                ui.set_status_text(SharedString::from(final_status));
-
-                                // Phase 2.3: Show "Open Folder" button after successful extraction
-                                if result.successful > 0 {
-                                    ui.set_extraction_complete(true);
-                                    ui.set_extraction_folder(SharedString::from(extraction_path));
-                                }
+                            if let Some(ui) = weak_dialog.upgrade() {
+                                show_drive_disconnected_prompt(&ui, &message);
                             }
                         });
+                        format!("Waiting for drive to reconnect: {folder}")
                     }
-                    Ok(Err(e)) => {
-                        let error_msg = format!("Extraction failed: {e}");
-                        tracing::error!("{}", error_msg);
-
+                    ExtractionProgress::DriveReconnected { folder } => {
+                        let weak_dialog = weak.clone();
                         let _ = slint::invoke_from_event_loop(move || {
-                            if let Some(ui) = weak_clone.upgrade() {
-                                ui.set_extracting(false);
-                                ui.set_status_text(SharedString::from(error_msg));
+                            if let Some(ui) = weak_dialog.upgrade() {
+                                notifications::hide_dialog(&ui);
+                                show_toast(&ui, &ToastData::info("Drive reconnected, resuming extraction"));
                             }
                         });
+                        format!("Drive reconnected: {}", folder.display())
                     }
-                    Err(e) => {
-                        tracing::error!("Extraction task failed: {}", e);
-
+                    ExtractionProgress::Aborted {
+                        consecutive_failures,
+                        cause,
+                    } => format!(
+                        "Stopping after {} failures in a row: {cause}",
+                        format_count(*consecutive_failures as u64)
+                    ),
+                    ExtractionProgress::Finished {
+                        successful,
+                        failed,
+                        skipped,
+                    } => {
+                        // Phase 2.3: Reset progress properties
+                        let weak_progress = weak.clone();
                         let _ = slint::invoke_from_event_loop(move || {
-                            if let Some(ui) = weak_clone.upgrade() {
-                                ui.set_extracting(false);
-                                ui.set_status_text(SharedString::from("Extraction task failed"));
+                            if let Some(ui) = weak_progress.upgrade() {
+                                ui.set_current_extracting_file(SharedString::from(""));
+                                ui.set_current_file_index(0);
+                                ui.set_total_extraction_files(0);
+                                ui.set_extraction_progress(0);
+                                ui.set_extraction_speed(SharedString::from("")); // Phase 2.3: Reset speed
+                                ui.set_extraction_eta(SharedString::from("")); // Phase 2.3: Reset ETA
+                                ui.set_extraction_finish(SharedString::from(""));
+                                ui.set_extraction_long_run(false);
                             }
                         });
+
+                        format!(
+                            "Extraction complete: {} successful, {} failed, {} skipped",
+                            format_count(*successful as u64),
+                            format_count(*failed as u64),
+                            format_count(*skipped as u64)
+                        )
+                    }
+                };
+
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(ui) = weak.upgrade() {
+                        ui.set_status_text(SharedString::from(status));
+                    }
+                });
+                    } // End of Some(progress) arm
+
+                    // Handle control signals
+                    Some(control) = control_rx.recv() => {
+                        tracing::info!("Received control signal: {:?}", control);
+                        match control {
+                            ExtractionControl::Pause => {
+                                tracing::info!("Pausing extraction");
+                                is_paused = true;
+                                let weak = weak_clone.clone();
+                                let _ = slint::invoke_from_event_loop(move || {
+                                    if let Some(ui) = weak.upgrade() {
+                                        ui.set_status_text(SharedString::from("Extraction paused"));
+                                    }
+                                });
+                            }
+                            ExtractionControl::Resume => {
+                                tracing::info!("Resuming extraction");
+                                is_paused = false;
+                                let weak = weak_clone.clone();
+                                let _ = slint::invoke_from_event_loop(move || {
+                                    if let Some(ui) = weak.upgrade() {
+                                        ui.set_status_text(SharedString::from("Extraction resumed"));
+                                    }
+                                });
+                            }
+                            ExtractionControl::Cancel => {
+                                tracing::info!("Cancelling extraction");
+                                should_cancel = true;
+                                let weak = weak_clone.clone();
+                                let _ = slint::invoke_from_event_loop(move || {
+                                    if let Some(ui) = weak.upgrade() {
+                                        ui.set_status_text(SharedString::from("Extraction cancelled"));
+                                    }
+                                });
+                                break;
+                            }
+                        }
                     }
+                } // End of tokio::select!
+
+                // Check if we should break (control signals or extraction finished)
+                if should_cancel {
+                    break;
                 }
-            });
-    });
-}
+            } // End of loop
 
-/// Set up sort callback
-fn setup_sort_callback(main_window: &MainWindow, state: Arc<Mutex<AppState>>) {
-    let weak = main_window.as_weak();
+            // Stop the remaining extractions so unfinished rows can be marked as skipped
+            if should_cancel {
+                extract_task.abort();
+            }
+            {
+                let weak = weak_clone.clone();
+                let state = Arc::clone(&state_clone);
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(ui) = weak.upgrade() {
+                        advance_phase(&ui, &state, PhaseEvent::ExtractionStopped);
+                    }
+                });
+            }
 
-    main_window.on_sort_by_column(move |column| {
-        let sort_by = match column {
-            0 => SortBy::Name,
-            1 => SortBy::Size,
-            2 => SortBy::FileCount,
-            3 => SortBy::ModName,
-            _ => return,
-        };
+            if let Some(metrics) = metrics {
+                record_run_metrics(&weak_clone, &state_clone, metrics);
+            }
+            let exclusion_advice = written_folder
+                .filter(|_| !should_cancel)
+                .and_then(|folder| {
+                    let medium = medium_override.unwrap_or_else(|| storage_medium(&folder));
+                    ExclusionAdvice::assess(
+                        &folder,
+                        throughput.written_bytes(),
+                        antimalware.elapsed(),
+                        medium,
+                        antimalware.cpu_percent(),
+                    )
+                });
+            // The run added loose files deeper than the folders' own modification times show
+            state_clone.lock().loose_stats.clear();
 
-        // Determine sort direction
-        let (new_ascending, reverse) = {
-            let mut app_state = state.lock();
+            // Options may have changed in the progress view while the run was going
+            let after_run = extraction_control_clone.lock().after_run;
 
-            let ascending = if app_state.sort_column == column {
-                !app_state.sort_ascending
-            } else {
-                // Default sort order for new column:
-                // Size (1) and FileCount (2) default to Descending (Largest/Most first)
-                // Name (0) and ModName (3) default to Ascending (A-Z)
-                !matches!(column, 1 | 2)
-            };
+            // Get extraction results
+            match extract_task.await {
+                Ok(Ok(result)) => {
+                    tracing::info!(
+                        "Extraction complete: {} successful, {} failed",
+                        result.successful,
+                        result.failed
+                    );
 
-            app_state.sort_column = column;
-            app_state.sort_ascending = ascending;
-            drop(app_state);
+                    if result.failed > 0 {
+                        tracing::warn!(
+                            "Failed files: {:?}",
+                            result
+                                .failed_files()
+                                .iter()
+                                .map(|p| p.display().to_string())
+                                .collect::<Vec<_>>()
+                        );
+                        clean_up_after_run(&weak_clone, &state_clone);
+                    }
 
-            // reverse=true means Descending (Z-A, 9-0)
-            // reverse=false means Ascending (A-Z, 0-9)
-            (ascending, !ascending)
-        };
+                    let final_status = format!(
+                        "Extraction complete: {} successful, {} failed, {} skipped",
+                        format_count(result.successful as u64),
+                        format_count(result.failed as u64),
+                        format_count(result.skipped as u64)
+                    );
+                    run_after_actions(&weak_clone, after_run, &final_status, true);
 
-        // Sort entries in state
-        {
-            let mut app_state = state.lock();
-            app_state.file_entries.sort_by(sort_by, reverse);
-        }
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak_clone.upgrade() {
+                            advance_phase(&ui, &state_clone, PhaseEvent::Completed);
+                            ui.set_status_text(SharedString::from(final_status));
 
-        // Update UI
-        let state_clone = Arc::clone(&state);
-        let weak_clone = weak.clone();
-        let _ = slint::invoke_from_event_loop(move || {
-            if let Some(ui) = weak_clone.upgrade() {
-                // Update sort indicators
-                ui.set_sort_column(column);
-                ui.set_sort_ascending(new_ascending);
+                            // Phase 2.3: Show "Open Folder" button after successful extraction
+                            if result.successful > 0 {
+                                ui.set_extraction_complete(true);
+                                ui.set_extraction_folder(SharedString::from(extraction_path));
+                            }
 
-                let row_data: Vec<FileRowData> = {
-                    let app_state = state_clone.lock();
-                    app_state
-                        .file_entries
-                        .entries()
-                        .iter()
-                        .map(|e| FileRowData {
-                            file_name: SharedString::from(&e.file_name),
-                            file_size: SharedString::from(e.size_display()),
-                            num_files: SharedString::from(e.file_count_display()),
-                            mod_name: SharedString::from(e.mod_display()),
-                            is_bad: e.is_corrupted(),
-                        })
-                        .collect()
-                }; // Lock dropped here before UI update
+                            // One explanation per cause instead of every per-file error
+                            if let Some(abort) = &result.aborted {
+                                show_batch_aborted_dialog(&ui, abort);
+                            } else if result.failed > 0 {
+                                show_failure_diagnosis(&ui, &diagnose(&result));
+                            }
 
-                ui.set_file_list(ModelRc::new(VecModel::from(row_data)));
-            }
-        });
-    });
-}
+                            if let Some(advice) = exclusion_advice {
+                                show_toast_with_action(
+                                    &ui,
+                                    &ToastData::warning(
+                                        "Microsoft Defender scans slowed the extraction down",
+                                    ),
+                                    "How to Fix",
+                                    move |ui| {
+                                        show_dialog(
+                                            ui,
+                                            DialogConfig::warning(
+                                                "Speed Up Extraction",
+                                                advice.to_text(),
+                                            ),
+                                        );
+                                    },
+                                );
+                            }
+                        }
+                    });
+                }
+                Ok(Err(e)) => {
+                    let error_msg = format!("Extraction failed: {e}");
+                    tracing::error!("{}", error_msg);
+                    run_after_actions(&weak_clone, after_run, &error_msg, false);
+                    clean_up_after_run(&weak_clone, &state_clone);
 
-/// Set up extraction control callbacks (Phase 2.3)
-fn setup_extraction_control_callbacks(
-    main_window: &MainWindow,
-    extraction_control: &Arc<Mutex<ExtractionControlState>>,
-) {
-    // Pause extraction
-    {
-        let extraction_control_clone = Arc::clone(extraction_control);
-        main_window.on_pause_extraction(move || {
-            tracing::info!("Pause extraction requested");
-            let ctrl_state = extraction_control_clone.lock();
-            if let Some(tx) = &ctrl_state.control_tx {
-                if let Err(e) = tx.send(ExtractionControl::Pause) {
-                    tracing::error!("Failed to send pause signal: {}", e);
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak_clone.upgrade() {
+                            advance_phase(&ui, &state_clone, PhaseEvent::Completed);
+                            ui.set_status_text(SharedString::from(error_msg));
+                        }
+                    });
                 }
-            } else {
-                tracing::warn!("No active extraction to pause");
-            }
-        });
-    }
+                Err(e) if e.is_cancelled() => {
+                    tracing::info!("Extraction cancelled by user");
+                    // Before the archives it was extracting are marked as skipped
+                    clean_up_after_run(&weak_clone, &state_clone);
+                    state_clone.lock().file_entries.skip_pending();
 
-    // Resume extraction
-    {
-        let extraction_control_clone = Arc::clone(extraction_control);
-        main_window.on_resume_extraction(move || {
-            tracing::info!("Resume extraction requested");
-            let ctrl_state = extraction_control_clone.lock();
-            if let Some(tx) = &ctrl_state.control_tx {
-                if let Err(e) = tx.send(ExtractionControl::Resume) {
-                    tracing::error!("Failed to send resume signal: {}", e);
+                    let state = Arc::clone(&state_clone);
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak_clone.upgrade() {
+                            advance_phase(&ui, &state, PhaseEvent::Completed);
+                            ui.set_status_text(SharedString::from("Extraction cancelled"));
+                            refresh_file_table(&ui, &state, current_threshold(&ui));
+                        }
+                    });
+                }
+                Err(e) => {
+                    let reset = reset_extraction(Arc::clone(&state_clone));
+                    recover_from_panic(&weak_clone, "Extraction", reset, e);
                 }
-            } else {
-                tracing::warn!("No active extraction to resume");
             }
         });
+}
+
+/// Leave the extracting state after a run broke off, skipping the archives it
+/// didn't get to
+fn reset_extraction(state: Arc<Mutex<AppState>>) -> impl FnOnce(&MainWindow) + Send + 'static {
+    move |ui| {
+        state.lock().file_entries.skip_pending();
+        advance_phase(ui, &state, PhaseEvent::Failed);
+        refresh_file_table(ui, &state, current_threshold(ui));
     }
+}
 
-    // Cancel extraction
-    {
-        let extraction_control_clone = Arc::clone(extraction_control);
-        main_window.on_cancel_extraction(move || {
-            tracing::info!("Cancel extraction requested");
-            let ctrl_state = extraction_control_clone.lock();
-            if let Some(tx) = &ctrl_state.control_tx {
-                if let Err(e) = tx.send(ExtractionControl::Cancel) {
-                    tracing::error!("Failed to send cancel signal: {}", e);
-                }
+/// Clear the busy state of a background task that broke off
+fn fail_phase(state: Arc<Mutex<AppState>>) -> impl FnOnce(&MainWindow) + Send + 'static {
+    move |ui| {
+        advance_phase(ui, &state, PhaseEvent::Failed);
+    }
+}
+
+/// Check that the app's phase accepts `event`, telling the user why not otherwise
+///
+/// Buttons may still be enabled for a moment after the phase changed, e.g.
+/// Extract right after Scan was clicked.
+fn phase_accepts(ui: &MainWindow, state: &Arc<Mutex<AppState>>, event: PhaseEvent) -> bool {
+    let phase = state.lock().phase;
+    if phase.accepts(event) {
+        return true;
+    }
+    tracing::warn!("Ignoring {:?} in phase {:?}", event, phase);
+    show_toast(ui, &ToastData::warning(phase.refusal()));
+    false
+}
+
+/// Move the app to the phase `event` leads to and show it in the window
+///
+/// Returns false, changing nothing, if the current phase doesn't accept `event`.
+fn advance_phase(ui: &MainWindow, state: &Arc<Mutex<AppState>>, event: PhaseEvent) -> bool {
+    let phase = {
+        let mut app_state = state.lock();
+        let Some(next) = app_state.phase.next(event) else {
+            tracing::warn!("Ignoring {:?} in phase {:?}", event, app_state.phase);
+            return false;
+        };
+        app_state.phase = next;
+        next
+    };
+    ui.set_scanning(phase.is_scanning());
+    ui.set_extracting(phase.is_extracting());
+    ui.set_paused(phase.is_paused());
+    true
+}
+
+/// Run a background task on the global runtime, recovering the window if it panics
+///
+/// A panicking task would leave the window in the busy state it set, e.g.
+/// scanning, for good; see [`recover_from_panic`].
+fn spawn_guarded(
+    weak: &slint::Weak<MainWindow>,
+    task: &'static str,
+    reset: impl FnOnce(&MainWindow) + Send + 'static,
+    future: impl Future<Output = ()> + Send + 'static,
+) {
+    let runtime = crate::get_runtime();
+    let handle = runtime.spawn(future);
+    let weak = weak.clone();
+    runtime.spawn(async move {
+        if let Err(e) = handle.await {
+            recover_from_panic(&weak, task, reset, e);
+        }
+    });
+}
+
+/// Clear the busy state of the background task `task` with `reset` after it
+/// panicked, and show the panic with its crash report
+///
+/// Cancelled tasks are left to their owners.
+fn recover_from_panic(
+    weak: &slint::Weak<MainWindow>,
+    task: &'static str,
+    reset: impl FnOnce(&MainWindow) + Send + 'static,
+    error: tokio::task::JoinError,
+) {
+    let payload = match error.try_into_panic() {
+        Ok(payload) => payload,
+        Err(e) => {
+            tracing::warn!("{} task ended early: {}", task, e);
+            return;
+        }
+    };
+    let report = crash::take_report(payload.as_ref());
+    tracing::error!("{} task panicked: {}", task, report.message);
+
+    let weak = weak.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(ui) = weak.upgrade() {
+            reset(&ui);
+            ui.set_status_text(SharedString::from(format!("{task} failed unexpectedly")));
+            show_crash_report(&ui, task, &report);
+        }
+    });
+}
+
+/// Show a panic that stopped the background task `task`
+fn show_crash_report(ui: &MainWindow, task: &str, report: &CrashReport) {
+    let mut message = format!("{task} stopped because of an internal error:\n\n{}", report.message);
+    if let Some(location) = &report.location {
+        let _ = write!(message, "\n({location})");
+    }
+    let title = format!("{task} failed unexpectedly");
+    let Some(path) = report.path.clone() else {
+        show_dialog(ui, DialogConfig::error(title, message));
+        return;
+    };
+    let _ = write!(
+        message,
+        "\n\nA crash report was saved to {}. Please attach it when reporting the problem.",
+        path.display()
+    );
+    show_confirmation(
+        ui,
+        DialogConfig::error(title, message)
+            .with_primary_button("Open Crash Report")
+            .with_secondary_button("Close"),
+        move |_| {
+            if let Err(e) = open::that(&path) {
+                tracing::error!("Failed to open {}: {}", path.display(), e);
+            }
+        },
+    );
+}
+
+/// Notify the user and shut down after a run, as chosen for it
+///
+/// Only runs that got through their archives (`completed`) shut the computer
+/// down; a shutdown can still be cancelled during [`SHUTDOWN_GRACE`].
+fn run_after_actions(
+    weak: &slint::Weak<MainWindow>,
+    after_run: AfterRun,
+    summary: &str,
+    completed: bool,
+) {
+    let shutdown = after_run.shutdown && completed;
+    if !after_run.notify && !shutdown {
+        return;
+    }
+
+    // The system tools take a moment to start, so keep them off the progress task
+    let weak = weak.clone();
+    let summary = summary.to_string();
+    tokio::task::spawn_blocking(move || {
+        if after_run.notify
+            && let Err(e) = notify_desktop("Unpackrr", &summary)
+        {
+            tracing::warn!("Failed to show a desktop notification: {}", e);
+        }
+        if !shutdown {
+            return;
+        }
+
+        let scheduled = schedule_shutdown(SHUTDOWN_GRACE);
+        match &scheduled {
+            Ok(()) => tracing::info!("Shutting down in {} seconds", SHUTDOWN_GRACE.as_secs()),
+            Err(e) => tracing::error!("Failed to schedule a shutdown: {}", e),
+        }
+        let _ = slint::invoke_from_event_loop(move || {
+            let Some(ui) = weak.upgrade() else {
+                return;
+            };
+            match scheduled {
+                Ok(()) => show_confirmation(
+                    &ui,
+                    DialogConfig::warning(
+                        "Shutting Down",
+                        format!(
+                            "The extraction finished, so the computer shuts down in {}.",
+                            format_duration(SHUTDOWN_GRACE.as_secs())
+                        ),
+                    )
+                    .with_primary_button("Cancel Shutdown")
+                    .with_secondary_button("Shut Down"),
+                    |ui| match cancel_shutdown() {
+                        Ok(()) => show_toast(ui, &ToastData::info("Shutdown cancelled")),
+                        Err(e) => show_toast(
+                            ui,
+                            &ToastData::error(format!("Could not cancel the shutdown: {e}")),
+                        ),
+                    },
+                ),
+                Err(e) => show_toast(
+                    &ui,
+                    &ToastData::error(format!("Could not shut the computer down: {e}")),
+                ),
+            }
+        });
+    });
+}
+
+/// The saved configuration with the run options applied
+fn effective_config(app_state: &AppState) -> crate::error::Result<EffectiveConfig> {
+    EffectiveConfig::new(&app_state.config, &app_state.run_overrides)
+}
+
+/// Reopen the Run options flyout with the reason its options no longer apply
+fn show_run_options_error(ui: &MainWindow, error: &Error) {
+    tracing::warn!("Invalid run options: {}", error);
+    ui.set_run_options_error(SharedString::from(error.user_message()));
+    ui.set_show_run_options(true);
+}
+
+/// Empty the Run options flyout
+fn reset_run_options(ui: &MainWindow) {
+    ui.set_run_postfixes(SharedString::new());
+    ui.set_run_ignored(SharedString::new());
+    ui.set_run_output_path(SharedString::new());
+    ui.set_run_backup(0);
+    ui.set_run_jobs(SharedString::new());
+    ui.set_run_texture_mips(0);
+    ui.set_run_options_error(SharedString::new());
+    ui.set_run_options_active(false);
+}
+
+/// Read the Run options flyout; empty fields keep the saved setting
+fn run_overrides(ui: &MainWindow) -> std::result::Result<RunOverrides, String> {
+    let list = |text: SharedString| -> Option<Vec<String>> {
+        let items: Vec<String> = text
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        (!items.is_empty()).then_some(items)
+    };
+    let output_path = ui.get_run_output_path().trim().to_string();
+    let jobs = ui.get_run_jobs();
+    let max_jobs = match jobs.trim() {
+        "" => None,
+        jobs => Some(
+            jobs.parse::<usize>()
+                .map_err(|_| format!("Parallel jobs must be a whole number, not '{jobs}'"))?,
+        ),
+    };
+
+    Ok(RunOverrides {
+        postfixes: list(ui.get_run_postfixes()),
+        // Typed like the untyped lists of older configurations
+        ignored_files: list(ui.get_run_ignored())
+            .map(|patterns| patterns.into_iter().map(IgnorePattern::migrate).collect()),
+        extraction_path: (!output_path.is_empty()).then_some(output_path),
+        auto_backup: match ui.get_run_backup() {
+            1 => Some(true),
+            2 => Some(false),
+            _ => None,
+        },
+        max_jobs,
+        texture_skip_mips: u8::try_from(ui.get_run_texture_mips() - 1).ok(),
+    })
+}
+
+/// Set up the Run options flyout, which overrides settings for the next extraction only
+fn setup_run_options_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    {
+        let weak = main_window.as_weak();
+        let state = Arc::clone(state);
+        main_window.on_run_options_applied(move || {
+            let Some(ui) = weak.upgrade() else {
+                return;
+            };
+            let overrides = match run_overrides(&ui) {
+                Ok(overrides) => overrides,
+                Err(message) => {
+                    ui.set_run_options_error(SharedString::from(message));
+                    return;
+                }
+            };
+
+            // Validate against the saved settings, which stay untouched
+            let mut app_state = state.lock();
+            if let Err(e) = EffectiveConfig::new(&app_state.config, &overrides) {
+                ui.set_run_options_error(SharedString::from(e.user_message()));
+                return;
+            }
+            let active = !overrides.is_empty();
+            app_state.run_overrides = overrides;
+            drop(app_state);
+
+            ui.set_run_options_error(SharedString::new());
+            ui.set_run_options_active(active);
+            ui.set_show_run_options(false);
+            if active {
+                show_toast(
+                    &ui,
+                    &ToastData::info("Run options set for the next extraction - scan again to list the archives they add"),
+                );
+            }
+        });
+    }
+
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+    main_window.on_run_options_cleared(move || {
+        state.lock().run_overrides = RunOverrides::default();
+        if let Some(ui) = weak.upgrade() {
+            reset_run_options(&ui);
+        }
+    });
+}
+
+/// Set up the note editor flyout: a note and tags on an archive or its whole mod
+fn setup_note_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    {
+        let weak = main_window.as_weak();
+        let state = Arc::clone(state);
+        main_window.on_note_scope_changed(move |scope| {
+            if let Some(ui) = weak.upgrade() {
+                show_note(&ui, &state, scope);
+            }
+        });
+    }
+
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+    main_window.on_note_saved(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let note = Note::parse(&ui.get_note_text(), &ui.get_note_tags());
+        let Some(key) = note_key(state.lock().note_target.as_ref(), ui.get_note_scope()) else {
+            return;
+        };
+        state.lock().set_note(key, note);
+
+        // Each save writes the notes as they are when it starts, without
+        // holding the state lock during the write
+        let state_clone = Arc::clone(&state);
+        std::thread::spawn(move || {
+            let notes = state_clone.lock().notes.clone();
+            if let Err(e) = notes.save() {
+                tracing::error!("Failed to save notes: {}", e);
+            }
+        });
+
+        ui.set_show_note_editor(false);
+        refresh_file_table(&ui, &state, current_threshold(&ui));
+    });
+}
+
+/// Key of the note in the editor: the archive's, or its mod's for scope 1
+fn note_key(target: Option<&(String, String)>, scope: i32) -> Option<String> {
+    let (dir_name, file_name) = target?;
+    Some(if scope == 1 {
+        dir_name.clone()
+    } else {
+        ModNotes::archive_key(dir_name, file_name)
+    })
+}
+
+/// Fill the note editor with the stored note of the archive or its mod
+fn show_note(ui: &MainWindow, state: &Arc<Mutex<AppState>>, scope: i32) {
+    let (note, tags) = {
+        let app_state = state.lock();
+        let Some(key) = note_key(app_state.note_target.as_ref(), scope) else {
+            return;
+        };
+        (app_state.notes.get(&key).cloned().unwrap_or_default(), app_state.notes.tags())
+    };
+    ui.set_note_scope(scope);
+    ui.set_note_text(SharedString::from(note.text.as_str()));
+    ui.set_note_tags(SharedString::from(note.tags_text()));
+    ui.set_note_tags_in_use(SharedString::from(tags.join(", ")));
+}
+
+/// Set up sort callback
+fn setup_sort_callback(main_window: &MainWindow, state: Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+
+    main_window.on_sort_by_column(move |column| {
+        let sort_by = match column {
+            0 => SortBy::Name,
+            1 => SortBy::Size,
+            2 => SortBy::FileCount,
+            3 => SortBy::ModName,
+            4 => SortBy::Status,
+            5 => SortBy::LoadOrder,
+            6 => SortBy::Note,
+            _ => return,
+        };
+
+        // Determine sort direction
+        let (new_ascending, reverse) = {
+            let mut app_state = state.lock();
+
+            let ascending = if app_state.sort_column == column {
+                !app_state.sort_ascending
             } else {
-                tracing::warn!("No active extraction to cancel");
+                // Default sort order for new column:
+                // Size (1) and FileCount (2) default to Descending (Largest/Most first)
+                // Status (4) defaults to Descending (Failed/Skipped first)
+                // Name (0), ModName (3), LoadOrder (5) and Note (6) default to Ascending
+                // (A-Z, first loaded first, noted archives first)
+                !matches!(column, 1 | 2 | 4)
+            };
+
+            app_state.sort_column = column;
+            app_state.sort_ascending = ascending;
+            drop(app_state);
+
+            // reverse=true means Descending (Z-A, 9-0)
+            // reverse=false means Ascending (A-Z, 0-9)
+            (ascending, !ascending)
+        };
+
+        // Sort entries in state
+        {
+            let mut app_state = state.lock();
+            app_state.file_entries.sort_by(sort_by, reverse);
+        }
+
+        // Update UI
+        let state_clone = Arc::clone(&state);
+        let weak_clone = weak.clone();
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(ui) = weak_clone.upgrade() {
+                // Update sort indicators
+                ui.set_sort_column(column);
+                ui.set_sort_ascending(new_ascending);
+
+                refresh_file_table(&ui, &state_clone, current_threshold(&ui));
+            }
+        });
+    });
+}
+
+/// Set up the failed-only, corrupted-only, show-ignored and note filter callbacks
+fn setup_failed_filter_callback(main_window: &MainWindow, state: Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+
+    let state_clone = Arc::clone(&state);
+    main_window.on_failed_filter_toggled(move |enabled| {
+        tracing::debug!("Failed-only filter toggled: {}", enabled);
+        if let Some(ui) = weak.upgrade() {
+            refresh_file_table(&ui, &state_clone, current_threshold(&ui));
+        }
+    });
+
+    // Quarantine view: only corrupted archives, with their reasons and actions
+    let weak = main_window.as_weak();
+    let state_clone = Arc::clone(&state);
+    main_window.on_corrupted_filter_toggled(move |enabled| {
+        tracing::debug!("Corrupted-only filter toggled: {}", enabled);
+        if let Some(ui) = weak.upgrade() {
+            refresh_file_table(&ui, &state_clone, current_threshold(&ui));
+        }
+    });
+
+    // Archives hidden by the ignore list, greyed out so they can be unignored
+    let weak = main_window.as_weak();
+    let state_clone = Arc::clone(&state);
+    main_window.on_show_ignored_toggled(move |enabled| {
+        tracing::debug!("Show-ignored toggled: {}", enabled);
+        if let Some(ui) = weak.upgrade() {
+            refresh_file_table(&ui, &state_clone, current_threshold(&ui));
+        }
+    });
+
+    // Archives whose note or tags contain the typed text
+    let weak = main_window.as_weak();
+    main_window.on_note_filter_changed(move |text| {
+        tracing::debug!("Note filter changed: {}", text);
+        if let Some(ui) = weak.upgrade() {
+            refresh_file_table(&ui, &state, current_threshold(&ui));
+        }
+    });
+}
+
+/// Set up "Export List" callback (writes the visible table to CSV/JSON)
+fn setup_export_callback(main_window: &MainWindow, state: Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+
+    main_window.on_export_list(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+
+        let entries = visible_entries(&ui, &state);
+        let source_folder = ui.get_selected_folder().to_string();
+        let (metrics, archive_limit) = {
+            let app_state = state.lock();
+            let collect = app_state.config.advanced.diagnostics.collect_metrics;
+            (
+                app_state.metrics.latest().filter(|_| collect).cloned(),
+                app_state.config.profile().archive_limit,
+            )
+        };
+        let weak_clone = weak.clone();
+
+        std::thread::spawn(move || {
+            let Some(path) = rfd::FileDialog::new()
+                .add_filter("CSV", &["csv"])
+                .add_filter("JSON", &["json"])
+                .add_filter("Markdown summary", &["md"])
+                .add_filter("BBCode summary", &["txt"])
+                .set_file_name("ba2-list.csv")
+                .save_file()
+            else {
+                tracing::debug!("Export dialog canceled by user");
+                return;
+            };
+
+            let format = ExportFormat::from_path(&path);
+            let result = export_entries(
+                &entries,
+                &source_folder,
+                &path,
+                format,
+                metrics.as_ref(),
+                archive_limit,
+            );
+            let archives = format!("{} archives", format_count(entries.len() as u64));
+            let exported = if format.is_summary() {
+                format!("a summary of {archives}")
+            } else {
+                archives
+            };
+            let toast = match result {
+                Ok(()) => ToastData::success(format!("Exported {exported} to {}", path.display())),
+                Err(e) => {
+                    tracing::error!("Failed to export list: {}", e);
+                    ToastData::error(format!("Export failed: {}", e.user_message()))
+                }
+            };
+
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = weak_clone.upgrade() {
+                    show_toast(&ui, &toast);
+                }
+            });
+        });
+    });
+}
+
+/// Set up "Import List" and "Clear selection" callbacks
+///
+/// Importing reads a JSON list written by "Export List", matches it against
+/// the current scan and selects the matches for extraction.
+fn setup_import_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state_clone = Arc::clone(state);
+
+    main_window.on_import_list(move || {
+        let weak_clone = weak.clone();
+        let state = Arc::clone(&state_clone);
+
+        std::thread::spawn(move || {
+            let Some(path) = rfd::FileDialog::new()
+                .add_filter("JSON", &["json"])
+                .pick_file()
+            else {
+                tracing::debug!("Import dialog canceled by user");
+                return;
+            };
+
+            let document = match load_export_document(&path) {
+                Ok(document) => document,
+                Err(e) => {
+                    tracing::error!("Failed to import list: {}", e);
+                    let toast = ToastData::error(format!("Import failed: {}", e.user_message()));
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak_clone.upgrade() {
+                            show_toast(&ui, &toast);
+                        }
+                    });
+                    return;
+                }
+            };
+
+            // Matching may hash archives, so keep it off the UI thread
+            let report = {
+                let app_state = state.lock();
+                match_entries(&document, app_state.file_entries.entries())
+            };
+            let matched: HashSet<PathBuf> = report.matched.iter().cloned().collect();
+
+            let _ = slint::invoke_from_event_loop(move || {
+                let Some(ui) = weak_clone.upgrade() else {
+                    return;
+                };
+
+                let selected = state.lock().file_entries.select_paths(&matched);
+                ui.set_marked_count(selected.try_into().unwrap_or(i32::MAX));
+                refresh_file_table(&ui, &state, current_threshold(&ui));
+
+                tracing::info!(
+                    "Imported list: {} of {} archives matched",
+                    selected,
+                    report.total()
+                );
+                show_toast(
+                    &ui,
+                    &ToastData::success(format!(
+                        "Selected {} of {} archives for extraction",
+                        format_count(selected as u64),
+                        format_count(report.total() as u64)
+                    )),
+                );
+
+                if !report.unmatched.is_empty() {
+                    let mut message = format!(
+                        "{} archives from the list were not found in the scanned folder:\n",
+                        format_count(report.unmatched.len() as u64)
+                    );
+                    for entry in report.unmatched.iter().take(REPORT_MAX_LISTED_PATHS) {
+                        let _ = write!(message, "\n{}", entry.relative_path);
+                    }
+                    if report.unmatched.len() > REPORT_MAX_LISTED_PATHS {
+                        let _ = write!(
+                            message,
+                            "\n... and {} more",
+                            report.unmatched.len() - REPORT_MAX_LISTED_PATHS
+                        );
+                    }
+                    show_dialog(&ui, DialogConfig::warning("Some archives were not found", message));
+                }
+            });
+        });
+    });
+
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+    main_window.on_clear_marked(move || {
+        state.lock().file_entries.clear_selection();
+        if let Some(ui) = weak.upgrade() {
+            ui.set_marked_count(0);
+            refresh_file_table(&ui, &state, current_threshold(&ui));
+        }
+    });
+}
+
+/// Set up "Recommend", which offers to select the archives worth unpacking
+///
+/// The recommendation brings the loaded archives under the engine limit with
+/// the fewest loose files; accepting it replaces the current selection.
+fn setup_recommendation_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+
+    main_window.on_recommend_archives(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let recommendation = {
+            let app_state = state.lock();
+            let Some(limit) = app_state.config.profile().archive_limit else {
+                show_toast(
+                    &ui,
+                    &ToastData::info(format!(
+                        "Nothing to unpack: {} has no archive limit",
+                        app_state.config.game
+                    )),
+                );
+                return;
+            };
+            recommend(
+                app_state.file_entries.entries(),
+                app_state.load_order.as_ref(),
+                limit,
+            )
+        };
+
+        if !recommendation.is_needed() {
+            show_toast(
+                &ui,
+                &ToastData::info(format!(
+                    "Nothing to unpack: {} of {} archives load",
+                    format_count(recommendation.loaded as u64),
+                    format_count(recommendation.limit as u64)
+                )),
+            );
+            return;
+        }
+        if recommendation.archives.is_empty() {
+            show_dialog(
+                &ui,
+                DialogConfig::warning(
+                    "No archives to recommend",
+                    "All archives over the limit are corrupted or installed by the Wabbajack modlist.",
+                ),
+            );
+            return;
+        }
+
+        let state = Arc::clone(&state);
+        let message = recommendation_message(&recommendation);
+        show_confirmation(
+            &ui,
+            DialogConfig::confirm("Recommended archives", message)
+                .with_primary_button("Apply recommendation")
+                .with_secondary_button("Cancel"),
+            move |ui| {
+                let paths: HashSet<PathBuf> = recommendation.archives.into_iter().collect();
+                let selected = state.lock().file_entries.select_paths(&paths);
+                ui.set_marked_count(selected.try_into().unwrap_or(i32::MAX));
+                refresh_file_table(ui, &state, current_threshold(ui));
+                show_toast(
+                    ui,
+                    &ToastData::success(format!(
+                        "Selected {} recommended archives for extraction",
+                        format_count(selected as u64)
+                    )),
+                );
+            },
+        );
+    });
+}
+
+/// Describe a recommendation for the confirmation dialog
+fn recommendation_message(recommendation: &Recommendation) -> String {
+    let count = recommendation.archives.len();
+    let mut message = format!(
+        "Unpack these {} archives to get under the limit of {} with the least loose files \
+         ({} files, {}):\n",
+        format_count(count as u64),
+        format_count(recommendation.limit as u64),
+        format_count(recommendation.loose_files),
+        format_size(recommendation.total_size)
+    );
+    for path in recommendation.archives.iter().take(REPORT_MAX_LISTED_PATHS) {
+        let name = path.file_name().unwrap_or(path.as_os_str());
+        let _ = write!(message, "\n{}", name.to_string_lossy());
+    }
+    if count > REPORT_MAX_LISTED_PATHS {
+        let _ = write!(message, "\n... and {} more", count - REPORT_MAX_LISTED_PATHS);
+    }
+    if recommendation.shortfall() > 0 {
+        let _ = write!(
+            message,
+            "\n\n{} archives remain over the limit: corrupted and modlist archives are not recommended.",
+            format_count(recommendation.shortfall() as u64)
+        );
+    }
+    message
+}
+
+/// Set up "Downloads", which counts the archives pending mod downloads add
+///
+/// Only lists what is inside the `.zip` and `.7z` files of the chosen folder;
+/// nothing is unpacked. The folder is remembered for the next time.
+fn setup_downloads_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+
+    main_window.on_preview_downloads(move || {
+        let weak = weak.clone();
+        let state = Arc::clone(&state);
+        let last_folder = state.lock().config.saved.downloads_path.clone();
+
+        std::thread::spawn(move || {
+            let mut dialog =
+                rfd::FileDialog::new().set_title("Choose the folder of your mod downloads");
+            if Path::new(&last_folder).is_dir() {
+                dialog = dialog.set_directory(&last_folder);
+            }
+            let Some(folder) = dialog.pick_folder() else {
+                tracing::debug!("Downloads dialog canceled by user");
+                return;
+            };
+            let path = folder.to_string_lossy().into_owned();
+            update_saved_config(&state, move |saved| {
+                let changed = saved.downloads_path != path;
+                saved.downloads_path = path;
+                changed
+            });
+
+            let status_weak = weak.clone();
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = status_weak.upgrade() {
+                    ui.set_status_text(SharedString::from("Looking inside downloads..."));
+                }
+            });
+            let result = preview_downloads(&folder, locate_seven_zip().as_deref());
+            let _ = slint::invoke_from_event_loop(move || {
+                let Some(ui) = weak.upgrade() else {
+                    return;
+                };
+                ui.set_status_text(SharedString::from("Ready"));
+                match result {
+                    Ok(preview) if preview.scanned == 0 => show_toast(
+                        &ui,
+                        &ToastData::info(format!("No .zip or .7z files in {}", folder.display())),
+                    ),
+                    Ok(preview) => show_downloads_preview(&ui, &state, &preview),
+                    Err(e) => {
+                        tracing::error!("Failed to look inside downloads: {}", e);
+                        show_toast(
+                            &ui,
+                            &ToastData::error(format!(
+                                "Could not read the downloads folder: {}",
+                                e.user_message()
+                            )),
+                        );
+                    }
+                }
+            });
+        });
+    });
+}
+
+/// Show the archives pending downloads add, and what they do to the archive limit
+fn show_downloads_preview(
+    ui: &MainWindow,
+    state: &Arc<Mutex<AppState>>,
+    preview: &DownloadsPreview,
+) {
+    let added = preview.archive_count();
+    let mut message = format!(
+        "{} of {} downloads add {} archives ({}).",
+        format_count(preview.downloads.len() as u64),
+        format_count(preview.scanned as u64),
+        format_count(added as u64),
+        format_size(preview.total_size())
+    );
+
+    // Compared with the archives the game loads now, as scanned
+    let budget = {
+        let app_state = state.lock();
+        app_state.config.profile().archive_limit.map(|limit| {
+            let loaded = simulate(
+                app_state.file_entries.entries(),
+                app_state.load_order.as_ref(),
+                limit,
+                |_| false,
+            )
+            .archives_after;
+            (loaded, limit)
+        })
+    };
+    let over_limit = budget.is_some_and(|(loaded, limit)| loaded + added > limit);
+    if let Some((loaded, limit)) = budget {
+        let _ = write!(
+            message,
+            "\n\nThe game loads {} of {} archives now, {} once these are installed{}.",
+            format_count(loaded as u64),
+            format_count(limit as u64),
+            format_count((loaded + added) as u64),
+            if over_limit { ", over the limit" } else { "" }
+        );
+    }
+
+    message.push('\n');
+    for download in preview.downloads.iter().take(REPORT_MAX_LISTED_PATHS) {
+        let names: Vec<&str> = download
+            .archives
+            .iter()
+            .map(PendingArchive::file_name)
+            .collect();
+        let _ = write!(
+            message,
+            "\n{}: {}",
+            download
+                .download
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy(),
+            names.join(", ")
+        );
+    }
+    if preview.downloads.len() > REPORT_MAX_LISTED_PATHS {
+        let _ = write!(
+            message,
+            "\n... and {} more",
+            format_count((preview.downloads.len() - REPORT_MAX_LISTED_PATHS) as u64)
+        );
+    }
+    if !preview.unreadable.is_empty() {
+        let _ = write!(
+            message,
+            "\n\nCould not look inside {} downloads:",
+            format_count(preview.unreadable.len() as u64)
+        );
+        for (path, reason) in preview.unreadable.iter().take(REPORT_MAX_LISTED_PATHS) {
+            let _ = write!(
+                message,
+                "\n{}: {reason}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            );
+        }
+    }
+
+    let dialog = if over_limit {
+        DialogConfig::warning("Pending downloads", message)
+    } else {
+        DialogConfig::info("Pending downloads", message)
+    };
+    show_dialog(ui, dialog);
+}
+
+/// Set up "Compare", which compares the contents of two archives
+///
+/// Typically used on the old and new version of a mod's archive. The
+/// differences can be exported to CSV from the result dialog.
+fn setup_compare_callback(main_window: &MainWindow) {
+    let weak = main_window.as_weak();
+
+    main_window.on_compare_archives(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let folder = PathBuf::from(ui.get_selected_folder().as_str());
+        let weak = weak.clone();
+
+        std::thread::spawn(move || {
+            let pick = |title: &str, dir: &Path| {
+                let mut dialog = rfd::FileDialog::new()
+                    .set_title(title)
+                    .add_filter("BA2 archive", &["ba2"]);
+                if dir.is_dir() {
+                    dialog = dialog.set_directory(dir);
+                }
+                dialog.pick_file()
+            };
+            let Some(old) = pick("Choose the old archive", &folder) else {
+                tracing::debug!("Compare dialog canceled by user");
+                return;
+            };
+            let Some(new) = pick("Choose the new archive", old.parent().unwrap_or(&folder)) else {
+                tracing::debug!("Compare dialog canceled by user");
+                return;
+            };
+
+            let result = compare_archives(&old, &new, true);
+            let _ = slint::invoke_from_event_loop(move || {
+                let Some(ui) = weak.upgrade() else {
+                    return;
+                };
+                match result {
+                    Ok(diff) => show_archive_diff(&ui, &old, &new, diff),
+                    Err(e) => {
+                        tracing::error!("Failed to compare archives: {}", e);
+                        show_dialog(
+                            &ui,
+                            DialogConfig::error("Comparison failed", e.user_message()),
+                        );
+                    }
+                }
+            });
+        });
+    });
+}
+
+/// Show the differences between two archives, offering to export them
+fn show_archive_diff(ui: &MainWindow, old: &Path, new: &Path, diff: ArchiveDiff) {
+    let name = |path: &Path| {
+        path.file_name()
+            .unwrap_or(path.as_os_str())
+            .to_string_lossy()
+            .into_owned()
+    };
+    let title = format!("{} vs. {}", name(old), name(new));
+    if diff.is_identical() {
+        show_dialog(
+            ui,
+            DialogConfig::success(
+                title,
+                format!(
+                    "Both archives contain the same {} files.",
+                    format_count(diff.unchanged as u64)
+                ),
+            ),
+        );
+        return;
+    }
+
+    let mut message = format!(
+        "{} added, {} removed, {} changed, {} unchanged.\n",
+        format_count(diff.count(ChangeKind::Added) as u64),
+        format_count(diff.count(ChangeKind::Removed) as u64),
+        format_count(diff.count(ChangeKind::Changed) as u64),
+        format_count(diff.unchanged as u64)
+    );
+    for change in diff.changes.iter().take(REPORT_MAX_LISTED_PATHS) {
+        let _ = write!(message, "\n{}: {}", change.kind, change.path);
+    }
+    if diff.changes.len() > REPORT_MAX_LISTED_PATHS {
+        let _ = write!(
+            message,
+            "\n... and {} more",
+            diff.changes.len() - REPORT_MAX_LISTED_PATHS
+        );
+    }
+
+    let file_name = format!(
+        "{}-diff.csv",
+        new.file_stem()
+            .map_or_else(|| "archive".into(), |stem| stem.to_string_lossy())
+    );
+    show_confirmation(
+        ui,
+        DialogConfig::info(title, message)
+            .with_primary_button("Export...")
+            .with_secondary_button("Close"),
+        move |ui| {
+            let weak = ui.as_weak();
+            let csv = diff.to_csv();
+            std::thread::spawn(move || {
+                let Some(path) = rfd::FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name(&file_name)
+                    .save_file()
+                else {
+                    tracing::debug!("Diff export dialog canceled by user");
+                    return;
+                };
+                let toast = match std::fs::write(&path, csv) {
+                    Ok(()) => ToastData::success(format!(
+                        "Exported differences to {}",
+                        path.display()
+                    )),
+                    Err(e) => {
+                        tracing::error!("Failed to export differences: {}", e);
+                        ToastData::error(format!("Export failed: {e}"))
+                    }
+                };
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(ui) = weak.upgrade() {
+                        show_toast(&ui, &toast);
+                    }
+                });
+            });
+        },
+    );
+}
+
+/// Set up "Load Modlist" and the own-mods filter
+///
+/// The loaded modlist is remembered and loaded again at the next start.
+fn setup_modlist_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state_clone = Arc::clone(state);
+
+    main_window.on_load_modlist(move || {
+        let weak = weak.clone();
+        let state = Arc::clone(&state_clone);
+
+        std::thread::spawn(move || {
+            let Some(path) = rfd::FileDialog::new()
+                .add_filter("Wabbajack modlist", &["wabbajack"])
+                .add_filter("All files", &["*"])
+                .pick_file()
+            else {
+                tracing::debug!("Modlist dialog canceled by user");
+                return;
+            };
+
+            match ModlistIndex::load(&path) {
+                Ok(modlist) => {
+                    let path = path.to_string_lossy().into_owned();
+                    update_saved_config(&state, move |saved| {
+                        let changed = saved.modlist_path != path;
+                        saved.modlist_path = path;
+                        changed
+                    });
+                    apply_modlist(&weak, &state, modlist, true);
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load modlist: {}", e);
+                    let toast =
+                        ToastData::error(format!("Could not load modlist: {}", e.user_message()));
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak.upgrade() {
+                            show_toast(&ui, &toast);
+                        }
+                    });
+                }
+            }
+        });
+    });
+
+    let weak = main_window.as_weak();
+    let state_clone = Arc::clone(state);
+    main_window.on_own_mods_filter_toggled(move |enabled| {
+        tracing::debug!("Own-mods filter toggled: {}", enabled);
+        if let Some(ui) = weak.upgrade() {
+            refresh_file_table(&ui, &state_clone, current_threshold(&ui));
+        }
+    });
+
+    // Load the modlist of the previous session quietly
+    let saved_path = state.lock().config.saved.modlist_path.clone();
+    if !saved_path.is_empty() {
+        let weak = main_window.as_weak();
+        let state = Arc::clone(state);
+        std::thread::spawn(move || match ModlistIndex::load(&host_path(&saved_path)) {
+            Ok(modlist) => apply_modlist(&weak, &state, modlist, false),
+            Err(e) => tracing::warn!("Could not load saved modlist {}: {}", saved_path, e),
+        });
+    }
+}
+
+/// Store a loaded modlist and tag the scanned archives it installs
+fn apply_modlist(
+    weak: &slint::Weak<MainWindow>,
+    state: &Arc<Mutex<AppState>>,
+    modlist: ModlistIndex,
+    notify: bool,
+) {
+    let (name, listed) = (modlist.name.clone(), modlist.len());
+    let tagged = state.lock().set_modlist(modlist);
+
+    let weak = weak.clone();
+    let state = Arc::clone(state);
+    let _ = slint::invoke_from_event_loop(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        ui.set_modlist_count(tagged.try_into().unwrap_or(i32::MAX));
+        refresh_file_table(&ui, &state, current_threshold(&ui));
+        if notify {
+            let toast = if listed == 0 {
+                ToastData::warning(format!("Modlist '{name}' does not install any BA2 archives"))
+            } else {
+                ToastData::success(format!(
+                    "{} scanned archives belong to modlist '{name}'",
+                    format_count(tagged as u64)
+                ))
+            };
+            show_toast(&ui, &toast);
+        }
+    });
+}
+
+/// Set up "Load Order" to choose the plugins.txt or loadorder.txt to use
+///
+/// Without a chosen file, the game's own plugins.txt is read at every scan.
+fn setup_load_order_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+
+    main_window.on_load_plugin_list(move || {
+        let weak = weak.clone();
+        let state = Arc::clone(&state);
+
+        std::thread::spawn(move || {
+            let mut dialog = rfd::FileDialog::new()
+                .add_filter("Plugin list", &["txt"])
+                .add_filter("All files", &["*"]);
+            let profile = state.lock().config.profile();
+            if let Some(dir) = LoadOrder::default_path(profile)
+                .as_deref()
+                .and_then(Path::parent)
+                .filter(|dir| dir.is_dir())
+            {
+                dialog = dialog.set_directory(dir);
+            }
+            let Some(path) = dialog.pick_file() else {
+                tracing::debug!("Plugin list dialog canceled by user");
+                return;
+            };
+
+            let toast = match LoadOrder::load(&path, profile) {
+                Ok(load_order) => {
+                    let path = path.to_string_lossy().into_owned();
+                    update_saved_config(&state, move |saved| {
+                        let changed = saved.plugins_path != path;
+                        saved.plugins_path = path;
+                        changed
+                    });
+
+                    let active = load_order.len();
+                    let matched = state.lock().set_load_order(Some(load_order));
+                    ToastData::success(format!(
+                        "{} scanned archives matched to {} active plugins",
+                        format_count(matched as u64),
+                        format_count(active as u64)
+                    ))
+                }
+                Err(e) => {
+                    tracing::error!("Failed to load plugin list: {}", e);
+                    ToastData::error(format!("Could not load plugin list: {}", e.user_message()))
+                }
+            };
+
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = weak.upgrade() {
+                    refresh_file_table(&ui, &state, current_threshold(&ui));
+                    show_toast(&ui, &toast);
+                }
+            });
+        });
+    });
+}
+
+/// Switch the game profile from the sidebar selector
+fn setup_game_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+
+    main_window.on_game_changed(move |index| {
+        let Some(&game) = usize::try_from(index).ok().and_then(|i| Game::ALL.get(i)) else {
+            return;
+        };
+        let weak = weak.clone();
+        let state = Arc::clone(&state);
+
+        std::thread::spawn(move || {
+            let postfixes = {
+                let mut app_state = state.lock();
+                let previous = app_state.config.extraction.postfixes.clone();
+                if !app_state.config.set_game(game) {
+                    return;
+                }
+                if let Err(e) = app_state.config.save() {
+                    tracing::error!("Failed to save configuration: {}", e);
+                }
+                (app_state.config.extraction.postfixes != previous)
+                    .then(|| app_state.config.extraction.postfixes.join(", "))
+            };
+            tracing::info!("Game changed to {}", game);
+
+            // The plugin list and expected archive versions differ per game, as
+            // does the Proton prefix on Linux
+            set_wine_prefix_from_config(&state.lock().config);
+            let load_order = read_load_order(&state);
+            state.lock().set_load_order(load_order);
+
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = weak.upgrade() {
+                    if let Some(postfixes) = &postfixes {
+                        ui.set_settings_postfixes(SharedString::from(postfixes.as_str()));
+                    }
+                    refresh_file_table(&ui, &state, current_threshold(&ui));
+                    update_dashboard(&ui, &state);
+                    let message = if postfixes.is_some() {
+                        format!("Switched to {game} - scan again to use its default archives")
+                    } else {
+                        format!("Switched to {game}")
+                    };
+                    show_toast(&ui, &ToastData::info(message));
+                }
+            });
+        });
+    });
+}
+
+/// Read the chosen plugin list, or the game's plugins.txt if none was chosen
+fn read_load_order(state: &Arc<Mutex<AppState>>) -> Option<LoadOrder> {
+    let (saved_path, profile) = {
+        let config = &state.lock().config;
+        (config.saved.plugins_path.clone(), config.profile())
+    };
+    if saved_path.is_empty() {
+        let path = LoadOrder::default_path(profile).filter(|path| path.is_file())?;
+        return LoadOrder::load(&path, profile)
+            .inspect_err(|e| tracing::debug!("Could not read {}: {}", path.display(), e))
+            .ok();
+    }
+    LoadOrder::load(&host_path(&saved_path), profile)
+        .inspect_err(|e| tracing::warn!("Could not load plugin list {}: {}", saved_path, e))
+        .ok()
+}
+
+/// Get the entries currently shown in the table, in display order
+fn visible_entries(ui: &MainWindow, state: &Arc<Mutex<AppState>>) -> Vec<FileEntry> {
+    let order: std::collections::HashMap<PathBuf, usize> = ui
+        .get_file_list()
+        .iter()
+        .enumerate()
+        .map(|(index, row)| (PathBuf::from(row.path.as_str()), index))
+        .collect();
+
+    let mut visible: Vec<(usize, FileEntry)> = state
+        .lock()
+        .file_entries
+        .entries()
+        .iter()
+        .filter_map(|e| order.get(&e.full_path).map(|&index| (index, e.clone())))
+        .collect();
+    visible.sort_unstable_by_key(|(index, _)| *index);
+    visible.into_iter().map(|(_, e)| e).collect()
+}
+
+/// Update an archive's extraction status in state and in the visible table,
+/// with its extraction time once it finished
+fn set_row_status(
+    weak: &slint::Weak<MainWindow>,
+    state: &Arc<Mutex<AppState>>,
+    path: &Path,
+    status: ExtractionStatus,
+    time: Option<Duration>,
+) {
+    let failed_count = {
+        let mut app_state = state.lock();
+        app_state.file_entries.set_status(path, status);
+        if let Some(time) = time {
+            app_state.file_entries.set_extraction_time(path, time);
+        }
+        app_state.file_entries.failed_count()
+    };
+    let time = time.map(format_elapsed);
+
+    let weak = weak.clone();
+    let path = path.to_string_lossy().to_string();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(ui) = weak.upgrade() {
+            ui.set_failed_count(failed_count.try_into().unwrap_or(i32::MAX));
+
+            let rows = ui.get_file_list();
+            for i in 0..rows.row_count() {
+                if let Some(mut row) = rows.row_data(i)
+                    && row.path.as_str() == path
+                {
+                    row.status = row_status(status);
+                    if let Some(time) = &time {
+                        row.extraction_time = SharedString::from(time);
+                    }
+                    rows.set_row_data(i, row);
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Set up extraction control callbacks (Phase 2.3)
+fn setup_extraction_control_callbacks(
+    main_window: &MainWindow,
+    state: &Arc<Mutex<AppState>>,
+    extraction_control: &Arc<Mutex<ExtractionControlState>>,
+) {
+    // Pause extraction
+    {
+        let weak = main_window.as_weak();
+        let state = Arc::clone(state);
+        let extraction_control_clone = Arc::clone(extraction_control);
+        main_window.on_pause_extraction(move || {
+            tracing::info!("Pause extraction requested");
+            let Some(ui) = weak.upgrade() else {
+                return;
+            };
+            if !phase_accepts(&ui, &state, PhaseEvent::Pause) {
+                return;
+            }
+            let control_tx = extraction_control_clone.lock().control_tx.clone();
+            let sent = control_tx.map_or_else(
+                || {
+                    tracing::warn!("No active extraction to pause");
+                    false
+                },
+                |tx| {
+                    tx.send(ExtractionControl::Pause)
+                        .inspect_err(|e| tracing::error!("Failed to send pause signal: {}", e))
+                        .is_ok()
+                },
+            );
+            if sent {
+                advance_phase(&ui, &state, PhaseEvent::Pause);
+            }
+        });
+    }
+
+    // Resume extraction
+    {
+        let weak = main_window.as_weak();
+        let state = Arc::clone(state);
+        let extraction_control_clone = Arc::clone(extraction_control);
+        main_window.on_resume_extraction(move || {
+            tracing::info!("Resume extraction requested");
+            let Some(ui) = weak.upgrade() else {
+                return;
+            };
+            if !phase_accepts(&ui, &state, PhaseEvent::Resume) {
+                return;
+            }
+            let control_tx = extraction_control_clone.lock().control_tx.clone();
+            let sent = control_tx.map_or_else(
+                || {
+                    tracing::warn!("No active extraction to resume");
+                    false
+                },
+                |tx| {
+                    tx.send(ExtractionControl::Resume)
+                        .inspect_err(|e| tracing::error!("Failed to send resume signal: {}", e))
+                        .is_ok()
+                },
+            );
+            if sent {
+                advance_phase(&ui, &state, PhaseEvent::Resume);
+            }
+        });
+    }
+
+    // Cancel extraction
+    {
+        let weak = main_window.as_weak();
+        let state = Arc::clone(state);
+        let extraction_control_clone = Arc::clone(extraction_control);
+        main_window.on_cancel_extraction(move || {
+            tracing::info!("Cancel extraction requested");
+            if let Some(ui) = weak.upgrade()
+                && !phase_accepts(&ui, &state, PhaseEvent::Cancel)
+            {
+                return;
+            }
+            let ctrl_state = extraction_control_clone.lock();
+            // Stops retry waits right away, even while the progress loop is paused
+            if let Some(token) = &ctrl_state.cancel_token {
+                token.cancel();
+            }
+            if let Some(tx) = &ctrl_state.control_tx {
+                if let Err(e) = tx.send(ExtractionControl::Cancel) {
+                    tracing::error!("Failed to send cancel signal: {}", e);
+                }
+            } else {
+                tracing::warn!("No active extraction to cancel");
+            }
+        });
+    }
+
+    // Notify / shut down when the running extraction finishes
+    {
+        let extraction_control_clone = Arc::clone(extraction_control);
+        main_window.on_after_run_changed(move |action, enabled| {
+            tracing::info!("Post-run {} set to {}", action, enabled);
+            let mut ctrl_state = extraction_control_clone.lock();
+            match action.as_str() {
+                "notify" => ctrl_state.after_run.notify = enabled,
+                "shutdown" => ctrl_state.after_run.shutdown = enabled,
+                _ => tracing::warn!("Unknown post-run action: {}", action),
+            }
+        });
+    }
+
+    // Switching priority applies to the running extraction, not the saved setting
+    main_window.on_low_priority_toggled(priority::set_low_priority);
+}
+
+/// Set up "Run Diagnostics" (environment self-test with a saveable report)
+fn setup_diagnostics_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+
+    main_window.on_run_diagnostics(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        show_toast(&ui, &ToastData::info("Running diagnostics..."));
+
+        let folder = ui.get_selected_folder().to_string();
+        let paths = SelfTestPaths {
+            config_dir: AppConfig::config_dir().ok(),
+            log_dir: crate::logging::get_log_dir().ok(),
+            scan_folder: (!folder.is_empty()).then(|| PathBuf::from(folder)),
+        };
+        let config = state.lock().config.clone();
+        let weak = weak.clone();
+        crate::get_runtime().spawn(async move {
+            let report = self_test(&config, &paths).await;
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = weak.upgrade() {
+                    show_self_test_report(&ui, &report);
+                }
+            });
+        });
+    });
+}
+
+/// Set up "Clean Up Leftovers", which removes what interrupted runs left next
+/// to the scanned archives
+fn setup_cleanup_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+    main_window.on_settings_clean_up_leftovers(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let (archives, config) = {
+            let app_state = state.lock();
+            let archives: Vec<PathBuf> = app_state
+                .file_entries
+                .entries()
+                .iter()
+                .map(|entry| entry.full_path.clone())
+                .collect();
+            (archives, app_state.config.clone())
+        };
+        if config.advanced.analysis_only {
+            show_toast(
+                &ui,
+                &ToastData::warning("Cleanup is disabled in analysis-only mode"),
+            );
+            return;
+        }
+        if archives.is_empty() {
+            show_toast(&ui, &ToastData::info("Scan a folder first"));
+            return;
+        }
+
+        ui.set_status_text(SharedString::from("Looking for leftovers..."));
+        let weak = ui.as_weak();
+        std::thread::spawn(move || {
+            let leftovers = find_leftovers(&archives);
+            let _ = slint::invoke_from_event_loop(move || {
+                let Some(ui) = weak.upgrade() else {
+                    return;
+                };
+                ui.set_status_text(SharedString::from("Ready"));
+                if leftovers.is_empty() {
+                    show_toast(&ui, &ToastData::info("No leftovers found"));
+                    return;
+                }
+                confirm_leftover_removal(&ui, leftovers, config, false);
+            });
+        });
+    });
+}
+
+/// Offer to remove what the archives a cancelled or failed run was extracting left behind
+///
+/// Only looks at archives that failed or were still being extracted. If
+/// something was left, a toast offers to review it, so the dialogs about the
+/// run stay open.
+fn clean_up_after_run(weak: &slint::Weak<MainWindow>, state: &Arc<Mutex<AppState>>) {
+    let (archives, config) = {
+        let app_state = state.lock();
+        let archives: Vec<PathBuf> = app_state
+            .file_entries
+            .entries()
+            .iter()
+            .filter(|entry| {
+                matches!(
+                    entry.status,
+                    ExtractionStatus::Failed | ExtractionStatus::Extracting
+                )
+            })
+            .map(|entry| entry.full_path.clone())
+            .collect();
+        (archives, app_state.config.clone())
+    };
+    if archives.is_empty() || config.advanced.analysis_only {
+        return;
+    }
+
+    let weak = weak.clone();
+    std::thread::spawn(move || {
+        let leftovers = find_leftovers(&archives);
+        if leftovers.is_empty() {
+            return;
+        }
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(ui) = weak.upgrade() {
+                show_toast_with_action(
+                    &ui,
+                    &ToastData::warning(format!(
+                        "The interrupted run left {} leftovers",
+                        format_count(leftovers.len() as u64)
+                    )),
+                    "Review",
+                    move |ui| confirm_leftover_removal(ui, leftovers, config, true),
+                );
+            }
+        });
+    });
+}
+
+/// Ask before removing `leftovers`, listing them
+fn confirm_leftover_removal(
+    ui: &MainWindow,
+    leftovers: Vec<Leftover>,
+    config: AppConfig,
+    after_run: bool,
+) {
+    let (title, question, description) = if after_run {
+        (
+            "Clean up after the interrupted run?",
+            "The interrupted run left",
+            "Clean up after an interrupted run",
+        )
+    } else {
+        (
+            "Clean up leftovers?",
+            "Interrupted runs left",
+            "Clean up leftovers",
+        )
+    };
+    let message = leftover_removal_message(question, &leftovers);
+
+    show_confirmation(
+        ui,
+        DialogConfig::confirm(title, message)
+            .with_primary_button("Remove")
+            .with_secondary_button("Keep"),
+        move |ui| {
+            if !after_run {
+                ui.set_status_text(SharedString::from("Removing leftovers..."));
+            }
+            let weak = ui.as_weak();
+            std::thread::spawn(move || {
+                remove_leftovers(&weak, &leftovers, &config, description, after_run);
+            });
+        },
+    );
+}
+
+/// Remove `leftovers` as one journal operation and report what was removed
+///
+/// After a run, the report is a toast; otherwise a dialog listing what went.
+fn remove_leftovers(
+    weak: &slint::Weak<MainWindow>,
+    leftovers: &[Leftover],
+    config: &AppConfig,
+    description: &str,
+    after_run: bool,
+) {
+    let journal = OperationJournal::open_default();
+    let mut operation = journal.as_ref().map(|j| j.begin(description));
+    let result = clean_leftovers(leftovers, config, operation.as_mut());
+    commit_operation(journal.as_ref(), operation);
+
+    let weak = weak.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        refresh_last_operation(&ui);
+        if !after_run {
+            ui.set_status_text(SharedString::from("Ready"));
+        }
+        match result {
+            Ok(report) if after_run => {
+                let toast = if report.failed.is_empty() {
+                    ToastData::info(format!(
+                        "Removed {} leftovers of the interrupted run",
+                        format_count(report.removed.len() as u64)
+                    ))
+                } else {
+                    ToastData::warning(format!(
+                        "Could not remove {} leftovers of the interrupted run; use Clean Up Leftovers in Settings to retry",
+                        format_count(report.failed.len() as u64)
+                    ))
+                };
+                show_toast(&ui, &toast);
+            }
+            Ok(report) => {
+                let summary = report.summary(REPORT_MAX_LISTED_PATHS);
+                let dialog = if report.failed.is_empty() {
+                    DialogConfig::success("Leftovers removed", summary)
+                } else {
+                    DialogConfig::warning("Leftovers partially removed", summary)
+                };
+                show_dialog(&ui, dialog);
+            }
+            Err(e) => {
+                tracing::error!("Failed to clean up leftovers: {}", e);
+                show_toast(&ui, &ToastData::error(e.user_message()));
+            }
+        }
+    });
+}
+
+/// Entries of the audit log in a saved diagnostics report
+const REPORT_AUDIT_ENTRIES: usize = 100;
+
+/// Entries of the audit log shown in Settings
+const VIEWED_AUDIT_ENTRIES: usize = 50;
+
+/// Set up "View Audit Log", which lists the latest changes to mod folders
+fn setup_audit_log_callback(main_window: &MainWindow) {
+    let weak = main_window.as_weak();
+    main_window.on_settings_view_audit_log(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let Some(audit) = AuditLog::open_default() else {
+            show_toast(&ui, &ToastData::error("No audit log is available"));
+            return;
+        };
+        let entries = audit.recent(VIEWED_AUDIT_ENTRIES);
+        if entries.is_empty() {
+            show_toast(&ui, &ToastData::info("No changes recorded yet"));
+            return;
+        }
+        let message = format!(
+            "Latest {} changes, newest first. Open the file for the full history.\n\n{}",
+            format_count(entries.len() as u64),
+            audit_text(&entries)
+        );
+        let path = audit.path().to_path_buf();
+        show_confirmation(
+            &ui,
+            DialogConfig::info("Audit Log", message)
+                .with_primary_button("Open File")
+                .with_secondary_button("Close"),
+            move |ui| {
+                if let Err(e) = open::that(&path) {
+                    tracing::error!("Failed to open {}: {}", path.display(), e);
+                    show_toast(ui, &ToastData::error(format!("Could not open the audit log: {e}")));
+                }
+            },
+        );
+    });
+}
+
+/// Record changes to archives in the audit log
+fn audit_paths(action: AuditAction, paths: &[PathBuf]) {
+    if let Some(audit) = AuditLog::open_default() {
+        for path in paths {
+            audit.record_or_warn(&AuditEntry::new(action, path.display().to_string()));
+        }
+    }
+}
+
+/// Show the self-test results with an option to save them for a support request
+///
+/// The saved report also lists the latest changes from the audit log.
+fn show_self_test_report(ui: &MainWindow, report: &SelfTestReport) {
+    let text = report.to_text();
+    let dialog = if report.count(CheckStatus::Fail) > 0 {
+        DialogConfig::error("Diagnostics", text.clone())
+    } else if report.count(CheckStatus::Warning) > 0 {
+        DialogConfig::warning("Diagnostics", text.clone())
+    } else {
+        DialogConfig::success("Diagnostics", text.clone())
+    };
+    show_confirmation(
+        ui,
+        dialog
+            .with_primary_button("Save Report...")
+            .with_secondary_button("Close"),
+        move |ui| {
+            let weak = ui.as_weak();
+            std::thread::spawn(move || {
+                let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Text", &["txt"])
+                    .set_file_name("unpackrr-diagnostics.txt")
+                    .save_file()
+                else {
+                    tracing::debug!("Diagnostics report dialog canceled by user");
+                    return;
+                };
+                let mut text = text;
+                if let Some(audit) = AuditLog::open_default() {
+                    let entries = audit.recent(REPORT_AUDIT_ENTRIES);
+                    if !entries.is_empty() {
+                        text.push_str("\n\nRecent changes, newest first:\n");
+                        text.push_str(&audit_text(&entries));
+                    }
+                }
+                let toast = match std::fs::write(&path, text) {
+                    Ok(()) => ToastData::success(format!(
+                        "Saved diagnostics report to {}",
+                        path.display()
+                    )),
+                    Err(e) => {
+                        tracing::error!("Failed to save diagnostics report: {}", e);
+                        ToastData::error(format!("Saving the report failed: {e}"))
+                    }
+                };
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(ui) = weak.upgrade() {
+                        show_toast(&ui, &toast);
+                    }
+                });
+            });
+        },
+    );
+}
+
+/// Set up update checker callback (Phase 2.6)
+fn setup_update_checker_callback(main_window: &MainWindow) {
+    let weak = main_window.as_weak();
+
+    main_window.on_check_for_updates(move || {
+        let weak_clone = weak.clone();
+
+        tracing::info!("User requested update check");
+
+        // Show toast notification that we're checking
+        if let Some(ui) = weak.upgrade() {
+            show_toast(&ui, &ToastData::info("Checking for updates..."));
+        }
+
+        // Run update check in background task using global runtime
+        crate::get_runtime().spawn(async move {
+            match crate::update_checker::check_for_updates().await {
+                    Ok(Some(update_info)) => {
+                        // Update available
+                        tracing::info!(
+                            "Update available: {} -> {}",
+                            update_info.current_version,
+                            update_info.latest_version
+                        );
+
+                        let download_url = update_info.download_url.clone();
+
+                        // Open the download page in the browser immediately
+                        if let Err(e) = open::that(&download_url) {
+                            tracing::error!("Failed to open browser: {}", e);
+                        }
+
+                        let message = format!(
+                            "Update available!\n\nCurrent version: {}\nLatest version: {}\n\nOpening download page in your browser...\n\n{}",
+                            update_info.current_version,
+                            update_info.latest_version,
+                            if update_info.release_notes.len() > 200 {
+                                format!("{}...", &update_info.release_notes[..200])
+                            } else {
+                                update_info.release_notes.clone()
+                            }
+                        );
+
+                        let _ = slint::invoke_from_event_loop(move || {
+                            if let Some(ui) = weak_clone.upgrade() {
+                                show_dialog(&ui, DialogConfig {
+                                    title: "Update Available".to_string(),
+                                    message,
+                                    dialog_type: NotificationType::Success,
+                                    primary_button: "OK".to_string(),
+                                    secondary_button: None,
+                                    checkbox: None,
+                                });
+                            }
+                        });
+                    }
+                    Ok(None) => {
+                        // Already up to date
+                        tracing::info!("Already running the latest version");
+
+                        let _ = slint::invoke_from_event_loop(move || {
+                            if let Some(ui) = weak_clone.upgrade() {
+                                show_toast(&ui, &ToastData::success("You're running the latest version!"));
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        // Error checking for updates
+                        tracing::error!("Failed to check for updates: {}", e);
+
+                        let error_msg = format!("Failed to check for updates: {e}");
+                        let _ = slint::invoke_from_event_loop(move || {
+                            if let Some(ui) = weak_clone.upgrade() {
+                                show_toast(&ui, &ToastData::error(error_msg));
+                            }
+                        });
+                    }
+                }
+            });
+    });
+}
+
+/// Set up the external BA2 tool setting
+///
+/// Shows the configured tool, lets the user pick another one and validates
+/// the tool in use at startup and whenever the path changes. A picked tool
+/// that is not a BA2 unpacker is rejected instead of saved.
+fn setup_external_tool_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    main_window.set_settings_external_tool(SharedString::from(
+        state.lock().config.advanced.ext_ba2_exe.clone(),
+    ));
+    detect_tool_version(main_window.as_weak(), Arc::clone(state));
+
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+    main_window.on_settings_browse_external_tool(move || {
+        let weak = weak.clone();
+        let state = Arc::clone(&state);
+
+        std::thread::spawn(move || {
+            let mut dialog = rfd::FileDialog::new().set_title("Select BA2 Tool");
+            if cfg!(windows) {
+                dialog = dialog.add_filter("Executable", &["exe"]);
+            }
+            let Some(path) = dialog.pick_file() else {
+                return;
+            };
+            tracing::info!("User selected external BA2 tool: {}", path.display());
+
+            let version = match crate::get_runtime().block_on(validate_ba2_tool(&path)) {
+                Ok(version) => version,
+                Err(e) => {
+                    tracing::warn!("Rejected external BA2 tool {}: {}", path.display(), e);
+                    let message = std::iter::once(e.user_message())
+                        .chain(e.recovery_suggestions().iter().map(|s| format!("• {s}")))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak.upgrade() {
+                            show_dialog(&ui, DialogConfig::error("Invalid BA2 Tool", message));
+                        }
+                    });
+                    return;
+                }
+            };
+
+            let path_str = path.to_string_lossy().to_string();
+            {
+                let mut app_state = state.lock();
+                app_state.config.advanced.ext_ba2_exe.clone_from(&path_str);
+                app_state.bsarch_version = version;
+                if let Err(e) = app_state.config.save() {
+                    tracing::error!("Failed to save configuration: {}", e);
+                }
+            }
+
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = weak.upgrade() {
+                    ui.set_settings_bsarch_location(SharedString::from(format!(
+                        "Using {path_str} ({})",
+                        ToolOrigin::Configured
+                    )));
+                    ui.set_settings_external_tool(SharedString::from(path_str));
+                    ui.set_settings_bsarch_version(SharedString::from(version_text(version)));
+                }
+            });
+        });
+    });
+}
+
+/// Validate the tool in use and detect its version in the background
+///
+/// Stores the version in the app state and shows it in Settings together with
+/// where the tool was found. A configured tool or `.ba2` handler that is not a
+/// BA2 unpacker is reported with a warning toast.
+fn detect_tool_version(weak: slint::Weak<MainWindow>, state: Arc<Mutex<AppState>>) {
+    let tool = locate_bsarch(&state.lock().config);
+    let location = match tool.origin {
+        ToolOrigin::NotFound => format!("{BSARCH_EXE} not found"),
+        origin => format!("Using {} ({origin})", tool.path.display()),
+    };
+    if let Some(ui) = weak.upgrade() {
+        ui.set_settings_bsarch_location(SharedString::from(location));
+    }
+
+    let bsarch_path = tool.path;
+    let validate = tool.origin.needs_validation();
+    crate::get_runtime().spawn(async move {
+        let result = if validate {
+            validate_ba2_tool(&bsarch_path).await
+        } else {
+            detect_bsarch_version(&bsarch_path).await
+        };
+        let (text, warning) = match &result {
+            Ok(version) => (version_text(*version), None),
+            Err(e @ Error::BA2(BA2Error::InvalidTool { .. })) => {
+                tracing::warn!("Configured BA2 tool is invalid: {}", e);
+                ("Not a BA2 unpacker".to_string(), Some(e.user_message()))
+            }
+            Err(e) => {
+                tracing::warn!("BSArch version detection failed: {}", e);
+                ("Tool not found or could not be run".to_string(), None)
+            }
+        };
+        state.lock().bsarch_version = result.ok().flatten();
+
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(ui) = weak.upgrade() {
+                ui.set_settings_bsarch_version(SharedString::from(text));
+                if let Some(warning) = warning {
+                    show_toast(&ui, &ToastData::warning(warning));
+                }
+            }
+        });
+    });
+}
+
+/// Set up the Nexus Mods API key, kept in the credential store of the OS
+fn setup_nexus_key_callbacks(main_window: &MainWindow) {
+    // Show whether a key is stored
+    let weak = main_window.as_weak();
+    std::thread::spawn(move || update_nexus_key(&weak, None));
+
+    let weak = main_window.as_weak();
+    main_window.on_settings_nexus_key_saved(move |key| {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let key = match secrets::parse_api_key(&key) {
+            Ok(key) => key.to_string(),
+            Err(e) => {
+                show_toast(&ui, &ToastData::error(e.user_message()));
+                return;
+            }
+        };
+        let weak = weak.clone();
+        std::thread::spawn(move || {
+            let result = KeyringStore.set(Secret::NexusApiKey, &key);
+            if result.is_ok() {
+                // Kept on failure, so the key can be saved again
+                let weak = weak.clone();
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(ui) = weak.upgrade() {
+                        ui.set_settings_nexus_key_entry(SharedString::new());
+                    }
+                });
+            }
+            let toast = match result {
+                Ok(()) => {
+                    ToastData::success(format!("API key saved to the {}", KeyringStore::name()))
+                }
+                Err(e) => ToastData::error(e.user_message()),
+            };
+            update_nexus_key(&weak, Some(toast));
+        });
+    });
+
+    let weak = main_window.as_weak();
+    main_window.on_settings_nexus_key_cleared(move || {
+        let weak = weak.clone();
+        std::thread::spawn(move || {
+            let toast = match KeyringStore.delete(Secret::NexusApiKey) {
+                Ok(()) => ToastData::info("API key removed"),
+                Err(e) => ToastData::error(e.user_message()),
+            };
+            update_nexus_key(&weak, Some(toast));
+        });
+    });
+
+    let weak = main_window.as_weak();
+    main_window.on_settings_nexus_key_checked(move || {
+        if let Some(ui) = weak.upgrade() {
+            show_toast(&ui, &ToastData::info("Checking the API key with Nexus Mods..."));
+        }
+        let weak = weak.clone();
+        // The credential store blocks, so the key is read on a worker thread
+        std::thread::spawn(move || {
+            let toast = match KeyringStore.get(Secret::NexusApiKey) {
+                Ok(Some(key)) => {
+                    match crate::get_runtime().block_on(crate::nexus::validate_key(&key)) {
+                        Ok(user) => ToastData::success(format!(
+                            "The API key belongs to {}{}",
+                            user.name,
+                            if user.is_premium { " (Premium)" } else { "" }
+                        )),
+                        Err(e) => {
+                            tracing::warn!("API key check failed: {:#}", e);
+                            ToastData::error(format!("{e:#}"))
+                        }
+                    }
+                }
+                Ok(None) => ToastData::warning("No API key is stored"),
+                Err(e) => ToastData::error(e.user_message()),
+            };
+            update_nexus_key(&weak, Some(toast));
+        });
+    });
+}
+
+/// Show whether an API key is stored, with an optional toast
+///
+/// Asks the credential store, which blocks; call it from a worker thread.
+fn update_nexus_key(weak: &slint::Weak<MainWindow>, toast: Option<ToastData>) {
+    let store = KeyringStore::name();
+    let (stored, status) = match KeyringStore.get(Secret::NexusApiKey) {
+        Ok(Some(_)) => (true, format!("A key is stored in the {store}.")),
+        Ok(None) => (
+            false,
+            format!("No key stored. Keys are kept in the {store}, never in config.json."),
+        ),
+        Err(e) => {
+            tracing::warn!("Could not read the API key: {}", e);
+            (false, e.user_message())
+        }
+    };
+    let weak = weak.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(ui) = weak.upgrade() {
+            ui.set_settings_nexus_key_stored(stored);
+            ui.set_settings_nexus_key_status(SharedString::from(status));
+            if let Some(toast) = toast {
+                show_toast(&ui, &toast);
+            }
+        }
+    });
+}
+
+/// Settings caption for a detected tool version
+fn version_text(version: Option<BsarchVersion>) -> String {
+    version.map_or_else(
+        || "Version unknown".to_string(),
+        |version| format!("Detected version: {version}"),
+    )
+}
+
+/// Warn if the scanned archives need a newer `BSArch` than the one detected
+fn warn_if_tool_too_old(ui: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let (version, has_starfield) = {
+        let app_state = state.lock();
+        let has_starfield = app_state.file_entries.entries().iter().any(FileEntry::is_starfield);
+        (app_state.bsarch_version, has_starfield)
+    };
+    let Some(version) = version else {
+        return;
+    };
+    if version.capabilities().starfield || !has_starfield {
+        return;
+    }
+
+    tracing::warn!("BSArch {} is too old for the scanned Starfield archives", version);
+    show_toast(
+        ui,
+        &ToastData::warning(format!(
+            "BSArch {version} cannot unpack Starfield archives. Update to {STARFIELD_MIN_VERSION} or newer; these archives will be skipped."
+        )),
+    );
+}
+
+/// Point out archives of BA2 versions the selected game does not write after a scan
+fn warn_if_unknown_versions(ui: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let app_state = state.lock();
+    let profile = app_state.config.profile();
+    let unknown = app_state
+        .file_entries
+        .entries()
+        .iter()
+        .filter(|e| e.has_unknown_version(profile))
+        .count();
+    let game = app_state.config.game;
+    let allowed = app_state.config.extraction.allow_unknown_versions;
+    drop(app_state);
+    if unknown == 0 {
+        return;
+    }
+
+    tracing::warn!("Found {} archives of unknown BA2 versions", unknown);
+    let message = if allowed {
+        format!(
+            "{} archives use BA2 versions {} does not write and will be extracted anyway",
+            format_count(unknown as u64),
+            game
+        )
+    } else {
+        format!(
+            "{} archives use BA2 versions {} does not write and will be skipped - allow them in Settings to extract anyway",
+            format_count(unknown as u64),
+            game
+        )
+    };
+    show_toast(ui, &ToastData::warning(message));
+}
+
+/// Start the local automation interface if it is enabled
+fn setup_ipc_server(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let allowed = {
+        let automation = &state.lock().config.automation;
+        if !automation.enabled {
+            return;
+        }
+        automation.allowed_commands.clone()
+    };
+
+    let handler = Arc::new(GuiHandler {
+        weak: main_window.as_weak(),
+        state: Arc::clone(state),
+    });
+    crate::get_runtime().spawn(async move {
+        let endpoint = ipc::default_endpoint();
+        if let Err(e) = ipc::serve(&endpoint, allowed, handler).await {
+            tracing::error!("Automation interface stopped: {}", e);
+        }
+    });
+}
+
+/// Carries out automation commands on the main window
+struct GuiHandler {
+    weak: slint::Weak<MainWindow>,
+    state: Arc<Mutex<AppState>>,
+}
+
+impl GuiHandler {
+    /// Run `action` on the UI thread and wait for its reply
+    fn on_ui_thread(
+        &self,
+        action: impl FnOnce(&MainWindow) -> ipc::Response + Send + 'static,
+    ) -> ipc::Response {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let queued = self.weak.upgrade_in_event_loop(move |ui| {
+            let _ = tx.send(action(&ui));
+        });
+        if queued.is_err() {
+            return ipc::Response::error("The application is shutting down");
+        }
+        rx.recv()
+            .unwrap_or_else(|_| ipc::Response::error("The application is shutting down"))
+    }
+}
+
+impl ipc::Handler for GuiHandler {
+    fn handle(&self, request: ipc::Request) -> ipc::Response {
+        match request {
+            ipc::Request::Scan { folder } => {
+                let state = Arc::clone(&self.state);
+                self.on_ui_thread(move |ui| {
+                    if state.lock().phase.is_busy() {
+                        return ipc::Response::error("A scan or extraction is already running");
+                    }
+                    if let Some(folder) = folder {
+                        ui.set_selected_folder(SharedString::from(folder.to_string_lossy().as_ref()));
+                    }
+                    if ui.get_selected_folder().is_empty() {
+                        return ipc::Response::error("No folder selected");
+                    }
+                    ui.invoke_start_scan();
+                    ipc::Response::Ok {
+                        message: format!("Scanning {}", ui.get_selected_folder()),
+                    }
+                })
+            }
+            ipc::Request::List => ipc::Response::Archives {
+                archives: self
+                    .state
+                    .lock()
+                    .file_entries
+                    .entries()
+                    .iter()
+                    .map(ipc::ArchiveInfo::from)
+                    .collect(),
+            },
+            ipc::Request::ExtractSelection { paths } => {
+                if self.state.lock().config.advanced.analysis_only {
+                    return ipc::Response::error("Extraction is disabled in analysis-only mode");
+                }
+                let state = Arc::clone(&self.state);
+                self.on_ui_thread(move |ui| {
+                    if state.lock().phase.is_busy() {
+                        return ipc::Response::error("A scan or extraction is already running");
+                    }
+                    let paths: HashSet<PathBuf> = paths.into_iter().collect();
+                    let selected = {
+                        let mut app_state = state.lock();
+                        let matched = app_state
+                            .file_entries
+                            .entries()
+                            .iter()
+                            .any(|e| paths.contains(&e.full_path));
+                        // Keep the current selection if nothing matches
+                        if !matched {
+                            return ipc::Response::error(
+                                "None of the paths match a scanned archive",
+                            );
+                        }
+                        app_state.file_entries.select_paths(&paths)
+                    };
+                    ui.set_marked_count(selected.try_into().unwrap_or(i32::MAX));
+                    refresh_file_table(ui, &state, current_threshold(ui));
+                    ui.invoke_start_extraction();
+                    ipc::Response::Ok {
+                        message: format!("Extracting {} archives", format_count(selected as u64)),
+                    }
+                })
+            }
+            ipc::Request::Status | ipc::Request::Watch => ipc::Response::Status(
+                ipc::Status::from_entries(self.state.lock().file_entries.entries()),
+            ),
+        }
+    }
+
+    fn events(&self) -> Option<EventBus> {
+        Some(self.state.lock().events.clone())
+    }
+}
+
+/// Set up threshold filtering callbacks (Phase 2.3)
+#[allow(clippy::too_many_lines)] // Multiple threshold UI interactions
+fn setup_threshold_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+
+    // Handle threshold value changes
+    {
+        let state_clone = Arc::clone(state);
+        let weak_clone = weak.clone();
+
+        main_window.on_threshold_changed(move |value| {
+            let value_str = value.to_string();
+
+            if value_str.is_empty() {
+                // Clear threshold - show all files
+                update_saved_config(&state_clone, |saved| {
+                    std::mem::take(&mut saved.threshold) != SizeSpec::default()
+                });
+
+                let weak = weak_clone.clone();
+                let state = Arc::clone(&state_clone);
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(ui) = weak.upgrade() {
+                        refresh_file_table(&ui, &state, None);
+                    }
+                });
+                return;
+            }
+
+            // Parse the threshold value (accepts both KB and KiB notations)
+            match SizeSpec::parse(&value_str).map(SizeSpec::bytes) {
+                Ok(threshold_bytes) => {
+                    tracing::info!("Threshold set to: {} bytes", threshold_bytes);
+
+                    let threshold = SizeSpec::from_bytes(threshold_bytes);
+                    update_saved_config(&state_clone, move |saved| {
+                        std::mem::replace(&mut saved.threshold, threshold) != threshold
+                    });
+
+                    let weak = weak_clone.clone();
+                    let state = Arc::clone(&state_clone);
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak.upgrade() {
+                            refresh_file_table(&ui, &state, Some(threshold_bytes));
+                        }
+                    });
+                }
+                Err(e) => {
+                    tracing::warn!("Invalid threshold value '{}': {}", value_str, e);
+                }
+            }
+        });
+    }
+
+    // Handle auto-threshold toggle
+    {
+        let state_clone = Arc::clone(state);
+        let weak_clone = weak;
+
+        #[allow(clippy::significant_drop_tightening)] // Lock must be held while reading entries
+        main_window.on_auto_threshold_toggled(move |enabled| {
+            update_saved_config(&state_clone, move |saved| {
+                std::mem::replace(&mut saved.auto_threshold, enabled) != enabled
+            });
+
+            if enabled {
+                // Calculate auto-threshold (engine archive limit)
+                let (entries_count, profile, threshold_opt) = {
+                    let app_state = state_clone.lock();
+                    let profile = app_state.config.profile();
+                    // Archives of plugins disabled in the load order never load,
+                    // so they don't count against the limit and need no unpacking
+                    let load_order = app_state.load_order.as_ref();
+                    let loaded: Vec<&FileEntry> = app_state
+                        .file_entries
+                        .entries()
+                        .iter()
+                        .filter(|e| load_order.is_none_or(|order| order.loads(&e.file_name)))
+                        .collect();
+                    let count = loaded.len();
+
+                    match profile.archive_limit {
+                        Some(limit) if count > limit => {
+                            // Get the Nth largest file's size, N = engine limit
+                            let mut sorted_sizes: Vec<u64> = loaded.iter()
+                                .map(|e| e.file_size)
+                                .collect();
+                            sorted_sizes.sort_unstable();
+                            sorted_sizes.reverse();
+
+                            let threshold = sorted_sizes[limit - 1]; // 0-indexed
+                            (count, profile, Some((threshold, limit)))
+                        }
+                        _ => (count, profile, None),
+                    }
+                };
+
+                if let Some((threshold, limit)) = threshold_opt {
+                    // Filter by the value the displayed text parses back to, so
+                    // later refreshes from the input box filter identically
+                    let threshold_str = SizeSpec::from_bytes(threshold).to_string();
+                    let threshold = SizeSpec::parse(&threshold_str).map_or(threshold, SizeSpec::bytes);
+
+                    tracing::info!(
+                        "Auto-threshold calculated: {} ({} bytes) - will keep {} files",
+                        threshold_str,
+                        threshold,
+                        limit
+                    );
+
+                    let weak = weak_clone.clone();
+                    let state = Arc::clone(&state_clone);
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak.upgrade() {
+                            ui.set_threshold_value(SharedString::from(threshold_str.clone()));
+                            refresh_file_table(&ui, &state, Some(threshold));
+
+                            show_toast(
+                                &ui,
+                                &ToastData::success(format!(
+                                    "Auto-threshold set to {threshold_str} (keeping {limit} files)"
+                                )),
+                            );
+                        }
+                    });
+                } else {
+                    tracing::info!("Auto-threshold not needed: only {} files", entries_count);
+
+                    let message = profile.archive_limit.map_or_else(
+                        || format!("Auto-threshold not needed: {} has no archive limit", profile.name),
+                        |limit| format!(
+                            "Auto-threshold not needed: only {entries_count} loaded BA2 files found (limit is {limit})"
+                        ),
+                    );
+                    let weak = weak_clone.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak.upgrade() {
+                            ui.set_auto_threshold(false);
+                            show_toast(&ui, &ToastData::info(message));
+                        }
+                    });
+                }
+            } else {
+                // Auto-threshold disabled - clear threshold
+                let weak = weak_clone.clone();
+                let state = Arc::clone(&state_clone);
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(ui) = weak.upgrade() {
+                        ui.set_threshold_value(SharedString::from(""));
+                        refresh_file_table(&ui, &state, None);
+                    }
+                });
+            }
+        });
+    }
+}
+
+/// Set up file actions callback (Phase 2.3 - ignore/open)
+#[allow(clippy::too_many_lines)] // Multiple file action handlers
+fn setup_file_actions_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+
+    main_window.on_file_action(move |row_index, action| {
+        let action_str = action.to_string();
+        tracing::info!("File action requested: {} for row {}", action_str, row_index);
+
+        match action_str.as_str() {
+            "ignore" | "unignore" => {
+                let Some(ui) = weak.upgrade() else {
+                    return;
+                };
+                let Some(row) = usize::try_from(row_index)
+                    .ok()
+                    .and_then(|idx| ui.get_file_list().row_data(idx))
+                else {
+                    tracing::error!("Invalid row index: {}", row_index);
+                    return;
+                };
+                let paths = [PathBuf::from(row.path.as_str())];
+
+                if action_str == "ignore" {
+                    tracing::info!("Ignoring file: {}", row.file_name);
+                    ignore_and_refresh(&ui, &state, &paths);
+                } else {
+                    tracing::info!("Unignoring file: {}", row.file_name);
+                    unignore_and_refresh(&ui, &state, &paths);
+                }
+            }
+            action if action == "open" || action.starts_with("open:") => {
+                // "open" uses the only (or first) program, "open:<index>" one of the submenu
+                let index = action
+                    .strip_prefix("open:")
+                    .and_then(|index| index.parse::<usize>().ok())
+                    .unwrap_or(0);
+                let (file_name, file_path, tool) = {
+                    let app_state = state.lock();
+                    let entries = app_state.file_entries.entries();
+
+                    let idx = match usize::try_from(row_index) {
+                        Ok(i) if i < entries.len() => i,
+                        _ => {
+                            tracing::error!("Invalid row index: {}", row_index);
+                            return;
+                        }
+                    };
+
+                    let entry = &entries[idx];
+                    (
+                        entry.file_name.clone(),
+                        entry.full_path.clone(),
+                        open_tools(&app_state.config).into_iter().nth(index),
+                    )
+                };
+
+                tracing::info!("Opening BA2 file with external tool: {}", file_path.display());
+
+                // Check if file exists
+                if !file_path.exists() {
+                    tracing::error!("File not found: {}", file_path.display());
+                    let weak_clone = weak.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak_clone.upgrade() {
+                            show_toast(&ui, &ToastData::error(format!("File not found: {file_name}")));
+                        }
+                    });
+                    return;
+                }
+
+                // Check if a program to open archives with is configured or registered
+                let Some(tool) = tool else {
+                    tracing::warn!("No program configured to open archives with");
+                    let weak_clone = weak.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak_clone.upgrade() {
+                            show_toast(
+                                &ui,
+                                &ToastData::warning("No program to open BA2 files with.\nPlease add one under Open With in Settings > Advanced.")
+                                    .with_view(View::Settings),
+                            );
+                        }
+                    });
+                    return;
+                };
+
+                // Launch external tool in background thread
+                let weak_clone = weak.clone();
+                std::thread::spawn(move || {
+                    tracing::info!(
+                        "Launching {}: {} {:?}",
+                        tool.name,
+                        tool.path,
+                        tool.args(&file_path)
+                    );
+
+                    match tool.command(&file_path).spawn() {
+                        Ok(_) => {
+                            tracing::info!("Successfully opened {} with {}", file_name, tool.name);
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to launch {}: {}", tool.name, e);
+                            let error_msg = format!("Failed to open BA2 file with {}:\n{e}", tool.name);
+                            let _ = slint::invoke_from_event_loop(move || {
+                                if let Some(ui) = weak_clone.upgrade() {
+                                    show_toast(&ui, &ToastData::error(error_msg));
+                                }
+                            });
+                        }
+                    }
+                });
+            }
+            "note" => {
+                let Some(ui) = weak.upgrade() else {
+                    return;
+                };
+                let Some(row) = usize::try_from(row_index)
+                    .ok()
+                    .and_then(|idx| ui.get_file_list().row_data(idx))
+                else {
+                    tracing::error!("Invalid row index: {}", row_index);
+                    return;
+                };
+                let path = PathBuf::from(row.path.as_str());
+                let scope = {
+                    let mut app_state = state.lock();
+                    let Some(entry) = app_state
+                        .file_entries
+                        .entries()
+                        .iter()
+                        .chain(app_state.ignored_entries.entries())
+                        .find(|e| e.full_path == path)
+                    else {
+                        return;
+                    };
+                    let target = (entry.dir_name.clone(), entry.file_name.clone());
+                    // Open the archive's own note, or its mod's if only the mod has one
+                    let own = ModNotes::archive_key(&target.0, &target.1);
+                    let scope = i32::from(
+                        app_state.notes.get(&own).is_none()
+                            && app_state.notes.get(&target.0).is_some(),
+                    );
+                    ui.set_note_archive(SharedString::from(format!("{} ({})", target.1, target.0)));
+                    app_state.note_target = Some(target);
+                    scope
+                };
+                show_note(&ui, &state, scope);
+                ui.set_show_note_editor(true);
+            }
+            "verify" => {
+                let Some(ui) = weak.upgrade() else {
+                    return;
+                };
+                let Some(archive) = usize::try_from(row_index)
+                    .ok()
+                    .and_then(|idx| ui.get_file_list().row_data(idx))
+                    .map(|row| PathBuf::from(row.path.as_str()))
+                else {
+                    tracing::error!("Invalid row index: {}", row_index);
+                    return;
+                };
+
+                let weak_clone = weak.clone();
+                std::thread::spawn(move || {
+                    let output_dir = archive.parent().unwrap_or_else(|| Path::new("."));
+                    let manifest = manifest_path(&archive, output_dir);
+                    let file_name = archive
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+
+                    let dialog = if manifest.exists() {
+                        match verify_manifest(&manifest) {
+                            Ok(report) => verify_report_dialog(&file_name, &report),
+                            Err(e) => {
+                                tracing::error!("Manifest verification failed: {}", e);
+                                DialogConfig::error("Verification failed", e.user_message())
+                            }
+                        }
+                    } else {
+                        // Without a manifest, check the loose files against the archive itself
+                        match compare_folder(&archive, output_dir, true) {
+                            Ok(diff) => folder_diff_dialog(&file_name, &diff),
+                            Err(e) => {
+                                tracing::error!("Folder verification failed: {}", e);
+                                DialogConfig::error("Verification failed", e.user_message())
+                            }
+                        }
+                    };
+
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak_clone.upgrade() {
+                            show_dialog(&ui, dialog);
+                        }
+                    });
+                });
+            }
+            "inspect" => {
+                let Some(ui) = weak.upgrade() else {
+                    return;
+                };
+                let Some(archive) = usize::try_from(row_index)
+                    .ok()
+                    .and_then(|idx| ui.get_file_list().row_data(idx))
+                    .map(|row| PathBuf::from(row.path.as_str()))
+                else {
+                    tracing::error!("Invalid row index: {}", row_index);
+                    return;
+                };
+
+                let weak_clone = weak.clone();
+                std::thread::spawn(move || {
+                    let file_name = archive
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    let dialog = match inspect_archive(&archive) {
+                        Ok(report) => inspection_dialog(&file_name, &report),
+                        Err(e) => {
+                            tracing::error!("Inspecting {} failed: {}", archive.display(), e);
+                            DialogConfig::error("Inspection failed", e.user_message())
+                        }
+                    };
+
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak_clone.upgrade() {
+                            show_dialog(&ui, dialog);
+                        }
+                    });
+                });
+            }
+            "preview" => {
+                let Some(ui) = weak.upgrade() else {
+                    return;
+                };
+                let Some(archive) = usize::try_from(row_index)
+                    .ok()
+                    .and_then(|idx| ui.get_file_list().row_data(idx))
+                    .map(|row| row.path)
+                else {
+                    tracing::error!("Invalid row index: {}", row_index);
+                    return;
+                };
+                ui.invoke_texture_preview_open(archive);
+            }
+            "undo" => {
+                let Some(ui) = weak.upgrade() else {
+                    return;
+                };
+                let Some(archive) = usize::try_from(row_index)
+                    .ok()
+                    .and_then(|idx| ui.get_file_list().row_data(idx))
+                    .map(|row| PathBuf::from(row.path.as_str()))
+                else {
+                    tracing::error!("Invalid row index: {}", row_index);
+                    return;
+                };
+
+                let file_name = archive
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let state = Arc::clone(&state);
+                show_confirmation(
+                    &ui,
+                    DialogConfig::confirm(
+                        "Undo extraction?",
+                        format!(
+                            "Delete the loose files extracted from {file_name}?\n\nFiles changed since extraction are kept. The archive is restored from backup if it is missing."
+                        ),
+                    )
+                    .with_primary_button("Undo")
+                    .with_secondary_button("Cancel"),
+                    move |ui| undo_extractions(ui, &state, vec![archive]),
+                );
+            }
+            "quarantine" | "revalidate" | "force" | "modpage" => {
+                let Some(ui) = weak.upgrade() else {
+                    return;
+                };
+                let Some(archive) = usize::try_from(row_index)
+                    .ok()
+                    .and_then(|idx| ui.get_file_list().row_data(idx))
+                    .map(|row| PathBuf::from(row.path.as_str()))
+                else {
+                    tracing::error!("Invalid row index: {}", row_index);
+                    return;
+                };
+                corrupted_archive_action(&ui, &state, &action_str, archive);
+            }
+            "exclusion" => {
+                let Some(ui) = weak.upgrade() else {
+                    return;
+                };
+                let Some(archive) = usize::try_from(row_index)
+                    .ok()
+                    .and_then(|idx| ui.get_file_list().row_data(idx))
+                    .map(|row| PathBuf::from(row.path.as_str()))
+                else {
+                    tracing::error!("Invalid row index: {}", row_index);
+                    return;
+                };
+                toggle_exclusion_override(&ui, &state, archive);
+            }
+            _ => {
+                tracing::warn!("Unknown file action: {}", action_str);
+            }
+        }
+    });
+}
+
+/// Set up picking table rows and the actions run on all picked rows
+fn setup_bulk_actions_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let state = Arc::clone(state);
+
+    // Ctrl/Cmd-click toggles a row, Shift-click picks a range
+    let weak = main_window.as_weak();
+    main_window.on_row_clicked(move |row_index, toggle, range| {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let Ok(row) = usize::try_from(row_index) else {
+            return;
+        };
+        let model = ui.get_file_list();
+        let mut picked: Vec<bool> = model.iter().map(|r| r.selected).collect();
+        let anchor = usize::try_from(ui.get_selected_row()).ok();
+        let anchor = click_rows(&mut picked, anchor, row, toggle, range);
+
+        for (idx, selected) in picked.iter().copied().enumerate() {
+            if let Some(mut data) = model.row_data(idx)
+                && data.selected != selected
+            {
+                data.selected = selected;
+                model.set_row_data(idx, data);
+            }
+        }
+        ui.set_selected_row(anchor.and_then(|a| a.try_into().ok()).unwrap_or(-1));
+        let count = picked.iter().filter(|&&p| p).count();
+        ui.set_selected_count(count.try_into().unwrap_or(i32::MAX));
+    });
+
+    let weak = main_window.as_weak();
+    main_window.on_bulk_action(move |action| {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let rows: Vec<FileRowData> = ui.get_file_list().iter().filter(|r| r.selected).collect();
+        let paths: Vec<PathBuf> = rows.iter().map(|r| PathBuf::from(r.path.as_str())).collect();
+        tracing::info!("Bulk action requested: {} for {} rows", action, rows.len());
+
+        match action.as_str() {
+            "ignore" => {
+                // Ignored rows shown greyed out are already on the list
+                let paths: Vec<PathBuf> = rows
+                    .iter()
+                    .filter(|r| !r.ignored)
+                    .map(|r| PathBuf::from(r.path.as_str()))
+                    .collect();
+                ignore_and_refresh(&ui, &state, &paths);
+            }
+            "extract" => {
+                // The picked rows become the explicit selection of the run
+                let paths: HashSet<PathBuf> = rows
+                    .iter()
+                    .filter(|r| !r.ignored)
+                    .map(|r| PathBuf::from(r.path.as_str()))
+                    .collect();
+                if paths.is_empty() {
+                    return;
+                }
+                let selected = state.lock().file_entries.select_paths(&paths);
+                ui.set_marked_count(selected.try_into().unwrap_or(i32::MAX));
+                refresh_file_table(&ui, &state, current_threshold(&ui));
+                ui.invoke_start_extraction();
+            }
+            "open-folders" => {
+                let mut folders: Vec<&Path> = paths.iter().filter_map(|p| p.parent()).collect();
+                folders.sort_unstable();
+                folders.dedup();
+                for folder in folders {
+                    if let Err(e) = open::that(folder) {
+                        tracing::error!("Failed to open folder {}: {}", folder.display(), e);
+                        show_toast(
+                            &ui,
+                            &ToastData::error(format!("Failed to open {}: {e}", folder.display())),
+                        );
+                        break;
+                    }
+                }
+            }
+            "copy-paths" => {
+                let text = rows.iter().map(|r| r.path.as_str()).collect::<Vec<_>>().join("\n");
+                match copy_to_clipboard(text) {
+                    Ok(()) => show_toast(
+                        &ui,
+                        &ToastData::success(format!("Copied {} paths", format_count(rows.len() as u64))),
+                    ),
+                    Err(e) => {
+                        tracing::error!("Failed to copy paths: {}", e);
+                        show_toast(&ui, &ToastData::error(format!("Could not copy the paths: {e}")));
+                    }
+                }
+            }
+            "clear" => {}
+            _ => {
+                tracing::warn!("Unknown bulk action: {}", action);
+                return;
+            }
+        }
+        clear_picked_rows(&ui);
+    });
+}
+
+/// Unpick all rows of the table
+fn clear_picked_rows(ui: &MainWindow) {
+    let model = ui.get_file_list();
+    for idx in 0..model.row_count() {
+        if let Some(mut data) = model.row_data(idx)
+            && data.selected
+        {
+            data.selected = false;
+            model.set_row_data(idx, data);
+        }
+    }
+    ui.set_selected_row(-1);
+    ui.set_selected_count(0);
+}
+
+/// Put text on the system clipboard
+fn copy_to_clipboard(text: String) -> Result<(), arboard::Error> {
+    arboard::Clipboard::new()?.set_text(text)
+}
+
+/// Warn that a folder is not writable and offer to relaunch elevated
+fn show_elevation_warning(ui: &MainWindow, folder: &str, access: FolderAccess) {
+    let location = access
+        .location
+        .map(|l| format!(" It is inside {}.", l.description()))
+        .unwrap_or_default();
+    let message =
+        format!("Unpackrr cannot write to {folder}, so extraction would fail.{location}");
+
+    if !elevation::can_relaunch_elevated() {
+        show_dialog(
+            ui,
+            DialogConfig::warning(
+                "Folder is not writable",
+                format!("{message}\n\nCheck the folder permissions or move the mods to a writable location."),
+            ),
+        );
+        return;
+    }
+
+    show_confirmation(
+        ui,
+        DialogConfig::confirm(
+            "Administrator rights required",
+            format!("{message}\n\nRelaunch Unpackrr as administrator?"),
+        )
+        .with_primary_button("Relaunch as Administrator")
+        .with_secondary_button("Cancel"),
+        |ui| match elevation::relaunch_elevated() {
+            Ok(()) => {
+                let _ = slint::quit_event_loop();
+            }
+            Err(e) => {
+                tracing::warn!("Failed to relaunch elevated: {}", e);
+                show_toast(ui, &ToastData::error(e.user_message()));
+            }
+        },
+    );
+}
+
+/// Prompt while extraction waits for a disconnected drive to come back
+///
+/// Extraction resumes on its own once the folder is reachable again; the
+/// prompt only offers to give up instead of waiting.
+fn show_drive_disconnected_prompt(ui: &MainWindow, folder: &str) {
+    show_confirmation(
+        ui,
+        DialogConfig::confirm(
+            "Drive disconnected",
+            format!(
+                "{folder} can no longer be reached.\n\nReconnect the drive and extraction \
+                 will continue automatically, or cancel the remaining extractions."
+            ),
+        )
+        .with_primary_button("Cancel Extraction")
+        .with_secondary_button("Keep Waiting"),
+        MainWindow::invoke_cancel_extraction,
+    );
+}
+
+/// Explain why the rest of a batch was not started
+fn show_batch_aborted_dialog(ui: &MainWindow, abort: &BatchAbort) {
+    let mut message = format!(
+        "{} extractions in a row failed, so the remaining {} were not started.\n\n\
+         Most common error: {}",
+        format_count(abort.consecutive_failures as u64),
+        format_count(abort.not_started as u64),
+        abort.cause
+    );
+    if !abort.suggestions.is_empty() {
+        message.push_str("\n\nTry:");
+        for suggestion in &abort.suggestions {
+            message.push_str("\n• ");
+            message.push_str(suggestion);
+        }
+    }
+    show_dialog(ui, DialogConfig::warning("Extraction stopped", message));
+}
+
+/// Explain the failures of a run grouped by cause
+fn show_failure_diagnosis(ui: &MainWindow, diagnosis: &Diagnosis) {
+    let title = match diagnosis.clusters.as_slice() {
+        [only] => format!(
+            "{} archives failed: {}",
+            format_count(diagnosis.failed() as u64),
+            only.kind.title().to_lowercase()
+        ),
+        _ => format!(
+            "{} archives failed for {} reasons",
+            format_count(diagnosis.failed() as u64),
+            diagnosis.clusters.len()
+        ),
+    };
+    show_dialog(ui, DialogConfig::error(title, diagnosis.report()));
+}
+
+/// Set up "Undo Extraction" callback (undoes every archive extracted in the last run)
+fn setup_undo_extraction_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+
+    main_window.on_undo_extraction(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+
+        let archives: Vec<PathBuf> = state
+            .lock()
+            .file_entries
+            .entries()
+            .iter()
+            .filter(|e| e.status == ExtractionStatus::Done)
+            .map(|e| e.full_path.clone())
+            .collect();
+
+        if archives.is_empty() {
+            show_toast(&ui, &ToastData::info("No extracted archives to undo"));
+            return;
+        }
+
+        let state = Arc::clone(&state);
+        show_confirmation(
+            &ui,
+            DialogConfig::confirm(
+                "Undo extraction?",
+                format!(
+                    "Delete the loose files extracted from {} archives?\n\nFiles changed since extraction are kept. Archives are restored from backup if they are missing.",
+                    format_count(archives.len() as u64)
+                ),
+            )
+            .with_primary_button("Undo")
+            .with_secondary_button("Cancel"),
+            move |ui| undo_extractions(ui, &state, archives),
+        );
+    });
+}
+
+/// Set up "Undo", which reverts the last file operation in the journal
+fn setup_undo_operation_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    refresh_last_operation(main_window);
+
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+    main_window.on_undo_last_operation(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        if state.lock().config.advanced.analysis_only {
+            show_toast(&ui, &ToastData::warning("Undo is disabled in analysis-only mode"));
+            return;
+        }
+        let Some(journal) = OperationJournal::open_default() else {
+            show_toast(&ui, &ToastData::error("No operation journal is available"));
+            return;
+        };
+        let Some(last) = journal.last() else {
+            refresh_last_operation(&ui);
+            show_toast(&ui, &ToastData::info("Nothing to undo"));
+            return;
+        };
+
+        let state = Arc::clone(&state);
+        show_confirmation(
+            &ui,
+            DialogConfig::confirm(
+                "Undo last operation?",
+                format!(
+                    "Undo \"{}\"?\n\nFiles it removed or moved are put back where they were.",
+                    last.description
+                ),
+            )
+            .with_primary_button("Undo")
+            .with_secondary_button("Cancel"),
+            move |ui| {
+                let weak = ui.as_weak();
+                std::thread::spawn(move || {
+                    let toast = match journal.undo_last() {
+                        Ok(Some(entry)) => ToastData::success(format!(
+                            "Undid \"{}\". Rescan to see restored archives.",
+                            entry.description
+                        )),
+                        Ok(None) => ToastData::info("Nothing to undo"),
+                        Err(e) => {
+                            tracing::error!("Failed to undo last operation: {}", e);
+                            ToastData::error(e.user_message())
+                        }
+                    };
+
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak.upgrade() {
+                            refresh_last_operation(&ui);
+                            refresh_file_table(&ui, &state, current_threshold(&ui));
+                            show_toast(&ui, &toast);
+                        }
+                    });
+                });
+            },
+        );
+    });
+}
+
+/// Set up "Reorganize...", which moves the files of the last run into the
+/// layout mod managers expect, one previewed strategy at a time
+fn setup_reorganize_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+
+    main_window.on_reorganize_output(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+
+        let (archives, backup_root, analysis_only) = {
+            let app_state = state.lock();
+            let archives: Vec<PathBuf> = app_state
+                .file_entries
+                .entries()
+                .iter()
+                .filter(|e| e.status == ExtractionStatus::Done)
+                .map(|e| e.full_path.clone())
+                .collect();
+            (
+                archives,
+                backup_root(&app_state.config),
+                app_state.config.advanced.analysis_only,
+            )
+        };
+
+        if analysis_only {
+            show_toast(
+                &ui,
+                &ToastData::warning("Reorganizing is disabled in analysis-only mode"),
+            );
+            return;
+        }
+        if archives.is_empty() {
+            show_toast(&ui, &ToastData::info("No extracted archives to reorganize"));
+            return;
+        }
+
+        let scanned = PathBuf::from(ui.get_selected_folder().as_str());
+        ui.set_status_text(SharedString::from("Planning the reorganization..."));
+        let weak = ui.as_weak();
+        std::thread::spawn(move || {
+            let outputs = archives
+                .iter()
+                .filter_map(|archive| {
+                    read_extracted_output(archive, &scanned, backup_root.as_deref())
+                        .inspect_err(|e| {
+                            tracing::warn!(
+                                "Could not list the files of {}: {}",
+                                archive.display(),
+                                e
+                            );
+                        })
+                        .ok()
+                })
+                .collect();
+            offer_reorganize(weak, scanned, outputs, 0, 0);
+        });
+    });
+}
+
+/// What the last run extracted from `archive`, read from its backup if the
+/// extraction moved the archive there
+fn read_extracted_output(
+    archive: &Path,
+    scanned: &Path,
+    backup_root: Option<&Path>,
+) -> crate::error::Result<ExtractedOutput> {
+    let backup = backup_root
+        .filter(|_| !archive.is_file())
+        .and_then(|backup_root| backup_location(backup_root, archive))
+        .filter(|backup| backup.is_file());
+    match backup {
+        Some(backup) => Ok(ExtractedOutput::new(
+            archive,
+            scanned,
+            crate::ba2::read_file_names(&backup)?,
+        )),
+        None => ExtractedOutput::read(archive, scanned),
+    }
+}
+
+/// Plan the strategies from `Strategy::ALL[next..]` in the background and
+/// offer the first one with files to move, or report what was moved so far
+fn offer_reorganize(
+    weak: slint::Weak<MainWindow>,
+    scanned: PathBuf,
+    outputs: Vec<ExtractedOutput>,
+    next: usize,
+    moved: usize,
+) {
+    std::thread::spawn(move || {
+        let planned = Strategy::ALL
+            .iter()
+            .enumerate()
+            .skip(next)
+            .map(|(index, &strategy)| (index, reorganize::plan(strategy, &outputs)))
+            .find(|(_, plan)| !plan.is_empty());
+
+        let _ = slint::invoke_from_event_loop(move || {
+            let Some(ui) = weak.upgrade() else {
+                return;
+            };
+            ui.set_status_text(SharedString::from("Ready"));
+
+            let Some((index, plan)) = planned else {
+                let toast = if moved == 0 {
+                    ToastData::info("The extracted files need no reorganizing")
+                } else {
+                    ToastData::success(format!(
+                        "Moved {} extracted files. Use Undo to put them back.",
+                        format_count(moved as u64)
+                    ))
+                };
+                show_toast(&ui, &toast);
+                return;
+            };
+
+            let strategy = plan.strategy;
+            let message = format!(
+                "{}.\n\n{}\n\nUndo Extraction won't find files once they are moved; use Undo to put them back.",
+                strategy.description(),
+                plan.preview(&scanned, REPORT_MAX_LISTED_PATHS)
+            );
+            let skipped = (scanned.clone(), outputs.clone());
+            show_choice(
+                &ui,
+                DialogConfig::confirm(format!("{strategy}?"), message)
+                    .with_primary_button("Apply")
+                    .with_secondary_button("Skip"),
+                move |ui| apply_reorganize(ui, scanned, &outputs, plan, index, moved),
+                move |ui| {
+                    let (scanned, outputs) = skipped;
+                    offer_reorganize(ui.as_weak(), scanned, outputs, index + 1, moved);
+                },
+            );
+        });
+    });
+}
+
+/// Make the moves of `plan` in the background as one journal operation,
+/// then offer the strategies after it
+fn apply_reorganize(
+    ui: &MainWindow,
+    scanned: PathBuf,
+    outputs: &[ExtractedOutput],
+    plan: ReorganizePlan,
+    index: usize,
+    moved: usize,
+) {
+    ui.set_status_text(SharedString::from("Moving extracted files..."));
+    let outputs: Vec<ExtractedOutput> = outputs
+        .iter()
+        .map(|output| output.reorganized(plan.strategy))
+        .collect();
+    let weak = ui.as_weak();
+    std::thread::spawn(move || {
+        let journal = OperationJournal::open_default();
+        let mut operation = journal
+            .as_ref()
+            .map(|j| j.begin(format!("Reorganize extracted files: {}", plan.strategy)));
+        let report = reorganize::apply(&plan, operation.as_mut());
+        commit_operation(journal.as_ref(), operation);
+
+        let failed = report.failed.len();
+        let weak_after = weak.clone();
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(ui) = weak_after.upgrade() {
+                refresh_last_operation(&ui);
+                if failed > 0 {
+                    show_toast(
+                        &ui,
+                        &ToastData::warning(format!(
+                            "Could not move {} files; see the log for details",
+                            format_count(failed as u64)
+                        )),
+                    );
+                }
+            }
+        });
+        offer_reorganize(weak, scanned, outputs, index + 1, moved + report.moved);
+    });
+}
+
+/// Show the operation "Undo" would revert
+fn refresh_last_operation(ui: &MainWindow) {
+    let description = OperationJournal::open_default()
+        .and_then(|journal| journal.last())
+        .map(|entry| entry.description)
+        .unwrap_or_default();
+    ui.set_last_operation(SharedString::from(description));
+}
+
+/// Commit a finished operation, logging failures (the operation itself succeeded)
+fn commit_operation(journal: Option<&OperationJournal>, operation: Option<Operation>) {
+    if let (Some(journal), Some(operation)) = (journal, operation)
+        && let Err(e) = journal.commit(operation)
+    {
+        tracing::warn!("Failed to record operation in the journal: {}", e);
+    }
+}
+
+/// Offer to finish or undo a quarantine whose move stopped partway
+///
+/// A complete copy can be finished by removing the original or removed
+/// again; an incomplete copy can only be removed.
+fn show_partial_quarantine(
+    ui: &MainWindow,
+    state: &Arc<Mutex<AppState>>,
+    manager: QuarantineManager,
+    entry: FileEntry,
+    error: &Error,
+) {
+    let Error::PartialMove { to, outcome, .. } = error else {
+        return;
+    };
+    let config = DialogConfig::warning(
+        "Quarantine incomplete",
+        format!(
+            "{}\n\n{}",
+            error.user_message(),
+            error.recovery_suggestions().join("\n")
+        ),
+    );
+    let copy = to.clone();
+    let original = entry.full_path.clone();
+    let remove_copy = move |ui: &MainWindow| {
+        let toast = match revert_partial_move(&original, &copy) {
+            Ok(()) => ToastData::info(format!("Removed the copy at {}", copy.display())),
+            Err(e) => ToastData::error(format!("Could not remove the copy: {}", e.user_message())),
+        };
+        show_toast(ui, &toast);
+    };
+    if !outcome.is_copied() {
+        let config = config
+            .with_primary_button("Remove Copy")
+            .with_secondary_button("Keep");
+        show_confirmation(ui, config, remove_copy);
+        return;
+    }
+
+    let state = Arc::clone(state);
+    let copy = to.clone();
+    let finish = move |ui: &MainWindow| {
+        let journal = OperationJournal::open_default();
+        let mut operation = journal
+            .as_ref()
+            .map(|j| j.begin(format!("Quarantine {}", entry.file_name)));
+        let result = manager.finish(&entry, &copy, operation.as_mut());
+        commit_operation(journal.as_ref(), operation);
+        let toast = match result {
+            Ok(record) => {
+                state.lock().file_entries.remove_path(&entry.full_path);
+                ToastData::success(format!(
+                    "Moved {} to {}",
+                    entry.file_name,
+                    record.quarantined_path.display()
+                ))
             }
-        });
-    }
+            Err(e) => {
+                tracing::error!(
+                    "Failed to finish quarantine of {}: {}",
+                    entry.full_path.display(),
+                    e
+                );
+                ToastData::error(format!(
+                    "Could not remove {}: {}",
+                    entry.file_name,
+                    e.user_message()
+                ))
+            }
+        };
+        refresh_last_operation(ui);
+        ui.set_corrupted_count(corrupted_archive_count(&state));
+        refresh_file_table(ui, &state, current_threshold(ui));
+        update_dashboard(ui, &state);
+        show_toast(ui, &toast);
+    };
+    let config = config
+        .with_primary_button("Finish Move")
+        .with_secondary_button("Remove Copy");
+    show_choice(ui, config, finish, remove_copy);
 }
 
-/// Set up update checker callback (Phase 2.6)
-fn setup_update_checker_callback(main_window: &MainWindow) {
-    let weak = main_window.as_weak();
+/// Extract a known exclusion after all, once the user confirmed the risk, or
+/// keep it packed again
+fn toggle_exclusion_override(ui: &MainWindow, state: &Arc<Mutex<AppState>>, archive: PathBuf) {
+    let found = state
+        .lock()
+        .file_entries
+        .entries()
+        .iter()
+        .find(|entry| entry.full_path == archive)
+        .and_then(|entry| {
+            let reason = entry.exclusion.clone()?;
+            Some((entry.file_name.clone(), reason, entry.overrides.exclusion))
+        });
+    let Some((file_name, reason, overridden)) = found else {
+        return;
+    };
 
-    main_window.on_check_for_updates(move || {
-        let weak_clone = weak.clone();
+    let set_override = move |ui: &MainWindow, state: &Arc<Mutex<AppState>>, value: bool| {
+        if let Some(entry) = state.lock().file_entries.find_mut(&archive) {
+            entry.overrides.exclusion = value;
+        }
+        refresh_file_table(ui, state, current_threshold(ui));
+    };
 
-        tracing::info!("User requested update check");
+    if overridden {
+        set_override(ui, state, false);
+        show_toast(
+            ui,
+            &ToastData::info(format!("{file_name} will be kept packed again")),
+        );
+        return;
+    }
 
-        // Show toast notification that we're checking
-        if let Some(ui) = weak.upgrade() {
-            show_toast(&ui, &ToastData {
-                message: "Checking for updates...".to_string(),
-                notification_type: NotificationType::Info,
-                show: true,
-            });
+    let state = Arc::clone(state);
+    let dialog = DialogConfig::error(
+        "Extract an archive that must stay packed?",
+        format!(
+            "{file_name} is on the list of archives that must never be unpacked:\n\n{reason}\n\nUnpacking it can break the mod or the game, and the loose files are not removed when the mod is updated or uninstalled. Only continue if you know this archive is safe to unpack."
+        ),
+    )
+    .with_primary_button("Extract Anyway")
+    .with_secondary_button("Cancel")
+    .with_checkbox("I understand this can break my game");
+    show_confirmation(ui, dialog, move |ui| {
+        if ui.get_dialog_checkbox_checked() {
+            set_override(ui, &state, true);
+            show_toast(
+                ui,
+                &ToastData::warning(format!(
+                    "{file_name} will be extracted even though it must stay packed"
+                )),
+            );
+        } else {
+            show_toast(
+                ui,
+                &ToastData::warning("Extracting it anyway needs the confirmation box checked"),
+            );
         }
+    });
+}
 
-        // Run update check in background task using global runtime
-        crate::get_runtime().spawn(async move {
-            match crate::update_checker::check_for_updates().await {
-                    Ok(Some(update_info)) => {
-                        // Update available
-                        tracing::info!(
-                            "Update available: {} -> {}",
-                            update_info.current_version,
-                            update_info.latest_version
-                        );
-
-                        let download_url = update_info.download_url.clone();
+/// Run a Quarantine view action ("quarantine", "revalidate", "force" or "modpage") on a corrupted archive
+#[allow(clippy::too_many_lines)] // One arm per Quarantine view action
+fn corrupted_archive_action(
+    ui: &MainWindow,
+    state: &Arc<Mutex<AppState>>,
+    action: &str,
+    archive: PathBuf,
+) {
+    let file_name = archive
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    match action {
+        "force" => {
+            let forced = state.lock().file_entries.find_mut(&archive).map(|entry| {
+                entry.overrides.bad_file = !entry.overrides.bad_file;
+                entry.overrides.bad_file
+            });
+            let Some(forced) = forced else {
+                return;
+            };
+            refresh_file_table(ui, state, current_threshold(ui));
+            let toast = if forced {
+                ToastData::info(format!(
+                    "{file_name} will be extracted even though its header could not be read"
+                ))
+            } else {
+                ToastData::info(format!("{file_name} will be skipped again"))
+            };
+            show_toast(ui, &toast);
+        }
+        "revalidate" => {
+            let weak = ui.as_weak();
+            let state = Arc::clone(state);
+            std::thread::spawn(move || {
+                let result = revalidate(&archive);
+                let toast = state.lock().file_entries.find_mut(&archive).map(|entry| match result {
+                    Ok(header) => {
+                        entry.health = ArchiveHealth::Ok;
+                        entry.overrides.bad_file = false;
+                        entry.num_files = header.file_count;
+                        entry.archive_type = header.archive_type;
+                        entry.format_version = header.version;
+                        entry.file_size = std::fs::metadata(&archive).map_or(entry.file_size, |m| m.len());
+                        ToastData::success(format!("{file_name} is a valid archive now"))
+                    }
+                    Err(e) => {
+                        entry.health = ArchiveHealth::from_error(&e);
+                        ToastData::warning(format!(
+                            "{file_name} still can't be used - {}",
+                            entry.health
+                        ))
+                    }
+                });
+                let Some(toast) = toast else {
+                    return;
+                };
 
-                        // Open the download page in the browser immediately
-                        if let Err(e) = open::that(&download_url) {
-                            tracing::error!("Failed to open browser: {}", e);
-                        }
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(ui) = weak.upgrade() {
+                        ui.set_corrupted_count(corrupted_archive_count(&state));
+                        refresh_file_table(&ui, &state, current_threshold(&ui));
+                        update_dashboard(&ui, &state);
+                        show_toast(&ui, &toast);
+                    }
+                });
+            });
+        }
+        "quarantine" => {
+            // Unreadable archives are usually fine once their permissions or locks are sorted out
+            let health = state
+                .lock()
+                .file_entries
+                .entries()
+                .iter()
+                .find(|e| e.full_path == archive)
+                .map(|e| e.health.clone());
+            if let Some(health) = health.filter(|health| !health.is_corrupted()) {
+                show_toast(
+                    ui,
+                    &ToastData::warning(format!(
+                        "{file_name} is not corrupted, so it is not quarantined - {health}"
+                    )),
+                );
+                return;
+            }
+            let (root, analysis_only) = {
+                let config = &state.lock().config;
+                let root = if config.advanced.quarantine_path.is_empty() {
+                    default_quarantine_root()
+                } else {
+                    Some(host_path(&config.advanced.quarantine_path))
+                };
+                (root, config.advanced.analysis_only)
+            };
+            if analysis_only {
+                show_toast(ui, &ToastData::warning("Quarantine is disabled in analysis-only mode"));
+                return;
+            }
+            let Some(root) = root else {
+                show_toast(ui, &ToastData::error("No quarantine folder is available"));
+                return;
+            };
 
-                        let message = format!(
-                            "Update available!\n\nCurrent version: {}\nLatest version: {}\n\nOpening download page in your browser...\n\n{}",
-                            update_info.current_version,
-                            update_info.latest_version,
-                            if update_info.release_notes.len() > 200 {
-                                format!("{}...", &update_info.release_notes[..200])
-                            } else {
-                                update_info.release_notes.clone()
+            let state = Arc::clone(state);
+            show_confirmation(
+                ui,
+                DialogConfig::confirm(
+                    "Quarantine archive?",
+                    format!(
+                        "Move {file_name} out of its mod folder into:\n{}\n\nThe game will no longer load it.",
+                        root.display()
+                    ),
+                )
+                .with_primary_button("Quarantine")
+                .with_secondary_button("Cancel"),
+                move |ui| {
+                    let Some(entry) = state
+                        .lock()
+                        .file_entries
+                        .entries()
+                        .iter()
+                        .find(|e| e.full_path == archive)
+                        .cloned()
+                    else {
+                        return;
+                    };
+                    let weak = ui.as_weak();
+                    std::thread::spawn(move || {
+                        let journal = OperationJournal::open_default();
+                        let mut operation = journal
+                            .as_ref()
+                            .map(|j| j.begin(format!("Quarantine {file_name}")));
+                        let manager = QuarantineManager::new(root);
+                        let result = manager.quarantine(&entry, operation.as_mut());
+                        commit_operation(journal.as_ref(), operation);
+                        let (toast, partial) = match result {
+                            Ok(record) => {
+                                state.lock().file_entries.remove_path(&archive);
+                                let toast = ToastData::success(format!(
+                                    "Moved {file_name} to {}",
+                                    record.quarantined_path.display()
+                                ));
+                                (Some(toast), None)
                             }
-                        );
-
-                        let _ = slint::invoke_from_event_loop(move || {
-                            if let Some(ui) = weak_clone.upgrade() {
-                                show_dialog(&ui, DialogConfig {
-                                    title: "Update Available".to_string(),
-                                    message,
-                                    dialog_type: NotificationType::Success,
-                                    primary_button: "OK".to_string(),
-                                    secondary_button: None,
-                                });
+                            // Settled from the dialog instead of reported as a failure
+                            Err(e @ Error::PartialMove { .. }) => (None, Some(e)),
+                            Err(e) => {
+                                tracing::error!("Failed to quarantine {}: {}", archive.display(), e);
+                                let toast = ToastData::error(format!(
+                                    "Could not quarantine {file_name}: {}",
+                                    e.user_message()
+                                ));
+                                (Some(toast), None)
                             }
-                        });
-                    }
-                    Ok(None) => {
-                        // Already up to date
-                        tracing::info!("Already running the latest version");
+                        };
 
                         let _ = slint::invoke_from_event_loop(move || {
-                            if let Some(ui) = weak_clone.upgrade() {
-                                show_toast(&ui, &ToastData {
-                                    message: "You're running the latest version!".to_string(),
-                                    notification_type: NotificationType::Success,
-                                    show: true,
-                                });
+                            if let Some(ui) = weak.upgrade() {
+                                refresh_last_operation(&ui);
+                                ui.set_corrupted_count(corrupted_archive_count(&state));
+                                refresh_file_table(&ui, &state, current_threshold(&ui));
+                                update_dashboard(&ui, &state);
+                                if let Some(toast) = toast {
+                                    show_toast(&ui, &toast);
+                                }
+                                if let Some(error) = partial {
+                                    show_partial_quarantine(&ui, &state, manager, entry, &error);
+                                }
                             }
                         });
+                    });
+                },
+            );
+        }
+        "modpage" => {
+            let starfield = state
+                .lock()
+                .file_entries
+                .entries()
+                .iter()
+                .any(|e| e.full_path == archive && e.is_starfield());
+            let weak = ui.as_weak();
+            std::thread::spawn(move || {
+                let mod_folder = archive.parent().unwrap_or_else(|| Path::new("."));
+                let toast = match find_mod_page(mod_folder, starfield) {
+                    Some(page) => {
+                        tracing::info!("Opening mod page of {}: {}", file_name, page.url);
+                        if let Err(e) = open::that(&page.url) {
+                            tracing::error!("Failed to open {}: {}", page.url, e);
+                            ToastData::error(format!("Could not open {}", page.url))
+                        } else if let Some(download) = page.download_file {
+                            ToastData::info(format!("{file_name} was installed from {download}"))
+                        } else {
+                            ToastData::info(format!("Opened the mod page of {file_name}"))
+                        }
                     }
-                    Err(e) => {
-                        // Error checking for updates
-                        tracing::error!("Failed to check for updates: {}", e);
+                    None => ToastData::warning(format!(
+                        "No mod page is known for {file_name} (no Mod Organizer or Vortex download info)"
+                    )),
+                };
 
-                        let error_msg = format!("Failed to check for updates: {e}");
-                        let _ = slint::invoke_from_event_loop(move || {
-                            if let Some(ui) = weak_clone.upgrade() {
-                                show_toast(&ui, &ToastData {
-                                    message: error_msg,
-                                    notification_type: NotificationType::Error,
-                                    show: true,
-                                });
-                            }
-                        });
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(ui) = weak.upgrade() {
+                        show_toast(&ui, &toast);
                     }
-                }
+                });
             });
-    });
+        }
+        _ => tracing::warn!("Unknown corrupted archive action: {}", action),
+    }
 }
 
-/// Set up platform integration (Phase 2.9)
-///
-/// Detects the default BA2 file handler on Windows and auto-populates
-/// the external tool setting if it's empty.
-fn setup_platform_integration(_main_window: &MainWindow, _state: &Arc<Mutex<AppState>>) {
-    tracing::info!("Initializing platform integration (Phase 2.9)");
-    // Registry use is not required; we rely on the bundled BSArch.exe by default.
-    // Auto-detection logic removed.
+/// Get the number of archives whose header could not be read, for the Problems filter
+fn corrupted_archive_count(state: &Arc<Mutex<AppState>>) -> i32 {
+    state
+        .lock()
+        .file_entries
+        .bad_file_count()
+        .try_into()
+        .unwrap_or(i32::MAX)
 }
 
-/// Set up threshold filtering callbacks (Phase 2.3)
-#[allow(clippy::too_many_lines)] // Multiple threshold UI interactions
-fn setup_threshold_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
-    let weak = main_window.as_weak();
-
-    // Handle threshold value changes
-    {
-        let state_clone = Arc::clone(state);
-        let weak_clone = weak.clone();
-
-        main_window.on_threshold_changed(move |value| {
-            let value_str = value.to_string();
+/// Undo the extraction of `archives` in the background and report the outcome
+fn undo_extractions(ui: &MainWindow, state: &Arc<Mutex<AppState>>, archives: Vec<PathBuf>) {
+    let weak = ui.as_weak();
+    let state = Arc::clone(state);
+    let (backup_root, analysis_only, events) = {
+        let app_state = state.lock();
+        let config = &app_state.config;
+        (
+            backup_root(config),
+            config.advanced.analysis_only,
+            app_state.events.clone(),
+        )
+    };
 
-            if value_str.is_empty() {
-                // Clear threshold - show all files
-                let weak = weak_clone.clone();
-                let state = Arc::clone(&state_clone);
-                let _ = slint::invoke_from_event_loop(move || {
-                    if let Some(ui) = weak.upgrade() {
-                        refresh_file_table(&ui, &state, None);
-                    }
-                });
-                return;
-            }
+    if analysis_only {
+        show_toast(ui, &ToastData::warning("Undo is disabled in analysis-only mode"));
+        return;
+    }
 
-            // Parse the threshold value
-            match crate::operations::parse_size(&value_str) {
-                Ok(threshold_bytes) => {
-                    tracing::info!("Threshold set to: {} bytes", threshold_bytes);
+    ui.set_status_text(SharedString::from("Removing extracted files..."));
+
+    std::thread::spawn(move || {
+        let mut total = UndoReport::default();
+        let mut undone = Vec::new();
+        let mut without_manifest = 0usize;
+        let mut restored = 0usize;
+        let mut errors = Vec::new();
+        let journal = OperationJournal::open_default();
+        let mut operation = journal.as_ref().map(|j| {
+            j.begin(undo_description(&archives))
+        });
 
-                    let weak = weak_clone.clone();
-                    let state = Arc::clone(&state_clone);
-                    let _ = slint::invoke_from_event_loop(move || {
-                        if let Some(ui) = weak.upgrade() {
-                            refresh_file_table(&ui, &state, Some(threshold_bytes));
-                        }
-                    });
+        for archive in archives {
+            match undo_archive_extraction(&archive, backup_root.as_deref(), &events, operation.as_mut()) {
+                Ok(Some(report)) => {
+                    total.removed += report.removed;
+                    total.missing += report.missing;
+                    total.kept_modified.extend(report.kept_modified);
+                    restored += usize::from(report.restored.is_some());
+                    undone.push(archive);
                 }
+                Ok(None) => without_manifest += 1,
                 Err(e) => {
-                    tracing::warn!("Invalid threshold value '{}': {}", value_str, e);
+                    tracing::error!("Failed to undo extraction of {}: {}", archive.display(), e);
+                    errors.push(format!("{}: {}", archive.display(), e.user_message()));
                 }
             }
-        });
-    }
-
-    // Handle auto-threshold toggle
-    {
-        let state_clone = Arc::clone(state);
-        let weak_clone = weak;
-
-        #[allow(clippy::significant_drop_tightening)] // Lock must be held while reading entries
-        main_window.on_auto_threshold_toggled(move |enabled| {
-            if enabled {
-                // Calculate auto-threshold (235 file limit)
-                let (entries_count, threshold_opt) = {
-                    let app_state = state_clone.lock();
-                    let entries = app_state.file_entries.entries();
-                    let count = entries.len();
+        }
 
-                    if count <= 235 {
-                        (count, None)
-                    } else {
-                        // Get the 235th largest file's size
-                        let mut sorted_sizes: Vec<u64> = entries.iter()
-                            .map(|e| e.file_size)
-                            .collect();
-                        sorted_sizes.sort_unstable();
-                        sorted_sizes.reverse();
+        commit_operation(journal.as_ref(), operation);
 
-                        let threshold = sorted_sizes[234]; // 0-indexed, so 234 is the 235th item
-                        (count, Some(threshold))
-                    }
-                };
+        {
+            let mut app_state = state.lock();
+            for archive in &undone {
+                app_state.file_entries.set_status(archive, ExtractionStatus::Idle);
+            }
+        }
 
-                if let Some(threshold) = threshold_opt {
-                    let threshold_str = format_size(threshold, BINARY);
+        let message = undo_message(&total, undone.len(), restored, without_manifest, &errors);
 
-                    tracing::info!(
-                        "Auto-threshold calculated: {} ({} bytes) - will keep 235 files",
-                        threshold_str,
-                        threshold
-                    );
+        let dialog = if errors.is_empty() && total.kept_modified.is_empty() && without_manifest == 0
+        {
+            DialogConfig::success("Extraction undone", message)
+        } else {
+            DialogConfig::warning("Extraction partially undone", message)
+        };
 
-                    let weak = weak_clone.clone();
-                    let state = Arc::clone(&state_clone);
-                    let _ = slint::invoke_from_event_loop(move || {
-                        if let Some(ui) = weak.upgrade() {
-                            ui.set_threshold_value(SharedString::from(threshold_str.clone()));
-                            refresh_file_table(&ui, &state, Some(threshold));
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(ui) = weak.upgrade() {
+                ui.set_status_text(SharedString::from("Ready"));
+                refresh_last_operation(&ui);
+                refresh_file_table(&ui, &state, current_threshold(&ui));
+                show_dialog(&ui, dialog);
+            }
+        });
+    });
+}
 
-                            show_toast(&ui, &ToastData {
-                                message: format!(
-                                    "Auto-threshold set to {threshold_str} (keeping 235 files)"
-                                ),
-                                notification_type: NotificationType::Success,
-                                show: true,
-                            });
-                        }
-                    });
-                } else {
-                    tracing::info!("Auto-threshold not needed: only {} files", entries_count);
+/// Undo the extraction of one archive using its manifest
+///
+/// Returns `Ok(None)` if the archive has no manifest.
+fn undo_archive_extraction(
+    archive: &Path,
+    backup_root: Option<&Path>,
+    events: &EventBus,
+    operation: Option<&mut Operation>,
+) -> crate::error::Result<Option<UndoReport>> {
+    let output_dir = archive.parent().unwrap_or_else(|| Path::new("."));
+    let manifest = manifest_path(archive, output_dir);
+    if !manifest.exists() {
+        return Ok(None);
+    }
 
-                    let weak = weak_clone.clone();
-                    let _ = slint::invoke_from_event_loop(move || {
-                        if let Some(ui) = weak.upgrade() {
-                            ui.set_auto_threshold(false);
-                            show_toast(&ui, &ToastData {
-                                message: format!(
-                                    "Auto-threshold not needed: only {entries_count} BA2 files found (limit is 235)"
-                                ),
-                                notification_type: NotificationType::Info,
-                                show: true,
-                            });
-                        }
-                    });
-                }
-            } else {
-                // Auto-threshold disabled - clear threshold
-                let weak = weak_clone.clone();
-                let state = Arc::clone(&state_clone);
-                let _ = slint::invoke_from_event_loop(move || {
-                    if let Some(ui) = weak.upgrade() {
-                        ui.set_threshold_value(SharedString::from(""));
-                        refresh_file_table(&ui, &state, None);
-                    }
-                });
-            }
+    let backup = backup_root.and_then(|backup_root| backup_location(backup_root, archive));
+    let report = undo_extraction(&manifest, backup.as_deref(), operation)?;
+    if let (Some(archive), Some(backup)) = (&report.restored, backup) {
+        events.try_publish(BackupEvent::Restored {
+            archive: archive.clone(),
+            backup,
         });
     }
+    Ok(Some(report))
 }
 
-/// Set up file actions callback (Phase 2.3 - ignore/open)
-#[allow(clippy::too_many_lines)] // Multiple file action handlers
-fn setup_file_actions_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
-    let weak = main_window.as_weak();
-    let state = Arc::clone(state);
-
-    main_window.on_file_action(move |row_index, action| {
-        let action_str = action.to_string();
-        tracing::info!("File action requested: {} for row {}", action_str, row_index);
+/// Build the dialog summarizing a manifest verification
+fn verify_report_dialog(file_name: &str, report: &VerifyReport) -> DialogConfig {
+    // Downscaled textures only match the manifest if they were not re-extracted at full size
+    let downscaled = match report.texture_skip_mips {
+        0 => String::new(),
+        levels => format!(
+            "\n\nTextures were downscaled by skipping {levels} mip levels when they were extracted."
+        ),
+    };
+    if report.is_clean() {
+        return DialogConfig::success(
+            "Verification passed",
+            format!(
+                "All {} files extracted from {file_name} match the manifest.{downscaled}",
+                format_count(report.verified as u64)
+            ),
+        );
+    }
 
-        match action_str.as_str() {
-            "ignore" => {
-                // Get the file name from the row
-                let file_name = if let Some(ui) = weak.upgrade() {
-                    let file_list = ui.get_file_list();
-                    if let Ok(idx) = usize::try_from(row_index) {
-                        if idx < file_list.row_count() {
-                            file_list.row_data(idx).unwrap().file_name.to_string()
-                        } else {
-                            tracing::error!("Invalid row index: {}", row_index);
-                            return;
-                        }
-                    } else {
-                        tracing::error!("Invalid row index: {}", row_index);
-                        return;
-                    }
-                } else {
-                    return;
-                };
+    let mut message = format!(
+        "{} files verified, {} missing, {} modified.{downscaled}\n",
+        format_count(report.verified as u64),
+        format_count(report.missing.len() as u64),
+        format_count(report.modified.len() as u64)
+    );
+    let problems = report
+        .missing
+        .iter()
+        .map(|path| format!("Missing: {path}"))
+        .chain(report.modified.iter().map(|path| format!("Modified: {path}")));
+    for line in problems.take(REPORT_MAX_LISTED_PATHS) {
+        message.push('\n');
+        message.push_str(&line);
+    }
 
-                tracing::info!("Ignoring file: {}", file_name);
+    DialogConfig::warning(format!("{file_name} does not match its manifest"), message)
+}
 
-                // Add to ignored files in config
-                {
-                    let mut app_state = state.lock();
-                    // TODO: Add the file to the ignored list in config
-                    // For now, just remove it from the current list
-                    let entries = app_state.file_entries.entries().to_vec();
-                    let filtered: Vec<FileEntry> = entries
-                        .into_iter()
-                        .filter(|e| e.file_name != file_name)
-                        .collect();
-                    app_state.file_entries = FileEntryList::from_vec(filtered);
-                }
+/// Build the dialog summarizing a comparison of loose files with their archive
+fn folder_diff_dialog(file_name: &str, diff: &ArchiveDiff) -> DialogConfig {
+    if diff.is_identical() {
+        return DialogConfig::success(
+            "Verification passed",
+            format!(
+                "All {} files of {file_name} are present next to it.",
+                format_count(diff.unchanged as u64)
+            ),
+        );
+    }
 
-                // Refresh the table
-                let weak_clone = weak.clone();
-                let state_clone = Arc::clone(&state);
-                let _ = slint::invoke_from_event_loop(move || {
-                    if let Some(ui) = weak_clone.upgrade() {
-                        refresh_file_table(&ui, &state_clone, None);
+    let missing = diff.count(ChangeKind::Removed);
+    let mut message = format!(
+        "No checksum manifest exists, so the loose files were compared with the archive.\n\n\
+         {} files present, {} missing, {} extra, {} differing.\n",
+        format_count(diff.unchanged as u64),
+        format_count(missing as u64),
+        format_count(diff.count(ChangeKind::Added) as u64),
+        format_count(diff.count(ChangeKind::Changed) as u64)
+    );
+    let label = |kind: ChangeKind| match kind {
+        ChangeKind::Removed => "Missing",
+        ChangeKind::Added => "Extra",
+        ChangeKind::Changed => "Differs",
+    };
+    for change in diff.changes.iter().take(REPORT_MAX_LISTED_PATHS) {
+        let _ = write!(message, "\n{}: {}", label(change.kind), change.path);
+    }
+    if missing > 0 {
+        message.push_str("\n\nExtract the archive again to restore the missing files.");
+    }
 
-                        show_toast(&ui, &ToastData {
-                            message: format!("Ignored file: {file_name}"),
-                            notification_type: NotificationType::Success,
-                            show: true,
-                        });
-                    }
-                });
-            }
-            "open" => {
-                // Get the file info from state
-                let (file_name, file_path, ext_tool_path) = {
-                    let app_state = state.lock();
-                    let entries = app_state.file_entries.entries();
+    DialogConfig::warning(format!("{file_name} does not match its archive"), message)
+}
 
-                    let idx = match usize::try_from(row_index) {
-                        Ok(i) if i < entries.len() => i,
-                        _ => {
-                            tracing::error!("Invalid row index: {}", row_index);
-                            return;
-                        }
-                    };
+/// Build the dialog listing the quick facts of an archive's contents
+fn inspection_dialog(file_name: &str, report: &ArchiveInspection) -> DialogConfig {
+    const MAX_LISTED_ENTRIES: usize = 5;
 
-                    let entry = &entries[idx];
-                    (
-                        entry.file_name.clone(),
-                        entry.full_path.clone(),
-                        app_state.config.advanced.ext_ba2_exe.clone(),
-                    )
-                };
+    let mut message = format!(
+        "{} files, {} scripts",
+        format_count(report.files as u64),
+        format_count(report.scripts as u64)
+    );
 
-                tracing::info!("Opening BA2 file with external tool: {}", file_path.display());
+    let mut meshes: Vec<(&str, &NifInfo)> = report.meshes().collect();
+    if !meshes.is_empty() {
+        let triangles: u64 = meshes.iter().map(|(_, info)| info.triangles).sum();
+        let mut versions: BTreeSet<String> = BTreeSet::new();
+        for (_, info) in &meshes {
+            versions.insert(format!("{} (stream {})", info.version, info.bs_version));
+        }
+        let _ = write!(
+            message,
+            "\n\nMeshes: {}, about {} triangles\nNIF versions: {}",
+            format_count(meshes.len() as u64),
+            format_count(triangles),
+            versions.into_iter().collect::<Vec<_>>().join(", ")
+        );
+        meshes.sort_by_key(|(_, info)| std::cmp::Reverse(info.triangles));
+        for (name, info) in meshes.iter().take(MAX_LISTED_ENTRIES) {
+            let _ = write!(
+                message,
+                "\n{name}: {} triangles in {} shapes",
+                format_count(info.triangles),
+                info.shapes
+            );
+        }
+    }
 
-                // Check if file exists
-                if !file_path.exists() {
-                    tracing::error!("File not found: {}", file_path.display());
-                    let weak_clone = weak.clone();
-                    let _ = slint::invoke_from_event_loop(move || {
-                        if let Some(ui) = weak_clone.upgrade() {
-                            show_toast(&ui, &ToastData {
-                                message: format!("File not found: {file_name}"),
-                                notification_type: NotificationType::Error,
-                                show: true,
-                            });
-                        }
-                    });
-                    return;
-                }
+    let mut sounds: Vec<(&str, &AudioInfo)> = report.sounds().collect();
+    if !sounds.is_empty() {
+        let total: Duration = sounds.iter().map(|(_, info)| info.duration).sum();
+        let _ = write!(
+            message,
+            "\n\nSounds: {}, {} in total",
+            format_count(sounds.len() as u64),
+            format_elapsed(total)
+        );
+        sounds.sort_by_key(|(_, info)| std::cmp::Reverse(info.duration));
+        for (name, info) in sounds.iter().take(MAX_LISTED_ENTRIES) {
+            let _ = write!(
+                message,
+                "\n{name}: {} {}, {} Hz",
+                info.format,
+                format_elapsed(info.duration),
+                info.sample_rate
+            );
+        }
+    }
 
-                // Check if external tool is configured
-                if ext_tool_path.is_empty() {
-                    tracing::warn!("No external BA2 tool configured");
-                    let weak_clone = weak.clone();
-                    let _ = slint::invoke_from_event_loop(move || {
-                        if let Some(ui) = weak_clone.upgrade() {
-                            show_toast(&ui, &ToastData {
-                                message: "No external BA2 tool configured.\nPlease set the tool path in Settings > Advanced.".to_string(),
-                                notification_type: NotificationType::Warning,
-                                show: true,
-                            });
-                        }
-                    });
-                    return;
-                }
+    DialogConfig::info(format!("Contents of {file_name}"), message)
+}
 
-                // Launch external tool in background thread
-                let weak_clone = weak.clone();
-                std::thread::spawn(move || {
-                    use std::process::Command;
+/// Texture archive open in the preview dialog
+struct PreviewSession {
+    archive: Arc<BA2Archive>,
+    /// Index of the texture shown
+    index: usize,
+}
 
-                    tracing::info!("Launching: {} {}", ext_tool_path, file_path.display());
+/// State of the texture preview dialog
+#[derive(Default)]
+struct TexturePreviews {
+    session: Option<PreviewSession>,
+    /// Recently decoded textures, kept when the dialog is closed
+    cache: PreviewCache,
+}
 
-                    match Command::new(&ext_tool_path)
-                        .arg(&file_path)
-                        .spawn()
-                    {
-                        Ok(_) => {
-                            tracing::info!("Successfully launched external tool for {}", file_name);
-                        }
-                        Err(e) => {
-                            tracing::error!("Failed to launch external tool: {}", e);
-                            let error_msg = format!("Failed to open BA2 file:\n{e}");
-                            let _ = slint::invoke_from_event_loop(move || {
-                                if let Some(ui) = weak_clone.upgrade() {
-                                    show_toast(&ui, &ToastData {
-                                        message: error_msg,
-                                        notification_type: NotificationType::Error,
-                                        show: true,
-                                    });
-                                }
-                            });
-                        }
+impl TexturePreviews {
+    /// Move to the previous (negative `delta`) or next texture, wrapping around
+    ///
+    /// Returns false when no archive is open.
+    fn step(&mut self, delta: i32) -> bool {
+        let Some(session) = self.session.as_mut() else {
+            return false;
+        };
+        let count = session.archive.textures().len();
+        let step = if delta < 0 { count - 1 } else { 1 };
+        session.index = (session.index + step) % count;
+        true
+    }
+}
+
+/// Set up the texture preview dialog of DX10 archives
+fn setup_texture_preview_callbacks(main_window: &MainWindow) {
+    let previews = Arc::new(Mutex::new(TexturePreviews::default()));
+
+    {
+        let weak = main_window.as_weak();
+        let previews = Arc::clone(&previews);
+        main_window.on_texture_preview_open(move |path| {
+            let path = PathBuf::from(path.as_str());
+            let weak = weak.clone();
+            let previews = Arc::clone(&previews);
+            std::thread::spawn(move || {
+                let dialog = match BA2Archive::open(&path) {
+                    Ok(archive) if archive.textures().is_empty() => DialogConfig::info(
+                        "No textures",
+                        format!(
+                            "{} holds no textures to preview.",
+                            path.file_name().map(display_name).unwrap_or_default()
+                        ),
+                    ),
+                    Ok(archive) => {
+                        previews.lock().session = Some(PreviewSession {
+                            archive: Arc::new(archive),
+                            index: 0,
+                        });
+                        show_texture_preview(&weak, &previews);
+                        return;
+                    }
+                    Err(e) => {
+                        tracing::error!("Opening {} for a preview failed: {}", path.display(), e);
+                        DialogConfig::error("Preview failed", e.user_message())
+                    }
+                };
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(ui) = weak.upgrade() {
+                        show_dialog(&ui, dialog);
                     }
                 });
+            });
+        });
+    }
+
+    {
+        let weak = main_window.as_weak();
+        let previews = Arc::clone(&previews);
+        main_window.on_texture_preview_step(move |delta| {
+            if !previews.lock().step(delta) {
+                return;
             }
-            _ => {
-                tracing::warn!("Unknown file action: {}", action_str);
-            }
+            let weak = weak.clone();
+            let previews = Arc::clone(&previews);
+            std::thread::spawn(move || show_texture_preview(&weak, &previews));
+        });
+    }
+
+    main_window.on_texture_preview_closed(move || {
+        // Let go of the archive, so it can be extracted or moved again
+        previews.lock().session = None;
+    });
+}
+
+/// Decode the texture the preview dialog is on and show it
+///
+/// Runs on a background thread. Previews of textures the user has stepped
+/// past in the meantime are dropped.
+fn show_texture_preview(weak: &slint::Weak<MainWindow>, previews: &Arc<Mutex<TexturePreviews>>) {
+    let Some((archive, index)) = previews
+        .lock()
+        .session
+        .as_ref()
+        .map(|session| (Arc::clone(&session.archive), session.index))
+    else {
+        return;
+    };
+    let texture = &archive.textures()[index];
+    let cached = previews.lock().cache.get(archive.path(), &texture.name);
+    let decoded = cached.map_or_else(
+        || {
+            preview_texture(&archive, texture, PREVIEW_EDGE).map(|decoded| {
+                decoded.map(|preview| {
+                    previews
+                        .lock()
+                        .cache
+                        .insert(archive.path(), &texture.name, preview)
+                })
+            })
+        },
+        |preview| Ok(Some(preview)),
+    );
+    let message = match &decoded {
+        Ok(Some(_)) => String::new(),
+        Ok(None) if can_preview(texture.format) => {
+            "The data of this texture doesn't match its size, so it can't be previewed.".to_string()
+        }
+        Ok(None) => format!("{} textures can't be previewed.", texture_format(texture)),
+        Err(e) => {
+            tracing::warn!("Previewing {} failed: {}", texture.name, e);
+            e.user_message()
         }
+    };
+    let preview = decoded.ok().flatten();
+    let name = texture.name.clone();
+    let details = texture_details(texture);
+    let position = format!("{} of {}", index + 1, archive.textures().len());
+    let archive_name = archive
+        .path()
+        .file_name()
+        .map(display_name)
+        .unwrap_or_default();
+
+    let weak = weak.clone();
+    let previews = Arc::clone(previews);
+    let _ = slint::invoke_from_event_loop(move || {
+        let current = previews.lock().session.as_ref().is_some_and(|session| {
+            session.index == index && Arc::ptr_eq(&session.archive, &archive)
+        });
+        let Some(ui) = weak.upgrade().filter(|_| current) else {
+            return;
+        };
+        let image = preview.map_or_else(slint::Image::default, |preview| {
+            slint::Image::from_rgba8(SharedPixelBuffer::<Rgba8Pixel>::clone_from_slice(
+                &preview.rgba,
+                preview.width,
+                preview.height,
+            ))
+        });
+        ui.set_texture_preview_archive(SharedString::from(archive_name));
+        ui.set_texture_preview_name(SharedString::from(name));
+        ui.set_texture_preview_details(SharedString::from(details));
+        ui.set_texture_preview_position(SharedString::from(position));
+        ui.set_texture_preview_image(image);
+        ui.set_texture_preview_message(SharedString::from(message));
+        ui.set_show_texture_preview(true);
     });
 }
 
+/// Short name of a texture's format, or its DXGI number
+fn texture_format(texture: &TextureEntry) -> String {
+    format_name(texture.format)
+        .map_or_else(|| format!("DXGI format {}", texture.format), str::to_string)
+}
+
+/// Size, format and mip count of a texture, e.g. "2048 × 2048 · BC7 · 12 mips"
+fn texture_details(texture: &TextureEntry) -> String {
+    let mut details = format!(
+        "{} × {} · {} · {} mips",
+        texture.width,
+        texture.height,
+        texture_format(texture),
+        texture.num_mips
+    );
+    if texture.is_cubemap {
+        details.push_str(" · cubemap");
+    }
+    details
+}
+
 /// Set up open extraction folder callback (Phase 2.3)
 fn setup_open_folder_callback(main_window: &MainWindow, state: Arc<Mutex<AppState>>) {
     let weak = main_window.as_weak();
@@ -1233,11 +5944,7 @@ fn setup_open_folder_callback(main_window: &MainWindow, state: Arc<Mutex<AppStat
                     if let Some(ui) = weak_clone.upgrade() {
                         show_toast(
                             &ui,
-                            &ToastData {
-                                message: error_msg,
-                                notification_type: NotificationType::Error,
-                                show: true,
-                            },
+                            &ToastData::error(error_msg),
                         );
                     }
                 });
@@ -1253,11 +5960,7 @@ fn setup_open_folder_callback(main_window: &MainWindow, state: Arc<Mutex<AppStat
                     if let Some(ui) = weak_clone.upgrade() {
                         show_toast(
                             &ui,
-                            &ToastData {
-                                message: error_msg,
-                                notification_type: NotificationType::Error,
-                                show: true,
-                            },
+                            &ToastData::error(error_msg),
                         );
                     }
                 });
@@ -1266,50 +5969,596 @@ fn setup_open_folder_callback(main_window: &MainWindow, state: Arc<Mutex<AppStat
     });
 }
 
+/// Add exact patterns for archives to the ignore list and hide their rows
+///
+/// Returns the archives that were in the table; none in analysis-only mode,
+/// which leaves the ignore list alone.
+fn ignore_archives(state: &Arc<Mutex<AppState>>, paths: &[PathBuf]) -> Vec<PathBuf> {
+    let (ignored, patterns) = {
+        let mut app_state = state.lock();
+        if app_state.config.advanced.analysis_only {
+            return Vec::new();
+        }
+        let mut patterns = app_state.config.extraction.ignored_files.clone();
+        let mut ignored = Vec::new();
+        for path in paths {
+            let Some(entry) = app_state.file_entries.remove_path(path) else {
+                continue;
+            };
+            let pattern = IgnorePattern::new(PatternKind::Exact, entry.file_name.as_str());
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+            app_state.ignored_entries.push(entry);
+            ignored.push(path.clone());
+        }
+        drop(app_state);
+        (ignored, patterns)
+    };
+    if !ignored.is_empty() {
+        save_ignore_patterns(state, patterns);
+    }
+    ignored
+}
+
+/// Remove the exact patterns of archives from the ignore list and list them again
+///
+/// Returns the archives listed again; an archive stays hidden while
+/// another pattern of the list matches it.
+fn unignore_archives(state: &Arc<Mutex<AppState>>, paths: &[PathBuf]) -> Vec<PathBuf> {
+    let patterns = {
+        let app_state = state.lock();
+        let names: HashSet<String> = app_state
+            .ignored_entries
+            .entries()
+            .iter()
+            .filter(|e| paths.contains(&e.full_path))
+            .flat_map(|e| {
+                [
+                    e.file_name.to_lowercase(),
+                    e.full_path.to_string_lossy().to_lowercase(),
+                ]
+            })
+            .collect();
+        app_state
+            .config
+            .extraction
+            .ignored_files
+            .iter()
+            .filter(|p| p.kind != PatternKind::Exact || !names.contains(&p.pattern.to_lowercase()))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    let matcher = IgnoreMatcher::new(&patterns).ok();
+    save_ignore_patterns(state, patterns);
+
+    let mut app_state = state.lock();
+    let mut restored = Vec::new();
+    for path in paths {
+        if matcher.as_ref().is_none_or(|m| m.ignores(path)) {
+            continue;
+        }
+        if let Some(entry) = app_state.ignored_entries.remove_path(path) {
+            app_state.file_entries.push(entry);
+            restored.push(path.clone());
+        }
+    }
+    drop(app_state);
+    restored
+}
+
+/// Describe archives in a toast: their name if there is one, else their count
+fn archives_text(paths: &[PathBuf]) -> String {
+    match paths {
+        [path] => path.file_name().map(display_name).unwrap_or_default(),
+        _ => format!("{} archives", format_count(paths.len() as u64)),
+    }
+}
+
+/// Unignore archives, pointing to the ignore list if other patterns still hide some
+fn unignore_and_refresh(ui: &MainWindow, state: &Arc<Mutex<AppState>>, paths: &[PathBuf]) {
+    if state.lock().config.advanced.analysis_only {
+        show_toast(
+            ui,
+            &ToastData::warning("The ignore list can't be changed in analysis-only mode"),
+        );
+        return;
+    }
+    let restored = unignore_archives(state, paths);
+    audit_paths(AuditAction::Unignored, &restored);
+    if restored.len() == paths.len() {
+        show_toast(
+            ui,
+            &ToastData::success(format!("Unignored {}", archives_text(paths))),
+        );
+    } else {
+        let hidden: Vec<PathBuf> = paths
+            .iter()
+            .filter(|path| !restored.contains(path))
+            .cloned()
+            .collect();
+        show_toast(
+            ui,
+            &ToastData::warning(format!(
+                "{} still hidden by the ignore list",
+                archives_text(&hidden)
+            ))
+            .with_view(View::Settings),
+        );
+    }
+    refresh_file_table(ui, state, current_threshold(ui));
+    update_ignore_patterns(ui, state);
+}
+
+/// Ignore archives, offering to undo it in the toast
+fn ignore_and_refresh(ui: &MainWindow, state: &Arc<Mutex<AppState>>, paths: &[PathBuf]) {
+    if state.lock().config.advanced.analysis_only {
+        show_toast(
+            ui,
+            &ToastData::warning("The ignore list can't be changed in analysis-only mode"),
+        );
+        return;
+    }
+    let ignored = ignore_archives(state, paths);
+    if ignored.is_empty() {
+        return;
+    }
+    audit_paths(AuditAction::Ignored, &ignored);
+    refresh_file_table(ui, state, current_threshold(ui));
+    update_ignore_patterns(ui, state);
+
+    let state = Arc::clone(state);
+    show_toast_with_action(
+        ui,
+        &ToastData::success(format!("Ignored {}", archives_text(&ignored))),
+        "Undo",
+        move |ui| unignore_and_refresh(ui, &state, &ignored),
+    );
+}
+
 /// Refresh the file table with optional threshold filtering (Phase 2.3)
 fn refresh_file_table(ui: &MainWindow, state: &Arc<Mutex<AppState>>, threshold: Option<u64>) {
-    let entries = {
+    // Filter by threshold if provided, by failed status or corruption if
+    // requested, and hide the archives of the Wabbajack modlist if only own
+    // mods are wanted or by the typed note text; ignored archives are listed
+    // greyed out on request
+    let filter = TableFilter {
+        threshold,
+        failed_only: ui.get_failed_only(),
+        corrupted_only: ui.get_corrupted_only(),
+        own_mods_only: ui.get_own_mods_only(),
+        show_ignored: ui.get_show_ignored(),
+        note: ui.get_note_filter().to_string(),
+    };
+    let view = {
         let app_state = state.lock();
-        app_state.file_entries.entries().to_vec()
+        MainViewModel::build(
+            app_state.file_entries.entries(),
+            app_state.load_order.as_ref(),
+            &app_state.config,
+            &filter,
+        )
+        .with_ignored(app_state.ignored_entries.entries(), &app_state.config, &filter)
     };
+    show_main_view(ui, &view);
 
-    // Filter by threshold if provided
-    let filtered_entries: Vec<&FileEntry> = threshold.map_or_else(
-        || entries.iter().collect(),
-        |threshold_bytes| {
-            entries
-                .iter()
-                .filter(|e| e.file_size <= threshold_bytes)
-                .collect()
-        },
+    tracing::debug!(
+        "Refreshed table: {} files shown{}",
+        view.rows.len(),
+        if filter.is_active() { " (filtered)" } else { "" }
     );
+}
+
+/// Show the file table, its summary and the predicted archive count
+///
+/// Rows picked for the bulk actions stay picked if they are still shown.
+fn show_main_view(ui: &MainWindow, view: &MainViewModel) {
+    let picked: HashSet<String> = ui
+        .get_file_list()
+        .iter()
+        .filter(|row| row.selected)
+        .map(|row| row.path.to_string())
+        .collect();
+    let rows: Vec<FileRowData> = view
+        .all_rows()
+        .map(|row| FileRowData {
+            selected: picked.contains(&row.path),
+            ..file_row_data(row)
+        })
+        .collect();
+    let picked_count = rows.iter().filter(|row| row.selected).count();
+    ui.set_file_list(ModelRc::new(VecModel::from(rows)));
+    ui.set_selected_row(-1);
+    ui.set_selected_count(picked_count.try_into().unwrap_or(i32::MAX));
+    ui.set_total_files(view.rows.len().try_into().unwrap_or(i32::MAX));
+    ui.set_ignored_count(view.ignored_count.try_into().unwrap_or(i32::MAX));
+    ui.set_noted_count(view.noted_count.try_into().unwrap_or(i32::MAX));
+    ui.set_total_files_text(SharedString::from(view.total_files_text()));
+    ui.set_total_size(SharedString::from(view.total_size_text()));
+    ui.set_simulation_text(SharedString::from(view.simulation_text()));
+    ui.set_simulation_over_limit(view.simulation_over_limit());
+}
+
+/// Empty the file table for a new scan
+fn clear_file_table(ui: &MainWindow) {
+    ui.set_file_list(ModelRc::new(VecModel::<FileRowData>::default()));
+    ui.set_selected_row(-1);
+    ui.set_selected_count(0);
+    ui.set_total_files(0);
+}
+
+/// Add rows found by a running scan to the end of the file table
+///
+/// The table is rebuilt with sorting, load order and notes once the scan is
+/// done; until then rows are listed as they arrive.
+fn append_file_rows(ui: &MainWindow, rows: &[FileRow]) {
+    let model = ui.get_file_list();
+    let Some(list) = model.as_any().downcast_ref::<VecModel<FileRowData>>() else {
+        return;
+    };
+    list.extend(rows.iter().map(file_row_data));
+    ui.set_total_files(list.row_count().try_into().unwrap_or(i32::MAX));
+}
+
+/// Mod folders listed in the dashboard's loose file chart
+const DASHBOARD_LOOSE_MODS: usize = 5;
+
+/// Fill the dashboard page from the current scan results
+#[allow(clippy::cast_precision_loss)] // Bar fractions are display-only
+fn update_dashboard(ui: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let (archive_stats, limit, mod_folders, loose_stats) = {
+        let app_state = state.lock();
+        let limit = app_state.config.profile().archive_limit;
+        let mod_folders: BTreeSet<PathBuf> = app_state
+            .file_entries
+            .entries()
+            .iter()
+            .filter_map(|entry| entry.full_path.parent().map(Path::to_path_buf))
+            .collect();
+        (
+            ArchiveStats::compute(app_state.file_entries.entries(), limit),
+            limit,
+            mod_folders.into_iter().collect::<Vec<_>>(),
+            app_state.loose_stats.clone(),
+        )
+    };
+    let to_i32 = |n: usize| i32::try_from(n).unwrap_or(i32::MAX);
+
+    ui.set_dashboard_summary(DashboardSummary {
+        total_archives: SharedString::from(format_count(archive_stats.total_archives as u64)),
+        general_count: to_i32(archive_stats.general_archives),
+        texture_count: to_i32(archive_stats.texture_archives),
+        unknown_count: to_i32(archive_stats.unknown_archives),
+        corrupted_count: to_i32(archive_stats.corrupted_archives),
+        total_size: SharedString::from(format_size(archive_stats.total_size)),
+        average_size: SharedString::from(format_size(archive_stats.average_size)),
+        contained_files: SharedString::from(format_count(archive_stats.total_contained_files)),
+        over_limit: to_i32(archive_stats.over_limit),
+        engine_limit: limit.map_or(0, to_i32),
+    });
+
+    let largest_size = archive_stats.largest_mods.first().map_or(0, |m| m.total_size);
+    let largest_mods: Vec<StatBarData> = archive_stats
+        .largest_mods
+        .iter()
+        .map(|m| StatBarData {
+            label: SharedString::from(&m.name),
+            value: SharedString::from(format_size(m.total_size)),
+            fraction: if largest_size > 0 {
+                m.total_size as f32 / largest_size as f32
+            } else {
+                0.0
+            },
+        })
+        .collect();
+
+    let max_count = archive_stats.max_bucket_count();
+    let histogram: Vec<StatBarData> = archive_stats
+        .histogram
+        .iter()
+        .map(|b| StatBarData {
+            label: SharedString::from(b.label()),
+            value: SharedString::from(format_count(b.count as u64)),
+            fraction: if max_count > 0 {
+                b.count as f32 / max_count as f32
+            } else {
+                0.0
+            },
+        })
+        .collect();
+
+    ui.set_dashboard_largest_mods(ModelRc::new(VecModel::from(largest_mods)));
+    ui.set_dashboard_size_histogram(ModelRc::new(VecModel::from(histogram)));
+    ui.set_dashboard_has_data(archive_stats.total_archives > 0);
+
+    // Counting loose files walks every mod folder, so it runs in the background
+    let weak = ui.as_weak();
+    std::thread::spawn(move || {
+        let busiest = loose_stats.busiest(&mod_folders, DASHBOARD_LOOSE_MODS);
+        let most_files = busiest.first().map_or(0, |(_, s)| s.files);
+        let _ = slint::invoke_from_event_loop(move || {
+            let Some(ui) = weak.upgrade() else {
+                return;
+            };
+            let bars: Vec<StatBarData> = busiest
+                .iter()
+                .map(|(folder, s)| StatBarData {
+                    label: SharedString::from(
+                        folder
+                            .file_name()
+                            .map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+                    ),
+                    value: SharedString::from(format!(
+                        "{} ({})",
+                        format_count(s.files as u64),
+                        format_size(s.bytes)
+                    )),
+                    fraction: if most_files > 0 {
+                        s.files as f32 / most_files as f32
+                    } else {
+                        0.0
+                    },
+                })
+                .collect();
+            ui.set_dashboard_loose_mods(ModelRc::new(VecModel::from(bars)));
+        });
+    });
+}
+
+/// Store the metrics of a finished run and refresh the Performance page
+fn record_run_metrics(
+    weak: &slint::Weak<MainWindow>,
+    state: &Arc<Mutex<AppState>>,
+    recorder: MetricsRecorder,
+) {
+    let run = recorder.finish();
+    let history = {
+        let mut app_state = state.lock();
+        app_state.metrics.push(run);
+        app_state.metrics.clone()
+    };
+    if let Err(e) = history.save() {
+        tracing::warn!("Failed to save extraction metrics: {}", e);
+    }
+
+    let weak = weak.clone();
+    let state = Arc::clone(state);
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(ui) = weak.upgrade() {
+            update_performance(&ui, &state);
+        }
+    });
+}
+
+/// Fill the Performance page from the recorded run metrics
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Display-only values
+#[allow(clippy::cast_precision_loss)] // Bar fractions are display-only
+fn update_performance(ui: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let history = state.lock().metrics.clone();
+    let Some(latest) = history.latest() else {
+        ui.set_performance_has_data(false);
+        return;
+    };
+    let to_i32 = |n: usize| i32::try_from(n).unwrap_or(i32::MAX);
+    let numbers = number_format();
+
+    let age = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+        .saturating_sub(latest.started_at);
+    ui.set_performance_summary(PerformanceSummary {
+        runs: to_i32(history.runs.len()),
+        started: SharedString::from(format!("{} ago", format_duration(age.max(1)))),
+        duration: SharedString::from(format_duration(latest.duration_secs.ceil() as u64)),
+        archives_per_sec: SharedString::from(numbers.format_decimal(latest.archives_per_sec(), 1)),
+        throughput: SharedString::from(numbers.format_speed(latest.bytes_per_sec())),
+        retries: to_i32(latest.retries),
+        lock_retries: to_i32(latest.lock_retries),
+        peak_parallel: to_i32(latest.peak_parallel),
+        failed: to_i32(latest.failed),
+    });
+
+    // Newest run first
+    let fastest = history
+        .runs
+        .iter()
+        .map(RunMetrics::bytes_per_sec)
+        .fold(0.0, f64::max);
+    let runs: Vec<StatBarData> = history
+        .runs
+        .iter()
+        .rev()
+        .map(|run| StatBarData {
+            label: SharedString::from(format!(
+                "{} archives",
+                format_count((run.succeeded + run.failed) as u64)
+            )),
+            value: SharedString::from(numbers.format_speed(run.bytes_per_sec())),
+            fraction: if fastest > 0.0 {
+                (run.bytes_per_sec() / fastest) as f32
+            } else {
+                0.0
+            },
+        })
+        .collect();
+
+    let most_failures = latest.failure_categories.values().copied().max().unwrap_or(0);
+    let failures: Vec<StatBarData> = latest
+        .failure_categories
+        .iter()
+        .map(|(category, count)| StatBarData {
+            label: SharedString::from(category.as_str()),
+            value: SharedString::from(format_count(*count as u64)),
+            fraction: *count as f32 / most_failures as f32,
+        })
+        .collect();
 
-    let row_data: Vec<FileRowData> = filtered_entries
+    let longest = latest.slowest.first().map_or(0.0, |timing| timing.duration_secs);
+    let slowest: Vec<StatBarData> = latest
+        .slowest
         .iter()
-        .map(|e| FileRowData {
-            file_name: SharedString::from(&e.file_name),
-            file_size: SharedString::from(e.size_display()),
-            num_files: SharedString::from(e.file_count_display()),
-            mod_name: SharedString::from(e.mod_display()),
-            is_bad: e.is_corrupted(),
+        .map(|timing| StatBarData {
+            label: SharedString::from(format!("{} ({})", timing.name, format_size(timing.size))),
+            value: SharedString::from(format_elapsed(
+                Duration::try_from_secs_f64(timing.duration_secs).unwrap_or_default(),
+            )),
+            fraction: if longest > 0.0 {
+                (timing.duration_secs / longest) as f32
+            } else {
+                0.0
+            },
         })
         .collect();
 
-    let total_size: u64 = filtered_entries.iter().map(|e| e.file_size).sum();
+    ui.set_performance_history(ModelRc::new(VecModel::from(runs)));
+    ui.set_performance_failures(ModelRc::new(VecModel::from(failures)));
+    ui.set_performance_slowest(ModelRc::new(VecModel::from(slowest)));
+    ui.set_performance_has_data(true);
+}
+
+/// Set up the "Clear Metrics" callback of the Performance page
+fn setup_metrics_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+
+    main_window.on_clear_metrics(move || {
+        state.lock().metrics = MetricsHistory::default();
+        if let Err(e) = MetricsHistory::clear() {
+            tracing::warn!("Failed to delete extraction metrics: {}", e);
+        }
+        if let Some(ui) = weak.upgrade() {
+            update_performance(&ui, &state);
+            show_toast(&ui, &ToastData::info("Extraction metrics cleared"));
+        }
+    });
+}
+
+/// Re-apply the threshold from the previous session after a scan
+fn restore_saved_threshold(ui: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let saved = state.lock().config.saved.clone();
+
+    if saved.auto_threshold {
+        tracing::info!("Restoring saved auto-threshold");
+        ui.set_auto_threshold(true);
+        ui.invoke_auto_threshold_toggled(true);
+    } else if !saved.threshold.is_zero() {
+        tracing::info!("Restoring saved threshold: {} bytes", saved.threshold.bytes());
+        ui.set_auto_threshold(false);
+        ui.set_threshold_value(SharedString::from(saved.threshold.to_string()));
+        refresh_file_table(ui, state, current_threshold(ui));
+    }
+}
+
+/// Quiet time after the last change to the session settings before they are written
+const SAVED_CONFIG_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Requests to write the config, handled one at a time by a single thread
+///
+/// Requests arriving within [`SAVED_CONFIG_DEBOUNCE`] of each other are merged,
+/// so typing a threshold writes the config once, with the value typed last.
+static SAVED_CONFIG_WRITER: LazyLock<mpsc::Sender<Arc<Mutex<AppState>>>> = LazyLock::new(|| {
+    let (tx, rx) = mpsc::channel::<Arc<Mutex<AppState>>>();
+    std::thread::spawn(move || {
+        while let Ok(mut state) = rx.recv() {
+            while let Ok(newer) = rx.recv_timeout(SAVED_CONFIG_DEBOUNCE) {
+                state = newer;
+            }
+            // Write a snapshot, so the state stays unlocked during the write
+            let config = state.lock().config.clone();
+            if let Err(e) = config.save() {
+                tracing::error!("Failed to save configuration: {}", e);
+            }
+        }
+    });
+    tx
+});
+
+/// Update the saved session settings and write the config in the background
+///
+/// `update` returns whether anything changed; unchanged settings are not written.
+/// The settings change right away, in the order of the calls; the writes go
+/// through [`SAVED_CONFIG_WRITER`], which always writes the current settings.
+fn update_saved_config<F>(state: &Arc<Mutex<AppState>>, update: F)
+where
+    F: FnOnce(&mut SavedConfig) -> bool,
+{
+    if update(&mut state.lock().config.saved) {
+        let _ = SAVED_CONFIG_WRITER.send(Arc::clone(state));
+    }
+}
+
+/// Parse the threshold currently entered in the UI, if any
+fn current_threshold(ui: &MainWindow) -> Option<u64> {
+    let value = ui.get_threshold_value();
+    if value.is_empty() {
+        return None;
+    }
+    SizeSpec::parse(&value).ok().map(SizeSpec::bytes)
+}
+
+/// Convert a table row into its Slint representation
+fn file_row_data(row: &FileRow) -> FileRowData {
+    FileRowData {
+        file_name: SharedString::from(&row.file_name),
+        file_size: SharedString::from(&row.file_size),
+        num_files: SharedString::from(&row.num_files),
+        mod_name: SharedString::from(&row.mod_name),
+        is_bad: row.is_bad,
+        health: row_health(&row.health),
+        health_label: SharedString::from(row.health.label()),
+        path: SharedString::from(&row.path),
+        status: row_status(row.status),
+        extraction_time: SharedString::from(&row.extraction_time),
+        marked: row.marked,
+        texture_note: SharedString::from(&row.texture_note),
+        in_modlist: row.in_modlist,
+        bad_reason: SharedString::from(row.health.reason()),
+        forced: row.forced,
+        load_order: SharedString::from(&row.load_order),
+        version_warning: SharedString::from(&row.version_warning),
+        ignored: row.ignored,
+        selected: false,
+        note: SharedString::from(&row.note),
+        size_heat: f32::from(row.size_heat) / 100.0,
+        exclusion: SharedString::from(&row.exclusion),
+        exclusion_overridden: row.exclusion_overridden,
+    }
+}
+
+/// Map an extraction status to its Slint representation
+const fn row_status(status: ExtractionStatus) -> RowStatus {
+    match status {
+        ExtractionStatus::Idle => RowStatus::Idle,
+        ExtractionStatus::Queued => RowStatus::Queued,
+        ExtractionStatus::Extracting => RowStatus::Extracting,
+        ExtractionStatus::Done => RowStatus::Done,
+        ExtractionStatus::Failed => RowStatus::Failed,
+        ExtractionStatus::Skipped => RowStatus::Skipped,
+    }
+}
 
-    ui.set_file_list(ModelRc::new(VecModel::from(row_data)));
-    ui.set_total_files(filtered_entries.len().try_into().unwrap_or(i32::MAX));
-    ui.set_total_size(SharedString::from(format_size(total_size, BINARY)));
+/// Map the health of an archive to its Slint representation
+const fn row_health(health: &ArchiveHealth) -> RowHealth {
+    match health {
+        ArchiveHealth::Ok => RowHealth::Healthy,
+        ArchiveHealth::Unreadable(_) => RowHealth::Unreadable,
+        ArchiveHealth::InvalidFormat(_) => RowHealth::InvalidFormat,
+        ArchiveHealth::UnsupportedVersion(_) => RowHealth::UnsupportedVersion,
+    }
+}
 
-    tracing::debug!(
-        "Refreshed table: {} files shown{}",
-        filtered_entries.len(),
-        if threshold.is_some() {
-            " (filtered)"
-        } else {
-            ""
-        }
-    );
+/// Font scale steps offered by the settings "Font Size" combo box
+/// (must match `font-scale-steps` in main.slint)
+const FONT_SCALE_STEPS: [f32; 5] = [0.9, 1.0, 1.25, 1.5, 2.0];
+
+/// Find the combo box index of the step closest to `scale`
+fn font_scale_index(scale: f32) -> i32 {
+    FONT_SCALE_STEPS
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - scale).abs().total_cmp(&(*b - scale).abs()))
+        .and_then(|(idx, _)| i32::try_from(idx).ok())
+        .unwrap_or(1)
 }
 
 /// Set up debug log viewer callbacks (Phase 3.3)
@@ -1484,6 +6733,7 @@ fn setup_log_viewer_callbacks(main_window: &MainWindow) {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
 
     #[test]
     fn test_slint_module_exists() {
@@ -1491,79 +6741,566 @@ mod tests {
         // We can't actually run the UI in tests, but we can verify it compiles
         assert!(true, "Slint module compiled successfully");
     }
+
+    #[test]
+    fn test_font_scale_index() {
+        assert_eq!(font_scale_index(1.0), 1);
+        assert_eq!(font_scale_index(2.0), 4);
+        assert_eq!(font_scale_index(1.3), 2);
+        assert_eq!(font_scale_index(0.75), 0);
+    }
 }
-/// Set up settings callbacks (Phase 2.2)
-fn setup_settings_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
-    // Handle setting changes
-    let state_for_settings = Arc::clone(state);
-    main_window.on_settings_changed(move |key, value| {
-        let key_str = key.to_string();
-        let value_str = value.to_string();
-        tracing::info!("Setting changed: {} = {}", key_str, value_str);
+/// Set up "Preview Rules", a dry run of the selection rules on the current scan
+///
+/// Lists the archives each rule matches and offers to apply the resulting
+/// selection without rescanning.
+fn setup_rules_preview_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
 
-        let state_clone = Arc::clone(&state_for_settings);
+    main_window.on_settings_preview_rules(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        let (previews, selected) = {
+            let app_state = state.lock();
+            let rules = &app_state.config.extraction.selection_rules;
+            let entries = app_state.file_entries.entries();
+            let matches = (preview_rules(rules, entries), selected_paths(rules, entries));
+            drop(app_state);
+            matches
+        };
 
-        // Update config in background to avoid blocking UI
+        if previews.is_empty() {
+            show_dialog(
+                &ui,
+                DialogConfig::info(
+                    "No selection rules",
+                    "Enter rules such as \"always size < 5 MB\" or \"never mod *HD Textures*\", \
+                     separated by semicolons.",
+                ),
+            );
+            return;
+        }
+        if state.lock().file_entries.is_empty() {
+            show_toast(&ui, &ToastData::info("Scan a folder to preview the selection rules"));
+            return;
+        }
+
+        let mut message = String::new();
+        for preview in &previews {
+            let _ = write!(
+                message,
+                "{}: {} archives",
+                preview.rule,
+                format_count(preview.matched.len() as u64)
+            );
+            for name in preview.matched.iter().take(PREVIEW_MAX_LISTED_MATCHES) {
+                let _ = write!(message, "\n    {name}");
+            }
+            if preview.matched.len() > PREVIEW_MAX_LISTED_MATCHES {
+                let _ = write!(
+                    message,
+                    "\n    ... and {} more",
+                    preview.matched.len() - PREVIEW_MAX_LISTED_MATCHES
+                );
+            }
+            message.push_str("\n\n");
+        }
+        let _ = write!(
+            message,
+            "The rules select {} archives.",
+            format_count(selected.len() as u64)
+        );
+
+        let state = Arc::clone(&state);
+        show_confirmation(
+            &ui,
+            DialogConfig::info("Selection rules preview", message)
+                .with_primary_button("Apply selection")
+                .with_secondary_button("Close"),
+            move |ui| {
+                let paths: HashSet<PathBuf> = selected.into_iter().collect();
+                let selected = state.lock().file_entries.select_paths(&paths);
+                ui.set_marked_count(selected.try_into().unwrap_or(i32::MAX));
+                refresh_file_table(ui, &state, current_threshold(ui));
+                show_toast(
+                    ui,
+                    &ToastData::success(format!(
+                        "Selected {} archives by rule",
+                        format_count(selected as u64)
+                    )),
+                );
+            },
+        );
+    });
+}
+
+/// Maximum number of archives listed per rule in the rules preview
+const PREVIEW_MAX_LISTED_MATCHES: usize = 5;
+
+/// Show which archives of the scanned folder each postfix matches
+fn setup_postfix_test_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+
+    main_window.on_settings_test_postfixes(move || {
+        let (postfixes, directory) = {
+            let config = &state.lock().config;
+            (config.extraction.postfixes.clone(), config.saved.directory.clone())
+        };
+        if directory.is_empty() {
+            if let Some(ui) = weak.upgrade() {
+                show_toast(&ui, &ToastData::info("Choose a folder to test the postfixes against"));
+            }
+            return;
+        }
+        let weak = weak.clone();
+
+        // Listing every mod folder can take a moment on large setups
         std::thread::spawn(move || {
-            let save_result = {
-                let mut app_state = state_clone.lock();
-                let config = &mut app_state.config;
-                let mut save_needed = true;
+            let names: Vec<String> = match list_archives(Path::new(&directory)) {
+                Ok(archives) => archives
+                    .iter()
+                    .filter_map(|path| path.file_name().map(display_name))
+                    .collect(),
+                Err(e) => {
+                    tracing::error!("Failed to list archives in {}: {}", directory, e);
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak.upgrade() {
+                            show_toast(
+                                &ui,
+                                &ToastData::error(format!(
+                                    "Could not list archives: {}",
+                                    e.user_message()
+                                )),
+                            );
+                        }
+                    });
+                    return;
+                }
+            };
 
-                match key_str.as_str() {
-                    "postfixes" => {
-                        // Split by comma and trim
-                        config.extraction.postfixes = value_str
-                            .split(',')
-                            .map(|s| s.trim().to_string())
-                            .filter(|s| !s.is_empty())
-                            .collect();
-                    }
-                    "ignored_files" => {
-                        config.extraction.ignored_files = value_str
-                            .split(',')
-                            .map(|s| s.trim().to_string())
-                            .filter(|s| !s.is_empty())
-                            .collect();
-                    }
-                    "theme_mode" => {
-                        config.appearance.theme_mode = value_str;
-                    }
-                    "language" => {
-                        config.appearance.language = value_str;
+            let previews = preview_postfixes(&postfixes, &names);
+            let mut message = String::new();
+            for preview in &previews {
+                match &preview.matched {
+                    Ok(matched) => {
+                        let _ = write!(
+                            message,
+                            "{}: {} archives",
+                            preview.postfix,
+                            format_count(matched.len() as u64)
+                        );
+                        for name in matched.iter().take(PREVIEW_MAX_LISTED_MATCHES) {
+                            let _ = write!(message, "\n    {name}");
+                        }
+                        if matched.len() > PREVIEW_MAX_LISTED_MATCHES {
+                            let _ = write!(
+                                message,
+                                "\n    ... and {} more",
+                                matched.len() - PREVIEW_MAX_LISTED_MATCHES
+                            );
+                        }
                     }
-                    _ => {
-                        tracing::warn!("Unknown setting key: {}", key_str);
-                        save_needed = false;
+                    Err(reason) => {
+                        let _ = write!(message, "{}: invalid, {reason}", preview.postfix);
                     }
                 }
+                message.push_str("\n\n");
+            }
+            let matcher = PostfixMatcher::new(&postfixes).unwrap_or_default();
+            let listed = names.iter().filter(|name| matcher.matches(name)).count();
+            let _ = write!(
+                message,
+                "A scan lists {} of {} archives, before ignored files.",
+                format_count(listed as u64),
+                format_count(names.len() as u64)
+            );
+
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = weak.upgrade() {
+                    show_dialog(&ui, DialogConfig::info("Postfix test", message));
+                }
+            });
+        });
+    });
+}
 
-                if save_needed {
-                    Some(config.save())
-                } else {
-                    None
+/// Maximum number of archives named per pattern in the ignore list editor
+const IGNORE_PREVIEW_MAX_NAMES: usize = 3;
+
+/// Editor row of an ignore pattern, summarizing the archives it hides
+fn ignore_pattern_row(pattern: &IgnorePattern, archives: &[PathBuf]) -> IgnorePatternData {
+    let kind = PatternKind::ALL
+        .iter()
+        .position(|&kind| kind == pattern.kind)
+        .unwrap_or_default();
+    let mut row = IgnorePatternData {
+        kind: i32::try_from(kind).unwrap_or_default(),
+        pattern: SharedString::from(pattern.pattern.as_str()),
+        summary: SharedString::new(),
+        invalid: false,
+    };
+
+    let summary = if pattern.pattern.is_empty() {
+        "Enter a pattern".to_string()
+    } else if archives.is_empty() {
+        "Scan a folder to see which archives this pattern hides".to_string()
+    } else {
+        let preview = preview_ignored(std::slice::from_ref(pattern), archives);
+        match &preview[0].matched {
+            Ok(matched) if matched.is_empty() => "Hides no archives of the scanned folder".to_string(),
+            Ok(matched) => {
+                let mut names = matched
+                    .iter()
+                    .take(IGNORE_PREVIEW_MAX_NAMES)
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if matched.len() > IGNORE_PREVIEW_MAX_NAMES {
+                    let _ = write!(
+                        names,
+                        " and {} more",
+                        format_count((matched.len() - IGNORE_PREVIEW_MAX_NAMES) as u64)
+                    );
                 }
+                format!("Hides {} archives: {names}", format_count(matched.len() as u64))
+            }
+            Err(reason) => {
+                row.invalid = true;
+                reason.clone()
+            }
+        }
+    };
+    row.summary = SharedString::from(summary);
+    row
+}
+
+/// Fill the ignore list editor from the configuration
+fn update_ignore_patterns(ui: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    let rows: Vec<IgnorePatternData> = {
+        let app_state = state.lock();
+        app_state
+            .config
+            .extraction
+            .ignored_files
+            .iter()
+            .map(|pattern| ignore_pattern_row(pattern, &app_state.folder_archives))
+            .collect()
+    };
+    ui.set_settings_ignore_patterns(ModelRc::new(VecModel::from(rows)));
+}
+
+/// Read the patterns of the ignore list editor
+fn editor_ignore_patterns(ui: &MainWindow) -> Vec<IgnorePattern> {
+    ui.get_settings_ignore_patterns()
+        .iter()
+        .map(|row| {
+            let kind = usize::try_from(row.kind)
+                .ok()
+                .and_then(|i| PatternKind::ALL.get(i).copied())
+                .unwrap_or_default();
+            IgnorePattern::new(kind, row.pattern.as_str())
+        })
+        .collect()
+}
+
+/// Save the ignore list of the editor, unless a pattern is invalid
+///
+/// Nothing is saved in analysis-only mode.
+fn save_ignore_patterns(state: &Arc<Mutex<AppState>>, patterns: Vec<IgnorePattern>) {
+    // Empty rows are being typed into and would hide everything as substrings
+    let patterns: Vec<IgnorePattern> =
+        patterns.into_iter().filter(|p| !p.pattern.is_empty()).collect();
+    if let Err(e) = IgnoreMatcher::new(&patterns) {
+        tracing::debug!("Not saving ignore patterns: {}", e);
+        return;
+    }
+
+    {
+        let mut app_state = state.lock();
+        if app_state.config.advanced.analysis_only {
+            tracing::debug!("Not saving ignore patterns in analysis-only mode");
+            return;
+        }
+        if app_state.config.extraction.ignored_files == patterns {
+            return;
+        }
+        app_state.config.extraction.ignored_files = patterns;
+    }
+
+    // Each save writes the settings as they are when it starts, without
+    // holding the state lock during the write
+    let state = Arc::clone(state);
+    std::thread::spawn(move || {
+        let config = state.lock().config.clone();
+        if let Err(e) = config.save() {
+            tracing::error!("Failed to save configuration: {}", e);
+        }
+    });
+}
+
+/// Set up the ignore list editor, previewing each pattern while it is typed
+fn setup_ignore_editor_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    {
+        let weak = main_window.as_weak();
+        let state = Arc::clone(state);
+        main_window.on_settings_ignore_pattern_edited(move |index, kind, pattern| {
+            let Some(ui) = weak.upgrade() else {
+                return;
             };
+            let Ok(row) = usize::try_from(index) else {
+                return;
+            };
+            let mut patterns = editor_ignore_patterns(&ui);
+            let Some(edited) = patterns.get_mut(row) else {
+                return;
+            };
+            edited.kind = usize::try_from(kind)
+                .ok()
+                .and_then(|i| PatternKind::ALL.get(i).copied())
+                .unwrap_or_default();
+            edited.pattern = pattern.to_string();
+
+            // Only the edited row changes, so the other inputs keep their focus
+            let data = ignore_pattern_row(edited, &state.lock().folder_archives);
+            ui.get_settings_ignore_patterns().set_row_data(row, data);
+            save_ignore_patterns(&state, patterns);
+        });
+    }
 
-            if let Some(result) = save_result {
-                if let Err(e) = result {
-                    tracing::error!("Failed to save configuration: {}", e);
-                } else {
-                    tracing::debug!("Configuration saved");
-                }
+    {
+        let weak = main_window.as_weak();
+        let state = Arc::clone(state);
+        main_window.on_settings_ignore_pattern_removed(move |index| {
+            let Some(ui) = weak.upgrade() else {
+                return;
+            };
+            if state.lock().config.advanced.analysis_only {
+                show_toast(
+                    &ui,
+                    &ToastData::warning("The ignore list can't be changed in analysis-only mode"),
+                );
+                return;
             }
+            let mut patterns = editor_ignore_patterns(&ui);
+            let Some(row) = usize::try_from(index).ok().filter(|&row| row < patterns.len()) else {
+                return;
+            };
+            patterns.remove(row);
+
+            let rows: Vec<IgnorePatternData> = {
+                let archives = &state.lock().folder_archives;
+                patterns.iter().map(|pattern| ignore_pattern_row(pattern, archives)).collect()
+            };
+            ui.set_settings_ignore_patterns(ModelRc::new(VecModel::from(rows)));
+            save_ignore_patterns(&state, patterns);
         });
+    }
+
+    let weak = main_window.as_weak();
+    let state = Arc::clone(state);
+    main_window.on_settings_ignore_pattern_added(move || {
+        let Some(ui) = weak.upgrade() else {
+            return;
+        };
+        if state.lock().config.advanced.analysis_only {
+            show_toast(
+                &ui,
+                &ToastData::warning("The ignore list can't be changed in analysis-only mode"),
+            );
+            return;
+        }
+        let mut rows: Vec<IgnorePatternData> = ui.get_settings_ignore_patterns().iter().collect();
+        rows.push(ignore_pattern_row(
+            &IgnorePattern::new(PatternKind::default(), ""),
+            &[],
+        ));
+        ui.set_settings_ignore_patterns(ModelRc::new(VecModel::from(rows)));
     });
+}
+
+/// Apply a changed text setting to the config
+///
+/// Returns whether the config should be saved; values that don't parse yet
+/// are left out.
+#[allow(clippy::too_many_lines)] // One arm per text setting
+fn update_setting(
+    config: &mut AppConfig,
+    key: &str,
+    value: String,
+    weak: &slint::Weak<MainWindow>,
+) -> bool {
+    let mut save_needed = true;
+
+    match key {
+        "postfixes" => {
+            // Split by comma and trim
+            let postfixes: Vec<String> = value
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect();
+            // Incomplete patterns are expected while typing; keep the last valid ones
+            let error = match PostfixMatcher::new(&postfixes) {
+                Ok(_) => {
+                    config.extraction.postfixes = postfixes;
+                    String::new()
+                }
+                Err(e) => {
+                    tracing::debug!("Not saving postfixes: {}", e);
+                    save_needed = false;
+                    e.user_message()
+                }
+            };
+            let weak = weak.clone();
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = weak.upgrade() {
+                    ui.set_settings_postfixes_error(SharedString::from(error));
+                }
+            });
+        }
+        "selection_rules" => {
+            // Incomplete rules are expected while typing; keep the last valid ones
+            let error = match parse_rules(&value) {
+                Ok(rules) => {
+                    config.extraction.selection_rules = rules;
+                    String::new()
+                }
+                Err(e) => {
+                    tracing::debug!("Not saving selection rules: {}", e);
+                    save_needed = false;
+                    e.user_message()
+                }
+            };
+            let weak = weak.clone();
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = weak.upgrade() {
+                    ui.set_settings_rules_error(SharedString::from(error));
+                }
+            });
+        }
+        "open_with" => {
+            // Incomplete entries are expected while typing; keep the last valid ones
+            let error = match parse_tools(&value) {
+                Ok(tools) => {
+                    config.advanced.open_with = tools;
+                    String::new()
+                }
+                Err(e) => {
+                    tracing::debug!("Not saving open with tools: {}", e);
+                    save_needed = false;
+                    e.user_message()
+                }
+            };
+            let tools = open_tools(config);
+            let weak = weak.clone();
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(ui) = weak.upgrade() {
+                    ui.set_settings_open_with_error(SharedString::from(error));
+                    show_open_tools(&ui, &tools);
+                }
+            });
+        }
+        "abort_after_failures" => match AbortThreshold::parse(&value) {
+            Ok(threshold) => config.extraction.abort_after_failures = threshold,
+            Err(e) => {
+                // Incomplete thresholds are expected while typing
+                tracing::debug!("Not saving failure threshold: {}", e);
+                save_needed = false;
+            }
+        },
+        "loose_file_limit" => match value.trim().parse::<usize>() {
+            Ok(limit) => config.extraction.loose_file_limit = limit,
+            Err(e) => {
+                tracing::debug!("Not saving loose file limit: {}", e);
+                save_needed = false;
+            }
+        },
+        "theme_mode" => {
+            config.appearance.theme_mode = value;
+        }
+        "language" => {
+            config.appearance.language = value;
+        }
+        "link_mode" => match value.as_str() {
+            "off" => config.extraction.link_mode = LinkMode::Off,
+            "hardlink" => config.extraction.link_mode = LinkMode::Hardlink,
+            "symlink" => config.extraction.link_mode = LinkMode::Symlink,
+            _ => {
+                tracing::warn!("Unknown link mode: {}", value);
+                save_needed = false;
+            }
+        },
+        "schedule" => match value.as_str() {
+            "largest-first" => config.extraction.schedule = ScheduleOrder::LargestFirst,
+            "smallest-first" => config.extraction.schedule = ScheduleOrder::SmallestFirst,
+            "as-listed" => config.extraction.schedule = ScheduleOrder::AsListed,
+            _ => {
+                tracing::warn!("Unknown extraction order: {}", value);
+                save_needed = false;
+            }
+        },
+        "storage_medium" => match value.as_str() {
+            "auto" => config.advanced.performance.storage_medium = None,
+            "hdd" => config.advanced.performance.storage_medium = Some(StorageMedium::Hdd),
+            "ssd" => config.advanced.performance.storage_medium = Some(StorageMedium::Ssd),
+            "nvme" => config.advanced.performance.storage_medium = Some(StorageMedium::Nvme),
+            _ => {
+                tracing::warn!("Unknown drive type: {}", value);
+                save_needed = false;
+            }
+        },
+        "texture_policy" => match value.as_str() {
+            "skip" => config.extraction.texture_policy = TexturePolicy::Skip,
+            "extract" => config.extraction.texture_policy = TexturePolicy::Extract,
+            "convert" => config.extraction.texture_policy = TexturePolicy::Convert,
+            _ => {
+                tracing::warn!("Unknown texture policy: {}", value);
+                save_needed = false;
+            }
+        },
+        "size_units" => match value.as_str() {
+            "binary" => config.appearance.size_units = SizeUnits::Binary,
+            "decimal" => config.appearance.size_units = SizeUnits::Decimal,
+            _ => {
+                tracing::warn!("Unknown size units: {}", value);
+                save_needed = false;
+            }
+        },
+        "font_scale" => match value.parse::<f32>() {
+            Ok(scale) => {
+                config.appearance.font_scale = scale.clamp(
+                    AppearanceConfig::MIN_FONT_SCALE,
+                    AppearanceConfig::MAX_FONT_SCALE,
+                );
+            }
+            Err(e) => {
+                tracing::warn!("Invalid font scale '{}': {}", value, e);
+                save_needed = false;
+            }
+        },
+        _ => {
+            tracing::warn!("Unknown setting key: {}", key);
+            save_needed = false;
+        }
+    }
+    save_needed
+}
 
-    // Handle toggle changes
+/// Save toggled settings
+fn setup_toggle_callback(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
     let state_for_toggles = Arc::clone(state);
+    let weak_for_toggles = main_window.as_weak();
     main_window.on_settings_toggle_changed(move |key, value| {
         let key_str = key.to_string();
         tracing::info!("Toggle setting changed: {} = {}", key_str, value);
 
         let state = Arc::clone(&state_for_toggles);
+        let weak = weak_for_toggles.clone();
         std::thread::spawn(move || {
-            let save_result = {
+            let snapshot = {
                 let mut app_state = state.lock();
                 let config = &mut app_state.config;
                 let mut save_needed = true;
@@ -1571,14 +7308,92 @@ fn setup_settings_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState
                 match key_str.as_str() {
                     "ignore_bad_files" => config.extraction.ignore_bad_files = value,
                     "auto_backup" => config.extraction.auto_backup = value,
+                    "confirm_extraction" => config.extraction.confirm_extraction = value,
+                    "write_manifest" => config.extraction.write_manifest = value,
+                    "use_temp" => config.extraction.use_temp = value,
+                    "serialize_per_mod" => config.extraction.serialize_per_mod = value,
+                    "allow_unknown_versions" => {
+                        config.extraction.allow_unknown_versions = value;
+                    }
+                    "data_folder_mode" => config.extraction.data_folder_mode = value,
                     "check_updates" => config.update.check_at_startup = value,
                     "show_debug" => config.advanced.show_debug = value,
+                    "analysis_only" => config.advanced.analysis_only = value,
+                    "collect_metrics" => config.advanced.diagnostics.collect_metrics = value,
+                    "defender_benchmark" => config.advanced.diagnostics.defender_benchmark = value,
+                    "write_status_file" => config.advanced.diagnostics.write_status_file = value,
+                    "notify_when_done" => config.advanced.integrations.notify_when_done = value,
+                    "low_priority" => config.advanced.performance.low_priority = value,
+                    "auto_scan_on_start" => config.advanced.integrations.auto_scan_on_start = value,
+                    "discord_presence" => config.advanced.integrations.discord_presence = value,
+                    "high_contrast" => config.appearance.high_contrast = value,
                     _ => {
                         tracing::warn!("Unknown toggle setting key: {}", key_str);
                         save_needed = false;
                     }
                 }
 
+                // Write a snapshot, so the state stays unlocked during the write
+                let snapshot = save_needed.then(|| config.clone());
+                drop(app_state);
+                snapshot
+            };
+
+            match snapshot.map(|config| config.save()) {
+                Some(Ok(())) => refresh_config_backups(weak, state),
+                Some(Err(e)) => tracing::error!("Failed to save configuration: {}", e),
+                None => {}
+            }
+        });
+    });
+}
+
+/// Set up settings callbacks (Phase 2.2)
+fn setup_settings_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState>>) {
+    // Handle setting changes
+    let state_for_settings = Arc::clone(state);
+    let weak_for_settings = main_window.as_weak();
+    main_window.on_settings_changed(move |key, value| {
+        let key_str = key.to_string();
+        let value_str = value.to_string();
+        tracing::info!("Setting changed: {} = {}", key_str, value_str);
+
+        let state_clone = Arc::clone(&state_for_settings);
+        let weak = weak_for_settings.clone();
+        let weak_for_backups = weak_for_settings.clone();
+
+        // Update config in background to avoid blocking UI
+        std::thread::spawn(move || {
+            let save_result = {
+                let mut app_state = state_clone.lock();
+                let config = &mut app_state.config;
+                let save_needed = update_setting(config, &key_str, value_str, &weak);
+
+                // Texture archives show the active policy in the file list
+                if save_needed && key_str == "texture_policy" {
+                    let state = Arc::clone(&state_clone);
+                    let weak = weak.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak.upgrade() {
+                            refresh_file_table(&ui, &state, current_threshold(&ui));
+                        }
+                    });
+                }
+
+                // Locale and unit changes affect every displayed number, and
+                // the language how names are sorted
+                if save_needed && matches!(key_str.as_str(), "language" | "size_units") {
+                    set_number_format(NumberFormat::from_appearance(&config.appearance));
+                    set_name_collation_from_appearance(&config.appearance);
+
+                    let state = Arc::clone(&state_clone);
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(ui) = weak.upgrade() {
+                            refresh_file_table(&ui, &state, current_threshold(&ui));
+                        }
+                    });
+                }
+
                 if save_needed {
                     Some(config.save())
                 } else {
@@ -1586,9 +7401,19 @@ fn setup_settings_callbacks(main_window: &MainWindow, state: &Arc<Mutex<AppState
                 }
             };
 
-            if let Some(Err(e)) = save_result {
-                tracing::error!("Failed to save configuration: {}", e);
+            if let Some(result) = save_result {
+                if let Err(e) = result {
+                    tracing::error!("Failed to save configuration: {}", e);
+                } else {
+                    tracing::debug!("Configuration saved");
+                    refresh_config_backups(weak_for_backups, state_clone);
+                }
             }
         });
     });
+
+    setup_rules_preview_callback(main_window, state);
+    setup_postfix_test_callback(main_window, state);
+    setup_ignore_editor_callbacks(main_window, state);
+    setup_toggle_callback(main_window, state);
 }
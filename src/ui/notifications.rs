@@ -2,10 +2,52 @@
 //!
 //! This module provides helper functions for showing toast notifications and modal dialogs.
 //! It integrates with the Slint UI components defined in main.slint.
-
-use crate::ui::{MainWindow, NotificationType};
-use slint::{ComponentHandle, Model, ModelRc, SharedString, Timer, TimerMode, VecModel};
+//!
+//! Every toast is also kept in the notification center, a list of recent
+//! notifications behind the bell in the sidebar. Toasts are dismissed by id,
+//! so overlapping toasts with different timeouts never remove each other.
+//! A toast can carry one action button, such as "Undo", which is only
+//! offered while the toast is shown.
+
+use crate::ui::{MainWindow, NotificationItem, NotificationType, ToastItem};
+use slint::{ComponentHandle, Model, ModelRc, SharedString, Timer, VecModel};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
+use std::time::Duration;
+
+/// Notifications kept in the notification center
+pub const MAX_HISTORY: usize = 50;
+
+/// Action run when the user confirms the dialog currently shown
+type ConfirmAction = Box<dyn FnOnce(&MainWindow)>;
+
+/// Action run when the user clicks the button of a toast
+type ToastAction = Box<dyn FnOnce(&MainWindow)>;
+
+thread_local! {
+    /// Pending confirmation action (dialogs only exist on the UI thread)
+    static PENDING_CONFIRMATION: RefCell<Option<ConfirmAction>> = const { RefCell::new(None) };
+
+    /// Pending action of the secondary button, if it does more than decline
+    static PENDING_ALTERNATIVE: RefCell<Option<ConfirmAction>> = const { RefCell::new(None) };
+
+    /// Notifications shown so far (toasts only exist on the UI thread)
+    static CENTER: RefCell<NotificationCenter> = RefCell::new(NotificationCenter::default());
+
+    /// Actions of the toasts on screen, by notification id
+    static TOAST_ACTIONS: RefCell<HashMap<i32, ToastAction>> = RefCell::new(HashMap::new());
+}
+
+/// Screen of the main window, as numbered by `current-screen`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Extraction = 0,
+    CheckFiles = 1,
+    Settings = 2,
+    Dashboard = 3,
+    Performance = 4,
+}
 
 /// Toast notification data structure
 #[derive(Clone)]
@@ -16,6 +58,8 @@ pub struct ToastData {
     pub notification_type: NotificationType,
     /// Whether the toast is currently visible
     pub show: bool,
+    /// Screen opened when the notification is clicked
+    pub view: Option<View>,
 }
 
 impl ToastData {
@@ -25,6 +69,7 @@ impl ToastData {
             message: message.into(),
             notification_type: NotificationType::Success,
             show: true,
+            view: None,
         }
     }
 
@@ -34,6 +79,7 @@ impl ToastData {
             message: message.into(),
             notification_type: NotificationType::Error,
             show: true,
+            view: None,
         }
     }
 
@@ -43,6 +89,7 @@ impl ToastData {
             message: message.into(),
             notification_type: NotificationType::Warning,
             show: true,
+            view: None,
         }
     }
 
@@ -52,24 +99,92 @@ impl ToastData {
             message: message.into(),
             notification_type: NotificationType::Info,
             show: true,
+            view: None,
         }
     }
 
-    /// Convert to Slint's tuple format (message, show, type)
-    /// Note: The order must match the Slint anonymous struct field order
-    fn to_slint_tuple(&self) -> (SharedString, bool, NotificationType) {
-        (
-            self.message.clone().into(),
-            self.show,
-            self.notification_type,
-        )
+    /// Open `view` when the notification is clicked
+    #[must_use]
+    pub const fn with_view(mut self, view: View) -> Self {
+        self.view = Some(view);
+        self
+    }
+
+    /// How long the toast stays up; problems stay longer than confirmations
+    pub const fn timeout(&self) -> Duration {
+        match self.notification_type {
+            NotificationType::Error => Duration::from_secs(12),
+            NotificationType::Warning => Duration::from_secs(8),
+            NotificationType::Success | NotificationType::Info => Duration::from_secs(4),
+        }
+    }
+}
+
+/// A notification in the notification center
+#[derive(Clone)]
+pub struct HistoryEntry {
+    /// Id of the notification, shared with its toast
+    pub id: i32,
+    /// The notification
+    pub toast: ToastData,
+    /// Local time it was shown ("14:05:09")
+    pub time: String,
+}
+
+/// Recent notifications, newest first, and how many were not seen yet
+#[derive(Default)]
+pub struct NotificationCenter {
+    entries: VecDeque<HistoryEntry>,
+    next_id: i32,
+    unread: usize,
+}
+
+impl NotificationCenter {
+    /// Add a notification and return its id
+    pub fn push(&mut self, toast: &ToastData, time: String) -> i32 {
+        let id = self.next_id;
+        self.next_id = self.next_id.wrapping_add(1);
+        self.entries.push_front(HistoryEntry {
+            id,
+            toast: toast.clone(),
+            time,
+        });
+        self.entries.truncate(MAX_HISTORY);
+        self.unread = (self.unread + 1).min(self.entries.len());
+        id
+    }
+
+    /// The notification with `id`, if it is still kept
+    pub fn get(&self, id: i32) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// Notifications, newest first
+    pub fn entries(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter()
+    }
+
+    /// Notifications added since the center was last opened
+    pub const fn unread(&self) -> usize {
+        self.unread
+    }
+
+    /// Mark every notification as seen
+    pub const fn mark_read(&mut self) {
+        self.unread = 0;
+    }
+
+    /// Forget all notifications
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.unread = 0;
     }
 }
 
 /// Show a toast notification
 ///
-/// This adds a toast to the notification queue. Toasts will auto-dismiss after a timeout.
-/// Uses Slint's Timer API to ensure thread-safety and prevent UI blocking.
+/// This adds a toast to the notification queue and the notification center.
+/// The toast dismisses itself after its [`timeout`](ToastData::timeout).
 ///
 /// # Example
 ///
@@ -80,57 +195,134 @@ impl ToastData {
 /// show_toast(&window, &ToastData::success("Operation completed!"));
 /// ```
 pub fn show_toast(window: &MainWindow, toast: &ToastData) {
-    let current_toasts = window.get_toasts();
-    let mut toasts_vec = Vec::new();
-
-    // Copy existing toasts
-    for i in 0..current_toasts.row_count() {
-        if let Some(toast_tuple) = current_toasts.row_data(i) {
-            toasts_vec.push(toast_tuple);
-        }
-    }
-
-    // Add new toast
-    toasts_vec.push(toast.to_slint_tuple());
+    push_toast(window, toast, "");
+}
 
-    // Calculate index before moving the vector
-    let toast_index = toasts_vec.len() - 1;
+/// Show a toast with a button, such as "Undo", running `action` when clicked
+///
+/// The action is dropped once the toast is dismissed or times out.
+pub fn show_toast_with_action(
+    window: &MainWindow,
+    toast: &ToastData,
+    label: &str,
+    action: impl FnOnce(&MainWindow) + 'static,
+) {
+    let id = push_toast(window, toast, label);
+    TOAST_ACTIONS.with_borrow_mut(|actions| actions.insert(id, Box::new(action)));
+}
 
-    // Update UI
-    let new_model = Rc::new(VecModel::from(toasts_vec));
-    window.set_toasts(ModelRc::from(new_model));
+/// Add a toast and its notification, returning its id
+fn push_toast(window: &MainWindow, toast: &ToastData, action: &str) -> i32 {
+    let time = chrono::Local::now().format("%H:%M:%S").to_string();
+    let id = CENTER.with_borrow_mut(|center| {
+        let id = center.push(toast, time);
+        // Notifications arriving while the center is open are seen right away
+        if window.get_show_notification_center() {
+            center.mark_read();
+        }
+        id
+    });
+    refresh_notification_center(window);
+
+    let mut toasts: Vec<ToastItem> = window.get_toasts().iter().collect();
+    toasts.push(ToastItem {
+        id,
+        message: toast.message.clone().into(),
+        kind: toast.notification_type,
+        show: toast.show,
+        clickable: toast.view.is_some(),
+        action: SharedString::from(action),
+    });
+    window.set_toasts(ModelRc::from(Rc::new(VecModel::from(toasts))));
 
-    // Schedule auto-dismiss after 5 seconds using Slint's Timer
-    // This is thread-safe and runs on the event loop
     let window_weak = window.as_weak();
+    Timer::single_shot(toast.timeout(), move || {
+        if let Some(window) = window_weak.upgrade() {
+            dismiss_toast(&window, id);
+        }
+    });
+    id
+}
 
-    let timer = Timer::default();
-    timer.start(
-        TimerMode::SingleShot,
-        std::time::Duration::from_secs(5),
-        move || {
-            if let Some(window) = window_weak.upgrade() {
-                dismiss_toast(&window, toast_index);
-            }
-        },
-    );
+/// Dismiss the toast of notification `id`, if it is still shown
+fn dismiss_toast(window: &MainWindow, id: i32) {
+    TOAST_ACTIONS.with_borrow_mut(|actions| actions.remove(&id));
+    let toasts: Vec<ToastItem> = window
+        .get_toasts()
+        .iter()
+        .filter(|toast| toast.id != id)
+        .collect();
+    window.set_toasts(ModelRc::from(Rc::new(VecModel::from(toasts))));
 }
 
-/// Dismiss a toast notification by index
-fn dismiss_toast(window: &MainWindow, index: usize) {
-    let current_toasts = window.get_toasts();
-    let mut toasts_vec = Vec::new();
+/// Copy the notification center into the window
+fn refresh_notification_center(window: &MainWindow) {
+    let (items, unread) = CENTER.with_borrow(|center| {
+        let items: Vec<NotificationItem> = center
+            .entries()
+            .map(|entry| NotificationItem {
+                id: entry.id,
+                message: entry.toast.message.clone().into(),
+                kind: entry.toast.notification_type,
+                time: SharedString::from(entry.time.as_str()),
+                clickable: entry.toast.view.is_some(),
+            })
+            .collect();
+        (items, center.unread())
+    });
+    window.set_notification_history(ModelRc::from(Rc::new(VecModel::from(items))));
+    window.set_unread_notifications(i32::try_from(unread).unwrap_or(i32::MAX));
+}
 
-    for i in 0..current_toasts.row_count() {
-        if i != index
-            && let Some(toast_tuple) = current_toasts.row_data(i)
-        {
-            toasts_vec.push(toast_tuple);
+/// Wire the toasts, the bell and the notification center
+pub fn setup_notification_callbacks(window: &MainWindow) {
+    let weak = window.as_weak();
+    window.on_toast_dismissed(move |id| {
+        if let Some(window) = weak.upgrade() {
+            dismiss_toast(&window, id);
         }
-    }
+    });
+
+    let weak = window.as_weak();
+    window.on_toast_action(move |id| {
+        let action = TOAST_ACTIONS.with_borrow_mut(|actions| actions.remove(&id));
+        if let Some(window) = weak.upgrade() {
+            dismiss_toast(&window, id);
+            if let Some(action) = action {
+                action(&window);
+            }
+        }
+    });
+
+    // Clicking a toast or an entry of the center opens the screen it is about
+    let weak = window.as_weak();
+    window.on_notification_clicked(move |id| {
+        let Some(window) = weak.upgrade() else {
+            return;
+        };
+        let view = CENTER.with_borrow(|center| center.get(id).and_then(|entry| entry.toast.view));
+        if let Some(view) = view {
+            dismiss_toast(&window, id);
+            window.set_show_notification_center(false);
+            window.set_current_screen(view as i32);
+        }
+    });
+
+    let weak = window.as_weak();
+    window.on_notification_center_opened(move || {
+        CENTER.with_borrow_mut(NotificationCenter::mark_read);
+        if let Some(window) = weak.upgrade() {
+            refresh_notification_center(&window);
+        }
+    });
 
-    let new_model = Rc::new(VecModel::from(toasts_vec));
-    window.set_toasts(ModelRc::from(new_model));
+    let weak = window.as_weak();
+    window.on_notification_history_cleared(move || {
+        CENTER.with_borrow_mut(NotificationCenter::clear);
+        if let Some(window) = weak.upgrade() {
+            refresh_notification_center(&window);
+        }
+    });
 }
 
 /// Dialog configuration
@@ -145,6 +337,8 @@ pub struct DialogConfig {
     pub primary_button: String,
     /// Optional label for the secondary (cancel) button
     pub secondary_button: Option<String>,
+    /// Optional label for a checkbox, such as "Don't ask again"
+    pub checkbox: Option<String>,
 }
 
 impl DialogConfig {
@@ -156,6 +350,7 @@ impl DialogConfig {
             dialog_type: NotificationType::Info,
             primary_button: "OK".to_string(),
             secondary_button: None,
+            checkbox: None,
         }
     }
 
@@ -167,6 +362,7 @@ impl DialogConfig {
             dialog_type: NotificationType::Success,
             primary_button: "OK".to_string(),
             secondary_button: None,
+            checkbox: None,
         }
     }
 
@@ -178,6 +374,7 @@ impl DialogConfig {
             dialog_type: NotificationType::Error,
             primary_button: "OK".to_string(),
             secondary_button: None,
+            checkbox: None,
         }
     }
 
@@ -189,6 +386,7 @@ impl DialogConfig {
             dialog_type: NotificationType::Warning,
             primary_button: "OK".to_string(),
             secondary_button: None,
+            checkbox: None,
         }
     }
 
@@ -200,6 +398,7 @@ impl DialogConfig {
             dialog_type: NotificationType::Warning,
             primary_button: "Yes".to_string(),
             secondary_button: Some("No".to_string()),
+            checkbox: None,
         }
     }
 
@@ -216,6 +415,13 @@ impl DialogConfig {
         self.secondary_button = Some(text.into());
         self
     }
+
+    /// Show an unchecked checkbox, read with `get_dialog_checkbox_checked` on confirmation
+    #[must_use]
+    pub fn with_checkbox(mut self, text: impl Into<String>) -> Self {
+        self.checkbox = Some(text.into());
+        self
+    }
 }
 
 /// Show a modal dialog
@@ -232,19 +438,93 @@ impl DialogConfig {
 /// show_dialog(&window, config);
 /// ```
 pub fn show_dialog(window: &MainWindow, config: DialogConfig) {
+    // A new dialog replaces any confirmation that was still pending
+    PENDING_CONFIRMATION.with_borrow_mut(Option::take);
+    PENDING_ALTERNATIVE.with_borrow_mut(Option::take);
+
     window.set_dialog_title(config.title.into());
     window.set_dialog_message(config.message.into());
     window.set_dialog_type(config.dialog_type);
     window.set_dialog_primary_button(config.primary_button.into());
     window.set_dialog_secondary_button(config.secondary_button.unwrap_or_default().into());
+    window.set_dialog_checkbox_text(config.checkbox.unwrap_or_default().into());
+    window.set_dialog_checkbox_checked(false);
     window.set_show_dialog(true);
 }
 
+/// Show a confirmation dialog and run `on_confirm` if the user accepts it
+///
+/// Declining or dismissing the dialog drops the action.
+///
+/// # Example
+///
+/// ```ignore
+/// use unpackrr::ui::notifications::{show_confirmation, DialogConfig};
+///
+/// let config = DialogConfig::confirm("Delete File", "Are you sure?");
+/// show_confirmation(&window, config, |window| {
+///     // Delete the file
+/// });
+/// ```
+pub fn show_confirmation(
+    window: &MainWindow,
+    config: DialogConfig,
+    on_confirm: impl FnOnce(&MainWindow) + 'static,
+) {
+    show_dialog(window, config);
+    PENDING_CONFIRMATION.with_borrow_mut(|pending| *pending = Some(Box::new(on_confirm)));
+}
+
+/// Show a dialog whose two buttons both act: `on_primary` or `on_secondary`
+///
+/// Dismissing the dialog otherwise drops both actions.
+pub fn show_choice(
+    window: &MainWindow,
+    config: DialogConfig,
+    on_primary: impl FnOnce(&MainWindow) + 'static,
+    on_secondary: impl FnOnce(&MainWindow) + 'static,
+) {
+    show_confirmation(window, config, on_primary);
+    PENDING_ALTERNATIVE.with_borrow_mut(|pending| *pending = Some(Box::new(on_secondary)));
+}
+
 /// Hide the currently displayed dialog
 pub fn hide_dialog(window: &MainWindow) {
+    PENDING_CONFIRMATION.with_borrow_mut(Option::take);
+    PENDING_ALTERNATIVE.with_borrow_mut(Option::take);
     window.set_show_dialog(false);
 }
 
+/// Wire the dialog buttons to pending confirmation actions
+pub fn setup_dialog_callbacks(window: &MainWindow) {
+    let weak = window.as_weak();
+    window.on_dialog_primary_clicked(move || {
+        PENDING_ALTERNATIVE.with_borrow_mut(Option::take);
+        if let Some(action) = PENDING_CONFIRMATION.with_borrow_mut(Option::take) {
+            run_after_close(&weak, action);
+        }
+    });
+
+    let weak = window.as_weak();
+    window.on_dialog_secondary_clicked(move || {
+        PENDING_CONFIRMATION.with_borrow_mut(Option::take);
+        if let Some(action) = PENDING_ALTERNATIVE.with_borrow_mut(Option::take) {
+            run_after_close(&weak, action);
+        }
+    });
+}
+
+/// Run a dialog action after the dialog has finished closing, so the action
+/// may open another dialog
+fn run_after_close(weak: &slint::Weak<MainWindow>, action: ConfirmAction) {
+    let weak = weak.clone();
+    Timer::single_shot(std::time::Duration::ZERO, move || {
+        if let Some(window) = weak.upgrade() {
+            action(&window);
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -281,6 +561,44 @@ mod tests {
         assert!(toast.show);
     }
 
+    #[test]
+    fn test_toast_timeout() {
+        assert!(ToastData::error("e").timeout() > ToastData::warning("w").timeout());
+        assert!(ToastData::warning("w").timeout() > ToastData::info("i").timeout());
+        assert_eq!(
+            ToastData::info("i").with_view(View::Settings).view,
+            Some(View::Settings)
+        );
+    }
+
+    #[test]
+    fn test_notification_center() {
+        let mut center = NotificationCenter::default();
+        let first = center.push(&ToastData::info("first"), "10:00:00".to_string());
+        let second = center.push(&ToastData::error("second"), "10:00:01".to_string());
+        assert_ne!(first, second);
+        assert_eq!(center.unread(), 2);
+        assert_eq!(
+            center
+                .entries()
+                .map(|entry| entry.toast.message.as_str())
+                .collect::<Vec<_>>(),
+            ["second", "first"]
+        );
+        assert_eq!(center.get(first).unwrap().time, "10:00:00");
+
+        center.mark_read();
+        for _ in 0..MAX_HISTORY {
+            center.push(&ToastData::info("more"), String::new());
+        }
+        assert_eq!(center.entries().count(), MAX_HISTORY);
+        assert!(center.get(first).is_none());
+        assert_eq!(center.unread(), MAX_HISTORY);
+
+        center.clear();
+        assert_eq!((center.entries().count(), center.unread()), (0, 0));
+    }
+
     #[test]
     fn test_dialog_config_info() {
         let config = DialogConfig::info("Title", "Message");
@@ -309,5 +627,9 @@ mod tests {
 
         assert_eq!(config.primary_button, "Continue");
         assert_eq!(config.secondary_button, Some("Cancel".to_string()));
+        assert!(config.checkbox.is_none());
+
+        let config = config.with_checkbox("Don't ask again");
+        assert_eq!(config.checkbox, Some("Don't ask again".to_string()));
     }
 }
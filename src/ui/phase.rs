@@ -0,0 +1,183 @@
+//! What the app is doing, and which requests it accepts meanwhile
+//!
+//! A single [`AppPhase`] governs scans and extractions. Separate flags for
+//! scanning, extracting and pausing allowed sequences that make no sense,
+//! e.g. starting an extraction mid-scan from a button that hadn't updated
+//! yet. Every request that starts, pauses or ends an operation goes through
+//! [`AppPhase::next`], which refuses what the current phase doesn't allow.
+//! The window's `scanning`, `extracting` and `paused` properties only mirror
+//! the phase.
+
+/// The phase the app is in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AppPhase {
+    /// Nothing to extract: no folder scanned yet, or the scan found nothing
+    #[default]
+    Idle,
+
+    /// A scan is running
+    Scanning,
+
+    /// A scan found archives to extract
+    ReadyToExtract,
+
+    /// An extraction is running
+    Extracting {
+        /// Paused by the user
+        paused: bool,
+    },
+
+    /// The extraction stopped and its results are being collected
+    Completing,
+}
+
+/// A request or outcome that moves the app to another phase
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseEvent {
+    /// The user starts a scan
+    StartScan,
+
+    /// The scan finished, having `found` archives to extract or not
+    ScanFinished {
+        /// Whether the scan found archives to extract
+        found: bool,
+    },
+
+    /// The user starts an extraction
+    StartExtraction,
+
+    /// The user pauses the extraction
+    Pause,
+
+    /// The user resumes the paused extraction
+    Resume,
+
+    /// The user cancels the extraction
+    Cancel,
+
+    /// The extraction stopped, done or cancelled
+    ExtractionStopped,
+
+    /// The results of the extraction are shown
+    Completed,
+
+    /// The running operation broke off, e.g. with an error or a panic
+    Failed,
+}
+
+impl AppPhase {
+    /// The phase `event` leads to, or `None` if this phase doesn't accept it
+    pub const fn next(self, event: PhaseEvent) -> Option<Self> {
+        use PhaseEvent as E;
+        let next = match (self, event) {
+            (Self::Idle | Self::ReadyToExtract, E::StartScan) => Self::Scanning,
+            (Self::Scanning, E::ScanFinished { found: false } | E::Failed) => Self::Idle,
+            (Self::Scanning, E::ScanFinished { found: true })
+            | (Self::Completing, E::Completed)
+            | (Self::Extracting { .. } | Self::Completing, E::Failed) => Self::ReadyToExtract,
+            (Self::ReadyToExtract, E::StartExtraction)
+            | (Self::Extracting { paused: true }, E::Resume) => Self::Extracting { paused: false },
+            (Self::Extracting { paused: false }, E::Pause) => Self::Extracting { paused: true },
+            // Cancelling only asks the run to stop; it ends with `ExtractionStopped`
+            (Self::Extracting { .. }, E::Cancel) | (_, E::Failed) => self,
+            (Self::Extracting { .. }, E::ExtractionStopped) => Self::Completing,
+            _ => return None,
+        };
+        Some(next)
+    }
+
+    /// Whether this phase accepts `event`
+    pub const fn accepts(self, event: PhaseEvent) -> bool {
+        self.next(event).is_some()
+    }
+
+    /// Whether a scan or an extraction is running
+    pub const fn is_busy(self) -> bool {
+        matches!(
+            self,
+            Self::Scanning | Self::Extracting { .. } | Self::Completing
+        )
+    }
+
+    /// Whether the window shows the scan as running
+    pub const fn is_scanning(self) -> bool {
+        matches!(self, Self::Scanning)
+    }
+
+    /// Whether the window shows the extraction progress
+    pub const fn is_extracting(self) -> bool {
+        matches!(self, Self::Extracting { .. } | Self::Completing)
+    }
+
+    /// Whether the extraction is paused
+    pub const fn is_paused(self) -> bool {
+        matches!(self, Self::Extracting { paused: true })
+    }
+
+    /// Why this phase refuses a request, for the user
+    pub const fn refusal(self) -> &'static str {
+        match self {
+            Self::Idle => "Scan a folder first",
+            Self::Scanning => "Wait for the scan to finish",
+            Self::ReadyToExtract => "No extraction is running",
+            Self::Extracting { .. } | Self::Completing => "Wait for the extraction to finish",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_and_extract() {
+        let mut phase = AppPhase::default();
+        for (event, expected) in [
+            (PhaseEvent::StartScan, AppPhase::Scanning),
+            (
+                PhaseEvent::ScanFinished { found: true },
+                AppPhase::ReadyToExtract,
+            ),
+            (
+                PhaseEvent::StartExtraction,
+                AppPhase::Extracting { paused: false },
+            ),
+            (PhaseEvent::Pause, AppPhase::Extracting { paused: true }),
+            (PhaseEvent::Cancel, AppPhase::Extracting { paused: true }),
+            (PhaseEvent::ExtractionStopped, AppPhase::Completing),
+            (PhaseEvent::Completed, AppPhase::ReadyToExtract),
+        ] {
+            phase = phase.next(event).unwrap();
+            assert_eq!(phase, expected, "after {event:?}");
+        }
+        assert!(!phase.is_busy());
+    }
+
+    #[test]
+    fn test_conflicting_requests() {
+        // No extraction mid-scan, no scan mid-extraction
+        assert!(!AppPhase::Scanning.accepts(PhaseEvent::StartExtraction));
+        assert!(!AppPhase::Scanning.accepts(PhaseEvent::StartScan));
+        assert!(!AppPhase::Idle.accepts(PhaseEvent::StartExtraction));
+        let extracting = AppPhase::Extracting { paused: false };
+        assert!(!extracting.accepts(PhaseEvent::StartScan));
+        assert!(!extracting.accepts(PhaseEvent::StartExtraction));
+        assert!(!extracting.accepts(PhaseEvent::Resume));
+        assert!(!AppPhase::Completing.accepts(PhaseEvent::Cancel));
+        assert!(!AppPhase::ReadyToExtract.accepts(PhaseEvent::Pause));
+
+        // Failures always get the app out of a busy phase
+        assert_eq!(
+            AppPhase::Scanning.next(PhaseEvent::Failed),
+            Some(AppPhase::Idle)
+        );
+        assert_eq!(
+            AppPhase::Completing.next(PhaseEvent::Failed),
+            Some(AppPhase::ReadyToExtract)
+        );
+        assert_eq!(
+            AppPhase::ReadyToExtract.next(PhaseEvent::Failed),
+            Some(AppPhase::ReadyToExtract)
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Headless driver of the main window for UI flow tests
+//!
+//! Built with the `ui-testing` feature. A [`Harness`] creates the real main
+//! window on Slint's testing backend, with every callback wired up as in the
+//! app, but with the settings and the extractor backend the test chooses,
+//! e.g. a [`MockExtractor`](unpackrr_core::testing::MockExtractor). Flows
+//! are driven through the accessible elements, the way a screen reader would
+//! press buttons and type, and [`Harness::wait_until`] runs the event loop
+//! until the window reaches the expected state.
+//!
+//! The app keeps its settings, notes, journal and audit log in the user's
+//! folders; [`isolate_profile`] points them at a temporary folder first.
+
+use super::{AppState, FileRowData, MainWindow, setup_callbacks};
+use crate::config::AppConfig;
+use crate::models::FileEntry;
+use crate::operations::ExtractorBackend;
+use anyhow::{Result, anyhow};
+use i_slint_backend_testing::ElementHandle;
+use parking_lot::Mutex;
+use slint::{ComponentHandle, Model, SharedString, Timer, TimerMode};
+use std::cell::Cell;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::{Arc, Once};
+use std::time::{Duration, Instant};
+
+/// How long [`Harness::wait_until`] waits before failing
+const WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often the awaited condition is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Point the folders the app writes to at `dir`
+///
+/// Only redirects on Unix, where the folders follow `HOME` and the XDG
+/// variables; Windows looks its known folders up without the environment.
+///
+/// # Safety
+///
+/// Changes the environment of the process: no other thread may run yet.
+#[cfg(unix)]
+pub unsafe fn isolate_profile(dir: &Path) {
+    for (variable, folder) in [
+        ("HOME", "home"),
+        ("XDG_CONFIG_HOME", "config"),
+        ("XDG_DATA_HOME", "data"),
+        ("XDG_CACHE_HOME", "cache"),
+        ("XDG_RUNTIME_DIR", "runtime"),
+    ] {
+        // SAFETY: single-threaded, as required of the caller
+        unsafe { std::env::set_var(variable, dir.join(folder)) };
+    }
+}
+
+/// The main window, driven by a test
+pub struct Harness {
+    window: MainWindow,
+    state: Arc<Mutex<AppState>>,
+}
+
+impl Harness {
+    /// Create the main window with `config`, extracting with `backend`
+    pub fn new(config: AppConfig, backend: Arc<dyn ExtractorBackend>) -> Result<Self> {
+        static INIT: Once = Once::new();
+        INIT.call_once(i_slint_backend_testing::init_integration_test_with_system_time);
+
+        let window = MainWindow::new()?;
+        let mut app_state = AppState::with_config(config);
+        app_state.backend = Some(backend);
+        let state = Arc::new(Mutex::new(app_state));
+        setup_callbacks(&window, &state);
+        window.show()?;
+        Ok(Self { window, state })
+    }
+
+    /// The window, to read or set properties the flow has no element for
+    pub const fn window(&self) -> &MainWindow {
+        &self.window
+    }
+
+    /// Press the first button labelled `label`
+    pub fn click(&self, label: &str) -> Result<()> {
+        self.element(label)?.invoke_accessible_default_action();
+        Ok(())
+    }
+
+    /// Type `text` into the first input labelled `label`, replacing its text
+    pub fn type_into(&self, label: &str, text: &str) -> Result<()> {
+        self.element(label)?
+            .set_accessible_value(SharedString::from(text));
+        Ok(())
+    }
+
+    fn element(&self, label: &str) -> Result<ElementHandle> {
+        ElementHandle::find_by_accessible_label(&self.window, label)
+            .next()
+            .ok_or_else(|| anyhow!("No element labelled \"{label}\""))
+    }
+
+    /// Pick the folder to scan, as the folder picker does
+    pub fn select_folder(&self, folder: &Path) {
+        self.window
+            .set_selected_folder(SharedString::from(folder.to_string_lossy().as_ref()));
+    }
+
+    /// Run the event loop until `condition` holds, failing after a while
+    ///
+    /// `what` names the awaited state in the error.
+    pub fn wait_until(
+        &self,
+        what: &str,
+        condition: impl Fn(&MainWindow) -> bool + 'static,
+    ) -> Result<()> {
+        if condition(&self.window) {
+            return Ok(());
+        }
+        let deadline = Instant::now() + WAIT_TIMEOUT;
+        let met = Rc::new(Cell::new(false));
+        let timer = Timer::default();
+        let weak = self.window.as_weak();
+        let met_in_timer = Rc::clone(&met);
+        timer.start(TimerMode::Repeated, POLL_INTERVAL, move || {
+            let done = weak.upgrade().is_some_and(|ui| condition(&ui));
+            if done || Instant::now() >= deadline {
+                met_in_timer.set(done);
+                let _ = slint::quit_event_loop();
+            }
+        });
+        slint::run_event_loop_until_quit()?;
+        if met.get() {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Timed out waiting for {what} (status: {})",
+                self.window.get_status_text()
+            ))
+        }
+    }
+
+    /// Rows of the file table as shown
+    pub fn rows(&self) -> Vec<FileRowData> {
+        self.window.get_file_list().iter().collect()
+    }
+
+    /// Archives the app knows, with their extraction states
+    pub fn entries(&self) -> Vec<FileEntry> {
+        self.state.lock().file_entries.entries().to_vec()
+    }
+}
@@ -0,0 +1,921 @@
+//! View models of the main window
+//!
+//! Plain data computed from the application state, independent of Slint.
+//! The callbacks in [`crate::ui`] only copy these values into the generated
+//! Slint types, so the mapping and formatting logic can be unit tested and
+//! reused by other frontends.
+
+use crate::ba2::games_for_version;
+use crate::config::AppConfig;
+use crate::config::backups::ConfigBackup;
+use crate::config::overrides::EffectiveConfig;
+use crate::models::{ArchiveHealth, ExtractionStatus, FileEntry};
+use crate::operations::audit::AuditEntry;
+use crate::operations::cleanup::Leftover;
+use crate::operations::{
+    LoadOrder, PreflightReport, Simulation, UndoReport, backup_root, format_count, format_duration,
+    format_size, simulate,
+};
+use chrono::{DateTime, Local, NaiveDateTime, TimeDelta};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Runs expected to take at least this long offer the post-run options
+pub const LONG_RUN: Duration = Duration::from_mins(15);
+
+/// Maximum number of paths listed in import and verification report dialogs
+pub const REPORT_MAX_LISTED_PATHS: usize = 20;
+
+/// Filters of the file table
+#[allow(clippy::struct_excessive_bools)] // Independent switches of the filter bar
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableFilter {
+    /// Hide archives larger than this many bytes
+    pub threshold: Option<u64>,
+
+    /// Only show archives whose extraction failed
+    pub failed_only: bool,
+
+    /// Only show corrupted archives
+    pub corrupted_only: bool,
+
+    /// Hide the archives installed by the Wabbajack modlist
+    pub own_mods_only: bool,
+
+    /// Also list the archives hidden by the ignore list, greyed out
+    pub show_ignored: bool,
+
+    /// Only show archives whose note or tags contain this text, if not empty
+    pub note: String,
+}
+
+impl TableFilter {
+    /// Whether the table shows the archive
+    pub fn matches(&self, entry: &FileEntry) -> bool {
+        self.threshold
+            .is_none_or(|threshold| entry.file_size <= threshold)
+            && (!self.failed_only || entry.status == ExtractionStatus::Failed)
+            && (!self.corrupted_only || entry.is_bad())
+            && (!self.own_mods_only || !entry.in_modlist)
+            && entry.note.matches(&self.note)
+    }
+
+    /// Whether any filter hides archives
+    pub const fn is_active(&self) -> bool {
+        self.threshold.is_some()
+            || self.failed_only
+            || self.corrupted_only
+            || self.own_mods_only
+            || !self.note.is_empty()
+    }
+}
+
+/// A row of the file table
+#[allow(clippy::struct_excessive_bools)] // Independent flags shown as badges
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileRow {
+    pub file_name: String,
+    pub file_size: String,
+    pub num_files: String,
+    pub mod_name: String,
+    pub is_bad: bool,
+    /// Full path, used to match extraction progress events
+    pub path: String,
+    pub status: ExtractionStatus,
+    /// How long the archive took to extract in this run, empty until it finished
+    pub extraction_time: String,
+    pub marked: bool,
+    /// Texture policy note for DX10 archives, empty otherwise
+    pub texture_note: String,
+    pub in_modlist: bool,
+    /// Why the header could not be read, shown as a badge
+    pub health: ArchiveHealth,
+    pub forced: bool,
+    pub load_order: String,
+    /// Warning for archives of BA2 versions the game does not write, empty otherwise
+    pub version_warning: String,
+    /// Hidden by the ignore list, shown greyed out
+    pub ignored: bool,
+    /// Tags and text of the archive's note, empty without one
+    pub note: String,
+    /// Size in percent of the largest scanned archive's, for the size heat
+    pub size_heat: u8,
+    /// Why the archive must stay packed, empty unless it is a known exclusion
+    pub exclusion: String,
+    pub exclusion_overridden: bool,
+}
+
+impl FileRow {
+    /// Build the row of an archive
+    pub fn from_entry(entry: &FileEntry, config: &AppConfig) -> Self {
+        Self {
+            file_name: entry.file_name.clone(),
+            file_size: entry.size_display(),
+            num_files: entry.file_count_display(),
+            mod_name: entry.mod_display().to_string(),
+            is_bad: entry.is_bad(),
+            path: entry.full_path.to_string_lossy().into_owned(),
+            status: entry.status,
+            extraction_time: entry.extraction_time_display(),
+            marked: entry.selected,
+            texture_note: if entry.is_texture() {
+                config.extraction.texture_policy.row_note().to_string()
+            } else {
+                String::new()
+            },
+            in_modlist: entry.in_modlist,
+            health: entry.health.clone(),
+            forced: entry.overrides.bad_file,
+            load_order: entry.load_order_display(),
+            version_warning: version_warning(entry, config),
+            ignored: false,
+            note: entry.note_display(),
+            size_heat: 0,
+            exclusion: entry.exclusion.clone().unwrap_or_default(),
+            exclusion_overridden: entry.overrides.exclusion,
+        }
+    }
+}
+
+/// Badge text for an archive of a BA2 version the game does not write
+///
+/// Names the game that writes the version, if any ("Starfield BA2 v2").
+fn version_warning(entry: &FileEntry, config: &AppConfig) -> String {
+    if !entry.has_unknown_version(config.profile()) {
+        return String::new();
+    }
+    games_for_version(entry.format_version).first().map_or_else(
+        || format!("Unknown BA2 v{}", entry.format_version),
+        |game| format!("{game} BA2 v{}", entry.format_version),
+    )
+}
+
+/// Size of an archive in percent of the largest one's
+fn size_heat(size: u64, largest: u64) -> u8 {
+    if largest == 0 {
+        return 0;
+    }
+    u8::try_from(size.saturating_mul(100) / largest).unwrap_or(100)
+}
+
+/// The file table and its summary line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MainViewModel {
+    /// Rows shown in the table
+    pub rows: Vec<FileRow>,
+
+    /// Total size of the shown archives in bytes
+    pub total_size: u64,
+
+    /// Predicted archive count after extracting the current selection, if
+    /// any archives were found and the game has an archive limit
+    pub simulation: Option<Simulation>,
+
+    /// Rows of ignored archives, listed after the others
+    pub ignored_rows: Vec<FileRow>,
+
+    /// Number of archives hidden by the ignore list
+    pub ignored_count: usize,
+
+    /// Number of archives with a note or tags, shown or not
+    pub noted_count: usize,
+}
+
+impl MainViewModel {
+    /// Build the table from the scanned archives
+    ///
+    /// The simulation covers what a run would extract now: the selected
+    /// archives (all of them without a selection), skipping the modlist's
+    /// archives if only own mods are shown.
+    pub fn build(
+        entries: &[FileEntry],
+        load_order: Option<&LoadOrder>,
+        config: &AppConfig,
+        filter: &TableFilter,
+    ) -> Self {
+        let has_selection = entries.iter().any(|e| e.selected);
+        let simulation = config
+            .profile()
+            .archive_limit
+            .filter(|_| !entries.is_empty())
+            .map(|limit| {
+                simulate(entries, load_order, limit, |e| {
+                    (!has_selection || e.selected) && (!filter.own_mods_only || !e.in_modlist)
+                })
+            });
+
+        let largest = entries.iter().map(|e| e.file_size).max().unwrap_or(0);
+        let shown: Vec<&FileEntry> = entries.iter().filter(|e| filter.matches(e)).collect();
+        Self {
+            rows: shown
+                .iter()
+                .map(|e| FileRow {
+                    size_heat: size_heat(e.file_size, largest),
+                    ..FileRow::from_entry(e, config)
+                })
+                .collect(),
+            total_size: shown.iter().map(|e| e.file_size).sum(),
+            simulation,
+            ignored_rows: Vec::new(),
+            ignored_count: 0,
+            noted_count: entries.iter().filter(|e| !e.note.is_empty()).count(),
+        }
+    }
+
+    /// Count the archives hidden by the ignore list, and list them greyed
+    /// out if the filter shows them
+    ///
+    /// They are left out of the totals, the simulation and the size heat, as
+    /// no run extracts them.
+    #[must_use]
+    pub fn with_ignored(
+        mut self,
+        ignored: &[FileEntry],
+        config: &AppConfig,
+        filter: &TableFilter,
+    ) -> Self {
+        self.ignored_count = ignored.len();
+        if filter.show_ignored {
+            self.ignored_rows = ignored
+                .iter()
+                .filter(|e| filter.matches(e))
+                .map(|e| FileRow {
+                    ignored: true,
+                    ..FileRow::from_entry(e, config)
+                })
+                .collect();
+        }
+        self
+    }
+
+    /// Shown rows, the ignored archives last
+    pub fn all_rows(&self) -> impl Iterator<Item = &FileRow> {
+        self.rows.iter().chain(&self.ignored_rows)
+    }
+
+    /// Number of shown archives, for display
+    pub fn total_files_text(&self) -> String {
+        format_count(self.rows.len() as u64)
+    }
+
+    /// Total size of the shown archives, for display
+    pub fn total_size_text(&self) -> String {
+        format_size(self.total_size)
+    }
+
+    /// Predicted archive count after extraction, empty without archives
+    pub fn simulation_text(&self) -> String {
+        simulation_text(self.simulation.as_ref())
+    }
+
+    /// Whether the prediction exceeds the engine's archive limit
+    pub fn simulation_over_limit(&self) -> bool {
+        self.simulation.is_some_and(|s| !s.within_limit())
+    }
+}
+
+/// Pick table rows for the bulk actions after a click on `row`
+///
+/// A plain click picks only `row`, `toggle` (Ctrl/Cmd-click) adds or
+/// removes it and `range` (Shift-click) picks every row from `anchor` to
+/// it, adding to the picked rows if combined with `toggle`. Returns the
+/// anchor of the next range.
+pub fn click_rows(
+    picked: &mut [bool],
+    anchor: Option<usize>,
+    row: usize,
+    toggle: bool,
+    range: bool,
+) -> Option<usize> {
+    if row >= picked.len() {
+        return anchor;
+    }
+    match anchor.filter(|&anchor| range && anchor < picked.len()) {
+        Some(anchor) => {
+            if !toggle {
+                picked.fill(false);
+            }
+            picked[anchor.min(row)..=anchor.max(row)].fill(true);
+            Some(anchor)
+        }
+        None if toggle => {
+            picked[row] = !picked[row];
+            Some(row)
+        }
+        None => {
+            picked.fill(false);
+            picked[row] = true;
+            Some(row)
+        }
+    }
+}
+
+/// Describe the predicted archive count after extraction
+pub fn simulation_text(simulation: Option<&Simulation>) -> String {
+    simulation.map_or_else(String::new, |s| {
+        format!(
+            "After extraction: {}/{} archives, ~{} loose files",
+            format_count(s.archives_after as u64),
+            format_count(s.limit as u64),
+            format_count(s.loose_files)
+        )
+    })
+}
+
+/// Speed and ETA of a running extraction, for display
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProgressText {
+    /// Archive bytes per second, followed by the estimated write speed once
+    /// the unpacked size of finished archives is known
+    pub speed: String,
+
+    /// Estimated time left
+    pub eta: String,
+
+    /// Local time the run is expected to finish ("14:35", "tomorrow 01:10")
+    pub finish: String,
+
+    /// Whether the run is expected to take at least [`LONG_RUN`]
+    pub long_run: bool,
+}
+
+impl ProgressText {
+    /// Format the measured rate (archive bytes per second), expansion ratio
+    /// (unpacked to archive bytes) and ETA; all empty until a rate is known
+    #[allow(
+        clippy::cast_possible_truncation, // Rates fit in u64
+        clippy::cast_sign_loss // Rates are positive
+    )]
+    pub fn new(rate: Option<f64>, expansion_ratio: Option<f64>, eta: Option<Duration>) -> Self {
+        let Some(rate) = rate else {
+            return Self::default();
+        };
+        let mut speed = format!("{}/s", format_size(rate as u64));
+        if let Some(ratio) = expansion_ratio {
+            let _ = write!(
+                speed,
+                " (~{}/s unpacked)",
+                format_size((rate * ratio) as u64)
+            );
+        }
+        Self {
+            speed,
+            eta: eta
+                .map(|eta| format_duration(eta.as_secs()))
+                .unwrap_or_default(),
+            finish: eta
+                .map(|eta| finish_time(Local::now().naive_local(), eta))
+                .unwrap_or_default(),
+            long_run: eta.is_some_and(|eta| eta >= LONG_RUN),
+        }
+    }
+}
+
+/// Clock time `eta` after `now`, with the day when it is not today
+fn finish_time(now: NaiveDateTime, eta: Duration) -> String {
+    let Some(finish) = TimeDelta::from_std(eta)
+        .ok()
+        .and_then(|eta| now.checked_add_signed(eta))
+    else {
+        return String::new();
+    };
+    match (finish.date() - now.date()).num_days() {
+        0 => finish.format("%H:%M").to_string(),
+        1 => finish.format("tomorrow %H:%M").to_string(),
+        _ => finish.format("%a %d %b %H:%M").to_string(),
+    }
+}
+
+/// Audit entries as text, one line each starting with its local time
+pub fn audit_text(entries: &[AuditEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| {
+            let time = i64::try_from(entry.at)
+                .ok()
+                .and_then(|at| DateTime::from_timestamp(at, 0))
+                .map(|time| {
+                    time.with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                })
+                .unwrap_or_default();
+            format!("{time}  {}", entry.summary())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Restore points of the configuration as the local time they were replaced
+pub fn config_backup_labels(backups: &[ConfigBackup]) -> Vec<String> {
+    backups
+        .iter()
+        .map(|backup| {
+            i64::try_from(backup.replaced_at_secs())
+                .ok()
+                .and_then(|at| DateTime::from_timestamp(at, 0))
+                .map(|time| {
+                    time.with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M:%S")
+                        .to_string()
+                })
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Text of the dialog summarizing an extraction about to start
+pub fn preflight_message(report: &PreflightReport, config: &EffectiveConfig) -> String {
+    const MAX_LISTED_DESTINATIONS: usize = 3;
+
+    let mut message = format!(
+        "{} archives, {}\nUnpacked size: about {}",
+        format_count(report.archives as u64),
+        format_size(report.total_size),
+        format_size(report.estimated_output)
+    );
+
+    let mut destinations: Vec<String> = report
+        .destinations
+        .iter()
+        .take(MAX_LISTED_DESTINATIONS)
+        .map(|destination| destination.display().to_string())
+        .collect();
+    if report.destinations.len() > MAX_LISTED_DESTINATIONS {
+        destinations.push(format!(
+            "and {} more",
+            format_count((report.destinations.len() - MAX_LISTED_DESTINATIONS) as u64)
+        ));
+    }
+    let _ = write!(message, "\nInto: {}", destinations.join(", "));
+
+    let backup = if config.extraction.auto_backup {
+        backup_root(config).map_or_else(
+            || "on, but no backup folder is available".to_string(),
+            |backup_root| format!("on, to {}", backup_root.display()),
+        )
+    } else {
+        "off".to_string()
+    };
+    let _ = write!(message, "\nBackup: {backup}");
+    if !config.overrides().is_empty() {
+        message.push_str("\nRun options apply to this extraction");
+    }
+
+    for warning in &report.warnings {
+        let _ = write!(message, "\n\n⚠ {}", warning.message());
+    }
+    message
+}
+
+/// Text of the dialog asking before `leftovers` are removed
+///
+/// `question` opens the text, e.g. "Interrupted runs left".
+pub fn leftover_removal_message(question: &str, leftovers: &[Leftover]) -> String {
+    let mut message = format!(
+        "{question} {} leftovers. Remove them?\n",
+        format_count(leftovers.len() as u64)
+    );
+    for leftover in leftovers.iter().take(REPORT_MAX_LISTED_PATHS) {
+        let _ = write!(
+            message,
+            "\n{}: {}",
+            leftover.kind.label(),
+            leftover.path.display()
+        );
+    }
+    if leftovers.len() > REPORT_MAX_LISTED_PATHS {
+        let _ = write!(
+            message,
+            "\n... and {} more",
+            format_count((leftovers.len() - REPORT_MAX_LISTED_PATHS) as u64)
+        );
+    }
+    message.push_str("\n\nEverything removed can be put back with Undo.");
+    message
+}
+
+/// Journal description of undoing the extraction of `archives`
+pub fn undo_description(archives: &[PathBuf]) -> String {
+    match archives {
+        [archive] => format!(
+            "Undo extraction of {}",
+            archive.file_name().unwrap_or_default().to_string_lossy()
+        ),
+        _ => format!(
+            "Undo extraction of {} archives",
+            format_count(archives.len() as u64)
+        ),
+    }
+}
+
+/// Text of the dialog reporting an undo run
+pub fn undo_message(
+    total: &UndoReport,
+    undone: usize,
+    restored: usize,
+    without_manifest: usize,
+    errors: &[String],
+) -> String {
+    let mut message = format!(
+        "Removed {} files from {} archives.",
+        format_count(total.removed as u64),
+        format_count(undone as u64)
+    );
+    if restored > 0 {
+        let _ = write!(
+            message,
+            " Restored {} archives from backup.",
+            format_count(restored as u64)
+        );
+    }
+    if !total.kept_modified.is_empty() {
+        let _ = write!(
+            message,
+            "\n\nKept {} files that changed since extraction:",
+            format_count(total.kept_modified.len() as u64)
+        );
+        for path in total.kept_modified.iter().take(REPORT_MAX_LISTED_PATHS) {
+            let _ = write!(message, "\n{path}");
+        }
+    }
+    if without_manifest > 0 {
+        let _ = write!(
+            message,
+            "\n\n{} archives have no checksum manifest and were left untouched.",
+            format_count(without_manifest as u64)
+        );
+    }
+    for error in errors {
+        let _ = write!(message, "\n\n{error}");
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TexturePolicy;
+    use crate::config::profile::Game;
+    use crate::operations::Note;
+
+    fn entry(name: &str, size: u64) -> FileEntry {
+        FileEntry::new(
+            name.to_string(),
+            size,
+            10,
+            "Mod".to_string(),
+            PathBuf::from(format!("Mod/{name}")),
+        )
+    }
+
+    #[test]
+    fn test_table_filter() {
+        let mut failed = entry("Failed - Main.ba2", 100);
+        failed.status = ExtractionStatus::Failed;
+        let mut modlist = entry("Modlist - Main.ba2", 300);
+        modlist.in_modlist = true;
+        let large = entry("Large - Main.ba2", 5_000);
+        let entries = [failed, modlist, large];
+
+        let shown = |filter: TableFilter| {
+            entries
+                .iter()
+                .filter(|e| filter.matches(e))
+                .map(|e| e.file_name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(shown(TableFilter::default()).len(), 3);
+        assert!(!TableFilter::default().is_active());
+
+        let threshold = TableFilter {
+            threshold: Some(1_000),
+            ..TableFilter::default()
+        };
+        assert!(threshold.is_active());
+        assert_eq!(
+            shown(threshold),
+            ["Failed - Main.ba2", "Modlist - Main.ba2"]
+        );
+        assert_eq!(
+            shown(TableFilter {
+                failed_only: true,
+                ..TableFilter::default()
+            }),
+            ["Failed - Main.ba2"]
+        );
+        assert_eq!(
+            shown(TableFilter {
+                own_mods_only: true,
+                ..TableFilter::default()
+            }),
+            ["Failed - Main.ba2", "Large - Main.ba2"]
+        );
+    }
+
+    #[test]
+    fn test_note_filter() {
+        let mut kept = entry("Kept - Main.ba2", 100);
+        kept.note = Note::parse("retest after update", "keep packed");
+        let entries = [kept, entry("Plain - Main.ba2", 100)];
+        let filter = TableFilter {
+            note: "KEEP".to_string(),
+            ..TableFilter::default()
+        };
+        assert!(filter.is_active());
+        let view = MainViewModel::build(&entries, None, &AppConfig::default(), &filter);
+        let shown: Vec<&str> = view.rows.iter().map(|r| r.file_name.as_str()).collect();
+        assert_eq!(shown, ["Kept - Main.ba2"]);
+        assert_eq!(view.noted_count, 1);
+
+        let row = FileRow::from_entry(&entries[0], &AppConfig::default());
+        assert_eq!(row.note, "[keep packed] retest after update");
+    }
+
+    #[test]
+    fn test_build_main_view_model() {
+        let mut texture = entry("Mod - Textures.ba2", 2_000);
+        texture.archive_type = "DX10".to_string();
+        let entries = [entry("Mod - Main.ba2", 1_000), texture];
+        let filter = TableFilter {
+            threshold: Some(1_500),
+            ..TableFilter::default()
+        };
+
+        let mut config = AppConfig::default();
+        config.extraction.texture_policy = TexturePolicy::Skip;
+        let view = MainViewModel::build(&entries, None, &config, &filter);
+        assert_eq!(view.rows.len(), 1);
+        assert_eq!(view.rows[0].file_name, "Mod - Main.ba2");
+        assert_eq!(view.rows[0].texture_note, "");
+        // Relative to the largest scanned archive, shown or not
+        assert_eq!(view.rows[0].size_heat, 50);
+        assert_eq!(view.total_size, 1_000);
+        assert_eq!(view.total_files_text(), format_count(1));
+        // The simulation ignores the table filters
+        assert_eq!(view.simulation.unwrap().unpacked, 2);
+        assert!(!view.simulation_over_limit());
+
+        let row = FileRow::from_entry(&entries[1], &config);
+        assert_eq!(row.texture_note, TexturePolicy::Skip.row_note());
+        assert_eq!(row.version_warning, "");
+
+        let empty = MainViewModel::build(&[], None, &config, &filter);
+        assert!(empty.simulation.is_none());
+        assert_eq!(empty.simulation_text(), "");
+
+        // Starfield has no archive limit to simulate
+        config.set_game(Game::Starfield);
+        let view = MainViewModel::build(&entries, None, &config, &filter);
+        assert!(view.simulation.is_none());
+    }
+
+    #[test]
+    fn test_ignored_rows() {
+        let config = AppConfig::default();
+        let entries = [entry("Mod - Main.ba2", 1_000)];
+        let ignored = [entry("Old - Main.ba2", 500), entry("Big - Main.ba2", 5_000)];
+
+        let mut filter = TableFilter::default();
+        let view = MainViewModel::build(&entries, None, &config, &filter)
+            .with_ignored(&ignored, &config, &filter);
+        assert_eq!(view.ignored_count, 2);
+        assert_eq!(view.all_rows().count(), 1);
+
+        filter.show_ignored = true;
+        filter.threshold = Some(1_000);
+        assert!(filter.is_active());
+        let view = MainViewModel::build(&entries, None, &config, &filter)
+            .with_ignored(&ignored, &config, &filter);
+        let rows: Vec<(&str, bool)> = view
+            .all_rows()
+            .map(|row| (row.file_name.as_str(), row.ignored))
+            .collect();
+        assert_eq!(rows, [("Mod - Main.ba2", false), ("Old - Main.ba2", true)]);
+        // Ignored archives stay out of the totals
+        assert_eq!(view.total_files_text(), format_count(1));
+        assert_eq!(view.total_size, 1_000);
+    }
+
+    #[test]
+    fn test_click_rows() {
+        let mut picked = [false; 5];
+        let anchor = click_rows(&mut picked, None, 1, false, false);
+        assert_eq!(picked, [false, true, false, false, false]);
+
+        // Shift-click picks the range from the anchor, in either direction
+        let anchor = click_rows(&mut picked, anchor, 3, false, true);
+        assert_eq!(picked, [false, true, true, true, false]);
+        assert_eq!(anchor, Some(1));
+        let anchor = click_rows(&mut picked, anchor, 0, false, true);
+        assert_eq!(picked, [true, true, false, false, false]);
+
+        // Ctrl-click toggles a row and moves the anchor
+        let anchor = click_rows(&mut picked, anchor, 4, true, false);
+        assert_eq!(picked, [true, true, false, false, true]);
+        let anchor = click_rows(&mut picked, anchor, 0, true, false);
+        assert_eq!(picked, [false, true, false, false, true]);
+        assert_eq!(anchor, Some(0));
+
+        // Ctrl+Shift adds a range, a plain click starts over
+        let anchor = click_rows(&mut picked, Some(3), 2, true, true);
+        assert_eq!(picked, [false, true, true, true, true]);
+        click_rows(&mut picked, anchor, 2, false, false);
+        assert_eq!(picked, [false, false, true, false, false]);
+        assert_eq!(click_rows(&mut picked, Some(2), 9, false, false), Some(2));
+    }
+
+    #[test]
+    fn test_version_warning() {
+        let mut config = AppConfig::default();
+        let mut archive = entry("Mod - Main.ba2", 1_000);
+        archive.format_version = 1;
+        assert_eq!(FileRow::from_entry(&archive, &config).version_warning, "");
+
+        archive.format_version = 2;
+        assert_eq!(
+            FileRow::from_entry(&archive, &config).version_warning,
+            "Starfield BA2 v2"
+        );
+        archive.format_version = 9;
+        assert_eq!(
+            FileRow::from_entry(&archive, &config).version_warning,
+            "Unknown BA2 v9"
+        );
+
+        config.set_game(Game::Starfield);
+        archive.format_version = 8;
+        assert_eq!(
+            FileRow::from_entry(&archive, &config).version_warning,
+            "Fallout 4 BA2 v8"
+        );
+    }
+
+    #[test]
+    fn test_health_row() {
+        let config = AppConfig::default();
+        let mut locked = entry("Locked - Main.ba2", 100);
+        locked.health = ArchiveHealth::Unreadable("Permission denied".to_string());
+        let row = FileRow::from_entry(&locked, &config);
+        assert!(row.is_bad);
+        assert_eq!(row.health.label(), "Unreadable");
+        assert!(!locked.is_corrupted());
+
+        // The Problems filter lists archives that can't be read for any reason
+        let filter = TableFilter {
+            corrupted_only: true,
+            ..TableFilter::default()
+        };
+        assert!(filter.matches(&locked));
+        assert!(!filter.matches(&entry("Fine - Main.ba2", 100)));
+    }
+
+    #[test]
+    fn test_progress_text() {
+        assert_eq!(
+            ProgressText::new(None, Some(2.0), None),
+            ProgressText::default()
+        );
+
+        let text = ProgressText::new(Some(1_048_576.0), None, Some(Duration::from_secs(90)));
+        assert_eq!(text.speed, format!("{}/s", format_size(1_048_576)));
+        assert_eq!(text.eta, format_duration(90));
+        assert!(!text.finish.is_empty());
+        assert!(!text.long_run);
+
+        let text = ProgressText::new(Some(1_048_576.0), Some(2.0), None);
+        assert!(
+            text.speed
+                .ends_with(&format!("(~{}/s unpacked)", format_size(2_097_152)))
+        );
+        assert_eq!(text.eta, "");
+        assert_eq!(text.finish, "");
+    }
+
+    #[test]
+    fn test_finish_time() {
+        let now = chrono::NaiveDate::from_ymd_opt(2025, 3, 14)
+            .unwrap()
+            .and_hms_opt(22, 50, 0)
+            .unwrap();
+        assert_eq!(finish_time(now, Duration::from_mins(5)), "22:55");
+        assert_eq!(finish_time(now, Duration::from_hours(2)), "tomorrow 00:50");
+        assert_eq!(
+            finish_time(now, Duration::from_hours(50)),
+            "Mon 17 Mar 00:50"
+        );
+    }
+
+    #[test]
+    fn test_audit_text() {
+        use crate::operations::audit::AuditAction;
+
+        let entries = [
+            AuditEntry::new(AuditAction::Ignored, "Mod - Main.ba2"),
+            AuditEntry::new(AuditAction::Undone, "Clean up").failed("locked"),
+        ];
+        let text = audit_text(&entries);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        // "2025-03-14 22:50  " before the summary
+        assert_eq!(lines[0].find("Ignored"), Some(18));
+        assert!(lines[1].ends_with("Undid Clean up (failed: locked)"));
+    }
+
+    #[test]
+    fn test_preflight_message() {
+        use crate::config::overrides::RunOverrides;
+        use crate::operations::PreflightWarning;
+
+        let report = PreflightReport {
+            archives: 2,
+            total_size: 2048,
+            estimated_output: 4096,
+            destinations: ["A", "B", "C", "D", "E"].map(PathBuf::from).to_vec(),
+            warnings: vec![PreflightWarning::OverwritesLooseFiles { count: 3 }],
+            ..PreflightReport::default()
+        };
+        let mut config = AppConfig::default();
+        config.advanced.backup_path = "backups".to_string();
+        let effective =
+            |config: &AppConfig| EffectiveConfig::new(config, &RunOverrides::default()).unwrap();
+
+        let message = preflight_message(&report, &effective(&config));
+        let lines: Vec<&str> = message.lines().collect();
+        assert!(lines[0].starts_with("2 archives, "));
+        assert_eq!(lines[2], "Into: A, B, C, and 2 more");
+        assert_eq!(lines[3], "Backup: on, to backups");
+        assert_eq!(
+            lines[5],
+            "⚠ 3 loose files already in the mod folders will be overwritten"
+        );
+
+        config.extraction.auto_backup = false;
+        let message = preflight_message(&report, &effective(&config));
+        assert!(message.contains("\nBackup: off\n"));
+    }
+
+    #[test]
+    fn test_leftover_removal_message() {
+        use crate::operations::cleanup::LeftoverKind;
+
+        let leftovers: Vec<Leftover> = (0..REPORT_MAX_LISTED_PATHS + 2)
+            .map(|i| Leftover {
+                path: PathBuf::from(format!("Mod/meshes/{i}")),
+                kind: LeftoverKind::EmptyFolder,
+            })
+            .collect();
+        let message = leftover_removal_message("Interrupted runs left", &leftovers);
+        let lines: Vec<&str> = message.lines().collect();
+
+        assert_eq!(lines[0], "Interrupted runs left 22 leftovers. Remove them?");
+        assert_eq!(
+            lines[2],
+            format!("Empty folder: {}", leftovers[0].path.display())
+        );
+        assert_eq!(lines[REPORT_MAX_LISTED_PATHS + 2], "... and 2 more");
+        assert_eq!(
+            lines.last(),
+            Some(&"Everything removed can be put back with Undo.")
+        );
+    }
+
+    #[test]
+    fn test_undo_description() {
+        assert_eq!(
+            undo_description(&[PathBuf::from("Mod/Mod - Main.ba2")]),
+            "Undo extraction of Mod - Main.ba2"
+        );
+        assert_eq!(
+            undo_description(&[PathBuf::from("a.ba2"), PathBuf::from("b.ba2")]),
+            "Undo extraction of 2 archives"
+        );
+    }
+
+    #[test]
+    fn test_undo_message() {
+        let clean = UndoReport {
+            removed: 12,
+            ..UndoReport::default()
+        };
+        assert_eq!(
+            undo_message(&clean, 2, 0, 0, &[]),
+            "Removed 12 files from 2 archives."
+        );
+
+        let partial = UndoReport {
+            removed: 5,
+            kept_modified: vec!["meshes/a.nif".to_string()],
+            ..UndoReport::default()
+        };
+        let errors = ["Mod - Main.ba2: access denied".to_string()];
+        assert_eq!(
+            undo_message(&partial, 1, 1, 3, &errors),
+            "Removed 5 files from 1 archives. Restored 1 archives from backup.\n\n\
+             Kept 1 files that changed since extraction:\nmeshes/a.nif\n\n\
+             3 archives have no checksum manifest and were left untouched.\n\n\
+             Mod - Main.ba2: access denied"
+        );
+    }
+}
@@ -2,7 +2,9 @@
 //!
 //! Tests that configuration can be serialized, deserialized, and validated correctly.
 
+use unpackrr::config::ignore::{IgnorePattern, PatternKind};
 use unpackrr::config::{AppConfig, LogLevel};
+use unpackrr::operations::SizeSpec;
 
 /// Test that default configuration can be serialized and deserialized
 #[test]
@@ -31,14 +33,19 @@ fn test_modified_config_serialization() {
     // Create config with custom values
     let mut config = AppConfig::default();
     config.extraction.postfixes = vec!["Custom".to_string(), "Postfix".to_string()];
-    config.extraction.ignored_files = vec!["test.ba2".to_string(), "*.log".to_string()];
+    config.extraction.ignored_files = vec![
+        IgnorePattern::new(PatternKind::Substring, "test.ba2"),
+        IgnorePattern::new(PatternKind::Glob, "*.log"),
+    ];
     config.extraction.ignore_bad_files = true;
     config.extraction.auto_backup = true;
+    config.extraction.allow_unknown_versions = true;
     config.advanced.show_debug = true;
     config.advanced.log_level = LogLevel::Debug;
     config.appearance.theme_mode = "dark".to_string();
     config.saved.directory = "/test/path".to_string();
-    config.saved.threshold = 1024 * 1024; // 1MB
+    config.saved.threshold = SizeSpec::from_bytes(1024 * 1024); // 1MiB
+    config.saved.auto_threshold = true;
 
     // Serialize and deserialize
     let json = serde_json::to_string_pretty(&config).expect("Failed to serialize");
@@ -46,14 +53,16 @@ fn test_modified_config_serialization() {
 
     // Verify all modifications persisted
     assert_eq!(loaded.extraction.postfixes, vec!["Custom", "Postfix"]);
-    assert_eq!(loaded.extraction.ignored_files, vec!["test.ba2", "*.log"]);
+    assert_eq!(loaded.extraction.ignored_files, config.extraction.ignored_files);
     assert!(loaded.extraction.ignore_bad_files);
     assert!(loaded.extraction.auto_backup);
+    assert!(loaded.extraction.allow_unknown_versions);
     assert!(loaded.advanced.show_debug);
     assert_eq!(loaded.advanced.log_level, LogLevel::Debug);
     assert_eq!(loaded.appearance.theme_mode, "dark");
     assert_eq!(loaded.saved.directory, "/test/path");
-    assert_eq!(loaded.saved.threshold, 1024 * 1024);
+    assert_eq!(loaded.saved.threshold.bytes(), 1024 * 1024);
+    assert!(loaded.saved.auto_threshold);
 }
 
 /// Test that all LogLevel variants serialize correctly
@@ -106,7 +115,7 @@ fn test_valid_postfix_validation() {
 #[test]
 fn test_invalid_regex_validation() {
     let mut config = AppConfig::default();
-    config.extraction.ignored_files = vec!["[invalid(".to_string()]; // Invalid regex
+    config.extraction.ignored_files = vec![IgnorePattern::new(PatternKind::Regex, "[invalid(")];
 
     let result = config.validate();
     assert!(result.is_err(), "Validation should fail for invalid regex");
@@ -179,6 +188,43 @@ fn test_deserialize_minimal_json() {
     assert_eq!(config.appearance.accent_color, "#0078d4");
 }
 
+/// Test that the saved threshold accepts both byte counts and size strings
+#[test]
+fn test_saved_threshold_formats() {
+    let from_bytes: AppConfig = serde_json::from_str(
+        r##"{"extraction": {}, "saved": {"threshold": 1048576}, "appearance": {"theme_mode": "dark", "accent_color": "#0078D4", "language": "auto"}, "advanced": {}, "update": {"check_at_startup": true}}"##,
+    )
+    .expect("Failed to deserialize byte threshold");
+    assert_eq!(from_bytes.saved.threshold.bytes(), 1_048_576);
+    assert!(!from_bytes.saved.auto_threshold);
+
+    let from_text: AppConfig = serde_json::from_str(
+        r##"{"extraction": {}, "saved": {"threshold": "1 MiB", "auto_threshold": true}, "appearance": {"theme_mode": "dark", "accent_color": "#0078D4", "language": "auto"}, "advanced": {}, "update": {"check_at_startup": true}}"##,
+    )
+    .expect("Failed to deserialize text threshold");
+    assert_eq!(from_text.saved.threshold, from_bytes.saved.threshold);
+    assert!(from_text.saved.auto_threshold);
+}
+
+/// Test that untyped ignore patterns of older versions are migrated on load
+#[test]
+fn test_migrate_untyped_ignore_patterns() {
+    let config: AppConfig = serde_json::from_str(
+        r##"{"extraction": {"ignored_files": ["debug", "*.log", "^test_[0-9]+"]}, "saved": {}, "appearance": {"theme_mode": "dark", "accent_color": "#0078D4", "language": "auto"}, "advanced": {}, "update": {"check_at_startup": true}}"##,
+    )
+    .expect("Failed to deserialize untyped ignore patterns");
+
+    assert_eq!(
+        config.extraction.ignored_files,
+        vec![
+            IgnorePattern::new(PatternKind::Substring, "debug"),
+            IgnorePattern::new(PatternKind::Glob, "*.log"),
+            IgnorePattern::new(PatternKind::Regex, "^test_[0-9]+"),
+        ]
+    );
+    assert!(config.validate().is_ok());
+}
+
 /// Test that extra fields in JSON are ignored (forward compatibility)
 #[test]
 fn test_deserialize_with_extra_fields() {
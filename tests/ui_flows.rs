@@ -0,0 +1,145 @@
+//! Scripted flows through the real main window, run headlessly
+//!
+//! Needs the `ui-testing` feature:
+//!
+//! ```text
+//! cargo test --features ui-testing --test ui_flows
+//! ```
+//!
+//! Runs without the test harness, on the main thread, where the window and
+//! its event loop live. Archives are unpacked by the mock extractor, so no
+//! tool or game files are needed.
+
+#![cfg_attr(not(unix), allow(dead_code, unused_imports))]
+
+use anyhow::{Result, ensure};
+use slint::Model;
+use std::path::Path;
+use std::sync::Arc;
+use unpackrr::config::AppConfig;
+use unpackrr::models::ExtractionStatus;
+use unpackrr::ui::testing::Harness;
+use unpackrr::ui::{MainWindow, RowStatus};
+use unpackrr_core::testing::fixtures::write_gnrl;
+use unpackrr_core::testing::{Behavior, MockExtractor};
+
+#[cfg(not(unix))]
+fn main() {
+    // The app's folders can't be redirected, and the flows would write to them
+    println!("ui_flows: skipped, needs Unix");
+}
+
+/// A scripted flow, given an empty folder to work in
+type Flow = fn(&Path) -> Result<()>;
+
+#[cfg(unix)]
+fn main() -> Result<()> {
+    let profile = tempfile::tempdir()?;
+    // SAFETY: no other thread runs yet
+    unsafe { unpackrr::ui::testing::isolate_profile(profile.path()) };
+
+    let flows: [(&str, Flow); 2] = [
+        ("scan, filter and extract", scan_filter_extract),
+        ("failed archives", failed_archives),
+    ];
+    for (name, flow) in flows {
+        let folder = tempfile::tempdir()?;
+        flow(folder.path()).map_err(|e| e.context(format!("flow \"{name}\" failed")))?;
+        println!("ui_flows: {name} ... ok");
+    }
+    Ok(())
+}
+
+/// Settings of the flows: every `- Main.ba2` archive, extracted without asking
+fn config() -> AppConfig {
+    let mut config = AppConfig::default();
+    config.extraction.postfixes = vec!["- Main.ba2".to_string()];
+    config.extraction.confirm_extraction = false;
+    config.extraction.auto_backup = false;
+    config
+}
+
+/// Scan a folder, narrow the table with the size threshold, clear it again
+/// and extract everything
+fn scan_filter_extract(root: &Path) -> Result<()> {
+    let small = root.join("Small").join("Small - Main.ba2");
+    let big = root.join("Big").join("Big - Main.ba2");
+    write_gnrl(&small, &[r"meshes\small.nif"])?;
+    let names: Vec<String> = (0..50).map(|i| format!(r"meshes\big{i}.nif")).collect();
+    write_gnrl(&big, &names.iter().map(String::as_str).collect::<Vec<_>>())?;
+    let small_size = std::fs::metadata(&small)?.len();
+
+    let backend = Arc::new(MockExtractor::new());
+    let harness = Harness::new(config(), backend.clone())?;
+    harness.select_folder(root);
+    harness.click("Scan")?;
+    harness.wait_until("the scan", |ui| {
+        !ui.get_scanning() && ui.get_file_list().row_count() == 2
+    })?;
+
+    harness.type_into("Size threshold", &(small_size + 1).to_string())?;
+    harness.wait_until("the size filter", |ui| ui.get_file_list().row_count() == 1)?;
+    ensure!(harness.rows()[0].file_name == "Small - Main.ba2");
+    harness.type_into("Size threshold", "")?;
+    harness.wait_until("the cleared filter", |ui| {
+        ui.get_file_list().row_count() == 2
+    })?;
+
+    harness.click("Start Extraction")?;
+    harness.wait_until("the extraction", MainWindow::get_extraction_complete)?;
+    ensure!(backend.calls().len() == 2, "calls: {:?}", backend.calls());
+    ensure!(
+        harness
+            .entries()
+            .iter()
+            .all(|entry| entry.status == ExtractionStatus::Done)
+    );
+    ensure!(
+        harness
+            .rows()
+            .iter()
+            .all(|row| row.status == RowStatus::Done)
+    );
+    ensure!(
+        root.join("Small")
+            .join("meshes")
+            .join("small.nif")
+            .is_file()
+    );
+    Ok(())
+}
+
+/// Extract with one archive failing, then list only the failed ones
+fn failed_archives(root: &Path) -> Result<()> {
+    write_gnrl(
+        &root.join("Good").join("Good - Main.ba2"),
+        &[r"meshes\good.nif"],
+    )?;
+    write_gnrl(
+        &root.join("Broken").join("Broken - Main.ba2"),
+        &[r"meshes\broken.nif"],
+    )?;
+
+    let backend = Arc::new(MockExtractor::new().with_behavior("Broken - Main.ba2", Behavior::Fail));
+    let harness = Harness::new(config(), backend)?;
+    harness.select_folder(root);
+    harness.click("Scan")?;
+    harness.wait_until("the scan", |ui| {
+        !ui.get_scanning() && ui.get_file_list().row_count() == 2
+    })?;
+
+    harness.click("Start Extraction")?;
+    harness.wait_until("the extraction", |ui| {
+        !ui.get_extracting() && ui.get_failed_count() == 1
+    })?;
+
+    harness.window().set_failed_only(true);
+    harness.window().invoke_failed_filter_toggled(true);
+    harness.wait_until("the failed filter", |ui| {
+        ui.get_file_list().row_count() == 1
+    })?;
+    let rows = harness.rows();
+    ensure!(rows[0].file_name == "Broken - Main.ba2");
+    ensure!(rows[0].status == RowStatus::Failed);
+    Ok(())
+}